@@ -0,0 +1,57 @@
+//! ETag and code search cache flags of a run.
+
+use clap::Args;
+use core::time::Duration;
+use std::path::PathBuf;
+use template_upgrade_notifier::RunnerConfig;
+
+/// Caches that let a run skip repeated GitHub requests.
+#[derive(Args, Debug)]
+pub(crate) struct CacheArgs {
+    /// Cache of ETags for conditional requests (defaults to etag-cache.json next to the migrations folder).
+    #[arg(long, conflicts_with = "no_etag_cache")]
+    pub etag_cache: Option<PathBuf>,
+
+    /// Don't use conditional requests; always fetch full responses.
+    #[arg(long)]
+    pub no_etag_cache: bool,
+
+    /// Cache code search results in this directory, so a dry run and the live run after it search once.
+    #[arg(long, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Seconds after which cached code search results are searched again.
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value_t = 3600,
+        requires = "cache_dir"
+    )]
+    pub cache_ttl: u64,
+
+    /// Run every code search again, replacing the cached results.
+    #[arg(long, requires = "cache_dir", conflicts_with = "clear_cache")]
+    pub refresh_cache: bool,
+
+    /// Empty the code search cache before the run.
+    #[arg(long, requires = "cache_dir")]
+    pub clear_cache: bool,
+}
+
+impl CacheArgs {
+    /// Applies the ETag and code search cache settings to `config`.
+    pub(crate) fn apply(self, config: RunnerConfig) -> RunnerConfig {
+        let config = if self.no_etag_cache {
+            config.with_etag_cache_path(None)
+        } else if let Some(path) = self.etag_cache {
+            config.with_etag_cache_path(Some(path))
+        } else {
+            config
+        };
+        config
+            .with_search_cache_dir(self.cache_dir)
+            .with_search_cache_ttl(Duration::from_secs(self.cache_ttl))
+            .with_refresh_search_cache(self.refresh_cache)
+            .with_clear_search_cache(self.clear_cache)
+    }
+}
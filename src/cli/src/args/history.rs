@@ -0,0 +1,88 @@
+//! Run history flags and the `history`, `show` and `diff-runs` subcommands.
+
+use clap::Args;
+use std::path::PathBuf;
+use template_upgrade_notifier::RunnerConfig;
+
+/// Where a run is recorded in the history database.
+#[derive(Args, Debug)]
+pub(crate) struct RecordArgs {
+    /// Path to the run history database (defaults to history.sqlite next to the migrations folder).
+    #[arg(long)]
+    pub history_db: Option<PathBuf>,
+
+    /// Don't record this run in the history database.
+    #[arg(long, conflicts_with = "history_db")]
+    pub no_history: bool,
+}
+
+/// Options for the `diff-runs` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct DiffRunsArgs {
+    /// Id of the older run.
+    pub old_run_id: i64,
+
+    /// Id of the newer run.
+    pub new_run_id: i64,
+
+    /// Database location.
+    #[command(flatten)]
+    pub db: HistoryDbArgs,
+}
+
+/// Locates the run history database.
+#[derive(Args, Debug)]
+pub(crate) struct HistoryDbArgs {
+    /// Path to migrations folder; the database defaults to history.sqlite next to it.
+    #[arg(long, default_value = "migrations/")]
+    pub migrations_path: PathBuf,
+
+    /// Path to the run history database.
+    #[arg(long)]
+    pub history_db: Option<PathBuf>,
+}
+
+/// Options for the `history` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct HistoryArgs {
+    /// Database location.
+    #[command(flatten)]
+    pub db: HistoryDbArgs,
+
+    /// Maximum number of runs to list, newest first.
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+/// Options for the `show` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ShowArgs {
+    /// Run id, as listed by `history`.
+    pub run_id: i64,
+
+    /// Database location.
+    #[command(flatten)]
+    pub db: HistoryDbArgs,
+}
+
+impl RecordArgs {
+    /// Applies the history database location to `config`.
+    pub(crate) fn apply(self, config: RunnerConfig) -> RunnerConfig {
+        if self.no_history {
+            config.with_history_path(None)
+        } else if let Some(path) = self.history_db {
+            config.with_history_path(Some(path))
+        } else {
+            config
+        }
+    }
+}
+
+impl HistoryDbArgs {
+    /// Returns the database path, falling back to the default next to the migrations folder.
+    pub(crate) fn path(&self) -> PathBuf {
+        self.history_db
+            .clone()
+            .unwrap_or_else(|| RunnerConfig::default_history_path(&self.migrations_path))
+    }
+}
@@ -0,0 +1,405 @@
+//! Command-line argument definitions.
+//!
+//! Related flags are grouped in their own modules and flattened into the
+//! commands that take them.
+
+mod cache;
+#[cfg(feature = "history")]
+mod history;
+mod network;
+mod state;
+
+use cache::CacheArgs;
+#[cfg(feature = "history")]
+pub(crate) use history::{DiffRunsArgs, HistoryArgs, HistoryDbArgs, RecordArgs, ShowArgs};
+use network::NetworkArgs;
+use state::StateArgs;
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, Parser, Subcommand};
+use core::time::Duration;
+use std::path::PathBuf;
+use template_upgrade_notifier::{DryRunLevel, ReportFormat, RerunTarget, RunnerConfig, Sample};
+
+/// Template Upgrade Notifier - Scan repositories for outdated templates and create upgrade issues.
+///
+/// Without a subcommand, performs a scan-and-notify run.
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+pub(crate) struct Cli {
+    /// Subcommand to execute instead of a run.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Arguments for the default run.
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+/// Subcommands other than the default run.
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Verify token scopes, git, LLM config and rate limit headroom before a run.
+    Doctor(CommonArgs),
+
+    /// Show how many previously filed issues/PRs per migration are open vs closed/merged.
+    Status(CommonArgs),
+
+    /// Re-run the full pipeline for specific repositories and migrations.
+    Rerun(RerunArgs),
+
+    /// Write a compliance matrix of outdated repositories without notifying anyone.
+    Report(ReportArgs),
+
+    /// Scan repositories already checked out on disk, without contacting GitHub.
+    ScanLocal(ScanLocalArgs),
+
+    /// List past runs recorded in the history database.
+    #[cfg(feature = "history")]
+    History(HistoryArgs),
+
+    /// Show the per-repository results of a recorded run.
+    #[cfg(feature = "history")]
+    Show(ShowArgs),
+
+    /// Compare two recorded runs to measure campaign progress.
+    #[cfg(feature = "history")]
+    DiffRuns(DiffRunsArgs),
+}
+
+/// Options shared by every command that talks to GitHub.
+#[derive(Args, Debug)]
+pub(crate) struct CommonArgs {
+    /// Path to migrations folder.
+    #[arg(long, default_value = "migrations/")]
+    pub migrations_path: PathBuf,
+
+    /// GitHub Personal Access Token.
+    #[arg(long, env = "GITHUB_TOKEN")]
+    pub token: String,
+
+    /// Enable auto-PR generation via serdes-ai.
+    #[arg(long)]
+    pub auto_pr: bool,
+
+    /// Only open auto-PRs in repositories with a risk score (0-100) at most this.
+    #[arg(long, value_name = "SCORE", value_parser = clap::value_parser!(u32).range(0..=100))]
+    pub max_auto_pr_risk: Option<u32>,
+
+    /// Estimated cost in US dollars of one LLM agent run, priced into dry-run estimates.
+    #[arg(long, value_name = "USD")]
+    pub llm_cost_per_run: Option<f64>,
+
+    /// Path to the LLM config file.
+    #[arg(long)]
+    pub llm_config_path: Option<PathBuf>,
+
+    /// Path to the notifier settings file (defaults to notifier.toml next to the migrations folder).
+    #[arg(long)]
+    pub notifier_config_path: Option<PathBuf>,
+
+    /// Only run migrations whose id matches this glob (repeatable).
+    #[arg(long = "only-migration", value_name = "ID_GLOB")]
+    pub only_migrations: Vec<String>,
+
+    /// Fail instead of skipping migrations that fail to load.
+    #[arg(long)]
+    pub strict_migrations: bool,
+
+    /// Only process repositories whose owner/name matches this glob (repeatable).
+    #[arg(long = "only-repo", value_name = "OWNER/NAME_GLOB")]
+    pub only_repos: Vec<String>,
+
+    /// Timeouts, retries and proxy settings for GitHub API traffic.
+    #[command(flatten)]
+    pub network: NetworkArgs,
+
+    /// Per-repository template data (defaults to repo-data.toml next to the migrations folder).
+    #[arg(long, value_name = "PATH")]
+    pub repo_data: Option<PathBuf>,
+
+    /// Repositories to process first (defaults to priority.txt next to the migrations folder).
+    #[arg(long, value_name = "PATH")]
+    pub priority_file: Option<PathBuf>,
+
+    /// Check the repositories listed in this file (repos.txt or repos.toml) instead of searching for them.
+    #[arg(long, value_name = "PATH")]
+    pub repo_list: Option<PathBuf>,
+}
+
+/// Options for the default scan-and-notify run.
+#[derive(Args, Debug)]
+pub(crate) struct RunArgs {
+    /// Options shared with other commands.
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// Preview changes without creating issues/PRs.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// How far the dry run goes: discover-only, render-only (render every
+    /// issue and PR) or clone-and-diff (run strategies locally without
+    /// pushing). Implies --dry-run.
+    #[arg(long, value_name = "LEVEL")]
+    pub dry_run_level: Option<DryRunLevel>,
+
+    /// Process only N randomly selected repositories per migration, to
+    /// spot-check a campaign before the full rollout.
+    #[arg(long, value_name = "N")]
+    pub sample: Option<usize>,
+
+    /// Seed for --sample; the same seed selects the same repositories.
+    #[arg(long, value_name = "SEED", default_value_t = 0, requires = "sample")]
+    pub sample_seed: u64,
+
+    /// Maximum concurrent API requests.
+    #[arg(long, default_value_t = 5, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub concurrency: usize,
+
+    /// Write a markdown report of the run to this path.
+    #[arg(long)]
+    pub markdown_report: Option<PathBuf>,
+
+    /// Also print a per-repository table after the summary.
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Write a SARIF report of outdated template occurrences to this path.
+    #[arg(long)]
+    pub sarif_report: Option<PathBuf>,
+
+    /// Retry passes, after all migrations, for repositories or PRs that failed
+    /// with transient errors (0 disables).
+    #[arg(long, default_value_t = 1)]
+    pub retry_attempts: u32,
+
+    /// Seconds to wait before each retry pass.
+    #[arg(long, default_value_t = 30)]
+    pub retry_delay_secs: u64,
+
+    /// Repositories a migration may discover before a live run asks for confirmation (0 never asks).
+    #[arg(long, value_name = "COUNT", default_value_t = 500)]
+    pub large_threshold: usize,
+
+    /// Notify migrations above `--large-threshold` without asking.
+    #[arg(long)]
+    pub yes_large: bool,
+
+    /// Stop starting new repositories after this many seconds, write a checkpoint and exit with 3.
+    #[arg(long, value_name = "SECONDS")]
+    pub max_runtime: Option<u64>,
+
+    /// Checkpoint, lock, ledger and remote state store locations.
+    #[command(flatten)]
+    pub state: StateArgs,
+
+    /// ETag and code search caches.
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    /// Close our open issues/PRs in repositories that already contain the new string.
+    #[arg(long)]
+    pub auto_close: bool,
+
+    /// Run the named `[campaigns.<name>]` from notifier.toml as one campaign.
+    #[arg(long, value_name = "NAME")]
+    pub campaign: Option<String>,
+
+    /// Keep running on the `[schedule]` from notifier.toml until interrupted.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Where the run is recorded in the history database.
+    #[cfg(feature = "history")]
+    #[command(flatten)]
+    pub history: RecordArgs,
+}
+
+/// Options for the `rerun` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct RerunArgs {
+    /// Options shared with other commands.
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// Repository to re-run (repeatable).
+    #[arg(long = "repo", value_name = "OWNER/NAME", required = true, value_parser = parse_repository)]
+    pub repos: Vec<String>,
+
+    /// Migration to re-run for every `--repo` (repeatable).
+    #[arg(long = "migration", value_name = "ID", required = true)]
+    pub migrations: Vec<String>,
+
+    /// File issues even when an open issue with the same title already exists
+    /// or the ledger records the repository as notified (except as declined).
+    #[arg(long)]
+    pub force: bool,
+
+    /// Preview changes without creating issues/PRs.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Also print a per-repository table after the summary.
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+/// Options for the `report` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ReportArgs {
+    /// Options shared with other commands.
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    /// Format of the compliance matrix: csv, json or markdown.
+    #[arg(long, value_name = "FORMAT", default_value_t = ReportFormat::Markdown)]
+    pub format: ReportFormat,
+
+    /// Write the compliance matrix to this path instead of stdout.
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Maximum concurrent API requests.
+    #[arg(long, default_value_t = 5, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub concurrency: usize,
+}
+
+/// Options for the `scan-local` subcommand.
+#[derive(Args, Debug)]
+pub(crate) struct ScanLocalArgs {
+    /// A checked-out repository, or a directory whose subdirectories are repositories.
+    #[arg(long, value_name = "DIR")]
+    pub repos_dir: PathBuf,
+
+    /// Path to migrations folder.
+    #[arg(long, default_value = "migrations/")]
+    pub migrations_path: PathBuf,
+
+    /// Rewrite outdated files in place instead of only reporting them.
+    #[arg(long)]
+    pub apply: bool,
+
+    /// Write one `<repository>/<migration>.patch` per match below this directory.
+    #[arg(long, value_name = "DIR")]
+    pub patch_dir: Option<PathBuf>,
+
+    /// Write a markdown report of the scan to this path.
+    #[arg(long)]
+    pub markdown_report: Option<PathBuf>,
+
+    /// Write a SARIF report of outdated template occurrences to this path.
+    #[arg(long)]
+    pub sarif_report: Option<PathBuf>,
+}
+
+impl CommonArgs {
+    /// Builds a [`RunnerConfig`] from the shared options.
+    pub(crate) fn into_runner_config(self, dry_run: bool, concurrency: usize) -> RunnerConfig {
+        let mut config = RunnerConfig::new(
+            self.migrations_path,
+            self.token,
+            dry_run,
+            concurrency,
+            self.auto_pr,
+        );
+        if let Some(path) = self.llm_config_path {
+            config = config.with_llm_config_path(path);
+        }
+        if let Some(path) = self.notifier_config_path {
+            config = config.with_notifier_config_path(path);
+        }
+        if let Some(path) = self.repo_data {
+            config = config.with_repo_data_path(Some(path));
+        }
+        if let Some(path) = self.priority_file {
+            config = config.with_priority_path(Some(path));
+        }
+        if let Some(path) = self.repo_list {
+            config = config.with_repo_list_path(Some(path));
+        }
+        self.network
+            .apply(config)
+            .with_only_migrations(self.only_migrations)
+            .with_strict_migrations(self.strict_migrations)
+            .with_only_repos(self.only_repos)
+            .with_max_auto_pr_risk(self.max_auto_pr_risk)
+            .with_llm_cost_per_run(self.llm_cost_per_run)
+    }
+}
+
+impl RunArgs {
+    /// Builds a [`RunnerConfig`] for a run.
+    pub(crate) fn into_runner_config(self) -> RunnerConfig {
+        let dry_run = self.dry_run || self.dry_run_level.is_some();
+        let config = self
+            .common
+            .into_runner_config(dry_run, self.concurrency)
+            .with_dry_run_level(self.dry_run_level.unwrap_or_default())
+            .with_sample(self.sample.map(|size| Sample {
+                size,
+                seed: self.sample_seed,
+            }))
+            .with_retry_attempts(self.retry_attempts)
+            .with_retry_delay(Duration::from_secs(self.retry_delay_secs))
+            .with_max_runtime(self.max_runtime.map(Duration::from_secs))
+            .with_auto_close(self.auto_close)
+            .with_campaign(self.campaign)
+            .with_large_campaign_threshold(
+                (self.large_threshold > 0).then_some(self.large_threshold),
+            )
+            .with_confirm_large_campaigns(self.yes_large);
+        let config = self.cache.apply(self.state.apply(config));
+        #[cfg(feature = "history")]
+        let config = self.history.apply(config);
+        config
+    }
+}
+
+impl RerunArgs {
+    /// Returns every requested repository/migration pair.
+    pub(crate) fn targets(&self) -> Vec<RerunTarget> {
+        self.migrations
+            .iter()
+            .flat_map(|migration_id| {
+                self.repos.iter().map(|repository| RerunTarget {
+                    migration_id: migration_id.clone(),
+                    repository: repository.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Builds a [`RunnerConfig`] for the re-run.
+    pub(crate) fn into_runner_config(self) -> RunnerConfig {
+        self.common
+            .into_runner_config(self.dry_run, 1)
+            .with_force_issues(self.force)
+    }
+}
+
+impl ReportArgs {
+    /// Builds a report-only [`RunnerConfig`].
+    pub(crate) fn into_runner_config(self) -> RunnerConfig {
+        self.common
+            .into_runner_config(true, self.concurrency)
+            .with_report_only(true)
+    }
+}
+
+/// Accepts only `owner/name` repository names.
+fn parse_repository(value: &str) -> Result<String, String> {
+    match value.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+            Ok(value.to_string())
+        }
+        _ => Err(format!("expected owner/name, got `{value}`")),
+    }
+}
@@ -0,0 +1,56 @@
+//! Timeout, retry and proxy flags for GitHub API traffic.
+
+use clap::Args;
+use core::time::Duration;
+use std::path::PathBuf;
+use template_upgrade_notifier::RunnerConfig;
+
+/// Timeouts, retries and proxy settings shared by every command that talks
+/// to GitHub.
+#[derive(Args, Debug)]
+pub(crate) struct NetworkArgs {
+    /// Seconds allowed to connect to the GitHub API.
+    #[arg(long, default_value_t = 30, value_name = "SECONDS")]
+    pub connect_timeout: u64,
+
+    /// Seconds allowed for reading or writing a GitHub API request.
+    #[arg(long, default_value_t = 120, value_name = "SECONDS")]
+    pub request_timeout: u64,
+
+    /// Retries of an idempotent GitHub API request after a dropped connection, 429 or server error, with backoff (0 disables).
+    #[arg(long, default_value_t = 3)]
+    pub http_retries: usize,
+
+    /// HTTP proxy for GitHub API and git traffic (overrides HTTPS_PROXY).
+    #[arg(long, value_name = "URL")]
+    pub https_proxy: Option<String>,
+
+    /// Comma-separated hosts reached without the proxy (overrides NO_PROXY).
+    #[arg(long, value_name = "HOSTS")]
+    pub no_proxy: Option<String>,
+
+    /// PEM file of extra CA certificates to trust, e.g. a TLS-inspecting proxy's.
+    #[arg(long, value_name = "PATH")]
+    pub ca_bundle: Option<PathBuf>,
+}
+
+impl NetworkArgs {
+    /// Applies the timeouts, retries and proxy settings to `config`.
+    pub(crate) fn apply(self, config: RunnerConfig) -> RunnerConfig {
+        let mut proxy = config.proxy().clone();
+        if let Some(url) = self.https_proxy {
+            proxy = proxy.with_https_proxy(url);
+        }
+        if let Some(hosts) = self.no_proxy {
+            proxy = proxy.with_no_proxy(hosts);
+        }
+        if let Some(path) = self.ca_bundle {
+            proxy = proxy.with_ca_bundle(path);
+        }
+        config
+            .with_proxy(proxy)
+            .with_connect_timeout(Duration::from_secs(self.connect_timeout))
+            .with_request_timeout(Duration::from_secs(self.request_timeout))
+            .with_http_retries(self.http_retries)
+    }
+}
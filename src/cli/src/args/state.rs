@@ -0,0 +1,54 @@
+//! Checkpoint, lock, ledger and remote state store flags of a run.
+
+use clap::Args;
+use std::path::PathBuf;
+use template_upgrade_notifier::RunnerConfig;
+
+/// Where a run keeps the state carried between runs.
+#[derive(Args, Debug)]
+pub(crate) struct StateArgs {
+    /// Where to write the checkpoint (defaults to checkpoint.json next to the migrations folder).
+    #[arg(long)]
+    pub checkpoint_path: Option<PathBuf>,
+
+    /// Lock file preventing overlapping live runs (defaults to run.lock next to the migrations folder).
+    #[arg(long, conflicts_with = "no_lock")]
+    pub lock_file: Option<PathBuf>,
+
+    /// Don't take the run lock; only use when runs are already serialized elsewhere.
+    #[arg(long)]
+    pub no_lock: bool,
+
+    /// Ledger of already notified repositories (defaults to ledger.json next to the migrations folder).
+    #[arg(long, conflicts_with = "no_ledger")]
+    pub ledger_file: Option<PathBuf>,
+
+    /// Don't consult or update the ledger; rely on duplicate-issue searches only.
+    #[arg(long)]
+    pub no_ledger: bool,
+
+    /// Pull the ledger, checkpoint and history from this store before a live run and push them back after.
+    #[arg(long, value_name = "URL", env = "TEMPLATE_UPGRADE_STATE_URL")]
+    pub state_url: Option<String>,
+}
+
+impl StateArgs {
+    /// Applies the checkpoint, lock, ledger and state store locations to `config`.
+    pub(crate) fn apply(self, config: RunnerConfig) -> RunnerConfig {
+        let mut config = config.with_state_url(self.state_url);
+        if let Some(path) = self.checkpoint_path {
+            config = config.with_checkpoint_path(path);
+        }
+        if self.no_lock {
+            config = config.with_lock_path(None);
+        } else if let Some(path) = self.lock_file {
+            config = config.with_lock_path(Some(path));
+        }
+        if self.no_ledger {
+            config = config.with_ledger_path(None);
+        } else if let Some(path) = self.ledger_file {
+            config = config.with_ledger_path(Some(path));
+        }
+        config
+    }
+}
@@ -0,0 +1,59 @@
+//! The `doctor` subcommand.
+
+use crate::args::CommonArgs;
+use std::process::ExitCode;
+use template_upgrade_notifier::{CheckStatus, DoctorReport, Runner};
+use tracing::error;
+
+/// Runs preflight checks and prints an actionable report.
+///
+/// Exits with `1` if any check failed, `2` if the checks could not run at all.
+pub(crate) async fn execute(args: CommonArgs) -> ExitCode {
+    // Doctor never writes, so it runs as a single-threaded dry run.
+    let config = args.into_runner_config(true, 1);
+    let runner = match Runner::new(config) {
+        Ok(runner) => runner,
+        Err(e) => {
+            error!(error = %e, "Critical failure");
+            return ExitCode::from(2);
+        }
+    };
+
+    let report = runner.doctor().await;
+    print_report(&report);
+
+    if report.has_failures() {
+        ExitCode::from(1)
+    } else {
+        ExitCode::from(0)
+    }
+}
+
+/// Prints one line per check followed by an overall verdict.
+fn print_report(report: &DoctorReport) {
+    println!("\nPreflight checks:");
+    for check in &report.checks {
+        println!(
+            "  [{}] {}: {}",
+            check.status.label(),
+            check.name,
+            check.message
+        );
+    }
+
+    let verdict = if report.has_failures() {
+        CheckStatus::Fail
+    } else if report.has_warnings() {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    println!(
+        "\nResult: {}",
+        match verdict {
+            CheckStatus::Pass => "ready to run",
+            CheckStatus::Warn => "ready to run, with warnings",
+            CheckStatus::Fail => "fix the failures above before running",
+        }
+    );
+}
@@ -0,0 +1,176 @@
+//! The `history`, `show` and `diff-runs` subcommands.
+
+use crate::args::{DiffRunsArgs, HistoryArgs, HistoryDbArgs, ShowArgs};
+use std::process::ExitCode;
+use template_upgrade_notifier::{
+    HistoryError, HistoryStore, PatternSet, ResultRecord, RunDiff, RunRecord,
+};
+use tracing::error;
+
+/// Lists recorded runs, newest first.
+pub(crate) fn list(args: &HistoryArgs) -> ExitCode {
+    let result = open(&args.db).and_then(|store| store.list_runs(args.limit));
+    let runs = match result {
+        Ok(runs) => runs,
+        Err(e) => return failure(&e),
+    };
+
+    if runs.is_empty() {
+        println!("No runs recorded yet.");
+        return ExitCode::SUCCESS;
+    }
+
+    println!(
+        "{:>6}  {:<25}  {:>10}  {:>6}  {:>7}  {:>7}  {:>6}  {:>4}",
+        "RUN", "RECORDED", "MIGRATIONS", "REPOS", "ISSUES", "SKIPPED", "FAILED", "PRS"
+    );
+    for run in &runs {
+        println!(
+            "{:>6}  {:<25}  {:>10}  {:>6}  {:>7}  {:>7}  {:>6}  {:>4}{}",
+            run.id,
+            run.recorded_at,
+            run.migrations_processed,
+            run.repositories_discovered,
+            run.issues_created,
+            run.issues_skipped,
+            run.issues_failed + run.prs_failed,
+            run.prs_created,
+            if run.dry_run { "  (dry run)" } else { "" }
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+/// Prints a recorded run and every repository it touched.
+///
+/// Exits with `1` if no run has the requested id.
+pub(crate) fn show(args: &ShowArgs) -> ExitCode {
+    let result = open(&args.db).and_then(|store| {
+        let run = store.run(args.run_id)?;
+        let results = store.run_results(args.run_id)?;
+        Ok(run.map(|run| (run, results)))
+    });
+    match result {
+        Ok(Some((run, results))) => {
+            print_run(&run, &results);
+            ExitCode::SUCCESS
+        }
+        Ok(None) => {
+            error!(run_id = args.run_id, "No such run");
+            ExitCode::from(1)
+        }
+        Err(e) => failure(&e),
+    }
+}
+
+/// Compares two recorded runs: new repositories, upgraded repositories and
+/// still-outstanding issues.
+///
+/// Only repositories within the newer run's `--only-migration` and
+/// `--only-repo` filters are compared. Exits with `1` if either run does not
+/// exist.
+pub(crate) fn diff(args: &DiffRunsArgs) -> ExitCode {
+    let result = open(&args.db).and_then(|store| {
+        if store.run(args.old_run_id)?.is_none() {
+            return Ok(Err(args.old_run_id));
+        }
+        let Some(newer_run) = store.run(args.new_run_id)? else {
+            return Ok(Err(args.new_run_id));
+        };
+        let older = store.run_results(args.old_run_id)?;
+        let newer = store.run_results(args.new_run_id)?;
+        Ok(Ok((newer_run, older, newer)))
+    });
+    let (newer_run, older, newer) = match result {
+        Ok(Ok(runs)) => runs,
+        Ok(Err(run_id)) => {
+            error!(run_id, "No such run");
+            return ExitCode::from(1);
+        }
+        Err(e) => return failure(&e),
+    };
+    let filters = PatternSet::new(&newer_run.only_migrations)
+        .and_then(|migrations| Ok((migrations, PatternSet::new(&newer_run.only_repos)?)));
+    let (migrations, repositories) = match filters {
+        Ok(filters) => filters,
+        Err(e) => {
+            error!(run_id = newer_run.id, error = %e, "Run recorded an invalid filter");
+            return ExitCode::from(2);
+        }
+    };
+    let diff = RunDiff::between(&older, &newer, &migrations, &repositories);
+
+    println!("\nRun {} -> run {}", args.old_run_id, args.new_run_id);
+    if !migrations.is_empty() || !repositories.is_empty() {
+        println!(
+            "  Limited to run {}'s filters: migrations [{}], repositories [{}]",
+            newer_run.id,
+            newer_run.only_migrations.join(", "),
+            newer_run.only_repos.join(", ")
+        );
+    }
+    print_section("Newly discovered", &diff.newly_discovered);
+    print_section("Upgraded since", &diff.upgraded);
+    print_section("Still outstanding", &diff.outstanding);
+    println!();
+    ExitCode::SUCCESS
+}
+
+fn print_section(heading: &str, records: &[ResultRecord]) {
+    println!("\n  {heading}: {}", records.len());
+    for record in records {
+        match &record.issue_url {
+            Some(url) => println!(
+                "    [{}] {} ({url})",
+                record.migration_id, record.repository
+            ),
+            None => println!("    [{}] {}", record.migration_id, record.repository),
+        }
+    }
+}
+
+fn open(db: &HistoryDbArgs) -> Result<HistoryStore, HistoryError> {
+    HistoryStore::open(&db.path())
+}
+
+fn failure(e: &HistoryError) -> ExitCode {
+    error!(error = %e, "Failed to read run history");
+    ExitCode::from(2)
+}
+
+fn print_run(run: &RunRecord, results: &[ResultRecord]) {
+    println!(
+        "\nRun {} recorded at {}{}",
+        run.id,
+        run.recorded_at,
+        if run.dry_run { " (dry run)" } else { "" }
+    );
+    println!("  Migrations processed: {}", run.migrations_processed);
+    println!("  Repositories discovered: {}", run.repositories_discovered);
+    println!("  Issues created: {}", run.issues_created);
+    println!("  Issues skipped: {}", run.issues_skipped);
+    println!("  Issues failed: {}", run.issues_failed);
+    println!("  PRs created: {}", run.prs_created);
+    println!("  PRs failed: {}", run.prs_failed);
+
+    let mut current_migration = None;
+    for result in results {
+        if current_migration != Some(&result.migration_id) {
+            println!("\n  Migration: {}", result.migration_id);
+            current_migration = Some(&result.migration_id);
+        }
+
+        let mut line = format!("    {} [{}]", result.repository, result.outcome);
+        if let Some(url) = &result.issue_url {
+            line.push_str(&format!(" issue: {url}"));
+        }
+        if let Some(url) = &result.pr_url {
+            line.push_str(&format!(" pr: {url}"));
+        }
+        if let Some(detail) = &result.detail {
+            line.push_str(&format!(" ({detail})"));
+        }
+        println!("{line}");
+    }
+    println!();
+}
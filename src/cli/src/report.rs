@@ -0,0 +1,37 @@
+//! The `report` subcommand.
+
+use crate::args::ReportArgs;
+use crate::run::critical_failure;
+use std::process::ExitCode;
+use template_upgrade_notifier::Runner;
+use tracing::{error, info};
+
+/// Writes the compliance matrix of a report-only run.
+///
+/// Exits with `2` if the run failed or the report could not be written.
+pub(crate) async fn execute(args: ReportArgs) -> ExitCode {
+    let format = args.format;
+    let output = args.output.clone();
+    let runner = match Runner::new(args.into_runner_config()) {
+        Ok(runner) => runner,
+        Err(e) => return critical_failure(&e),
+    };
+    let matrix = match runner.report().await {
+        Ok(matrix) => matrix,
+        Err(e) => return critical_failure(&e),
+    };
+    let report = matrix.render(format);
+    match &output {
+        Some(path) => match std::fs::write(path, report) {
+            Ok(()) => {
+                info!(path = %path.display(), rows = matrix.rows.len(), "Wrote compliance report");
+            }
+            Err(e) => {
+                error!(path = %path.display(), error = %e, "Failed to write compliance report");
+                return ExitCode::from(2);
+            }
+        },
+        None => print!("{report}"),
+    }
+    ExitCode::SUCCESS
+}
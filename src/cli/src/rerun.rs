@@ -0,0 +1,30 @@
+//! The `rerun` subcommand.
+
+use crate::args::RerunArgs;
+use crate::run::{critical_failure, print_summary};
+use std::process::ExitCode;
+use template_upgrade_notifier::{RunOutcome, Runner};
+use tracing::warn;
+
+/// Re-runs the pipeline for the requested repository/migration pairs.
+///
+/// Exits like a normal run, according to the `[exit-policy]`.
+pub(crate) async fn execute(args: RerunArgs) -> ExitCode {
+    let targets = args.targets();
+    let verbose = args.verbose;
+    let runner = match Runner::new(args.into_runner_config()) {
+        Ok(runner) => runner,
+        Err(e) => return critical_failure(&e),
+    };
+    match runner.rerun(&targets).await {
+        Ok(summary) => {
+            print_summary(&summary, verbose);
+            let outcome = runner.notifier().exit_policy.evaluate(&summary);
+            if outcome == RunOutcome::Warnings {
+                warn!("Re-run completed with warnings");
+            }
+            ExitCode::from(outcome.exit_code())
+        }
+        Err(e) => critical_failure(&e),
+    }
+}
@@ -0,0 +1,174 @@
+//! The default scan-and-notify run, including watch mode.
+
+use crate::args::RunArgs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use template_upgrade_notifier::{
+    append_step_summary, emit_annotations, write_github_outputs, write_markdown_report,
+    write_sarif_report, CancellationToken, RunOutcome, RunSummary, Runner, RunnerError,
+};
+use tracing::{error, info, warn};
+
+/// Executes a run (or watch mode) and maps the outcome to an exit code.
+pub(crate) async fn execute(args: RunArgs) -> ExitCode {
+    let reports = ReportPaths::from(&args);
+    let verbose = args.verbose;
+
+    if args.watch {
+        return watch(args).await;
+    }
+
+    // Run the main logic
+    let cancellation = CancellationToken::new();
+    let runner = match build_runner(args, cancellation.clone()) {
+        Ok(runner) => runner,
+        Err(e) => return critical_failure(&e),
+    };
+    cancel_on_ctrl_c(cancellation);
+    match runner.run().await {
+        Ok(summary) => {
+            print_summary(&summary, verbose);
+            write_reports(&summary, &reports);
+
+            let outcome = runner.notifier().exit_policy.evaluate(&summary);
+            if outcome == RunOutcome::Warnings {
+                warn!("Run completed with warnings");
+            }
+            ExitCode::from(outcome.exit_code())
+        }
+        Err(e) => critical_failure(&e),
+    }
+}
+
+/// Builds the runner, asking on the terminal before large campaigns when
+/// run interactively.
+fn build_runner(args: RunArgs, cancellation: CancellationToken) -> Result<Runner, RunnerError> {
+    let config = args
+        .into_runner_config()
+        .with_cancellation_token(cancellation);
+    let runner = Runner::new(config)?;
+    if std::io::stdin().is_terminal() {
+        Ok(runner.with_large_campaign_prompt(prompt_large_campaign))
+    } else {
+        Ok(runner)
+    }
+}
+
+/// Asks on the terminal whether to notify `count` repositories for a migration.
+fn prompt_large_campaign(migration_id: &str, count: usize, threshold: usize) -> bool {
+    eprint!(
+        "Migration {migration_id} matched {count} repositories (more than {threshold}). Notify all of them? [y/N] "
+    );
+    let _ = std::io::stderr().flush();
+    // An unreadable terminal counts as "no".
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Cancels the run on Ctrl-C, abandoning clones and agent runs in progress.
+///
+/// The run still saves its ledger and writes a checkpoint of what was left.
+fn cancel_on_ctrl_c(cancellation: CancellationToken) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            warn!("Interrupted, cancelling the run");
+            cancellation.cancel();
+        }
+    });
+}
+
+/// Logs an error that prevented the run from completing.
+pub(crate) fn critical_failure(e: &RunnerError) -> ExitCode {
+    error!(error = %e, "Critical failure");
+    ExitCode::from(2)
+}
+
+/// Watch mode: runs on the configured schedule until Ctrl-C.
+///
+/// Every completed iteration prints its summary and writes reports, exactly
+/// like a one-shot run. Ctrl-C cancels the iteration in progress, whose
+/// summary is still reported, and then stops.
+async fn watch(args: RunArgs) -> ExitCode {
+    let reports = ReportPaths::from(&args);
+    let verbose = args.verbose;
+    let cancellation = CancellationToken::new();
+    let runner = match build_runner(args, cancellation.clone()) {
+        Ok(runner) => runner,
+        Err(e) => return critical_failure(&e),
+    };
+    cancel_on_ctrl_c(cancellation);
+
+    // The cancellation token ends the loop, after the iteration in progress.
+    let shutdown = core::future::pending::<()>();
+
+    let result = runner
+        .watch(shutdown, |summary| {
+            print_summary(summary, verbose);
+            write_reports(summary, &reports);
+        })
+        .await;
+
+    match result {
+        Ok(()) => ExitCode::from(0),
+        Err(e) => critical_failure(&e),
+    }
+}
+
+/// Report files requested on the command line.
+struct ReportPaths {
+    markdown: Option<PathBuf>,
+    sarif: Option<PathBuf>,
+}
+
+impl From<&RunArgs> for ReportPaths {
+    fn from(args: &RunArgs) -> Self {
+        Self {
+            markdown: args.markdown_report.clone(),
+            sarif: args.sarif_report.clone(),
+        }
+    }
+}
+
+/// Writes the markdown and SARIF reports to the requested paths and, inside GitHub Actions,
+/// the step summary, annotations and step outputs.
+///
+/// Report failures are logged rather than fatal; the run itself already completed.
+fn write_reports(summary: &RunSummary, reports: &ReportPaths) {
+    if let Some(path) = &reports.markdown {
+        match write_markdown_report(summary, path) {
+            Ok(()) => info!(path = %path.display(), "Wrote markdown report"),
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to write markdown report"),
+        }
+    }
+
+    if let Some(path) = &reports.sarif {
+        match write_sarif_report(summary, path) {
+            Ok(()) => info!(path = %path.display(), "Wrote SARIF report"),
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to write SARIF report"),
+        }
+    }
+
+    match append_step_summary(summary) {
+        Ok(true) => info!("Appended report to GitHub Actions step summary"),
+        Ok(false) => {}
+        Err(e) => warn!(error = %e, "Failed to write GitHub Actions step summary"),
+    }
+
+    emit_annotations(summary);
+    match write_github_outputs(summary, reports.markdown.as_deref()) {
+        Ok(true) => info!("Wrote GitHub Actions step outputs"),
+        Ok(false) => {}
+        Err(e) => warn!(error = %e, "Failed to write GitHub Actions step outputs"),
+    }
+}
+
+/// Prints the final run summary as a per-migration table.
+///
+/// With `verbose`, also prints one row per processed repository.
+pub(crate) fn print_summary(summary: &RunSummary, verbose: bool) {
+    print!("{}", summary.to_table(verbose));
+}
@@ -0,0 +1,48 @@
+//! The `scan-local` subcommand.
+
+use crate::args::ScanLocalArgs;
+use crate::run::print_summary;
+use std::process::ExitCode;
+use template_upgrade_notifier::{
+    scan_local, scan_migrations, write_markdown_report, write_sarif_report, LocalScanOptions,
+};
+use tracing::{error, info, warn};
+
+/// Scans checked-out repositories and writes the requested patches and reports.
+///
+/// Exits with `2` if the migrations or repositories could not be read.
+pub(crate) fn execute(args: &ScanLocalArgs) -> ExitCode {
+    let migrations = match scan_migrations(&args.migrations_path) {
+        Ok(migrations) => migrations,
+        Err(e) => {
+            error!(error = %e, "Failed to load migrations");
+            return ExitCode::from(2);
+        }
+    };
+    let options = LocalScanOptions {
+        apply: args.apply,
+        patch_dir: args.patch_dir.clone(),
+    };
+    let scan = match scan_local(&args.repos_dir, &migrations, &options) {
+        Ok(scan) => scan,
+        Err(e) => {
+            error!(error = %e, "Local scan failed");
+            return ExitCode::from(2);
+        }
+    };
+
+    print_summary(&scan.summary, false);
+    if let Some(path) = &args.markdown_report {
+        match write_markdown_report(&scan.summary, path) {
+            Ok(()) => info!(path = %path.display(), "Wrote markdown report"),
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to write markdown report"),
+        }
+    }
+    if let Some(path) = &args.sarif_report {
+        match write_sarif_report(&scan.summary, path) {
+            Ok(()) => info!(path = %path.display(), "Wrote SARIF report"),
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to write SARIF report"),
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,67 @@
+//! The `status` subcommand.
+
+use crate::args::CommonArgs;
+use std::process::ExitCode;
+use template_upgrade_notifier::{MigrationStatus, Runner};
+use tracing::error;
+
+/// Prints an adoption dashboard of previously filed issues and PRs.
+///
+/// Exits with `2` if the status could not be collected.
+pub(crate) async fn execute(args: CommonArgs) -> ExitCode {
+    // Status only reads, so it runs as a single-threaded dry run.
+    let config = args.into_runner_config(true, 1);
+    let result = match Runner::new(config) {
+        Ok(runner) => runner.status().await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(statuses) => {
+            print_dashboard(&statuses);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            error!(error = %e, "Critical failure");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Prints one row per migration with issue, PR and acknowledgement counts.
+fn print_dashboard(statuses: &[MigrationStatus]) {
+    if statuses.is_empty() {
+        println!("No migrations found.");
+        return;
+    }
+
+    println!(
+        "\n{:<40}  {:>11}  {:>13}  {:>8}  {:>10}  {:>10}  {:>8}  {:>5}  {:>8}",
+        "MIGRATION",
+        "ISSUES OPEN",
+        "ISSUES CLOSED",
+        "PRS OPEN",
+        "PRS MERGED",
+        "PRS CLOSED",
+        "ADOPTED",
+        "ACKED",
+        "DECLINED"
+    );
+    for status in statuses {
+        let adopted = status
+            .adoption_rate()
+            .map_or_else(|| "-".to_string(), |rate| format!("{:.0}%", rate * 100.0));
+        println!(
+            "{:<40}  {:>11}  {:>13}  {:>8}  {:>10}  {:>10}  {:>8}  {:>5}  {:>8}",
+            status.migration_id,
+            status.issues_open,
+            status.issues_closed,
+            status.prs_open,
+            status.prs_merged,
+            status.prs_closed,
+            adopted,
+            status.acknowledged,
+            status.declined()
+        );
+    }
+    println!();
+}
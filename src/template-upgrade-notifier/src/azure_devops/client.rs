@@ -0,0 +1,408 @@
+//! REST client for Azure DevOps code search, work items and pull requests.
+
+use super::query::{escape_wiql, repository_tag, search_text, NOTIFIER_TAG};
+use super::{AzureDevOpsConfig, AzureDevOpsError, AzureRepository};
+use crate::config::Migration;
+use crate::ids::HtmlUrl;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::summary::PERMISSION_DENIED_REASON;
+use crate::templates::{
+    generate_branch_name, generate_issue_title, generate_pr_title, TemplateRenderer,
+};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use tracing::{debug, info, warn};
+
+/// REST API version sent with every request.
+const API_VERSION: &str = "7.1";
+
+/// Results per code search request; the API's maximum.
+const SEARCH_PAGE_SIZE: usize = 1000;
+
+/// Azure DevOps REST client authenticated with a personal access token.
+#[derive(Debug, Clone)]
+pub struct AzureDevOpsClient {
+    http: Client,
+    config: AzureDevOpsConfig,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    count: usize,
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    path: String,
+    project: NamedRef,
+    repository: RepositoryRef,
+    #[serde(default)]
+    versions: Vec<Version>,
+}
+
+#[derive(Deserialize)]
+struct NamedRef {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct RepositoryRef {
+    name: String,
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Version {
+    branch_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GitRepository {
+    #[serde(default)]
+    default_branch: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WiqlResponse {
+    #[serde(default)]
+    work_items: Vec<IdRef>,
+}
+
+#[derive(Deserialize)]
+struct IdRef {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct WorkItem {
+    id: u64,
+    #[serde(rename = "_links")]
+    links: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequest {
+    pull_request_id: u64,
+    repository: PullRequestRepository,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestRepository {
+    web_url: String,
+}
+
+impl AzureDevOpsClient {
+    /// Creates a client for the configured organization.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AzureDevOpsError::MissingToken`] if no token is configured.
+    pub fn new(config: AzureDevOpsConfig) -> Result<Self, AzureDevOpsError> {
+        let token = config
+            .resolved_token()
+            .ok_or(AzureDevOpsError::MissingToken)?;
+        Ok(Self {
+            http: Client::new(),
+            config,
+            token,
+        })
+    }
+
+    /// Finds repositories containing the migration's `old_string` in any of
+    /// its target files, deduplicated by repository.
+    ///
+    /// Searches every configured project, or the whole organization if none
+    /// are configured. A repository's branch is the one its match was found
+    /// on, or else its default branch; it is left unknown, with a warning,
+    /// if Azure DevOps reports neither.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AzureDevOpsError`] if a search request fails.
+    pub async fn discover_repositories(
+        &self,
+        migration: &Migration,
+    ) -> Result<Vec<AzureRepository>, AzureDevOpsError> {
+        let text = search_text(&migration.old_string, &migration.target_files());
+        debug!(query = %text, "Executing Azure DevOps code search");
+
+        let mut seen = HashSet::new();
+        let mut repositories = Vec::new();
+        let mut skip = 0;
+        loop {
+            let mut body = json!({
+                "searchText": text,
+                "$skip": skip,
+                "$top": SEARCH_PAGE_SIZE,
+                "includeFacets": false,
+            });
+            if !self.config.projects.is_empty() {
+                body["filters"] = json!({ "Project": self.config.projects });
+            }
+            let url = format!(
+                "{}/{}/_apis/search/codesearchresults?api-version={API_VERSION}",
+                self.config.search_url, self.config.organization
+            );
+            let page: SearchResponse = self.send(self.http.post(url).json(&body)).await?;
+
+            let fetched = page.results.len();
+            for result in page.results {
+                let full_name = format!("{}/{}", result.project.name, result.repository.name);
+                if !seen.insert(full_name.clone()) {
+                    continue;
+                }
+                let default_branch = match result.versions.into_iter().next() {
+                    Some(version) => Some(version.branch_name),
+                    None => {
+                        self.default_branch(&result.project.name, &result.repository.id)
+                            .await?
+                    }
+                };
+                if default_branch.is_none() {
+                    warn!(repo = %full_name, "Default branch unknown");
+                }
+                repositories.push(AzureRepository {
+                    project: result.project.name,
+                    name: result.repository.name,
+                    id: result.repository.id,
+                    full_name,
+                    file_path: result.path,
+                    default_branch,
+                });
+            }
+
+            skip += fetched;
+            if fetched < SEARCH_PAGE_SIZE || skip >= page.count {
+                break;
+            }
+        }
+
+        info!(
+            count = repositories.len(),
+            "Azure DevOps discovery complete"
+        );
+        Ok(repositories)
+    }
+
+    /// Files the upgrade notification as a work item in the repository's project.
+    ///
+    /// Work items belong to projects rather than repositories, so each one is
+    /// tagged with the repository name; an open work item with the same title
+    /// and tag counts as a duplicate and the repository is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AzureDevOpsError`] if the duplicate check or creation fails,
+    /// except for missing permissions, which yield a skipped status.
+    pub async fn create_work_item(
+        &self,
+        repository: &AzureRepository,
+        migration: &Migration,
+        renderer: &TemplateRenderer,
+    ) -> Result<IssueStatus, AzureDevOpsError> {
+        let title = generate_issue_title(migration)
+            .map_err(|e| AzureDevOpsError::TemplateError(e.to_string()))?;
+        let tag = repository_tag(repository);
+
+        if let Some(existing) = self.find_open_work_item(repository, &title, &tag).await? {
+            info!(work_item = existing, "Duplicate work item exists, skipping");
+            return Ok(IssueStatus::Skipped {
+                reason: format!("duplicate work item exists (#{existing})"),
+            });
+        }
+
+        let body = renderer
+            .render_issue_template(&migration.issue_template, migration, None, None, None)
+            .map_err(|e| AzureDevOpsError::TemplateError(e.to_string()))?;
+        let operations = json!([
+            { "op": "add", "path": "/fields/System.Title", "value": title },
+            { "op": "add", "path": "/fields/System.Description", "value": body },
+            { "op": "add", "path": "/multilineFieldsFormat/System.Description", "value": "Markdown" },
+            { "op": "add", "path": "/fields/System.Tags", "value": format!("{NOTIFIER_TAG}; {tag}") },
+        ]);
+        let url = format!(
+            "{}/{}/{}/_apis/wit/workitems/${}?api-version={API_VERSION}",
+            self.config.base_url,
+            self.config.organization,
+            repository.project,
+            self.config.work_item_type
+        );
+        let request = self
+            .http
+            .post(url)
+            .header(CONTENT_TYPE, "application/json-patch+json")
+            .body(operations.to_string());
+
+        match self.send::<WorkItem>(request).await {
+            Ok(item) => {
+                let url = item.links["html"]["href"].as_str().map_or_else(
+                    || {
+                        format!(
+                            "{}/{}/{}/_workitems/edit/{}",
+                            self.config.base_url,
+                            self.config.organization,
+                            repository.project,
+                            item.id
+                        )
+                    },
+                    str::to_string,
+                );
+                let url =
+                    HtmlUrl::new(url).map_err(|e| AzureDevOpsError::Request(e.to_string()))?;
+                info!(work_item = item.id, "Work item created successfully");
+                Ok(IssueStatus::Created {
+                    number: item.id,
+                    url,
+                })
+            }
+            Err(e) if e.is_permission_denied() => {
+                warn!(repo = %repository.full_name, "Permission denied, skipping repository");
+                Ok(IssueStatus::Skipped {
+                    reason: PERMISSION_DENIED_REASON.to_string(),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Opens a pull request from the migration's branch into the repository's
+    /// default branch, optionally linking the notification work item.
+    ///
+    /// The branch (named by the migration's `branch_name_format`) must already
+    /// have been pushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AzureDevOpsError::UnknownDefaultBranch`] if the repository's
+    /// default branch is unknown, or [`AzureDevOpsError`] if a template fails
+    /// to render or the API rejects the pull request.
+    pub async fn create_pull_request(
+        &self,
+        repository: &AzureRepository,
+        migration: &Migration,
+        renderer: &TemplateRenderer,
+        work_item: Option<u64>,
+    ) -> Result<PrStatus, AzureDevOpsError> {
+        let target = repository.default_branch.as_deref().ok_or_else(|| {
+            AzureDevOpsError::UnknownDefaultBranch {
+                repository: repository.full_name.clone(),
+            }
+        })?;
+        let template_error =
+            |e: crate::templates::TemplateError| AzureDevOpsError::TemplateError(e.to_string());
+        let branch = generate_branch_name(migration).map_err(template_error)?;
+        let title = generate_pr_title(migration).map_err(template_error)?;
+        let description = renderer
+            .render_pr_template(&migration.pr_template, migration, None)
+            .map_err(template_error)?;
+
+        let mut body = json!({
+            "sourceRefName": format!("refs/heads/{branch}"),
+            "targetRefName": format!("refs/heads/{target}"),
+            "title": title,
+            "description": description,
+        });
+        if let Some(id) = work_item {
+            body["workItemRefs"] = json!([{ "id": id.to_string() }]);
+        }
+        let url = format!(
+            "{}/{}/{}/_apis/git/repositories/{}/pullrequests?api-version={API_VERSION}",
+            self.config.base_url, self.config.organization, repository.project, repository.id
+        );
+
+        let pr: PullRequest = self.send(self.http.post(url).json(&body)).await?;
+        info!(
+            pr_number = pr.pull_request_id,
+            "Pull request created successfully"
+        );
+        let url = HtmlUrl::new(format!(
+            "{}/pullrequest/{}",
+            pr.repository.web_url, pr.pull_request_id
+        ))
+        .map_err(|e| AzureDevOpsError::Request(e.to_string()))?;
+        Ok(PrStatus::Created {
+            number: pr.pull_request_id,
+            url,
+        })
+    }
+
+    /// Returns the default branch of repository `id` in `project`, without
+    /// its `refs/heads/` prefix; `None` for an empty repository.
+    async fn default_branch(
+        &self,
+        project: &str,
+        id: &str,
+    ) -> Result<Option<String>, AzureDevOpsError> {
+        let url = format!(
+            "{}/{}/{project}/_apis/git/repositories/{id}?api-version={API_VERSION}",
+            self.config.base_url, self.config.organization
+        );
+        let repository: GitRepository = self.send(self.http.get(url)).await?;
+        Ok(repository.default_branch.map(|branch| {
+            branch
+                .strip_prefix("refs/heads/")
+                .map_or(branch.clone(), str::to_string)
+        }))
+    }
+
+    /// Returns the id of an open work item with `title` tagged `tag`, if any.
+    async fn find_open_work_item(
+        &self,
+        repository: &AzureRepository,
+        title: &str,
+        tag: &str,
+    ) -> Result<Option<u64>, AzureDevOpsError> {
+        let query = format!(
+            "SELECT [System.Id] FROM WorkItems \
+             WHERE [System.TeamProject] = @project \
+             AND [System.Title] = '{}' \
+             AND [System.Tags] CONTAINS '{}' \
+             AND [System.State] NOT IN ('Closed', 'Done', 'Removed')",
+            escape_wiql(title),
+            escape_wiql(tag)
+        );
+        let url = format!(
+            "{}/{}/{}/_apis/wit/wiql?$top=1&api-version={API_VERSION}",
+            self.config.base_url, self.config.organization, repository.project
+        );
+        let response: WiqlResponse = self
+            .send(self.http.post(url).json(&json!({ "query": query })))
+            .await?;
+        Ok(response.work_items.first().map(|item| item.id))
+    }
+
+    /// Sends an authenticated request and parses the JSON response.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, AzureDevOpsError> {
+        let request_error = |e: reqwest::Error| AzureDevOpsError::Request(e.to_string());
+        let response = request
+            .basic_auth("", Some(&self.token))
+            .send()
+            .await
+            .map_err(request_error)?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(AzureDevOpsError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        response.json().await.map_err(request_error)
+    }
+}
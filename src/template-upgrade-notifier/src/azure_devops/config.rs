@@ -0,0 +1,71 @@
+//! Azure DevOps connection settings.
+
+use serde::Deserialize;
+
+/// Environment variable holding the Azure DevOps personal access token.
+pub(crate) const AZURE_DEVOPS_TOKEN_ENV: &str = "AZURE_DEVOPS_TOKEN";
+
+/// Settings for an Azure DevOps organization.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AzureDevOpsConfig {
+    /// Organization name, as in `https://dev.azure.com/{organization}`.
+    pub organization: String,
+
+    /// Projects to search; empty searches the whole organization.
+    #[serde(default)]
+    pub projects: Vec<String>,
+
+    /// Work item type filed as the upgrade notification (defaults to "Issue").
+    #[serde(default = "default_work_item_type")]
+    pub work_item_type: String,
+
+    /// Personal access token (optional, falls back to `AZURE_DEVOPS_TOKEN`).
+    pub token: Option<String>,
+
+    /// Base URL of the REST API (defaults to `https://dev.azure.com`).
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+
+    /// Base URL of the code search API (defaults to `https://almsearch.dev.azure.com`).
+    #[serde(default = "default_search_url")]
+    pub search_url: String,
+}
+
+impl AzureDevOpsConfig {
+    /// Creates settings for `organization` with every other field at its default.
+    pub fn new(organization: impl Into<String>) -> Self {
+        Self {
+            organization: organization.into(),
+            projects: Vec::new(),
+            work_item_type: default_work_item_type(),
+            token: None,
+            base_url: default_base_url(),
+            search_url: default_search_url(),
+        }
+    }
+
+    /// Resolves the access token, preferring the environment over the config.
+    #[cfg(feature = "azure-devops")]
+    pub(crate) fn resolved_token(&self) -> Option<String> {
+        let token = std::env::var(AZURE_DEVOPS_TOKEN_ENV)
+            .ok()
+            .or_else(|| self.token.clone());
+        if let Some(token) = &token {
+            crate::redact::register_secret(token.as_str());
+        }
+        token
+    }
+}
+
+fn default_work_item_type() -> String {
+    "Issue".to_string()
+}
+
+fn default_base_url() -> String {
+    "https://dev.azure.com".to_string()
+}
+
+fn default_search_url() -> String {
+    "https://almsearch.dev.azure.com".to_string()
+}
@@ -0,0 +1,59 @@
+//! Azure DevOps error types.
+
+use crate::error_kind::ErrorKind;
+use thiserror::Error;
+
+/// Errors that can occur while talking to Azure DevOps.
+#[derive(Debug, Error)]
+pub enum AzureDevOpsError {
+    /// No personal access token was configured.
+    #[error("No Azure DevOps token; set AZURE_DEVOPS_TOKEN or `token`")]
+    MissingToken,
+
+    /// The request could not be sent or its response could not be read.
+    #[error("Azure DevOps request failed: {0}")]
+    Request(String),
+
+    /// The API answered with an error status.
+    #[error("Azure DevOps API error ({status}): {message}")]
+    Api { status: u16, message: String },
+
+    /// Neither code search nor the repository reported a default branch to
+    /// open the pull request against.
+    #[error("default branch of '{repository}' is unknown")]
+    UnknownDefaultBranch { repository: String },
+
+    /// A title or body template failed to render.
+    #[error("Template error: {0}")]
+    TemplateError(String),
+
+    /// The crate was built without the `azure-devops` feature.
+    #[error("Azure DevOps support is not enabled; rebuild with the `azure-devops` feature")]
+    Disabled,
+}
+
+impl AzureDevOpsError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::MissingToken => ErrorKind::Auth,
+            Self::Request(_) => ErrorKind::Transient,
+            Self::Api { status, message } => ErrorKind::of_status(*status, message),
+            Self::TemplateError(_) => ErrorKind::Validation,
+            Self::UnknownDefaultBranch { .. } | Self::Disabled => ErrorKind::Other,
+        }
+    }
+
+    /// Returns true if the token lacks access to the project or repository.
+    #[must_use]
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(
+            self,
+            Self::Api {
+                status: 401 | 403,
+                ..
+            }
+        )
+    }
+}
@@ -0,0 +1,52 @@
+//! Azure DevOps repository support.
+//!
+//! Finds outdated templates in Azure Repos with Azure DevOps code search,
+//! files upgrade notifications as work items and opens pull requests. The
+//! REST client is only compiled with the `azure-devops` feature; the
+//! configuration types are always available.
+
+#[cfg(feature = "azure-devops")]
+mod client;
+mod config;
+mod error;
+#[cfg(feature = "azure-devops")]
+mod query;
+mod repository;
+
+#[cfg(feature = "azure-devops")]
+pub use client::AzureDevOpsClient;
+pub use config::AzureDevOpsConfig;
+pub(crate) use config::AZURE_DEVOPS_TOKEN_ENV;
+pub use error::AzureDevOpsError;
+pub use repository::AzureRepository;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config_with_defaults() {
+        let config: AzureDevOpsConfig = toml::from_str(
+            r#"
+organization = "contoso"
+projects = ["services"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.work_item_type, "Issue");
+        assert_eq!(config.base_url, "https://dev.azure.com");
+        assert_eq!(config.search_url, "https://almsearch.dev.azure.com");
+        assert!(config.token.is_none());
+    }
+
+    #[test]
+    fn permission_errors_are_detected() {
+        let denied = AzureDevOpsError::Api {
+            status: 403,
+            message: String::new(),
+        };
+        assert!(denied.is_permission_denied());
+        assert!(!AzureDevOpsError::MissingToken.is_permission_denied());
+    }
+}
@@ -0,0 +1,53 @@
+//! Query strings for Azure DevOps code search and WIQL.
+
+use super::AzureRepository;
+
+/// Tag added to every work item filed by the notifier.
+pub(super) const NOTIFIER_TAG: &str = "template-upgrade";
+
+/// Builds a code search query for `old_string` in files named any of
+/// `target_files`.
+pub(super) fn search_text(old_string: &str, target_files: &[&str]) -> String {
+    let files: Vec<String> = target_files
+        .iter()
+        .map(|file| format!("file:{file}"))
+        .collect();
+    let files = match files.as_slice() {
+        [file] => file.clone(),
+        _ => format!("({})", files.join(" OR ")),
+    };
+    format!("\"{}\" {files}", old_string.replace('"', "\\\""))
+}
+
+/// Returns the tag identifying a repository's work items within its project.
+pub(super) fn repository_tag(repository: &AzureRepository) -> String {
+    // Tags are separated by semicolons, so they can't contain one.
+    format!("repo:{}", repository.name.replace(';', "_"))
+}
+
+/// Escapes a value for use inside a single-quoted WIQL string.
+pub(super) fn escape_wiql(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_search_text() {
+        assert_eq!(
+            search_text("template:1.0", &["version.txt"]),
+            "\"template:1.0\" file:version.txt"
+        );
+        assert_eq!(
+            search_text("template:1.0", &["version.txt", ".template-version"]),
+            "\"template:1.0\" (file:version.txt OR file:.template-version)"
+        );
+    }
+
+    #[test]
+    fn escapes_wiql_quotes() {
+        assert_eq!(escape_wiql("Upgrade 'template'"), "Upgrade ''template''");
+    }
+}
@@ -0,0 +1,26 @@
+//! Azure DevOps repository information.
+
+use serde::Serialize;
+
+/// An Azure Repos repository found to contain an outdated template version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AzureRepository {
+    /// Project containing the repository.
+    pub project: String,
+
+    /// Repository name.
+    pub name: String,
+
+    /// Repository id, used by the pull request API.
+    pub id: String,
+
+    /// Full repository name in "project/name" format.
+    pub full_name: String,
+
+    /// Path to the file containing the match.
+    pub file_path: String,
+
+    /// Branch the match was found on (e.g., "main"), or else the repository's
+    /// default branch; `None` if Azure DevOps reported neither.
+    pub default_branch: Option<String>,
+}
@@ -0,0 +1,27 @@
+//! Campaign configuration.
+
+use serde::Deserialize;
+
+/// A named set of migrations run together, from a `[campaigns.<name>]`
+/// section of `notifier.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CampaignConfig {
+    /// Human-readable title for reports and the tracking issue (defaults to the name).
+    pub title: Option<String>,
+
+    /// Glob patterns selecting the campaign's migration ids.
+    pub migrations: Vec<String>,
+
+    /// `owner/name` repository in which a tracking issue is kept up to date.
+    pub tracking_repository: Option<String>,
+
+    /// Overrides whether auto-fix PRs are generated.
+    pub auto_pr: Option<bool>,
+
+    /// Overrides whether notifications in upgraded repositories are closed.
+    pub auto_close: Option<bool>,
+
+    /// Overrides the maximum number of repositories processed concurrently.
+    pub concurrency: Option<usize>,
+}
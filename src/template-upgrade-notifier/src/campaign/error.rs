@@ -0,0 +1,23 @@
+//! Campaign error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while resolving a campaign or updating its tracking issue.
+#[derive(Debug, Error)]
+pub enum CampaignError {
+    /// No campaign with this name is configured in `notifier.toml`.
+    #[error("unknown campaign: {name}")]
+    Unknown { name: String },
+
+    /// The campaign selects no migrations.
+    #[error("campaign '{name}' lists no migrations")]
+    NoMigrations { name: String },
+
+    /// The tracking repository is not in `owner/name` format.
+    #[error("invalid tracking repository '{value}', expected owner/name")]
+    InvalidRepository { value: String },
+
+    /// GitHub API error while updating the tracking issue.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+}
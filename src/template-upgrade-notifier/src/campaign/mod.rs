@@ -0,0 +1,116 @@
+//! Campaigns: named sets of migrations run together.
+//!
+//! A template release often ships several coordinated migrations. Running
+//! them as a campaign applies shared settings to all of them, labels the
+//! report and email digest with the campaign, and keeps a single tracking
+//! issue up to date instead of producing one disjoint report per migration.
+
+mod config;
+mod error;
+mod tracking;
+
+pub use config::CampaignConfig;
+pub use error::CampaignError;
+pub use tracking::update_tracking_issue;
+
+use crate::selection::PatternSet;
+use std::collections::BTreeMap;
+
+/// A campaign resolved from `notifier.toml`.
+#[derive(Debug, Clone)]
+pub struct Campaign {
+    /// Name of the `[campaigns.<name>]` section.
+    pub name: String,
+
+    /// The campaign's settings.
+    pub config: CampaignConfig,
+}
+
+impl Campaign {
+    /// Looks up the campaign called `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CampaignError::Unknown`] if no such campaign is configured, or
+    /// [`CampaignError::NoMigrations`] if it selects no migrations.
+    pub fn resolve(
+        campaigns: &BTreeMap<String, CampaignConfig>,
+        name: &str,
+    ) -> Result<Self, CampaignError> {
+        let config = campaigns.get(name).ok_or_else(|| CampaignError::Unknown {
+            name: name.to_string(),
+        })?;
+        if config.migrations.is_empty() {
+            return Err(CampaignError::NoMigrations {
+                name: name.to_string(),
+            });
+        }
+        Ok(Self {
+            name: name.to_string(),
+            config: config.clone(),
+        })
+    }
+
+    /// Returns the campaign's title, falling back to its name.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        self.config.title.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Returns the title of the campaign's tracking issue.
+    #[must_use]
+    pub fn tracking_title(&self) -> String {
+        format!("Template upgrade campaign: {}", self.title())
+    }
+
+    /// Compiles the campaign's migration selection.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SelectionError`](crate::selection::SelectionError) if a pattern is invalid.
+    pub fn migration_filter(&self) -> Result<PatternSet, crate::selection::SelectionError> {
+        PatternSet::new(&self.config.migrations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaigns() -> BTreeMap<String, CampaignConfig> {
+        toml::from_str(
+            r#"
+[v2]
+title = "Template v2"
+migrations = ["v2-*"]
+auto-pr = true
+
+[empty]
+migrations = []
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_configured_campaign() {
+        let campaign = Campaign::resolve(&campaigns(), "v2").unwrap();
+
+        assert_eq!(campaign.title(), "Template v2");
+        assert_eq!(campaign.config.auto_pr, Some(true));
+        assert!(campaign.migration_filter().unwrap().matches("v2-ci"));
+        assert!(!campaign.migration_filter().unwrap().matches("v1-ci"));
+    }
+
+    #[test]
+    fn rejects_unknown_or_empty_campaigns() {
+        assert!(matches!(
+            Campaign::resolve(&campaigns(), "missing"),
+            Err(CampaignError::Unknown { .. })
+        ));
+        assert!(matches!(
+            Campaign::resolve(&campaigns(), "empty"),
+            Err(CampaignError::NoMigrations { .. })
+        ));
+    }
+}
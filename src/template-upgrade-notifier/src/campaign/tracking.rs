@@ -0,0 +1,38 @@
+//! Tracking issue summarising a campaign's progress.
+
+use super::{Campaign, CampaignError};
+use crate::issues::{split_repository, upsert_own_issue};
+use crate::summary::RunSummary;
+use octocrab::Octocrab;
+
+/// Creates or updates the campaign's tracking issue with the run's report.
+///
+/// The issue is found by its exact title among our open issues in the
+/// tracking repository; its body is replaced with the latest markdown report.
+///
+/// # Returns
+///
+/// The tracking issue's number and URL, or `None` if the campaign has no
+/// tracking repository.
+///
+/// # Errors
+///
+/// Returns [`CampaignError`] if the tracking repository is malformed or a
+/// GitHub API call fails.
+pub async fn update_tracking_issue(
+    octocrab: &Octocrab,
+    campaign: &Campaign,
+    summary: &RunSummary,
+) -> Result<Option<(u64, String)>, CampaignError> {
+    let Some(repository) = campaign.config.tracking_repository.as_deref() else {
+        return Ok(None);
+    };
+    let (owner, name) =
+        split_repository(repository).ok_or_else(|| CampaignError::InvalidRepository {
+            value: repository.to_string(),
+        })?;
+    let body = summary.to_markdown();
+    let issue =
+        upsert_own_issue(octocrab, owner, name, &campaign.tracking_title(), |_| body).await?;
+    Ok(Some((issue.number, issue.html_url.to_string())))
+}
@@ -0,0 +1,32 @@
+//! Changelog error types.
+
+use crate::error_kind::ErrorKind;
+use thiserror::Error;
+
+/// Errors that can occur while fetching a migration's changelog.
+#[derive(Debug, Error)]
+pub enum ChangelogError {
+    /// GitHub API error.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+
+    /// The URL is not a `github.com/<owner>/<repo>/blob/<ref>/<path>` file URL.
+    #[error("Unsupported changelog URL '{0}': expected https://github.com/<owner>/<repo>/blob/<ref>/<path>")]
+    UnsupportedUrl(String),
+
+    /// The file does not exist at the URL's ref.
+    #[error("Changelog '{0}' not found")]
+    NotFound(String),
+}
+
+impl ChangelogError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::GitHubError(e) => ErrorKind::of_github(e),
+            Self::UnsupportedUrl(_) => ErrorKind::Validation,
+            Self::NotFound(_) => ErrorKind::NotFound,
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Release notes excerpts for issue and PR bodies.
+//!
+//! A migration's `changelog-file` (next to its `metadata.toml`) or
+//! `changelog-url` is searched for the section whose heading names the
+//! version being upgraded to. The section is available to templates as
+//! `{{changelog}}`, so consumers see what changed without clicking away.
+
+mod error;
+
+pub use error::ChangelogError;
+
+use crate::http_cache::{get_json, route, Contents};
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+
+/// Longest excerpt kept, in bytes; longer sections are cut at a line break.
+const MAX_EXCERPT_LEN: usize = 8_000;
+
+/// Returns the body of the first markdown section whose heading names
+/// `version`, without the heading.
+///
+/// The section ends at the next heading of the same or a higher level. A
+/// leading `v` is ignored on both sides, so `v1.2.0` finds `## [1.2.0]`, but
+/// `1.2.0` does not find `1.2.0-rc.1` or `1.2.01`. Sections longer than a
+/// few thousand bytes are cut at a line break. `None` if no heading names
+/// the version or its section is empty.
+#[must_use]
+pub fn extract_section(markdown: &str, version: &str) -> Option<String> {
+    let version = version.trim().trim_start_matches('v');
+    if version.is_empty() {
+        return None;
+    }
+    let mut lines = markdown.lines();
+    let level = lines.by_ref().find_map(|line| {
+        let (level, text) = heading(line)?;
+        names_version(text, version).then_some(level)
+    })?;
+    let body: Vec<&str> = lines
+        .take_while(|line| heading(line).is_none_or(|(next, _)| next > level))
+        .collect();
+    let body = body.join("\n");
+    let body = body.trim_matches('\n').trim_end();
+    (!body.is_empty()).then(|| truncate(body))
+}
+
+/// Returns the release notes for the version a migration upgrades to.
+///
+/// Looks for the `section` heading if set. Otherwise looks for `new_string`,
+/// then for the version at its end (`2.0.0` of `my-template:2.0.0`), since
+/// changelogs rarely repeat the template name in their headings.
+#[must_use]
+pub fn excerpt_for(markdown: &str, section: Option<&str>, new_string: &str) -> Option<String> {
+    if let Some(section) = section {
+        return extract_section(markdown, section);
+    }
+    extract_section(markdown, new_string).or_else(|| {
+        let version = new_string.rsplit([':', '@', '/', ' ']).next()?;
+        (version != new_string)
+            .then(|| extract_section(markdown, version))
+            .flatten()
+    })
+}
+
+/// Fetches the changelog file at a `https://github.com/<owner>/<repo>/blob/<ref>/<path>` URL.
+///
+/// The file is read through the GitHub API, so private repositories the
+/// token can read work too and the token is never sent to another host.
+///
+/// # Errors
+///
+/// Returns [`ChangelogError::UnsupportedUrl`] for any other URL,
+/// [`ChangelogError::NotFound`] if the file does not exist at that ref, or
+/// [`ChangelogError::GitHubError`] if the request fails.
+pub async fn fetch_changelog(octocrab: &Octocrab, url: &str) -> Result<String, ChangelogError> {
+    let blob =
+        BlobUrl::parse(url).ok_or_else(|| ChangelogError::UnsupportedUrl(url.to_string()))?;
+    ensure_core_rate_limit(octocrab).await?;
+    let contents_route = route(
+        ["repos", blob.owner.as_str(), blob.repo.as_str(), "contents"]
+            .into_iter()
+            .chain(blob.path.split('/')),
+    );
+    let git_ref: String = url::form_urlencoded::byte_serialize(blob.git_ref.as_bytes()).collect();
+    let result = get_json::<Contents>(octocrab, &format!("{contents_route}?ref={git_ref}")).await;
+    let contents = match result {
+        Ok(contents) => contents,
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+            return Err(ChangelogError::NotFound(url.to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    contents
+        .decoded_content()
+        .ok_or_else(|| ChangelogError::NotFound(url.to_string()))
+}
+
+/// Returns true if `url` is a changelog URL [`fetch_changelog`] can read.
+#[must_use]
+pub fn is_supported_url(url: &str) -> bool {
+    BlobUrl::parse(url).is_some()
+}
+
+/// A file in a GitHub repository at a ref.
+#[derive(Debug, PartialEq, Eq)]
+struct BlobUrl {
+    owner: String,
+    repo: String,
+    git_ref: String,
+    path: String,
+}
+
+impl BlobUrl {
+    /// Parses a `https://github.com/<owner>/<repo>/blob/<ref>/<path>` URL.
+    ///
+    /// The ref is the single segment after `blob`, so branch names containing
+    /// slashes are not supported; use a tag or commit instead.
+    fn parse(url: &str) -> Option<Self> {
+        let url = url::Url::parse(url).ok()?;
+        if url.scheme() != "https" || url.host_str() != Some("github.com") {
+            return None;
+        }
+        let mut segments = url.path_segments()?;
+        let owner = segments.next().filter(|s| !s.is_empty())?;
+        let repo = segments.next().filter(|s| !s.is_empty())?;
+        if segments.next() != Some("blob") {
+            return None;
+        }
+        let git_ref = segments.next().filter(|s| !s.is_empty())?;
+        let path: Vec<&str> = segments.collect();
+        if path.is_empty() || path.iter().any(|s| s.is_empty()) {
+            return None;
+        }
+        let decode = |s: &str| {
+            percent_encoding::percent_decode_str(s)
+                .decode_utf8()
+                .ok()
+                .map(|s| s.into_owned())
+        };
+        Some(Self {
+            owner: decode(owner)?,
+            repo: decode(repo)?,
+            git_ref: decode(git_ref)?,
+            path: path
+                .into_iter()
+                .map(decode)
+                .collect::<Option<Vec<_>>>()?
+                .join("/"),
+        })
+    }
+}
+
+/// Returns the level and text of an ATX heading (`## text`).
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let line = line.trim_start();
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let text = &line[level..];
+    (text.is_empty() || text.starts_with([' ', '\t'])).then_some((level, text.trim()))
+}
+
+/// Returns true if `text` contains `version` not directly followed or
+/// preceded by more of a version.
+fn names_version(text: &str, version: &str) -> bool {
+    text.match_indices(version).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + version.len()..].chars().next();
+        let continues = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+');
+        !before.is_some_and(|c| c.is_ascii_digit() || c == '.') && !after.is_some_and(continues)
+    })
+}
+
+/// Cuts `body` at the last line break that keeps it within [`MAX_EXCERPT_LEN`].
+fn truncate(body: &str) -> String {
+    if body.len() <= MAX_EXCERPT_LEN {
+        return body.to_string();
+    }
+    let mut end = MAX_EXCERPT_LEN;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = body[..end].rfind('\n').unwrap_or(end);
+    format!("{}\n\n…", body[..end].trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+# Changelog
+
+## [Unreleased]
+
+- Nothing yet
+
+## [1.2.0] - 2024-05-01
+
+### Added
+
+- Dark mode
+
+## [1.2.0-rc.1] - 2024-04-20
+
+- Preview
+";
+
+    #[test]
+    fn extracts_section_of_version() {
+        assert_eq!(
+            extract_section(CHANGELOG, "v1.2.0").as_deref(),
+            Some("### Added\n\n- Dark mode")
+        );
+        assert_eq!(
+            extract_section(CHANGELOG, "1.2.0-rc.1").as_deref(),
+            Some("- Preview")
+        );
+        assert_eq!(extract_section(CHANGELOG, "1.2"), None);
+        assert_eq!(extract_section(CHANGELOG, "2.0.0"), None);
+    }
+
+    #[test]
+    fn excerpt_falls_back_to_version_suffix() {
+        assert_eq!(
+            excerpt_for(CHANGELOG, None, "my-template:v1.2.0").as_deref(),
+            Some("### Added\n\n- Dark mode")
+        );
+        assert_eq!(
+            excerpt_for(CHANGELOG, Some("Unreleased"), "my-template:v1.2.0").as_deref(),
+            Some("- Nothing yet")
+        );
+        assert_eq!(excerpt_for(CHANGELOG, None, "9.9.9"), None);
+    }
+
+    #[test]
+    fn truncates_long_sections() {
+        let markdown = format!("## 1.0.0\n{}", "- change\n".repeat(2_000));
+
+        let section = extract_section(&markdown, "1.0.0").unwrap();
+
+        assert!(section.len() <= MAX_EXCERPT_LEN + 5);
+        assert!(section.ends_with("- change\n\n…"));
+    }
+
+    #[test]
+    fn parses_blob_urls() {
+        assert_eq!(
+            BlobUrl::parse("https://github.com/org/tpl/blob/v2.0.0/docs/CHANGELOG.md"),
+            Some(BlobUrl {
+                owner: "org".to_string(),
+                repo: "tpl".to_string(),
+                git_ref: "v2.0.0".to_string(),
+                path: "docs/CHANGELOG.md".to_string(),
+            })
+        );
+        assert!(!is_supported_url("https://example.com/CHANGELOG.md"));
+        assert!(!is_supported_url(
+            "https://github.com/org/tpl/tree/main/CHANGELOG.md"
+        ));
+        assert!(!is_supported_url("https://github.com/org/tpl/blob/main"));
+    }
+}
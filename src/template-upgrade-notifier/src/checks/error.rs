@@ -0,0 +1,11 @@
+//! Commit status and check run error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while publishing a commit status or check run.
+#[derive(Debug, Error)]
+pub enum ChecksError {
+    /// GitHub API error.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+}
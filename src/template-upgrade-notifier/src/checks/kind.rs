@@ -0,0 +1,17 @@
+//! Kinds of passive signal published on outdated repositories.
+
+use serde::{Deserialize, Serialize};
+
+/// How an outdated repository is flagged on its default branch HEAD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckKind {
+    /// A commit status, shown as a failing status next to the commit.
+    ///
+    /// Works with any token that has the `repo:status` scope.
+    Status,
+    /// A check run with a neutral conclusion, shown in the Checks tab.
+    ///
+    /// The Checks API only accepts GitHub App tokens.
+    CheckRun,
+}
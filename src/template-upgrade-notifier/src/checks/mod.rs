@@ -0,0 +1,257 @@
+//! Commit statuses and check runs on outdated repositories.
+//!
+//! Besides the upgrade issue, a migration can opt into flagging the head of
+//! each outdated repository's default branch with a `template-version`
+//! commit status or check run. It gives consumers a passive signal in the
+//! commit list and on pull requests, even when they ignore issues.
+
+mod error;
+mod kind;
+
+pub use error::ChecksError;
+pub use kind::CheckKind;
+
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::http_cache::route;
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Context of the commit status and name of the check run.
+pub const CHECK_CONTEXT: &str = "template-version";
+
+/// Maximum length GitHub accepts for a commit status description.
+const MAX_DESCRIPTION_CHARS: usize = 140;
+
+/// Check runs read when looking for an earlier one; only the latest is updated.
+const CHECK_RUNS_PER_PAGE: u8 = 1;
+
+/// A git reference, as returned by `GET /repos/{owner}/{repo}/git/ref/{ref}`.
+#[derive(Deserialize)]
+struct GitRef {
+    object: GitObject,
+}
+
+#[derive(Deserialize)]
+struct GitObject {
+    sha: String,
+}
+
+/// One page of `GET /repos/{owner}/{repo}/commits/{sha}/check-runs`.
+#[derive(Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    id: u64,
+}
+
+/// Marks the head of the repository's default branch as outdated.
+///
+/// `target_url` is linked from the status or check run; typically the upgrade
+/// issue, or the migration guide when no issue was filed. Publishing again on
+/// the same commit replaces the previous commit status, or updates the
+/// previous check run instead of adding another.
+///
+/// # Returns
+///
+/// The SHA of the flagged commit.
+///
+/// # Errors
+///
+/// Returns [`ChecksError`] if the head commit cannot be resolved or GitHub
+/// rejects the status or check run.
+pub async fn publish_outdated_check(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+    kind: CheckKind,
+    target_url: Option<&str>,
+) -> Result<String, ChecksError> {
+    let head = head_commit(octocrab, repository).await?;
+    debug!(repo = %repository.full_name, sha = %head.sha, ?kind, "Publishing outdated check");
+
+    let description = outdated_description(migration);
+    match kind {
+        CheckKind::Status => {
+            let mut body = json!({
+                "state": "failure",
+                "context": CHECK_CONTEXT,
+                "description": description,
+            });
+            if let Some(url) = target_url {
+                body["target_url"] = json!(url);
+            }
+            let route = route([
+                "repos",
+                &repository.owner,
+                &repository.name,
+                "statuses",
+                &head.sha,
+            ]);
+            let _: serde_json::Value = octocrab.post(route, Some(&body)).await?;
+        }
+        CheckKind::CheckRun => {
+            let summary = match target_url {
+                Some(url) => format!(
+                    "This repository uses `{}`. See {url} for upgrade instructions.",
+                    migration.old_string
+                ),
+                None => format!("This repository uses `{}`.", migration.old_string),
+            };
+            let mut body = json!({
+                "name": CHECK_CONTEXT,
+                "head_sha": head.sha,
+                "status": "completed",
+                "conclusion": "neutral",
+                "output": {
+                    "title": description,
+                    "summary": summary,
+                },
+            });
+            if let Some(url) = target_url {
+                body["details_url"] = json!(url);
+            }
+            let _: serde_json::Value = match latest_check_run(octocrab, repository, &head.sha)
+                .await?
+            {
+                Some(id) => {
+                    let route = route([
+                        "repos",
+                        &repository.owner,
+                        &repository.name,
+                        "check-runs",
+                        &id.to_string(),
+                    ]);
+                    octocrab.patch(route, Some(&body)).await?
+                }
+                None => {
+                    let route = route(["repos", &repository.owner, &repository.name, "check-runs"]);
+                    octocrab.post(route, Some(&body)).await?
+                }
+            };
+        }
+    }
+    info!(repo = %repository.full_name, sha = %head.sha, "Flagged default branch as outdated");
+    Ok(head.sha)
+}
+
+/// Resolves the head of the repository's default branch.
+///
+/// Reads the branch reference rather than the commit, so no diff is
+/// downloaded and nothing is cached.
+async fn head_commit(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+) -> Result<GitObject, ChecksError> {
+    ensure_core_rate_limit(octocrab).await?;
+    let segments = [
+        "repos",
+        &repository.owner,
+        &repository.name,
+        "git",
+        "ref",
+        "heads",
+    ];
+    let route = route(
+        segments
+            .into_iter()
+            .chain(repository.default_branch.split('/')),
+    );
+    let reference: GitRef = octocrab.get(route, None::<&()>).await?;
+    Ok(reference.object)
+}
+
+/// Returns the ID of the latest [`CHECK_CONTEXT`] check run on `sha`, if any.
+///
+/// Asks the check-runs endpoint for that name only, and reads a single page.
+async fn latest_check_run(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    sha: &str,
+) -> Result<Option<u64>, ChecksError> {
+    ensure_core_rate_limit(octocrab).await?;
+    let route = route([
+        "repos",
+        &repository.owner,
+        &repository.name,
+        "commits",
+        sha,
+        "check-runs",
+    ]);
+    let runs: CheckRuns = octocrab
+        .get(
+            route,
+            Some(&[
+                ("check_name", CHECK_CONTEXT.to_string()),
+                ("filter", "latest".to_string()),
+                ("per_page", CHECK_RUNS_PER_PAGE.to_string()),
+            ]),
+        )
+        .await?;
+    Ok(runs.check_runs.first().map(|run| run.id))
+}
+
+/// Returns `outdated: old -> new`, shortened to fit a commit status.
+fn outdated_description(migration: &Migration) -> String {
+    let description = format!(
+        "outdated: {} -> {}",
+        migration.old_string, migration.new_string
+    );
+    if description.chars().count() <= MAX_DESCRIPTION_CHARS {
+        return description;
+    }
+    let mut short: String = description
+        .chars()
+        .take(MAX_DESCRIPTION_CHARS - 3)
+        .collect();
+    short.push_str("...");
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(old_string: &str, new_string: &str) -> Migration {
+        Migration {
+            old_string: old_string.to_string(),
+            new_string: new_string.to_string(),
+            ..Migration::default()
+        }
+    }
+
+    #[test]
+    fn describes_upgrade() {
+        assert_eq!(
+            outdated_description(&migration("v1", "v2")),
+            "outdated: v1 -> v2"
+        );
+    }
+
+    #[test]
+    fn truncates_long_description() {
+        let description = outdated_description(&migration(&"a".repeat(100), &"b".repeat(100)));
+
+        assert_eq!(description.chars().count(), MAX_DESCRIPTION_CHARS);
+        assert!(description.ends_with("..."));
+    }
+
+    #[test]
+    fn parses_kinds() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            kind: CheckKind,
+        }
+        let parse = |s: &str| toml::from_str::<Wrapper>(&format!("kind = \"{s}\"")).map(|w| w.kind);
+
+        assert_eq!(parse("status").unwrap(), CheckKind::Status);
+        assert_eq!(parse("check-run").unwrap(), CheckKind::CheckRun);
+        assert!(parse("annotation").is_err());
+    }
+}
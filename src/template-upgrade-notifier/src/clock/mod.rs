@@ -0,0 +1,31 @@
+//! Time source used by the runner.
+//!
+//! Deadlines, retry delays, watch-mode scheduling and recorded timestamps all
+//! go through [`Clock`], so tests can substitute a clock that doesn't wait.
+
+use chrono::{DateTime, Utc};
+use core::time::Duration;
+use futures::future::BoxFuture;
+
+/// Provides the current time and waits for durations to elapse.
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Returns a future that completes after `duration`.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// The real system clock, sleeping on the Tokio timer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
@@ -0,0 +1,79 @@
+//! Chains of migrations upgrading the same template.
+//!
+//! Migrations of a template are chained when one's `new_string` is the next
+//! one's `old_string` (`v1 -> v2`, `v2 -> v3`). A repository found by the
+//! first is then two versions behind the newest.
+
+use crate::config::Migration;
+use std::collections::HashMap;
+
+/// Sets each migration's [`versions_behind`](Migration::versions_behind): the
+/// number of migrations from it to the end of its template's chain.
+///
+/// A cycle (`v1 -> v2`, `v2 -> v1`) stops counting once every migration of
+/// the template has been visited.
+pub(super) fn link_chains(migrations: &mut [Migration]) {
+    let next: HashMap<(&str, &str), usize> = migrations
+        .iter()
+        .enumerate()
+        .map(|(index, m)| ((m.template(), m.old_string.as_str()), index))
+        .collect();
+    let behind: Vec<usize> = migrations
+        .iter()
+        .map(|migration| {
+            let mut steps = 1;
+            let mut current = migration;
+            while let Some(&index) = next.get(&(current.template(), current.new_string.as_str())) {
+                if steps >= migrations.len() {
+                    break;
+                }
+                steps += 1;
+                current = &migrations[index];
+            }
+            steps
+        })
+        .collect();
+    for (migration, behind) in migrations.iter_mut().zip(behind) {
+        migration.versions_behind = behind;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(id: &str, old_string: &str, new_string: &str) -> Migration {
+        Migration {
+            id: id.parse().unwrap(),
+            old_string: old_string.to_string(),
+            new_string: new_string.to_string(),
+            ..Migration::default()
+        }
+    }
+
+    #[test]
+    fn counts_migrations_to_end_of_chain() {
+        let mut migrations = [
+            migration("tpl/v2-to-v3", "tpl:2", "tpl:3"),
+            migration("tpl/v1-to-v2", "tpl:1", "tpl:2"),
+            migration("other/v2-to-v3", "tpl:3", "tpl:4"),
+        ];
+
+        link_chains(&mut migrations);
+
+        let behind: Vec<usize> = migrations.iter().map(|m| m.versions_behind).collect();
+        assert_eq!(behind, [1, 2, 1]);
+    }
+
+    #[test]
+    fn stops_on_cycles() {
+        let mut migrations = [
+            migration("tpl/v1-to-v2", "tpl:1", "tpl:2"),
+            migration("tpl/v2-to-v1", "tpl:2", "tpl:1"),
+        ];
+
+        link_chains(&mut migrations);
+
+        assert_eq!(migrations[0].versions_behind, 2);
+    }
+}
@@ -0,0 +1,92 @@
+//! How far a dry run goes before stopping short of changing anything.
+
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// How much of a live run a dry run exercises (`dry-run` in `metadata.toml`,
+/// `--dry-run-level` on the command line).
+///
+/// Each level does everything the previous one does.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum DryRunLevel {
+    /// List the discovered repositories with a sample issue body.
+    #[default]
+    DiscoverOnly,
+    /// Also render every repository's issue and PR title and body.
+    RenderOnly,
+    /// Also clone each repository and run the migration's strategy locally,
+    /// printing the resulting diff without committing or pushing.
+    CloneAndDiff,
+}
+
+impl DryRunLevel {
+    /// Every level, from least to most thorough.
+    pub const ALL: [Self; 3] = [Self::DiscoverOnly, Self::RenderOnly, Self::CloneAndDiff];
+
+    /// Returns the level as it is written in `metadata.toml`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DiscoverOnly => "discover-only",
+            Self::RenderOnly => "render-only",
+            Self::CloneAndDiff => "clone-and-diff",
+        }
+    }
+
+    /// Returns true if issue and PR bodies are rendered for every repository.
+    #[must_use]
+    pub fn renders(self) -> bool {
+        self >= Self::RenderOnly
+    }
+
+    /// Returns true if repositories are cloned and the strategy run locally.
+    #[must_use]
+    pub fn clones(self) -> bool {
+        self >= Self::CloneAndDiff
+    }
+}
+
+impl fmt::Display for DryRunLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DryRunLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str() == s)
+            .ok_or_else(|| {
+                format!("unknown dry-run level '{s}'; expected discover-only, render-only or clone-and-diff")
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_level() {
+        for level in DryRunLevel::ALL {
+            assert_eq!(level.as_str().parse::<DryRunLevel>(), Ok(level));
+        }
+        assert!("full".parse::<DryRunLevel>().is_err());
+    }
+
+    #[test]
+    fn levels_are_cumulative() {
+        assert!(!DryRunLevel::DiscoverOnly.renders());
+        assert!(DryRunLevel::RenderOnly.renders());
+        assert!(!DryRunLevel::RenderOnly.clones());
+        assert!(DryRunLevel::CloneAndDiff.renders());
+        assert!(DryRunLevel::CloneAndDiff.clones());
+    }
+}
@@ -14,7 +14,7 @@ pub enum ConfigError {
     },
 
     /// Failed to parse TOML content.
-    #[error("Failed to parse metadata.toml in '{path}': {source}")]
+    #[error("Failed to parse TOML in '{path}': {source}")]
     TomlError {
         path: String,
         #[source]
@@ -28,4 +28,12 @@ pub enum ConfigError {
     /// Missing required file.
     #[error("Missing required file: {path}")]
     MissingFile { path: String },
+
+    /// Migrations failed to load in a strict scan.
+    #[error(
+        "{} migration(s) failed to load: {}",
+        failures.len(),
+        failures.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    InvalidMigrations { failures: Vec<ConfigError> },
 }
@@ -1,7 +1,16 @@
 //! Migration metadata deserialization and validation.
 
-use crate::config::ConfigError;
+use crate::changelog;
+use crate::checks::CheckKind;
+use crate::config::{ConfigError, DryRunLevel, NotPlannedPolicy, TargetFiles, VersionSource};
+use crate::dispatch::DispatchConfig;
+use crate::issues::DuplicatePolicy;
+use crate::pull_requests::{CommitIdentity, MigrationStrategy, PrMetadata};
+use crate::selection::PatternSet;
+use crate::stages::RolloutPercent;
 use handlebars::Handlebars;
+use regex::Regex;
+use semver::VersionReq;
 use serde::Deserialize;
 use std::path::Path;
 
@@ -18,25 +27,57 @@ pub struct MigrationMetadata {
     /// URL to migration documentation (optional).
     pub migration_guide_link: Option<String>,
 
-    /// File name to search for (defaults to "template-version.txt").
-    #[serde(default = "default_target_file")]
-    pub target_file: String,
+    /// Changelog next to `metadata.toml` (e.g. `"CHANGELOG.md"`) whose
+    /// section for `new_string` is exposed to templates as `{{changelog}}`.
+    pub changelog_file: Option<String>,
+
+    /// `https://github.com/<owner>/<repo>/blob/<ref>/<path>` URL of a
+    /// changelog, read at the start of each run instead of `changelog_file`.
+    pub changelog_url: Option<String>,
+
+    /// Heading text of the changelog section to include; defaults to
+    /// `new_string`, or the version at its end.
+    pub changelog_section: Option<String>,
+
+    /// File name, or list of file names, to search for (defaults to
+    /// "template-version.txt", or the answers file of the `version_source` tool).
+    #[serde(default)]
+    pub target_file: TargetFiles,
+
+    /// Glob the matched file's path must match, e.g.
+    /// `**/.github/template-version.txt`; any path when absent.
+    pub target_path: Option<String>,
+
+    /// Users or organizations whose repositories code search is restricted
+    /// to; searches all of GitHub when empty.
+    #[serde(default)]
+    pub search_owners: Vec<String>,
+
+    /// Glob patterns of `owner/name` repositories to notify; every discovered
+    /// repository when empty.
+    #[serde(default)]
+    pub include_repos: Vec<String>,
+
+    /// Glob patterns of `owner/name` repositories never to notify, even when
+    /// `include_repos` matches them.
+    #[serde(default)]
+    pub exclude_repos: Vec<String>,
 
     /// Handlebars format for issue titles.
     ///
-    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `migration_guide_link`
+    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `target_files`, `migration_guide_link`
     #[serde(default = "default_issue_title_format")]
     pub issue_title_format: String,
 
     /// Handlebars format for PR titles.
     ///
-    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `migration_guide_link`
+    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `target_files`, `migration_guide_link`
     #[serde(default = "default_pr_title_format")]
     pub pr_title_format: String,
 
     /// Handlebars format for branch names.
     ///
-    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `migration_guide_link`
+    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `target_files`, `migration_guide_link`
     ///
     /// The rendered value must be a valid git branch name.
     #[serde(default = "default_branch_name_format")]
@@ -44,9 +85,91 @@ pub struct MigrationMetadata {
 
     /// Handlebars format for commit titles.
     ///
-    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `migration_guide_link`
+    /// Available variables: `old_string`, `new_string`, `id`, `target_file`, `target_files`, `migration_guide_link`
     #[serde(default = "default_commit_title_format")]
     pub commit_title_format: String,
+
+    /// Team slugs to @-mention in issue bodies, in organization-owned repositories.
+    #[serde(default)]
+    pub mention_teams: Vec<String>,
+
+    /// Team slugs to request as reviewers of auto-fix PRs, in organization-owned repositories.
+    #[serde(default)]
+    pub reviewer_teams: Vec<String>,
+
+    /// Flags the default branch HEAD of outdated repositories with a commit
+    /// status or check run (`"status"` or `"check-run"`); off when unset.
+    #[serde(default)]
+    pub commit_check: Option<CheckKind>,
+
+    /// Where the current template version is read from (`"text"`, `"copier"`
+    /// or `"cruft"`); defaults to matching `old_string` anywhere in the file.
+    #[serde(default)]
+    pub version_source: VersionSource,
+
+    /// Semver range of outdated versions (e.g. `">=1.0, <1.2"`), matched
+    /// against the version read by `version_source` instead of `old_string`.
+    ///
+    /// `old_string` is then only used as the code search term.
+    pub old_version_range: Option<String>,
+
+    /// Regex capturing the current version from the line containing
+    /// `old_string` (e.g. `"image: tool:(?P<version>[\\w.-]+)"`), in its
+    /// `version` group or else its first group; the version is `old_string`
+    /// (or the one read by `version_source`) when unset.
+    pub version_pattern: Option<String>,
+
+    /// Renovate/Dependabot-style branch prefix, labels and version table for
+    /// auto-fix PRs (`[pr-metadata]`); off when unset.
+    #[serde(default)]
+    pub pr_metadata: Option<PrMetadata>,
+
+    /// Share of discovered repositories to notify, as one percentage (`25`) or
+    /// as stages advanced after every live run (`[10, 50, 100]`); all when unset.
+    #[serde(default)]
+    pub rollout_percent: Option<RolloutPercent>,
+
+    /// Author of this migration's auto-fix commits (`[commit-identity]`),
+    /// replacing the one from `notifier.toml`.
+    #[serde(default)]
+    pub commit_identity: Option<CommitIdentity>,
+
+    /// What to do when the issue is already open (`"skip"`, `"comment"`,
+    /// `"update"` or `"recreate"`); defaults to skipping the repository.
+    #[serde(default)]
+    pub duplicate_policy: DuplicatePolicy,
+
+    /// Lines from the start of the repository's README to include in the
+    /// auto-fix prompt as context on the project's conventions; off when unset.
+    #[serde(default)]
+    pub readme_context_lines: Option<usize>,
+
+    /// How auto-fix PRs apply the migration (`"llm"`, `"replace"`, or a
+    /// `patch`, `script` or `composite` table); defaults to the LLM.
+    #[serde(default)]
+    pub strategy: MigrationStrategy,
+
+    /// Command (program and arguments) run in the clone to check an
+    /// `escalate` strategy's work; a non-zero exit means work remains.
+    #[serde(default)]
+    pub validate_command: Option<Vec<String>>,
+
+    /// What an issue closed as "not planned" means for later notifications
+    /// (`"template"`, `"migration"` or `"ignore"`); defaults to skipping the
+    /// repository for the template until a new major version.
+    #[serde(default)]
+    pub not_planned: NotPlannedPolicy,
+
+    /// Keeps this migration a dry run at the given level (`"discover-only"`,
+    /// `"render-only"` or `"clone-and-diff"`) even in live runs, and replaces
+    /// the run's level in dry runs; follows the run when unset.
+    #[serde(default)]
+    pub dry_run: Option<DryRunLevel>,
+
+    /// Sends outdated repositories a `repository_dispatch` event
+    /// (`[dispatch]`), alongside or instead of the issue; off when unset.
+    #[serde(default)]
+    pub dispatch: Option<DispatchConfig>,
 }
 
 impl MigrationMetadata {
@@ -96,7 +219,18 @@ impl MigrationMetadata {
     /// - `old_string` is empty
     /// - `new_string` is empty
     /// - `migration_guide_link` is not a valid URL (if present)
-    /// - `target_file` contains path separators
+    /// - both `changelog_file` and `changelog_url` are set, `changelog_file`
+    ///   is absolute or leaves the migration folder, or `changelog_url` is not
+    ///   a GitHub file URL
+    /// - `target_file` is an empty list, or a name is empty or contains path separators
+    /// - `target_path` is not a valid glob
+    /// - a `search_owners` entry is not a valid user or organization name
+    /// - an `include_repos` or `exclude_repos` entry is not a valid glob
+    /// - `old_version_range` is not a semver range, or is set without a `version_source`
+    /// - `version_pattern` is not a valid regex or has no capture group
+    /// - `rollout_percent` is empty, above 100, or has decreasing stages
+    /// - `validate_command` is empty
+    /// - `dispatch` has an empty or over-long event type, or an invalid payload template
     pub fn validate(&self, path: &Path) -> Result<(), ConfigError> {
         let path_str = path.display().to_string();
 
@@ -134,11 +268,132 @@ impl MigrationMetadata {
             }
         }
 
-        // Validate target_file doesn't contain path separators
-        if self.target_file.contains('/') || self.target_file.contains('\\') {
+        // Validate the changelog source
+        let changelog_problem = match (&self.changelog_file, &self.changelog_url) {
+            (Some(_), Some(_)) => {
+                Some("changelog-file and changelog-url are mutually exclusive".to_string())
+            }
+            (Some(file), None)
+                if Path::new(file).is_absolute()
+                    || Path::new(file)
+                        .components()
+                        .any(|c| matches!(c, std::path::Component::ParentDir)) =>
+            {
+                Some(format!(
+                    "changelog-file must be a path within the migration folder: {file}"
+                ))
+            }
+            (None, Some(url)) if !changelog::is_supported_url(url) => Some(format!(
+                "changelog-url must be https://github.com/<owner>/<repo>/blob/<ref>/<path>: {url}"
+            )),
+            _ => None,
+        };
+        if let Some(message) = changelog_problem {
+            return Err(ConfigError::ValidationError {
+                path: path_str,
+                message,
+            });
+        }
+
+        // Validate target_file names are plain, non-empty file names
+        if let Some(problem) = self.target_file.problem() {
             return Err(ConfigError::ValidationError {
                 path: path_str.clone(),
-                message: "target-file must not contain path separators".to_string(),
+                message: format!("target-file {problem}"),
+            });
+        }
+
+        // Validate search owners are plain account names, not search syntax
+        if let Some(owner) = self.search_owners.iter().find(|owner| {
+            owner.is_empty()
+                || !owner
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        }) {
+            return Err(ConfigError::ValidationError {
+                path: path_str,
+                message: format!("search-owners entry is not a valid account name: '{owner}'"),
+            });
+        }
+
+        // Validate the target path and repository globs compile
+        if let Err(e) = PatternSet::new(&self.target_path) {
+            return Err(ConfigError::ValidationError {
+                path: path_str,
+                message: format!("target-path: {e}"),
+            });
+        }
+        for (field, patterns) in [
+            ("include-repos", &self.include_repos),
+            ("exclude-repos", &self.exclude_repos),
+        ] {
+            if let Err(e) = PatternSet::new(patterns) {
+                return Err(ConfigError::ValidationError {
+                    path: path_str,
+                    message: format!("{field}: {e}"),
+                });
+            }
+        }
+
+        // Validate the version range and that it has a version to match against
+        if let Some(range) = &self.old_version_range {
+            if let Err(e) = VersionReq::parse(range) {
+                return Err(ConfigError::ValidationError {
+                    path: path_str,
+                    message: format!("old-version-range is not a valid semver range: {e}"),
+                });
+            }
+            if self.version_source == VersionSource::Text {
+                return Err(ConfigError::ValidationError {
+                    path: path_str,
+                    message: "old-version-range requires a version-source other than text"
+                        .to_string(),
+                });
+            }
+        }
+
+        // Validate the version pattern and that it captures something
+        if let Some(pattern) = &self.version_pattern {
+            let message = match Regex::new(pattern) {
+                Ok(regex) if regex.captures_len() < 2 => {
+                    Some("version-pattern must have a capture group".to_string())
+                }
+                Ok(_) => None,
+                Err(e) => Some(format!("version-pattern is not a valid regex: {e}")),
+            };
+            if let Some(message) = message {
+                return Err(ConfigError::ValidationError {
+                    path: path_str,
+                    message,
+                });
+            }
+        }
+
+        // Validate rollout percentages
+        if let Some(problem) = self
+            .rollout_percent
+            .as_ref()
+            .and_then(RolloutPercent::problem)
+        {
+            return Err(ConfigError::ValidationError {
+                path: path_str,
+                message: format!("rollout-percent {problem}"),
+            });
+        }
+
+        // Validate the validation command has a program to run
+        if self.validate_command.as_ref().is_some_and(Vec::is_empty) {
+            return Err(ConfigError::ValidationError {
+                path: path_str,
+                message: "validate-command must not be empty".to_string(),
+            });
+        }
+
+        // Validate the dispatch event type and payload template
+        if let Some(problem) = self.dispatch.as_ref().and_then(DispatchConfig::problem) {
+            return Err(ConfigError::ValidationError {
+                path: path_str,
+                message: format!("dispatch: {problem}"),
             });
         }
 
@@ -315,6 +570,208 @@ target-file = "version.txt"
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn validation_target_file_list() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"v1\"\nnew-string = \"v2\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(parse("target-file = [\"version.txt\", \".template-version\"]").is_ok());
+        assert!(matches!(
+            parse("target-file = []"),
+            Err(ConfigError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            parse("target-file = [\"version.txt\", \"dir/version.txt\"]"),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_target_path() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"v1\"\nnew-string = \"v2\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(parse("target-path = \"**/.github/template-version.txt\"").is_ok());
+        assert!(matches!(
+            parse("target-path = \"a/[b\""),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_version_pattern() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"tool:1\"\nnew-string = \"tool:2\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(parse("version-pattern = 'tool:(?P<version>[\\d.]+)'").is_ok());
+        assert!(matches!(
+            parse("version-pattern = 'tool:[\\d.]+'"),
+            Err(ConfigError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            parse("version-pattern = 'tool:(['"),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_changelog() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"v1\"\nnew-string = \"v2\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(parse("changelog-file = \"CHANGELOG.md\"").is_ok());
+        assert!(
+            parse("changelog-url = \"https://github.com/org/tpl/blob/v2/CHANGELOG.md\"").is_ok()
+        );
+        for invalid in [
+            "changelog-file = \"../CHANGELOG.md\"",
+            "changelog-url = \"https://example.com/CHANGELOG.md\"",
+            "changelog-file = \"CHANGELOG.md\"\n\
+             changelog-url = \"https://github.com/org/tpl/blob/v2/CHANGELOG.md\"",
+        ] {
+            assert!(
+                matches!(parse(invalid), Err(ConfigError::ValidationError { .. })),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn validation_dispatch() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"v1\"\nnew-string = \"v2\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(parse("[dispatch]\nmode = \"instead\"").is_ok());
+        assert!(matches!(
+            parse("[dispatch]\nevent-type = \"\""),
+            Err(ConfigError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            parse("[dispatch]\npayload-template = \"{{#each}}\""),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_old_version_range() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"template\"\nnew-string = \"0.4.0\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(parse("version-source = \"cargo-generate\"\nold-version-range = \"<0.4\"").is_ok());
+        assert!(matches!(
+            parse("version-source = \"cargo-generate\"\nold-version-range = \"nope\""),
+            Err(ConfigError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            parse("old-version-range = \"<0.4\""),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_search_owners() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"0.3.0\"\nnew-string = \"0.4.0\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(parse("search-owners = [\"my-org\", \"some_user\"]").is_ok());
+        assert!(matches!(
+            parse("search-owners = [\"my-org repo:evil/x\"]"),
+            Err(ConfigError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            parse("search-owners = [\"\"]"),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_repo_globs() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"0.3.0\"\nnew-string = \"0.4.0\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+            .validate(Path::new("test"))
+        };
+
+        assert!(
+            parse("include-repos = [\"my-org/*\"]\nexclude-repos = [\"*/experiment-*\"]").is_ok()
+        );
+        assert!(matches!(
+            parse("exclude-repos = [\"my-org/[\"]"),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_rollout_percent() {
+        let parse = |extra: &str| {
+            MigrationMetadata::parse(
+                &format!("old-string = \"0.3.0\"\nnew-string = \"0.4.0\"\n{extra}"),
+                Path::new("test"),
+            )
+            .unwrap()
+        };
+
+        let staged = parse("rollout-percent = [10, 50, 100]");
+        assert_eq!(
+            staged.rollout_percent,
+            Some(RolloutPercent::Stages(vec![10, 50, 100]))
+        );
+        assert!(staged.validate(Path::new("test")).is_ok());
+        assert_eq!(
+            parse("rollout-percent = 25").rollout_percent,
+            Some(RolloutPercent::Fixed(25))
+        );
+        assert!(matches!(
+            parse("rollout-percent = [50, 10]").validate(Path::new("test")),
+            Err(ConfigError::ValidationError { .. })
+        ));
+    }
+
     #[test]
     fn default_target_file_returns_correct_value() {
         assert_eq!(default_target_file(), "template-version.txt");
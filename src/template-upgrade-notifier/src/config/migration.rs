@@ -1,16 +1,30 @@
 //! Complete migration definition and loading.
 
-use crate::config::{ConfigError, MigrationMetadata};
+use crate::changelog;
+use crate::checks::CheckKind;
+use crate::config::metadata::default_target_file;
+use crate::config::{ConfigError, DryRunLevel, MigrationMetadata, NotPlannedPolicy, VersionSource};
+use crate::dispatch::DispatchConfig;
+use crate::ids::MigrationId;
+use crate::issues::DuplicatePolicy;
+use crate::pull_requests::{CommitIdentity, MigrationStrategy, PrMetadata};
+use crate::stages::RolloutPercent;
+use regex::Regex;
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// A complete migration definition loaded from a migrations folder.
 ///
 /// Combines [`MigrationMetadata`] with template contents and a derived ID.
-#[derive(Debug, Clone)]
+/// Serializes with the same kebab-case keys as `metadata.toml`, so a loaded
+/// migration can be written out and read back without its folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Migration {
     /// Unique identifier derived from folder path (e.g., "my-template/v1.0.0-to-v1.0.1").
-    pub id: String,
+    pub id: MigrationId,
 
     /// The version string to search for.
     pub old_string: String,
@@ -21,9 +35,35 @@ pub struct Migration {
     /// URL to migration documentation (optional).
     pub migration_guide_link: Option<String>,
 
+    /// Release notes for `new_string`, read from `changelog-file` when loaded
+    /// or from `changelog_url` at the start of a run.
+    pub changelog: Option<String>,
+
+    /// GitHub URL of the changelog `changelog` is read from, if remote.
+    pub changelog_url: Option<String>,
+
+    /// Heading text of the changelog section, if not `new_string`.
+    pub changelog_section: Option<String>,
+
     /// File name to search for containing the version string.
     pub target_file: String,
 
+    /// Further file names to search for, when `target-file` is a list.
+    pub additional_target_files: Vec<String>,
+
+    /// Glob the matched file's path must match; any path when `None`.
+    pub target_path: Option<String>,
+
+    /// Users or organizations code search is restricted to; all of GitHub
+    /// when empty.
+    pub search_owners: Vec<String>,
+
+    /// Glob patterns of `owner/name` repositories to notify; all when empty.
+    pub include_repos: Vec<String>,
+
+    /// Glob patterns of `owner/name` repositories never to notify.
+    pub exclude_repos: Vec<String>,
+
     /// Contents of issue-template.md.
     pub issue_template: String,
 
@@ -41,6 +81,62 @@ pub struct Migration {
 
     /// Handlebars format for commit titles.
     pub commit_title_format: String,
+
+    /// Team slugs to @-mention in issue bodies.
+    pub mention_teams: Vec<String>,
+
+    /// Team slugs to request as reviewers of auto-fix PRs.
+    pub reviewer_teams: Vec<String>,
+
+    /// Commit status or check run to publish on outdated repositories, if any.
+    pub commit_check: Option<CheckKind>,
+
+    /// Where the current template version is read from.
+    pub version_source: VersionSource,
+
+    /// Semver range of outdated versions, matched instead of `old_string`.
+    pub old_version_range: Option<VersionReq>,
+
+    /// Regex whose `version` group, or else first group, captures the
+    /// current version from the matched line, if set.
+    pub version_pattern: Option<String>,
+
+    /// Migrations from this one to the newest version of the template,
+    /// counting this one; set when loaded with
+    /// [`scan_migrations`](crate::config::scan_migrations).
+    #[serde(skip)]
+    pub versions_behind: usize,
+
+    /// Dependency-bot style metadata for auto-fix PRs, if enabled.
+    pub pr_metadata: Option<PrMetadata>,
+
+    /// Share of discovered repositories to notify, if rolled out gradually.
+    pub rollout_percent: Option<RolloutPercent>,
+
+    /// Author of auto-fix commits, if overridden for this migration.
+    pub commit_identity: Option<CommitIdentity>,
+
+    /// How an already open issue with the same title is handled.
+    pub duplicate_policy: DuplicatePolicy,
+
+    /// README lines included in the auto-fix prompt, if enabled.
+    pub readme_context_lines: Option<usize>,
+
+    /// How auto-fix PRs apply the migration, with paths resolved against the
+    /// migration folder.
+    pub strategy: MigrationStrategy,
+
+    /// Command checking an escalating strategy's work, if any.
+    pub validate_command: Option<Vec<String>>,
+
+    /// How issues closed as "not planned" affect later notifications.
+    pub not_planned: NotPlannedPolicy,
+
+    /// Dry-run level this migration is always previewed at, if overridden.
+    pub dry_run: Option<DryRunLevel>,
+
+    /// `repository_dispatch` event sent to outdated repositories, if enabled.
+    pub dispatch: Option<DispatchConfig>,
 }
 
 impl Migration {
@@ -62,6 +158,11 @@ impl Migration {
     pub fn load(path: &Path, migration_id: &str) -> Result<Self, ConfigError> {
         debug!(path = %path.display(), migration_id, "Loading migration");
 
+        let id = MigrationId::new(migration_id).map_err(|e| ConfigError::ValidationError {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
         // Load and parse metadata.toml
         let metadata = MigrationMetadata::load(path)?;
 
@@ -98,20 +199,124 @@ impl Migration {
             });
         }
 
+        let old_version_range = metadata
+            .old_version_range
+            .as_deref()
+            .map(VersionReq::parse)
+            .transpose()
+            .map_err(|e| ConfigError::ValidationError {
+                path: path.display().to_string(),
+                message: format!("old-version-range is not a valid semver range: {e}"),
+            })?;
+
+        let changelog = match &metadata.changelog_file {
+            Some(file) => {
+                let changelog_path = path.join(file);
+                let markdown =
+                    std::fs::read_to_string(&changelog_path).map_err(|e| ConfigError::IoError {
+                        path: changelog_path.display().to_string(),
+                        source: e,
+                    })?;
+                let excerpt = changelog::excerpt_for(
+                    &markdown,
+                    metadata.changelog_section.as_deref(),
+                    &metadata.new_string,
+                );
+                if excerpt.is_none() {
+                    warn!(
+                        path = %changelog_path.display(),
+                        "No changelog section found for the new version"
+                    );
+                }
+                excerpt
+            }
+            None => None,
+        };
+
+        // Answers-file tools write a well-known file; use it unless overridden.
+        let target_files = match metadata.version_source.default_file() {
+            Some(file) if metadata.target_file.is_default() => vec![file.to_string()],
+            _ => metadata.target_file.as_slice().to_vec(),
+        };
+        let (target_file, additional_target_files) = match target_files.split_first() {
+            Some((first, rest)) => (first.clone(), rest.to_vec()),
+            None => (default_target_file(), Vec::new()),
+        };
+
         Ok(Self {
-            id: migration_id.to_string(),
+            id,
             old_string: metadata.old_string,
             new_string: metadata.new_string,
             migration_guide_link: metadata.migration_guide_link,
-            target_file: metadata.target_file,
+            changelog,
+            changelog_url: metadata.changelog_url,
+            changelog_section: metadata.changelog_section,
+            target_file,
+            additional_target_files,
+            target_path: metadata.target_path,
+            search_owners: metadata.search_owners,
+            include_repos: metadata.include_repos,
+            exclude_repos: metadata.exclude_repos,
             issue_template,
             pr_template,
             issue_title_format: metadata.issue_title_format,
             pr_title_format: metadata.pr_title_format,
             branch_name_format: metadata.branch_name_format,
             commit_title_format: metadata.commit_title_format,
+            mention_teams: metadata.mention_teams,
+            reviewer_teams: metadata.reviewer_teams,
+            commit_check: metadata.commit_check,
+            version_source: metadata.version_source,
+            old_version_range,
+            version_pattern: metadata.version_pattern,
+            versions_behind: 1,
+            pr_metadata: metadata.pr_metadata,
+            rollout_percent: metadata.rollout_percent,
+            commit_identity: metadata.commit_identity,
+            duplicate_policy: metadata.duplicate_policy,
+            readme_context_lines: metadata.readme_context_lines,
+            strategy: metadata.strategy.resolve(path)?,
+            validate_command: metadata.validate_command,
+            not_planned: metadata.not_planned,
+            dry_run: metadata.dry_run,
+            dispatch: metadata.dispatch,
         })
     }
+
+    /// Returns the template this migration belongs to: the first segment of
+    /// its identifier (`my-template` for `my-template/v1-to-v2`).
+    #[must_use]
+    pub fn template(&self) -> &str {
+        self.id.template()
+    }
+
+    /// Returns the current version captured by `version_pattern` from the
+    /// first line of `content` containing `old_string`, or else from the
+    /// whole content.
+    ///
+    /// `None` without a pattern or when it does not match.
+    #[must_use]
+    pub fn extract_version(&self, content: &str) -> Option<String> {
+        // Validated when the migration was loaded.
+        let pattern = Regex::new(self.version_pattern.as_deref()?).ok()?;
+        let haystack = content
+            .lines()
+            .find(|line| line.contains(&self.old_string))
+            .unwrap_or(content);
+        let captures = pattern.captures(haystack)?;
+        captures
+            .name("version")
+            .or_else(|| captures.get(1))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Returns every file name searched for, starting with `target_file`.
+    #[must_use]
+    pub fn target_files(&self) -> Vec<&str> {
+        core::iter::once(self.target_file.as_str())
+            .chain(self.additional_target_files.iter().map(String::as_str))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -159,6 +364,90 @@ target-file = "version.txt"
             Some("https://example.com/guide".to_string())
         );
         assert_eq!(migration.target_file, "version.txt");
+        assert_eq!(migration.target_files(), ["version.txt"]);
+    }
+
+    #[test]
+    fn loads_changelog_section_for_new_version() {
+        let temp = TempDir::new().unwrap();
+        create_test_migration(temp.path());
+        fs::write(
+            temp.path().join("metadata.toml"),
+            "old-string = \"test:1.0.0\"\nnew-string = \"test:1.0.1\"\n\
+             changelog-file = \"CHANGELOG.md\"\n",
+        )
+        .unwrap();
+        fs::write(
+            temp.path().join("CHANGELOG.md"),
+            "# Changelog\n\n## 1.0.1\n\n- Fixed CI\n\n## 1.0.0\n\n- Initial\n",
+        )
+        .unwrap();
+
+        let migration = Migration::load(temp.path(), "test/v1").unwrap();
+
+        assert_eq!(migration.changelog.as_deref(), Some("- Fixed CI"));
+    }
+
+    #[test]
+    fn loads_a_list_of_target_files() {
+        let temp = TempDir::new().unwrap();
+        create_test_migration(temp.path());
+        fs::write(
+            temp.path().join("metadata.toml"),
+            "old-string = \"v1\"\nnew-string = \"v2\"\n\
+             target-file = [\"version.txt\", \".template-version\"]\n",
+        )
+        .unwrap();
+
+        let migration = Migration::load(temp.path(), "test/v1").unwrap();
+
+        assert_eq!(migration.target_file, "version.txt");
+        assert_eq!(
+            migration.target_files(),
+            ["version.txt", ".template-version"]
+        );
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let temp = TempDir::new().unwrap();
+        create_test_migration(temp.path());
+        let migration = Migration::load(temp.path(), "test/v1").unwrap();
+
+        let json = serde_json::to_string(&migration).unwrap();
+        let parsed: Migration = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.id, "test/v1");
+        assert_eq!(parsed.old_string, "test:1.0.0");
+        assert_eq!(parsed.issue_template, migration.issue_template);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn extracts_version_from_matched_line() {
+        let migration = Migration {
+            old_string: "tool:1".to_string(),
+            version_pattern: Some(r"tool:(?P<version>[\d.]+)".to_string()),
+            ..Migration::default()
+        };
+        let content = "base: tool:9.9\nimage: tool:1.4.2 # pinned\n";
+
+        assert_eq!(migration.extract_version(content).as_deref(), Some("1.4.2"));
+        assert_eq!(migration.extract_version("nothing here"), None);
+        let unset = Migration {
+            version_pattern: None,
+            ..migration
+        };
+        assert_eq!(unset.extract_version(content), None);
+    }
+
+    #[test]
+    fn template_is_first_id_segment() {
+        let migration = Migration {
+            id: "my-template/v1-to-v2".parse().unwrap(),
+            ..Migration::default()
+        };
+        assert_eq!(migration.template(), "my-template");
     }
 
     #[test]
@@ -187,6 +476,26 @@ new-string = "test:1.0.1"
         assert_eq!(migration.migration_guide_link, None);
     }
 
+    #[test]
+    fn copier_migration_targets_answers_file() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("metadata.toml"),
+            r#"
+old-string = "v1.0.0"
+new-string = "v1.1.0"
+version-source = "copier"
+"#,
+        )
+        .unwrap();
+        fs::write(temp.path().join("issue-template.md"), "content").unwrap();
+        fs::write(temp.path().join("pr-template.md"), "content").unwrap();
+
+        let migration = Migration::load(temp.path(), "test/v1").unwrap();
+        assert_eq!(migration.version_source, VersionSource::Copier);
+        assert_eq!(migration.target_file, ".copier-answers.yml");
+    }
+
     #[test]
     fn load_migration_with_default_formats() {
         let temp = TempDir::new().unwrap();
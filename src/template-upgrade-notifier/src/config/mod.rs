@@ -3,20 +3,36 @@
 //! This module handles parsing metadata.toml files and loading migrations
 //! from the filesystem.
 
+mod chain;
+mod dry_run;
 mod error;
 mod metadata;
 mod migration;
+mod not_planned;
+mod notifier;
+mod target_file;
+mod version_source;
 
+pub use dry_run::DryRunLevel;
 pub use error::ConfigError;
 pub use metadata::{
     default_branch_name_format, default_commit_title_format, default_issue_title_format,
     default_pr_title_format, MigrationMetadata,
 };
 pub use migration::Migration;
+pub use not_planned::{is_new_major, major_version, NotPlannedPolicy};
+pub use notifier::NotifierConfig;
+pub use target_file::TargetFiles;
+pub use version_source::{parse_semver, VersionSource};
 
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use std::path::Path;
 use tracing::{debug, info, warn};
 
+/// File in the migrations root listing, in gitignore syntax, directories
+/// [`scan_migrations`] skips.
+pub const MIGRATIONS_IGNORE_FILE: &str = ".migrationsignore";
+
 /// Scans a migrations directory and loads all valid migrations.
 ///
 /// The directory structure should be:
@@ -29,6 +45,10 @@ use tracing::{debug, info, warn};
 /// │       └── pr-template.md
 /// ```
 ///
+/// Directories matched by a [`MIGRATIONS_IGNORE_FILE`] in the migrations root
+/// (gitignore syntax, e.g. `archive/` or `*-experimental`) are skipped along
+/// with everything below them.
+///
 /// # Arguments
 ///
 /// * `migrations_path` - Path to the root migrations directory
@@ -40,9 +60,28 @@ use tracing::{debug, info, warn};
 ///
 /// # Errors
 ///
-/// Returns an error if the migrations directory doesn't exist or can't be read.
+/// Returns an error if the migrations directory doesn't exist or can't be
+/// read, or its ignore file is invalid.
 pub fn scan_migrations(migrations_path: &Path) -> Result<Vec<Migration>, ConfigError> {
-    info!(path = %migrations_path.display(), "Scanning migrations directory");
+    scan(migrations_path, false)
+}
+
+/// Scans a migrations directory like [`scan_migrations`], but fails if any
+/// migration fails to load instead of skipping it.
+///
+/// Meant for validating a migrations repository in CI.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::InvalidMigrations`] with every load failure, or
+/// any error [`scan_migrations`] returns.
+pub fn scan_migrations_strict(migrations_path: &Path) -> Result<Vec<Migration>, ConfigError> {
+    scan(migrations_path, true)
+}
+
+/// Scans `migrations_path`, failing on any migration load error if `strict`.
+fn scan(migrations_path: &Path, strict: bool) -> Result<Vec<Migration>, ConfigError> {
+    info!(path = %migrations_path.display(), strict, "Scanning migrations directory");
 
     if !migrations_path.exists() {
         return Err(ConfigError::MissingFile {
@@ -51,19 +90,53 @@ pub fn scan_migrations(migrations_path: &Path) -> Result<Vec<Migration>, ConfigE
     }
 
     let mut migrations = Vec::new();
+    let mut failures = Vec::new();
+    let ignore = load_ignore_file(migrations_path)?;
 
     // Walk the directory tree looking for metadata.toml files
-    scan_directory_recursive(migrations_path, migrations_path, &mut migrations)?;
+    scan_directory_recursive(
+        migrations_path,
+        migrations_path,
+        &ignore,
+        &mut migrations,
+        &mut failures,
+    )?;
+
+    if strict && !failures.is_empty() {
+        return Err(ConfigError::InvalidMigrations { failures });
+    }
 
+    chain::link_chains(&mut migrations);
     info!(count = migrations.len(), "Loaded migrations");
     Ok(migrations)
 }
 
-/// Recursively scans a directory for migration folders.
+/// Loads the migrations root's [`MIGRATIONS_IGNORE_FILE`]; ignores nothing
+/// if there is none.
+fn load_ignore_file(migrations_path: &Path) -> Result<Gitignore, ConfigError> {
+    let path = migrations_path.join(MIGRATIONS_IGNORE_FILE);
+    if !path.is_file() {
+        return Ok(Gitignore::empty());
+    }
+    let invalid = |e: ignore::Error| ConfigError::ValidationError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    };
+    let mut builder = GitignoreBuilder::new(migrations_path);
+    if let Some(e) = builder.add(&path) {
+        return Err(invalid(e));
+    }
+    builder.build().map_err(invalid)
+}
+
+/// Recursively scans a directory for migration folders, skipping ignored
+/// ones and collecting the errors of those that fail to load in `failures`.
 fn scan_directory_recursive(
     base_path: &Path,
     current_path: &Path,
+    ignore: &Gitignore,
     migrations: &mut Vec<Migration>,
+    failures: &mut Vec<ConfigError>,
 ) -> Result<(), ConfigError> {
     let entries = std::fs::read_dir(current_path).map_err(|e| ConfigError::IoError {
         path: current_path.display().to_string(),
@@ -79,6 +152,11 @@ fn scan_directory_recursive(
         let path = entry.path();
 
         if path.is_dir() {
+            if ignore.matched(&path, true).is_ignore() {
+                debug!(path = %path.display(), "Skipping ignored directory");
+                continue;
+            }
+
             // Check if this directory contains metadata.toml
             let metadata_path = path.join("metadata.toml");
             if metadata_path.exists() {
@@ -97,11 +175,12 @@ fn scan_directory_recursive(
                     }
                     Err(e) => {
                         warn!(path = %path.display(), error = %e, "Failed to load migration");
+                        failures.push(e);
                     }
                 }
             } else {
                 // Continue scanning subdirectories
-                scan_directory_recursive(base_path, &path, migrations)?;
+                scan_directory_recursive(base_path, &path, ignore, migrations, failures)?;
             }
         }
     }
@@ -188,4 +267,28 @@ target-file = "version.txt"
         let migrations = scan_migrations(temp.path()).unwrap();
         assert_eq!(migrations.len(), 2);
     }
+
+    #[test]
+    fn scan_migrations_skips_ignored_directories() {
+        let temp = TempDir::new().unwrap();
+        for dir in [
+            "template-a/v1-to-v2",
+            "archive/template-a/v0-to-v1",
+            "template-b/v2-to-v3-experimental",
+        ] {
+            let path = temp.path().join(dir);
+            fs::create_dir_all(&path).unwrap();
+            create_test_migration(&path);
+        }
+        fs::write(
+            temp.path().join(MIGRATIONS_IGNORE_FILE),
+            "# retired and work-in-progress migrations\narchive/\n*-experimental\n",
+        )
+        .unwrap();
+
+        let migrations = scan_migrations(temp.path()).unwrap();
+
+        assert_eq!(migrations.len(), 1);
+        assert_eq!(migrations[0].id, "template-a/v1-to-v2");
+    }
 }
@@ -0,0 +1,67 @@
+//! How an issue closed as "not planned" affects later migrations.
+
+use serde::{Deserialize, Serialize};
+
+/// What a consumer closing our issue as "not planned" means for later
+/// notifications (`not-planned` in `metadata.toml`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotPlannedPolicy {
+    /// Skip the repository for every migration of the same template until
+    /// one targets a new major version.
+    #[default]
+    Template,
+    /// Skip the repository for this migration only, even when issues are
+    /// forced.
+    Migration,
+    /// Treat the closed issue like any other notice.
+    Ignore,
+}
+
+/// Returns the major version in a migration's `new_string`, such as `2` in
+/// `my-template:2.1.0` or `v2`.
+///
+/// The last version-like token wins; `None` if there is none.
+#[must_use]
+pub fn major_version(version: &str) -> Option<u64> {
+    version
+        .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')))
+        .filter_map(|token| {
+            let token = token.strip_prefix('v').unwrap_or(token);
+            let major = token.split(['.', '-', '+']).next()?;
+            major.parse().ok()
+        })
+        .next_back()
+}
+
+/// Returns true if `new_string` targets a later major version than the
+/// `rejected` one.
+///
+/// Versions without a recognizable major never count as newer.
+#[must_use]
+pub fn is_new_major(rejected: &str, new_string: &str) -> bool {
+    match (major_version(rejected), major_version(new_string)) {
+        (Some(rejected), Some(new)) => new > rejected,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_major_versions() {
+        assert_eq!(major_version("my-template:2.1.0"), Some(2));
+        assert_eq!(major_version("v3"), Some(3));
+        assert_eq!(major_version("template-v1:4.0"), Some(4));
+        assert_eq!(major_version("latest"), None);
+    }
+
+    #[test]
+    fn only_later_majors_are_new() {
+        assert!(is_new_major("tpl:1.4.0", "tpl:2.0.0"));
+        assert!(!is_new_major("tpl:1.4.0", "tpl:1.5.0"));
+        assert!(!is_new_major("tpl:1.4.0", "tpl:next"));
+    }
+}
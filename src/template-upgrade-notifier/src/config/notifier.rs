@@ -0,0 +1,302 @@
+//! Run-wide notifier settings loaded from `notifier.toml`.
+
+use crate::azure_devops::AzureDevOpsConfig;
+use crate::campaign::CampaignConfig;
+use crate::config::ConfigError;
+use crate::discovery::{ExcludeConfig, FalsePositiveConfig, OptOutConfig};
+use crate::email::EmailConfig;
+use crate::gitlab::GitLabConfig;
+use crate::hooks::HooksConfig;
+use crate::project_board::ProjectBoardConfig;
+use crate::pull_requests::{CheckoutConfig, CommitIdentity};
+use crate::rollout::RolloutIssueConfig;
+use crate::schedule::ScheduleConfig;
+use crate::summary::{AbortThreshold, ExitPolicy};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Settings that apply to a whole run rather than a single migration.
+///
+/// Loaded from `notifier.toml`, which lives next to the migrations directory
+/// by default. Every section is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NotifierConfig {
+    /// SMTP digest settings; no email is sent when absent.
+    pub email: Option<EmailConfig>,
+
+    /// Watch-mode schedule; required when running with `--watch`.
+    pub schedule: Option<ScheduleConfig>,
+
+    /// Which outcomes fail the run.
+    #[serde(default)]
+    pub exit_policy: ExitPolicy,
+
+    /// Failure rate at which a run stops early; runs never stop when absent.
+    pub abort_threshold: Option<AbortThreshold>,
+
+    /// External commands run at lifecycle points.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Named sets of migrations that can be run together with `--campaign`.
+    #[serde(default)]
+    pub campaigns: BTreeMap<String, CampaignConfig>,
+
+    /// Rollout issue kept in the template's repository; not kept when absent.
+    pub rollout_issue: Option<RolloutIssueConfig>,
+
+    /// GitHub Projects board mirroring processed repositories; not kept when absent.
+    pub project_board: Option<ProjectBoardConfig>,
+
+    /// Minimum days between two notifications of the same repository, by any
+    /// migration; no cooldown when absent.
+    pub min_days_between_notifications: Option<u32>,
+
+    /// Whether rendered issue and PR bodies are linted for unclosed code
+    /// fences and broken links before they are posted.
+    #[serde(default)]
+    pub lint_markdown: bool,
+
+    /// Author of auto-fix commits; migrations can override it.
+    #[serde(default)]
+    pub commit_identity: CommitIdentity,
+
+    /// Policies for auto-fix PRs in repositories using Git LFS or submodules.
+    #[serde(default)]
+    pub checkout: CheckoutConfig,
+
+    /// Owners (users or organizations) whose repositories live runs may
+    /// touch; required for live runs, and anything else is refused.
+    pub allowed_owners: Option<Vec<String>>,
+
+    /// Bot-owned, mirror and owner-pattern exclusions applied after discovery.
+    #[serde(default)]
+    pub exclude: ExcludeConfig,
+
+    /// Heuristics dropping search matches unlikely to be real template instances.
+    #[serde(default)]
+    pub false_positives: FalsePositiveConfig,
+
+    /// Topics and issue labels by which consumers opt out of notices.
+    #[serde(default)]
+    pub opt_out: OptOutConfig,
+
+    /// GitLab groups and projects notified alongside GitHub; requires the
+    /// `gitlab` feature.
+    pub gitlab: Option<GitLabConfig>,
+
+    /// Azure DevOps organization notified alongside GitHub; requires the
+    /// `azure-devops` feature.
+    pub azure_devops: Option<AzureDevOpsConfig>,
+}
+
+impl NotifierConfig {
+    /// Parses notifier settings from TOML content.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::TomlError`] if parsing fails, or
+    /// [`ConfigError::ValidationError`] if a campaign sets a concurrency of 0.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(content).map_err(|e| ConfigError::TomlError {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        config.validate(path)?;
+        Ok(config)
+    }
+
+    /// Rejects settings that parse but would stall a run.
+    fn validate(&self, path: &Path) -> Result<(), ConfigError> {
+        for (name, campaign) in &self.campaigns {
+            if campaign.concurrency == Some(0) {
+                return Err(ConfigError::ValidationError {
+                    path: path.display().to_string(),
+                    message: format!("campaigns.{name}.concurrency must be at least 1"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads notifier settings from a file, returning defaults if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::IoError`] if the file exists but cannot be read,
+    /// or any error [`NotifierConfig::parse`] returns.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| ConfigError::IoError {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Self::parse(&content, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn load_returns_default_when_missing() {
+        let temp = TempDir::new().unwrap();
+        let config = NotifierConfig::load(&temp.path().join("notifier.toml")).unwrap();
+        assert!(config.email.is_none());
+    }
+
+    #[test]
+    fn parses_email_section() {
+        let config = NotifierConfig::parse(
+            r#"
+[email]
+smtp-host = "smtp.example.com"
+from = "bot@example.com"
+to = ["a@example.com", "b@example.com"]
+"#,
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+
+        let email = config.email.unwrap();
+        assert_eq!(email.smtp_host, "smtp.example.com");
+        assert_eq!(email.to.len(), 2);
+    }
+
+    #[test]
+    fn parses_allowed_owners() {
+        let config = NotifierConfig::parse(
+            "allowed-owners = [\"my-org\", \"me\"]\n",
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.allowed_owners,
+            Some(vec!["my-org".to_string(), "me".to_string()])
+        );
+    }
+
+    #[test]
+    fn parses_notification_cooldown() {
+        let config = NotifierConfig::parse(
+            "min-days-between-notifications = 30\n",
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+
+        assert_eq!(config.min_days_between_notifications, Some(30));
+    }
+
+    #[test]
+    fn parses_schedule_section() {
+        let config = NotifierConfig::parse(
+            r#"
+[schedule]
+cron = "0 0 6 * * Mon"
+run-on-start = false
+"#,
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+
+        let schedule = config.schedule.unwrap();
+        assert_eq!(schedule.cron.as_deref(), Some("0 0 6 * * Mon"));
+        assert!(!schedule.run_on_start);
+    }
+
+    #[test]
+    fn parses_campaigns() {
+        let config = NotifierConfig::parse(
+            r#"
+[campaigns.v2-release]
+migrations = ["v2-*"]
+tracking-repository = "org/template"
+"#,
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+
+        let campaign = &config.campaigns["v2-release"];
+        assert_eq!(campaign.migrations, ["v2-*"]);
+        assert_eq!(
+            campaign.tracking_repository.as_deref(),
+            Some("org/template")
+        );
+    }
+
+    #[test]
+    fn rejects_zero_campaign_concurrency() {
+        let result = NotifierConfig::parse(
+            "[campaigns.v2-release]\nmigrations = [\"v2-*\"]\nconcurrency = 0\n",
+            Path::new("notifier.toml"),
+        );
+
+        assert!(matches!(result, Err(ConfigError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn parses_commit_identity() {
+        let config = NotifierConfig::parse(
+            "[commit-identity]\ngithub-actions-bot = true\n",
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+        assert!(config.commit_identity.github_actions_bot);
+        assert_eq!(
+            NotifierConfig::default().commit_identity,
+            CommitIdentity::default()
+        );
+    }
+
+    #[test]
+    fn parses_markdown_lint() {
+        let config =
+            NotifierConfig::parse("lint-markdown = true", Path::new("notifier.toml")).unwrap();
+        assert!(config.lint_markdown);
+        assert!(!NotifierConfig::default().lint_markdown);
+    }
+
+    #[test]
+    fn parses_abort_threshold() {
+        let config = NotifierConfig::parse(
+            "[abort-threshold]\nfailure-percent = 20\nmin-processed = 5\n",
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+
+        let threshold = config.abort_threshold.unwrap();
+        assert_eq!(threshold.failure_percent, 20);
+        assert_eq!(threshold.min_processed, 5);
+    }
+
+    #[test]
+    fn parses_other_forges() {
+        let config = NotifierConfig::parse(
+            r#"
+[gitlab]
+groups = ["my-org/services"]
+
+[azure-devops]
+organization = "contoso"
+"#,
+            Path::new("notifier.toml"),
+        )
+        .unwrap();
+
+        assert_eq!(config.gitlab.unwrap().groups, ["my-org/services"]);
+        assert_eq!(config.azure_devops.unwrap().organization, "contoso");
+    }
+
+    #[test]
+    fn reports_invalid_toml() {
+        let result = NotifierConfig::parse("email = [", Path::new("notifier.toml"));
+        assert!(matches!(result, Err(ConfigError::TomlError { .. })));
+    }
+}
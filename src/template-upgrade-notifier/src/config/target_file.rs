@@ -0,0 +1,98 @@
+//! The `target-file` setting.
+
+use super::metadata::default_target_file;
+use serde::{Deserialize, Serialize};
+
+/// File names code search looks for `old_string` in.
+///
+/// Written in `metadata.toml` either as one name
+/// (`target-file = "template-version.txt"`) or as a list
+/// (`target-file = ["template-version.txt", ".template-version"]`), whose
+/// results are combined by repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TargetFiles {
+    /// A single file name.
+    One(String),
+    /// Several file names; the first is the primary one.
+    Many(Vec<String>),
+}
+
+impl Default for TargetFiles {
+    fn default() -> Self {
+        Self::One(default_target_file())
+    }
+}
+
+impl TargetFiles {
+    /// Returns the file names, primary first.
+    #[must_use]
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            Self::One(file) => core::slice::from_ref(file),
+            Self::Many(files) => files,
+        }
+    }
+
+    /// Returns true if this is the default `template-version.txt`.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        self.as_slice() == [default_target_file()]
+    }
+
+    /// Returns a description of what is wrong with the setting, if anything.
+    #[must_use]
+    pub fn problem(&self) -> Option<&'static str> {
+        let files = self.as_slice();
+        if files.is_empty() {
+            return Some("must not be empty");
+        }
+        if files.iter().any(|file| file.trim().is_empty()) {
+            return Some("must not contain empty file names");
+        }
+        if files
+            .iter()
+            .any(|file| file.contains('/') || file.contains('\\'))
+        {
+            return Some("must not contain path separators");
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        target_file: TargetFiles,
+    }
+
+    fn parse(toml: &str) -> TargetFiles {
+        toml::from_str::<Wrapper>(toml).unwrap().target_file
+    }
+
+    #[test]
+    fn parses_one_name_or_a_list() {
+        assert_eq!(parse("target_file = \"a.txt\"").as_slice(), ["a.txt"]);
+        assert_eq!(
+            parse("target_file = [\"a.txt\", \"b.txt\"]").as_slice(),
+            ["a.txt", "b.txt"]
+        );
+    }
+
+    #[test]
+    fn reports_problems() {
+        assert_eq!(
+            TargetFiles::Many(Vec::new()).problem(),
+            Some("must not be empty")
+        );
+        assert_eq!(
+            TargetFiles::One("dir/a.txt".to_string()).problem(),
+            Some("must not contain path separators")
+        );
+        assert_eq!(TargetFiles::default().problem(), None);
+        assert!(TargetFiles::default().is_default());
+    }
+}
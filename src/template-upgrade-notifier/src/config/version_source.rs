@@ -0,0 +1,247 @@
+//! Where a repository's current template version is read from.
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+/// How a migration decides which template version a repository is on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionSource {
+    /// The target file contains `old_string` anywhere.
+    #[default]
+    Text,
+    /// The `_commit` recorded in a copier answers file (`.copier-answers.yml`).
+    Copier,
+    /// The `checkout` (or else `commit`) recorded in a cruft state file (`.cruft.json`).
+    Cruft,
+    /// The version of a `cargo-generate` project: `package.metadata.template.version`
+    /// (or `workspace.metadata.template.version`) in `Cargo.toml`, or the whole
+    /// content of a one-line file such as `.template-version`.
+    CargoGenerate,
+}
+
+impl VersionSource {
+    /// Returns the file the template tool writes, used when a migration sets
+    /// no `target-file` of its own.
+    #[must_use]
+    pub fn default_file(self) -> Option<&'static str> {
+        match self {
+            Self::Text => None,
+            Self::Copier => Some(".copier-answers.yml"),
+            Self::Cruft => Some(".cruft.json"),
+            Self::CargoGenerate => Some("Cargo.toml"),
+        }
+    }
+
+    /// Reads the template version recorded in `content`.
+    ///
+    /// Returns `None` for [`VersionSource::Text`], which has no structure to read.
+    #[must_use]
+    pub fn read_version(self, content: &str) -> Option<String> {
+        match self {
+            Self::Text => None,
+            Self::Copier => read_copier_commit(content),
+            Self::Cruft => read_cruft_version(content),
+            Self::CargoGenerate => read_cargo_generate_version(content),
+        }
+    }
+
+    /// Returns true if `content` records a version within `range`.
+    ///
+    /// Always false for [`VersionSource::Text`] and for versions that are not
+    /// semver (see [`parse_semver`]).
+    #[must_use]
+    pub fn records_in_range(self, content: &str, range: &VersionReq) -> bool {
+        self.read_version(content)
+            .and_then(|version| parse_semver(&version))
+            .is_some_and(|version| range.matches(&version))
+    }
+
+    /// Returns true if `content` records `version`.
+    ///
+    /// For answers files, a `git describe` version past the tag
+    /// (`v1.0.0-3-g1a2b3c4`) also counts as `v1.0.0`.
+    #[must_use]
+    pub fn records(self, content: &str, version: &str) -> bool {
+        match self {
+            Self::Text => content.contains(version),
+            Self::Copier | Self::Cruft | Self::CargoGenerate => self
+                .read_version(content)
+                .is_some_and(|found| is_version_of(&found, version)),
+        }
+    }
+}
+
+/// Extracts the semver of a recorded template version.
+///
+/// Accepts a leading `v` and drops a `git describe` suffix past the tag, so
+/// `v1.2.0-3-g1a2b3c4` yields `1.2.0`.
+#[must_use]
+pub fn parse_semver(version: &str) -> Option<Version> {
+    let tag = version
+        .rmatch_indices('-')
+        .filter_map(|(index, _)| {
+            let (tag, rest) = version.split_at(index);
+            // `rest` is `-<commits>-g<sha>` for a describe suffix.
+            is_describe_suffix(&rest[1..]).then_some(tag)
+        })
+        .next_back()
+        .unwrap_or(version);
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+    Version::parse(tag.trim()).ok()
+}
+
+/// Returns true if `found` is `version` or a `git describe` output based on it.
+fn is_version_of(found: &str, version: &str) -> bool {
+    found == version
+        || found
+            .strip_prefix(version)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(is_describe_suffix)
+}
+
+/// Matches the `<commits>-g<sha>` part of `git describe` output.
+fn is_describe_suffix(rest: &str) -> bool {
+    let Some((count, sha)) = rest.split_once("-g") else {
+        return false;
+    };
+    !count.is_empty()
+        && count.bytes().all(|b| b.is_ascii_digit())
+        && !sha.is_empty()
+        && sha.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Reads the top-level `_commit` key of a copier answers file.
+///
+/// Copier writes a flat YAML mapping, so a line-based read is enough.
+fn read_copier_commit(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix("_commit:")?;
+        let value = value.split(" #").next().unwrap_or_default().trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Reads the template version from a `Cargo.toml` metadata table, or takes
+/// a one-line file as the version itself.
+fn read_cargo_generate_version(content: &str) -> Option<String> {
+    if let Ok(manifest) = toml::from_str::<toml::Table>(content) {
+        return ["package", "workspace"].into_iter().find_map(|section| {
+            manifest
+                .get(section)?
+                .get("metadata")?
+                .get("template")?
+                .get("version")?
+                .as_str()
+                .map(str::to_string)
+        });
+    }
+    let version = content.trim();
+    (!version.is_empty() && !version.contains(char::is_whitespace)).then(|| version.to_string())
+}
+
+/// Reads `checkout`, falling back to `commit`, from a cruft state file.
+fn read_cruft_version(content: &str) -> Option<String> {
+    let state: serde_json::Value = serde_json::from_str(content).ok()?;
+    ["checkout", "commit"]
+        .into_iter()
+        .find_map(|key| state[key].as_str().filter(|v| !v.is_empty()))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_copier_commit() {
+        let answers = "# Changes here will be overwritten by Copier\n\
+                       _commit: v1.2.0\n\
+                       _src_path: gh:org/template\n\
+                       project_name: demo\n";
+        assert_eq!(
+            VersionSource::Copier.read_version(answers).as_deref(),
+            Some("v1.2.0")
+        );
+        assert_eq!(
+            VersionSource::Copier
+                .read_version("_commit: 'v1.2.0'  # pinned\n")
+                .as_deref(),
+            Some("v1.2.0")
+        );
+        assert_eq!(
+            VersionSource::Copier.read_version("project_name: demo\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_cruft_checkout_before_commit() {
+        let state = r#"{"template": "gh:org/t", "commit": "abc123", "checkout": "v2.0.0"}"#;
+        assert_eq!(
+            VersionSource::Cruft.read_version(state).as_deref(),
+            Some("v2.0.0")
+        );
+
+        let state = r#"{"template": "gh:org/t", "commit": "abc123", "checkout": null}"#;
+        assert_eq!(
+            VersionSource::Cruft.read_version(state).as_deref(),
+            Some("abc123")
+        );
+    }
+
+    #[test]
+    fn matches_exact_and_describe_versions() {
+        let copier = VersionSource::Copier;
+        assert!(copier.records("_commit: v1.0\n", "v1.0"));
+        assert!(copier.records("_commit: v1.0-4-g1a2b3c4\n", "v1.0"));
+        assert!(!copier.records("_commit: v1.0.1\n", "v1.0"));
+        assert!(!copier.records("other: v1.0\n", "v1.0"));
+    }
+
+    #[test]
+    fn reads_cargo_generate_versions() {
+        let manifest = "[package]\nname = \"demo\"\n\n\
+                        [package.metadata.template]\nversion = \"0.3.1\"\n";
+        let source = VersionSource::CargoGenerate;
+
+        assert_eq!(source.read_version(manifest).as_deref(), Some("0.3.1"));
+        assert_eq!(source.read_version("v0.3.1\n").as_deref(), Some("v0.3.1"));
+        assert_eq!(source.read_version("[package]\nname = \"demo\"\n"), None);
+    }
+
+    #[test]
+    fn parses_semver_of_tags() {
+        assert_eq!(parse_semver("v1.2.0"), Some(Version::new(1, 2, 0)));
+        assert_eq!(
+            parse_semver("1.2.0-3-g1a2b3c4"),
+            Some(Version::new(1, 2, 0))
+        );
+        assert_eq!(
+            parse_semver("1.2.0-rc.1"),
+            Some(Version::parse("1.2.0-rc.1").unwrap())
+        );
+        assert_eq!(parse_semver("main"), None);
+    }
+
+    #[test]
+    fn matches_version_ranges() {
+        let range = VersionReq::parse(">=0.2, <0.4").unwrap();
+        let source = VersionSource::CargoGenerate;
+
+        assert!(source.records_in_range("v0.3.1", &range));
+        assert!(!source.records_in_range("v0.4.0", &range));
+        assert!(!VersionSource::Text.records_in_range("0.3.1", &range));
+    }
+
+    #[test]
+    fn text_matches_substring() {
+        assert!(VersionSource::Text.records("image: tool:1.0", "tool:1.0"));
+        assert_eq!(VersionSource::Text.read_version("tool:1.0"), None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Disk-backed cache of code search results.
+//!
+//! GitHub's code search allows only a handful of requests a minute, so a dry
+//! run followed by a live run would otherwise pay for every search twice.
+//! Each query's results are stored under its migration with the time they
+//! were fetched, and reused until they are older than the time to live.
+//!
+//! Like the [ETag cache](crate::http_cache), the cache is process-wide and
+//! enabled with [`load_search_cache`]. [`discover_repositories`],
+//! [`discover_upgraded_repositories`] and [`discover_in_repository`] use it.
+//!
+//! [`discover_repositories`]: super::discover_repositories
+//! [`discover_upgraded_repositories`]: super::discover_upgraded_repositories
+//! [`discover_in_repository`]: super::discover_in_repository
+
+use super::search::CodeSearchResult;
+use super::SearchCacheError;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The process-wide cache; `None` while caching is disabled.
+static CACHE: Mutex<Option<SearchCache>> = Mutex::new(None);
+
+/// Results of one search and when they were fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedSearch {
+    /// Unix time in seconds the search ran at.
+    fetched_at: u64,
+    results: Vec<CodeSearchResult>,
+}
+
+/// The searches saved on disk, keyed by migration id and query.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SearchCacheFile {
+    entries: BTreeMap<String, CachedSearch>,
+}
+
+/// The loaded cache and how its entries are used.
+#[derive(Debug, Default)]
+struct SearchCache {
+    file: SearchCacheFile,
+    /// Age after which an entry is searched again.
+    ttl: Duration,
+    /// Whether every search runs again this run, replacing cached entries.
+    refresh: bool,
+}
+
+impl SearchCache {
+    /// Returns the cached results of `key` if they are younger than the TTL at `now`.
+    fn get(&self, key: &str, now: u64) -> Option<Vec<CodeSearchResult>> {
+        if self.refresh {
+            return None;
+        }
+        let entry = self.file.entries.get(key)?;
+        let fresh = now.saturating_sub(entry.fetched_at) < self.ttl.as_secs();
+        fresh.then(|| entry.results.clone())
+    }
+
+    /// Stores fresh results for `key`, fetched at `now`.
+    fn insert(&mut self, key: String, results: &[CodeSearchResult], now: u64) {
+        let entry = CachedSearch {
+            fetched_at: now,
+            results: results.to_vec(),
+        };
+        self.file.entries.insert(key, entry);
+    }
+
+    /// Drops the entries older than the TTL at `now`.
+    fn prune(&mut self, now: u64) {
+        let ttl = self.ttl.as_secs();
+        self.file
+            .entries
+            .retain(|_, entry| now.saturating_sub(entry.fetched_at) < ttl);
+    }
+}
+
+/// Enables the cache, loading the searches saved by an earlier run from `path`.
+///
+/// Entries older than `ttl` are searched again. With `refresh`, every search
+/// runs again and replaces its entry. A missing file starts an empty cache.
+/// Returns the number of cached searches.
+///
+/// # Errors
+///
+/// Returns [`SearchCacheError`] if the file exists but cannot be read or parsed.
+pub fn load_search_cache(
+    path: &Path,
+    ttl: Duration,
+    refresh: bool,
+) -> Result<usize, SearchCacheError> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => Some(json),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(source) => {
+            return Err(SearchCacheError::Io {
+                path: path.display().to_string(),
+                source,
+            })
+        }
+    };
+    let file: SearchCacheFile = match json {
+        Some(json) => serde_json::from_str(&json).map_err(|source| SearchCacheError::Json {
+            path: path.display().to_string(),
+            source,
+        })?,
+        None => SearchCacheFile::default(),
+    };
+    let len = file.entries.len();
+    *cache() = Some(SearchCache { file, ttl, refresh });
+    Ok(len)
+}
+
+/// Saves the cached searches still within their TTL to `path`, creating its
+/// directory if needed.
+///
+/// Does nothing while caching is disabled.
+///
+/// # Errors
+///
+/// Returns [`SearchCacheError::Io`] if the file cannot be written.
+pub fn save_search_cache(path: &Path) -> Result<(), SearchCacheError> {
+    let json = {
+        let mut guard = cache();
+        let Some(store) = guard.as_mut() else {
+            return Ok(());
+        };
+        store.prune(unix_now());
+        serde_json::to_string(&store.file).map_err(|source| SearchCacheError::Json {
+            path: path.display().to_string(),
+            source,
+        })?
+    };
+    let io_error = |source| SearchCacheError::Io {
+        path: path.display().to_string(),
+        source,
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    std::fs::write(path, json).map_err(io_error)
+}
+
+/// Enables the cache with no cached searches.
+pub fn reset_search_cache(ttl: Duration) {
+    *cache() = Some(SearchCache {
+        ttl,
+        ..SearchCache::default()
+    });
+}
+
+/// Disables the cache and drops every cached search.
+pub fn disable_search_cache() {
+    *cache() = None;
+}
+
+/// Returns the cached results of `query` for `migration_id`, if the cache is
+/// enabled and holds results younger than its TTL.
+pub(super) fn cached_results(migration_id: &str, query: &str) -> Option<Vec<CodeSearchResult>> {
+    cache()
+        .as_ref()?
+        .get(&cache_key(migration_id, query), unix_now())
+}
+
+/// Stores the results of `query` for `migration_id`, if the cache is enabled.
+pub(super) fn store_results(migration_id: &str, query: &str, results: &[CodeSearchResult]) {
+    if let Some(store) = cache().as_mut() {
+        store.insert(cache_key(migration_id, query), results, unix_now());
+    }
+}
+
+/// Returns the key of `query` for `migration_id`.
+fn cache_key(migration_id: &str, query: &str) -> String {
+    format!("{migration_id}\n{query}")
+}
+
+/// Returns the current Unix time in seconds.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Locks the cache, recovering it if a previous holder panicked.
+fn cache() -> MutexGuard<'static, Option<SearchCache>> {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn result(full_name: &str) -> CodeSearchResult {
+        let (owner, name) = full_name.split_once('/').unwrap();
+        CodeSearchResult {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            full_name: full_name.parse().unwrap(),
+            file_path: "template-version.txt".to_string(),
+            file_url: format!("https://github.com/{full_name}/template-version.txt")
+                .parse()
+                .unwrap(),
+            owner_is_bot: false,
+            archived: false,
+            disabled: false,
+        }
+    }
+
+    fn store(ttl_secs: u64, refresh: bool) -> SearchCache {
+        SearchCache {
+            ttl: Duration::from_secs(ttl_secs),
+            refresh,
+            ..SearchCache::default()
+        }
+    }
+
+    #[test]
+    fn expires_entries_after_ttl() {
+        let mut store = store(60, false);
+        store.insert("m\nq".to_string(), &[result("user/repo")], 1_000);
+
+        assert_eq!(store.get("m\nq", 1_059).map(|r| r.len()), Some(1));
+        assert!(store.get("m\nq", 1_060).is_none());
+        assert!(store.get("other\nq", 1_000).is_none());
+
+        store.prune(1_060);
+        assert!(store.file.entries.is_empty());
+    }
+
+    #[test]
+    fn refresh_ignores_cached_entries() {
+        let mut store = store(60, true);
+        store.insert("m\nq".to_string(), &[result("user/repo")], 1_000);
+
+        assert!(store.get("m\nq", 1_000).is_none());
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("cache").join("search-cache.json");
+
+        assert_eq!(
+            load_search_cache(&path, Duration::from_secs(3600), false).unwrap(),
+            0
+        );
+        store_results("tpl/v1-to-v2", "\"tpl:1\" in:file", &[result("user/repo")]);
+        save_search_cache(&path).unwrap();
+
+        assert_eq!(
+            load_search_cache(&path, Duration::from_secs(3600), false).unwrap(),
+            1
+        );
+        let cached = cached_results("tpl/v1-to-v2", "\"tpl:1\" in:file").unwrap();
+        assert_eq!(cached[0].full_name.to_string(), "user/repo");
+        disable_search_cache();
+    }
+}
@@ -1,5 +1,6 @@
 //! Repository discovery error types.
 
+use crate::error_kind::ErrorKind;
 use thiserror::Error;
 
 /// Errors that can occur during repository discovery.
@@ -12,4 +13,85 @@ pub enum DiscoveryError {
     /// Rate limit exceeded.
     #[error("Rate limit exceeded, reset at {reset_at}")]
     RateLimitExceeded { reset_at: u64 },
+
+    /// The run was cancelled during discovery.
+    #[error("Discovery cancelled")]
+    Cancelled,
+}
+
+impl DiscoveryError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::GitHubError(e) => ErrorKind::of_github(e),
+            Self::RateLimitExceeded { .. } => ErrorKind::RateLimit,
+            Self::Cancelled => ErrorKind::Cancelled,
+        }
+    }
+
+    /// Returns true if retrying the operation later may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_is_retryable() {
+        assert!(DiscoveryError::RateLimitExceeded { reset_at: 0 }.is_retryable());
+        assert!(!DiscoveryError::Cancelled.is_retryable());
+    }
+}
+
+/// Errors that can occur while loading or saving the code search cache.
+#[derive(Debug, Error)]
+pub enum SearchCacheError {
+    /// The cache file could not be read or written.
+    #[error("Failed to access search cache '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The cache file is not valid JSON.
+    #[error("Invalid search cache '{path}': {source}")]
+    Json {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Errors that can occur while loading a static repository list.
+#[derive(Debug, Error)]
+pub enum RepoListError {
+    /// The list could not be read.
+    #[error("Failed to read repository list '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The TOML list is invalid.
+    #[error("Invalid repository list '{path}': {source}")]
+    Toml {
+        path: String,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    /// A line of a text list is not `owner/name [branch]`.
+    #[error("Invalid repository list '{path}' line {line}: {message}")]
+    InvalidEntry {
+        path: String,
+        line: usize,
+        message: String,
+    },
 }
@@ -0,0 +1,44 @@
+//! Repositories left out of discovery because nobody would read an issue there.
+
+use serde::{Deserialize, Serialize};
+
+/// `[exclude]` settings in `notifier.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ExcludeConfig {
+    /// Leave out repositories owned by bot accounts; on by default.
+    pub bots: bool,
+    /// Leave out mirrors of repositories hosted elsewhere; on by default.
+    pub mirrors: bool,
+    /// Skip archived and disabled repositories, where creating issues and
+    /// PRs always fails; on by default. Skips are recorded in the run summary.
+    pub archived: bool,
+    /// Glob patterns of further owners to leave out, e.g. `"*-bot"`.
+    pub owners: Vec<String>,
+}
+
+impl Default for ExcludeConfig {
+    fn default() -> Self {
+        Self {
+            bots: true,
+            mirrors: true,
+            archived: true,
+            owners: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_exclude_bots_and_mirrors() {
+        let config: ExcludeConfig = toml::from_str("owners = [\"*-bot\"]").unwrap();
+
+        assert!(config.bots);
+        assert!(config.mirrors);
+        assert!(config.archived);
+        assert_eq!(config.owners, ["*-bot"]);
+    }
+}
@@ -0,0 +1,188 @@
+//! Heuristics for search matches that are unlikely to be real template
+//! instances.
+
+use super::DiscoveredRepository;
+use serde::{Deserialize, Serialize};
+
+/// `[false-positives]` settings in `notifier.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct FalsePositiveConfig {
+    /// Directory names whose matches are ignored, since they hold copies of
+    /// other projects (`node_modules`, `vendor`, ...). A repository whose
+    /// every match is inside one is dropped.
+    pub vendored_dirs: Vec<String>,
+    /// `owner/name` of the template repositories themselves; they and their
+    /// forks, including forks of forks, are dropped.
+    pub template_repos: Vec<String>,
+    /// Size in bytes above which the matched file is assumed not to be a
+    /// version marker (e.g. a bundled or generated file); no limit when absent.
+    pub max_file_size: Option<u64>,
+}
+
+impl Default for FalsePositiveConfig {
+    fn default() -> Self {
+        Self {
+            vendored_dirs: ["node_modules", "vendor", "third_party", "bower_components"]
+                .map(String::from)
+                .to_vec(),
+            template_repos: Vec::new(),
+            max_file_size: None,
+        }
+    }
+}
+
+impl FalsePositiveConfig {
+    /// Drops `repository`'s matches inside vendored directories, promoting
+    /// the first remaining one to its primary match.
+    ///
+    /// The size of a promoted match is not known yet, so
+    /// [`file_size`](DiscoveredRepository::file_size) is cleared until it is
+    /// looked up again with [`fetch_file_sizes`](super::fetch_file_sizes).
+    ///
+    /// Returns false if every match was vendored.
+    pub fn strip_vendored(&self, repository: &mut DiscoveredRepository) -> bool {
+        let mut locations = repository.locations();
+        let before = locations.len();
+        locations.retain(|location| !self.is_vendored(&location.path));
+        if locations.len() == before {
+            return true;
+        }
+        let mut locations = locations.into_iter();
+        let Some(primary) = locations.next() else {
+            return false;
+        };
+        if primary.path != repository.file_path {
+            repository.file_size = None;
+        }
+        repository.file_path = primary.path;
+        repository.file_url = primary.url;
+        repository.additional_locations = locations.collect();
+        true
+    }
+
+    /// Returns why `repository` looks like a false positive, if it does.
+    ///
+    /// Fork parents and file sizes are only known once looked up (see
+    /// [`enrich_with_graphql`](super::enrich_with_graphql)); those checks
+    /// pass otherwise.
+    #[must_use]
+    pub fn reason(&self, repository: &DiscoveredRepository) -> Option<&'static str> {
+        let is_template = |name: &str| {
+            self.template_repos
+                .iter()
+                .any(|template| template.eq_ignore_ascii_case(name))
+        };
+        if is_template(&repository.full_name) {
+            Some("the template repository itself")
+        } else if repository
+            .fork_ancestors
+            .iter()
+            .any(|ancestor| is_template(ancestor))
+        {
+            Some("fork of the template repository")
+        } else if self
+            .max_file_size
+            .zip(repository.file_size)
+            .is_some_and(|(max, size)| size > max)
+        {
+            Some("matched file too large")
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if `path` is inside one of the vendored directories.
+    fn is_vendored(&self, path: &str) -> bool {
+        let mut components: Vec<&str> = path.split('/').collect();
+        components.pop();
+        components
+            .iter()
+            .any(|component| self.vendored_dirs.iter().any(|dir| dir == component))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::TemplateLocation;
+
+    fn repository(paths: &[&str]) -> DiscoveredRepository {
+        let location = |path: &str| TemplateLocation {
+            path: path.to_string(),
+            url: format!("https://github.com/user/app/blob/main/{path}")
+                .parse()
+                .unwrap(),
+        };
+        let primary = location(paths[0]);
+        DiscoveredRepository {
+            owner: "user".to_string(),
+            name: "app".to_string(),
+            full_name: "user/app".parse().unwrap(),
+            file_path: primary.path,
+            file_url: primary.url,
+            default_branch: "main".to_string(),
+            template_version: None,
+            additional_locations: paths[1..].iter().map(|path| location(path)).collect(),
+            snippet: None,
+            previously_notified: false,
+            previous_issue_url: None,
+            owner_is_bot: false,
+            is_mirror: false,
+            archived: false,
+            disabled: false,
+            visibility: None,
+            fork_ancestors: Vec::new(),
+            file_size: None,
+            topics: None,
+            opted_out: None,
+            repo_data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn strips_vendored_matches() {
+        let config = FalsePositiveConfig::default();
+        let mut repo = repository(&["node_modules/pkg/version.txt", "version.txt"]);
+
+        repo.file_size = Some(4096);
+
+        assert!(config.strip_vendored(&mut repo));
+        assert_eq!(repo.file_path, "version.txt");
+        assert!(repo.additional_locations.is_empty());
+        assert_eq!(repo.file_size, None);
+
+        let mut vendored = repository(&["vendor/version.txt"]);
+        assert!(!config.strip_vendored(&mut vendored));
+    }
+
+    #[test]
+    fn file_names_are_not_directories() {
+        let config = FalsePositiveConfig {
+            vendored_dirs: vec!["version.txt".to_string()],
+            ..FalsePositiveConfig::default()
+        };
+        assert!(config.strip_vendored(&mut repository(&["version.txt"])));
+    }
+
+    #[test]
+    fn flags_template_forks_and_large_files() {
+        let config = FalsePositiveConfig {
+            template_repos: vec!["Org/Template".to_string()],
+            max_file_size: Some(100),
+            ..FalsePositiveConfig::default()
+        };
+        let mut repo = repository(&["version.txt"]);
+        assert_eq!(config.reason(&repo), None);
+
+        repo.fork_ancestors = vec!["user/fork".to_string(), "org/template".to_string()];
+        assert_eq!(
+            config.reason(&repo),
+            Some("fork of the template repository")
+        );
+
+        repo.fork_ancestors.clear();
+        repo.file_size = Some(101);
+        assert_eq!(config.reason(&repo), Some("matched file too large"));
+    }
+}
@@ -0,0 +1,319 @@
+//! Batched repository metadata lookups over GraphQL.
+//!
+//! Code search results carry only a minimal repository object: no default
+//! branch, archived, disabled or mirror flag, visibility, topics or fork
+//! parent, and no size for the matched file. Fetching each repository over REST
+//! costs a request per repository, so discovered repositories are instead
+//! looked up `BATCH_SIZE` at a time as aliased fields of a single GraphQL
+//! query. GraphQL has no code search of its own, so the search itself still
+//! goes through REST.
+
+use super::{discover_repositories, DiscoveredRepository, DiscoveryError};
+use crate::config::Migration;
+use crate::http_cache::mark_public_repository;
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tracing::{debug, warn};
+
+/// Repositories looked up per GraphQL query.
+const BATCH_SIZE: usize = 50;
+
+/// Topics read per repository; GitHub allows at most 20.
+const MAX_TOPICS: usize = 20;
+
+/// Fork parents followed per repository, to catch forks of forks.
+const MAX_FORK_DEPTH: usize = 4;
+
+/// Fields read for every repository by [`enrich_with_graphql`].
+const METADATA_FIELDS: &str = "defaultBranchRef { name } isArchived isDisabled isMirror visibility";
+
+/// Field reading the size of the matched file, bound to the `f` variable.
+const FILE_SIZE_FIELD: &str = "... on Blob { byteSize }";
+
+/// Who can see a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Anyone.
+    Public,
+    /// Only collaborators.
+    Private,
+    /// Members of the enterprise.
+    Internal,
+}
+
+impl Visibility {
+    /// Parses the GraphQL `RepositoryVisibility` enum value.
+    fn from_graphql(value: &str) -> Option<Self> {
+        match value {
+            "PUBLIC" => Some(Self::Public),
+            "PRIVATE" => Some(Self::Private),
+            "INTERNAL" => Some(Self::Internal),
+            _ => None,
+        }
+    }
+}
+
+/// Discovers repositories like [`discover_repositories`], then fills in
+/// their default branch, flags, visibility, topics, fork parent and file
+/// size with [`enrich_with_graphql`].
+///
+/// A failed metadata lookup is logged and leaves the repositories as the
+/// search returned them.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if the search fails.
+pub async fn discover_repositories_graphql(
+    octocrab: &Octocrab,
+    migration: &Migration,
+) -> Result<Vec<DiscoveredRepository>, DiscoveryError> {
+    let mut repositories = discover_repositories(octocrab, migration).await?;
+    if let Err(e) = enrich_with_graphql(octocrab, &mut repositories).await {
+        warn!(error = %e, "Failed to fetch repository metadata");
+    }
+    Ok(repositories)
+}
+
+/// Fetches the default branch, archived, disabled and mirror flags,
+/// visibility, topics and fork parents of every repository, and the size of
+/// its matched file on the default branch, `BATCH_SIZE` per GraphQL query.
+///
+/// Replaces one REST round trip per repository
+/// ([`enrich_with_default_branches`](super::enrich_with_default_branches))
+/// with one query per batch. Returns how many repositories were answered;
+/// ones missing from a response (for example ones the token cannot see)
+/// keep their defaults.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if a query fails outright; repositories from
+/// earlier batches stay enriched.
+pub async fn enrich_with_graphql(
+    octocrab: &Octocrab,
+    repositories: &mut [DiscoveredRepository],
+) -> Result<usize, DiscoveryError> {
+    let total = repositories.len();
+    let mut answered = 0;
+    let selection = format!(
+        "{METADATA_FIELDS} {} \
+         repositoryTopics(first: {MAX_TOPICS}) {{ nodes {{ topic {{ name }} }} }}",
+        fork_parents_field(MAX_FORK_DEPTH)
+    );
+    for batch in repositories.chunks_mut(BATCH_SIZE) {
+        ensure_core_rate_limit(octocrab).await?;
+        let request = batch_request(batch.iter(), &selection);
+        let response: Value = octocrab.graphql(&request).await?;
+        if let Some(errors) = response.get("errors") {
+            warn!(errors = %errors, "Batched repository lookup returned errors");
+        }
+        for (index, repository) in batch.iter_mut().enumerate() {
+            if apply_metadata(repository, &response["data"][alias(index)]) {
+                answered += 1;
+            }
+        }
+    }
+    debug!(answered, total, "Fetched repository metadata");
+    Ok(answered)
+}
+
+/// Looks up the size of each repository's matched file on its default
+/// branch, `BATCH_SIZE` per GraphQL query.
+///
+/// Used when the matched file changed after [`enrich_with_graphql`], for
+/// example when [`strip_vendored`](super::FalsePositiveConfig::strip_vendored)
+/// promoted another match. Returns one size per repository, in order; `None`
+/// where the response has none.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if a query fails outright.
+pub async fn fetch_file_sizes(
+    octocrab: &Octocrab,
+    repositories: &[&DiscoveredRepository],
+) -> Result<Vec<Option<u64>>, DiscoveryError> {
+    let mut sizes = Vec::with_capacity(repositories.len());
+    for batch in repositories.chunks(BATCH_SIZE) {
+        ensure_core_rate_limit(octocrab).await?;
+        let request = batch_request(batch.iter().copied(), "");
+        let response: Value = octocrab.graphql(&request).await?;
+        sizes.extend(
+            (0..batch.len())
+                .map(|index| response["data"][alias(index)]["file"]["byteSize"].as_u64()),
+        );
+    }
+    Ok(sizes)
+}
+
+/// Builds one query reading `selection` and the matched file's size for
+/// every repository in `batch`, each owner, name and matched file passed as
+/// a variable.
+fn batch_request<'a>(
+    batch: impl IntoIterator<Item = &'a DiscoveredRepository>,
+    selection: &str,
+) -> Value {
+    let mut declarations = Vec::new();
+    let mut fields = String::new();
+    let mut variables = Map::new();
+    for (index, repository) in batch.into_iter().enumerate() {
+        let alias = alias(index);
+        declarations.push(format!(
+            "${alias}o: String!, ${alias}n: String!, ${alias}f: String!"
+        ));
+        fields.push_str(&format!(
+            "{alias}: repository(owner: ${alias}o, name: ${alias}n) \
+             {{ {selection} file: object(expression: ${alias}f) {{ {FILE_SIZE_FIELD} }} }} "
+        ));
+        variables.insert(format!("{alias}o"), Value::String(repository.owner.clone()));
+        variables.insert(format!("{alias}n"), Value::String(repository.name.clone()));
+        variables.insert(
+            format!("{alias}f"),
+            Value::String(format!("HEAD:{}", repository.file_path)),
+        );
+    }
+    json!({
+        "query": format!("query({}) {{ {fields}}}", declarations.join(", ")),
+        "variables": variables,
+    })
+}
+
+/// Returns `parent { nameWithOwner parent { ... } }`, nested `depth` times.
+fn fork_parents_field(depth: usize) -> String {
+    let mut field = String::new();
+    for _ in 0..depth {
+        field = format!("parent {{ nameWithOwner {field}}}");
+    }
+    field.trim_end().to_string()
+}
+
+/// Returns the `owner/name` of each fork parent nested in `node`, nearest
+/// first.
+fn fork_ancestors(node: &Value) -> Vec<String> {
+    core::iter::successors(Some(&node["parent"]), |parent| Some(&parent["parent"]))
+        .map_while(|parent| parent["nameWithOwner"].as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns the alias of the `index`th repository in a batch.
+fn alias(index: usize) -> String {
+    format!("r{index}")
+}
+
+/// Copies a repository's metadata from its `node`, returning false if the
+/// response has none.
+fn apply_metadata(repository: &mut DiscoveredRepository, node: &Value) -> bool {
+    if !node.is_object() {
+        return false;
+    }
+    if let Some(branch) = node["defaultBranchRef"]["name"].as_str() {
+        repository.default_branch = branch.to_string();
+    }
+    repository.archived = node["isArchived"].as_bool().unwrap_or(false);
+    repository.disabled = node["isDisabled"].as_bool().unwrap_or(false);
+    repository.is_mirror = node["isMirror"].as_bool().unwrap_or(false);
+    repository.visibility = node["visibility"]
+        .as_str()
+        .and_then(Visibility::from_graphql);
+    if repository.visibility == Some(Visibility::Public) {
+        mark_public_repository(&repository.owner, &repository.name);
+    }
+    repository.fork_ancestors = fork_ancestors(node);
+    repository.topics = node["repositoryTopics"]["nodes"].as_array().map(|nodes| {
+        nodes
+            .iter()
+            .filter_map(|node| node["topic"]["name"].as_str().map(str::to_string))
+            .collect()
+    });
+    repository.file_size = node["file"]["byteSize"].as_u64();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository(full_name: &str) -> DiscoveredRepository {
+        let (owner, name) = full_name.split_once('/').unwrap();
+        DiscoveredRepository {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            full_name: full_name.parse().unwrap(),
+            file_path: "version.txt".to_string(),
+            file_url: format!("https://github.com/{full_name}/blob/main/version.txt")
+                .parse()
+                .unwrap(),
+            default_branch: "main".to_string(),
+            template_version: None,
+            additional_locations: Vec::new(),
+            snippet: None,
+            previously_notified: false,
+            previous_issue_url: None,
+            owner_is_bot: false,
+            is_mirror: false,
+            archived: false,
+            disabled: false,
+            visibility: None,
+            fork_ancestors: Vec::new(),
+            file_size: None,
+            topics: None,
+            opted_out: None,
+            repo_data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn passes_owners_and_names_as_variables() {
+        let request = batch_request(&[repository("user/a"), repository("org/b")], "isMirror");
+
+        let query = request["query"].as_str().unwrap();
+        assert!(query.starts_with("query($r0o: String!, $r0n: String!, $r0f: String!, $r1o"));
+        assert!(query.contains("r1: repository(owner: $r1o, name: $r1n) { isMirror file:"));
+        assert_eq!(request["variables"]["r1o"], "org");
+        assert_eq!(request["variables"]["r1n"], "b");
+        assert_eq!(request["variables"]["r1f"], "HEAD:version.txt");
+    }
+
+    #[test]
+    fn applies_metadata_from_response() {
+        let mut repo = repository("user/a");
+        let node = json!({
+            "defaultBranchRef": { "name": "master" },
+            "isArchived": true,
+            "isDisabled": false,
+            "isMirror": true,
+            "visibility": "INTERNAL",
+            "parent": {
+                "nameWithOwner": "user/fork",
+                "parent": { "nameWithOwner": "org/template", "parent": null },
+            },
+            "repositoryTopics": { "nodes": [{ "topic": { "name": "rust" } }] },
+            "file": { "byteSize": 42 },
+        });
+
+        assert!(apply_metadata(&mut repo, &node));
+        assert_eq!(repo.default_branch, "master");
+        assert!(repo.archived);
+        assert!(!repo.disabled);
+        assert!(repo.is_mirror);
+        assert_eq!(repo.visibility, Some(Visibility::Internal));
+        assert_eq!(repo.fork_ancestors, ["user/fork", "org/template"]);
+        assert_eq!(repo.file_size, Some(42));
+        assert_eq!(repo.topics.as_deref(), Some(&["rust".to_string()][..]));
+
+        let mut missing = repository("user/b");
+        assert!(!apply_metadata(&mut missing, &Value::Null));
+        assert_eq!(missing.default_branch, "main");
+    }
+
+    #[test]
+    fn nests_fork_parents() {
+        assert_eq!(
+            fork_parents_field(2),
+            "parent { nameWithOwner parent { nameWithOwner }}"
+        );
+        assert_eq!(fork_parents_field(0), "");
+    }
+}
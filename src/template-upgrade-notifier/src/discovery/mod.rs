@@ -3,37 +3,52 @@
 //! This module provides functions to discover repositories containing
 //! outdated template versions using GitHub's code search.
 
+mod cache;
 mod error;
+mod exclude;
+mod false_positive;
+mod graphql;
+mod opt_out;
+mod repo_list;
 mod repository;
-
-pub use error::DiscoveryError;
-pub use repository::DiscoveredRepository;
+mod search;
+mod snippet;
+mod verify;
+
+pub use cache::{disable_search_cache, load_search_cache, reset_search_cache, save_search_cache};
+pub use error::{DiscoveryError, RepoListError, SearchCacheError};
+pub use exclude::ExcludeConfig;
+pub use false_positive::FalsePositiveConfig;
+pub use graphql::{
+    discover_repositories_graphql, enrich_with_graphql, fetch_file_sizes, Visibility,
+};
+pub use opt_out::{find_opt_outs, OptOutConfig};
+pub use repo_list::{discover_from_list, ListedRepository, RepoList};
+pub use repository::{DiscoveredRepository, TemplateLocation};
+pub use snippet::FileSnippet;
+pub use verify::{
+    detect_template_version, verify_old_string, verify_on_default_branch, verify_upgraded,
+    ALREADY_UPGRADED_REASON, STALE_SEARCH_RESULT_REASON,
+};
+pub(crate) use verify::{fetch_default_branch_file, fetch_file, version_in};
 
 use crate::config::Migration;
-use crate::rate_limit::{ensure_core_rate_limit, ensure_search_rate_limit};
+use crate::http_cache::{get_json, route};
+use crate::rate_limit::ensure_core_rate_limit;
+use crate::selection::PatternSet;
+use futures::stream::TryStreamExt;
 use octocrab::Octocrab;
-use std::collections::HashSet;
+use search::{execute_code_search, CodeSearchResult};
+use std::collections::BTreeMap;
 use tracing::{debug, info, info_span, warn, Instrument};
 
-/// Maximum results from GitHub Code Search API.
-const MAX_SEARCH_RESULTS: usize = 1000;
-
-/// Results per page for code search.
-const RESULTS_PER_PAGE: u8 = 100;
-
-/// Intermediate search result before deduplication.
-struct CodeSearchResult {
-    owner: String,
-    name: String,
-    full_name: String,
-    file_path: String,
-    file_url: String,
-}
-
 /// Discovers repositories containing the outdated template version.
 ///
 /// Uses GitHub Code Search API to find repositories containing the
-/// `old_string` in the `target_file`.
+/// `old_string` in any of the migration's target files, owned by one of the
+/// migration's `search_owners` if any are set. Each target file is searched
+/// separately and the results are combined by repository. Matches outside the
+/// migration's `target_path` glob, if set, are dropped.
 ///
 /// # Arguments
 ///
@@ -42,7 +57,9 @@ struct CodeSearchResult {
 ///
 /// # Returns
 ///
-/// A vector of discovered repositories, deduplicated by full_name.
+/// A vector of discovered repositories, deduplicated by full_name. Every
+/// matching file in a repository is kept as one of its
+/// [`locations`](DiscoveredRepository::locations).
 ///
 /// # Errors
 ///
@@ -55,21 +72,15 @@ pub async fn discover_repositories(
         "discover",
         migration_id = %migration.id,
         old_string = %migration.old_string,
-        target_file = %migration.target_file
+        target_files = ?migration.target_files()
     );
 
     async {
         info!("Starting repository discovery");
 
-        // Build search query
-        let query = build_search_query(&migration.old_string, &migration.target_file);
-        debug!(query = %query, "Executing code search");
-
-        // Execute search with pagination
-        let results = execute_code_search(octocrab, &query).await?;
-
-        // Deduplicate results
-        let repositories = deduplicate_results(results);
+        let queries = build_search_queries(&migration.old_string, migration);
+        debug!(queries = ?queries, "Executing code search");
+        let repositories = collect_repositories(octocrab, queries, migration).await?;
 
         info!(count = repositories.len(), "Discovery complete");
         Ok(repositories)
@@ -78,113 +89,195 @@ pub async fn discover_repositories(
     .await
 }
 
-/// Builds a GitHub code search query.
+/// Discovers repositories that already contain the migration's `new_string`.
 ///
-/// Format: `"{old_string}" in:file filename:{target_file}`
-fn build_search_query(old_string: &str, target_file: &str) -> String {
-    format!("\"{}\" in:file filename:{}", old_string, target_file)
-}
-
-/// Executes the code search with pagination.
-async fn execute_code_search(
+/// Used to find repositories that upgraded on their own, so notifications
+/// filed earlier can be closed.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if the search fails.
+pub async fn discover_upgraded_repositories(
     octocrab: &Octocrab,
-    query: &str,
-) -> Result<Vec<CodeSearchResult>, DiscoveryError> {
-    let mut all_results = Vec::new();
-
-    // Check rate limit before first search
-    ensure_search_rate_limit(octocrab).await?;
-
-    // Get first page
-    let mut page = octocrab
-        .search()
-        .code(query)
-        .per_page(RESULTS_PER_PAGE)
-        .send()
-        .await?;
-
-    // Extract results from first page
-    all_results.extend(extract_search_results(&page));
-
-    // Paginate through remaining results
-    loop {
-        if all_results.len() >= MAX_SEARCH_RESULTS {
-            warn!(
-                max = MAX_SEARCH_RESULTS,
-                "Reached maximum search results limit"
-            );
-            break;
-        }
-
-        if page.next.is_none() {
-            break;
-        }
+    migration: &Migration,
+) -> Result<Vec<DiscoveredRepository>, DiscoveryError> {
+    let queries = build_search_queries(&migration.new_string, migration);
+    debug!(queries = ?queries, "Executing code search for upgraded repositories");
 
-        // Check rate limit before making the API call
-        ensure_search_rate_limit(octocrab).await?;
+    collect_repositories(octocrab, queries, migration).await
+}
 
-        let Some(next_page) = octocrab
-            .get_page::<octocrab::models::Code>(&page.next)
-            .await?
-        else {
-            break;
-        };
+/// Rediscovers a single repository for a migration.
+///
+/// Runs the same code search as [`discover_repositories`], scoped to
+/// `full_name` with a `repo:` qualifier.
+///
+/// # Returns
+///
+/// The repository with its current match, or `None` if the search no longer
+/// finds `old_string` in it.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if the search fails.
+pub async fn discover_in_repository(
+    octocrab: &Octocrab,
+    migration: &Migration,
+    full_name: &str,
+) -> Result<Option<DiscoveredRepository>, DiscoveryError> {
+    let queries: Vec<String> = build_search_queries(&migration.old_string, migration)
+        .into_iter()
+        .map(|query| format!("{query} repo:{full_name}"))
+        .collect();
+    debug!(queries = ?queries, "Executing scoped code search");
+
+    let repositories = collect_repositories(octocrab, queries, migration).await?;
+    Ok(repositories
+        .into_iter()
+        .find(|repo| repo.full_name.eq_ignore_ascii_case(full_name)))
+}
 
-        all_results.extend(extract_page_results(&next_page));
-        page.next = next_page.next;
-    }
+/// Builds one GitHub code search query for `term` per target file of the
+/// migration, since `filename:` qualifiers cannot be ORed.
+///
+/// Format: `"{term}" in:file filename:{target_file}`, followed by a
+/// `path:` qualifier narrowing the search to the `target_path` glob's
+/// directory, if it names one, and a `user:{owner}` qualifier per search
+/// owner, which GitHub ORs together.
+fn build_search_queries(term: &str, migration: &Migration) -> Vec<String> {
+    let path = migration.target_path.as_deref().and_then(path_qualifier);
+    migration
+        .target_files()
+        .into_iter()
+        .map(|target_file| {
+            let mut query = format!("\"{term}\" in:file filename:{target_file}");
+            if let Some(path) = &path {
+                query.push_str(" path:");
+                query.push_str(path);
+            }
+            for owner in &migration.search_owners {
+                query.push_str(" user:");
+                query.push_str(owner);
+            }
+            query
+        })
+        .collect()
+}
 
-    Ok(all_results)
+/// Returns the literal directories at the end of a `target_path` glob's
+/// directory, for a `path:` qualifier: `.github` for
+/// `**/.github/template-version.txt`. `None` if there are none.
+fn path_qualifier(target_path: &str) -> Option<String> {
+    let (dir, _) = target_path.rsplit_once('/')?;
+    let mut literal: Vec<&str> = dir
+        .rsplit('/')
+        .take_while(|component| !component.contains(['*', '?', '[', '{']))
+        .filter(|component| !component.is_empty())
+        .collect();
+    literal.reverse();
+    (!literal.is_empty()).then(|| literal.join("/"))
 }
 
-/// Extracts search results from a search response page.
-fn extract_search_results(page: &octocrab::Page<octocrab::models::Code>) -> Vec<CodeSearchResult> {
-    page.items
-        .iter()
-        .filter_map(|item| {
-            let repo = &item.repository;
-            let owner = repo.owner.as_ref()?.login.clone();
-            let name = repo.name.clone();
-            let full_name = format!("{}/{}", owner, name);
+/// Returns the matcher for the migration's `target_path`, which matches
+/// every path when it is unset.
+pub(crate) fn target_path_matcher(migration: &Migration) -> PatternSet {
+    PatternSet::new(&migration.target_path).unwrap_or_else(|e| {
+        warn!(error = %e, "Invalid target-path, not filtering matches by path");
+        PatternSet::default()
+    })
+}
 
-            Some(CodeSearchResult {
-                owner,
-                name,
-                full_name,
-                file_path: item.path.clone(),
-                file_url: item.html_url.to_string(),
-            })
-        })
-        .collect()
+/// Runs `queries` to completion, grouping every match within the
+/// migration's `target_path` by repository.
+async fn collect_repositories(
+    octocrab: &Octocrab,
+    queries: Vec<String>,
+    migration: &Migration,
+) -> Result<Vec<DiscoveredRepository>, DiscoveryError> {
+    let paths = target_path_matcher(migration);
+    let mut results = Vec::new();
+    for query in queries {
+        results.extend(search_cached(octocrab, migration, query).await?);
+    }
+    results.retain(|result| paths.matches(&result.file_path));
+    Ok(group_by_repository(results).collect())
 }
 
-/// Extracts search results from a raw page response.
-fn extract_page_results(page: &octocrab::Page<octocrab::models::Code>) -> Vec<CodeSearchResult> {
-    extract_search_results(page)
+/// Runs `query` to completion, reusing the results cached for the migration
+/// by an earlier run while the search cache holds them.
+async fn search_cached(
+    octocrab: &Octocrab,
+    migration: &Migration,
+    query: String,
+) -> Result<Vec<CodeSearchResult>, DiscoveryError> {
+    let migration_id = migration.id.to_string();
+    if let Some(results) = cache::cached_results(&migration_id, &query) {
+        debug!(query = %query, count = results.len(), "Using cached code search results");
+        return Ok(results);
+    }
+    let results: Vec<CodeSearchResult> = execute_code_search(octocrab, query.clone())
+        .try_concat()
+        .await?;
+    cache::store_results(&migration_id, &query, &results);
+    Ok(results)
 }
 
-/// Deduplicates search results by repository full_name.
+/// Groups search results by repository full_name, in the order repositories
+/// were first seen.
 ///
-/// If multiple files match in the same repository, only the first is kept.
-fn deduplicate_results(results: Vec<CodeSearchResult>) -> Vec<DiscoveredRepository> {
-    let mut seen = HashSet::new();
-    let mut repositories = Vec::new();
-
+/// The first match in a repository becomes its primary file; later matches
+/// are kept as additional locations.
+fn group_by_repository(
+    results: Vec<CodeSearchResult>,
+) -> impl Iterator<Item = DiscoveredRepository> {
+    let mut repositories: Vec<DiscoveredRepository> = Vec::new();
     for result in results {
-        if seen.insert(result.full_name.clone()) {
-            repositories.push(DiscoveredRepository {
-                owner: result.owner,
-                name: result.name,
-                full_name: result.full_name,
-                file_path: result.file_path,
-                file_url: result.file_url,
-                // Default branch will be fetched separately if needed
-                default_branch: "main".to_string(),
-            });
+        match repositories
+            .iter_mut()
+            .find(|repo| repo.full_name == result.full_name)
+        {
+            Some(repo) => {
+                let location = TemplateLocation {
+                    path: result.file_path,
+                    url: result.file_url,
+                };
+                if repo.file_path != location.path && !repo.additional_locations.contains(&location)
+                {
+                    repo.additional_locations.push(location);
+                }
+            }
+            None => repositories.push(into_repository(result)),
         }
     }
+    repositories.into_iter()
+}
 
-    repositories
+/// Converts a search result into a discovered repository.
+fn into_repository(result: CodeSearchResult) -> DiscoveredRepository {
+    DiscoveredRepository {
+        owner: result.owner,
+        name: result.name,
+        full_name: result.full_name,
+        file_path: result.file_path,
+        file_url: result.file_url,
+        // Default branch will be fetched separately if needed
+        default_branch: "main".to_string(),
+        template_version: None,
+        additional_locations: Vec::new(),
+        snippet: None,
+        previously_notified: false,
+        previous_issue_url: None,
+        owner_is_bot: result.owner_is_bot,
+        is_mirror: false,
+        archived: result.archived,
+        disabled: result.disabled,
+        visibility: None,
+        fork_ancestors: Vec::new(),
+        file_size: None,
+        topics: None,
+        opted_out: None,
+        repo_data: BTreeMap::new(),
+    }
 }
 
 /// Fetches the default branch for a repository.
@@ -208,7 +301,8 @@ pub async fn get_default_branch(
     repo: &str,
 ) -> Result<String, DiscoveryError> {
     ensure_core_rate_limit(octocrab).await?;
-    let repo_info = octocrab.repos(owner, repo).get().await?;
+    let repo_info: octocrab::models::Repository =
+        get_json(octocrab, &route(["repos", owner, repo])).await?;
     Ok(repo_info
         .default_branch
         .unwrap_or_else(|| "main".to_string()))
@@ -217,7 +311,9 @@ pub async fn get_default_branch(
 /// Enriches discovered repositories with default branch information.
 ///
 /// This makes additional API calls to fetch the default branch for each repository.
-/// Use sparingly to avoid rate limiting.
+/// Use sparingly to avoid rate limiting; [`enrich_with_graphql`] fetches the
+/// same information, plus the archived flag and visibility, in one query per
+/// batch of repositories.
 pub async fn enrich_with_default_branches(
     octocrab: &Octocrab,
     repositories: &mut [DiscoveredRepository],
@@ -243,40 +339,124 @@ mod tests {
 
     #[test]
     fn can_build_search_query() {
-        let query = build_search_query("my-template:1.0.0", "version.txt");
-        assert_eq!(query, "\"my-template:1.0.0\" in:file filename:version.txt");
+        let migration = Migration {
+            target_file: "version.txt".to_string(),
+            ..Default::default()
+        };
+        let queries = build_search_queries("my-template:1.0.0", &migration);
+        assert_eq!(
+            queries,
+            ["\"my-template:1.0.0\" in:file filename:version.txt"]
+        );
+    }
+
+    #[test]
+    fn builds_one_query_per_target_file() {
+        let migration = Migration {
+            target_file: "version.txt".to_string(),
+            additional_target_files: vec![".template-version".to_string()],
+            ..Default::default()
+        };
+        let queries = build_search_queries("v1", &migration);
+        assert_eq!(
+            queries,
+            [
+                "\"v1\" in:file filename:version.txt",
+                "\"v1\" in:file filename:.template-version",
+            ]
+        );
     }
 
     #[test]
-    fn can_deduplicate_results() {
+    fn narrows_search_to_target_path_directory() {
+        let migration = Migration {
+            target_file: "template-version.txt".to_string(),
+            target_path: Some("**/.github/template-version.txt".to_string()),
+            ..Default::default()
+        };
+        let queries = build_search_queries("v1", &migration);
+        assert_eq!(
+            queries,
+            ["\"v1\" in:file filename:template-version.txt path:.github"]
+        );
+
+        assert_eq!(
+            path_qualifier("a/*/b/c/version.txt").as_deref(),
+            Some("b/c")
+        );
+        assert_eq!(path_qualifier("**/version.txt"), None);
+        assert_eq!(path_qualifier("version.txt"), None);
+
+        let paths = target_path_matcher(&migration);
+        assert!(paths.matches("services/api/.github/template-version.txt"));
+        assert!(paths.matches(".github/template-version.txt"));
+        assert!(!paths.matches("docs/template-version.txt"));
+    }
+
+    #[test]
+    fn restricts_search_to_owners() {
+        let migration = Migration {
+            target_file: "version.txt".to_string(),
+            search_owners: vec!["my-org".to_string(), "me".to_string()],
+            ..Default::default()
+        };
+        let queries = build_search_queries("v1", &migration);
+        assert_eq!(
+            queries,
+            ["\"v1\" in:file filename:version.txt user:my-org user:me"]
+        );
+    }
+
+    #[test]
+    fn can_group_results_by_repository() {
         let results = vec![
             CodeSearchResult {
                 owner: "user".to_string(),
                 name: "repo".to_string(),
-                full_name: "user/repo".to_string(),
+                full_name: "user/repo".parse().unwrap(),
                 file_path: "file1.txt".to_string(),
-                file_url: "https://github.com/user/repo/file1.txt".to_string(),
+                file_url: "https://github.com/user/repo/file1.txt".parse().unwrap(),
+                owner_is_bot: false,
+                archived: false,
+                disabled: false,
             },
             CodeSearchResult {
                 owner: "user".to_string(),
                 name: "repo".to_string(),
-                full_name: "user/repo".to_string(),
+                full_name: "user/repo".parse().unwrap(),
                 file_path: "file2.txt".to_string(),
-                file_url: "https://github.com/user/repo/file2.txt".to_string(),
+                file_url: "https://github.com/user/repo/file2.txt".parse().unwrap(),
+                owner_is_bot: false,
+                archived: false,
+                disabled: false,
             },
             CodeSearchResult {
                 owner: "other".to_string(),
                 name: "project".to_string(),
-                full_name: "other/project".to_string(),
+                full_name: "other/project".parse().unwrap(),
                 file_path: "version.txt".to_string(),
-                file_url: "https://github.com/other/project/version.txt".to_string(),
+                file_url: "https://github.com/other/project/version.txt"
+                    .parse()
+                    .unwrap(),
+                owner_is_bot: false,
+                archived: false,
+                disabled: false,
             },
         ];
 
-        let deduped = deduplicate_results(results);
-
-        assert_eq!(deduped.len(), 2);
-        assert_eq!(deduped[0].full_name, "user/repo");
-        assert_eq!(deduped[1].full_name, "other/project");
+        let grouped: Vec<_> = group_by_repository(results).collect();
+
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].full_name, "user/repo");
+        assert_eq!(grouped[0].file_path, "file1.txt");
+        assert_eq!(
+            grouped[0].additional_locations,
+            vec![TemplateLocation {
+                path: "file2.txt".to_string(),
+                url: "https://github.com/user/repo/file2.txt".parse().unwrap(),
+            }]
+        );
+        assert_eq!(grouped[1].full_name, "other/project");
+        assert!(!grouped[1].has_multiple_locations());
     }
 }
@@ -0,0 +1,184 @@
+//! Repositories whose maintainers asked not to receive upgrade notices.
+
+use super::{DiscoveredRepository, DiscoveryError};
+use crate::http_cache::{get_json, route};
+use crate::issues::{author_qualifier, repository_from_api_url};
+use crate::rate_limit::{ensure_core_rate_limit, ensure_search_rate_limit};
+use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, warn};
+
+/// Results per page of the opt-out label search.
+const RESULTS_PER_PAGE: u8 = 100;
+
+/// Pages the search API returns at most (1000 results).
+const MAX_PAGES: u32 = 10;
+
+/// `[opt-out]` settings in `notifier.toml`.
+///
+/// A repository carrying one of the `topics`, or whose maintainers put one of
+/// the `labels` on an issue we filed, is left out of every later campaign.
+/// Empty lists turn the corresponding check off.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct OptOutConfig {
+    /// Repository topics that opt a repository out.
+    pub topics: Vec<String>,
+    /// Labels on one of our earlier issues that opt a repository out.
+    pub labels: Vec<String>,
+}
+
+impl Default for OptOutConfig {
+    fn default() -> Self {
+        Self {
+            topics: vec!["template-upgrades-opt-out".to_string()],
+            labels: vec!["template-upgrades:opt-out".to_string()],
+        }
+    }
+}
+
+impl OptOutConfig {
+    /// Returns the first of `topics` that opts a repository out, compared
+    /// case-insensitively.
+    #[must_use]
+    pub fn matching_topic<'a>(&self, topics: &'a [String]) -> Option<&'a str> {
+        topics
+            .iter()
+            .find(|topic| self.topics.iter().any(|t| t.eq_ignore_ascii_case(topic)))
+            .map(String::as_str)
+    }
+}
+
+/// Records in [`DiscoveredRepository::opted_out`] why each of
+/// `repositories` opted out of upgrade notices, if it did.
+///
+/// Topics come from [`enrich_with_graphql`](super::enrich_with_graphql);
+/// repositories it did not look up are fetched one at a time. Opt-out labels
+/// on our earlier issues are found with one search across all repositories,
+/// rather than a search per repository.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if a repository or the search cannot be
+/// fetched. Callers should then notify none of the repositories, so an
+/// opt-out is never missed.
+pub async fn find_opt_outs(
+    octocrab: &Octocrab,
+    repositories: &mut [DiscoveredRepository],
+    config: &OptOutConfig,
+) -> Result<(), DiscoveryError> {
+    if repositories.is_empty() {
+        return Ok(());
+    }
+    if !config.topics.is_empty() {
+        for repository in repositories.iter_mut() {
+            let topics = match repository.topics.take() {
+                Some(topics) => topics,
+                None => fetch_topics(octocrab, repository).await?,
+            };
+            if let Some(topic) = config.matching_topic(&topics) {
+                repository.opted_out = Some(format!("opted out (topic `{topic}`)"));
+            }
+            repository.topics = Some(topics);
+        }
+    }
+    if config.labels.is_empty() {
+        return Ok(());
+    }
+
+    let labelled = labelled_issues(octocrab, config).await?;
+    for repository in repositories.iter_mut() {
+        if repository.opted_out.is_some() {
+            continue;
+        }
+        if let Some(number) = labelled.get(repository.full_name.as_str()) {
+            repository.opted_out = Some(format!("opted out (label on #{number})"));
+        }
+    }
+    Ok(())
+}
+
+/// Fetches the topics of a repository that enrichment did not look up.
+async fn fetch_topics(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+) -> Result<Vec<String>, DiscoveryError> {
+    ensure_core_rate_limit(octocrab).await?;
+    let info: octocrab::models::Repository = get_json(
+        octocrab,
+        &route(["repos", &repository.owner, &repository.name]),
+    )
+    .await?;
+    Ok(info.topics.unwrap_or_default())
+}
+
+/// Returns, per `owner/name`, an issue of ours carrying one of the opt-out
+/// labels.
+async fn labelled_issues(
+    octocrab: &Octocrab,
+    config: &OptOutConfig,
+) -> Result<HashMap<String, u64>, DiscoveryError> {
+    let labels: Vec<String> = config
+        .labels
+        .iter()
+        .map(|label| format!("\"{}\"", label.replace('"', "")))
+        .collect();
+    let query = format!(
+        "is:issue {} label:{}",
+        author_qualifier(octocrab).await?,
+        labels.join(",")
+    );
+    debug!(query = %query, "Looking for opt-out labels");
+
+    let mut labelled = HashMap::new();
+    for page in 1..=MAX_PAGES {
+        ensure_search_rate_limit(octocrab).await?;
+        let result = octocrab
+            .search()
+            .issues_and_pull_requests(&query)
+            .per_page(RESULTS_PER_PAGE)
+            .page(page)
+            .send()
+            .await?;
+        let fetched = result.items.len();
+        for issue in result.items {
+            if let Some(repository) = repository_from_api_url(issue.repository_url.as_str()) {
+                labelled.entry(repository).or_insert(issue.number);
+            }
+        }
+        if fetched < usize::from(RESULTS_PER_PAGE) {
+            return Ok(labelled);
+        }
+    }
+    warn!(
+        results = labelled.len(),
+        "Opt-out label search exceeds the result cap; some opt-outs may be missed"
+    );
+    Ok(labelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_check_topic_and_label() {
+        let config: OptOutConfig = toml::from_str("").unwrap();
+
+        assert_eq!(config.topics, ["template-upgrades-opt-out"]);
+        assert_eq!(config.labels, ["template-upgrades:opt-out"]);
+    }
+
+    #[test]
+    fn matches_topics_case_insensitively() {
+        let config = OptOutConfig::default();
+        let topics = ["rust".to_string(), "Template-Upgrades-Opt-Out".to_string()];
+
+        assert_eq!(
+            config.matching_topic(&topics),
+            Some("Template-Upgrades-Opt-Out")
+        );
+        assert_eq!(config.matching_topic(&topics[..1]), None);
+    }
+}
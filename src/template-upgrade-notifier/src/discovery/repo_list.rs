@@ -0,0 +1,341 @@
+//! Static repository lists as a discovery source.
+//!
+//! CI pipelines that already know their downstream repositories can list
+//! them instead of relying on code search, whose index lags behind pushes.
+//! `repos.txt` holds one `owner/name` per line, optionally followed by the
+//! branch to read; `repos.toml` can also name the file to read:
+//!
+//! ```toml
+//! [[repos]]
+//! name = "my-org/api"
+//! default-branch = "develop"
+//! path = "config/template-version.txt"
+//! ```
+
+use super::verify::{fetch_file_at, version_in};
+use super::{
+    enrich_with_graphql, DiscoveredRepository, DiscoveryError, FileSnippet, RepoListError,
+    TemplateLocation,
+};
+use crate::config::Migration;
+use crate::ids::{HtmlUrl, RepoSlug};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use octocrab::Octocrab;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{debug, info, warn};
+
+/// Listed repositories read at the same time.
+const LIST_CONCURRENCY: usize = 5;
+
+/// One repository of a [`RepoList`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ListedRepository {
+    /// Repository full name.
+    pub name: RepoSlug,
+    /// Branch the template file is read from and PRs target; the
+    /// repository's default branch when unset.
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Path of the template file; each of the migration's target files at
+    /// the repository root when unset.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Repositories to check instead of searching for them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RepoList {
+    /// The listed repositories, in file order.
+    #[serde(default)]
+    pub repos: Vec<ListedRepository>,
+}
+
+impl RepoList {
+    /// Loads a list from `path`: TOML for a `.toml` extension, otherwise one
+    /// `owner/name [branch]` per line, with `#` comments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepoListError`] if the file cannot be read or an entry is
+    /// invalid.
+    pub fn load(path: &Path) -> Result<Self, RepoListError> {
+        let content = std::fs::read_to_string(path).map_err(|source| RepoListError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            Self::parse_toml(&content, path)
+        } else {
+            Self::parse_lines(&content, path)
+        }
+    }
+
+    /// Parses `owner/name [branch]` lines, skipping blank lines and `#` comments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepoListError::InvalidEntry`] for a line that is not a
+    /// repository name, optionally followed by one branch name.
+    pub fn parse_lines(content: &str, path: &Path) -> Result<Self, RepoListError> {
+        let repos = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or("").trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(line_number, line)| {
+                let invalid = |message: String| RepoListError::InvalidEntry {
+                    path: path.display().to_string(),
+                    line: line_number,
+                    message,
+                };
+                let mut fields = line.split_whitespace();
+                let name = fields.next().unwrap_or_default();
+                let name = name.parse().map_err(|e| invalid(format!("{e}")))?;
+                let default_branch = fields.next().map(str::to_string);
+                if let Some(extra) = fields.next() {
+                    return Err(invalid(format!("unexpected '{extra}' after the branch")));
+                }
+                Ok(ListedRepository {
+                    name,
+                    default_branch,
+                    path: None,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { repos })
+    }
+
+    /// Parses a TOML list of `[[repos]]` tables.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepoListError::Toml`] if parsing fails.
+    pub fn parse_toml(content: &str, path: &Path) -> Result<Self, RepoListError> {
+        toml::from_str(content).map_err(|source| RepoListError::Toml {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    /// Returns true if no repositories are listed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.repos.is_empty()
+    }
+}
+
+/// Discovers the listed repositories that contain the outdated template
+/// version, without code search.
+///
+/// Every listed repository is looked up with [`enrich_with_graphql`], then
+/// its listed `path`, or else each of the migration's target files at the
+/// repository root, is read from its listed or default branch and checked
+/// like a search result on the default branch (see
+/// [`verify_on_default_branch`](super::verify_on_default_branch)). The
+/// migration's `search_owners` do not apply; `include_repos` and
+/// `exclude_repos` still do. A repository that cannot be read is logged and
+/// left out.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] only if the run is rate limited; per-repository
+/// failures are logged.
+pub async fn discover_from_list(
+    octocrab: &Octocrab,
+    list: &RepoList,
+    migration: &Migration,
+) -> Result<Vec<DiscoveredRepository>, DiscoveryError> {
+    info!(count = list.repos.len(), "Checking listed repositories");
+    let target_files = migration.target_files();
+    let mut repositories: Vec<DiscoveredRepository> = list
+        .repos
+        .iter()
+        .map(|listed| {
+            let path = listed.path.as_deref().unwrap_or(target_files[0]);
+            placeholder(listed, path)
+        })
+        .collect();
+    if let Err(e) = enrich_with_graphql(octocrab, &mut repositories).await {
+        warn!(error = %e, "Failed to fetch repository metadata");
+    }
+
+    let found: Vec<Option<DiscoveredRepository>> =
+        stream::iter(list.repos.iter().zip(repositories))
+            .map(|(listed, repository)| {
+                let paths: Vec<&str> = match &listed.path {
+                    Some(path) => vec![path.as_str()],
+                    None => target_files.clone(),
+                };
+                check_listed(octocrab, listed, repository, paths, migration)
+            })
+            .buffered(LIST_CONCURRENCY)
+            .try_collect()
+            .await?;
+    let found: Vec<DiscoveredRepository> = found.into_iter().flatten().collect();
+    info!(count = found.len(), "Listed repository check complete");
+    Ok(found)
+}
+
+/// Reads `paths` from the listed repository and returns it if any of them
+/// records the outdated version; the first such file becomes its primary
+/// location.
+async fn check_listed(
+    octocrab: &Octocrab,
+    listed: &ListedRepository,
+    mut repository: DiscoveredRepository,
+    paths: Vec<&str>,
+    migration: &Migration,
+) -> Result<Option<DiscoveredRepository>, DiscoveryError> {
+    let git_ref = listed.default_branch.as_deref();
+    if let Some(branch) = git_ref {
+        // The metadata lookup reports the repository's own default branch.
+        repository.default_branch = branch.to_string();
+    }
+    let mut matches = Vec::new();
+    for path in paths {
+        let content = match fetch_file_at(
+            octocrab,
+            &repository.owner,
+            &repository.name,
+            path,
+            git_ref,
+        )
+        .await
+        {
+            Ok(Some(content)) => content,
+            Ok(None) => continue,
+            Err(e @ DiscoveryError::RateLimitExceeded { .. }) => return Err(e),
+            Err(e) => {
+                warn!(repo = %repository.full_name, path, error = %e, "Failed to read listed repository");
+                return Ok(None);
+            }
+        };
+        if let Some(version) = version_in(&content, migration) {
+            matches.push((path, version, content));
+        }
+    }
+
+    let mut matches = matches.into_iter();
+    let Some((path, version, content)) = matches.next() else {
+        debug!(repo = %repository.full_name, "Listed repository is not outdated");
+        return Ok(None);
+    };
+    let url = |path: &str| blob_url(&repository.full_name, &repository.default_branch, path);
+    repository.file_url = url(path);
+    repository.file_path = path.to_string();
+    repository.template_version = Some(version);
+    repository.snippet =
+        FileSnippet::extract(&content, &migration.old_string, &migration.new_string);
+    repository.additional_locations = matches
+        .map(|(path, _, _)| TemplateLocation {
+            path: path.to_string(),
+            url: url(path),
+        })
+        .collect();
+    Ok(Some(repository))
+}
+
+/// Returns a repository for `listed` with its file at `path`, before its
+/// metadata is looked up.
+fn placeholder(listed: &ListedRepository, path: &str) -> DiscoveredRepository {
+    let default_branch = listed.default_branch.as_deref().unwrap_or("main");
+    DiscoveredRepository {
+        owner: listed.name.owner().to_string(),
+        name: listed.name.name().to_string(),
+        full_name: listed.name.clone(),
+        file_path: path.to_string(),
+        file_url: blob_url(&listed.name, default_branch, path),
+        default_branch: default_branch.to_string(),
+        template_version: None,
+        additional_locations: Vec::new(),
+        snippet: None,
+        previously_notified: false,
+        previous_issue_url: None,
+        owner_is_bot: false,
+        is_mirror: false,
+        archived: false,
+        disabled: false,
+        visibility: None,
+        fork_ancestors: Vec::new(),
+        file_size: None,
+        topics: None,
+        opted_out: None,
+        repo_data: BTreeMap::new(),
+    }
+}
+
+/// Returns the link to `path` on `branch` of `repository`.
+fn blob_url(repository: &RepoSlug, branch: &str, path: &str) -> HtmlUrl {
+    let mut url = url::Url::parse("https://github.com").expect("static URL is valid");
+    url.path_segments_mut()
+        .expect("https URLs have a path")
+        .extend([repository.owner(), repository.name(), "blob", branch])
+        .extend(path.split('/'));
+    HtmlUrl::from(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines_with_optional_branch() {
+        let list = RepoList::parse_lines(
+            "# downstream consumers\nmy-org/api\n\nmy-org/web develop # staging first\n",
+            Path::new("repos.txt"),
+        )
+        .unwrap();
+
+        assert_eq!(list.repos.len(), 2);
+        assert_eq!(list.repos[0].name, "my-org/api");
+        assert_eq!(list.repos[0].default_branch, None);
+        assert_eq!(list.repos[1].default_branch.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        for content in ["not-a-repo\n", "my-org/api main extra\n"] {
+            assert!(matches!(
+                RepoList::parse_lines(content, Path::new("repos.txt")),
+                Err(RepoListError::InvalidEntry { line: 1, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn parses_toml() {
+        let list = RepoList::parse_toml(
+            "[[repos]]\nname = \"my-org/api\"\ndefault-branch = \"develop\"\n\
+             path = \"config/template-version.txt\"\n\n[[repos]]\nname = \"my-org/web\"\n",
+            Path::new("repos.toml"),
+        )
+        .unwrap();
+
+        assert_eq!(list.repos.len(), 2);
+        assert_eq!(
+            list.repos[0].path.as_deref(),
+            Some("config/template-version.txt")
+        );
+        assert!(RepoList::parse_toml("[[repos]]\nname = \"nope\"\n", Path::new("r.toml")).is_err());
+    }
+
+    #[test]
+    fn links_file_on_branch() {
+        let listed = ListedRepository {
+            name: "my-org/api".parse().unwrap(),
+            default_branch: Some("develop".to_string()),
+            path: None,
+        };
+
+        let repository = placeholder(&listed, ".github/template version.txt");
+
+        assert_eq!(
+            repository.file_url.as_str(),
+            "https://github.com/my-org/api/blob/develop/.github/template%20version.txt"
+        );
+    }
+}
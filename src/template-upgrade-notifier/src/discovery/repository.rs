@@ -1,6 +1,10 @@
 //! Discovered repository information.
 
+use super::{FileSnippet, Visibility};
+use crate::ids::{HtmlUrl, RepoSlug};
 use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
 
 /// A repository discovered to contain an outdated template version.
 #[derive(Debug, Clone, Serialize)]
@@ -12,14 +16,114 @@ pub struct DiscoveredRepository {
     pub name: String,
 
     /// Full repository name in "owner/name" format.
-    pub full_name: String,
+    pub full_name: RepoSlug,
 
     /// Path to the file containing the match.
     pub file_path: String,
 
     /// GitHub URL to the matched file.
-    pub file_url: String,
+    pub file_url: HtmlUrl,
 
     /// Default branch name (e.g., "main").
     pub default_branch: String,
+
+    /// Template version the repository is on, once verified on the default
+    /// branch (see [`detect_template_version`](super::detect_template_version)).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_version: Option<String>,
+
+    /// Further matches in the same repository beyond [`file_path`](Self::file_path),
+    /// for repositories containing several instances of the template.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional_locations: Vec<TemplateLocation>,
+
+    /// Lines of the matched file that will change, once read from the
+    /// default branch (see [`verify_on_default_branch`](super::verify_on_default_branch)).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<FileSnippet>,
+
+    /// Whether the ledger records an earlier notice in this repository, by
+    /// any migration.
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub previously_notified: bool,
+
+    /// URL of the most recent earlier issue (or PR, if no issue was filed).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_issue_url: Option<HtmlUrl>,
+
+    /// Whether the owner is a bot account.
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub owner_is_bot: bool,
+
+    /// Whether the repository mirrors one hosted elsewhere, once looked up
+    /// (see [`enrich_with_graphql`](super::enrich_with_graphql)).
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub is_mirror: bool,
+
+    /// Whether the repository is archived (read-only), once looked up
+    /// (see [`enrich_with_graphql`](super::enrich_with_graphql)).
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub archived: bool,
+
+    /// Whether GitHub has disabled the repository, for example for a terms
+    /// of service violation, once looked up.
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub disabled: bool,
+
+    /// Who can see the repository, once looked up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+
+    /// `owner/name` of the repositories this one was forked from, nearest
+    /// first, once looked up; empty for repositories that are not forks.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fork_ancestors: Vec<String>,
+
+    /// Size in bytes of the matched file on the default branch, once looked up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+
+    /// Repository topics, once looked up
+    /// (see [`enrich_with_graphql`](super::enrich_with_graphql)).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topics: Option<Vec<String>>,
+
+    /// Why the maintainers opted out of upgrade notices, if they did
+    /// (see [`find_opt_outs`](super::find_opt_outs)).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opted_out: Option<String>,
+
+    /// Custom values for this repository from `repo-data.toml`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub repo_data: BTreeMap<String, Value>,
+}
+
+/// A file in a repository that matched the code search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateLocation {
+    /// Path to the matched file.
+    pub path: String,
+
+    /// GitHub URL to the matched file.
+    pub url: HtmlUrl,
+}
+
+impl DiscoveredRepository {
+    /// Returns every matched location, starting with the primary match.
+    #[must_use]
+    pub fn locations(&self) -> Vec<TemplateLocation> {
+        let primary = TemplateLocation {
+            path: self.file_path.clone(),
+            url: self.file_url.clone(),
+        };
+        core::iter::once(primary)
+            .chain(self.additional_locations.iter().cloned())
+            .collect()
+    }
+
+    /// Returns true if the template was found in more than one location.
+    #[must_use]
+    pub fn has_multiple_locations(&self) -> bool {
+        !self.additional_locations.is_empty()
+    }
 }
@@ -0,0 +1,275 @@
+//! Paginated execution of GitHub code searches.
+//!
+//! The first page reports how many pages there are; the remaining pages are
+//! then fetched a few at a time and yielded in page order, so callers can
+//! start working on early results while later pages are still in flight.
+//!
+//! GitHub serves at most [`MAX_SEARCH_RESULTS`] results per query. A query
+//! matching more is split into `size:` ranges, each small enough to be read
+//! in full, so popular templates don't silently lose repositories.
+
+use super::DiscoveryError;
+use crate::ids::{HtmlUrl, RepoSlug};
+use crate::rate_limit::ensure_search_rate_limit;
+use futures::stream::{self, Stream, StreamExt};
+use octocrab::models::Code;
+use octocrab::{Octocrab, Page};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Maximum results from GitHub Code Search API.
+const MAX_SEARCH_RESULTS: u32 = 1000;
+
+/// Results per page for code search.
+const RESULTS_PER_PAGE: u8 = 100;
+
+/// Largest file, in bytes, GitHub indexes for code search.
+const MAX_INDEXED_FILE_SIZE: u64 = 384 * 1024;
+
+/// Most search requests spent on finding `size:` ranges under the result
+/// cap for one query, which bounds how many ranges a query is split into.
+const MAX_SPLIT_REQUESTS: usize = 40;
+
+/// Maximum number of result pages requested at the same time.
+///
+/// Kept small because GitHub's secondary rate limits penalise bursts of
+/// search requests.
+const MAX_CONCURRENT_PAGES: usize = 3;
+
+/// Intermediate search result before deduplication.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct CodeSearchResult {
+    pub(super) owner: String,
+    pub(super) name: String,
+    pub(super) full_name: RepoSlug,
+    pub(super) file_path: String,
+    pub(super) file_url: HtmlUrl,
+    pub(super) owner_is_bot: bool,
+    pub(super) archived: bool,
+    pub(super) disabled: bool,
+}
+
+/// Executes the code search, yielding the results of each page in page order.
+///
+/// A query matching more than [`MAX_SEARCH_RESULTS`] files is first split
+/// into `size:` ranges (see [`split_by_size`]), which are then read one after
+/// another, smallest files first. Pages after the first of each range are
+/// fetched concurrently, bounded by [`MAX_CONCURRENT_PAGES`], each after
+/// checking the search rate limit. The stream ends after the first error.
+pub(super) fn execute_code_search(
+    octocrab: &Octocrab,
+    query: String,
+) -> impl Stream<Item = Result<Vec<CodeSearchResult>, DiscoveryError>> + '_ {
+    stream::once(split_by_size(octocrab, query.into()))
+        .flat_map(move |slices| match slices {
+            Ok(slices) => stream::iter(slices)
+                .flat_map(move |slice| slice_pages(octocrab, slice))
+                .left_stream(),
+            Err(e) => stream::once(async move { Err(e) }).right_stream(),
+        })
+        .scan(false, |failed, page| {
+            // Stop after the first error rather than skipping a page silently.
+            let item = (!*failed).then(|| {
+                *failed = page.is_err();
+                page.map(|page| extract_search_results(&page))
+            });
+            async move { item }
+        })
+}
+
+/// One `size:` range of a split search, or the whole search, with its first page.
+struct Slice {
+    query: Arc<str>,
+    first: Page<Code>,
+}
+
+/// Yields the first page of `slice`, then its remaining pages in order.
+fn slice_pages(
+    octocrab: &Octocrab,
+    slice: Slice,
+) -> impl Stream<Item = Result<Page<Code>, DiscoveryError>> + '_ {
+    let pages = page_count(&slice.first);
+    debug!(pages, "Fetching remaining code search pages");
+    let query = slice.query;
+    let rest = stream::iter(2..=pages)
+        .map(move |number| fetch_page(octocrab, Arc::clone(&query), number))
+        .buffered(MAX_CONCURRENT_PAGES);
+    stream::once(async move { Ok(slice.first) }).chain(rest)
+}
+
+/// Splits `query` into `size:` ranges matching at most
+/// [`MAX_SEARCH_RESULTS`] files each, if it matches more.
+///
+/// Ranges over the cap are halved (geometrically while they are wide, since
+/// template files are small) until they fit, a range is a single size, or
+/// [`MAX_SPLIT_REQUESTS`] would be exceeded; ranges matching nothing are
+/// dropped. Queries that already filter by `size:` are not split. Hitting
+/// the cap logs a warning with how many of the matches the ranges reach.
+async fn split_by_size(octocrab: &Octocrab, query: Arc<str>) -> Result<Vec<Slice>, DiscoveryError> {
+    let first = fetch_page(octocrab, Arc::clone(&query), 1).await?;
+    let total = total_count(&first);
+    if total <= u64::from(MAX_SEARCH_RESULTS) {
+        return Ok(vec![Slice { query, first }]);
+    }
+    if query.contains("size:") {
+        log_coverage(&query, total, u64::from(MAX_SEARCH_RESULTS), 1);
+        return Ok(vec![Slice { query, first }]);
+    }
+
+    let mut slices = Vec::new();
+    let mut pending = vec![(0, MAX_INDEXED_FILE_SIZE)];
+    let mut requests = 0;
+    while let Some((low, high)) = pending.pop() {
+        let sliced: Arc<str> = format!("{query} size:{low}..{high}").into();
+        let page = fetch_page(octocrab, Arc::clone(&sliced), 1).await?;
+        requests += 1;
+        let count = total_count(&page);
+        let affordable = requests + pending.len() + 2 <= MAX_SPLIT_REQUESTS;
+        if count > u64::from(MAX_SEARCH_RESULTS) && low < high && affordable {
+            let middle = split_point(low, high);
+            // Popped last-in first-out, so smaller sizes are read first.
+            pending.push((middle + 1, high));
+            pending.push((low, middle));
+        } else if count > 0 {
+            slices.push(Slice {
+                query: sliced,
+                first: page,
+            });
+        }
+    }
+    let reachable = slices
+        .iter()
+        .map(|slice| total_count(&slice.first).min(u64::from(MAX_SEARCH_RESULTS)))
+        .sum();
+    log_coverage(&query, total, reachable, slices.len());
+    Ok(slices)
+}
+
+/// Returns where to split the `low..=high` size range: the geometric mean
+/// while the range spans more than a factor of two, else the midpoint.
+fn split_point(low: u64, high: u64) -> u64 {
+    let middle = if high > 2 * low.max(1) {
+        ((low.max(1) as f64) * (high as f64)).sqrt() as u64
+    } else {
+        low + (high - low) / 2
+    };
+    middle.clamp(low, high - 1)
+}
+
+/// Logs how many of a query's `total` matches its `slices` can reach,
+/// warning only when some of them are out of reach.
+fn log_coverage(query: &str, total: u64, reachable: u64, slices: usize) {
+    let coverage = coverage_percent(total, reachable);
+    if reachable < total {
+        warn!(
+            query,
+            total,
+            reachable,
+            slices,
+            coverage = format!("{coverage:.1}%"),
+            "Code search exceeds the {MAX_SEARCH_RESULTS}-result cap; some repositories will be missed"
+        );
+    } else {
+        info!(
+            query,
+            total,
+            slices,
+            coverage = format!("{coverage:.1}%"),
+            "Code search exceeds the {MAX_SEARCH_RESULTS}-result cap; split by file size"
+        );
+    }
+}
+
+/// Returns the share of `total` matches that `reachable` covers, at most 100%.
+fn coverage_percent(total: u64, reachable: u64) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    (reachable.min(total) as f64 / total as f64) * 100.0
+}
+
+/// Returns how many files GitHub reports the query matches.
+fn total_count(page: &Page<Code>) -> u64 {
+    page.total_count.unwrap_or(page.items.len() as u64)
+}
+
+/// Fetches one page of search results.
+async fn fetch_page(
+    octocrab: &Octocrab,
+    query: Arc<str>,
+    number: u32,
+) -> Result<Page<Code>, DiscoveryError> {
+    ensure_search_rate_limit(octocrab).await?;
+    Ok(octocrab
+        .search()
+        .code(&query)
+        .per_page(RESULTS_PER_PAGE)
+        .page(number)
+        .send()
+        .await?)
+}
+
+/// Returns how many pages to fetch, capped at the search API's result limit.
+///
+/// Queries over the limit are reported by [`split_by_size`].
+fn page_count(first: &Page<Code>) -> u32 {
+    let max_pages = MAX_SEARCH_RESULTS / u32::from(RESULTS_PER_PAGE);
+    first.number_of_pages().unwrap_or(1).clamp(1, max_pages)
+}
+
+/// Extracts search results from a search response page.
+///
+/// Results whose repository name is not a valid `owner/name` slug are
+/// dropped with a warning.
+fn extract_search_results(page: &Page<Code>) -> Vec<CodeSearchResult> {
+    page.items
+        .iter()
+        .filter_map(|item| {
+            let repo = &item.repository;
+            let owner_account = repo.owner.as_ref()?;
+            let owner = owner_account.login.clone();
+            let name = repo.name.clone();
+            let full_name = match RepoSlug::from_parts(&owner, &name) {
+                Ok(full_name) => full_name,
+                Err(e) => {
+                    warn!(error = %e, "Skipping search result with an invalid repository name");
+                    return None;
+                }
+            };
+
+            Some(CodeSearchResult {
+                owner,
+                name,
+                full_name,
+                file_path: item.path.clone(),
+                file_url: HtmlUrl::from(item.html_url.clone()),
+                owner_is_bot: owner_account.r#type == "Bot",
+                archived: repo.archived.unwrap_or(false),
+                disabled: repo.disabled.unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_wide_ranges_geometrically() {
+        assert_eq!(split_point(0, MAX_INDEXED_FILE_SIZE), 627);
+        assert_eq!(split_point(0, 627), 25);
+        assert_eq!(split_point(26, 627), 127);
+        assert_eq!(split_point(40, 60), 50);
+        assert_eq!(split_point(7, 8), 7);
+        assert_eq!(split_point(0, 1), 0);
+    }
+
+    #[test]
+    fn reports_coverage() {
+        assert_eq!(coverage_percent(2_000, 1_500), 75.0);
+        assert_eq!(coverage_percent(2_000, 2_100), 100.0);
+        assert_eq!(coverage_percent(0, 0), 100.0);
+    }
+}
@@ -0,0 +1,80 @@
+//! Before/after excerpts of the lines a migration changes.
+
+use serde::Serialize;
+
+/// Lines of unchanged context shown around the changed lines.
+const CONTEXT_LINES: usize = 1;
+
+/// Most lines shown in a snippet, so long files stay readable in an issue.
+const MAX_LINES: usize = 10;
+
+/// The lines of a file that contain `old_string`, before and after replacing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileSnippet {
+    /// The lines as they are now.
+    pub before: String,
+    /// The same lines with `old_string` replaced by `new_string`.
+    pub after: String,
+}
+
+impl FileSnippet {
+    /// Extracts the lines from the first to the last one containing `old`,
+    /// with one line of context and at most `MAX_LINES` lines.
+    ///
+    /// Returns `None` if `old` does not occur literally, as with answers files
+    /// matched by version range.
+    #[must_use]
+    pub fn extract(content: &str, old: &str, new: &str) -> Option<Self> {
+        if old.is_empty() {
+            return None;
+        }
+        let lines: Vec<&str> = content.lines().collect();
+        let first = lines.iter().position(|line| line.contains(old))?;
+        let last = lines.iter().rposition(|line| line.contains(old))?;
+
+        let start = first.saturating_sub(CONTEXT_LINES);
+        let end = (last + CONTEXT_LINES + 1)
+            .min(lines.len())
+            .min(start + MAX_LINES);
+        let before = lines[start..end].join("\n");
+        let after = before.replace(old, new);
+        Some(Self { before, after })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_changed_lines_with_context() {
+        let content =
+            "[template]\nname = \"app\"\nversion = \"1.0\"\nowner = \"me\"\nlicense = \"MIT\"\n";
+
+        let snippet = FileSnippet::extract(content, "1.0", "2.0").unwrap();
+
+        assert_eq!(
+            snippet.before,
+            "name = \"app\"\nversion = \"1.0\"\nowner = \"me\""
+        );
+        assert_eq!(
+            snippet.after,
+            "name = \"app\"\nversion = \"2.0\"\nowner = \"me\""
+        );
+    }
+
+    #[test]
+    fn caps_long_snippets() {
+        let content = format!("v1\n{}v1\n", "filler\n".repeat(20));
+
+        let snippet = FileSnippet::extract(&content, "v1", "v2").unwrap();
+
+        assert_eq!(snippet.before.lines().count(), MAX_LINES);
+        assert!(snippet.after.starts_with("v2\n"));
+    }
+
+    #[test]
+    fn none_without_literal_match() {
+        assert!(FileSnippet::extract("_commit: v1.2.0", "v1.0.0", "v2.0.0").is_none());
+    }
+}
@@ -0,0 +1,264 @@
+//! Confirmation that a search match is still present on the default branch.
+
+use super::{DiscoveredRepository, DiscoveryError, FileSnippet};
+use crate::config::{Migration, VersionSource};
+use crate::http_cache::{get_json, route, Contents};
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use tracing::debug;
+
+/// Skip reason recorded when the default branch no longer contains `old_string`.
+///
+/// Superseded by [`ALREADY_UPGRADED_REASON`]; kept for callers matching
+/// reports written by earlier versions.
+pub const STALE_SEARCH_RESULT_REASON: &str = "stale search result";
+
+/// Skip reason recorded when the default branch no longer contains
+/// `old_string`: the search result was stale because the repository upgraded
+/// after it was indexed.
+pub const ALREADY_UPGRADED_REASON: &str = "already upgraded";
+
+/// Checks that the matched file still contains the migration's `old_string`
+/// at the head of the default branch.
+///
+/// The code search index lags behind pushes, so a repository may have
+/// upgraded since it was indexed. A file that no longer exists counts as
+/// upgraded.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if the file contents cannot be fetched.
+pub async fn verify_old_string(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+) -> Result<bool, DiscoveryError> {
+    Ok(detect_template_version(octocrab, repository, migration)
+        .await?
+        .is_some())
+}
+
+/// Returns the template version the repository is on at the head of the
+/// default branch, if it is still the migration's `old_string`.
+///
+/// A migration's `version_pattern` captures the version from the matched
+/// line. Otherwise, for [`VersionSource::Text`] that is `old_string` itself;
+/// answers files (copier, cruft) yield the version they record, which may be
+/// a `git describe` version past the `old_string` tag, or any version within
+/// the migration's `old_version_range`. `None` means the repository no
+/// longer needs the migration, as for [`verify_old_string`].
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if the file contents cannot be fetched.
+pub async fn detect_template_version(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+) -> Result<Option<String>, DiscoveryError> {
+    Ok(verify_on_default_branch(octocrab, repository, migration)
+        .await?
+        .and_then(|repository| repository.template_version))
+}
+
+/// Re-reads the matched file from the default branch and returns the
+/// repository with its detected template version and a before/after
+/// [`FileSnippet`] of the lines that will change.
+///
+/// Like [`detect_template_version`], `None` means the repository no longer
+/// needs the migration. Only one request is made for both.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if the file contents cannot be fetched.
+pub async fn verify_on_default_branch(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+) -> Result<Option<DiscoveredRepository>, DiscoveryError> {
+    debug!(
+        repo = %repository.full_name,
+        path = %repository.file_path,
+        "Verifying old string on default branch"
+    );
+    let Some(content) = fetch_default_branch_file(octocrab, repository).await? else {
+        return Ok(None);
+    };
+    Ok(
+        version_in(&content, migration).map(|version| DiscoveredRepository {
+            template_version: Some(version),
+            snippet: FileSnippet::extract(&content, &migration.old_string, &migration.new_string),
+            ..repository.clone()
+        }),
+    )
+}
+
+/// Returns the outdated template version recorded in `content`, if any.
+///
+/// The version is the one captured by the migration's `version_pattern`,
+/// else the one read by its `version_source`, else `old_string`.
+pub(crate) fn version_in(content: &str, migration: &Migration) -> Option<String> {
+    let source = migration.version_source;
+    let outdated = match &migration.old_version_range {
+        Some(range) => source.records_in_range(content, range),
+        None => source.records(content, &migration.old_string),
+    };
+    outdated.then(|| {
+        migration
+            .extract_version(content)
+            .or_else(|| source.read_version(content))
+            .unwrap_or_else(|| migration.old_string.clone())
+    })
+}
+
+/// Checks that the matched file contains the migration's `new_string` and no
+/// longer contains `old_string` at the head of the default branch.
+///
+/// # Errors
+///
+/// Returns [`DiscoveryError`] if the file contents cannot be fetched.
+pub async fn verify_upgraded(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+) -> Result<bool, DiscoveryError> {
+    debug!(
+        repo = %repository.full_name,
+        path = %repository.file_path,
+        "Verifying upgrade on default branch"
+    );
+    Ok(fetch_default_branch_file(octocrab, repository)
+        .await?
+        .is_some_and(|content| is_upgraded(&content, migration)))
+}
+
+/// Returns true if `content` has `new_string` and no `old_string` outside of it.
+///
+/// Occurrences of `new_string` are removed first, since a new version often
+/// extends the old one (`v1.0` -> `v1.0.1`). Answers files only need to
+/// record `new_string`.
+fn is_upgraded(content: &str, migration: &Migration) -> bool {
+    if migration.version_source != VersionSource::Text {
+        return migration
+            .version_source
+            .records(content, &migration.new_string);
+    }
+    content.contains(&migration.new_string)
+        && !content
+            .replace(&migration.new_string, "")
+            .contains(&migration.old_string)
+}
+
+/// Fetches the matched file from the default branch; `None` if it no longer exists.
+pub(crate) async fn fetch_default_branch_file(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+) -> Result<Option<String>, DiscoveryError> {
+    fetch_file(
+        octocrab,
+        &repository.owner,
+        &repository.name,
+        &repository.file_path,
+    )
+    .await
+}
+
+/// Fetches `path` from the default branch of `owner/name`; `None` if it does
+/// not exist.
+pub(crate) async fn fetch_file(
+    octocrab: &Octocrab,
+    owner: &str,
+    name: &str,
+    path: &str,
+) -> Result<Option<String>, DiscoveryError> {
+    fetch_file_at(octocrab, owner, name, path, None).await
+}
+
+/// Fetches `path` from `git_ref` of `owner/name`, or from its default branch
+/// without one; `None` if it does not exist.
+pub(crate) async fn fetch_file_at(
+    octocrab: &Octocrab,
+    owner: &str,
+    name: &str,
+    path: &str,
+    git_ref: Option<&str>,
+) -> Result<Option<String>, DiscoveryError> {
+    ensure_core_rate_limit(octocrab).await?;
+
+    // Without a ref, the contents API reads the default branch.
+    let mut route = route(
+        ["repos", owner, name, "contents"]
+            .into_iter()
+            .chain(path.split('/')),
+    );
+    if let Some(git_ref) = git_ref {
+        route.push_str("?ref=");
+        route.extend(url::form_urlencoded::byte_serialize(git_ref.as_bytes()));
+    }
+    let result = get_json::<Contents>(octocrab, &route).await;
+    let contents = match result {
+        Ok(contents) => contents,
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(contents.decoded_content())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        default_branch_name_format, default_commit_title_format, default_issue_title_format,
+        default_pr_title_format,
+    };
+
+    fn migration(old_string: &str, new_string: &str) -> Migration {
+        Migration {
+            id: "test".parse().unwrap(),
+            old_string: old_string.to_string(),
+            new_string: new_string.to_string(),
+            migration_guide_link: None,
+            target_file: "version.txt".to_string(),
+            issue_template: String::new(),
+            pr_template: String::new(),
+            issue_title_format: default_issue_title_format(),
+            pr_title_format: default_pr_title_format(),
+            branch_name_format: default_branch_name_format(),
+            commit_title_format: default_commit_title_format(),
+            ..Migration::default()
+        }
+    }
+
+    #[test]
+    fn upgraded_when_only_new_string_remains() {
+        assert!(is_upgraded("version = v2", &migration("v1", "v2")));
+        assert!(!is_upgraded("version = v1", &migration("v1", "v2")));
+        assert!(!is_upgraded("a = v1\nb = v2", &migration("v1", "v2")));
+    }
+
+    #[test]
+    fn copier_upgraded_when_answers_record_new_version() {
+        let migration = Migration {
+            version_source: VersionSource::Copier,
+            ..migration("v1.0", "v1.1")
+        };
+        assert!(is_upgraded("_commit: v1.1\n", &migration));
+        assert!(is_upgraded("_commit: v1.1-2-gabc1234\n", &migration));
+        assert!(!is_upgraded("_commit: v1.0\n# v1.1\n", &migration));
+    }
+
+    #[test]
+    fn new_string_extending_old_string_counts_as_upgraded() {
+        assert!(is_upgraded(
+            "version = v1.0.1",
+            &migration("v1.0", "v1.0.1")
+        ));
+        assert!(!is_upgraded(
+            "v1.0.1 and v1.0",
+            &migration("v1.0", "v1.0.1")
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+//! `[dispatch]` settings in `metadata.toml`.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest `event_type` GitHub accepts for a `repository_dispatch` event.
+pub(super) const MAX_EVENT_TYPE_CHARS: usize = 100;
+
+/// How a migration sends `repository_dispatch` events to outdated repositories.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DispatchConfig {
+    /// Event type consumers filter on in `on: repository_dispatch: types: [...]`.
+    #[serde(default = "default_event_type")]
+    pub event_type: String,
+
+    /// Handlebars template rendering the `client_payload` JSON object; the
+    /// migration's fields are sent as-is when unset.
+    #[serde(default)]
+    pub payload_template: Option<String>,
+
+    /// Whether the event replaces the upgrade issue or is sent after it.
+    #[serde(default)]
+    pub mode: DispatchMode,
+}
+
+impl Default for DispatchConfig {
+    fn default() -> Self {
+        Self {
+            event_type: default_event_type(),
+            payload_template: None,
+            mode: DispatchMode::default(),
+        }
+    }
+}
+
+impl DispatchConfig {
+    /// Returns what is wrong with the settings, if anything.
+    #[must_use]
+    pub fn problem(&self) -> Option<String> {
+        if self.event_type.trim().is_empty() {
+            return Some("event-type must not be empty".to_string());
+        }
+        if self.event_type.chars().count() > MAX_EVENT_TYPE_CHARS {
+            return Some(format!(
+                "event-type must be at most {MAX_EVENT_TYPE_CHARS} characters"
+            ));
+        }
+        let template = self.payload_template.as_deref()?;
+        handlebars::Template::compile(template)
+            .err()
+            .map(|e| format!("payload-template is not a valid Handlebars template: {e}"))
+    }
+}
+
+/// Whether a `repository_dispatch` event replaces the upgrade issue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DispatchMode {
+    /// The issue (and any auto-fix PR) is created as usual, then the event is
+    /// sent with the issue's URL.
+    #[default]
+    Alongside,
+    /// Only the event is sent; no issue or PR is created.
+    Instead,
+}
+
+fn default_event_type() -> String {
+    "template-upgrade".to_string()
+}
@@ -0,0 +1,37 @@
+//! Repository dispatch error types.
+
+use crate::error_kind::ErrorKind;
+use thiserror::Error;
+
+/// Errors that can occur while sending a `repository_dispatch` event.
+#[derive(Debug, Error)]
+pub enum DispatchError {
+    /// GitHub API error.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+
+    /// The payload template failed to render.
+    #[error("Payload template rendering error: {0}")]
+    RenderError(#[from] handlebars::RenderError),
+
+    /// The rendered payload is not a JSON object GitHub accepts.
+    #[error("Invalid dispatch payload: {0}")]
+    InvalidPayload(String),
+}
+
+impl DispatchError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::GitHubError(e) => ErrorKind::of_github(e),
+            Self::RenderError(_) | Self::InvalidPayload(_) => ErrorKind::Validation,
+        }
+    }
+
+    /// Returns true if sending the event again later may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
@@ -0,0 +1,232 @@
+//! `repository_dispatch` events to outdated repositories.
+//!
+//! Instead of, or besides, the upgrade issue, a migration with `[dispatch]`
+//! sends each outdated repository a `repository_dispatch` event carrying the
+//! migration. Consumers with a workflow on that event type run their own
+//! upgrade automation, with their own tooling and permissions.
+
+mod config;
+mod error;
+
+pub use config::{DispatchConfig, DispatchMode};
+pub use error::DispatchError;
+
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::http_cache::route;
+use crate::rate_limit::ensure_core_rate_limit;
+use handlebars::Handlebars;
+use octocrab::Octocrab;
+use serde_json::{json, Value};
+use tracing::info;
+
+/// Most top-level properties GitHub accepts in a `client_payload`.
+const MAX_PAYLOAD_PROPERTIES: usize = 10;
+
+/// Sends the migration's `repository_dispatch` event to the repository.
+///
+/// `issue_url` is included in the payload when the event follows an issue.
+///
+/// # Errors
+///
+/// Returns [`DispatchError`] if the payload does not render to a JSON object
+/// GitHub accepts, or GitHub rejects the event (the token needs write access
+/// to the repository's contents).
+pub async fn send_dispatch(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+    config: &DispatchConfig,
+    issue_url: Option<&str>,
+) -> Result<(), DispatchError> {
+    let payload = render_payload(config, repository, migration, issue_url)?;
+    ensure_core_rate_limit(octocrab).await?;
+    let body = json!({
+        "event_type": config.event_type,
+        "client_payload": payload,
+    });
+    let route = route(["repos", &repository.owner, &repository.name, "dispatches"]);
+    // GitHub answers with an empty 204, so there is no body to deserialize.
+    let response = octocrab._post(route, Some(&body)).await?;
+    octocrab::map_github_error(response).await?;
+    info!(
+        repo = %repository.full_name,
+        event_type = %config.event_type,
+        "Sent repository dispatch event"
+    );
+    Ok(())
+}
+
+/// Renders the `client_payload` sent to the repository.
+///
+/// Without a `payload_template`, the payload is the template variables
+/// themselves. Values substituted into a template are JSON-escaped, so they
+/// can be placed inside string literals.
+///
+/// Available variables: `migration_id`, `old_string`, `new_string`,
+/// `target_files`, `migration_guide_link`, `repository`, `file_path`,
+/// `template_version` and `issue_url`.
+///
+/// # Errors
+///
+/// Returns [`DispatchError::RenderError`] if the template fails to render, or
+/// [`DispatchError::InvalidPayload`] if the result is not a JSON object of at
+/// most ten properties.
+pub fn render_payload(
+    config: &DispatchConfig,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+    issue_url: Option<&str>,
+) -> Result<Value, DispatchError> {
+    let data = json!({
+        "migration_id": migration.id,
+        "old_string": migration.old_string,
+        "new_string": migration.new_string,
+        "target_files": migration.target_files(),
+        "migration_guide_link": migration.migration_guide_link,
+        "repository": repository.full_name,
+        "file_path": repository.file_path,
+        "template_version": repository.template_version,
+        "issue_url": issue_url,
+    });
+    let payload = match &config.payload_template {
+        Some(template) => {
+            let mut hbs = Handlebars::new();
+            hbs.register_escape_fn(escape_json);
+            let rendered = hbs.render_template(template, &data)?;
+            serde_json::from_str(&rendered).map_err(|e| {
+                DispatchError::InvalidPayload(format!("payload-template is not JSON: {e}"))
+            })?
+        }
+        None => data,
+    };
+    let Value::Object(properties) = &payload else {
+        return Err(DispatchError::InvalidPayload(
+            "payload must be a JSON object".to_string(),
+        ));
+    };
+    if properties.len() > MAX_PAYLOAD_PROPERTIES {
+        return Err(DispatchError::InvalidPayload(format!(
+            "payload has {} top-level properties, GitHub accepts at most {MAX_PAYLOAD_PROPERTIES}",
+            properties.len()
+        )));
+    }
+    Ok(payload)
+}
+
+/// Escapes a substituted value for use inside a JSON string literal.
+fn escape_json(value: &str) -> String {
+    let quoted = Value::from(value).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository() -> DiscoveredRepository {
+        DiscoveredRepository {
+            owner: "owner".to_string(),
+            name: "repo".to_string(),
+            full_name: "owner/repo".parse().unwrap(),
+            file_path: "template-version.txt".to_string(),
+            file_url: "https://github.com/owner/repo/blob/main/template-version.txt"
+                .parse()
+                .unwrap(),
+            default_branch: "main".to_string(),
+            template_version: Some("1.0.0".to_string()),
+            additional_locations: Vec::new(),
+            snippet: None,
+            previously_notified: false,
+            previous_issue_url: None,
+            owner_is_bot: false,
+            is_mirror: false,
+            archived: false,
+            disabled: false,
+            visibility: None,
+            fork_ancestors: Vec::new(),
+            file_size: None,
+            topics: None,
+            opted_out: None,
+            repo_data: Default::default(),
+        }
+    }
+
+    fn migration() -> Migration {
+        Migration {
+            id: "my-template/v1-to-v2".parse().unwrap(),
+            old_string: "my-template:1.0.0".to_string(),
+            new_string: "my-template:2.0.0".to_string(),
+            target_file: "template-version.txt".to_string(),
+            ..Migration::default()
+        }
+    }
+
+    fn config(payload_template: Option<&str>) -> DispatchConfig {
+        DispatchConfig {
+            payload_template: payload_template.map(str::to_string),
+            ..DispatchConfig::default()
+        }
+    }
+
+    #[test]
+    fn default_payload_describes_migration() {
+        let payload = render_payload(
+            &config(None),
+            &repository(),
+            &migration(),
+            Some("https://x"),
+        )
+        .unwrap();
+
+        assert_eq!(payload["migration_id"], "my-template/v1-to-v2");
+        assert_eq!(payload["new_string"], "my-template:2.0.0");
+        assert_eq!(payload["repository"], "owner/repo");
+        assert_eq!(payload["issue_url"], "https://x");
+    }
+
+    #[test]
+    fn renders_payload_template_with_escaped_values() {
+        let mut migration = migration();
+        migration.new_string = "say \"hi\"".to_string();
+        let template = r#"{"to": "{{new_string}}", "repo": "{{repository}}"}"#;
+
+        let payload =
+            render_payload(&config(Some(template)), &repository(), &migration, None).unwrap();
+
+        assert_eq!(payload, json!({"to": "say \"hi\"", "repo": "owner/repo"}));
+    }
+
+    #[test]
+    fn rejects_payloads_github_would_refuse() {
+        let render = |template: &str| {
+            render_payload(&config(Some(template)), &repository(), &migration(), None)
+        };
+
+        assert!(matches!(
+            render("not json"),
+            Err(DispatchError::InvalidPayload(_))
+        ));
+        assert!(matches!(
+            render("[1, 2]"),
+            Err(DispatchError::InvalidPayload(_))
+        ));
+        let eleven: Vec<String> = (0..11).map(|i| format!("\"k{i}\": {i}")).collect();
+        assert!(matches!(
+            render(&format!("{{{}}}", eleven.join(", "))),
+            Err(DispatchError::InvalidPayload(_))
+        ));
+    }
+
+    #[test]
+    fn validates_settings() {
+        assert_eq!(DispatchConfig::default().problem(), None);
+        let event_type = |event_type: &str| DispatchConfig {
+            event_type: event_type.to_string(),
+            ..DispatchConfig::default()
+        };
+        assert!(event_type("").problem().is_some());
+        assert!(event_type(&"a".repeat(101)).problem().is_some());
+        assert!(config(Some("{{#if}}")).problem().is_some());
+    }
+}
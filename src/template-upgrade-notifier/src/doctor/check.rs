@@ -0,0 +1,87 @@
+//! Preflight check result types.
+
+use serde::Serialize;
+
+/// Outcome of a single preflight check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// The check passed.
+    Pass,
+    /// The run can proceed, but something may not work as expected.
+    Warn,
+    /// The run is expected to fail.
+    Fail,
+}
+
+impl CheckStatus {
+    /// Returns a short uppercase label for display.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+/// Result of a single preflight check.
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    /// Short name of what was checked.
+    pub name: &'static str,
+
+    /// Outcome of the check.
+    pub status: CheckStatus,
+
+    /// Actionable description of the outcome.
+    pub message: String,
+}
+
+impl DoctorCheck {
+    pub(crate) fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn warn(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn fail(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            message: message.into(),
+        }
+    }
+}
+
+/// Results of all preflight checks.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DoctorReport {
+    /// Individual check results, in execution order.
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// Returns true if any check failed.
+    #[must_use]
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    /// Returns true if any check produced a warning.
+    #[must_use]
+    pub fn has_warnings(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Warn)
+    }
+}
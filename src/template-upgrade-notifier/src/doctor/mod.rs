@@ -0,0 +1,264 @@
+//! Environment preflight checks (`doctor`).
+//!
+//! Verifies the GitHub token, local tooling, LLM configuration and rate limit
+//! headroom before a run, so misconfiguration surfaces as one actionable
+//! message instead of hundreds of per-repository failures.
+
+mod check;
+
+pub use check::{CheckStatus, DoctorCheck, DoctorReport};
+
+use crate::config::{scan_migrations, scan_migrations_strict};
+use crate::rate_limit::{check_core_rate_limit, check_search_rate_limit, RateLimitInfo};
+use crate::runner::RunnerConfig;
+use octocrab::Octocrab;
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Remaining-quota fraction below which rate limit headroom is reported as low.
+const LOW_HEADROOM_RATIO: f64 = 0.1;
+
+/// Runs all preflight checks for the given configuration.
+///
+/// Checks never abort early; every problem is reported in one pass.
+pub async fn run_doctor(octocrab: &Octocrab, config: &RunnerConfig) -> DoctorReport {
+    let mut report = DoctorReport::default();
+    report
+        .checks
+        .push(check_token(octocrab, config.auto_pr()).await);
+    report.checks.push(check_git().await);
+    report.checks.push(check_llm(config));
+    report.checks.push(check_rate_limits(octocrab).await);
+    report.checks.push(check_migrations(config));
+    report
+}
+
+/// Verifies the token authenticates and carries the scopes a run needs.
+async fn check_token(octocrab: &Octocrab, auto_pr: bool) -> DoctorCheck {
+    const NAME: &str = "GitHub token";
+
+    let response = match octocrab._get("/user").await {
+        Ok(response) => response,
+        Err(e) => return DoctorCheck::fail(NAME, format!("Failed to reach the GitHub API: {e}")),
+    };
+
+    let status = response.status();
+    if status.as_u16() == 401 {
+        return DoctorCheck::fail(
+            NAME,
+            "Token was rejected (401); check that it is valid and has not expired",
+        );
+    }
+    if !status.is_success() {
+        return DoctorCheck::fail(NAME, format!("GitHub returned {status} for /user"));
+    }
+
+    let scopes = response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|v| v.to_str().ok());
+    evaluate_scopes(scopes, auto_pr)
+}
+
+/// Classifies the `X-OAuth-Scopes` header of a classic token.
+///
+/// Fine-grained and app tokens don't report scopes, so they can only be warned about.
+fn evaluate_scopes(scopes: Option<&str>, auto_pr: bool) -> DoctorCheck {
+    const NAME: &str = "GitHub token";
+
+    let Some(scopes) = scopes else {
+        return DoctorCheck::warn(
+            NAME,
+            "Token authenticated but does not report scopes (fine-grained or app token); \
+             ensure it has read/write access to Issues, Contents and Pull requests",
+        );
+    };
+
+    let scopes: Vec<&str> = scopes.split(',').map(str::trim).collect();
+    let has = |scope: &str| scopes.contains(&scope);
+
+    if !has("repo") && !has("public_repo") {
+        return DoctorCheck::fail(
+            NAME,
+            "Token is missing the `repo` scope; issues and PRs cannot be created",
+        );
+    }
+    if auto_pr && !has("workflow") {
+        return DoctorCheck::warn(
+            NAME,
+            "Token is missing the `workflow` scope; auto-PRs that touch .github/workflows will be rejected",
+        );
+    }
+    if !has("repo") {
+        return DoctorCheck::warn(
+            NAME,
+            "Token only has `public_repo`; private repositories will be skipped",
+        );
+    }
+    DoctorCheck::pass(NAME, format!("Scopes: {}", scopes.join(", ")))
+}
+
+/// Verifies `git` is available on `PATH`.
+async fn check_git() -> DoctorCheck {
+    const NAME: &str = "git";
+
+    let output = Command::new("git")
+        .arg("--version")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => DoctorCheck::pass(
+            NAME,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        Ok(output) => DoctorCheck::fail(
+            NAME,
+            format!(
+                "git --version failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ),
+        Err(e) => DoctorCheck::fail(NAME, format!("git is not available on PATH: {e}")),
+    }
+}
+
+/// Verifies the LLM model can be resolved when auto-PR is enabled.
+fn check_llm(config: &RunnerConfig) -> DoctorCheck {
+    const NAME: &str = "LLM config";
+
+    if !config.auto_pr() {
+        return DoctorCheck::pass(NAME, "Auto-PR disabled; no LLM required");
+    }
+    match crate::llm::check_config(config.llm_config_path()) {
+        Ok(source) => DoctorCheck::pass(NAME, format!("Model resolved from {source}")),
+        Err(e) => DoctorCheck::fail(NAME, e.to_string()),
+    }
+}
+
+/// Verifies there is rate limit headroom for a run.
+async fn check_rate_limits(octocrab: &Octocrab) -> DoctorCheck {
+    const NAME: &str = "Rate limits";
+
+    let core = check_core_rate_limit(octocrab).await;
+    let search = check_search_rate_limit(octocrab).await;
+    match (core, search) {
+        (Ok(core), Ok(search)) => evaluate_rate_limits(&core, &search),
+        (Err(e), _) | (_, Err(e)) => {
+            DoctorCheck::fail(NAME, format!("Failed to query rate limits: {e}"))
+        }
+    }
+}
+
+/// Classifies core and search rate limit headroom.
+fn evaluate_rate_limits(core: &RateLimitInfo, search: &RateLimitInfo) -> DoctorCheck {
+    const NAME: &str = "Rate limits";
+
+    let message = format!(
+        "core {}/{}, search {}/{}",
+        core.remaining, core.limit, search.remaining, search.limit
+    );
+    if core.remaining == 0 || search.remaining == 0 {
+        return DoctorCheck::fail(
+            NAME,
+            format!("{message}; quota exhausted, wait for the reset before running"),
+        );
+    }
+    let low = |info: &RateLimitInfo| {
+        info.limit > 0 && f64::from(info.remaining) < f64::from(info.limit) * LOW_HEADROOM_RATIO
+    };
+    if low(core) || low(search) {
+        return DoctorCheck::warn(NAME, format!("{message}; headroom is low"));
+    }
+    DoctorCheck::pass(NAME, message)
+}
+
+/// Verifies the migrations directory loads.
+fn check_migrations(config: &RunnerConfig) -> DoctorCheck {
+    const NAME: &str = "Migrations";
+
+    let scanned = if config.strict_migrations() {
+        scan_migrations_strict(config.migrations_path())
+    } else {
+        scan_migrations(config.migrations_path())
+    };
+    match scanned {
+        Ok(migrations) if migrations.is_empty() => DoctorCheck::warn(
+            NAME,
+            format!(
+                "No migrations found in {}",
+                config.migrations_path().display()
+            ),
+        ),
+        Ok(migrations) => DoctorCheck::pass(NAME, format!("{} loaded", migrations.len())),
+        Err(e) => DoctorCheck::fail(NAME, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(remaining: u32, limit: u32) -> RateLimitInfo {
+        RateLimitInfo {
+            remaining,
+            reset: 0,
+            limit,
+        }
+    }
+
+    #[test]
+    fn scopes_missing_repo_fails() {
+        let check = evaluate_scopes(Some("read:org, gist"), false);
+        assert_eq!(check.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn scopes_with_repo_pass() {
+        let check = evaluate_scopes(Some("repo, read:org"), false);
+        assert_eq!(check.status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn scopes_without_workflow_warn_for_auto_pr() {
+        let check = evaluate_scopes(Some("repo"), true);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.message.contains("workflow"));
+    }
+
+    #[test]
+    fn unreported_scopes_warn() {
+        let check = evaluate_scopes(None, false);
+        assert_eq!(check.status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn rate_limits_classified() {
+        assert_eq!(
+            evaluate_rate_limits(&info(4000, 5000), &info(30, 30)).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            evaluate_rate_limits(&info(100, 5000), &info(30, 30)).status,
+            CheckStatus::Warn
+        );
+        assert_eq!(
+            evaluate_rate_limits(&info(4000, 5000), &info(0, 30)).status,
+            CheckStatus::Fail
+        );
+    }
+
+    #[test]
+    fn report_flags_failures() {
+        let report = DoctorReport {
+            checks: vec![
+                DoctorCheck::pass("a", "ok"),
+                DoctorCheck::fail("b", "broken"),
+            ],
+        };
+        assert!(report.has_failures());
+        assert!(!report.has_warnings());
+    }
+}
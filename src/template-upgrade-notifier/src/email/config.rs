@@ -0,0 +1,97 @@
+//! SMTP digest configuration.
+
+use core::fmt;
+use serde::Deserialize;
+
+/// Environment variable holding the SMTP password.
+pub(crate) const SMTP_PASSWORD_ENV: &str = "TEMPLATE_UPGRADE_SMTP_PASSWORD";
+
+/// SMTP settings for the end-of-run email digest, read from the `[email]`
+/// section of `notifier.toml`.
+///
+/// The [`Debug`](fmt::Debug) output hides the password.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EmailConfig {
+    /// SMTP relay host name.
+    pub smtp_host: String,
+
+    /// SMTP port (defaults to 587).
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+
+    /// Connection security used to reach the relay.
+    #[serde(default)]
+    pub security: SmtpSecurity,
+
+    /// SMTP username (optional).
+    pub username: Option<String>,
+
+    /// SMTP password (optional, falls back to `TEMPLATE_UPGRADE_SMTP_PASSWORD`).
+    pub password: Option<String>,
+
+    /// Sender address (e.g., "Template Bot <bot@example.com>").
+    pub from: String,
+
+    /// Recipient addresses.
+    pub to: Vec<String>,
+
+    /// Subject line of the digest.
+    #[serde(default = "default_subject")]
+    pub subject: String,
+
+    /// Whether to also send the digest after dry runs.
+    #[serde(default)]
+    pub send_on_dry_run: bool,
+}
+
+/// Transport security for the SMTP connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS.
+    #[default]
+    Starttls,
+    /// Connect over implicit TLS (typically port 465).
+    Tls,
+    /// Plain, unencrypted connection. Only use for local relays.
+    None,
+}
+
+impl fmt::Debug for EmailConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("smtp_host", &self.smtp_host)
+            .field("smtp_port", &self.smtp_port)
+            .field("security", &self.security)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("subject", &self.subject)
+            .field("send_on_dry_run", &self.send_on_dry_run)
+            .finish()
+    }
+}
+
+impl EmailConfig {
+    /// Resolves the SMTP password, preferring the environment over the config file.
+    #[cfg(feature = "email")]
+    pub(crate) fn resolved_password(&self) -> Option<String> {
+        let password = std::env::var(SMTP_PASSWORD_ENV)
+            .ok()
+            .or_else(|| self.password.clone());
+        if let Some(password) = &password {
+            crate::redact::register_secret(password.as_str());
+        }
+        password
+    }
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_subject() -> String {
+    "Template upgrade run summary".to_string()
+}
@@ -0,0 +1,23 @@
+//! Email digest error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while sending the email digest.
+#[derive(Debug, Error)]
+pub enum EmailError {
+    /// An address in the configuration could not be parsed.
+    #[error("Invalid email address '{address}': {message}")]
+    InvalidAddress { address: String, message: String },
+
+    /// The message could not be assembled.
+    #[error("Failed to build email: {0}")]
+    Build(String),
+
+    /// The SMTP transport failed.
+    #[error("SMTP error: {0}")]
+    Smtp(String),
+
+    /// The crate was built without the `email` feature.
+    #[error("Email support is not enabled; rebuild with the `email` feature")]
+    Disabled,
+}
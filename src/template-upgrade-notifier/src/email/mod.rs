@@ -0,0 +1,147 @@
+//! Email digest notifications.
+//!
+//! Sends the markdown run report to a list of recipients over SMTP after
+//! each run, for teams that don't use chat webhooks. The transport is only
+//! compiled with the `email` feature; the configuration types are always
+//! available so `notifier.toml` parses identically either way.
+
+mod config;
+mod error;
+
+pub(crate) use config::SMTP_PASSWORD_ENV;
+pub use config::{EmailConfig, SmtpSecurity};
+pub use error::EmailError;
+
+use crate::summary::RunSummary;
+
+/// Sends the run summary to the configured recipients.
+///
+/// # Errors
+///
+/// Returns [`EmailError`] if an address is invalid or the SMTP relay rejects
+/// the message.
+#[cfg(feature = "email")]
+pub async fn send_email_digest(
+    config: &EmailConfig,
+    summary: &RunSummary,
+) -> Result<(), EmailError> {
+    use lettre::message::header::ContentType;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+    let parse = |address: &str| {
+        address
+            .parse::<lettre::message::Mailbox>()
+            .map_err(|e| EmailError::InvalidAddress {
+                address: address.to_string(),
+                message: e.to_string(),
+            })
+    };
+
+    let mut builder = Message::builder()
+        .from(parse(&config.from)?)
+        .subject(&config.subject)
+        .header(ContentType::TEXT_PLAIN);
+    for recipient in &config.to {
+        builder = builder.to(parse(recipient)?);
+    }
+    let message = builder
+        .body(summary.to_markdown())
+        .map_err(|e| EmailError::Build(e.to_string()))?;
+
+    let host = config.smtp_host.as_str();
+    let mut transport = match config.security {
+        SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            host,
+        )),
+    }
+    .map_err(|e| EmailError::Smtp(e.to_string()))?
+    .port(config.smtp_port);
+
+    if let (Some(username), Some(password)) = (&config.username, config.resolved_password()) {
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+
+    transport
+        .build()
+        .send(message)
+        .await
+        .map_err(|e| EmailError::Smtp(e.to_string()))?;
+    Ok(())
+}
+
+/// Sends the run summary to the configured recipients.
+///
+/// # Errors
+///
+/// Always returns [`EmailError::Disabled`]; enable the `email` feature to send mail.
+#[cfg(not(feature = "email"))]
+pub async fn send_email_digest(
+    _config: &EmailConfig,
+    _summary: &RunSummary,
+) -> Result<(), EmailError> {
+    Err(EmailError::Disabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_email_config_with_defaults() {
+        let config: EmailConfig = toml::from_str(
+            r#"
+smtp-host = "smtp.example.com"
+from = "bot@example.com"
+to = ["team@example.com"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.smtp_port, 587);
+        assert_eq!(config.security, SmtpSecurity::Starttls);
+        assert_eq!(config.subject, "Template upgrade run summary");
+        assert!(!config.send_on_dry_run);
+    }
+
+    #[test]
+    fn debug_output_hides_password() {
+        let mut config: EmailConfig = toml::from_str(
+            r#"
+smtp-host = "smtp.example.com"
+from = "bot@example.com"
+to = []
+"#,
+        )
+        .unwrap();
+        config.password = Some("hunter2".to_string());
+
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    #[cfg(feature = "email")]
+    fn password_env_overrides_config() {
+        let mut config: EmailConfig = toml::from_str(
+            r#"
+smtp-host = "smtp.example.com"
+security = "tls"
+from = "bot@example.com"
+to = []
+"#,
+        )
+        .unwrap();
+        config.password = Some("from-config".to_string());
+
+        temp_env::with_var(config::SMTP_PASSWORD_ENV, Some("from-env"), || {
+            assert_eq!(config.resolved_password().as_deref(), Some("from-env"));
+        });
+        temp_env::with_var_unset(config::SMTP_PASSWORD_ENV, || {
+            assert_eq!(config.resolved_password().as_deref(), Some("from-config"));
+        });
+    }
+}
@@ -0,0 +1,132 @@
+//! Shared classification of failures across discovery, issues and PRs.
+//!
+//! Each module's error type reports an [`ErrorKind`], so retry passes, exit
+//! codes and reports make the same call for the same failure instead of
+//! inspecting error messages.
+
+use serde::{Deserialize, Serialize};
+
+/// What kind of failure an error represents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorKind {
+    /// The token is missing, invalid, or lacks access (`401`, `403`).
+    Auth,
+    /// The repository, file or feature does not exist (`404`, `410`).
+    NotFound,
+    /// A primary or secondary rate limit was hit.
+    RateLimit,
+    /// A server error, dropped connection or timeout.
+    Transient,
+    /// The request or its input was rejected as invalid (`422`, bad templates).
+    Validation,
+    /// The run was cancelled.
+    Cancelled,
+    /// Anything else, including failures recorded before kinds were.
+    #[default]
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns true if retrying the operation later may succeed.
+    #[must_use]
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::RateLimit | Self::Transient)
+    }
+
+    /// Returns the kind as written in reports.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auth => "auth",
+            Self::NotFound => "not-found",
+            Self::RateLimit => "rate-limit",
+            Self::Transient => "transient",
+            Self::Validation => "validation",
+            Self::Cancelled => "cancelled",
+            Self::Other => "other",
+        }
+    }
+
+    /// Classifies an error returned by the GitHub client.
+    #[must_use]
+    pub fn of_github(error: &octocrab::Error) -> Self {
+        match error {
+            octocrab::Error::GitHub { source, .. } => {
+                Self::of_status(source.status_code.as_u16(), &source.message)
+            }
+            octocrab::Error::Hyper { .. } | octocrab::Error::Service { .. } => Self::Transient,
+            _ => Self::Other,
+        }
+    }
+
+    /// Classifies a GitHub API response by its status code.
+    ///
+    /// GitHub answers secondary rate limits with `403` too; those are told
+    /// apart from missing permissions by their message.
+    #[must_use]
+    pub fn of_status(status: u16, message: &str) -> Self {
+        match status {
+            403 if message.to_lowercase().contains("rate limit") => Self::RateLimit,
+            401 | 403 => Self::Auth,
+            404 | 410 => Self::NotFound,
+            422 => Self::Validation,
+            429 => Self::RateLimit,
+            500..=599 => Self::Transient,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_statuses() {
+        assert_eq!(
+            ErrorKind::of_status(401, "Bad credentials"),
+            ErrorKind::Auth
+        );
+        assert_eq!(
+            ErrorKind::of_status(403, "Resource not accessible by integration"),
+            ErrorKind::Auth
+        );
+        assert_eq!(
+            ErrorKind::of_status(403, "You have exceeded a secondary rate limit"),
+            ErrorKind::RateLimit
+        );
+        assert_eq!(ErrorKind::of_status(404, "Not Found"), ErrorKind::NotFound);
+        assert_eq!(
+            ErrorKind::of_status(422, "Validation Failed"),
+            ErrorKind::Validation
+        );
+        assert_eq!(ErrorKind::of_status(429, ""), ErrorKind::RateLimit);
+        assert_eq!(
+            ErrorKind::of_status(502, "Bad Gateway"),
+            ErrorKind::Transient
+        );
+        assert_eq!(ErrorKind::of_status(418, ""), ErrorKind::Other);
+    }
+
+    #[test]
+    fn only_rate_limits_and_transient_errors_are_retryable() {
+        assert!(ErrorKind::RateLimit.is_retryable());
+        assert!(ErrorKind::Transient.is_retryable());
+        for kind in [
+            ErrorKind::Auth,
+            ErrorKind::NotFound,
+            ErrorKind::Validation,
+            ErrorKind::Cancelled,
+            ErrorKind::Other,
+        ] {
+            assert!(!kind.is_retryable(), "{kind} is retryable");
+        }
+    }
+}
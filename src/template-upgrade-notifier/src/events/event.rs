@@ -0,0 +1,74 @@
+//! Progress events emitted during a run.
+
+use crate::error_kind::ErrorKind;
+use serde::Serialize;
+
+/// A per-repository progress event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunEvent {
+    /// A migration is about to be processed.
+    MigrationStarted {
+        /// Migration identifier.
+        migration_id: String,
+    },
+
+    /// A repository containing the old string was found.
+    Discovered {
+        /// Migration identifier.
+        migration_id: String,
+        /// Repository full name.
+        repository: String,
+    },
+
+    /// An upgrade issue was created.
+    IssueCreated {
+        /// Migration identifier.
+        migration_id: String,
+        /// Repository full name.
+        repository: String,
+        /// GitHub issue number.
+        number: u64,
+        /// GitHub issue URL.
+        url: String,
+    },
+
+    /// An auto-fix PR was created.
+    PrCreated {
+        /// Migration identifier.
+        migration_id: String,
+        /// Repository full name.
+        repository: String,
+        /// GitHub PR number.
+        number: u64,
+        /// GitHub PR URL.
+        url: String,
+    },
+
+    /// An open issue or PR was closed because the repository upgraded on its own.
+    Resolved {
+        /// Migration identifier.
+        migration_id: String,
+        /// Repository full name.
+        repository: String,
+        /// GitHub issue or PR number.
+        number: u64,
+    },
+
+    /// Processing a repository failed.
+    ///
+    /// Retryable failures may be followed by further events for the same
+    /// repository once it is retried.
+    Failed {
+        /// Migration identifier.
+        migration_id: String,
+        /// Repository full name.
+        repository: String,
+        /// Error message.
+        error: String,
+        /// What kind of failure it was.
+        kind: ErrorKind,
+        /// Whether the failure was transient and may be retried.
+        retryable: bool,
+    },
+}
@@ -0,0 +1,88 @@
+//! Real-time progress events for library consumers.
+//!
+//! Register an [`EventHandler`] on a [`Runner`](crate::Runner) to observe
+//! per-repository progress while a run is in flight, rather than only the
+//! final [`RunSummary`](crate::RunSummary). Closures and unbounded Tokio
+//! channel senders implement the trait, so events can be consumed as a stream.
+
+mod event;
+
+pub use event::RunEvent;
+
+/// Receives progress events as they happen.
+///
+/// Handlers are called synchronously from the run and must not block; hand
+/// slow work off to a channel or task.
+pub trait EventHandler: Send + Sync {
+    /// Handles a single event.
+    fn handle(&self, event: &RunEvent);
+}
+
+impl<F> EventHandler for F
+where
+    F: Fn(&RunEvent) + Send + Sync,
+{
+    fn handle(&self, event: &RunEvent) {
+        self(event);
+    }
+}
+
+impl EventHandler for tokio::sync::mpsc::UnboundedSender<RunEvent> {
+    fn handle(&self, event: &RunEvent) {
+        // A dropped receiver means nobody is listening any more.
+        let _ = self.send(event.clone());
+    }
+}
+
+/// The event handlers registered on a runner.
+#[derive(Default)]
+pub(crate) struct EventHandlers {
+    handlers: Vec<Box<dyn EventHandler>>,
+}
+
+impl EventHandlers {
+    /// Registers an additional handler.
+    pub(crate) fn push(&mut self, handler: Box<dyn EventHandler>) {
+        self.handlers.push(handler);
+    }
+
+    /// Sends an event to every handler in registration order.
+    ///
+    /// The event is only built when at least one handler is registered.
+    pub(crate) fn emit(&self, event: impl FnOnce() -> RunEvent) {
+        if self.handlers.is_empty() {
+            return;
+        }
+        let event = event();
+        for handler in &self.handlers {
+            handler.handle(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started() -> RunEvent {
+        RunEvent::MigrationStarted {
+            migration_id: "test-migration".to_string(),
+        }
+    }
+
+    #[test]
+    fn forwards_events_to_channel() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut handlers = EventHandlers::default();
+        handlers.push(Box::new(tx));
+
+        handlers.emit(started);
+
+        assert_eq!(rx.try_recv().unwrap(), started());
+    }
+
+    #[test]
+    fn skips_building_event_without_handlers() {
+        EventHandlers::default().emit(|| unreachable!());
+    }
+}
@@ -0,0 +1,64 @@
+//! [`Forge`] implementation for Azure DevOps.
+
+use super::{Forge, ForgeError};
+use crate::azure_devops::{AzureDevOpsClient, AzureRepository};
+use crate::config::Migration;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::rate_limit::RateLimitInfo;
+use crate::templates::TemplateRenderer;
+use futures::future::BoxFuture;
+
+impl Forge for AzureDevOpsClient {
+    type Repository = AzureRepository;
+
+    fn name(&self) -> &'static str {
+        "Azure DevOps"
+    }
+
+    fn full_name<'r>(&self, repository: &'r AzureRepository) -> &'r str {
+        &repository.full_name
+    }
+
+    fn discover<'a>(
+        &'a self,
+        migration: &'a Migration,
+    ) -> BoxFuture<'a, Result<Vec<AzureRepository>, ForgeError>> {
+        Box::pin(async move { Ok(self.discover_repositories(migration).await?) })
+    }
+
+    /// Files the notification as a work item, since Azure Repos has no
+    /// issues of its own.
+    fn create_issue<'a>(
+        &'a self,
+        repository: &'a AzureRepository,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+    ) -> BoxFuture<'a, Result<IssueStatus, ForgeError>> {
+        Box::pin(async move {
+            Ok(self
+                .create_work_item(repository, migration, renderer)
+                .await?)
+        })
+    }
+
+    fn create_merge_request<'a>(
+        &'a self,
+        repository: &'a AzureRepository,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+        issue: Option<u64>,
+    ) -> BoxFuture<'a, Result<PrStatus, ForgeError>> {
+        Box::pin(async move {
+            Ok(self
+                .create_pull_request(repository, migration, renderer, issue)
+                .await?)
+        })
+    }
+
+    /// Azure DevOps throttles by delaying requests rather than reporting a
+    /// limit, so there is none to return.
+    fn rate_limit(&self) -> BoxFuture<'_, Result<Option<RateLimitInfo>, ForgeError>> {
+        Box::pin(async { Ok(None) })
+    }
+}
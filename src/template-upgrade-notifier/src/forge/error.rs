@@ -0,0 +1,63 @@
+//! Forge error types.
+
+use crate::azure_devops::AzureDevOpsError;
+use crate::discovery::DiscoveryError;
+use crate::error_kind::ErrorKind;
+use crate::gitlab::GitLabError;
+use crate::issues::IssueError;
+use crate::pull_requests::PrError;
+use thiserror::Error;
+
+/// Errors from any [`Forge`](super::Forge) implementation.
+#[derive(Debug, Error)]
+pub enum ForgeError {
+    /// GitHub API error outside discovery, issues and PRs.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+
+    /// GitHub discovery failed.
+    #[error(transparent)]
+    Discovery(#[from] DiscoveryError),
+
+    /// Creating a GitHub issue failed.
+    #[error(transparent)]
+    Issue(#[from] IssueError),
+
+    /// Creating a GitHub pull request failed.
+    #[error(transparent)]
+    PullRequest(#[from] PrError),
+
+    /// A GitLab request failed.
+    #[error(transparent)]
+    GitLab(#[from] GitLabError),
+
+    /// An Azure DevOps request failed.
+    #[error(transparent)]
+    AzureDevOps(#[from] AzureDevOpsError),
+
+    /// A title or body template failed to render.
+    #[error("Template error: {0}")]
+    TemplateError(String),
+}
+
+impl ForgeError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::GitHubError(e) => ErrorKind::of_github(e),
+            Self::Discovery(e) => e.kind(),
+            Self::Issue(e) => e.kind(),
+            Self::PullRequest(e) => e.kind(),
+            Self::GitLab(e) => e.kind(),
+            Self::AzureDevOps(e) => e.kind(),
+            Self::TemplateError(_) => ErrorKind::Validation,
+        }
+    }
+
+    /// Returns true if retrying the operation later may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
@@ -0,0 +1,93 @@
+//! [`Forge`] implementation for GitHub.
+
+use super::{with_closing_reference, Forge, ForgeError};
+use crate::config::Migration;
+use crate::discovery::{discover_repositories_graphql, DiscoveredRepository};
+use crate::issues::{create_issue, IssueStatus};
+use crate::pull_requests::{create_github_pr, PrStatus};
+use crate::rate_limit::{check_core_rate_limit, RateLimitInfo};
+use crate::templates::{generate_branch_name, generate_pr_title, TemplateRenderer};
+use futures::future::BoxFuture;
+use octocrab::Octocrab;
+
+/// GitHub, through an authenticated client.
+#[derive(Debug, Clone)]
+pub struct GitHubForge {
+    octocrab: Octocrab,
+}
+
+impl GitHubForge {
+    /// Creates a forge using `octocrab` for every request.
+    #[must_use]
+    pub fn new(octocrab: Octocrab) -> Self {
+        Self { octocrab }
+    }
+
+    /// Returns the underlying GitHub client.
+    #[must_use]
+    pub fn octocrab(&self) -> &Octocrab {
+        &self.octocrab
+    }
+}
+
+impl Forge for GitHubForge {
+    type Repository = DiscoveredRepository;
+
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    fn full_name<'r>(&self, repository: &'r DiscoveredRepository) -> &'r str {
+        &repository.full_name
+    }
+
+    fn discover<'a>(
+        &'a self,
+        migration: &'a Migration,
+    ) -> BoxFuture<'a, Result<Vec<DiscoveredRepository>, ForgeError>> {
+        Box::pin(async move { Ok(discover_repositories_graphql(&self.octocrab, migration).await?) })
+    }
+
+    fn create_issue<'a>(
+        &'a self,
+        repository: &'a DiscoveredRepository,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+    ) -> BoxFuture<'a, Result<IssueStatus, ForgeError>> {
+        Box::pin(async move {
+            let issue =
+                create_issue(&self.octocrab, repository, migration, renderer, None, None).await?;
+            Ok(issue.status)
+        })
+    }
+
+    fn create_merge_request<'a>(
+        &'a self,
+        repository: &'a DiscoveredRepository,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+        issue: Option<u64>,
+    ) -> BoxFuture<'a, Result<PrStatus, ForgeError>> {
+        Box::pin(async move {
+            let template_error =
+                |e: crate::templates::TemplateError| ForgeError::TemplateError(e.to_string());
+            let branch = generate_branch_name(migration).map_err(template_error)?;
+            let title = generate_pr_title(migration).map_err(template_error)?;
+            let body = renderer
+                .render_pr_template(
+                    &migration.pr_template,
+                    migration,
+                    repository.template_version.as_deref(),
+                )
+                .map_err(template_error)?;
+            let body = with_closing_reference(body, issue);
+            let (number, url) =
+                create_github_pr(&self.octocrab, repository, &branch, &title, &body).await?;
+            Ok(PrStatus::Created { number, url })
+        })
+    }
+
+    fn rate_limit(&self) -> BoxFuture<'_, Result<Option<RateLimitInfo>, ForgeError>> {
+        Box::pin(async move { Ok(Some(check_core_rate_limit(&self.octocrab).await?)) })
+    }
+}
@@ -0,0 +1,61 @@
+//! [`Forge`] implementation for GitLab.
+
+use super::{Forge, ForgeError};
+use crate::config::Migration;
+use crate::gitlab::{GitLabClient, GitLabProject};
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::rate_limit::RateLimitInfo;
+use crate::templates::TemplateRenderer;
+use futures::future::BoxFuture;
+
+impl Forge for GitLabClient {
+    type Repository = GitLabProject;
+
+    fn name(&self) -> &'static str {
+        "GitLab"
+    }
+
+    fn full_name<'r>(&self, repository: &'r GitLabProject) -> &'r str {
+        &repository.path_with_namespace
+    }
+
+    fn discover<'a>(
+        &'a self,
+        migration: &'a Migration,
+    ) -> BoxFuture<'a, Result<Vec<GitLabProject>, ForgeError>> {
+        Box::pin(async move { Ok(self.discover_projects(migration).await?) })
+    }
+
+    fn create_issue<'a>(
+        &'a self,
+        repository: &'a GitLabProject,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+    ) -> BoxFuture<'a, Result<IssueStatus, ForgeError>> {
+        Box::pin(async move {
+            Ok(GitLabClient::create_issue(self, repository, migration, renderer).await?)
+        })
+    }
+
+    fn create_merge_request<'a>(
+        &'a self,
+        repository: &'a GitLabProject,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+        issue: Option<u64>,
+    ) -> BoxFuture<'a, Result<PrStatus, ForgeError>> {
+        Box::pin(async move {
+            Ok(
+                GitLabClient::create_merge_request(self, repository, migration, renderer, issue)
+                    .await?,
+            )
+        })
+    }
+
+    /// GitLab reports its rate limit on every response rather than through
+    /// an endpoint, so this is the limit reported by the last request.
+    fn rate_limit(&self) -> BoxFuture<'_, Result<Option<RateLimitInfo>, ForgeError>> {
+        Box::pin(async move { Ok(self.last_rate_limit()) })
+    }
+}
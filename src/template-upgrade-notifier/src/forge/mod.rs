@@ -0,0 +1,96 @@
+//! A common interface over the code hosts the notifier can target.
+//!
+//! [`Forge`] covers what a notification campaign needs from a host: finding
+//! repositories with the outdated template, filing the notification issue,
+//! opening the upgrade merge request and reporting the rate limit.
+//! [`GitHubForge`] wraps the GitHub functions the [`Runner`](crate::Runner)
+//! uses; `GitLabClient` implements it for GitLab with the `gitlab` feature
+//! and `AzureDevOpsClient` for Azure DevOps with the `azure-devops` feature.
+//!
+//! Merge requests are opened from a branch that has already been pushed; the
+//! clone-edit-push flow of [`create_pr`](crate::pull_requests::create_pr)
+//! remains GitHub-only.
+
+#[cfg(feature = "azure-devops")]
+mod azure_devops;
+mod error;
+mod github;
+#[cfg(feature = "gitlab")]
+mod gitlab;
+
+pub use error::ForgeError;
+pub use github::GitHubForge;
+
+use crate::config::Migration;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::rate_limit::RateLimitInfo;
+use crate::templates::TemplateRenderer;
+use futures::future::BoxFuture;
+
+/// A code host that repositories can be discovered, notified and upgraded on.
+pub trait Forge: Send + Sync {
+    /// A repository found by [`discover`](Self::discover).
+    type Repository: Send + Sync;
+
+    /// Returns the host's display name, e.g. "GitHub".
+    fn name(&self) -> &'static str;
+
+    /// Returns the repository's full name, e.g. "owner/repo".
+    fn full_name<'r>(&self, repository: &'r Self::Repository) -> &'r str;
+
+    /// Finds the repositories whose target files still contain the
+    /// migration's `old_string`.
+    fn discover<'a>(
+        &'a self,
+        migration: &'a Migration,
+    ) -> BoxFuture<'a, Result<Vec<Self::Repository>, ForgeError>>;
+
+    /// Files the upgrade notification issue in `repository`, skipping it if
+    /// an open notification already exists or access is denied.
+    fn create_issue<'a>(
+        &'a self,
+        repository: &'a Self::Repository,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+    ) -> BoxFuture<'a, Result<IssueStatus, ForgeError>>;
+
+    /// Opens a merge request from the migration's already pushed branch into
+    /// the default branch, closing (or, on Azure DevOps, linking) issue
+    /// `issue` if given.
+    fn create_merge_request<'a>(
+        &'a self,
+        repository: &'a Self::Repository,
+        migration: &'a Migration,
+        renderer: &'a TemplateRenderer,
+        issue: Option<u64>,
+    ) -> BoxFuture<'a, Result<PrStatus, ForgeError>>;
+
+    /// Returns the current rate limit for regular API requests, or `None` if
+    /// the host has not reported one.
+    fn rate_limit(&self) -> BoxFuture<'_, Result<Option<RateLimitInfo>, ForgeError>>;
+}
+
+/// Appends a reference that closes `issue` when the merge request merges.
+///
+/// GitHub and GitLab both recognize `Closes #N` in the description.
+pub(crate) fn with_closing_reference(mut body: String, issue: Option<u64>) -> String {
+    if let Some(number) = issue {
+        body.push_str(&format!("\n\nCloses #{number}"));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_closing_reference() {
+        assert_eq!(
+            with_closing_reference("Body".to_string(), Some(7)),
+            "Body\n\nCloses #7"
+        );
+        assert_eq!(with_closing_reference("Body".to_string(), None), "Body");
+    }
+}
@@ -0,0 +1,412 @@
+//! REST client for GitLab projects, issues and merge requests.
+
+use super::{GitLabConfig, GitLabError, GitLabProject};
+use crate::config::Migration;
+use crate::forge::with_closing_reference;
+use crate::ids::HtmlUrl;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::rate_limit::{wait_if_needed, RateLimitInfo};
+use crate::summary::PERMISSION_DENIED_REASON;
+use crate::templates::{
+    generate_branch_name, generate_issue_title, generate_pr_title, TemplateRenderer,
+};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError};
+use tracing::{debug, info, warn};
+
+/// Results per page of list requests; the API's maximum.
+const PAGE_SIZE: usize = 100;
+
+/// Projects whose files are read at the same time.
+const FILE_CONCURRENCY: usize = 5;
+
+/// GitLab REST client authenticated with an access token.
+#[derive(Debug, Clone)]
+pub struct GitLabClient {
+    http: Client,
+    config: GitLabConfig,
+    token: String,
+    /// Rate limit reported by the last response, if it reported one.
+    rate_limit: Arc<Mutex<Option<RateLimitInfo>>>,
+}
+
+#[derive(Deserialize)]
+struct ApiProject {
+    id: u64,
+    path_with_namespace: String,
+    web_url: String,
+    /// `None` for empty repositories.
+    default_branch: Option<String>,
+    #[serde(default)]
+    archived: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiIssue {
+    iid: u64,
+    title: String,
+    web_url: String,
+}
+
+#[derive(Deserialize)]
+struct ApiMergeRequest {
+    iid: u64,
+    web_url: String,
+}
+
+impl GitLabClient {
+    /// Creates a client for the configured instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GitLabError::MissingToken`] if no token is configured.
+    pub fn new(config: GitLabConfig) -> Result<Self, GitLabError> {
+        let token = config.resolved_token().ok_or(GitLabError::MissingToken)?;
+        Ok(Self {
+            http: Client::new(),
+            config,
+            token,
+            rate_limit: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Returns the rate limit reported by the most recent response.
+    ///
+    /// `None` until a request has been made, or if the instance does not
+    /// report rate limits.
+    #[must_use]
+    pub fn last_rate_limit(&self) -> Option<RateLimitInfo> {
+        self.rate_limit
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Finds projects containing the migration's `old_string` in any of its
+    /// target files on their default branch.
+    ///
+    /// GitLab only offers code search across groups with Advanced Search, so
+    /// the projects of every configured group (including subgroups) and every
+    /// configured project are listed, and their target files read directly.
+    /// Archived and empty projects are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GitLabError`] if listing projects or reading a file fails.
+    pub async fn discover_projects(
+        &self,
+        migration: &Migration,
+    ) -> Result<Vec<GitLabProject>, GitLabError> {
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        for group in &self.config.groups {
+            let url = format!(
+                "{}/groups/{}/projects?include_subgroups=true&archived=false",
+                self.api_url(),
+                encode(group)
+            );
+            for project in self.get_all::<ApiProject>(&url).await? {
+                if seen.insert(project.id) {
+                    candidates.push(project);
+                }
+            }
+        }
+        for path in &self.config.projects {
+            let url = format!("{}/projects/{}", self.api_url(), encode(path));
+            let project: ApiProject = self.send(self.http.get(url)).await?;
+            if seen.insert(project.id) {
+                candidates.push(project);
+            }
+        }
+        candidates.retain(|project| !project.archived && project.default_branch.is_some());
+        debug!(count = candidates.len(), "Checking GitLab projects");
+
+        let target_files = migration.target_files();
+        let found: Vec<Option<GitLabProject>> = stream::iter(candidates)
+            .map(|project| self.find_match(project, &target_files, &migration.old_string))
+            .buffered(FILE_CONCURRENCY)
+            .try_collect()
+            .await?;
+        let projects: Vec<GitLabProject> = found.into_iter().flatten().collect();
+
+        info!(count = projects.len(), "GitLab discovery complete");
+        Ok(projects)
+    }
+
+    /// Files the upgrade notification as an issue in the project.
+    ///
+    /// An open issue with the same title counts as a duplicate and the
+    /// project is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GitLabError`] if the duplicate check or creation fails,
+    /// except for missing permissions, which yield a skipped status.
+    pub async fn create_issue(
+        &self,
+        project: &GitLabProject,
+        migration: &Migration,
+        renderer: &TemplateRenderer,
+    ) -> Result<IssueStatus, GitLabError> {
+        let title = generate_issue_title(migration)
+            .map_err(|e| GitLabError::TemplateError(e.to_string()))?;
+
+        if let Some(existing) = self.find_open_issue(project, &title).await? {
+            info!(issue = existing, "Duplicate issue exists, skipping");
+            return Ok(IssueStatus::Skipped {
+                reason: format!("duplicate issue exists (#{existing})"),
+            });
+        }
+
+        let description = renderer
+            .render_issue_template(&migration.issue_template, migration, None, None, None)
+            .map_err(|e| GitLabError::TemplateError(e.to_string()))?;
+        let body = json!({
+            "title": title,
+            "description": description,
+            "labels": self.config.labels.join(","),
+        });
+        let url = format!("{}/projects/{}/issues", self.api_url(), project.id);
+
+        match self.send::<ApiIssue>(self.http.post(url).json(&body)).await {
+            Ok(issue) => {
+                let url =
+                    HtmlUrl::new(issue.web_url).map_err(|e| GitLabError::Request(e.to_string()))?;
+                info!(issue = issue.iid, "Issue created successfully");
+                Ok(IssueStatus::Created {
+                    number: issue.iid,
+                    url,
+                })
+            }
+            Err(e) if e.is_permission_denied() => {
+                warn!(repo = %project.path_with_namespace, "Permission denied, skipping project");
+                Ok(IssueStatus::Skipped {
+                    reason: PERMISSION_DENIED_REASON.to_string(),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Opens a merge request from the migration's branch into the project's
+    /// default branch, optionally closing the notification issue on merge.
+    ///
+    /// The branch (named by the migration's `branch_name_format`) must already
+    /// have been pushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GitLabError`] if a template fails to render or the API
+    /// rejects the merge request.
+    pub async fn create_merge_request(
+        &self,
+        project: &GitLabProject,
+        migration: &Migration,
+        renderer: &TemplateRenderer,
+        issue: Option<u64>,
+    ) -> Result<PrStatus, GitLabError> {
+        let template_error =
+            |e: crate::templates::TemplateError| GitLabError::TemplateError(e.to_string());
+        let branch = generate_branch_name(migration).map_err(template_error)?;
+        let title = generate_pr_title(migration).map_err(template_error)?;
+        let description = renderer
+            .render_pr_template(&migration.pr_template, migration, None)
+            .map_err(template_error)?;
+        let description = with_closing_reference(description, issue);
+
+        let body = json!({
+            "source_branch": branch,
+            "target_branch": project.default_branch,
+            "title": title,
+            "description": description,
+            "remove_source_branch": true,
+        });
+        let url = format!("{}/projects/{}/merge_requests", self.api_url(), project.id);
+
+        let mr: ApiMergeRequest = self.send(self.http.post(url).json(&body)).await?;
+        info!(mr_number = mr.iid, "Merge request created successfully");
+        let url = HtmlUrl::new(mr.web_url).map_err(|e| GitLabError::Request(e.to_string()))?;
+        Ok(PrStatus::Created {
+            number: mr.iid,
+            url,
+        })
+    }
+
+    /// Returns the project if any of `target_files` on its default branch
+    /// contains `old_string`; the first such file is recorded.
+    async fn find_match(
+        &self,
+        project: ApiProject,
+        target_files: &[&str],
+        old_string: &str,
+    ) -> Result<Option<GitLabProject>, GitLabError> {
+        let Some(default_branch) = project.default_branch else {
+            return Ok(None);
+        };
+        for file in target_files {
+            let url = format!(
+                "{}/projects/{}/repository/files/{}/raw?ref={}",
+                self.api_url(),
+                project.id,
+                encode(file),
+                encode(&default_branch)
+            );
+            let content = match self.send_text(self.http.get(url)).await {
+                Ok(content) => content,
+                Err(GitLabError::Api { status: 404, .. }) => continue,
+                Err(e) => return Err(e),
+            };
+            if content.contains(old_string) {
+                return Ok(Some(GitLabProject {
+                    id: project.id,
+                    path_with_namespace: project.path_with_namespace,
+                    web_url: project.web_url,
+                    file_path: (*file).to_string(),
+                    default_branch,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the iid of an open issue titled exactly `title`, if any.
+    async fn find_open_issue(
+        &self,
+        project: &GitLabProject,
+        title: &str,
+    ) -> Result<Option<u64>, GitLabError> {
+        let url = format!(
+            "{}/projects/{}/issues?state=opened&in=title&search={}&per_page={PAGE_SIZE}",
+            self.api_url(),
+            project.id,
+            encode(title)
+        );
+        let issues: Vec<ApiIssue> = self.send(self.http.get(url)).await?;
+        Ok(issues
+            .into_iter()
+            .find(|issue| issue.title == title)
+            .map(|issue| issue.iid))
+    }
+
+    /// Fetches every page of a list endpoint, following `x-next-page`.
+    async fn get_all<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>, GitLabError> {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+        let mut page = "1".to_string();
+        loop {
+            let request = self
+                .http
+                .get(format!("{url}{separator}per_page={PAGE_SIZE}&page={page}"));
+            let response = self.execute(request).await?;
+            let next = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|value| value.to_str().ok())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string);
+            let batch: Vec<T> = response.json().await.map_err(request_error)?;
+            items.extend(batch);
+            match next {
+                Some(next) => page = next,
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// Sends an authenticated request and parses the JSON response.
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, GitLabError> {
+        let response = self.execute(request).await?;
+        response.json().await.map_err(request_error)
+    }
+
+    /// Sends an authenticated request and returns the response body as text.
+    async fn send_text(&self, request: RequestBuilder) -> Result<String, GitLabError> {
+        let response = self.execute(request).await?;
+        response.text().await.map_err(request_error)
+    }
+
+    /// Sends an authenticated request, waiting first if the last response
+    /// reported the rate limit nearly used up, and fails on error statuses.
+    async fn execute(&self, request: RequestBuilder) -> Result<Response, GitLabError> {
+        if let Some(info) = self.last_rate_limit() {
+            wait_if_needed(&info).await;
+        }
+        let response = request
+            .header("PRIVATE-TOKEN", &self.token)
+            .send()
+            .await
+            .map_err(request_error)?;
+        if let Some(info) = parse_rate_limit(response.headers()) {
+            *self
+                .rate_limit
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(info);
+        }
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(GitLabError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        Ok(response)
+    }
+
+    /// Returns the base URL of the REST API.
+    fn api_url(&self) -> String {
+        format!("{}/api/v4", self.config.base_url.trim_end_matches('/'))
+    }
+}
+
+/// Reads GitLab's `RateLimit-*` response headers.
+fn parse_rate_limit(headers: &HeaderMap) -> Option<RateLimitInfo> {
+    let number = |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+    Some(RateLimitInfo {
+        remaining: u32::try_from(number("ratelimit-remaining")?).unwrap_or(u32::MAX),
+        reset: number("ratelimit-reset")?,
+        limit: u32::try_from(number("ratelimit-limit")?).unwrap_or(u32::MAX),
+    })
+}
+
+/// Percent-encodes a path or id for use as one URL path segment or query value.
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+fn request_error(e: reqwest::Error) -> GitLabError {
+    GitLabError::Request(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_project_paths() {
+        assert_eq!(encode("my-group/sub/app"), "my-group%2Fsub%2Fapp");
+        assert_eq!(encode(".github/version.txt"), ".github%2Fversion.txt");
+    }
+
+    #[test]
+    fn parses_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("ratelimit-remaining", "12".parse().unwrap());
+        headers.insert("ratelimit-reset", "1700000000".parse().unwrap());
+        headers.insert("ratelimit-limit", "2000".parse().unwrap());
+
+        let info = parse_rate_limit(&headers).unwrap();
+
+        assert_eq!(info.remaining, 12);
+        assert_eq!(info.reset, 1_700_000_000);
+        assert_eq!(info.limit, 2000);
+        assert!(parse_rate_limit(&HeaderMap::new()).is_none());
+    }
+}
@@ -0,0 +1,64 @@
+//! GitLab connection settings.
+
+use serde::Deserialize;
+
+/// Environment variable holding the GitLab personal or group access token.
+pub(crate) const GITLAB_TOKEN_ENV: &str = "GITLAB_TOKEN";
+
+/// Settings for a GitLab instance.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GitLabConfig {
+    /// Groups whose projects, including those of subgroups, are checked.
+    #[serde(default)]
+    pub groups: Vec<String>,
+
+    /// Additional projects to check, as `namespace/project` paths.
+    #[serde(default)]
+    pub projects: Vec<String>,
+
+    /// Labels added to every issue filed by the notifier.
+    #[serde(default = "default_labels")]
+    pub labels: Vec<String>,
+
+    /// Access token (optional, falls back to `GITLAB_TOKEN`).
+    pub token: Option<String>,
+
+    /// Base URL of the instance (defaults to `https://gitlab.com`).
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+}
+
+impl GitLabConfig {
+    /// Creates settings checking the projects of `groups`, with every other
+    /// field at its default.
+    pub fn new(groups: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            groups: groups.into_iter().map(Into::into).collect(),
+            projects: Vec::new(),
+            labels: default_labels(),
+            token: None,
+            base_url: default_base_url(),
+        }
+    }
+
+    /// Resolves the access token, preferring the environment over the config.
+    #[cfg(feature = "gitlab")]
+    pub(crate) fn resolved_token(&self) -> Option<String> {
+        let token = std::env::var(GITLAB_TOKEN_ENV)
+            .ok()
+            .or_else(|| self.token.clone());
+        if let Some(token) = &token {
+            crate::redact::register_secret(token.as_str());
+        }
+        token
+    }
+}
+
+fn default_labels() -> Vec<String> {
+    vec!["template-upgrade".to_string()]
+}
+
+fn default_base_url() -> String {
+    "https://gitlab.com".to_string()
+}
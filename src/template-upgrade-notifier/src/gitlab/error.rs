@@ -0,0 +1,54 @@
+//! GitLab error types.
+
+use crate::error_kind::ErrorKind;
+use thiserror::Error;
+
+/// Errors that can occur while talking to GitLab.
+#[derive(Debug, Error)]
+pub enum GitLabError {
+    /// No access token was configured.
+    #[error("No GitLab token; set GITLAB_TOKEN or `token`")]
+    MissingToken,
+
+    /// The request could not be sent or its response could not be read.
+    #[error("GitLab request failed: {0}")]
+    Request(String),
+
+    /// The API answered with an error status.
+    #[error("GitLab API error ({status}): {message}")]
+    Api { status: u16, message: String },
+
+    /// A title or body template failed to render.
+    #[error("Template error: {0}")]
+    TemplateError(String),
+
+    /// The crate was built without the `gitlab` feature.
+    #[error("GitLab support is not enabled; rebuild with the `gitlab` feature")]
+    Disabled,
+}
+
+impl GitLabError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::MissingToken => ErrorKind::Auth,
+            Self::Request(_) => ErrorKind::Transient,
+            Self::Api { status, message } => ErrorKind::of_status(*status, message),
+            Self::TemplateError(_) => ErrorKind::Validation,
+            Self::Disabled => ErrorKind::Other,
+        }
+    }
+
+    /// Returns true if the token lacks access to the project.
+    #[must_use]
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(
+            self,
+            Self::Api {
+                status: 401 | 403,
+                ..
+            }
+        )
+    }
+}
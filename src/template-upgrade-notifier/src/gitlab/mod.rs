@@ -0,0 +1,55 @@
+//! GitLab project support.
+//!
+//! Finds outdated templates in the projects of GitLab groups, files upgrade
+//! notifications as issues and opens merge requests. The REST client is only
+//! compiled with the `gitlab` feature; the configuration types are always
+//! available.
+
+#[cfg(feature = "gitlab")]
+mod client;
+mod config;
+mod error;
+mod project;
+
+#[cfg(feature = "gitlab")]
+pub use client::GitLabClient;
+pub use config::GitLabConfig;
+pub(crate) use config::GITLAB_TOKEN_ENV;
+pub use error::GitLabError;
+pub use project::GitLabProject;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config_with_defaults() {
+        let config: GitLabConfig = toml::from_str(
+            r#"
+groups = ["my-org/services"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.base_url, "https://gitlab.com");
+        assert_eq!(config.labels, ["template-upgrade"]);
+        assert!(config.projects.is_empty());
+        assert!(config.token.is_none());
+    }
+
+    #[test]
+    fn classifies_api_errors() {
+        let denied = GitLabError::Api {
+            status: 403,
+            message: String::new(),
+        };
+        assert!(denied.is_permission_denied());
+        assert_eq!(denied.kind(), crate::error_kind::ErrorKind::Auth);
+        let limited = GitLabError::Api {
+            status: 429,
+            message: String::new(),
+        };
+        assert_eq!(limited.kind(), crate::error_kind::ErrorKind::RateLimit);
+        assert!(!GitLabError::MissingToken.is_permission_denied());
+    }
+}
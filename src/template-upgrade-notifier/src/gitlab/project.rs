@@ -0,0 +1,22 @@
+//! GitLab project information.
+
+use serde::Serialize;
+
+/// A GitLab project found to contain an outdated template version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GitLabProject {
+    /// Numeric project id, used by the REST API.
+    pub id: u64,
+
+    /// Full project path in "namespace/project" format.
+    pub path_with_namespace: String,
+
+    /// Link to the project.
+    pub web_url: String,
+
+    /// Path to the file containing the match.
+    pub file_path: String,
+
+    /// Branch the match was found on (e.g., "main").
+    pub default_branch: String,
+}
@@ -0,0 +1,115 @@
+//! Comparison of two recorded runs.
+
+use super::ResultRecord;
+use crate::selection::PatternSet;
+use std::collections::HashSet;
+
+/// Differences between an older and a newer run, keyed by migration and repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunDiff {
+    /// Repositories found by the newer run but not the older one.
+    pub newly_discovered: Vec<ResultRecord>,
+    /// Repositories found by the older run that no longer match, i.e. upgraded
+    /// since, among those the newer run looked for.
+    pub upgraded: Vec<ResultRecord>,
+    /// Repositories found by both runs, whose issues are still outstanding.
+    pub outstanding: Vec<ResultRecord>,
+}
+
+impl RunDiff {
+    /// Compares the results of two runs.
+    ///
+    /// Results are matched on `(migration_id, repository)`; the order of each
+    /// list follows the order of the input it was taken from.
+    ///
+    /// `migrations` and `repositories` are the filters the newer run was
+    /// limited to. Older results outside them were not looked for again, so
+    /// they are left out instead of being reported as upgraded.
+    #[must_use]
+    pub fn between(
+        older: &[ResultRecord],
+        newer: &[ResultRecord],
+        migrations: &PatternSet,
+        repositories: &PatternSet,
+    ) -> Self {
+        let key = |r: &ResultRecord| (r.migration_id.clone(), r.repository.clone());
+        let older_keys: HashSet<_> = older.iter().map(key).collect();
+        let newer_keys: HashSet<_> = newer.iter().map(key).collect();
+
+        let (outstanding, newly_discovered) = newer
+            .iter()
+            .cloned()
+            .partition(|r| older_keys.contains(&key(r)));
+        let upgraded = older
+            .iter()
+            .filter(|r| migrations.matches(&r.migration_id) && repositories.matches(&r.repository))
+            .filter(|r| !newer_keys.contains(&key(r)))
+            .cloned()
+            .collect();
+
+        Self {
+            newly_discovered,
+            upgraded,
+            outstanding,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(migration_id: &str, repository: &str) -> ResultRecord {
+        ResultRecord {
+            migration_id: migration_id.to_string(),
+            repository: repository.to_string(),
+            outcome: "success".to_string(),
+            issue_status: Some("created".to_string()),
+            issue_url: None,
+            pr_status: None,
+            pr_url: None,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn classifies_repositories() {
+        let older = [record("m1", "a/kept"), record("m1", "a/upgraded")];
+        let newer = [
+            record("m1", "a/kept"),
+            record("m1", "a/new"),
+            record("m2", "a/upgraded"),
+        ];
+
+        let all = PatternSet::default();
+        let diff = RunDiff::between(&older, &newer, &all, &all);
+
+        let names = |records: &[ResultRecord]| {
+            records
+                .iter()
+                .map(|r| format!("{}:{}", r.migration_id, r.repository))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&diff.outstanding), ["m1:a/kept"]);
+        assert_eq!(names(&diff.newly_discovered), ["m1:a/new", "m2:a/upgraded"]);
+        assert_eq!(names(&diff.upgraded), ["m1:a/upgraded"]);
+    }
+
+    #[test]
+    fn leaves_out_results_outside_the_newer_runs_filters() {
+        let older = [
+            record("m1", "a/upgraded"),
+            record("m1", "b/unfiltered"),
+            record("m2", "a/unfiltered"),
+        ];
+
+        let diff = RunDiff::between(
+            &older,
+            &[],
+            &PatternSet::new(["m1"]).unwrap(),
+            &PatternSet::new(["a/*"]).unwrap(),
+        );
+
+        assert_eq!(diff.upgraded, [record("m1", "a/upgraded")]);
+    }
+}
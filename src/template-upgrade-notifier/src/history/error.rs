@@ -0,0 +1,19 @@
+//! Run history error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while reading or writing the run history database.
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    /// The database file could not be opened or initialised.
+    #[error("Failed to open history database '{path}': {source}")]
+    Open {
+        path: String,
+        #[source]
+        source: rusqlite::Error,
+    },
+
+    /// A query against the database failed.
+    #[error("History database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
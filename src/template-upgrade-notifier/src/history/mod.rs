@@ -0,0 +1,16 @@
+//! Local run history.
+//!
+//! Every completed run and its per-repository results are stored in a SQLite
+//! database so past campaigns can be inspected later, including which
+//! repositories were notified and when. Only compiled with the `history`
+//! feature.
+
+mod diff;
+mod error;
+mod record;
+mod store;
+
+pub use diff::RunDiff;
+pub use error::HistoryError;
+pub use record::{ResultRecord, RunRecord};
+pub use store::HistoryStore;
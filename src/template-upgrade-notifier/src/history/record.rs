@@ -0,0 +1,122 @@
+//! Rows read back from the run history database.
+
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::redact::redact;
+use crate::summary::ProcessingResult;
+
+/// A single recorded run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunRecord {
+    /// Database identifier of the run.
+    pub id: i64,
+    /// RFC 3339 timestamp of when the run was recorded.
+    pub recorded_at: String,
+    /// Whether the run was a dry run.
+    pub dry_run: bool,
+    /// Number of migrations processed.
+    pub migrations_processed: i64,
+    /// Number of repositories discovered.
+    pub repositories_discovered: i64,
+    /// Number of issues created.
+    pub issues_created: i64,
+    /// Number of issues skipped.
+    pub issues_skipped: i64,
+    /// Number of issues failed.
+    pub issues_failed: i64,
+    /// Number of PRs created.
+    pub prs_created: i64,
+    /// Number of PRs failed.
+    pub prs_failed: i64,
+    /// Glob patterns the run's migrations were limited to; empty if all ran.
+    pub only_migrations: Vec<String>,
+    /// Glob patterns the run's repositories were limited to; empty if all ran.
+    pub only_repos: Vec<String>,
+}
+
+/// The outcome for one repository within a recorded run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultRecord {
+    /// Migration identifier the repository was processed for.
+    pub migration_id: String,
+    /// Repository full name.
+    pub repository: String,
+    /// Overall outcome: `success`, `pr_only`, `skipped` or `failed`.
+    pub outcome: String,
+    /// Issue status, as returned by [`IssueStatus::as_str`].
+    pub issue_status: Option<String>,
+    /// URL of the created issue.
+    pub issue_url: Option<String>,
+    /// PR status, as returned by [`PrStatus::as_str`].
+    pub pr_status: Option<String>,
+    /// URL of the created PR.
+    pub pr_url: Option<String>,
+    /// Skip reason or error message, if any.
+    pub detail: Option<String>,
+}
+
+impl From<&ProcessingResult> for ResultRecord {
+    fn from(result: &ProcessingResult) -> Self {
+        let mut record = Self {
+            migration_id: result.migration_id().to_string(),
+            repository: result.repository().to_string(),
+            outcome: String::new(),
+            issue_status: None,
+            issue_url: None,
+            pr_status: None,
+            pr_url: None,
+            detail: None,
+        };
+
+        match result {
+            ProcessingResult::Success { issue, pr, .. } => {
+                record.outcome = "success".to_string();
+                record.issue_status = Some(issue.as_str().to_string());
+                match issue {
+                    IssueStatus::Created { url, .. } => record.issue_url = Some(url.to_string()),
+                    IssueStatus::Skipped { reason } => record.detail = Some(reason.clone()),
+                    IssueStatus::Failed { error } => record.detail = Some(error.clone()),
+                    IssueStatus::Pending => {}
+                }
+                if let Some(pr) = pr {
+                    record.set_pr(pr);
+                }
+            }
+            ProcessingResult::PrOnly { pr, .. } => {
+                record.outcome = "pr_only".to_string();
+                record.set_pr(pr);
+            }
+            ProcessingResult::Dispatched { event_type, .. } => {
+                record.outcome = "dispatched".to_string();
+                record.detail = Some(event_type.clone());
+            }
+            ProcessingResult::Skipped { reason, .. } => {
+                record.outcome = "skipped".to_string();
+                record.detail = Some(reason.clone());
+            }
+            ProcessingResult::Failed { error, .. } => {
+                record.outcome = "failed".to_string();
+                record.detail = Some(error.clone());
+            }
+        }
+
+        // Stored errors can outlive the run, so scrub them like any report.
+        record.detail = record.detail.as_deref().map(redact);
+        record
+    }
+}
+
+impl ResultRecord {
+    fn set_pr(&mut self, pr: &PrStatus) {
+        self.pr_status = Some(pr.as_str().to_string());
+        match pr {
+            PrStatus::Created { url, .. } | PrStatus::Updated { url, .. } => {
+                self.pr_url = Some(url.to_string());
+            }
+            PrStatus::Failed { error, .. } => {
+                self.detail.get_or_insert_with(|| error.clone());
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! SQLite storage for run history.
+
+use super::{HistoryError, ResultRecord, RunRecord};
+use crate::summary::RunSummary;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    recorded_at TEXT NOT NULL,
+    dry_run INTEGER NOT NULL,
+    migrations_processed INTEGER NOT NULL,
+    repositories_discovered INTEGER NOT NULL,
+    issues_created INTEGER NOT NULL,
+    issues_skipped INTEGER NOT NULL,
+    issues_failed INTEGER NOT NULL,
+    prs_created INTEGER NOT NULL,
+    prs_failed INTEGER NOT NULL,
+    only_migrations TEXT NOT NULL DEFAULT '',
+    only_repos TEXT NOT NULL DEFAULT ''
+);
+CREATE TABLE IF NOT EXISTS results (
+    run_id INTEGER NOT NULL REFERENCES runs(id) ON DELETE CASCADE,
+    migration_id TEXT NOT NULL,
+    repository TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    issue_status TEXT,
+    issue_url TEXT,
+    pr_status TEXT,
+    pr_url TEXT,
+    detail TEXT
+);
+CREATE INDEX IF NOT EXISTS results_run_id ON results(run_id);
+";
+
+/// Columns added to `runs` after it was first created, with their definitions.
+///
+/// Databases created before a column existed get it on open.
+const ADDED_RUN_COLUMNS: [(&str, &str); 2] = [
+    ("only_migrations", "TEXT NOT NULL DEFAULT ''"),
+    ("only_repos", "TEXT NOT NULL DEFAULT ''"),
+];
+
+const RUN_COLUMNS: &str = "id, recorded_at, dry_run, migrations_processed, \
+    repositories_discovered, issues_created, issues_skipped, issues_failed, \
+    prs_created, prs_failed, only_migrations, only_repos";
+
+/// Separates the glob patterns of a run's filters within their column.
+const PATTERN_SEPARATOR: char = '\n';
+
+/// A local SQLite database of past runs.
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    /// Opens (creating if needed) the history database at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HistoryError::Open`] if the file cannot be opened or the
+    /// schema cannot be created.
+    pub fn open(path: &Path) -> Result<Self, HistoryError> {
+        let open_error = |source| HistoryError::Open {
+            path: path.display().to_string(),
+            source,
+        };
+        let conn = Connection::open(path).map_err(open_error)?;
+        conn.execute_batch(SCHEMA).map_err(open_error)?;
+        add_missing_columns(&conn).map_err(open_error)?;
+        Ok(Self { conn })
+    }
+
+    /// Opens a throwaway in-memory database.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HistoryError`] if the schema cannot be created.
+    pub fn open_in_memory() -> Result<Self, HistoryError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(SCHEMA)?;
+        add_missing_columns(&conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Records a run completed at `recorded_at` and every per-repository result.
+    ///
+    /// `only_migrations` and `only_repos` are the glob patterns the run was
+    /// limited to, empty if it was not. Returns the id assigned to the run.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HistoryError`] if the write fails; nothing is stored in that case.
+    pub fn record_run(
+        &mut self,
+        summary: &RunSummary,
+        only_migrations: &[String],
+        only_repos: &[String],
+        recorded_at: DateTime<Utc>,
+    ) -> Result<i64, HistoryError> {
+        let join = |patterns: &[String]| patterns.join(&PATTERN_SEPARATOR.to_string());
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO runs (recorded_at, dry_run, migrations_processed, \
+             repositories_discovered, issues_created, issues_skipped, issues_failed, \
+             prs_created, prs_failed, only_migrations, only_repos) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                recorded_at.to_rfc3339(),
+                summary.dry_run,
+                summary.migrations_processed as i64,
+                summary.repositories_discovered as i64,
+                summary.issues_created as i64,
+                summary.issues_skipped as i64,
+                summary.issues_failed as i64,
+                summary.prs_created as i64,
+                summary.prs_failed as i64,
+                join(only_migrations),
+                join(only_repos),
+            ],
+        )?;
+        let run_id = tx.last_insert_rowid();
+
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO results (run_id, migration_id, repository, outcome, \
+                 issue_status, issue_url, pr_status, pr_url, detail) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for result in &summary.results {
+                let record = ResultRecord::from(result);
+                insert.execute(params![
+                    run_id,
+                    record.migration_id,
+                    record.repository,
+                    record.outcome,
+                    record.issue_status,
+                    record.issue_url,
+                    record.pr_status,
+                    record.pr_url,
+                    record.detail,
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(run_id)
+    }
+
+    /// Returns the most recent runs, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HistoryError`] if the query fails.
+    pub fn list_runs(&self, limit: usize) -> Result<Vec<RunRecord>, HistoryError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {RUN_COLUMNS} FROM runs ORDER BY id DESC LIMIT ?1"
+        ))?;
+        let runs = stmt
+            .query_map(params![limit as i64], run_from_row)?
+            .collect::<Result<_, _>>()?;
+        Ok(runs)
+    }
+
+    /// Returns a single run, or `None` if no run has that id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HistoryError`] if the query fails.
+    pub fn run(&self, run_id: i64) -> Result<Option<RunRecord>, HistoryError> {
+        let run = self
+            .conn
+            .query_row(
+                &format!("SELECT {RUN_COLUMNS} FROM runs WHERE id = ?1"),
+                params![run_id],
+                run_from_row,
+            )
+            .optional()?;
+        Ok(run)
+    }
+
+    /// Returns the per-repository results of a run in migration/repository order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HistoryError`] if the query fails.
+    pub fn run_results(&self, run_id: i64) -> Result<Vec<ResultRecord>, HistoryError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT migration_id, repository, outcome, issue_status, issue_url, \
+             pr_status, pr_url, detail FROM results WHERE run_id = ?1 \
+             ORDER BY migration_id, repository",
+        )?;
+        let results = stmt
+            .query_map(params![run_id], |row| {
+                Ok(ResultRecord {
+                    migration_id: row.get(0)?,
+                    repository: row.get(1)?,
+                    outcome: row.get(2)?,
+                    issue_status: row.get(3)?,
+                    issue_url: row.get(4)?,
+                    pr_status: row.get(5)?,
+                    pr_url: row.get(6)?,
+                    detail: row.get(7)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(results)
+    }
+}
+
+/// Adds the [`ADDED_RUN_COLUMNS`] a database created by an older version lacks.
+fn add_missing_columns(conn: &Connection) -> rusqlite::Result<()> {
+    let existing = conn
+        .prepare("SELECT name FROM pragma_table_info('runs')")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (name, definition) in ADDED_RUN_COLUMNS {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute_batch(&format!("ALTER TABLE runs ADD COLUMN {name} {definition}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn split_patterns(column: &str) -> Vec<String> {
+    column
+        .split(PATTERN_SEPARATOR)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn run_from_row(row: &Row<'_>) -> rusqlite::Result<RunRecord> {
+    Ok(RunRecord {
+        id: row.get(0)?,
+        recorded_at: row.get(1)?,
+        dry_run: row.get(2)?,
+        migrations_processed: row.get(3)?,
+        repositories_discovered: row.get(4)?,
+        issues_created: row.get(5)?,
+        issues_skipped: row.get(6)?,
+        issues_failed: row.get(7)?,
+        prs_created: row.get(8)?,
+        prs_failed: row.get(9)?,
+        only_migrations: split_patterns(&row.get::<_, String>(10)?),
+        only_repos: split_patterns(&row.get::<_, String>(11)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_kind::ErrorKind;
+    use crate::issues::IssueStatus;
+    use crate::summary::ProcessingResult;
+    use crate::summary::StageTimings;
+
+    fn sample_summary() -> RunSummary {
+        let mut summary = RunSummary::new(false);
+        summary.migrations_processed = 1;
+        summary.repositories_discovered = 2;
+        summary.record_result(&ProcessingResult::Success {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            issue: IssueStatus::Created {
+                number: 3,
+                url: "https://github.com/user/repo/issues/3".parse().unwrap(),
+            },
+            pr: None,
+            timings: StageTimings::default(),
+        });
+        summary.record_result(&ProcessingResult::Failed {
+            migration_id: "test-migration".to_string(),
+            repository: "other/project".to_string(),
+            error: "boom".to_string(),
+            kind: ErrorKind::Other,
+            timings: StageTimings::default(),
+        });
+        summary
+    }
+
+    #[test]
+    fn records_and_reads_back_runs() {
+        let mut store = HistoryStore::open_in_memory().unwrap();
+        let first = store
+            .record_run(&sample_summary(), &[], &[], Utc::now())
+            .unwrap();
+        let second = store
+            .record_run(
+                &RunSummary::new(true),
+                &["test-*".to_string()],
+                &["user/*".to_string(), "other/*".to_string()],
+                Utc::now(),
+            )
+            .unwrap();
+
+        let runs = store.list_runs(10).unwrap();
+        assert_eq!(
+            runs.iter().map(|r| r.id).collect::<Vec<_>>(),
+            [second, first]
+        );
+        assert!(runs[0].dry_run);
+        assert_eq!(runs[0].only_migrations, ["test-*"]);
+        assert_eq!(runs[0].only_repos, ["user/*", "other/*"]);
+        assert!(runs[1].only_repos.is_empty());
+
+        let run = store.run(first).unwrap().unwrap();
+        assert_eq!(run.issues_created, 1);
+        assert_eq!(run.issues_failed, 1);
+
+        let results = store.run_results(first).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].repository, "other/project");
+        assert_eq!(results[0].outcome, "failed");
+        assert_eq!(results[0].detail.as_deref(), Some("boom"));
+        assert_eq!(results[1].issue_status.as_deref(), Some("created"));
+        assert_eq!(
+            results[1].issue_url.as_deref(),
+            Some("https://github.com/user/repo/issues/3")
+        );
+    }
+
+    #[test]
+    fn adds_filter_columns_to_older_databases() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("history.sqlite");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE runs (id INTEGER PRIMARY KEY AUTOINCREMENT, \
+                 recorded_at TEXT NOT NULL, dry_run INTEGER NOT NULL, \
+                 migrations_processed INTEGER NOT NULL, \
+                 repositories_discovered INTEGER NOT NULL, \
+                 issues_created INTEGER NOT NULL, issues_skipped INTEGER NOT NULL, \
+                 issues_failed INTEGER NOT NULL, prs_created INTEGER NOT NULL, \
+                 prs_failed INTEGER NOT NULL); \
+                 INSERT INTO runs VALUES (1, '2024-01-01T00:00:00Z', 0, 1, 1, 1, 0, 0, 0, 0);",
+            )
+            .unwrap();
+
+        let store = HistoryStore::open(&path).unwrap();
+        let run = store.run(1).unwrap().unwrap();
+        assert!(run.only_migrations.is_empty());
+        assert!(run.only_repos.is_empty());
+    }
+
+    #[test]
+    fn unknown_run_is_none() {
+        let store = HistoryStore::open_in_memory().unwrap();
+        assert!(store.run(42).unwrap().is_none());
+        assert!(store.run_results(42).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! Hooks that run external commands.
+
+use super::{HookCommand, HookError, HookEvent, HookStage, LifecycleHook};
+use core::time::Duration;
+use futures::future::BoxFuture;
+use std::io::ErrorKind;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Runs an external command for events of one stage, passing the event as
+/// JSON on stdin.
+///
+/// A non-zero exit status or exceeding the timeout is reported as an error.
+/// The command's stderr is inherited; its stdout is discarded.
+#[derive(Debug, Clone)]
+pub struct CommandHook {
+    stage: HookStage,
+    command: HookCommand,
+}
+
+impl CommandHook {
+    /// Creates a hook running `command` for events of `stage`.
+    pub fn new(stage: HookStage, command: HookCommand) -> Self {
+        Self { stage, command }
+    }
+
+    async fn run(&self, event: &HookEvent) -> Result<(), HookError> {
+        let input = serde_json::to_vec(event)?;
+        let io_error = |source| HookError::Io {
+            command: self.command.command.clone(),
+            source,
+        };
+
+        let mut child = Command::new(&self.command.command)
+            .args(&self.command.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(io_error)?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+
+        let run = async {
+            // Hooks that don't read their input close stdin early; that's fine.
+            match stdin.write_all(&input).await {
+                Err(e) if e.kind() != ErrorKind::BrokenPipe => return Err(e),
+                _ => {}
+            }
+            drop(stdin);
+            child.wait().await
+        };
+        let status = tokio::time::timeout(Duration::from_secs(self.command.timeout_secs), run)
+            .await
+            .map_err(|_| HookError::TimedOut {
+                command: self.command.command.clone(),
+                timeout_secs: self.command.timeout_secs,
+            })?
+            .map_err(io_error)?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(HookError::Failed {
+                command: self.command.command.clone(),
+                status: status.to_string(),
+            })
+        }
+    }
+}
+
+impl LifecycleHook for CommandHook {
+    fn on_event<'a>(&'a self, event: &'a HookEvent) -> BoxFuture<'a, Result<(), HookError>> {
+        Box::pin(async move {
+            if event.stage() != self.stage {
+                return Ok(());
+            }
+            self.run(event).await
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn event() -> HookEvent {
+        HookEvent::AfterDiscovery {
+            migration_id: "test-migration".to_string(),
+            repositories: Vec::new(),
+        }
+    }
+
+    fn hook(command: &str, args: &[&str]) -> CommandHook {
+        CommandHook::new(
+            HookStage::AfterDiscovery,
+            HookCommand {
+                command: command.to_string(),
+                args: args.iter().map(ToString::to_string).collect(),
+                timeout_secs: 5,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn passes_event_json_on_stdin() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let out = temp.path().join("event.json");
+        let script = format!("cat > '{}'", out.display());
+
+        hook("sh", &["-c", &script])
+            .on_event(&event())
+            .await
+            .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(json["event"], "after_discovery");
+        assert_eq!(json["migration_id"], "test-migration");
+    }
+
+    #[tokio::test]
+    async fn reports_failure_status() {
+        let result = hook("sh", &["-c", "exit 3"]).on_event(&event()).await;
+        assert!(matches!(result, Err(HookError::Failed { .. })));
+    }
+
+    #[tokio::test]
+    async fn ignores_other_stages() {
+        let hook = CommandHook::new(
+            HookStage::AfterPr,
+            HookCommand {
+                command: "false".to_string(),
+                args: Vec::new(),
+                timeout_secs: 5,
+            },
+        );
+        assert!(hook.on_event(&event()).await.is_ok());
+    }
+}
@@ -0,0 +1,86 @@
+//! Lifecycle hook configuration.
+
+use super::HookStage;
+use serde::Deserialize;
+
+/// Default time a hook command may run before it is killed.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// External commands to run at lifecycle points, from the `[hooks]` section
+/// of `notifier.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct HooksConfig {
+    /// Run after repositories are discovered for a migration.
+    pub after_discovery: Vec<HookCommand>,
+    /// Run before each issue is created.
+    pub before_issue: Vec<HookCommand>,
+    /// Run after each issue creation attempt.
+    pub after_issue: Vec<HookCommand>,
+    /// Run after each PR creation attempt.
+    pub after_pr: Vec<HookCommand>,
+}
+
+impl HooksConfig {
+    /// Returns every configured command with the stage it runs at.
+    pub fn commands(&self) -> impl Iterator<Item = (HookStage, &HookCommand)> {
+        with_stage(HookStage::AfterDiscovery, &self.after_discovery)
+            .chain(with_stage(HookStage::BeforeIssue, &self.before_issue))
+            .chain(with_stage(HookStage::AfterIssue, &self.after_issue))
+            .chain(with_stage(HookStage::AfterPr, &self.after_pr))
+    }
+}
+
+fn with_stage(
+    stage: HookStage,
+    commands: &[HookCommand],
+) -> impl Iterator<Item = (HookStage, &HookCommand)> {
+    commands.iter().map(move |command| (stage, command))
+}
+
+/// An external command that receives the event as JSON on stdin.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HookCommand {
+    /// Program to run.
+    pub command: String,
+    /// Arguments passed to the program.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Seconds the command may run before it is killed.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_per_stage() {
+        let config: HooksConfig = toml::from_str(
+            r#"
+            [[after-issue]]
+            command = "./sync.sh"
+            args = ["--tracker", "jira"]
+
+            [[after-pr]]
+            command = "notify"
+            timeout-secs = 5
+            "#,
+        )
+        .unwrap();
+
+        let commands: Vec<_> = config.commands().collect();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].0, HookStage::AfterIssue);
+        assert_eq!(commands[0].1.args, ["--tracker", "jira"]);
+        assert_eq!(commands[0].1.timeout_secs, 30);
+        assert_eq!(commands[1].0, HookStage::AfterPr);
+        assert_eq!(commands[1].1.timeout_secs, 5);
+    }
+}
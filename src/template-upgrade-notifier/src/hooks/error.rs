@@ -0,0 +1,31 @@
+//! Lifecycle hook error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while running a lifecycle hook.
+#[derive(Debug, Error)]
+pub enum HookError {
+    /// The hook command could not be started or fed its input.
+    #[error("Failed to run hook '{command}': {source}")]
+    Io {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The hook command exited unsuccessfully.
+    #[error("Hook '{command}' exited with {status}")]
+    Failed { command: String, status: String },
+
+    /// The hook command did not finish in time.
+    #[error("Hook '{command}' timed out after {timeout_secs}s")]
+    TimedOut { command: String, timeout_secs: u64 },
+
+    /// The event could not be serialized.
+    #[error("Failed to serialize hook event: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    /// A library-provided hook reported an error.
+    #[error("Hook error: {0}")]
+    Custom(String),
+}
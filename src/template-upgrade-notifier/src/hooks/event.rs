@@ -0,0 +1,76 @@
+//! Events passed to lifecycle hooks.
+
+use crate::discovery::DiscoveredRepository;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use serde::{Deserialize, Serialize};
+
+/// Point in a run at which hooks are invoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookStage {
+    /// Repositories have been discovered for a migration.
+    AfterDiscovery,
+    /// An issue is about to be created.
+    BeforeIssue,
+    /// An issue creation attempt has finished.
+    AfterIssue,
+    /// A PR creation attempt has finished.
+    AfterPr,
+}
+
+/// Data passed to a hook; serialized as JSON for command hooks.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    /// Repositories have been discovered for a migration.
+    AfterDiscovery {
+        /// Migration identifier.
+        migration_id: String,
+        /// Repositories that will be processed.
+        repositories: Vec<DiscoveredRepository>,
+    },
+
+    /// An issue is about to be created.
+    BeforeIssue {
+        /// Migration identifier.
+        migration_id: String,
+        /// Target repository.
+        repository: DiscoveredRepository,
+    },
+
+    /// An issue creation attempt has finished.
+    AfterIssue {
+        /// Migration identifier.
+        migration_id: String,
+        /// Target repository.
+        repository: DiscoveredRepository,
+        /// Issue status.
+        issue: IssueStatus,
+    },
+
+    /// A PR creation attempt has finished.
+    AfterPr {
+        /// Migration identifier.
+        migration_id: String,
+        /// Target repository.
+        repository: DiscoveredRepository,
+        /// Issue status.
+        issue: IssueStatus,
+        /// PR status.
+        pr: PrStatus,
+    },
+}
+
+impl HookEvent {
+    /// Returns the stage this event belongs to.
+    #[must_use]
+    pub fn stage(&self) -> HookStage {
+        match self {
+            Self::AfterDiscovery { .. } => HookStage::AfterDiscovery,
+            Self::BeforeIssue { .. } => HookStage::BeforeIssue,
+            Self::AfterIssue { .. } => HookStage::AfterIssue,
+            Self::AfterPr { .. } => HookStage::AfterPr,
+        }
+    }
+}
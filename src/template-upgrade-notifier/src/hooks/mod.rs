@@ -0,0 +1,68 @@
+//! Lifecycle hooks.
+//!
+//! Hooks run at fixed points of a run (after discovery, before and after
+//! issue creation, after PR creation) so results can be synced into other
+//! systems without forking the crate. External commands are configured in
+//! the `[hooks]` section of `notifier.toml`; library users can implement
+//! [`LifecycleHook`] directly. Hook failures are logged and never stop a run.
+
+mod command;
+mod config;
+mod error;
+mod event;
+
+pub use command::CommandHook;
+pub use config::{HookCommand, HooksConfig};
+pub use error::HookError;
+pub use event::{HookEvent, HookStage};
+
+use futures::future::BoxFuture;
+use tracing::warn;
+
+/// Receives lifecycle events during a run.
+///
+/// Implementations should return quickly; the run waits for each hook.
+pub trait LifecycleHook: Send + Sync {
+    /// Handles an event. Errors are logged and the run continues.
+    fn on_event<'a>(&'a self, event: &'a HookEvent) -> BoxFuture<'a, Result<(), HookError>>;
+}
+
+/// The hooks registered on a runner.
+#[derive(Default)]
+pub(crate) struct HookSet {
+    hooks: Vec<Box<dyn LifecycleHook>>,
+}
+
+impl HookSet {
+    /// Creates command hooks for every command in the configuration.
+    pub(crate) fn from_config(config: &HooksConfig) -> Self {
+        Self {
+            hooks: config
+                .commands()
+                .map(|(stage, command)| {
+                    Box::new(CommandHook::new(stage, command.clone())) as Box<dyn LifecycleHook>
+                })
+                .collect(),
+        }
+    }
+
+    /// Registers an additional hook.
+    pub(crate) fn push(&mut self, hook: Box<dyn LifecycleHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Sends an event to every hook in registration order.
+    ///
+    /// The event is only built when at least one hook is registered.
+    pub(crate) async fn emit(&self, event: impl FnOnce() -> HookEvent) {
+        if self.hooks.is_empty() {
+            return;
+        }
+        let event = event();
+        for hook in &self.hooks {
+            if let Err(e) = hook.on_event(&event).await {
+                warn!(stage = ?event.stage(), error = %e, "Lifecycle hook failed");
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! ETag cache error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while loading or saving the ETag cache.
+#[derive(Debug, Error)]
+pub enum HttpCacheError {
+    /// The cache file could not be read or written.
+    #[error("Failed to access ETag cache '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The cache file is not valid JSON.
+    #[error("Invalid ETag cache '{path}': {source}")]
+    Json {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
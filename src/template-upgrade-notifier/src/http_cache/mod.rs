@@ -0,0 +1,248 @@
+//! ETag-based conditional requests for repeated GETs.
+//!
+//! Repository metadata and file contents are stored with their ETag and
+//! revalidated with `If-None-Match`. GitHub answers an unchanged resource
+//! with `304 Not Modified`, which does not count against the core rate limit,
+//! so weekly re-runs over a mostly unchanged fleet spend far less quota.
+//!
+//! The cache is process-wide, like the [`redact`](mod@crate::redact) registry,
+//! so every library function benefits once it is enabled with
+//! [`load_etag_cache`]. `/rate_limit` is never cached: it is free to call
+//! and changes on every request.
+//!
+//! Only responses of repositories known to be public are written to disk;
+//! those of private and internal repositories are revalidated within a run
+//! but never saved. A repository is known to be public once its metadata
+//! says so, or once [`enrich_with_graphql`](crate::enrich_with_graphql)
+//! reports it public.
+
+mod error;
+mod store;
+
+pub use error::HttpCacheError;
+
+use http::header::{ETAG, IF_NONE_MATCH};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use octocrab::models::repos::Content;
+use octocrab::Octocrab;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use store::{repository_of, EtagStore};
+use tracing::debug;
+
+/// The process-wide cache; `None` while caching is disabled.
+static CACHE: Mutex<Option<EtagStore>> = Mutex::new(None);
+
+/// Enables the cache, loading responses saved by an earlier run from `path`.
+///
+/// A missing file starts an empty cache. Returns the number of cached responses.
+///
+/// # Errors
+///
+/// Returns [`HttpCacheError`] if the file exists but cannot be read or parsed.
+pub fn load_etag_cache(path: &Path) -> Result<usize, HttpCacheError> {
+    let store = EtagStore::load(path)?;
+    let len = store.len();
+    *cache() = Some(store);
+    Ok(len)
+}
+
+/// Saves the responses requested since the cache was loaded to `path`.
+///
+/// Does nothing while caching is disabled.
+///
+/// # Errors
+///
+/// Returns [`HttpCacheError::Io`] if the file cannot be written.
+pub fn save_etag_cache(path: &Path) -> Result<(), HttpCacheError> {
+    match cache().as_ref() {
+        Some(store) => store.save(path),
+        None => Ok(()),
+    }
+}
+
+/// Enables the cache with no cached responses.
+pub fn reset_etag_cache() {
+    *cache() = Some(EtagStore::default());
+}
+
+/// Disables the cache and drops every cached response.
+pub fn disable_etag_cache() {
+    *cache() = None;
+}
+
+/// Allows the cached responses of the public repository `owner/name` to be
+/// written to disk.
+pub(crate) fn mark_public_repository(owner: &str, name: &str) {
+    if let Some(store) = cache().as_mut() {
+        store.mark_public(route(["repos", owner, name]));
+    }
+}
+
+/// GETs `route` and deserializes the JSON response, revalidating a cached
+/// copy with `If-None-Match` when the cache is enabled.
+///
+/// Falls back to a plain request if a cached body no longer deserializes; a
+/// fresh body that does not deserialize is an error.
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    octocrab: &Octocrab,
+    route: &str,
+) -> Result<T, octocrab::Error> {
+    // Never hold the lock across an await.
+    let lookup = cache().as_mut().map(|store| store.etag(route));
+    let Some(etag) = lookup else {
+        return octocrab.get(route, None::<&()>).await;
+    };
+
+    let mut headers = HeaderMap::new();
+    if let Some(value) = etag.and_then(|etag| HeaderValue::from_str(&etag).ok()) {
+        headers.insert(IF_NONE_MATCH, value);
+    }
+    let response = octocrab._get_with_headers(route, Some(headers)).await?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        let cached = cache()
+            .as_ref()
+            .and_then(|store| store.body(route))
+            .and_then(|body| serde_json::from_str(body).ok());
+        if let Some(value) = cached {
+            debug!(route, "Not modified, using cached response");
+            return Ok(value);
+        }
+        return octocrab.get(route, None::<&()>).await;
+    }
+
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let response = octocrab::map_github_error(response).await?;
+    let body = octocrab.body_to_string(response).await?;
+    match serde_json::from_str(&body) {
+        Ok(value) => {
+            if let (Some(etag), Some(store)) = (etag, cache().as_mut()) {
+                if is_public_repository(route, &body) {
+                    store.mark_public(route.to_string());
+                }
+                store.insert(route, etag, body);
+            }
+            Ok(value)
+        }
+        Err(source) => Err(octocrab::Error::Serde {
+            source,
+            backtrace: std::backtrace::Backtrace::capture(),
+        }),
+    }
+}
+
+/// A contents API response: one file, or the entries of a directory.
+///
+/// Read with [`get_json`] instead of octocrab's
+/// [`ContentItems`](octocrab::models::repos::ContentItems), which only
+/// deserializes through octocrab's own requests.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Contents {
+    /// A file, with its Base64-encoded content.
+    File(Box<Content>),
+    /// A directory listing, without content.
+    Directory(Vec<Content>),
+}
+
+impl Contents {
+    /// Returns the decoded content of the file, or of a directory's first
+    /// entry if it has any.
+    pub(crate) fn decoded_content(&self) -> Option<String> {
+        match self {
+            Self::File(content) => content.decoded_content(),
+            Self::Directory(entries) => entries.first().and_then(Content::decoded_content),
+        }
+    }
+}
+
+/// The visibility flag of a repository metadata response.
+#[derive(Deserialize)]
+struct RepositoryPrivacy {
+    private: bool,
+}
+
+/// Returns true if `body` is the metadata of a public repository at `route`.
+fn is_public_repository(route: &str, body: &str) -> bool {
+    repository_of(route) == Some(route)
+        && serde_json::from_str::<RepositoryPrivacy>(body).is_ok_and(|privacy| !privacy.private)
+}
+
+/// Builds an API route from path segments, percent-encoding each one.
+///
+/// `["repos", "o", "r", "contents", "a b.txt"]` becomes `/repos/o/r/contents/a%20b.txt`.
+pub(crate) fn route<'a>(segments: impl IntoIterator<Item = &'a str>) -> String {
+    let mut url = url::Url::parse("https://api.github.com").expect("static URL is valid");
+    url.path_segments_mut()
+        .expect("https URLs have a path")
+        .extend(segments);
+    url.path().to_string()
+}
+
+fn cache() -> MutexGuard<'static, Option<EtagStore>> {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(content: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "name": "version.txt",
+            "path": "version.txt",
+            "sha": "abc",
+            "encoding": content.map(|_| "base64"),
+            "content": content,
+            "size": 5,
+            "url": "https://api.github.com/repos/o/r/contents/version.txt",
+            "html_url": null,
+            "git_url": null,
+            "download_url": null,
+            "type": "file",
+            "_links": { "self": "https://api.github.com/repos/o/r/contents/version.txt" },
+            "license": null
+        })
+    }
+
+    #[test]
+    fn decodes_file_and_directory_contents() {
+        let file: Contents = serde_json::from_value(content(Some("djEK\n"))).unwrap();
+        let directory: Contents =
+            serde_json::from_value(serde_json::json!([content(None)])).unwrap();
+
+        assert_eq!(file.decoded_content().as_deref(), Some("v1\n"));
+        assert_eq!(directory.decoded_content(), None);
+    }
+
+    #[test]
+    fn recognizes_public_repository_metadata() {
+        assert!(is_public_repository("/repos/o/r", r#"{"private":false}"#));
+        assert!(!is_public_repository("/repos/o/r", r#"{"private":true}"#));
+        assert!(!is_public_repository(
+            "/repos/o/r/contents/a",
+            r#"{"private":false}"#
+        ));
+        assert!(!is_public_repository("/repos/o/r", "{}"));
+    }
+
+    #[test]
+    fn encodes_route_segments() {
+        let path = "docs/read me.md";
+        assert_eq!(
+            route(
+                ["repos", "user", "repo", "contents"]
+                    .into_iter()
+                    .chain(path.split('/'))
+            ),
+            "/repos/user/repo/contents/docs/read%20me.md"
+        );
+    }
+}
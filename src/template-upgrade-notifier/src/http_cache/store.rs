@@ -0,0 +1,198 @@
+//! In-memory ETag store with JSON persistence.
+
+use super::HttpCacheError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// A cached response body and the ETag it was served with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct CachedResponse {
+    pub(super) etag: String,
+    pub(super) body: String,
+    /// Whether the entry was requested since it was loaded; only those are saved.
+    #[serde(skip)]
+    pub(super) used: bool,
+}
+
+/// Cached responses keyed by route.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct EtagStore {
+    entries: HashMap<String, CachedResponse>,
+    /// `/repos/{owner}/{name}` prefixes of the repositories seen to be public
+    /// this run; only their responses are written to disk.
+    #[serde(skip)]
+    public: HashSet<String>,
+}
+
+impl EtagStore {
+    /// Loads the store at `path`; a missing file is an empty store.
+    pub(super) fn load(path: &Path) -> Result<Self, HttpCacheError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(HttpCacheError::Io {
+                    path: path.display().to_string(),
+                    source,
+                })
+            }
+        };
+        serde_json::from_str(&json).map_err(|source| HttpCacheError::Json {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    /// Writes the entries used since loading, dropping resources that are no
+    /// longer requested so the file doesn't grow forever.
+    ///
+    /// Responses of repositories not seen to be public this run stay in
+    /// memory only, so private file contents never reach the disk. The file
+    /// is replaced atomically, so an interrupted save leaves the previous one.
+    pub(super) fn save(&self, path: &Path) -> Result<(), HttpCacheError> {
+        let saved = Self {
+            entries: self
+                .entries
+                .iter()
+                .filter(|(route, entry)| entry.used && self.is_public(route))
+                .map(|(route, entry)| (route.clone(), entry.clone()))
+                .collect(),
+            public: HashSet::new(),
+        };
+        let json = serde_json::to_string(&saved).map_err(|source| HttpCacheError::Json {
+            path: path.display().to_string(),
+            source,
+        })?;
+        let io_error = |source| HttpCacheError::Io {
+            path: path.display().to_string(),
+            source,
+        };
+        let directory = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let mut file = NamedTempFile::new_in(directory).map_err(io_error)?;
+        file.write_all(json.as_bytes()).map_err(io_error)?;
+        file.persist(path).map_err(|e| io_error(e.error))?;
+        Ok(())
+    }
+
+    /// Records that the repository `/repos/{owner}/{name}` is public.
+    pub(super) fn mark_public(&mut self, repository: String) {
+        self.public.insert(repository);
+    }
+
+    /// Returns true if `route` belongs to a repository seen to be public.
+    fn is_public(&self, route: &str) -> bool {
+        repository_of(route).is_some_and(|repository| self.public.contains(repository))
+    }
+
+    /// Returns the ETag cached for `route`, marking the entry as used.
+    pub(super) fn etag(&mut self, route: &str) -> Option<String> {
+        let entry = self.entries.get_mut(route)?;
+        entry.used = true;
+        Some(entry.etag.clone())
+    }
+
+    /// Returns the body cached for `route`.
+    pub(super) fn body(&self, route: &str) -> Option<&str> {
+        self.entries.get(route).map(|entry| entry.body.as_str())
+    }
+
+    /// Stores a fresh response for `route`.
+    pub(super) fn insert(&mut self, route: &str, etag: String, body: String) {
+        self.entries.insert(
+            route.to_string(),
+            CachedResponse {
+                etag,
+                body,
+                used: true,
+            },
+        );
+    }
+
+    /// Returns the number of cached responses.
+    pub(super) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Returns the `/repos/{owner}/{name}` prefix of `route`, if it has one.
+pub(super) fn repository_of(route: &str) -> Option<&str> {
+    let rest = route.strip_prefix("/repos/")?;
+    let mut slashes = rest.match_indices('/').map(|(index, _)| index);
+    let owner_end = slashes.next()?;
+    let end = slashes.next().unwrap_or(rest.len());
+    (end > owner_end + 1).then(|| &route[..end + "/repos/".len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn saves_only_used_entries() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("etag-cache.json");
+        let mut store = EtagStore::default();
+        store.mark_public("/repos/a/b".to_string());
+        store.mark_public("/repos/c/d".to_string());
+        store.insert("/repos/a/b", "\"1\"".to_string(), "{}".to_string());
+        store.insert("/repos/c/d", "\"2\"".to_string(), "{}".to_string());
+        store.save(&path).unwrap();
+
+        let mut loaded = EtagStore::load(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.etag("/repos/a/b").as_deref(), Some("\"1\""));
+        loaded.mark_public("/repos/a/b".to_string());
+        loaded.save(&path).unwrap();
+
+        let mut pruned = EtagStore::load(&path).unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert!(pruned.etag("/repos/c/d").is_none());
+        assert_eq!(pruned.body("/repos/a/b"), Some("{}"));
+    }
+
+    #[test]
+    fn keeps_private_responses_in_memory() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("etag-cache.json");
+        let mut store = EtagStore::default();
+        store.mark_public("/repos/a/public".to_string());
+        store.insert(
+            "/repos/a/public/contents/v.txt",
+            "\"1\"".to_string(),
+            "{}".to_string(),
+        );
+        store.insert(
+            "/repos/a/private/contents/v.txt",
+            "\"2\"".to_string(),
+            "{}".to_string(),
+        );
+        store.save(&path).unwrap();
+
+        let mut loaded = EtagStore::load(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded.etag("/repos/a/private/contents/v.txt").is_none());
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("private"));
+    }
+
+    #[test]
+    fn finds_repository_of_route() {
+        assert_eq!(repository_of("/repos/o/r"), Some("/repos/o/r"));
+        assert_eq!(repository_of("/repos/o/r/contents/a"), Some("/repos/o/r"));
+        assert_eq!(repository_of("/repos/o"), None);
+        assert_eq!(repository_of("/rate_limit"), None);
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let temp = TempDir::new().unwrap();
+        let store = EtagStore::load(&temp.path().join("missing.json")).unwrap();
+        assert_eq!(store.len(), 0);
+    }
+}
@@ -0,0 +1,19 @@
+//! Identifier error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while validating an identifier or URL.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IdError {
+    /// A repository slug is not in `owner/name` form.
+    #[error("Invalid repository '{value}': {message}")]
+    RepoSlug { value: String, message: String },
+
+    /// A migration id is not a relative, `/`-separated path.
+    #[error("Invalid migration id '{value}': {message}")]
+    MigrationId { value: String, message: String },
+
+    /// A URL could not be parsed, or uses an unsupported scheme.
+    #[error("Invalid URL '{value}': {message}")]
+    HtmlUrl { value: String, message: String },
+}
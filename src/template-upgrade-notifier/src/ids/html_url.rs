@@ -0,0 +1,74 @@
+//! Links to files, issues and pull requests.
+
+use super::IdError;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// An absolute link to a page a user can open: an issue, a pull request, or
+/// a matched file.
+///
+/// Only `http` and `https` URLs are accepted, plus `file` URLs for files
+/// found by local scans.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HtmlUrl(pub(super) String);
+
+string_id!(HtmlUrl);
+
+impl HtmlUrl {
+    /// Validates `value` as a link.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdError::HtmlUrl`] if `value` does not parse, or is not an
+    /// `http`, `https` or `file` URL.
+    pub fn new(value: impl Into<String>) -> Result<Self, IdError> {
+        let value = value.into();
+        let invalid = |message: String| IdError::HtmlUrl {
+            value: value.clone(),
+            message,
+        };
+        let url = Url::parse(&value).map_err(|e| invalid(e.to_string()))?;
+        match url.scheme() {
+            "http" | "https" | "file" => {}
+            scheme => return Err(invalid(format!("unsupported scheme '{scheme}'"))),
+        }
+        Ok(Self(value))
+    }
+}
+
+impl From<Url> for HtmlUrl {
+    /// Wraps a URL returned by the API. Its scheme is not checked, since
+    /// GitHub only returns web links in `html_url` fields.
+    fn from(url: Url) -> Self {
+        Self(url.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_web_and_file_links() {
+        for value in [
+            "https://github.com/user/repo/issues/1",
+            "http://localhost:8080/pr/2",
+            "file:///home/user/repo/template.toml",
+        ] {
+            assert_eq!(HtmlUrl::new(value).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn rejects_other_values() {
+        for value in [
+            "",
+            "github.com/user/repo",
+            "ftp://host/file",
+            "mailto:a@b.c",
+        ] {
+            assert!(HtmlUrl::new(value).is_err(), "{value:?} was accepted");
+        }
+    }
+}
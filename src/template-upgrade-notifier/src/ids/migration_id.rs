@@ -0,0 +1,84 @@
+//! Migration identifiers.
+
+use super::IdError;
+use serde::{Deserialize, Serialize};
+
+/// Identifier of a migration: its folder's path relative to the migrations
+/// root, with `/` separators (e.g. `my-template/v1.0.0-to-v1.0.1`).
+///
+/// Every segment is non-empty, is not `.` or `..`, and contains no
+/// whitespace, control characters or backslashes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MigrationId(String);
+
+string_id!(MigrationId);
+
+impl MigrationId {
+    /// Validates `value` as a migration id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdError::MigrationId`] if `value` is empty, absolute, or has
+    /// an invalid segment.
+    pub fn new(value: impl Into<String>) -> Result<Self, IdError> {
+        let value = value.into();
+        let invalid = |message: &str| IdError::MigrationId {
+            value: value.clone(),
+            message: message.to_string(),
+        };
+        if value.is_empty() {
+            return Err(invalid("must not be empty"));
+        }
+        for segment in value.split('/') {
+            if segment.is_empty() {
+                return Err(invalid("segments must not be empty"));
+            }
+            if matches!(segment, "." | "..") {
+                return Err(invalid("segments must not be '.' or '..'"));
+            }
+            if segment
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control() || c == '\\')
+            {
+                return Err(invalid(
+                    "segments must not contain whitespace or backslashes",
+                ));
+            }
+        }
+        Ok(Self(value))
+    }
+
+    /// Returns the first segment, which names the template.
+    #[must_use]
+    pub fn template(&self) -> &str {
+        self.0.split('/').next().unwrap_or(&self.0)
+    }
+}
+
+/// The placeholder id `migration`, so migrations built in code can start
+/// from [`Default`].
+impl Default for MigrationId {
+    fn default() -> Self {
+        Self("migration".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_nested_ids() {
+        let id = MigrationId::new("my-template/v1.0.0-to-v1.0.1").unwrap();
+        assert_eq!(id.template(), "my-template");
+        assert_eq!(id, "my-template/v1.0.0-to-v1.0.1");
+    }
+
+    #[test]
+    fn rejects_malformed_ids() {
+        for value in ["", "/abs", "a//b", "a/", "../a", "a/./b", "a b", "a\\b"] {
+            assert!(MigrationId::new(value).is_err(), "{value:?} was accepted");
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Repository slugs in `owner/name` form.
+
+use super::{HtmlUrl, IdError};
+use serde::{Deserialize, Serialize};
+
+/// Longest owner (user or organization) name GitHub allows.
+const MAX_OWNER_LEN: usize = 39;
+
+/// Longest repository name GitHub allows.
+const MAX_NAME_LEN: usize = 100;
+
+/// A repository's full name, `owner/name`.
+///
+/// The owner is made of ASCII letters, digits, `-` and `_`; the name may
+/// also contain `.`, but is never `.` or `..`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RepoSlug(String);
+
+string_id!(RepoSlug);
+
+impl RepoSlug {
+    /// Validates `value` as an `owner/name` slug.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdError::RepoSlug`] if `value` is not exactly two valid
+    /// segments separated by `/`.
+    pub fn new(value: impl Into<String>) -> Result<Self, IdError> {
+        let value = value.into();
+        let invalid = |message: &str| IdError::RepoSlug {
+            value: value.clone(),
+            message: message.to_string(),
+        };
+        let Some((owner, name)) = value.split_once('/') else {
+            return Err(invalid("expected 'owner/name'"));
+        };
+        if owner.is_empty() || owner.len() > MAX_OWNER_LEN {
+            return Err(invalid("owner must be 1 to 39 characters"));
+        }
+        if !owner
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        {
+            return Err(invalid(
+                "owner may only contain letters, digits, '-' and '_'",
+            ));
+        }
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(invalid("name must be 1 to 100 characters"));
+        }
+        if matches!(name, "." | "..")
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(invalid(
+                "name may only contain letters, digits, '-', '_' and '.'",
+            ));
+        }
+        Ok(Self(value))
+    }
+
+    /// Builds the slug for `owner` and `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdError::RepoSlug`] if either part is invalid.
+    pub fn from_parts(owner: &str, name: &str) -> Result<Self, IdError> {
+        Self::new(format!("{owner}/{name}"))
+    }
+
+    /// Returns the owning user or organization.
+    #[must_use]
+    pub fn owner(&self) -> &str {
+        self.0.split_once('/').map_or("", |(owner, _)| owner)
+    }
+
+    /// Returns the repository name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.0.split_once('/').map_or("", |(_, name)| name)
+    }
+
+    /// Returns the GitHub link to pull request `number`, for when the API
+    /// response lacks one.
+    #[must_use]
+    pub fn pull_request_url(&self, number: u64) -> HtmlUrl {
+        // A slug only contains URL-safe characters, so the link is valid.
+        HtmlUrl(format!("https://github.com/{}/pull/{number}", self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_owner_and_name() {
+        let slug: RepoSlug = "Sewer56/template.rs".parse().unwrap();
+        assert_eq!(slug.owner(), "Sewer56");
+        assert_eq!(slug.name(), "template.rs");
+        assert_eq!(slug, "Sewer56/template.rs");
+        assert_eq!(
+            slug.pull_request_url(7),
+            "https://github.com/Sewer56/template.rs/pull/7"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_slugs() {
+        for value in [
+            "",
+            "owner",
+            "owner/",
+            "/name",
+            "a/b/c",
+            "owner/..",
+            "own er/name",
+            "owner/na me",
+            "owner/name\n",
+        ] {
+            assert!(RepoSlug::new(value).is_err(), "{value:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn deserializes_with_validation() {
+        let slug: RepoSlug = serde_json::from_str("\"user/repo\"").unwrap();
+        assert_eq!(serde_json::to_string(&slug).unwrap(), "\"user/repo\"");
+        assert!(serde_json::from_str::<RepoSlug>("\"user\"").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+//! The search qualifier matching issues and PRs we authored.
+//!
+//! `author:@me` only resolves for user tokens: a GitHub App installation
+//! token acts as `<slug>[bot]`, whose items are found with `author:app/<slug>`.
+//! The token's login is looked up once over GraphQL, whose `viewer` answers
+//! for both kinds of token.
+
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use serde_json::{json, Value};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tracing::{debug, warn};
+
+/// The qualifier for the token in use, once looked up.
+static AUTHOR: Mutex<Option<String>> = Mutex::new(None);
+
+/// Forgets the looked-up author, e.g. after switching tokens.
+pub(super) fn clear_author() {
+    *author() = None;
+}
+
+/// Returns the search qualifier matching items authored by the token in use:
+/// `author:<login>` for users and `author:app/<slug>` for GitHub Apps.
+///
+/// Falls back to `author:@me` if the response carries no login.
+///
+/// # Errors
+///
+/// Returns an error if the GraphQL request fails.
+pub(crate) async fn author_qualifier(octocrab: &Octocrab) -> Result<String, octocrab::Error> {
+    if let Some(qualifier) = author().clone() {
+        return Ok(qualifier);
+    }
+    ensure_core_rate_limit(octocrab).await?;
+    let response: Value = octocrab
+        .graphql(&json!({ "query": "query { viewer { login } }" }))
+        .await?;
+    let qualifier = match response["data"]["viewer"]["login"].as_str() {
+        Some(login) => qualifier_for(login),
+        None => {
+            warn!("Failed to read the authenticated login, searching author:@me");
+            "author:@me".to_string()
+        }
+    };
+    debug!(qualifier = %qualifier, "Resolved search author");
+    *author() = Some(qualifier.clone());
+    Ok(qualifier)
+}
+
+/// Builds the author qualifier for `login`.
+fn qualifier_for(login: &str) -> String {
+    match login.strip_suffix("[bot]") {
+        Some(slug) => format!("author:app/{slug}"),
+        None => format!("author:{login}"),
+    }
+}
+
+fn author() -> MutexGuard<'static, Option<String>> {
+    AUTHOR.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualifies_users_and_apps() {
+        assert_eq!(qualifier_for("octocat"), "author:octocat");
+        assert_eq!(qualifier_for("upgrade-bot[bot]"), "author:app/upgrade-bot");
+    }
+}
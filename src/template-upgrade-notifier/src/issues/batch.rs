@@ -0,0 +1,157 @@
+//! Batched duplicate-issue lookups over GraphQL.
+//!
+//! Looking for an open issue with the migration's title costs one REST search
+//! per repository. Before processing a migration, the same searches are sent
+//! for up to `BATCH_SIZE` repositories at a time as aliased fields of a
+//! single GraphQL query, and their answers seed the lookup memo, so
+//! [`create_issue`](super::create_issue) finds them without a request.
+
+use super::{duplicate_query, memo, IssueError};
+use crate::discovery::DiscoveredRepository;
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use serde_json::{json, Map, Value};
+use tracing::{debug, warn};
+
+/// Repositories looked up per GraphQL query.
+const BATCH_SIZE: usize = 50;
+
+/// Search results read per repository; only exact title matches count.
+const RESULTS_PER_REPOSITORY: u32 = 20;
+
+/// Looks up an open issue titled `title` in every repository, in batches of
+/// `BATCH_SIZE`, and memoizes each answer for the rest of the run.
+///
+/// Returns how many repositories were answered. Repositories missing from a
+/// response (for example ones the token cannot see) are left to the
+/// per-repository search.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if a query fails outright; answers from earlier
+/// batches stay memoized.
+pub async fn prefetch_duplicate_issues(
+    octocrab: &Octocrab,
+    repositories: &[DiscoveredRepository],
+    title: &str,
+) -> Result<usize, IssueError> {
+    let mut answered = 0;
+    for batch in repositories.chunks(BATCH_SIZE) {
+        ensure_core_rate_limit(octocrab).await?;
+        let response: Value = octocrab.graphql(&batch_request(batch, title)).await?;
+        if let Some(errors) = response.get("errors") {
+            warn!(errors = %errors, "Batched duplicate lookup returned errors");
+        }
+        for (index, repository) in batch.iter().enumerate() {
+            let Some(nodes) = response["data"][alias(index)]["nodes"].as_array() else {
+                continue;
+            };
+            let existing = find_title(nodes, title);
+            memo::seed(&repository.full_name, title, existing);
+            answered += 1;
+        }
+    }
+    debug!(
+        answered,
+        total = repositories.len(),
+        "Prefetched duplicate issue lookups"
+    );
+    Ok(answered)
+}
+
+/// Builds one query searching every repository in `batch`, each search
+/// string passed as a variable.
+fn batch_request(batch: &[DiscoveredRepository], title: &str) -> Value {
+    let mut declarations = Vec::with_capacity(batch.len());
+    let mut fields = String::new();
+    let mut variables = Map::new();
+    for (index, repository) in batch.iter().enumerate() {
+        let alias = alias(index);
+        declarations.push(format!("${alias}: String!"));
+        fields.push_str(&format!(
+            "{alias}: search(query: ${alias}, type: ISSUE, first: {RESULTS_PER_REPOSITORY}) \
+             {{ nodes {{ ... on Issue {{ number title }} }} }} "
+        ));
+        variables.insert(
+            alias,
+            Value::String(duplicate_query(&repository.full_name, title)),
+        );
+    }
+    json!({
+        "query": format!("query({}) {{ {fields}}}", declarations.join(", ")),
+        "variables": variables,
+    })
+}
+
+/// Returns the alias of the `index`th repository in a batch.
+fn alias(index: usize) -> String {
+    format!("r{index}")
+}
+
+/// Returns the number of the node titled exactly `title`, if any.
+fn find_title(nodes: &[Value], title: &str) -> Option<u64> {
+    nodes
+        .iter()
+        .find(|node| node["title"].as_str() == Some(title))
+        .and_then(|node| node["number"].as_u64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository(full_name: &str) -> DiscoveredRepository {
+        let (owner, name) = full_name.split_once('/').unwrap();
+        DiscoveredRepository {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            full_name: full_name.parse().unwrap(),
+            file_path: "version.txt".to_string(),
+            file_url: format!("https://github.com/{full_name}/blob/main/version.txt")
+                .parse()
+                .unwrap(),
+            default_branch: "main".to_string(),
+            template_version: None,
+            additional_locations: Vec::new(),
+            snippet: None,
+            previously_notified: false,
+            previous_issue_url: None,
+            owner_is_bot: false,
+            is_mirror: false,
+            archived: false,
+            disabled: false,
+            visibility: None,
+            fork_ancestors: Vec::new(),
+            file_size: None,
+            topics: None,
+            opted_out: None,
+            repo_data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn aliases_one_search_per_repository() {
+        let batch = [repository("user/one"), repository("user/two")];
+
+        let request = batch_request(&batch, "Upgrade \"tpl\"");
+
+        let query = request["query"].as_str().unwrap();
+        assert!(query.starts_with("query($r0: String!, $r1: String!)"));
+        assert!(query.contains("r1: search(query: $r1, type: ISSUE"));
+        assert_eq!(
+            request["variables"]["r0"],
+            duplicate_query("user/one", "Upgrade \"tpl\"")
+        );
+    }
+
+    #[test]
+    fn only_exact_titles_match() {
+        let nodes = [
+            json!({ "number": 3, "title": "Upgrade tpl to v2 (again)" }),
+            json!({ "number": 4, "title": "Upgrade tpl to v2" }),
+        ];
+
+        assert_eq!(find_title(&nodes, "Upgrade tpl to v2"), Some(4));
+        assert_eq!(find_title(&nodes[..1], "Upgrade tpl to v2"), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! What to do when a migration's issue is already open in a repository.
+
+use super::{close_notification, IssueError};
+use crate::discovery::DiscoveredRepository;
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// How [`create_issue`](super::create_issue) treats an open issue with the
+/// same title.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuplicatePolicy {
+    /// Leave the existing issue alone and skip the repository.
+    #[default]
+    Skip,
+    /// Post the freshly rendered body as a comment on the existing issue.
+    Comment,
+    /// Replace the existing issue's body with the freshly rendered one.
+    Update,
+    /// File a new issue and close the existing one as superseded.
+    Recreate,
+}
+
+impl DuplicatePolicy {
+    /// Returns the policy as it is written in `metadata.toml`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Comment => "comment",
+            Self::Update => "update",
+            Self::Recreate => "recreate",
+        }
+    }
+}
+
+/// Refreshes the existing issue `number` with `body`, for the
+/// [`Comment`](DuplicatePolicy::Comment) and [`Update`](DuplicatePolicy::Update)
+/// policies, returning the skip reason to report.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if the comment or update fails.
+pub(super) async fn refresh_existing(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    number: u64,
+    policy: DuplicatePolicy,
+    body: &str,
+) -> Result<String, IssueError> {
+    let issues = octocrab.issues(&repository.owner, &repository.name);
+    ensure_core_rate_limit(octocrab).await?;
+    if policy == DuplicatePolicy::Update {
+        issues.update(number).body(body).send().await?;
+        info!(issue_number = number, "Updated duplicate issue");
+        Ok(format!("duplicate issue exists (#{number}), updated"))
+    } else {
+        issues.create_comment(number, body).await?;
+        info!(issue_number = number, "Commented on duplicate issue");
+        Ok(format!("duplicate issue exists (#{number}), commented"))
+    }
+}
+
+/// Closes the existing issue `number` in favour of the newly filed `replacement`.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if the comment or the close fails.
+pub(super) async fn close_superseded(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    number: u64,
+    replacement: u64,
+) -> Result<(), IssueError> {
+    let comment = format!("Superseded by #{replacement}.");
+    close_notification(octocrab, repository, number, &comment).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Metadata {
+        #[serde(default)]
+        duplicate_policy: DuplicatePolicy,
+    }
+
+    #[test]
+    fn parses_policies() {
+        for policy in [
+            DuplicatePolicy::Skip,
+            DuplicatePolicy::Comment,
+            DuplicatePolicy::Update,
+            DuplicatePolicy::Recreate,
+        ] {
+            let toml = format!("duplicate-policy = \"{}\"", policy.as_str());
+            let parsed: Metadata = toml::from_str(&toml).unwrap();
+            assert_eq!(parsed.duplicate_policy, policy);
+        }
+        let parsed: Metadata = toml::from_str("").unwrap();
+        assert_eq!(parsed.duplicate_policy, DuplicatePolicy::Skip);
+    }
+}
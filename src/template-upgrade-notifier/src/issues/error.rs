@@ -1,5 +1,6 @@
 //! Issue creation error types.
 
+use crate::error_kind::ErrorKind;
 use thiserror::Error;
 
 /// Errors that can occur during issue operations.
@@ -13,6 +14,10 @@ pub enum IssueError {
     #[error("Permission denied: no write access to {owner}/{repo}")]
     PermissionDenied { owner: String, repo: String },
 
+    /// The repository has issues disabled.
+    #[error("Issues are disabled in {owner}/{repo}")]
+    IssuesDisabled { owner: String, repo: String },
+
     /// Rate limit exceeded.
     #[error("Rate limit exceeded, reset at {reset_at}")]
     RateLimitExceeded { reset_at: u64 },
@@ -21,3 +26,40 @@ pub enum IssueError {
     #[error("Template rendering error: {0}")]
     TemplateError(String),
 }
+
+impl IssueError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::GitHubError(e) => ErrorKind::of_github(e),
+            Self::PermissionDenied { .. } => ErrorKind::Auth,
+            Self::IssuesDisabled { .. } => ErrorKind::NotFound,
+            Self::RateLimitExceeded { .. } => ErrorKind::RateLimit,
+            Self::TemplateError(_) => ErrorKind::Validation,
+        }
+    }
+
+    /// Returns true if retrying the operation later may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_retryable_errors() {
+        assert!(IssueError::RateLimitExceeded { reset_at: 0 }.is_retryable());
+        assert!(!IssueError::TemplateError("bad".to_string()).is_retryable());
+        let denied = IssueError::PermissionDenied {
+            owner: "user".to_string(),
+            repo: "repo".to_string(),
+        };
+        assert_eq!(denied.kind(), ErrorKind::Auth);
+        assert!(!denied.is_retryable());
+    }
+}
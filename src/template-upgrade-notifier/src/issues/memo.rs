@@ -0,0 +1,85 @@
+//! Memoization of duplicate lookups within a run.
+//!
+//! Retry passes (and several migrations sharing an issue title) would
+//! otherwise repeat identical duplicate-issue searches and open auto-PR
+//! lookups for the same repository. Results are cached per repository and
+//! marker (the issue title, or the auto-PR head) until
+//! [`clear_lookup_cache`] is called at the start of the next run.
+
+use super::author::clear_author;
+use super::IssueError;
+use core::future::Future;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tracing::debug;
+
+/// Cached lookup results keyed by `(repository, marker)`.
+static LOOKUPS: Mutex<BTreeMap<(String, String), Option<u64>>> = Mutex::new(BTreeMap::new());
+
+/// Forgets every memoized duplicate lookup and the token's search author.
+pub fn clear_lookup_cache() {
+    lookups().clear();
+    clear_author();
+}
+
+/// Returns the memoized result for `(repository, marker)`, running `lookup`
+/// and caching its result on a miss. Errors are not cached.
+pub(super) async fn memoized<F, Fut>(
+    repository: &str,
+    marker: &str,
+    lookup: F,
+) -> Result<Option<u64>, IssueError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Option<u64>, IssueError>>,
+{
+    let key = (repository.to_string(), marker.to_string());
+    let cached = lookups().get(&key).copied();
+    if let Some(number) = cached {
+        debug!(repository, marker, "Using memoized duplicate lookup");
+        return Ok(number);
+    }
+    let number = lookup().await?;
+    lookups().insert(key, number);
+    Ok(number)
+}
+
+/// Records the answer to a lookup made elsewhere, such as a batched query.
+pub(super) fn seed(repository: &str, marker: &str, number: Option<u64>) {
+    lookups().insert((repository.to_string(), marker.to_string()), number);
+}
+
+/// Records that `number` now exists for `(repository, marker)`.
+pub(super) fn remember(repository: &str, marker: &str, number: u64) {
+    lookups().insert((repository.to_string(), marker.to_string()), Some(number));
+}
+
+fn lookups() -> MutexGuard<'static, BTreeMap<(String, String), Option<u64>>> {
+    LOOKUPS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn runs_lookup_once_per_key() {
+        let calls = AtomicUsize::new(0);
+        let lookup = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(None)
+        };
+
+        assert_eq!(memoized("memo/once", "title", lookup).await.unwrap(), None);
+        assert_eq!(memoized("memo/once", "title", lookup).await.unwrap(), None);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        remember("memo/once", "title", 7);
+        assert_eq!(
+            memoized("memo/once", "title", lookup).await.unwrap(),
+            Some(7)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -3,18 +3,38 @@
 //! This module handles creating upgrade notification issues in discovered
 //! repositories, including duplicate detection and permission handling.
 
+mod author;
+mod batch;
+mod duplicate_policy;
 mod error;
+mod memo;
+mod open_pr;
+mod recent;
+mod resolve;
 mod status;
 mod upgrade_issue;
+mod upsert;
 
+pub(crate) use author::author_qualifier;
+pub use batch::prefetch_duplicate_issues;
+pub use duplicate_policy::DuplicatePolicy;
 pub use error::IssueError;
+pub use memo::clear_lookup_cache;
+pub use recent::last_notified_at;
+pub(crate) use resolve::repository_from_api_url;
+pub use resolve::{close_notification, find_open_notifications, OpenNotification};
 pub use status::IssueStatus;
 pub use upgrade_issue::UpgradeIssue;
+pub(crate) use upsert::{split_repository, upsert_own_issue};
 
 use crate::config::Migration;
 use crate::discovery::DiscoveredRepository;
+use crate::error_kind::ErrorKind;
+use crate::ids::HtmlUrl;
 use crate::pull_requests::PrStatus;
 use crate::rate_limit::{ensure_core_rate_limit, ensure_search_rate_limit};
+use crate::summary::PERMISSION_DENIED_REASON;
+use crate::teams::append_team_mentions;
 use crate::templates::generate_issue_title;
 use crate::templates::TemplateRenderer;
 use octocrab::Octocrab;
@@ -23,9 +43,11 @@ use tracing::{debug, info, info_span, warn, Instrument};
 /// Creates an upgrade notification issue in a repository.
 ///
 /// This function:
-/// 1. Checks for existing duplicate issues
-/// 2. Renders the issue template
-/// 3. Creates the issue via GitHub API
+/// 1. Checks for existing duplicate issues, handling them according to the
+///    migration's [`DuplicatePolicy`]
+/// 2. Checks for an open auto-fix PR from an earlier run
+/// 3. Renders the issue template
+/// 4. Creates the issue via GitHub API
 ///
 /// # Arguments
 ///
@@ -43,7 +65,8 @@ use tracing::{debug, info, info_span, warn, Instrument};
 /// # Errors
 ///
 /// Returns [`IssueError`] if creation fails (except for permission denied,
-/// which returns a [`Skipped`][`IssueStatus::Skipped`] status).
+/// which returns a [`Skipped`][`IssueStatus::Skipped`] status), including
+/// [`IssueError::IssuesDisabled`] when the repository has issues turned off.
 pub async fn create_issue(
     octocrab: &Octocrab,
     repository: &DiscoveredRepository,
@@ -51,6 +74,68 @@ pub async fn create_issue(
     renderer: &TemplateRenderer,
     pr_status: Option<&PrStatus>,
     pr_link: Option<&str>,
+) -> Result<UpgradeIssue, IssueError> {
+    create_issue_with(
+        octocrab, repository, migration, renderer, pr_status, pr_link, false,
+    )
+    .await
+}
+
+/// Creates an upgrade notification issue even if one was already filed.
+///
+/// Used to deliberately re-file an issue (e.g. after fixing a template) when
+/// an open issue with the same title or an open auto-fix PR already exists.
+/// Only the default [`DuplicatePolicy::Skip`] is overridden: the issue is
+/// filed alongside the existing one. The other policies still comment on,
+/// update or recreate the existing issue.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if creation fails (except for permission denied,
+/// which returns a [`Skipped`][`IssueStatus::Skipped`] status).
+pub async fn force_create_issue(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+    renderer: &TemplateRenderer,
+    pr_status: Option<&PrStatus>,
+    pr_link: Option<&str>,
+) -> Result<UpgradeIssue, IssueError> {
+    create_issue_with(
+        octocrab, repository, migration, renderer, pr_status, pr_link, true,
+    )
+    .await
+}
+
+/// Returns the open issue with `migration`'s title in `repository`, if any.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if the title cannot be rendered or the search fails.
+pub(crate) async fn find_duplicate_issue(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+) -> Result<Option<u64>, IssueError> {
+    let title =
+        generate_issue_title(migration).map_err(|e| IssueError::TemplateError(e.to_string()))?;
+    memo::memoized(&repository.full_name, &title, || {
+        check_duplicate_issue(octocrab, repository, &title)
+    })
+    .await
+}
+
+/// Creates an issue, applying the migration's duplicate policy when a
+/// duplicate issue is open and, unless `forced`, skipping it when an auto-PR
+/// is open.
+async fn create_issue_with(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+    renderer: &TemplateRenderer,
+    pr_status: Option<&PrStatus>,
+    pr_link: Option<&str>,
+    forced: bool,
 ) -> Result<UpgradeIssue, IssueError> {
     let span = info_span!(
         "create_issue",
@@ -65,12 +150,21 @@ pub async fn create_issue(
         let title = generate_issue_title(migration)
             .map_err(|e| IssueError::TemplateError(e.to_string()))?;
 
-        // Check for duplicate
-        if let Some(existing) = check_duplicate_issue(octocrab, repository, &title).await? {
+        // Check for duplicate; forcing files alongside it under `skip`.
+        let policy = migration.duplicate_policy;
+        let existing = if forced && policy == DuplicatePolicy::Skip {
+            None
+        } else {
+            memo::memoized(&repository.full_name, &title, || {
+                check_duplicate_issue(octocrab, repository, &title)
+            })
+            .await?
+        };
+        if let (Some(existing), DuplicatePolicy::Skip) = (existing, policy) {
             info!(issue_number = existing, "Duplicate issue exists, skipping");
             return Ok(UpgradeIssue {
                 repository: repository.clone(),
-                migration_id: migration.id.clone(),
+                migration_id: migration.id.to_string(),
                 title,
                 body: String::new(),
                 status: IssueStatus::Skipped {
@@ -79,35 +173,93 @@ pub async fn create_issue(
             });
         }
 
+        // An open auto-PR from an earlier run already proposes the upgrade.
+        let open_pr = if !forced && existing.is_none() {
+            open_pr::find_open_auto_pr(octocrab, repository, migration).await?
+        } else {
+            None
+        };
+        if let Some(pr_number) = open_pr {
+            info!(pr_number, "Open auto-PR exists, skipping");
+            return Ok(UpgradeIssue {
+                repository: repository.clone(),
+                migration_id: migration.id.to_string(),
+                title,
+                body: String::new(),
+                status: IssueStatus::Skipped {
+                    reason: format!("open auto-PR exists (#{pr_number})"),
+                },
+            });
+        }
+
         // Render template
         let body = renderer
-            .render_issue_template(&migration.issue_template, migration, pr_status, pr_link)
+            .render_issue_template(
+                &migration.issue_template,
+                migration,
+                Some(repository),
+                pr_status,
+                pr_link,
+            )
             .map_err(|e: crate::templates::TemplateError| {
                 IssueError::TemplateError(e.to_string())
             })?;
+        let body =
+            append_team_mentions(octocrab, &repository.owner, &migration.mention_teams, body).await;
+
+        if let Some(existing) = existing.filter(|_| policy != DuplicatePolicy::Recreate) {
+            let reason = duplicate_policy::refresh_existing(
+                octocrab, repository, existing, policy, &body,
+            )
+            .await?;
+            return Ok(UpgradeIssue {
+                repository: repository.clone(),
+                migration_id: migration.id.to_string(),
+                title,
+                body,
+                status: IssueStatus::Skipped { reason },
+            });
+        }
 
         // Create issue
         match create_github_issue(octocrab, repository, &title, &body).await {
             Ok((number, url)) => {
                 info!(issue_number = number, "Issue created successfully");
+                memo::remember(&repository.full_name, &title, number);
+                if let Some(existing) = existing {
+                    // The new issue is already filed; a stale one left open
+                    // is only noise, so don't fail the repository over it.
+                    if let Err(e) =
+                        duplicate_policy::close_superseded(octocrab, repository, existing, number)
+                            .await
+                    {
+                        warn!(issue_number = existing, error = %e, "Failed to close superseded issue");
+                    }
+                }
                 Ok(UpgradeIssue {
                     repository: repository.clone(),
-                    migration_id: migration.id.clone(),
+                    migration_id: migration.id.to_string(),
                     title,
                     body,
                     status: IssueStatus::Created { number, url },
                 })
             }
             Err(e) => {
-                if is_permission_denied(&e) {
+                if is_issues_disabled(&e) {
+                    warn!("Issues are disabled in repository");
+                    Err(IssueError::IssuesDisabled {
+                        owner: repository.owner.clone(),
+                        repo: repository.name.clone(),
+                    })
+                } else if is_permission_denied(&e) {
                     warn!("Permission denied, skipping repository");
                     Ok(UpgradeIssue {
                         repository: repository.clone(),
-                        migration_id: migration.id.clone(),
+                        migration_id: migration.id.to_string(),
                         title,
                         body,
                         status: IssueStatus::Skipped {
-                            reason: "no write access".to_string(),
+                            reason: PERMISSION_DENIED_REASON.to_string(),
                         },
                     })
                 } else {
@@ -161,12 +313,15 @@ pub async fn update_issue_with_pr(
             .render_issue_template(
                 &migration.issue_template,
                 migration,
+                Some(repository),
                 Some(pr_status),
                 pr_link,
             )
             .map_err(|e: crate::templates::TemplateError| {
                 IssueError::TemplateError(e.to_string())
             })?;
+        let body =
+            append_team_mentions(octocrab, &repository.owner, &migration.mention_teams, body).await;
 
         // Ensure rate limit
         ensure_core_rate_limit(octocrab).await?;
@@ -197,10 +352,7 @@ async fn check_duplicate_issue(
     debug!(title = %title, "Checking for duplicate issue");
 
     // Search for open issues with exact title match
-    let query = format!(
-        "repo:{} is:issue is:open in:title \"{}\"",
-        repository.full_name, title
-    );
+    let query = duplicate_query(&repository.full_name, title);
 
     // Check rate limit before search API call
     ensure_search_rate_limit(octocrab).await?;
@@ -221,13 +373,18 @@ async fn check_duplicate_issue(
     Ok(None)
 }
 
+/// Builds the search query for open issues in `repository` titled like `title`.
+fn duplicate_query(repository: &str, title: &str) -> String {
+    format!("repo:{repository} is:issue is:open in:title \"{title}\"")
+}
+
 /// Creates an issue via GitHub API.
 async fn create_github_issue(
     octocrab: &Octocrab,
     repository: &DiscoveredRepository,
     title: &str,
     body: &str,
-) -> Result<(u64, String), IssueError> {
+) -> Result<(u64, HtmlUrl), IssueError> {
     ensure_core_rate_limit(octocrab).await?;
     let issue = octocrab
         .issues(&repository.owner, &repository.name)
@@ -236,22 +393,45 @@ async fn create_github_issue(
         .send()
         .await?;
 
-    let url = issue.html_url.to_string();
-    Ok((issue.number, url))
+    Ok((issue.number, HtmlUrl::from(issue.html_url)))
+}
+
+/// Checks if an error indicates the repository has issues disabled.
+///
+/// GitHub answers issue creation with `410 Gone` in that case.
+fn is_issues_disabled(error: &IssueError) -> bool {
+    match error {
+        IssueError::GitHubError(octocrab::Error::GitHub { source, .. }) => {
+            source.status_code.as_u16() == 410
+                || source
+                    .message
+                    .to_lowercase()
+                    .contains("issues are disabled")
+        }
+        IssueError::IssuesDisabled { .. } => true,
+        _ => false,
+    }
 }
 
 /// Checks if an error indicates permission denied.
+///
+/// Only `403` and `404` count: a `401` means the token itself was rejected,
+/// which fails every repository alike and must not pass as a skip.
 fn is_permission_denied(error: &IssueError) -> bool {
     match error {
-        IssueError::GitHubError(e) => {
-            let msg = e.to_string().to_lowercase();
-            msg.contains("403") || msg.contains("forbidden") || msg.contains("permission")
-        }
         IssueError::PermissionDenied { .. } => true,
+        IssueError::GitHubError(octocrab::Error::GitHub { source, .. }) => {
+            is_denied_status(source.status_code.as_u16(), &source.message)
+        }
         _ => false,
     }
 }
 
+/// Returns true for a `403` or `404` that is not a secondary rate limit.
+fn is_denied_status(status: u16, message: &str) -> bool {
+    matches!(status, 403 | 404) && ErrorKind::of_status(status, message) != ErrorKind::RateLimit
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,4 +447,30 @@ mod tests {
             "error".to_string()
         )));
     }
+
+    #[test]
+    fn rejected_tokens_are_not_permission_denied() {
+        assert!(is_denied_status(
+            403,
+            "Resource not accessible by integration"
+        ));
+        assert!(is_denied_status(404, "Not Found"));
+        assert!(!is_denied_status(401, "Bad credentials"));
+        assert!(!is_denied_status(
+            403,
+            "You have exceeded a secondary rate limit"
+        ));
+    }
+
+    #[test]
+    fn can_detect_issues_disabled() {
+        assert!(is_issues_disabled(&IssueError::IssuesDisabled {
+            owner: "test".to_string(),
+            repo: "repo".to_string()
+        }));
+        assert!(!is_issues_disabled(&IssueError::PermissionDenied {
+            owner: "test".to_string(),
+            repo: "repo".to_string()
+        }));
+    }
 }
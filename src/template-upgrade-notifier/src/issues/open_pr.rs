@@ -0,0 +1,41 @@
+//! Detection of auto-fix PRs left open by earlier runs.
+
+use super::{memo, IssueError};
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::rate_limit::ensure_core_rate_limit;
+use crate::templates::generate_branch_name;
+use octocrab::params::State;
+use octocrab::Octocrab;
+use tracing::debug;
+
+/// Returns the number of an open auto-fix PR for `migration`, if one exists.
+///
+/// Auto-fix PRs are pushed to the migration's branch in the repository
+/// itself, so an open PR from that branch means an earlier run already
+/// proposed the upgrade. Lookups are memoized for the rest of the run.
+pub(super) async fn find_open_auto_pr(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+) -> Result<Option<u64>, IssueError> {
+    // No PR can exist for a branch name that doesn't render.
+    let Ok(branch) = generate_branch_name(migration) else {
+        return Ok(None);
+    };
+    let head = format!("{}:{branch}", repository.owner);
+    memo::memoized(&repository.full_name, &head, || async {
+        debug!(head = %head, "Checking for an open auto-PR");
+        ensure_core_rate_limit(octocrab).await?;
+        let page = octocrab
+            .pulls(&repository.owner, &repository.name)
+            .list()
+            .state(State::Open)
+            .head(head.clone())
+            .per_page(1)
+            .send()
+            .await?;
+        Ok(page.items.first().map(|pr| pr.number))
+    })
+    .await
+}
@@ -0,0 +1,40 @@
+//! When a repository was last notified, judged by our own issues.
+
+use super::{author_qualifier, IssueError};
+use crate::discovery::DiscoveredRepository;
+use crate::rate_limit::ensure_search_rate_limit;
+use chrono::{DateTime, Utc};
+use octocrab::Octocrab;
+use tracing::debug;
+
+/// Returns when the newest issue we opened in the repository since `since`
+/// was created, open or closed.
+///
+/// Used for the notification cooldown when no ledger is kept.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if the search fails.
+pub async fn last_notified_at(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    since: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, IssueError> {
+    let query = format!(
+        "repo:{} is:issue {} created:>={}",
+        repository.full_name,
+        author_qualifier(octocrab).await?,
+        since.format("%Y-%m-%d")
+    );
+    debug!(query = %query, "Looking for recent notifications");
+    ensure_search_rate_limit(octocrab).await?;
+    let page = octocrab
+        .search()
+        .issues_and_pull_requests(&query)
+        .sort("created")
+        .order("desc")
+        .per_page(1)
+        .send()
+        .await?;
+    Ok(page.items.first().map(|issue| issue.created_at))
+}
@@ -0,0 +1,142 @@
+//! Closing notifications once a repository upgrades on its own.
+
+use super::{author_qualifier, IssueError};
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::rate_limit::{ensure_core_rate_limit, ensure_search_rate_limit};
+use crate::templates::{generate_issue_title, generate_pr_title};
+use octocrab::models::IssueState;
+use octocrab::Octocrab;
+use tracing::{debug, info};
+
+/// Results per page when listing open notifications.
+const RESULTS_PER_PAGE: u8 = 100;
+
+/// An open issue or PR this tool filed for a migration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenNotification {
+    /// Repository full name in `owner/name` format.
+    pub repository: String,
+    /// Issue or PR number.
+    pub number: u64,
+    /// Whether this is an auto-fix PR rather than an issue.
+    pub is_pr: bool,
+}
+
+/// Lists the open issues and PRs authored by the authenticated user and
+/// titled as this tool titles them for `migration`.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if a title cannot be rendered or a search fails.
+pub async fn find_open_notifications(
+    octocrab: &Octocrab,
+    migration: &Migration,
+) -> Result<Vec<OpenNotification>, IssueError> {
+    let issue_title =
+        generate_issue_title(migration).map_err(|e| IssueError::TemplateError(e.to_string()))?;
+    let pr_title =
+        generate_pr_title(migration).map_err(|e| IssueError::TemplateError(e.to_string()))?;
+
+    let mut notifications = search_open(octocrab, "issue", &issue_title).await?;
+    notifications.extend(search_open(octocrab, "pr", &pr_title).await?);
+    debug!(count = notifications.len(), "Found open notifications");
+    Ok(notifications)
+}
+
+/// Comments on an issue or PR that the repository has upgraded, then closes it.
+///
+/// # Errors
+///
+/// Returns [`IssueError`] if the comment or the close fails.
+pub async fn close_notification(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    number: u64,
+    comment: &str,
+) -> Result<(), IssueError> {
+    let issues = octocrab.issues(&repository.owner, &repository.name);
+    ensure_core_rate_limit(octocrab).await?;
+    issues.create_comment(number, comment).await?;
+    // The issues API closes PRs too.
+    ensure_core_rate_limit(octocrab).await?;
+    issues
+        .update(number)
+        .state(IssueState::Closed)
+        .send()
+        .await?;
+    info!(repo = %repository.full_name, number, "Closed resolved notification");
+    Ok(())
+}
+
+/// Returns every open item of `kind` we authored with exactly `title`.
+async fn search_open(
+    octocrab: &Octocrab,
+    kind: &str,
+    title: &str,
+) -> Result<Vec<OpenNotification>, IssueError> {
+    let query = format!(
+        "{} is:{kind} is:open in:title \"{}\"",
+        author_qualifier(octocrab).await?,
+        title.replace('"', "")
+    );
+    ensure_search_rate_limit(octocrab).await?;
+    let mut page = octocrab
+        .search()
+        .issues_and_pull_requests(&query)
+        .per_page(RESULTS_PER_PAGE)
+        .send()
+        .await?;
+
+    let mut notifications = Vec::new();
+    loop {
+        notifications.extend(
+            page.items
+                .iter()
+                .filter(|item| item.title == title)
+                .filter_map(|item| {
+                    Some(OpenNotification {
+                        repository: repository_from_api_url(item.repository_url.as_str())?,
+                        number: item.number,
+                        is_pr: item.pull_request.is_some(),
+                    })
+                }),
+        );
+        if page.next.is_none() {
+            break;
+        }
+        ensure_search_rate_limit(octocrab).await?;
+        match octocrab.get_page(&page.next).await? {
+            Some(next) => page = next,
+            None => break,
+        }
+    }
+    Ok(notifications)
+}
+
+/// Extracts `owner/name` from an API repository URL such as
+/// `https://api.github.com/repos/owner/name`.
+pub(crate) fn repository_from_api_url(url: &str) -> Option<String> {
+    let mut segments = url.trim_end_matches('/').rsplit('/');
+    let name = segments.next()?;
+    let owner = segments.next()?;
+    (segments.next() == Some("repos")).then(|| format!("{owner}/{name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_repository_from_api_url() {
+        assert_eq!(
+            repository_from_api_url("https://api.github.com/repos/user/repo").as_deref(),
+            Some("user/repo")
+        );
+        assert_eq!(
+            repository_from_api_url("https://ghe.example.com/api/v3/repos/org/app/").as_deref(),
+            Some("org/app")
+        );
+        assert_eq!(repository_from_api_url("https://github.com/user"), None);
+    }
+}
@@ -1,9 +1,10 @@
 //! Issue status types.
 
-use serde::Serialize;
+use crate::ids::HtmlUrl;
+use serde::{Deserialize, Serialize};
 
 /// Status of an issue creation operation.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum IssueStatus {
     /// Issue not yet created.
@@ -14,7 +15,7 @@ pub enum IssueStatus {
         /// GitHub issue number.
         number: u64,
         /// GitHub issue URL.
-        url: String,
+        url: HtmlUrl,
     },
 
     /// Issue creation skipped.
@@ -29,3 +30,16 @@ pub enum IssueStatus {
         error: String,
     },
 }
+
+impl IssueStatus {
+    /// Returns the status as a string for storage and reporting.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Created { .. } => "created",
+            Self::Skipped { .. } => "skipped",
+            Self::Failed { .. } => "failed",
+        }
+    }
+}
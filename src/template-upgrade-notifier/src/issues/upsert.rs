@@ -0,0 +1,79 @@
+//! Issues kept up to date across runs, found again by their exact title.
+
+use super::author_qualifier;
+use crate::rate_limit::{ensure_core_rate_limit, ensure_search_rate_limit};
+use octocrab::models::issues::Issue;
+use octocrab::Octocrab;
+use tracing::debug;
+
+/// Creates or updates our open issue titled exactly `title` in `owner/name`.
+///
+/// `render` receives the existing issue's body, if there is one, and returns
+/// the new body.
+///
+/// # Errors
+///
+/// Returns an error if the search or the create/update call fails.
+pub(crate) async fn upsert_own_issue(
+    octocrab: &Octocrab,
+    owner: &str,
+    name: &str,
+    title: &str,
+    render: impl FnOnce(Option<&str>) -> String,
+) -> Result<Issue, octocrab::Error> {
+    let query = format!(
+        "repo:{owner}/{name} is:issue is:open {} in:title \"{title}\"",
+        author_qualifier(octocrab).await?
+    );
+    debug!(query = %query, "Looking for existing issue");
+    ensure_search_rate_limit(octocrab).await?;
+    let existing = octocrab
+        .search()
+        .issues_and_pull_requests(&query)
+        .send()
+        .await?
+        .items
+        .into_iter()
+        .find(|issue| issue.title == title);
+
+    ensure_core_rate_limit(octocrab).await?;
+    match existing {
+        Some(issue) => {
+            let body = render(issue.body.as_deref());
+            octocrab
+                .issues(owner, name)
+                .update(issue.number)
+                .body(&body)
+                .send()
+                .await
+        }
+        None => {
+            let body = render(None);
+            octocrab
+                .issues(owner, name)
+                .create(title)
+                .body(&body)
+                .send()
+                .await
+        }
+    }
+}
+
+/// Splits an `owner/name` repository into its parts.
+pub(crate) fn split_repository(repository: &str) -> Option<(&str, &str)> {
+    repository
+        .split_once('/')
+        .filter(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_repository() {
+        assert_eq!(split_repository("org/template"), Some(("org", "template")));
+        assert_eq!(split_repository("org/"), None);
+        assert_eq!(split_repository("org/a/b"), None);
+    }
+}
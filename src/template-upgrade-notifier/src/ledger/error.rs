@@ -0,0 +1,23 @@
+//! Idempotency ledger error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while reading or writing the ledger.
+#[derive(Debug, Error)]
+pub enum LedgerError {
+    /// The ledger file could not be read or written.
+    #[error("Failed to access ledger '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The ledger file is not valid JSON.
+    #[error("Invalid ledger '{path}': {source}")]
+    Json {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
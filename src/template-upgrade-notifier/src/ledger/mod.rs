@@ -0,0 +1,394 @@
+//! Durable record of the issues and PRs filed for each migration and repository.
+//!
+//! The ledger is a small JSON file, suitable for committing to the tracking
+//! repository. It is consulted before duplicate-issue searches, so a repository
+//! that was already notified is never notified again, even if the issue was
+//! retitled or closed, and no search API call is spent on it.
+
+mod error;
+
+pub use error::LedgerError;
+
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// What was filed for one migration in one repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Repository full name in `owner/name` format.
+    pub repository: String,
+    /// Number of the upgrade issue, if one was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue: Option<u64>,
+    /// Number of the auto-fix PR, if one was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr: Option<u64>,
+    /// Whether a `repository_dispatch` event was sent instead of an issue.
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub dispatched: bool,
+    /// RFC 3339 timestamp of the last change to this entry.
+    pub recorded_at: String,
+    /// Whether the maintainers declined the upgrade (👎 or a "wontfix"-style
+    /// label on the issue), as found by the `status` subcommand.
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub declined: bool,
+    /// The migration's `new_string` when the maintainers closed its issue as
+    /// "not planned", as found by the `status` subcommand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_planned_version: Option<String>,
+}
+
+impl LedgerEntry {
+    /// Returns the skip reason reported for a repository found in the ledger.
+    #[must_use]
+    pub fn skip_reason(&self) -> String {
+        if self.not_planned_version.is_some() {
+            return match self.issue {
+                Some(issue) => format!("closed as not planned (#{issue})"),
+                None => "closed as not planned".to_string(),
+            };
+        }
+        if self.declined {
+            return match self.issue {
+                Some(issue) => format!("declined by maintainers (#{issue})"),
+                None => "declined by maintainers".to_string(),
+            };
+        }
+        match (self.issue, self.pr) {
+            (Some(issue), _) => format!("already notified (#{issue})"),
+            (None, Some(pr)) => format!("already notified (PR #{pr})"),
+            (None, None) if self.dispatched => "already dispatched".to_string(),
+            (None, None) => "already notified".to_string(),
+        }
+    }
+
+    /// Returns the URL of the issue, or of the PR if no issue was created.
+    #[must_use]
+    pub fn notice_url(&self) -> Option<String> {
+        match (self.issue, self.pr) {
+            (Some(issue), _) => Some(format!(
+                "https://github.com/{}/issues/{issue}",
+                self.repository
+            )),
+            (None, Some(pr)) => Some(format!("https://github.com/{}/pull/{pr}", self.repository)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The set of repositories already notified, keyed by migration and repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ledger {
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    /// Loads the ledger at `path`; a missing file is an empty ledger.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError`] if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, LedgerError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(LedgerError::Io {
+                    path: path.display().to_string(),
+                    source,
+                })
+            }
+        };
+        serde_json::from_str(&json).map_err(|source| LedgerError::Json {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    /// Writes the ledger to `path` as pretty-printed JSON, sorted for stable diffs.
+    ///
+    /// The file is replaced atomically, so a crash never leaves it half-written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LedgerError::Io`] if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), LedgerError> {
+        let io_error = |source| LedgerError::Io {
+            path: path.display().to_string(),
+            source,
+        };
+        let mut sorted = self.clone();
+        sorted.entries.sort_by(|a, b| {
+            (&a.migration_id, &a.repository).cmp(&(&b.migration_id, &b.repository))
+        });
+        let json = serde_json::to_string_pretty(&sorted).map_err(|e| io_error(e.into()))?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, json).map_err(io_error)?;
+        std::fs::rename(&temp, path).map_err(io_error)
+    }
+
+    /// Returns the entry for `migration_id` in `repository`, if it was notified.
+    #[must_use]
+    pub fn get(&self, migration_id: &str, repository: &str) -> Option<&LedgerEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.migration_id == migration_id && e.repository == repository)
+    }
+
+    /// Returns the most recently recorded entry for `repository`, across all
+    /// migrations, if it was ever notified.
+    #[must_use]
+    pub fn latest_for_repository(&self, repository: &str) -> Option<&LedgerEntry> {
+        // Timestamps are all written as UTC RFC 3339, so they sort as strings.
+        self.entries
+            .iter()
+            .filter(|e| e.repository == repository)
+            .max_by(|a, b| a.recorded_at.cmp(&b.recorded_at))
+    }
+
+    /// Records that issue `number` was created.
+    pub fn record_issue(
+        &mut self,
+        migration_id: &str,
+        repository: &str,
+        number: u64,
+        recorded_at: &str,
+    ) {
+        let entry = self.entry(migration_id, repository, recorded_at);
+        entry.issue = Some(number);
+    }
+
+    /// Records that PR `number` was created.
+    pub fn record_pr(
+        &mut self,
+        migration_id: &str,
+        repository: &str,
+        number: u64,
+        recorded_at: &str,
+    ) {
+        let entry = self.entry(migration_id, repository, recorded_at);
+        entry.pr = Some(number);
+    }
+
+    /// Records that a `repository_dispatch` event was sent.
+    pub fn record_dispatch(&mut self, migration_id: &str, repository: &str, recorded_at: &str) {
+        let entry = self.entry(migration_id, repository, recorded_at);
+        entry.dispatched = true;
+    }
+
+    /// Records that the maintainers declined the upgrade in issue `number`.
+    ///
+    /// An existing entry keeps its `recorded_at`, so a decline found later
+    /// doesn't restart the notification cooldown. Returns true if the entry
+    /// was not already marked declined.
+    pub fn record_declined(
+        &mut self,
+        migration_id: &str,
+        repository: &str,
+        number: u64,
+        recorded_at: &str,
+    ) -> bool {
+        if self
+            .get(migration_id, repository)
+            .is_some_and(|entry| entry.declined)
+        {
+            return false;
+        }
+        let entry = self.entry_keeping_stamp(migration_id, repository, recorded_at);
+        entry.issue = Some(number);
+        entry.declined = true;
+        true
+    }
+
+    /// Records that the maintainers closed issue `number` as "not planned",
+    /// for a migration to `version`.
+    ///
+    /// Like [`Ledger::record_declined`], an existing entry keeps its
+    /// `recorded_at`. Returns true if the entry was not already marked not
+    /// planned.
+    pub fn record_not_planned(
+        &mut self,
+        migration_id: &str,
+        repository: &str,
+        number: u64,
+        version: &str,
+        recorded_at: &str,
+    ) -> bool {
+        if self
+            .get(migration_id, repository)
+            .is_some_and(|entry| entry.not_planned_version.is_some())
+        {
+            return false;
+        }
+        let entry = self.entry_keeping_stamp(migration_id, repository, recorded_at);
+        entry.issue = Some(number);
+        entry.not_planned_version = Some(version.to_string());
+        true
+    }
+
+    /// Returns all entries.
+    #[must_use]
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    /// Returns the entry for the pair, creating it stamped with `recorded_at`
+    /// if needed; an existing entry keeps its stamp.
+    fn entry_keeping_stamp(
+        &mut self,
+        migration_id: &str,
+        repository: &str,
+        recorded_at: &str,
+    ) -> &mut LedgerEntry {
+        let stamp = self.get(migration_id, repository).map_or_else(
+            || recorded_at.to_string(),
+            |entry| entry.recorded_at.clone(),
+        );
+        self.entry(migration_id, repository, &stamp)
+    }
+
+    /// Returns the entry for the pair, creating it if needed, stamped with `recorded_at`.
+    fn entry(
+        &mut self,
+        migration_id: &str,
+        repository: &str,
+        recorded_at: &str,
+    ) -> &mut LedgerEntry {
+        let index = match self
+            .entries
+            .iter()
+            .position(|e| e.migration_id == migration_id && e.repository == repository)
+        {
+            Some(index) => index,
+            None => {
+                self.entries.push(LedgerEntry {
+                    migration_id: migration_id.to_string(),
+                    repository: repository.to_string(),
+                    issue: None,
+                    pr: None,
+                    dispatched: false,
+                    recorded_at: String::new(),
+                    declined: false,
+                    not_planned_version: None,
+                });
+                self.entries.len() - 1
+            }
+        };
+        let entry = &mut self.entries[index];
+        entry.recorded_at = recorded_at.to_string();
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const NOW: &str = "2024-01-01T00:00:00+00:00";
+    const LATER: &str = "2024-02-01T00:00:00+00:00";
+
+    #[test]
+    fn finds_latest_notice_across_migrations() {
+        let mut ledger = Ledger::default();
+        ledger.record_issue("tpl/v1-to-v2", "user/repo", 12, NOW);
+        ledger.record_pr("tpl/v2-to-v3", "user/repo", 30, "2024-06-01T00:00:00+00:00");
+        ledger.record_issue("tpl/v1-to-v2", "user/other", 4, NOW);
+
+        let latest = ledger.latest_for_repository("user/repo").unwrap();
+
+        assert_eq!(latest.migration_id, "tpl/v2-to-v3");
+        assert_eq!(
+            latest.notice_url().as_deref(),
+            Some("https://github.com/user/repo/pull/30")
+        );
+        assert!(ledger.latest_for_repository("user/none").is_none());
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let temp = TempDir::new().unwrap();
+        let ledger = Ledger::load(&temp.path().join("ledger.json")).unwrap();
+        assert!(ledger.entries().is_empty());
+    }
+
+    #[test]
+    fn records_issue_and_pr_in_one_entry() {
+        let mut ledger = Ledger::default();
+        ledger.record_issue("m1", "user/repo", 3, NOW);
+        ledger.record_pr("m1", "user/repo", 4, NOW);
+
+        let entry = ledger.get("m1", "user/repo").unwrap();
+        assert_eq!((entry.issue, entry.pr), (Some(3), Some(4)));
+        assert_eq!(entry.skip_reason(), "already notified (#3)");
+        assert!(ledger.get("m2", "user/repo").is_none());
+        assert_eq!(ledger.entries().len(), 1);
+    }
+
+    #[test]
+    fn records_dispatch() {
+        let mut ledger = Ledger::default();
+        ledger.record_dispatch("m1", "user/repo", NOW);
+
+        let entry = ledger.get("m1", "user/repo").unwrap();
+        assert!(entry.dispatched);
+        assert_eq!(entry.skip_reason(), "already dispatched");
+        assert_eq!(entry.notice_url(), None);
+    }
+
+    #[test]
+    fn records_decline_once() {
+        let mut ledger = Ledger::default();
+        ledger.record_issue("m1", "user/repo", 3, NOW);
+
+        assert!(ledger.record_declined("m1", "user/repo", 3, LATER));
+        assert!(!ledger.record_declined("m1", "user/repo", 3, LATER));
+        assert_eq!(ledger.get("m1", "user/repo").unwrap().recorded_at, NOW);
+        assert_eq!(
+            ledger.get("m1", "user/repo").unwrap().skip_reason(),
+            "declined by maintainers (#3)"
+        );
+    }
+
+    #[test]
+    fn records_not_planned_once() {
+        let mut ledger = Ledger::default();
+
+        assert!(ledger.record_not_planned("m1", "user/repo", 3, "tpl:2.0.0", NOW));
+        assert!(!ledger.record_not_planned("m1", "user/repo", 3, "tpl:2.0.0", NOW));
+        let entry = ledger.get("m1", "user/repo").unwrap();
+        assert_eq!(entry.not_planned_version.as_deref(), Some("tpl:2.0.0"));
+        assert_eq!(entry.skip_reason(), "closed as not planned (#3)");
+    }
+
+    #[test]
+    fn round_trips_sorted() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("ledger.json");
+        let mut ledger = Ledger::default();
+        ledger.record_pr("m2", "user/b", 7, NOW);
+        ledger.record_issue("m1", "user/a", 1, NOW);
+
+        ledger.save(&path).unwrap();
+        let loaded = Ledger::load(&path).unwrap();
+
+        assert_eq!(loaded.entries()[0].repository, "user/a");
+        assert_eq!(
+            loaded.get("m2", "user/b").unwrap().skip_reason(),
+            "already notified (PR #7)"
+        );
+        assert!(!temp.path().join("ledger.json.tmp").exists());
+    }
+
+    #[test]
+    fn rejects_invalid_json() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("ledger.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(Ledger::load(&path), Err(LedgerError::Json { .. })));
+    }
+}
@@ -1,29 +1,144 @@
 #![doc = include_str!(concat!("../", env!("CARGO_PKG_README")))]
 
+pub mod azure_devops;
+pub mod campaign;
+pub mod changelog;
+pub mod checks;
+pub mod clock;
 pub mod config;
 pub mod discovery;
+pub mod dispatch;
+pub mod doctor;
+pub mod email;
+pub mod error_kind;
+pub mod events;
+pub mod forge;
+pub mod gitlab;
+#[cfg(feature = "history")]
+pub mod history;
+pub mod hooks;
+pub mod http_cache;
+pub mod ids;
 pub mod issues;
+pub mod ledger;
 pub(crate) mod llm;
+pub mod local;
+pub mod preferences;
+pub mod project_board;
+pub mod proxy;
 pub mod pull_requests;
 pub mod rate_limit;
+pub mod redact;
+pub mod replace;
+pub mod repo_data;
+pub mod risk;
+pub mod rollout;
 pub mod runner;
+pub mod schedule;
+pub mod selection;
+pub mod stages;
+pub mod state;
+pub mod status;
 pub mod summary;
+pub mod teams;
 pub mod templates;
+pub mod webhooks;
 
-pub use config::{scan_migrations, ConfigError, Migration, MigrationMetadata};
+#[cfg(feature = "azure-devops")]
+pub use azure_devops::AzureDevOpsClient;
+pub use azure_devops::{AzureDevOpsConfig, AzureDevOpsError, AzureRepository};
+pub use campaign::{update_tracking_issue, Campaign, CampaignConfig, CampaignError};
+pub use changelog::{excerpt_for, extract_section, fetch_changelog, ChangelogError};
+pub use checks::{publish_outdated_check, CheckKind, ChecksError, CHECK_CONTEXT};
+pub use clock::{Clock, SystemClock};
+pub use config::{
+    scan_migrations, scan_migrations_strict, ConfigError, DryRunLevel, Migration,
+    MigrationMetadata, NotPlannedPolicy, NotifierConfig, VersionSource, MIGRATIONS_IGNORE_FILE,
+};
 pub use discovery::{
-    discover_repositories, enrich_with_default_branches, get_default_branch, DiscoveredRepository,
-    DiscoveryError,
+    detect_template_version, disable_search_cache, discover_from_list, discover_in_repository,
+    discover_repositories, discover_repositories_graphql, discover_upgraded_repositories,
+    enrich_with_default_branches, enrich_with_graphql, fetch_file_sizes, get_default_branch,
+    load_search_cache, reset_search_cache, save_search_cache, verify_old_string,
+    verify_on_default_branch, verify_upgraded, DiscoveredRepository, DiscoveryError, ExcludeConfig,
+    FalsePositiveConfig, FileSnippet, ListedRepository, OptOutConfig, RepoList, RepoListError,
+    SearchCacheError, TemplateLocation, Visibility, ALREADY_UPGRADED_REASON,
+    STALE_SEARCH_RESULT_REASON,
+};
+pub use dispatch::{render_payload, send_dispatch, DispatchConfig, DispatchError, DispatchMode};
+pub use doctor::{run_doctor, CheckStatus, DoctorCheck, DoctorReport};
+pub use email::{send_email_digest, EmailConfig, EmailError, SmtpSecurity};
+pub use error_kind::ErrorKind;
+pub use events::{EventHandler, RunEvent};
+pub use forge::{Forge, ForgeError, GitHubForge};
+#[cfg(feature = "gitlab")]
+pub use gitlab::GitLabClient;
+pub use gitlab::{GitLabConfig, GitLabError, GitLabProject};
+#[cfg(feature = "history")]
+pub use history::{HistoryError, HistoryStore, ResultRecord, RunDiff, RunRecord};
+pub use hooks::{
+    CommandHook, HookCommand, HookError, HookEvent, HookStage, HooksConfig, LifecycleHook,
+};
+pub use http_cache::{
+    disable_etag_cache, load_etag_cache, reset_etag_cache, save_etag_cache, HttpCacheError,
+};
+pub use ids::{HtmlUrl, IdError, MigrationId, RepoSlug};
+pub use issues::{
+    clear_lookup_cache, close_notification, create_issue, find_open_notifications,
+    force_create_issue, last_notified_at, prefetch_duplicate_issues, update_issue_with_pr,
+    DuplicatePolicy, IssueError, IssueStatus, OpenNotification, UpgradeIssue,
+};
+pub use ledger::{Ledger, LedgerEntry, LedgerError};
+pub use local::{scan_local, LocalPatch, LocalScan, LocalScanError, LocalScanOptions};
+pub use preferences::{
+    clear_preferences_cache, fetch_preferences, PreferencesError, RepoPreferences, PREFERENCES_PATH,
+};
+pub use project_board::{
+    sync_project_board, BoardStatus, BoardStatusNames, ProjectBoardConfig, ProjectBoardError,
+};
+pub use proxy::{ProxyConfig, ProxyError};
+pub use pull_requests::{
+    create_pr, create_pr_timed, preview_pr, CheckoutConfig, CheckoutPolicy, CommitIdentity,
+    MigrationStrategy, PrDiff, PrError, PrMetadata, PrOptions, PrStatus, UpgradePR,
+    PUSH_REJECTED_REASON,
 };
-pub use issues::{create_issue, update_issue_with_pr, IssueError, IssueStatus, UpgradeIssue};
-pub use pull_requests::{create_pr, PrError, PrStatus, UpgradePR};
 pub use rate_limit::{
     check_core_rate_limit, check_search_rate_limit, ensure_core_rate_limit,
     ensure_search_rate_limit, wait_for_retry_after, wait_if_needed, RateLimitInfo,
 };
-pub use runner::{Runner, RunnerConfig, RunnerError};
-pub use summary::{ProcessingResult, RunSummary};
+pub use redact::{redact, register_secret, RedactingWriter};
+pub use replace::{replace_in_bytes, replace_in_file, ReplaceError, ReplaceOutcome};
+pub use repo_data::{RepoData, RepoDataError};
+pub use risk::{assess_risk, RiskError, RiskFactors};
+pub use rollout::{update_rollout_issue, RolloutError, RolloutIssueConfig};
+pub use runner::{
+    Checkpoint, PendingRepository, RerunTarget, Runner, RunnerBuilder, RunnerConfig, RunnerError,
+};
+pub use schedule::{Schedule, ScheduleConfig, ScheduleError};
+pub use selection::{PatternSet, PriorityList, Sample, SelectionError};
+pub use stages::{in_rollout, rollout_bucket, RolloutPercent, StageError, StageState};
+#[cfg(feature = "state-object")]
+pub use state::ObjectStateStore;
+#[cfg(feature = "state-sqlite")]
+pub use state::SqliteStateStore;
+pub use state::{
+    open_state_store, LocalStateStore, StateError, StateStore, StateVersion, StoredState,
+    CHECKPOINT_KEY, HISTORY_KEY, LEDGER_KEY,
+};
+pub use status::{
+    declined_issues, fleet_status, Acknowledgement, DeclinedIssue, Declines, MigrationStatus,
+    StatusError,
+};
+pub use summary::{
+    append_step_summary, emit_annotations, write_github_outputs, write_markdown_report,
+    write_sarif_report, ComplianceMatrix, ComplianceRow, ExitPolicy, MigrationTotals,
+    ProcessingResult, QuotaEstimate, ReportFormat, RunOutcome, RunSummary, Severity,
+    SummaryCollector, GITHUB_ACTIONS_ENV, GITHUB_OUTPUT_ENV, GITHUB_STEP_SUMMARY_ENV,
+};
+pub use teams::{clear_team_cache, existing_teams};
 pub use templates::{
     create_handlebars_registry, generate_branch_name, generate_commit_title, generate_issue_title,
-    generate_pr_title, TemplateError, TemplateRenderer,
+    generate_pr_title, slugify, TemplateError, TemplateRenderer,
 };
+pub use tokio_util::sync::CancellationToken;
+pub use webhooks::{WebhookDelivery, WebhookError, WebhookSource, WebhookVerifier};
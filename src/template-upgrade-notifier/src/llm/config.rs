@@ -1,6 +1,7 @@
 //! LLM provider configuration.
 
 use crate::llm::error::LlmError;
+use crate::redact::register_secret;
 use serdes_ai_models::{build_model_with_config, infer_model, openrouter::OpenRouterModel, Model};
 use std::sync::Arc;
 
@@ -100,6 +101,9 @@ impl LlmConfig {
                 let resolved_key = std::env::var("OPENROUTER_API_KEY")
                     .ok()
                     .or_else(|| api_key.clone());
+                if let Some(key) = &resolved_key {
+                    register_secret(key.as_str());
+                }
                 let resolved_referer = std::env::var("OPENROUTER_HTTP_REFERER")
                     .ok()
                     .or_else(|| http_referer.clone());
@@ -159,6 +163,9 @@ fn build_configured_model(
 ) -> Result<Arc<dyn Model>, LlmError> {
     // Env vars take precedence over config values
     let resolved_key = env_api_key(provider).or_else(|| api_key.as_deref().map(str::to_owned));
+    if let Some(key) = &resolved_key {
+        register_secret(key.as_str());
+    }
     let resolved_base_url = env_base_url(provider).or_else(|| base_url.clone());
     let resolved_timeout_secs = env_timeout_secs(provider).or(*timeout_secs);
     let timeout = resolved_timeout_secs.map(core::time::Duration::from_secs);
@@ -2,11 +2,14 @@
 
 mod config;
 mod error;
+mod readme;
 
 pub(crate) use config::LlmConfig;
 pub(crate) use error::LlmError;
+pub(crate) use readme::fetch_readme_excerpt;
 
-use crate::config::Migration;
+use crate::config::{Migration, VersionSource};
+use crate::discovery::DiscoveredRepository;
 use llm_coding_tools_serdesai::agent_ext::AgentBuilderExt;
 use llm_coding_tools_serdesai::allowed::{EditTool, GlobTool, GrepTool, ReadTool};
 use llm_coding_tools_serdesai::{AllowedPathResolver, BashTool, SystemPromptBuilder};
@@ -33,6 +36,9 @@ struct LlmConfigFile {
 /// * `repo_path` - Path to the cloned repository
 /// * `config_path` - Path to the LLM config.toml file
 /// * `migration` - Migration to apply
+/// * `repository` - Repository being migrated, with its detected version and
+///   every location the template was found in
+/// * `readme` - Leading lines of the repository's README, if enabled
 ///
 /// # Returns
 ///
@@ -41,12 +47,14 @@ pub(crate) async fn apply_migration(
     repo_path: &Path,
     config_path: &Path,
     migration: &Migration,
+    repository: &DiscoveredRepository,
+    readme: Option<&str>,
 ) -> Result<(), LlmError> {
     let config = load_config(config_path)?;
     let model = resolve_model(config.as_ref())?;
     let temperature = resolve_temperature(config.as_ref());
     let agent = build_agent(model, repo_path, temperature)?;
-    let prompt = build_prompt(migration);
+    let prompt = build_prompt(migration, repository, readme);
 
     tokio::time::timeout(
         tokio::time::Duration::from_secs(LLM_TIMEOUT_SECS),
@@ -58,6 +66,20 @@ pub(crate) async fn apply_migration(
     .map_err(LlmError::from)
 }
 
+/// Resolves the LLM model without running it, for preflight checks.
+///
+/// # Returns
+///
+/// A description of where the model was resolved from.
+pub(crate) fn check_config(config_path: &Path) -> Result<String, LlmError> {
+    let config = load_config(config_path)?;
+    resolve_model(config.as_ref())?;
+    Ok(match config {
+        Some(_) => config_path.display().to_string(),
+        None => MODEL_ENV.to_string(),
+    })
+}
+
 /// Resolves the LLM model from config or environment.
 fn resolve_model(config: Option<&LlmConfig>) -> Result<Arc<dyn serdes_ai_models::Model>, LlmError> {
     if let Some(config) = config {
@@ -140,26 +162,75 @@ fn build_agent(
 }
 
 /// Builds the migration prompt for the LLM.
-fn build_prompt(migration: &Migration) -> String {
+///
+/// Repositories containing several instances of the template get every
+/// matched path listed, so each instance is migrated. A README excerpt, if
+/// given, is included so edits follow the project's conventions.
+fn build_prompt(
+    migration: &Migration,
+    repository: &DiscoveredRepository,
+    readme: Option<&str>,
+) -> String {
+    let template_version = repository.template_version.as_deref();
     let guide_line = migration
         .migration_guide_link
         .as_ref()
         .map(|g| format!("Migration guide: {g}\n"))
         .unwrap_or_default();
+    let current = template_version.unwrap_or(&migration.old_string);
+    let source_line = match migration.version_source {
+        VersionSource::Text => String::new(),
+        VersionSource::Copier => format!(
+            "Version source: the `_commit` key of the copier answers file (currently {current}); \
+set it to the new string.\n"
+        ),
+        VersionSource::Cruft => format!(
+            "Version source: the `checkout` and `commit` keys of the cruft state file \
+(currently {current}); set `checkout` to the new string.\n"
+        ),
+        VersionSource::CargoGenerate => format!(
+            "Version source: the cargo-generate template version in {target_file} \
+(currently {current}); set it to the new string.\n",
+            target_file = migration.target_file
+        ),
+    };
+
+    let locations_line = if repository.has_multiple_locations() {
+        let paths: String = repository
+            .locations()
+            .iter()
+            .map(|location| format!("- {}\n", location.path))
+            .collect();
+        format!("The template is instantiated in several locations; migrate each of them:\n{paths}")
+    } else {
+        String::new()
+    };
+
+    let readme_section = readme
+        .map(|text| {
+            format!(
+                "Project README (leading lines; follow the conventions it describes):\n\
+<readme>\n{text}\n</readme>\n"
+            )
+        })
+        .unwrap_or_default();
 
     format!(
         "Apply the template migration using the available tools.\n\
-Target file: {target_file}\n\
+Target file: {target_files}\n\
 Old string: {old_string}\n\
 New string: {new_string}\n\
 {guide_line}\
+{source_line}\
+{locations_line}\
+{readme_section}\
 Steps:\n\
 1) Use glob/grep to locate relevant files.\n\
 2) Update occurrences of the old string to the new string.\n\
 3) Keep changes minimal and confined to the repo.\n\
 4) Do not commit or push any changes.\n\
 5) Reply with a brief summary of edits.",
-        target_file = migration.target_file,
+        target_files = migration.target_files().join(", "),
         old_string = migration.old_string,
         new_string = migration.new_string,
     )
@@ -168,6 +239,7 @@ Steps:\n\
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::discovery::TemplateLocation;
     use std::fs;
     use tempfile::TempDir;
 
@@ -322,6 +394,15 @@ model = "claude-3-5-sonnet-20241022"
         assert_eq!(config.temperature(), None);
     }
 
+    #[test]
+    fn check_config_reports_missing_model() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("missing.toml");
+        temp_env::with_var_unset(MODEL_ENV, || {
+            assert!(matches!(check_config(&path), Err(LlmError::MissingModel)));
+        });
+    }
+
     #[test]
     fn resolve_temperature_returns_none_without_config_or_env() {
         temp_env::with_var_unset(TEMPERATURE_ENV, || {
@@ -329,6 +410,68 @@ model = "claude-3-5-sonnet-20241022"
         });
     }
 
+    fn repository(paths: &[&str]) -> DiscoveredRepository {
+        let location = |path: &str| TemplateLocation {
+            path: path.to_string(),
+            url: format!("https://github.com/user/repo/blob/main/{path}")
+                .parse()
+                .unwrap(),
+        };
+        DiscoveredRepository {
+            owner: "user".to_string(),
+            name: "repo".to_string(),
+            full_name: "user/repo".parse().unwrap(),
+            file_path: paths[0].to_string(),
+            file_url: location(paths[0]).url,
+            default_branch: "main".to_string(),
+            template_version: None,
+            additional_locations: paths[1..].iter().map(|path| location(path)).collect(),
+            snippet: None,
+            previously_notified: false,
+            previous_issue_url: None,
+            owner_is_bot: false,
+            is_mirror: false,
+            archived: false,
+            disabled: false,
+            visibility: None,
+            fork_ancestors: Vec::new(),
+            file_size: None,
+            topics: None,
+            opted_out: None,
+            repo_data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn prompt_lists_every_template_location() {
+        let migration = Migration {
+            target_file: "version.txt".to_string(),
+            ..Migration::default()
+        };
+
+        let single = build_prompt(&migration, &repository(&["version.txt"]), None);
+        let multiple = build_prompt(
+            &migration,
+            &repository(&["a/version.txt", "b/version.txt"]),
+            None,
+        );
+
+        assert!(!single.contains("several locations"));
+        assert!(multiple.contains("migrate each of them:\n- a/version.txt\n- b/version.txt\n"));
+    }
+
+    #[test]
+    fn prompt_includes_readme_excerpt() {
+        let migration = Migration::default();
+        let repository = repository(&["version.txt"]);
+
+        let without = build_prompt(&migration, &repository, None);
+        let with = build_prompt(&migration, &repository, Some("# Repo\nUse tabs."));
+
+        assert!(!without.contains("<readme>"));
+        assert!(with.contains("<readme>\n# Repo\nUse tabs.\n</readme>\n"));
+    }
+
     #[test]
     fn resolve_temperature_uses_config_value() {
         temp_env::with_var_unset(TEMPERATURE_ENV, || {
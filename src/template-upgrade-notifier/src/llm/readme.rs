@@ -0,0 +1,59 @@
+//! README excerpts giving the agent context on a project's conventions.
+
+use crate::discovery::DiscoveredRepository;
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use tracing::{debug, warn};
+
+/// Fetches the first `lines` lines of the repository's README.
+///
+/// The excerpt is optional context, so a missing README or a failed request
+/// yields `None` rather than failing the auto-fix.
+pub(crate) async fn fetch_readme_excerpt(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    lines: usize,
+) -> Option<String> {
+    if let Err(e) = ensure_core_rate_limit(octocrab).await {
+        warn!(error = %e, "Rate limit check failed, skipping README context");
+        return None;
+    }
+    let readme = octocrab
+        .repos(&repository.owner, &repository.name)
+        .get_readme()
+        .send()
+        .await;
+    match readme {
+        Ok(content) => content
+            .decoded_content()
+            .map(|text| excerpt(&text, lines))
+            .filter(|text| !text.trim().is_empty()),
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+            debug!("Repository has no README");
+            None
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to fetch README, continuing without it");
+            None
+        }
+    }
+}
+
+/// Returns the first `lines` lines of `text`.
+fn excerpt(text: &str, lines: usize) -> String {
+    text.lines().take(lines).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpt_keeps_leading_lines() {
+        assert_eq!(
+            excerpt("# Title\n\nUse tabs.\nMore", 3),
+            "# Title\n\nUse tabs."
+        );
+        assert_eq!(excerpt("short", 10), "short");
+    }
+}
@@ -0,0 +1,19 @@
+//! Local scan error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while scanning checked-out repositories.
+#[derive(Debug, Error)]
+pub enum LocalScanError {
+    /// A directory or file could not be read or written.
+    #[error("I/O error at {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A replacement could not be computed.
+    #[error(transparent)]
+    Replace(#[from] crate::replace::ReplaceError),
+}
@@ -0,0 +1,384 @@
+//! Offline scans of repositories already checked out on disk.
+//!
+//! Instead of GitHub code search, every repository under a directory is
+//! searched for each migration's `target_file` containing `old_string`. The
+//! replacement is computed locally with [`replace_in_bytes`], and the result
+//! is reported as a [`RunSummary`] (so the usual markdown, SARIF and table
+//! reports work) plus one patch per repository and migration. Nothing talks
+//! to GitHub.
+
+mod error;
+mod patch;
+
+pub use error::LocalScanError;
+
+use crate::config::Migration;
+use crate::discovery::{target_path_matcher, DiscoveredRepository, TemplateLocation};
+use crate::ids::{HtmlUrl, RepoSlug};
+use crate::replace::{replace_in_bytes, replace_in_file, ReplaceOutcome};
+use crate::summary::{Occurrence, RunSummary};
+use crate::templates::slugify;
+use patch::unified_diff;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// Owner given to checked-out repositories, which have none of their own.
+const LOCAL_OWNER: &str = "local";
+
+/// Directories never searched for target files.
+const SKIPPED_DIRS: [&str; 3] = [".git", "node_modules", "target"];
+
+/// How a local scan treats the files it finds.
+#[derive(Debug, Clone, Default)]
+pub struct LocalScanOptions {
+    /// Rewrite matched files in place instead of only reporting them.
+    pub apply: bool,
+    /// Write one `<repository>/<migration>.patch` per match below this directory.
+    pub patch_dir: Option<PathBuf>,
+}
+
+/// Changes a migration makes to one checked-out repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalPatch {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Repository directory name.
+    pub repository: String,
+    /// Unified diff of every matched file, relative to the repository root.
+    pub diff: String,
+}
+
+/// Result of a local scan.
+#[derive(Debug, Clone, Default)]
+pub struct LocalScan {
+    /// Repositories and files found, in the same shape as a dry run's summary.
+    pub summary: RunSummary,
+    /// Changes per repository and migration.
+    pub patches: Vec<LocalPatch>,
+}
+
+/// Scans the repositories under `root` for every migration.
+///
+/// `root` is either a single checked-out repository or a directory whose
+/// immediate subdirectories are repositories.
+///
+/// # Errors
+///
+/// Returns [`LocalScanError`] if `root` cannot be listed, or a patch cannot be
+/// written. Unreadable files are skipped with a warning.
+pub fn scan_local(
+    root: &Path,
+    migrations: &[Migration],
+    options: &LocalScanOptions,
+) -> Result<LocalScan, LocalScanError> {
+    let repositories = list_repositories(root)?;
+    info!(
+        root = %root.display(),
+        count = repositories.len(),
+        "Scanning local repositories"
+    );
+
+    let mut scan = LocalScan {
+        summary: RunSummary::new(true),
+        patches: Vec::new(),
+    };
+    scan.summary.migrations_processed = migrations.len();
+
+    for migration in migrations {
+        for repo_path in &repositories {
+            let Some(patch) = scan_repository(repo_path, migration, options, &mut scan.summary)?
+            else {
+                continue;
+            };
+            if let Some(dir) = &options.patch_dir {
+                write_patch(dir, &patch)?;
+            }
+            scan.patches.push(patch);
+        }
+    }
+
+    info!(
+        discovered = scan.summary.repositories_discovered,
+        applied = options.apply,
+        "Local scan complete"
+    );
+    Ok(scan)
+}
+
+/// Scans one repository for one migration, recording what it finds in `summary`.
+fn scan_repository(
+    repo_path: &Path,
+    migration: &Migration,
+    options: &LocalScanOptions,
+    summary: &mut RunSummary,
+) -> Result<Option<LocalPatch>, LocalScanError> {
+    let name = repo_path.file_name().map_or_else(
+        || repo_path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    let full_name = match RepoSlug::from_parts(LOCAL_OWNER, &slugify(&name)) {
+        Ok(full_name) => full_name,
+        Err(e) => {
+            warn!(repo = %name, error = %e, "Unusable repository directory name, skipping");
+            return Ok(None);
+        }
+    };
+    let mut files = Vec::new();
+    for target_file in migration.target_files() {
+        find_target_files(repo_path, target_file, &mut files)?;
+    }
+    let paths = target_path_matcher(migration);
+    files.retain(|file| {
+        file.strip_prefix(repo_path)
+            .is_ok_and(|relative| paths.matches(&relative.to_string_lossy().replace('\\', "/")))
+    });
+    files.sort();
+    files.dedup();
+
+    let mut locations = Vec::new();
+    let mut diff = String::new();
+    for file in files {
+        let Some(url) = file_url(&file) else {
+            warn!(path = %file.display(), "Failed to build a file URL, skipping");
+            continue;
+        };
+        let contents = match std::fs::read(&file) {
+            Ok(contents) => contents,
+            Err(e) => {
+                warn!(path = %file.display(), error = %e, "Failed to read file, skipping");
+                continue;
+            }
+        };
+        let (replaced, outcome) =
+            replace_in_bytes(&contents, &migration.old_string, &migration.new_string)?;
+        let Some(replaced) = replaced else {
+            if let ReplaceOutcome::Skipped { reason } = outcome {
+                debug!(path = %file.display(), %reason, "File cannot be edited losslessly");
+            }
+            continue;
+        };
+
+        let relative = relative_path(repo_path, &file);
+        // Only UTF-8 files can be shown as text; others are still applied.
+        if let (Ok(old), Ok(new)) = (
+            core::str::from_utf8(&contents),
+            core::str::from_utf8(&replaced),
+        ) {
+            diff.push_str(&unified_diff(&relative, old, new).unwrap_or_default());
+        }
+        if options.apply {
+            replace_in_file(&file, &migration.old_string, &migration.new_string)?;
+        }
+        locations.push(TemplateLocation {
+            url,
+            path: relative,
+        });
+    }
+
+    let mut locations = locations.into_iter();
+    let Some(primary) = locations.next() else {
+        return Ok(None);
+    };
+    let repository = DiscoveredRepository {
+        owner: LOCAL_OWNER.to_string(),
+        name: name.clone(),
+        full_name,
+        file_path: primary.path,
+        file_url: primary.url,
+        default_branch: String::new(),
+        template_version: None,
+        additional_locations: locations.collect(),
+        snippet: None,
+        previously_notified: false,
+        previous_issue_url: None,
+        owner_is_bot: false,
+        is_mirror: false,
+        archived: false,
+        disabled: false,
+        visibility: None,
+        fork_ancestors: Vec::new(),
+        file_size: None,
+        topics: None,
+        opted_out: None,
+        repo_data: Default::default(),
+    };
+    debug!(repo = %name, files = repository.locations().len(), "Found outdated template");
+
+    summary.repositories_discovered += 1;
+    for location in repository.locations() {
+        summary.occurrences.push(Occurrence {
+            repository: name.clone(),
+            file_path: location.path,
+            file_url: location.url.into(),
+            ..Occurrence::new(migration, &repository)
+        });
+    }
+    Ok(Some(LocalPatch {
+        migration_id: migration.id.to_string(),
+        repository: name,
+        diff,
+    }))
+}
+
+/// Returns `root` if it is a repository, otherwise its subdirectories.
+fn list_repositories(root: &Path) -> Result<Vec<PathBuf>, LocalScanError> {
+    if root.join(".git").exists() {
+        return Ok(vec![root.to_path_buf()]);
+    }
+    let mut repositories: Vec<PathBuf> = read_dir(root)?
+        .filter(|path| path.is_dir() && !is_skipped(path))
+        .collect();
+    repositories.sort();
+    Ok(repositories)
+}
+
+/// Collects every file below `dir` matching `target_file`.
+///
+/// A `target_file` containing `/` must match the path relative to the
+/// repository's root; otherwise any file with that name matches.
+fn find_target_files(
+    dir: &Path,
+    target_file: &str,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), LocalScanError> {
+    if target_file.contains('/') {
+        let path = dir.join(target_file.trim_start_matches('/'));
+        if path.is_file() {
+            files.push(path);
+        }
+        return Ok(());
+    }
+    for path in read_dir(dir)? {
+        if path.is_dir() {
+            if !is_skipped(&path) && !path.is_symlink() {
+                find_target_files(&path, target_file, files)?;
+            }
+        } else if path.file_name().is_some_and(|name| name == target_file) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Lists the entries of `dir`.
+fn read_dir(dir: &Path) -> Result<impl Iterator<Item = PathBuf>, LocalScanError> {
+    let entries = std::fs::read_dir(dir).map_err(|source| LocalScanError::Io {
+        path: dir.display().to_string(),
+        source,
+    })?;
+    Ok(entries.filter_map(Result::ok).map(|entry| entry.path()))
+}
+
+/// Returns true for directories that never hold template files.
+fn is_skipped(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| SKIPPED_DIRS.iter().any(|skipped| name == *skipped))
+}
+
+/// Returns `file` relative to `root`, with `/` separators.
+fn relative_path(root: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns a `file://` URL for `path`, if it can be made absolute.
+fn file_url(path: &Path) -> Option<HtmlUrl> {
+    std::path::absolute(path)
+        .ok()
+        .and_then(|absolute| url::Url::from_file_path(absolute).ok())
+        .map(HtmlUrl::from)
+}
+
+/// Writes `patch` to `<dir>/<repository>/<migration>.patch`.
+fn write_patch(dir: &Path, patch: &LocalPatch) -> Result<(), LocalScanError> {
+    let repo_dir = dir.join(&patch.repository);
+    let path = repo_dir.join(format!("{}.patch", slugify(&patch.migration_id)));
+    let io_error = |source| LocalScanError::Io {
+        path: path.display().to_string(),
+        source,
+    };
+    std::fs::create_dir_all(&repo_dir).map_err(io_error)?;
+    std::fs::write(&path, &patch.diff).map_err(io_error)?;
+    debug!(path = %path.display(), "Wrote patch");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn migration() -> Migration {
+        Migration {
+            id: "tpl/v1-to-v2".parse().unwrap(),
+            target_file: "version.txt".to_string(),
+            old_string: "tpl:1".to_string(),
+            new_string: "tpl:2".to_string(),
+            ..Migration::default()
+        }
+    }
+
+    fn fixture() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let outdated = temp.path().join("outdated");
+        fs::create_dir_all(outdated.join("nested")).unwrap();
+        fs::write(outdated.join("version.txt"), "tpl:1\n").unwrap();
+        fs::write(outdated.join("nested/version.txt"), "tpl:1\n").unwrap();
+        let current = temp.path().join("current");
+        fs::create_dir_all(&current).unwrap();
+        fs::write(current.join("version.txt"), "tpl:2\n").unwrap();
+        temp
+    }
+
+    #[test]
+    fn reports_every_outdated_file() {
+        let temp = fixture();
+
+        let scan = scan_local(temp.path(), &[migration()], &LocalScanOptions::default()).unwrap();
+
+        assert_eq!(scan.summary.repositories_discovered, 1);
+        assert_eq!(scan.summary.occurrences.len(), 2);
+        assert_eq!(scan.summary.occurrences[0].repository, "outdated");
+        assert_eq!(scan.summary.occurrences[0].file_path, "nested/version.txt");
+        assert_eq!(scan.patches.len(), 1);
+        assert!(scan.patches[0]
+            .diff
+            .contains("+++ b/version.txt\n@@ -1 +1 @@\n-tpl:1\n+tpl:2\n"));
+        // Without `apply`, nothing on disk changes.
+        let contents = fs::read_to_string(temp.path().join("outdated/version.txt")).unwrap();
+        assert_eq!(contents, "tpl:1\n");
+    }
+
+    #[test]
+    fn applies_and_writes_patches() {
+        let temp = fixture();
+        let patches = TempDir::new().unwrap();
+        let options = LocalScanOptions {
+            apply: true,
+            patch_dir: Some(patches.path().to_path_buf()),
+        };
+
+        scan_local(temp.path(), &[migration()], &options).unwrap();
+
+        let contents = fs::read_to_string(temp.path().join("outdated/version.txt")).unwrap();
+        assert_eq!(contents, "tpl:2\n");
+        let patch = fs::read_to_string(patches.path().join("outdated/tpl-v1-to-v2.patch")).unwrap();
+        assert!(patch.contains("--- a/nested/version.txt"));
+    }
+
+    #[test]
+    fn scans_single_repository_root() {
+        let temp = fixture();
+        let repo = temp.path().join("outdated");
+        fs::create_dir(repo.join(".git")).unwrap();
+
+        let scan = scan_local(&repo, &[migration()], &LocalScanOptions::default()).unwrap();
+
+        assert_eq!(scan.summary.repositories_discovered, 1);
+    }
+}
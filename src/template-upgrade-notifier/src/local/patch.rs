@@ -0,0 +1,105 @@
+//! Unified diffs of local replacements, applicable with `git apply`.
+
+use core::fmt::Write as _;
+
+/// Lines of unchanged context around each change.
+const CONTEXT: usize = 3;
+
+/// Returns a unified diff turning `old` into `new` for the file at `path`,
+/// or `None` if they are identical.
+///
+/// All changes go into one hunk spanning the first to the last changed line,
+/// which keeps the diff simple and still applies cleanly.
+pub(super) fn unified_diff(path: &str, old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let start = prefix.saturating_sub(CONTEXT);
+    let old_end = (old_lines.len() - suffix + CONTEXT).min(old_lines.len());
+    let new_end = (new_lines.len() - suffix + CONTEXT).min(new_lines.len());
+    let old_changed_end = old_lines.len() - suffix;
+    let new_changed_end = new_lines.len() - suffix;
+
+    let mut diff = String::new();
+    // Writing into a `String` cannot fail.
+    let _ = writeln!(diff, "--- a/{path}\n+++ b/{path}");
+    let _ = writeln!(
+        diff,
+        "@@ -{} +{} @@",
+        range(start, old_end - start),
+        range(start, new_end - start)
+    );
+    push_lines(&mut diff, ' ', &old_lines[start..prefix]);
+    push_lines(&mut diff, '-', &old_lines[prefix..old_changed_end]);
+    push_lines(&mut diff, '+', &new_lines[prefix..new_changed_end]);
+    push_lines(&mut diff, ' ', &old_lines[old_changed_end..old_end]);
+    Some(diff)
+}
+
+/// Formats a hunk range; empty ranges point at the line before them.
+fn range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{start},0"),
+        1 => (start + 1).to_string(),
+        len => format!("{},{len}", start + 1),
+    }
+}
+
+/// Appends `lines` with `marker`, noting a missing final newline like git does.
+fn push_lines(diff: &mut String, marker: char, lines: &[&str]) {
+    for line in lines {
+        diff.push(marker);
+        diff.push_str(line);
+        if !line.ends_with('\n') {
+            diff.push_str("\n\\ No newline at end of file\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_changed_line_with_context() {
+        let old = "a\nb\nc\nd\nversion = 1\ne\nf\ng\nh\n";
+        let new = old.replace("version = 1", "version = 2");
+
+        let diff = unified_diff("version.txt", old, &new).unwrap();
+
+        assert_eq!(
+            diff,
+            "--- a/version.txt\n+++ b/version.txt\n@@ -2,7 +2,7 @@\n \
+             b\n c\n d\n-version = 1\n+version = 2\n e\n f\n g\n"
+        );
+    }
+
+    #[test]
+    fn notes_missing_final_newline() {
+        let diff = unified_diff("v.txt", "v1", "v2").unwrap();
+
+        assert!(diff.ends_with(
+            "@@ -1 +1 @@\n-v1\n\\ No newline at end of file\n+v2\n\\ No newline at end of file\n"
+        ));
+    }
+
+    #[test]
+    fn identical_contents_have_no_diff() {
+        assert!(unified_diff("v.txt", "same\n", "same\n").is_none());
+    }
+}
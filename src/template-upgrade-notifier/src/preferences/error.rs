@@ -0,0 +1,24 @@
+//! Consumer preference error types.
+
+use crate::discovery::DiscoveryError;
+use thiserror::Error;
+
+/// Errors that can occur while reading a `template-upgrade.yml`.
+#[derive(Debug, Error)]
+pub enum PreferencesError {
+    /// The file could not be fetched.
+    #[error("Failed to fetch preferences from '{repository}': {source}")]
+    Fetch {
+        repository: String,
+        #[source]
+        source: DiscoveryError,
+    },
+
+    /// The file is not valid YAML, or has unexpected keys.
+    #[error("Invalid preferences in '{repository}': {source}")]
+    Yaml {
+        repository: String,
+        #[source]
+        source: serde_yaml::Error,
+    },
+}
@@ -0,0 +1,183 @@
+//! Consumer preferences from `.github/template-upgrade.yml`.
+//!
+//! An organization sets defaults for all of its repositories in the file in
+//! its `.github` repository; a repository's own file overrides them key by
+//! key. Organization files are cached for the rest of the run, until
+//! [`clear_preferences_cache`] is called.
+
+mod error;
+
+pub use error::PreferencesError;
+
+use crate::discovery::fetch_file;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tracing::debug;
+
+/// Path of the preferences file, in a repository or in an organization's
+/// `.github` repository.
+pub const PREFERENCES_PATH: &str = ".github/template-upgrade.yml";
+
+/// Repository holding an organization's defaults.
+const ORG_DEFAULTS_REPOSITORY: &str = ".github";
+
+/// Organization defaults already read, keyed by lowercased owner.
+static ORG_PREFERENCES: Mutex<BTreeMap<String, RepoPreferences>> = Mutex::new(BTreeMap::new());
+
+/// What a consumer asked of upgrade notices. Unset keys fall back to the
+/// organization's defaults, then to the notifier's own behavior.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RepoPreferences {
+    /// Labels to add to our issues and PRs.
+    pub labels: Option<Vec<String>>,
+    /// Whether auto-fix PRs are welcome; `false` asks for issues only.
+    pub auto_pr: Option<bool>,
+    /// Whether to leave the repository out of every campaign.
+    pub opt_out: Option<bool>,
+}
+
+impl RepoPreferences {
+    /// Parses preferences from YAML content read from `repository`.
+    ///
+    /// An empty file sets nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PreferencesError::Yaml`] if parsing fails.
+    pub fn parse(content: &str, repository: &str) -> Result<Self, PreferencesError> {
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(content).map_err(|source| PreferencesError::Yaml {
+            repository: repository.to_string(),
+            source,
+        })
+    }
+
+    /// Returns these preferences with every key set in `overrides` replaced.
+    #[must_use]
+    pub fn merged(self, overrides: Self) -> Self {
+        Self {
+            labels: overrides.labels.or(self.labels),
+            auto_pr: overrides.auto_pr.or(self.auto_pr),
+            opt_out: overrides.opt_out.or(self.opt_out),
+        }
+    }
+
+    /// Returns the labels to add to our issues and PRs.
+    #[must_use]
+    pub fn labels(&self) -> &[String] {
+        self.labels.as_deref().unwrap_or_default()
+    }
+
+    /// Returns false if the consumer asked for issues only.
+    #[must_use]
+    pub fn wants_auto_pr(&self) -> bool {
+        self.auto_pr != Some(false)
+    }
+
+    /// Returns true if the consumer opted out of every campaign.
+    #[must_use]
+    pub fn opted_out(&self) -> bool {
+        self.opt_out == Some(true)
+    }
+}
+
+/// Forgets every organization's defaults, so the next lookup reads them again.
+pub fn clear_preferences_cache() {
+    org_preferences().clear();
+}
+
+/// Returns the preferences of `owner/name`: its organization's defaults
+/// merged with its own file. Missing files set nothing.
+///
+/// # Errors
+///
+/// Returns [`PreferencesError`] if a file cannot be fetched or parsed.
+/// Organization defaults that fail are not cached.
+pub async fn fetch_preferences(
+    octocrab: &Octocrab,
+    owner: &str,
+    name: &str,
+) -> Result<RepoPreferences, PreferencesError> {
+    let key = owner.to_lowercase();
+    let cached = org_preferences().get(&key).cloned();
+    let defaults = match cached {
+        Some(defaults) => defaults,
+        None => {
+            let defaults = read(octocrab, owner, ORG_DEFAULTS_REPOSITORY).await?;
+            org_preferences().insert(key, defaults.clone());
+            defaults
+        }
+    };
+    if name == ORG_DEFAULTS_REPOSITORY {
+        return Ok(defaults);
+    }
+    Ok(defaults.merged(read(octocrab, owner, name).await?))
+}
+
+/// Reads and parses the preferences file of `owner/name`.
+async fn read(
+    octocrab: &Octocrab,
+    owner: &str,
+    name: &str,
+) -> Result<RepoPreferences, PreferencesError> {
+    let repository = format!("{owner}/{name}");
+    let content = fetch_file(octocrab, owner, name, PREFERENCES_PATH)
+        .await
+        .map_err(|source| PreferencesError::Fetch {
+            repository: repository.clone(),
+            source,
+        })?;
+    match content {
+        Some(content) => RepoPreferences::parse(&content, &repository),
+        None => {
+            debug!(repo = %repository, "No preferences file");
+            Ok(RepoPreferences::default())
+        }
+    }
+}
+
+fn org_preferences() -> MutexGuard<'static, BTreeMap<String, RepoPreferences>> {
+    ORG_PREFERENCES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repository_overrides_org_defaults_by_key() {
+        let org = RepoPreferences::parse("labels: [dependencies]\nauto-pr: false\n", "org/.github")
+            .unwrap();
+        let repo = RepoPreferences::parse("auto-pr: true\n", "org/repo").unwrap();
+
+        let merged = org.merged(repo);
+
+        assert_eq!(merged.labels(), ["dependencies"]);
+        assert!(merged.wants_auto_pr());
+        assert!(!merged.opted_out());
+    }
+
+    #[test]
+    fn empty_file_sets_nothing() {
+        let preferences = RepoPreferences::parse("\n", "org/repo").unwrap();
+
+        assert_eq!(preferences, RepoPreferences::default());
+        assert!(preferences.wants_auto_pr());
+        assert!(preferences.labels().is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(matches!(
+            RepoPreferences::parse("auto_pr: false\n", "org/repo"),
+            Err(PreferencesError::Yaml { .. })
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+//! Project board configuration.
+
+use serde::Deserialize;
+
+/// Settings for the GitHub Projects board mirroring processed repositories,
+/// from the `[project-board]` section of `notifier.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProjectBoardConfig {
+    /// Organization owning the project.
+    pub owner: String,
+
+    /// Project number, as in `https://github.com/orgs/<owner>/projects/<number>`.
+    pub number: u64,
+
+    /// Single-select field holding each item's status (defaults to "Status").
+    #[serde(default = "default_status_field")]
+    pub status_field: String,
+
+    /// Option names of the status field for each outcome.
+    #[serde(default)]
+    pub statuses: BoardStatusNames,
+}
+
+/// Names of the status field's options, matched exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct BoardStatusNames {
+    /// Issue filed, no PR yet (defaults to "Notified").
+    pub notified: String,
+    /// Auto-fix PR open (defaults to "PR Open").
+    pub pr_open: String,
+    /// Auto-fix PR merged (defaults to "Merged").
+    pub merged: String,
+    /// Upgrade declined by the maintainers (defaults to "Declined").
+    pub declined: String,
+}
+
+impl Default for BoardStatusNames {
+    fn default() -> Self {
+        Self {
+            notified: "Notified".to_string(),
+            pr_open: "PR Open".to_string(),
+            merged: "Merged".to_string(),
+            declined: "Declined".to_string(),
+        }
+    }
+}
+
+fn default_status_field() -> String {
+    "Status".to_string()
+}
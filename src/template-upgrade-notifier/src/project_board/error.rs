@@ -0,0 +1,32 @@
+//! Project board error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while updating the project board.
+#[derive(Debug, Error)]
+pub enum ProjectBoardError {
+    /// The organization has no project with this number, or the token
+    /// cannot see it.
+    #[error("project {number} of '{owner}' not found")]
+    ProjectNotFound { owner: String, number: u64 },
+
+    /// The project has no single-select field with this name.
+    #[error("project has no single-select field named '{field}'")]
+    FieldNotFound { field: String },
+
+    /// The status field has no option with this name.
+    #[error("status field '{field}' has no option named '{option}'")]
+    OptionNotFound { field: String, option: String },
+
+    /// A ledger entry's repository is not in `owner/name` format.
+    #[error("invalid repository '{value}', expected owner/name")]
+    InvalidRepository { value: String },
+
+    /// A GraphQL query or mutation returned errors.
+    #[error("GraphQL error: {0}")]
+    GraphQl(String),
+
+    /// GitHub API error.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+}
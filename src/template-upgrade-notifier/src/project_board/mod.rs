@@ -0,0 +1,393 @@
+//! GitHub Projects board mirroring processed repositories.
+//!
+//! Template organizations that plan upgrades on a Projects (v2) board get one
+//! item per notified repository and migration: its upgrade issue, or its
+//! auto-fix PR when no issue was filed. A single-select status field tracks
+//! the outcome (Notified, PR Open, Merged or Declined). Items are added by
+//! the first live run that notifies a repository and their status refreshed
+//! by every later one.
+
+mod config;
+mod error;
+
+pub use config::{BoardStatusNames, ProjectBoardConfig};
+pub use error::ProjectBoardError;
+
+use crate::issues::split_repository;
+use crate::ledger::LedgerEntry;
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use tracing::{debug, warn};
+
+/// Outcome of a repository's upgrade, shown in the board's status field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BoardStatus {
+    /// Issue filed, no PR open or merged.
+    Notified,
+    /// Auto-fix PR open.
+    PrOpen,
+    /// Auto-fix PR merged.
+    Merged,
+    /// The maintainers declined the upgrade or closed its PR unmerged.
+    Declined,
+}
+
+impl BoardStatus {
+    /// Every status, in board order.
+    pub const ALL: [Self; 4] = [Self::Notified, Self::PrOpen, Self::Merged, Self::Declined];
+
+    /// Returns the status field option configured for this status.
+    #[must_use]
+    pub fn option_name(self, names: &BoardStatusNames) -> &str {
+        match self {
+            Self::Notified => &names.notified,
+            Self::PrOpen => &names.pr_open,
+            Self::Merged => &names.merged,
+            Self::Declined => &names.declined,
+        }
+    }
+}
+
+/// Adds every ledger entry's issue (or PR, if it has no issue) to the
+/// project board and sets its status.
+///
+/// An entry's status comes from the current state of its issue and PR plus
+/// the declines recorded by the `status` subcommand; a merged PR wins over a
+/// decline. Items already on the board are kept, and their status is only
+/// written when it changed. Entries whose issue and PR cannot be read are
+/// logged and skipped.
+///
+/// # Returns
+///
+/// The number of items added or updated.
+///
+/// # Errors
+///
+/// Returns [`ProjectBoardError`] if the project, its status field or one of
+/// the configured options cannot be found, or adding or updating an item fails.
+pub async fn sync_project_board(
+    octocrab: &Octocrab,
+    config: &ProjectBoardConfig,
+    entries: &[LedgerEntry],
+) -> Result<usize, ProjectBoardError> {
+    let board = load_board(octocrab, config).await?;
+    let mut changed = 0;
+    for entry in entries {
+        if entry.issue.is_none() && entry.pr.is_none() {
+            continue;
+        }
+        let (issue, pr) = match read_contents(octocrab, entry).await {
+            Ok(contents) => contents,
+            Err(e) => {
+                warn!(repo = %entry.repository, error = %e, "Failed to read issue and PR for project board");
+                continue;
+            }
+        };
+        let Some(content) = issue.as_ref().or(pr.as_ref()) else {
+            continue;
+        };
+        let status = classify(entry, issue.as_ref(), pr.as_ref());
+        let option = &board.options[&status];
+        let item = add_item(octocrab, &board, &content.id).await?;
+        if item.option.as_ref() == Some(option) {
+            debug!(repo = %entry.repository, ?status, "Project board item up to date");
+            continue;
+        }
+        set_status(octocrab, &board, &item.id, option).await?;
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+/// An issue or PR as read over GraphQL.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Content {
+    /// GraphQL node ID.
+    id: String,
+    /// `OPEN`, `CLOSED` or, for PRs, `MERGED`.
+    state: String,
+    /// Why an issue was closed, e.g. `NOT_PLANNED`.
+    state_reason: Option<String>,
+    /// Whether a PR was merged.
+    merged: bool,
+}
+
+/// Returns the board status of an entry whose issue and PR are in the
+/// given states.
+fn classify(entry: &LedgerEntry, issue: Option<&Content>, pr: Option<&Content>) -> BoardStatus {
+    if pr.is_some_and(|pr| pr.merged) {
+        return BoardStatus::Merged;
+    }
+    let not_planned =
+        issue.is_some_and(|issue| issue.state_reason.as_deref() == Some("NOT_PLANNED"));
+    let pr_closed = pr.is_some_and(|pr| pr.state == "CLOSED");
+    if entry.declined || entry.not_planned_version.is_some() || not_planned || pr_closed {
+        return BoardStatus::Declined;
+    }
+    if pr.is_some_and(|pr| pr.state == "OPEN") {
+        BoardStatus::PrOpen
+    } else {
+        BoardStatus::Notified
+    }
+}
+
+/// The project and its status field, with the option ID of every status.
+struct Board {
+    project_id: String,
+    field_id: String,
+    field_name: String,
+    options: BTreeMap<BoardStatus, String>,
+}
+
+/// An item on the board and its current status option.
+struct Item {
+    id: String,
+    option: Option<String>,
+}
+
+/// Looks up the project, its status field and the configured options.
+async fn load_board(
+    octocrab: &Octocrab,
+    config: &ProjectBoardConfig,
+) -> Result<Board, ProjectBoardError> {
+    let query = "query($owner: String!, $number: Int!, $field: String!) { \
+        organization(login: $owner) { projectV2(number: $number) { id \
+        field(name: $field) { ... on ProjectV2SingleSelectField { id options { id name } } } } } }";
+    let response = request(
+        octocrab,
+        query,
+        json!({ "owner": config.owner, "number": config.number, "field": config.status_field }),
+    )
+    .await?;
+    let project = &response["data"]["organization"]["projectV2"];
+    let Some(project_id) = project["id"].as_str() else {
+        return Err(ProjectBoardError::ProjectNotFound {
+            owner: config.owner.clone(),
+            number: config.number,
+        });
+    };
+    let field = &project["field"];
+    let Some(field_id) = field["id"].as_str() else {
+        return Err(ProjectBoardError::FieldNotFound {
+            field: config.status_field.clone(),
+        });
+    };
+    let available: BTreeMap<&str, &str> = field["options"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|option| Some((option["name"].as_str()?, option["id"].as_str()?)))
+        .collect();
+    let options = BoardStatus::ALL
+        .into_iter()
+        .map(|status| {
+            let name = status.option_name(&config.statuses);
+            let id = available
+                .get(name)
+                .ok_or_else(|| ProjectBoardError::OptionNotFound {
+                    field: config.status_field.clone(),
+                    option: name.to_string(),
+                })?;
+            Ok((status, (*id).to_string()))
+        })
+        .collect::<Result<_, ProjectBoardError>>()?;
+    Ok(Board {
+        project_id: project_id.to_string(),
+        field_id: field_id.to_string(),
+        field_name: config.status_field.clone(),
+        options,
+    })
+}
+
+/// Reads the current state of an entry's issue and PR.
+async fn read_contents(
+    octocrab: &Octocrab,
+    entry: &LedgerEntry,
+) -> Result<(Option<Content>, Option<Content>), ProjectBoardError> {
+    let (owner, name) = split_repository(&entry.repository).ok_or_else(|| {
+        ProjectBoardError::InvalidRepository {
+            value: entry.repository.clone(),
+        }
+    })?;
+    let fields = "... on Issue { id state stateReason } ... on PullRequest { id state merged }";
+    let mut selections = String::new();
+    for (alias, number) in [("issue", entry.issue), ("pr", entry.pr)] {
+        if let Some(number) = number {
+            selections.push_str(&format!(
+                "{alias}: issueOrPullRequest(number: {number}) {{ {fields} }} "
+            ));
+        }
+    }
+    let query = format!(
+        "query($owner: String!, $name: String!) {{ repository(owner: $owner, name: $name) {{ {selections}}} }}"
+    );
+    let response = request(octocrab, &query, json!({ "owner": owner, "name": name })).await?;
+    check_errors(&response)?;
+    let content = |alias: &str| {
+        let value = &response["data"]["repository"][alias];
+        serde_json::from_value::<Content>(value.clone())
+            .ok()
+            .filter(|content| !content.id.is_empty())
+    };
+    Ok((content("issue"), content("pr")))
+}
+
+/// Adds the issue or PR with node ID `content_id` to the board, or returns
+/// the item already there.
+async fn add_item(
+    octocrab: &Octocrab,
+    board: &Board,
+    content_id: &str,
+) -> Result<Item, ProjectBoardError> {
+    let query = "mutation($project: ID!, $content: ID!, $field: String!) { \
+        addProjectV2ItemById(input: { projectId: $project, contentId: $content }) { item { id \
+        fieldValueByName(name: $field) { ... on ProjectV2ItemFieldSingleSelectValue { optionId } } } } }";
+    let response = request(
+        octocrab,
+        query,
+        json!({ "project": board.project_id, "content": content_id, "field": board.field_name }),
+    )
+    .await?;
+    check_errors(&response)?;
+    let item = &response["data"]["addProjectV2ItemById"]["item"];
+    let id = item["id"]
+        .as_str()
+        .ok_or_else(|| ProjectBoardError::GraphQl("no item in response".to_string()))?;
+    Ok(Item {
+        id: id.to_string(),
+        option: item["fieldValueByName"]["optionId"]
+            .as_str()
+            .map(str::to_string),
+    })
+}
+
+/// Sets the status field of `item_id` to `option_id`.
+async fn set_status(
+    octocrab: &Octocrab,
+    board: &Board,
+    item_id: &str,
+    option_id: &str,
+) -> Result<(), ProjectBoardError> {
+    let query = "mutation($project: ID!, $item: ID!, $field: ID!, $option: String!) { \
+        updateProjectV2ItemFieldValue(input: { projectId: $project, itemId: $item, fieldId: $field, \
+        value: { singleSelectOptionId: $option } }) { projectV2Item { id } } }";
+    let response = request(
+        octocrab,
+        query,
+        json!({
+            "project": board.project_id,
+            "item": item_id,
+            "field": board.field_id,
+            "option": option_id,
+        }),
+    )
+    .await?;
+    check_errors(&response)
+}
+
+/// Sends a GraphQL query with `variables`, waiting for the rate limit first.
+async fn request(
+    octocrab: &Octocrab,
+    query: &str,
+    variables: Value,
+) -> Result<Value, ProjectBoardError> {
+    ensure_core_rate_limit(octocrab).await?;
+    Ok(octocrab
+        .graphql(&json!({ "query": query, "variables": variables }))
+        .await?)
+}
+
+/// Returns the response's errors, if any, as a [`ProjectBoardError::GraphQl`].
+fn check_errors(response: &Value) -> Result<(), ProjectBoardError> {
+    match response.get("errors") {
+        Some(errors) if !errors.is_null() => Err(ProjectBoardError::GraphQl(errors.to_string())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> LedgerEntry {
+        LedgerEntry {
+            migration_id: "tpl/v1-to-v2".to_string(),
+            repository: "my-org/api".to_string(),
+            issue: Some(1),
+            pr: None,
+            dispatched: false,
+            recorded_at: "2024-05-01T00:00:00Z".to_string(),
+            declined: false,
+            not_planned_version: None,
+        }
+    }
+
+    fn content(state: &str, state_reason: Option<&str>, merged: bool) -> Content {
+        Content {
+            id: "node".to_string(),
+            state: state.to_string(),
+            state_reason: state_reason.map(str::to_string),
+            merged,
+        }
+    }
+
+    #[test]
+    fn classifies_issue_and_pr_states() {
+        let open_issue = content("OPEN", None, false);
+        let status = |pr: Option<&Content>| classify(&entry(), Some(&open_issue), pr);
+
+        assert_eq!(status(None), BoardStatus::Notified);
+        assert_eq!(
+            status(Some(&content("OPEN", None, false))),
+            BoardStatus::PrOpen
+        );
+        assert_eq!(
+            status(Some(&content("MERGED", None, true))),
+            BoardStatus::Merged
+        );
+        assert_eq!(
+            status(Some(&content("CLOSED", None, false))),
+            BoardStatus::Declined
+        );
+    }
+
+    #[test]
+    fn classifies_declines() {
+        let not_planned = content("CLOSED", Some("NOT_PLANNED"), false);
+        let declined = LedgerEntry {
+            declined: true,
+            ..entry()
+        };
+
+        assert_eq!(
+            classify(&entry(), Some(&not_planned), None),
+            BoardStatus::Declined
+        );
+        assert_eq!(classify(&declined, None, None), BoardStatus::Declined);
+        assert_eq!(
+            classify(&declined, None, Some(&content("MERGED", None, true))),
+            BoardStatus::Merged
+        );
+    }
+
+    #[test]
+    fn parses_config_with_default_statuses() {
+        let config: ProjectBoardConfig =
+            toml::from_str("owner = \"my-org\"\nnumber = 7\n[statuses]\npr-open = \"In review\"\n")
+                .unwrap();
+
+        assert_eq!(config.status_field, "Status");
+        assert_eq!(
+            BoardStatus::Notified.option_name(&config.statuses),
+            "Notified"
+        );
+        assert_eq!(
+            BoardStatus::PrOpen.option_name(&config.statuses),
+            "In review"
+        );
+    }
+}
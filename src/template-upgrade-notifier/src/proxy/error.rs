@@ -0,0 +1,23 @@
+//! Proxy and TLS configuration error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while applying proxy or TLS settings.
+#[derive(Debug, Error)]
+pub enum ProxyError {
+    /// The proxy URL could not be parsed or is not an `http://` URL.
+    #[error("Invalid proxy URL '{url}': {message}")]
+    InvalidProxyUrl { url: String, message: String },
+
+    /// The CA bundle could not be read.
+    #[error("Failed to read CA bundle {path}: {source}")]
+    CaBundle {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The CA bundle holds no usable certificates.
+    #[error("CA bundle {path} contains no valid certificates")]
+    EmptyCaBundle { path: String },
+}
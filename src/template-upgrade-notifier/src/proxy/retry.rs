@@ -0,0 +1,117 @@
+//! Retrying idempotent GitHub requests with exponential backoff.
+
+use core::time::Duration;
+use futures::future::BoxFuture;
+use http::{Method, Request, Response, StatusCode};
+use octocrab::service::middleware::retry::RetryConfig;
+use tower::retry::Policy;
+use tracing::debug;
+
+/// Delay before the first retry; doubled before each further one.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest delay between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// Retries requests that failed to connect or got a 429 or 5xx response,
+/// waiting longer before each attempt.
+///
+/// Only idempotent methods are retried. A `POST` or `PATCH` may have taken
+/// effect on GitHub's side (for example, created an issue) before the
+/// connection dropped or the gateway timed out, so repeating it could act twice.
+#[derive(Clone)]
+pub(crate) struct RetryPolicy {
+    max_retries: usize,
+    attempts: u32,
+    /// Copies retried requests; octocrab's body type is not nameable outside it.
+    cloner: RetryConfig,
+}
+
+impl RetryPolicy {
+    /// Retries each idempotent request up to `max_retries` times.
+    pub(crate) fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            attempts: 0,
+            cloner: RetryConfig::Simple(max_retries),
+        }
+    }
+}
+
+impl<Body, B, E> Policy<Request<Body>, Response<B>, E> for RetryPolicy
+where
+    RetryConfig: Policy<Request<Body>, Response<B>, E>,
+{
+    type Future = BoxFuture<'static, ()>;
+
+    fn retry(
+        &mut self,
+        req: &mut Request<Body>,
+        result: &mut Result<Response<B>, E>,
+    ) -> Option<Self::Future> {
+        let status = result.as_ref().ok().map(Response::status);
+        if self.attempts as usize >= self.max_retries || !should_retry(req.method(), status) {
+            return None;
+        }
+        let delay = backoff(self.attempts);
+        self.attempts += 1;
+        debug!(method = %req.method(), uri = %req.uri(), ?status, ?delay, "Retrying request");
+        Some(Box::pin(tokio::time::sleep(delay)))
+    }
+
+    fn clone_request(&mut self, req: &Request<Body>) -> Option<Request<Body>> {
+        if self.max_retries == 0 || !req.method().is_idempotent() {
+            return None;
+        }
+        self.cloner.clone_request(req)
+    }
+}
+
+/// Returns true if a `method` request that got `status` (or no response at
+/// all) is worth sending again.
+fn should_retry(method: &Method, status: Option<StatusCode>) -> bool {
+    method.is_idempotent()
+        && status.is_none_or(|status| {
+            status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        })
+}
+
+/// Returns the delay before retry number `attempt` (counting from zero).
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_only_idempotent_methods() {
+        let unavailable = Some(StatusCode::SERVICE_UNAVAILABLE);
+
+        assert!(should_retry(&Method::GET, unavailable));
+        assert!(should_retry(&Method::PUT, None));
+        assert!(!should_retry(&Method::POST, unavailable));
+        assert!(!should_retry(&Method::PATCH, None));
+    }
+
+    #[test]
+    fn retries_only_transient_statuses() {
+        assert!(should_retry(
+            &Method::GET,
+            Some(StatusCode::TOO_MANY_REQUESTS)
+        ));
+        assert!(!should_retry(&Method::GET, Some(StatusCode::NOT_FOUND)));
+        assert!(!should_retry(&Method::GET, Some(StatusCode::OK)));
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_a_cap() {
+        assert_eq!(backoff(0), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,272 @@
+//! Cloning repositories, with policies for Git LFS and submodules.
+//!
+//! Repositories are cloned without downloading LFS objects, so LFS-tracked
+//! files are small text pointers. Letting the LLM edit such a pointer as if it
+//! were the file produces a broken commit, so LFS repositories are skipped by
+//! default, and edited pointers are always refused.
+
+use super::git::run_git_command;
+use super::PrError;
+use crate::discovery::DiscoveredRepository;
+use crate::proxy::ProxyConfig;
+use crate::redact::redact;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{debug, info};
+
+/// Default per-clone disk quota, in MiB.
+const DEFAULT_MAX_CLONE_MB: u64 = 2048;
+
+/// What to do with repositories using Git LFS or submodules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckoutPolicy {
+    /// Open no PR for the repository.
+    Skip,
+    /// Clone without LFS objects or submodule contents.
+    Without,
+    /// Download LFS objects or initialize submodules too.
+    Full,
+}
+
+/// `[checkout]` settings in `notifier.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CheckoutConfig {
+    /// Policy for repositories using Git LFS; skipped by default.
+    pub lfs: CheckoutPolicy,
+    /// Policy for repositories with submodules; cloned without them by default.
+    pub submodules: CheckoutPolicy,
+    /// Largest clone, in MiB, handed to the agent; larger ones are skipped.
+    /// `0` disables the limit.
+    pub max_clone_mb: u64,
+}
+
+impl Default for CheckoutConfig {
+    fn default() -> Self {
+        Self {
+            lfs: CheckoutPolicy::Skip,
+            submodules: CheckoutPolicy::Without,
+            max_clone_mb: DEFAULT_MAX_CLONE_MB,
+        }
+    }
+}
+
+/// What a fresh clone turned out to use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct CloneFeatures {
+    /// A `.gitattributes` file, at the root or in any directory, routes files
+    /// through the LFS filter.
+    pub(super) lfs: bool,
+    /// The repository declares submodules in `.gitmodules`.
+    pub(super) submodules: bool,
+}
+
+impl CheckoutConfig {
+    /// Returns why no PR should be opened for a clone with `features`, if any.
+    pub(super) fn skip_reason(&self, features: CloneFeatures) -> Option<&'static str> {
+        if features.lfs && self.lfs == CheckoutPolicy::Skip {
+            Some("repository uses Git LFS")
+        } else if features.submodules && self.submodules == CheckoutPolicy::Skip {
+            Some("repository uses submodules")
+        } else {
+            None
+        }
+    }
+}
+
+/// Shallow-clones a repository to `path`, without downloading LFS objects,
+/// and reports whether it uses LFS or submodules.
+///
+/// Proxy and CA settings are stored in the clone, so the later push uses them.
+pub(super) async fn clone_repository(
+    repository: &DiscoveredRepository,
+    path: &Path,
+    token: &str,
+    proxy: &ProxyConfig,
+) -> Result<CloneFeatures, PrError> {
+    debug!(repo = %repository.full_name, "Cloning repository");
+
+    let clone_url = format!(
+        "https://x-access-token:{}@github.com/{}.git",
+        token, repository.full_name
+    );
+
+    let output = Command::new("git")
+        .kill_on_drop(true)
+        .arg("clone")
+        .args(proxy.git_clone_args("github.com"))
+        .args(["--depth", "1", &clone_url, "."])
+        .env("GIT_LFS_SKIP_SMUDGE", "1")
+        .current_dir(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| PrError::CloneFailed {
+            message: format!("Failed to execute git clone: {e}"),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PrError::CloneFailed {
+            message: format!("git clone failed: {}", redact(&stderr)),
+        });
+    }
+
+    let attribute_files = git_output(path, &["ls-files", "--", ":(glob)**/.gitattributes"]).await?;
+    Ok(CloneFeatures {
+        lfs: routes_to_lfs(path, &attribute_files),
+        submodules: path.join(".gitmodules").is_file(),
+    })
+}
+
+/// Returns true if any of the `.gitattributes` files listed one per line in
+/// `attribute_files` (relative to the clone at `path`) uses the LFS filter.
+fn routes_to_lfs(path: &Path, attribute_files: &str) -> bool {
+    attribute_files
+        .lines()
+        .filter(|file| !file.is_empty())
+        .any(|file| {
+            std::fs::read_to_string(path.join(file))
+                .is_ok_and(|attributes| attributes.contains("filter=lfs"))
+        })
+}
+
+/// Downloads LFS objects and initializes submodules where the policy asks
+/// for a full checkout.
+pub(super) async fn complete_checkout(
+    path: &Path,
+    config: &CheckoutConfig,
+    features: CloneFeatures,
+) -> Result<(), PrError> {
+    if features.lfs && config.lfs == CheckoutPolicy::Full {
+        info!("Downloading Git LFS objects");
+        run_git_command(path, &["lfs", "pull"]).await?;
+    }
+    if features.submodules && config.submodules == CheckoutPolicy::Full {
+        info!("Initializing submodules");
+        run_git_command(
+            path,
+            &[
+                "submodule",
+                "update",
+                "--init",
+                "--recursive",
+                "--depth",
+                "1",
+            ],
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Returns the modified files that are LFS pointers rather than contents.
+///
+/// Only meaningful for clones made without LFS objects.
+pub(super) async fn edited_lfs_pointers(path: &Path) -> Result<Vec<String>, PrError> {
+    let changed = git_output(path, &["diff", "--name-only"]).await?;
+    let files: Vec<&str> = changed.lines().filter(|line| !line.is_empty()).collect();
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut args = vec!["check-attr", "filter", "--"];
+    args.extend(&files);
+    Ok(lfs_files(&git_output(path, &args).await?))
+}
+
+/// Parses `git check-attr filter` output into the files using the LFS filter.
+fn lfs_files(check_attr: &str) -> Vec<String> {
+    check_attr
+        .lines()
+        .filter_map(|line| line.strip_suffix(": filter: lfs"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Runs a git command and returns its standard output.
+pub(super) async fn git_output(path: &Path, args: &[&str]) -> Result<String, PrError> {
+    let output = Command::new("git")
+        .kill_on_drop(true)
+        .args(args)
+        .current_dir(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| PrError::CloneFailed {
+            message: format!("Failed to execute git {}: {e}", args.join(" ")),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PrError::CloneFailed {
+            message: redact(&format!("git {} failed: {stderr}", args.join(" "))),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn skips_lfs_and_keeps_submodules_by_default() {
+        let config = CheckoutConfig::default();
+        let lfs = CloneFeatures {
+            lfs: true,
+            submodules: false,
+        };
+        let submodules = CloneFeatures {
+            lfs: false,
+            submodules: true,
+        };
+
+        assert_eq!(config.skip_reason(lfs), Some("repository uses Git LFS"));
+        assert_eq!(config.skip_reason(submodules), None);
+        assert_eq!(config.skip_reason(CloneFeatures::default()), None);
+    }
+
+    #[test]
+    fn parses_policies() {
+        let config: CheckoutConfig = toml::from_str(
+            r#"
+lfs = "full"
+submodules = "skip"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.lfs, CheckoutPolicy::Full);
+        assert_eq!(config.submodules, CheckoutPolicy::Skip);
+        assert_eq!(config.max_clone_mb, DEFAULT_MAX_CLONE_MB);
+    }
+
+    #[test]
+    fn detects_lfs_in_nested_gitattributes() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join(".gitattributes"), "* text=auto\n").unwrap();
+        std::fs::create_dir(temp.path().join("assets")).unwrap();
+        std::fs::write(
+            temp.path().join("assets/.gitattributes"),
+            "*.png filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+
+        assert!(!routes_to_lfs(temp.path(), ".gitattributes\n"));
+        assert!(routes_to_lfs(
+            temp.path(),
+            ".gitattributes\nassets/.gitattributes\n"
+        ));
+    }
+
+    #[test]
+    fn finds_lfs_files_in_check_attr_output() {
+        let output = "assets/logo.png: filter: lfs\nREADME.md: filter: unspecified\n";
+        assert_eq!(lfs_files(output), ["assets/logo.png"]);
+    }
+}
@@ -1,5 +1,6 @@
 //! Pull request error types.
 
+use crate::error_kind::ErrorKind;
 use thiserror::Error;
 
 /// Errors that can occur during PR operations.
@@ -21,6 +22,14 @@ pub enum PrError {
     #[error("LLM timed out after {timeout_secs} seconds")]
     Timeout { timeout_secs: u64 },
 
+    /// A script or `validate-command` ran past its timeout and was killed.
+    #[error("{command} timed out after {timeout_secs} seconds")]
+    ScriptTimeout { command: String, timeout_secs: u64 },
+
+    /// A replacement, patch or script strategy failed.
+    #[error("Migration strategy failed: {message}")]
+    StrategyFailed { message: String },
+
     /// Push failed.
     #[error("Failed to push changes: {message}")]
     PushFailed { message: String },
@@ -28,4 +37,59 @@ pub enum PrError {
     /// No changes were made.
     #[error("No changes were made")]
     NoChanges,
+
+    /// The run was cancelled while the PR was being prepared.
+    #[error("PR creation cancelled")]
+    Cancelled,
+}
+
+impl PrError {
+    /// Returns what kind of failure this is.
+    ///
+    /// Clone failures and LLM or script timeouts count as transient; a
+    /// strategy that fails, or makes no changes, will do the same on the next
+    /// attempt.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::GitHubError(e) => ErrorKind::of_github(e),
+            Self::CloneFailed { .. } | Self::Timeout { .. } | Self::ScriptTimeout { .. } => {
+                ErrorKind::Transient
+            }
+            Self::StrategyFailed { .. } | Self::NoChanges => ErrorKind::Validation,
+            Self::LlmFailed { .. } | Self::PushFailed { .. } => ErrorKind::Other,
+            Self::Cancelled => ErrorKind::Cancelled,
+        }
+    }
+
+    /// Returns true if retrying the operation later may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_pr_errors() {
+        assert!(PrError::Timeout { timeout_secs: 600 }.is_retryable());
+        let script = PrError::ScriptTimeout {
+            command: "./upgrade.sh".to_string(),
+            timeout_secs: 60,
+        };
+        assert!(script.is_retryable());
+        assert_eq!(
+            script.to_string(),
+            "./upgrade.sh timed out after 60 seconds"
+        );
+        assert_eq!(PrError::NoChanges.kind(), ErrorKind::Validation);
+        assert_eq!(PrError::Cancelled.kind(), ErrorKind::Cancelled);
+        assert!(!PrError::PushFailed {
+            message: "rejected".to_string()
+        }
+        .is_retryable());
+    }
 }
@@ -0,0 +1,109 @@
+//! Local git operations in a PR clone.
+
+use super::{CommitIdentity, PrError};
+use crate::config::Migration;
+use crate::redact::redact;
+use crate::templates::generate_commit_title;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Creates and checks out a new branch.
+pub(super) async fn create_branch(path: &Path, branch_name: &str) -> Result<(), PrError> {
+    debug!(branch = %branch_name, "Creating branch");
+
+    let output = Command::new("git")
+        .kill_on_drop(true)
+        .args(["checkout", "-b", branch_name])
+        .current_dir(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| PrError::CloneFailed {
+            message: format!("Failed to create branch: {e}"),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PrError::CloneFailed {
+            message: format!("git checkout failed: {}", redact(&stderr)),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks if there are uncommitted changes.
+pub(super) async fn has_changes(path: &Path) -> Result<bool, PrError> {
+    let output = Command::new("git")
+        .kill_on_drop(true)
+        .args(["status", "--porcelain"])
+        .current_dir(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| PrError::CloneFailed {
+            message: format!("Failed to check git status: {e}"),
+        })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(!stdout.trim().is_empty())
+}
+
+/// Commits all changes.
+pub(super) async fn commit_changes(
+    path: &Path,
+    migration: &Migration,
+    identity: &CommitIdentity,
+) -> Result<(), PrError> {
+    debug!("Committing changes");
+
+    // Configure git user
+    let (name, email) = identity.author();
+    run_git_command(path, &["config", "user.email", email]).await?;
+    run_git_command(path, &["config", "user.name", name]).await?;
+
+    // Add all changes
+    run_git_command(path, &["add", "-A"]).await?;
+
+    // Commit
+    let commit_title = generate_commit_title(migration).map_err(|e| PrError::LlmFailed {
+        message: format!("Failed to generate commit title: {e}"),
+    })?;
+    let guide_line = migration
+        .migration_guide_link
+        .as_ref()
+        .map(|g| format!("\n\nMigration guide: {g}"))
+        .unwrap_or_default();
+    let commit_msg = format!("{commit_title}{guide_line}{}", identity.trailers());
+    run_git_command(path, &["commit", "-m", &commit_msg]).await?;
+
+    Ok(())
+}
+
+/// Runs a git command.
+pub(super) async fn run_git_command(path: &Path, args: &[&str]) -> Result<(), PrError> {
+    let output = Command::new("git")
+        .kill_on_drop(true)
+        .args(args)
+        .current_dir(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| PrError::CloneFailed {
+            message: redact(&format!("Failed to execute git {}: {e}", args.join(" "))),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PrError::CloneFailed {
+            message: redact(&format!("git {} failed: {stderr}", args.join(" "))),
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! GitHub API calls opening the upgrade PR and requesting its reviews and labels.
+
+use super::PrError;
+use crate::discovery::DiscoveredRepository;
+use crate::ids::HtmlUrl;
+use crate::rate_limit::ensure_core_rate_limit;
+use crate::teams::existing_teams;
+use octocrab::Octocrab;
+use tracing::warn;
+
+/// Requests reviews from the migration's teams that exist in the repository's organization.
+///
+/// Failures are logged; the PR is already open and stays reported as created.
+pub(super) async fn request_team_reviews(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    number: u64,
+    slugs: &[String],
+) {
+    if slugs.is_empty() {
+        return;
+    }
+    let teams = existing_teams(octocrab, &repository.owner, slugs).await;
+    if teams.is_empty() {
+        return;
+    }
+    if let Err(e) = octocrab
+        .pulls(&repository.owner, &repository.name)
+        .request_reviews(number, Vec::<String>::new(), teams)
+        .await
+    {
+        warn!(pr_number = number, error = %e, "Failed to request team reviews");
+    }
+}
+
+/// Adds `labels` to the PR, creating missing labels as GitHub does.
+///
+/// Failures are logged; the PR is already open and stays reported as created.
+pub(super) async fn add_labels(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    number: u64,
+    labels: &[String],
+) {
+    if labels.is_empty() {
+        return;
+    }
+    if let Err(e) = octocrab
+        .issues(&repository.owner, &repository.name)
+        .add_labels(number, labels)
+        .await
+    {
+        warn!(pr_number = number, error = %e, "Failed to add PR labels");
+    }
+}
+
+/// Creates a PR via GitHub API.
+///
+/// `head` is the branch name, or `owner:branch` for a branch in a fork.
+pub(crate) async fn create_github_pr(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    head: &str,
+    title: &str,
+    body: &str,
+) -> Result<(u64, HtmlUrl), PrError> {
+    ensure_core_rate_limit(octocrab).await?;
+    let pr = octocrab
+        .pulls(&repository.owner, &repository.name)
+        .create(title, head, &repository.default_branch)
+        .body(body)
+        .send()
+        .await?;
+
+    let url = pr.html_url.clone().map_or_else(
+        || repository.full_name.pull_request_url(pr.number),
+        HtmlUrl::from,
+    );
+
+    Ok((pr.number, url))
+}
@@ -0,0 +1,109 @@
+//! Author and committer identity of auto-fix commits.
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the GitHub Actions bot account.
+const GITHUB_ACTIONS_NAME: &str = "github-actions[bot]";
+
+/// No-reply email of the GitHub Actions bot; GitHub links it to the bot's
+/// account, so its avatar renders on the commit.
+const GITHUB_ACTIONS_EMAIL: &str = "41898282+github-actions[bot]@users.noreply.github.com";
+
+/// `[commit-identity]` settings in `notifier.toml` or `metadata.toml`.
+///
+/// Used as both author and committer of auto-fix commits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CommitIdentity {
+    /// Commit author name.
+    pub name: String,
+
+    /// Commit author email.
+    pub email: String,
+
+    /// Commit as the GitHub Actions bot instead of `name` and `email`.
+    pub github_actions_bot: bool,
+
+    /// `Co-authored-by` trailers, each as `"Name <email>"`.
+    pub co_authors: Vec<String>,
+}
+
+impl Default for CommitIdentity {
+    fn default() -> Self {
+        Self {
+            name: "Template Upgrade Bot".to_string(),
+            email: "bot@template-upgrade-notifier".to_string(),
+            github_actions_bot: false,
+            co_authors: Vec::new(),
+        }
+    }
+}
+
+impl CommitIdentity {
+    /// Returns the name and email commits are made with.
+    #[must_use]
+    pub fn author(&self) -> (&str, &str) {
+        if self.github_actions_bot {
+            (GITHUB_ACTIONS_NAME, GITHUB_ACTIONS_EMAIL)
+        } else {
+            (&self.name, &self.email)
+        }
+    }
+
+    /// Returns the `Co-authored-by` trailers to append to the commit message,
+    /// including the separating blank line, or an empty string.
+    #[must_use]
+    pub fn trailers(&self) -> String {
+        if self.co_authors.is_empty() {
+            return String::new();
+        }
+        let lines: Vec<String> = self
+            .co_authors
+            .iter()
+            .map(|co_author| format!("Co-authored-by: {co_author}"))
+            .collect();
+        format!("\n\n{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_notifier_bot() {
+        let identity = CommitIdentity::default();
+        assert_eq!(
+            identity.author(),
+            ("Template Upgrade Bot", "bot@template-upgrade-notifier")
+        );
+        assert_eq!(identity.trailers(), "");
+    }
+
+    #[test]
+    fn uses_github_actions_bot() {
+        let identity: CommitIdentity = toml::from_str("github-actions-bot = true").unwrap();
+        assert_eq!(
+            identity.author(),
+            (GITHUB_ACTIONS_NAME, GITHUB_ACTIONS_EMAIL)
+        );
+    }
+
+    #[test]
+    fn renders_co_author_trailers() {
+        let identity: CommitIdentity = toml::from_str(
+            r#"
+name = "Platform Team"
+email = "platform@example.com"
+co-authors = ["Jane Doe <jane@example.com>", "Bot <bot@example.com>"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(identity.author(), ("Platform Team", "platform@example.com"));
+        assert_eq!(
+            identity.trailers(),
+            "\n\nCo-authored-by: Jane Doe <jane@example.com>\nCo-authored-by: Bot <bot@example.com>"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Dependency-bot style metadata on auto-fix PRs.
+//!
+//! Organizations that aggregate dependency PRs (Renovate dashboards,
+//! Dependabot-style tooling) recognise them by branch prefix, labels and a
+//! package/version table in the body. Migrations with `[pr-metadata]` emit the
+//! same, so template upgrades show up alongside other dependency updates.
+
+use crate::config::{parse_semver, Migration};
+use serde::{Deserialize, Serialize};
+
+/// `[pr-metadata]` settings in `metadata.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PrMetadata {
+    /// Dependency name shown for the template; defaults to the first segment
+    /// of the migration id (`my-template` for `my-template/v1-to-v2`).
+    #[serde(default)]
+    pub package: Option<String>,
+
+    /// Prefix prepended to the rendered branch name, e.g. `"renovate/"`.
+    #[serde(default)]
+    pub branch_prefix: String,
+
+    /// Labels added to the PR, e.g. `["dependencies"]`.
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl PrMetadata {
+    /// Returns the dependency name for `migration`.
+    #[must_use]
+    pub fn package_name<'a>(&'a self, migration: &'a Migration) -> &'a str {
+        self.package
+            .as_deref()
+            .unwrap_or_else(|| migration.template())
+    }
+
+    /// Renders the Renovate-style update table and a machine-readable
+    /// comment block for the PR body.
+    ///
+    /// `from` is the version the repository is on, normally the migration's
+    /// `old_string` or the detected template version.
+    #[must_use]
+    pub fn render_block(&self, migration: &Migration, from: &str) -> String {
+        let package = self.package_name(migration);
+        let to = &migration.new_string;
+        let update = update_type(from, to);
+        let update_line = update
+            .map(|update| format!("  update-type: version-update:semver-{update}\n"))
+            .unwrap_or_default();
+        format!(
+            "| Package | Type | Update | Change |\n\
+             |---|---|---|---|\n\
+             | {package} | template | {update} | `{from}` -> `{to}` |\n\n\
+             <!-- template-upgrade-metadata\n\
+             updated-dependencies:\n\
+             - dependency-name: {package}\n\
+             \x20 dependency-type: template\n\
+             \x20 from-version: {from}\n\
+             \x20 to-version: {to}\n\
+             {update_line}\
+             -->\n",
+            update = update.unwrap_or("replacement"),
+        )
+    }
+}
+
+/// Classifies the update as `major`, `minor` or `patch` when both versions
+/// are semver.
+fn update_type(from: &str, to: &str) -> Option<&'static str> {
+    let (from, to) = (parse_semver(from)?, parse_semver(to)?);
+    Some(if from.major != to.major {
+        "major"
+    } else if from.minor != to.minor {
+        "minor"
+    } else {
+        "patch"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(id: &str, new_string: &str) -> Migration {
+        Migration {
+            id: id.parse().unwrap(),
+            new_string: new_string.to_string(),
+            ..Migration::default()
+        }
+    }
+
+    #[test]
+    fn package_defaults_to_template_name() {
+        let metadata = PrMetadata::default();
+        assert_eq!(
+            metadata.package_name(&migration("my-template/v1-to-v2", "v2")),
+            "my-template"
+        );
+
+        let metadata = PrMetadata {
+            package: Some("org/template".to_string()),
+            ..PrMetadata::default()
+        };
+        assert_eq!(
+            metadata.package_name(&migration("my-template/v1-to-v2", "v2")),
+            "org/template"
+        );
+    }
+
+    #[test]
+    fn renders_semver_update() {
+        let block = PrMetadata::default().render_block(&migration("tpl/x", "v1.3.0"), "v1.2.4");
+
+        assert!(block.contains("| tpl | template | minor | `v1.2.4` -> `v1.3.0` |"));
+        assert!(block.contains("- dependency-name: tpl\n"));
+        assert!(block.contains("  update-type: version-update:semver-minor\n"));
+        assert!(block.ends_with("-->\n"));
+    }
+
+    #[test]
+    fn renders_non_semver_update() {
+        let block = PrMetadata::default().render_block(&migration("tpl/x", "tpl:new"), "tpl:old");
+
+        assert!(block.contains("| replacement |"));
+        assert!(!block.contains("update-type"));
+    }
+}
@@ -0,0 +1,23 @@
+//! Run-wide settings for creating auto-fix PRs.
+
+use super::{CheckoutConfig, CommitIdentity};
+use crate::proxy::ProxyConfig;
+use std::path::Path;
+use tokio_util::sync::CancellationToken;
+
+/// How auto-fix PRs are created, shared by every repository of a migration.
+#[derive(Debug, Clone, Copy)]
+pub struct PrOptions<'a> {
+    /// GitHub token used to clone and push.
+    pub token: &'a str,
+    /// Path to the LLM `config.toml`.
+    pub llm_config_path: &'a Path,
+    /// Author of the auto-fix commit.
+    pub identity: &'a CommitIdentity,
+    /// Policies for repositories using Git LFS or submodules.
+    pub checkout: &'a CheckoutConfig,
+    /// Proxy and CA settings for cloning and pushing.
+    pub proxy: &'a ProxyConfig,
+    /// Token that abandons the clone, agent run or push in progress.
+    pub cancellation: &'a CancellationToken,
+}
@@ -0,0 +1,102 @@
+//! Running a migration's strategy locally to preview an auto-fix PR's diff.
+
+use super::checkout::{clone_repository, complete_checkout, git_output};
+use super::git::{create_branch, has_changes};
+use super::strategy::StrategyContext;
+use super::workspace::Workspace;
+use super::{PrError, PrOptions};
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::redact::register_secret;
+use crate::templates::generate_branch_name;
+use octocrab::Octocrab;
+use tracing::{info, info_span, warn, Instrument};
+
+/// What an auto-fix PR would change in a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrDiff {
+    /// The strategy changed files; the staged diff against the default branch.
+    Changes(String),
+    /// No PR would be opened, for the given reason.
+    Skipped {
+        /// Why no PR would be opened.
+        reason: String,
+    },
+}
+
+/// Clones the repository and applies the migration with its strategy, as
+/// [`create_pr`](super::create_pr) does, then returns the diff instead of
+/// committing and pushing it.
+///
+/// Nothing is written to the repository; the clone is removed afterwards.
+/// Only `options.token`, `options.llm_config_path`, `options.checkout`,
+/// `options.proxy` and `options.cancellation` are used.
+///
+/// # Errors
+///
+/// Returns [`PrError`] if the clone, the strategy or `git diff` fails, or
+/// the work is cancelled.
+pub async fn preview_pr(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+    options: &PrOptions<'_>,
+) -> Result<PrDiff, PrError> {
+    let span = info_span!(
+        "preview_pr",
+        repo = %repository.full_name,
+        migration_id = %migration.id
+    );
+    register_secret(options.token);
+
+    let work = async {
+        info!("Previewing upgrade PR");
+        let branch_name = generate_branch_name(migration).map_err(|e| PrError::LlmFailed {
+            message: format!("Failed to generate branch name: {e}"),
+        })?;
+
+        let workspace = Workspace::create()?;
+        let features =
+            clone_repository(repository, workspace.path(), options.token, options.proxy).await?;
+        if let Some(reason) = options.checkout.skip_reason(features) {
+            return Ok(PrDiff::Skipped {
+                reason: reason.to_string(),
+            });
+        }
+        complete_checkout(workspace.path(), options.checkout, features).await?;
+        create_branch(workspace.path(), &branch_name).await?;
+        if let Some(reason) = workspace
+            .quota_exceeded(options.checkout.max_clone_mb)
+            .await
+        {
+            return Ok(PrDiff::Skipped { reason });
+        }
+
+        let context = StrategyContext {
+            octocrab,
+            path: workspace.path(),
+            llm_config_path: options.llm_config_path,
+            migration,
+            repository,
+        };
+        migration.strategy.apply(&context).await?;
+
+        if !has_changes(workspace.path()).await? {
+            return Ok(PrDiff::Skipped {
+                reason: "no changes made".to_string(),
+            });
+        }
+        git_output(workspace.path(), &["add", "-A"]).await?;
+        let diff = git_output(workspace.path(), &["diff", "--cached", "--no-color"]).await?;
+        Ok(PrDiff::Changes(diff))
+    }
+    .instrument(span);
+
+    tokio::select! {
+        result = work => result,
+        () = options.cancellation.cancelled() => {
+            warn!(repo = %repository.full_name, "PR preview cancelled");
+            Err(PrError::Cancelled)
+        }
+    }
+}
@@ -1,9 +1,10 @@
 //! Pull request status types.
 
-use serde::Serialize;
+use crate::ids::HtmlUrl;
+use serde::{Deserialize, Serialize};
 
 /// Status of a PR creation operation.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum PrStatus {
     /// PR not yet created.
@@ -14,7 +15,15 @@ pub enum PrStatus {
         /// GitHub PR number.
         number: u64,
         /// GitHub PR URL.
-        url: String,
+        url: HtmlUrl,
+    },
+
+    /// An auto-fix PR left open by an earlier run was updated instead.
+    Updated {
+        /// GitHub PR number.
+        number: u64,
+        /// GitHub PR URL.
+        url: HtmlUrl,
     },
 
     /// PR creation skipped.
@@ -27,6 +36,9 @@ pub enum PrStatus {
     Failed {
         /// Error message.
         error: String,
+        /// Whether the failure was transient and may succeed on retry.
+        #[serde(default)]
+        retryable: bool,
     },
 
     /// Timed out during PR generation.
@@ -40,20 +52,33 @@ impl PrStatus {
         match self {
             Self::Pending => "pending",
             Self::Created { .. } => "created",
+            Self::Updated { .. } => "updated",
             Self::Skipped { .. } => "skipped",
             Self::Failed { .. } => "failed",
             Self::TimedOut => "failed",
         }
     }
 
-    /// Returns the PR URL if created.
+    /// Returns the PR URL if created or updated.
     #[must_use]
     pub fn url(&self) -> Option<&str> {
         match self {
-            Self::Created { url, .. } => Some(url),
+            Self::Created { url, .. } | Self::Updated { url, .. } => Some(url.as_str()),
             _ => None,
         }
     }
+
+    /// Returns true if PR creation failed with a transient error.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Failed {
+                retryable: true,
+                ..
+            }
+        )
+    }
 }
 
 #[cfg(test)]
@@ -66,11 +91,19 @@ mod tests {
         assert_eq!(
             PrStatus::Created {
                 number: 1,
-                url: "https://example.com".to_string()
+                url: "https://example.com".parse().unwrap()
             }
             .as_str(),
             "created"
         );
+        assert_eq!(
+            PrStatus::Updated {
+                number: 1,
+                url: "https://example.com".parse().unwrap()
+            }
+            .as_str(),
+            "updated"
+        );
         assert_eq!(
             PrStatus::Skipped {
                 reason: "test".to_string()
@@ -80,7 +113,8 @@ mod tests {
         );
         assert_eq!(
             PrStatus::Failed {
-                error: "test".to_string()
+                error: "test".to_string(),
+                retryable: false,
             }
             .as_str(),
             "failed"
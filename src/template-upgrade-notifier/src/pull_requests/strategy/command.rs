@@ -0,0 +1,53 @@
+//! Running external commands in a clone, for scripts and validation.
+
+use super::StrategyContext;
+use crate::pull_requests::PrError;
+use core::time::Duration;
+use std::process::{Output, Stdio};
+use tokio::process::Command;
+
+/// Runs `command` in the clone, killing it after `timeout_secs`.
+///
+/// The migration is passed in `TEMPLATE_UPGRADE_*` environment variables;
+/// matched files are listed one per line in `TEMPLATE_UPGRADE_FILES`. The
+/// exit status is left to the caller.
+pub(super) async fn run_command(
+    context: &StrategyContext<'_>,
+    command: &str,
+    args: &[String],
+    timeout_secs: u64,
+) -> Result<Output, PrError> {
+    let migration = context.migration;
+    let files: Vec<String> = context
+        .repository
+        .locations()
+        .into_iter()
+        .map(|location| location.path)
+        .collect();
+
+    let run = Command::new(command)
+        .args(args)
+        .current_dir(context.path)
+        .env("TEMPLATE_UPGRADE_MIGRATION_ID", migration.id.as_str())
+        .env("TEMPLATE_UPGRADE_OLD_STRING", &migration.old_string)
+        .env("TEMPLATE_UPGRADE_NEW_STRING", &migration.new_string)
+        .env(
+            "TEMPLATE_UPGRADE_REPOSITORY",
+            context.repository.full_name.as_str(),
+        )
+        .env("TEMPLATE_UPGRADE_FILES", files.join("\n"))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+    tokio::time::timeout(Duration::from_secs(timeout_secs), run)
+        .await
+        .map_err(|_| PrError::ScriptTimeout {
+            command: command.to_string(),
+            timeout_secs,
+        })?
+        .map_err(|e| PrError::StrategyFailed {
+            message: format!("Failed to execute {command}: {e}"),
+        })
+}
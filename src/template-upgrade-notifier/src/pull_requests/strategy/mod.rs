@@ -0,0 +1,403 @@
+//! How an auto-fix PR edits the clone, chosen per migration.
+//!
+//! Every strategy works on a fresh clone and leaves its edits uncommitted;
+//! [`create_pr`](super::create_pr) then checks for changes, commits and pushes
+//! the same way whichever strategy ran.
+
+mod command;
+mod validate;
+
+use super::PrError;
+use crate::config::{ConfigError, Migration};
+use crate::discovery::DiscoveredRepository;
+use crate::llm::{apply_migration, fetch_readme_excerpt, LlmError};
+use crate::redact::redact;
+use crate::replace::{replace_in_file, ReplaceOutcome};
+use command::run_command;
+use futures::future::BoxFuture;
+use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+use validate::follow_up_reason;
+
+/// Default time a [`MigrationStrategy::Script`] may run.
+const DEFAULT_SCRIPT_TIMEOUT_SECS: u64 = 600;
+
+/// How the migration is applied to a clone (`strategy` in `metadata.toml`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MigrationStrategy {
+    /// Let the LLM agent apply the migration.
+    #[default]
+    Llm,
+    /// Replace `old_string` with `new_string` in every matched file, without
+    /// a model.
+    Replace,
+    /// Apply a patch file with `git apply`.
+    Patch {
+        /// Patch file, relative to the migration folder.
+        file: PathBuf,
+    },
+    /// Run a command in the clone, with the migration in its environment.
+    #[serde(rename_all = "kebab-case")]
+    Script {
+        /// Program to run; `./` and `../` paths are relative to the
+        /// migration folder.
+        command: String,
+        /// Arguments passed to the program.
+        #[serde(default)]
+        args: Vec<String>,
+        /// Time the command may run before it is killed.
+        #[serde(default = "default_script_timeout_secs")]
+        timeout_secs: u64,
+    },
+    /// Run several strategies in order, stopping at the first failure.
+    Composite(Vec<MigrationStrategy>),
+    /// Run strategies in order only while follow-up work is needed: a
+    /// matched file still contains `old_string`, or `validate-command` fails.
+    ///
+    /// `["replace", "llm"]` gets the cheap, reliable replacement done without
+    /// a model and reserves the LLM for repositories it doesn't finish.
+    Escalate(Vec<MigrationStrategy>),
+}
+
+fn default_script_timeout_secs() -> u64 {
+    DEFAULT_SCRIPT_TIMEOUT_SECS
+}
+
+/// Everything a strategy needs to edit one clone.
+pub(super) struct StrategyContext<'a> {
+    pub(super) octocrab: &'a Octocrab,
+    pub(super) path: &'a Path,
+    pub(super) llm_config_path: &'a Path,
+    pub(super) migration: &'a Migration,
+    pub(super) repository: &'a DiscoveredRepository,
+}
+
+impl MigrationStrategy {
+    /// Resolves paths relative to the migration folder `dir` and checks that
+    /// referenced files exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::ValidationError`] if a patch file is missing or
+    /// a composite or escalating strategy has no steps.
+    pub(crate) fn resolve(self, dir: &Path) -> Result<Self, ConfigError> {
+        match self {
+            Self::Patch { file } => {
+                let file = dir.join(file);
+                if !file.is_file() {
+                    return Err(ConfigError::ValidationError {
+                        path: dir.display().to_string(),
+                        message: format!("patch file not found: {}", file.display()),
+                    });
+                }
+                Ok(Self::Patch { file })
+            }
+            Self::Script {
+                command,
+                args,
+                timeout_secs,
+            } => {
+                let command = if command.starts_with("./") || command.starts_with("../") {
+                    dir.join(&command).display().to_string()
+                } else {
+                    command
+                };
+                Ok(Self::Script {
+                    command,
+                    args,
+                    timeout_secs,
+                })
+            }
+            Self::Composite(steps) => Ok(Self::Composite(resolve_steps(steps, dir)?)),
+            Self::Escalate(steps) => Ok(Self::Escalate(resolve_steps(steps, dir)?)),
+            strategy => Ok(strategy),
+        }
+    }
+
+    /// Returns the strategy's name as written in `metadata.toml`.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Llm => "llm",
+            Self::Replace => "replace",
+            Self::Patch { .. } => "patch",
+            Self::Script { .. } => "script",
+            Self::Composite(_) => "composite",
+            Self::Escalate(_) => "escalate",
+        }
+    }
+
+    /// Returns true if the strategy may start an LLM agent run.
+    ///
+    /// An escalating strategy counts if any of its steps does, although
+    /// earlier steps may finish the migration first.
+    #[must_use]
+    pub fn uses_llm(&self) -> bool {
+        match self {
+            Self::Llm => true,
+            Self::Replace | Self::Patch { .. } | Self::Script { .. } => false,
+            Self::Composite(steps) | Self::Escalate(steps) => steps.iter().any(Self::uses_llm),
+        }
+    }
+
+    /// Applies the strategy to the clone in `context`.
+    pub(super) fn apply<'a>(
+        &'a self,
+        context: &'a StrategyContext<'a>,
+    ) -> BoxFuture<'a, Result<(), PrError>> {
+        Box::pin(async move {
+            debug!(strategy = self.as_str(), "Applying migration strategy");
+            match self {
+                Self::Llm => apply_llm(context).await,
+                Self::Replace => apply_replace(context),
+                Self::Patch { file } => apply_patch(context.path, file).await,
+                Self::Script {
+                    command,
+                    args,
+                    timeout_secs,
+                } => run_script(context, command, args, *timeout_secs).await,
+                Self::Composite(steps) => {
+                    for step in steps {
+                        step.apply(context).await?;
+                    }
+                    Ok(())
+                }
+                Self::Escalate(steps) => escalate(context, steps).await,
+            }
+        })
+    }
+}
+
+/// Resolves every step of a composite or escalating strategy, which must have
+/// at least one.
+fn resolve_steps(
+    steps: Vec<MigrationStrategy>,
+    dir: &Path,
+) -> Result<Vec<MigrationStrategy>, ConfigError> {
+    if steps.is_empty() {
+        return Err(ConfigError::ValidationError {
+            path: dir.display().to_string(),
+            message: "strategy must have at least one step".to_string(),
+        });
+    }
+    steps.into_iter().map(|step| step.resolve(dir)).collect()
+}
+
+/// Runs the LLM agent, with a README excerpt if the migration asks for one.
+async fn apply_llm(context: &StrategyContext<'_>) -> Result<(), PrError> {
+    let StrategyContext {
+        octocrab,
+        path,
+        llm_config_path,
+        migration,
+        repository,
+    } = *context;
+    let readme = match migration.readme_context_lines {
+        Some(lines) => fetch_readme_excerpt(octocrab, repository, lines).await,
+        None => None,
+    };
+    apply_migration(
+        path,
+        llm_config_path,
+        migration,
+        repository,
+        readme.as_deref(),
+    )
+    .await
+    .map_err(|e| match e {
+        LlmError::Timeout(secs) => PrError::Timeout { timeout_secs: secs },
+        _ => PrError::LlmFailed {
+            message: redact(&e.to_string()),
+        },
+    })
+}
+
+/// Replaces `old_string` with `new_string` in every matched location.
+fn apply_replace(context: &StrategyContext<'_>) -> Result<(), PrError> {
+    let migration = context.migration;
+    for location in context.repository.locations() {
+        let file = context.path.join(&location.path);
+        let outcome = replace_in_file(&file, &migration.old_string, &migration.new_string)
+            .map_err(|e| PrError::StrategyFailed {
+                message: e.to_string(),
+            })?;
+        match outcome {
+            ReplaceOutcome::Replaced { count } => {
+                debug!(path = %location.path, count, "Replaced old string");
+            }
+            ReplaceOutcome::NotFound => {
+                debug!(path = %location.path, "Old string not found");
+            }
+            ReplaceOutcome::Skipped { reason } => {
+                warn!(path = %location.path, %reason, "Skipped file");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Applies `patch` to the clone with `git apply`.
+async fn apply_patch(path: &Path, patch: &Path) -> Result<(), PrError> {
+    info!(patch = %patch.display(), "Applying patch");
+    let output = Command::new("git")
+        .kill_on_drop(true)
+        .arg("apply")
+        .arg(patch)
+        .current_dir(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| PrError::StrategyFailed {
+            message: format!("Failed to execute git apply: {e}"),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PrError::StrategyFailed {
+            message: redact(&format!("git apply failed: {stderr}")),
+        });
+    }
+    Ok(())
+}
+
+/// Runs a [`MigrationStrategy::Script`], failing on a non-zero exit status.
+async fn run_script(
+    context: &StrategyContext<'_>,
+    command: &str,
+    args: &[String],
+    timeout_secs: u64,
+) -> Result<(), PrError> {
+    info!(%command, "Running migration script");
+    let output = run_command(context, command, args, timeout_secs).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PrError::StrategyFailed {
+            message: redact(&format!(
+                "{command} exited with {}: {stderr}",
+                output.status
+            )),
+        });
+    }
+    Ok(())
+}
+
+/// Runs `steps` in order until the clone no longer needs follow-up work.
+async fn escalate(
+    context: &StrategyContext<'_>,
+    steps: &[MigrationStrategy],
+) -> Result<(), PrError> {
+    for (index, step) in steps.iter().enumerate() {
+        step.apply(context).await?;
+        let Some(reason) = follow_up_reason(context).await? else {
+            info!(strategy = step.as_str(), "Migration complete");
+            return Ok(());
+        };
+        if index + 1 < steps.len() {
+            info!(strategy = step.as_str(), %reason, "Follow-up work needed");
+        } else {
+            warn!(%reason, "Follow-up work still needed after the last step");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Metadata {
+        #[serde(default)]
+        strategy: MigrationStrategy,
+    }
+
+    fn parse(toml: &str) -> MigrationStrategy {
+        toml::from_str::<Metadata>(toml).unwrap().strategy
+    }
+
+    #[test]
+    fn parses_strategies() {
+        assert_eq!(parse(""), MigrationStrategy::Llm);
+        assert_eq!(parse("strategy = \"replace\""), MigrationStrategy::Replace);
+        assert_eq!(
+            parse("strategy = { patch = { file = \"fix.patch\" } }"),
+            MigrationStrategy::Patch {
+                file: PathBuf::from("fix.patch")
+            }
+        );
+        assert_eq!(
+            parse("strategy = { script = { command = \"./upgrade.sh\", args = [\"-v\"] } }"),
+            MigrationStrategy::Script {
+                command: "./upgrade.sh".to_string(),
+                args: vec!["-v".to_string()],
+                timeout_secs: DEFAULT_SCRIPT_TIMEOUT_SECS,
+            }
+        );
+        assert_eq!(
+            parse("strategy = { script = { command = \"fix\", timeout-secs = 30 } }"),
+            MigrationStrategy::Script {
+                command: "fix".to_string(),
+                args: Vec::new(),
+                timeout_secs: 30,
+            }
+        );
+        assert_eq!(
+            parse("strategy = { composite = [\"replace\", \"llm\"] }"),
+            MigrationStrategy::Composite(vec![MigrationStrategy::Replace, MigrationStrategy::Llm])
+        );
+        assert_eq!(
+            parse("strategy = { escalate = [\"replace\", \"llm\"] }"),
+            MigrationStrategy::Escalate(vec![MigrationStrategy::Replace, MigrationStrategy::Llm])
+        );
+    }
+
+    #[test]
+    fn detects_llm_steps() {
+        assert!(MigrationStrategy::Llm.uses_llm());
+        assert!(!MigrationStrategy::Replace.uses_llm());
+        assert!(parse("strategy = { escalate = [\"replace\", \"llm\"] }").uses_llm());
+        assert!(!parse("strategy = { composite = [\"replace\"] }").uses_llm());
+    }
+
+    #[test]
+    fn resolves_paths_against_migration_folder() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("fix.patch"), "").unwrap();
+
+        let patch = MigrationStrategy::Patch {
+            file: PathBuf::from("fix.patch"),
+        };
+        let script = MigrationStrategy::Script {
+            command: "./upgrade.sh".to_string(),
+            args: Vec::new(),
+            timeout_secs: 1,
+        };
+        let missing = MigrationStrategy::Patch {
+            file: PathBuf::from("missing.patch"),
+        };
+
+        assert_eq!(
+            patch.resolve(temp.path()).unwrap(),
+            MigrationStrategy::Patch {
+                file: temp.path().join("fix.patch")
+            }
+        );
+        assert!(matches!(
+            script.resolve(temp.path()).unwrap(),
+            MigrationStrategy::Script { command, .. }
+                if command == temp.path().join("./upgrade.sh").display().to_string()
+        ));
+        assert!(missing.resolve(temp.path()).is_err());
+        assert!(MigrationStrategy::Composite(Vec::new())
+            .resolve(temp.path())
+            .is_err());
+        assert!(MigrationStrategy::Escalate(Vec::new())
+            .resolve(temp.path())
+            .is_err());
+    }
+}
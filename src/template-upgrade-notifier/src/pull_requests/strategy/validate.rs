@@ -0,0 +1,90 @@
+//! Deciding whether a clone still needs work after a strategy step.
+
+use super::command::run_command;
+use super::{StrategyContext, DEFAULT_SCRIPT_TIMEOUT_SECS};
+use crate::config::{Migration, VersionSource};
+use crate::discovery::version_in;
+use crate::pull_requests::PrError;
+use tracing::debug;
+
+/// Returns why the clone still needs follow-up work, if it does.
+///
+/// Work remains while any matched file still records an outdated template
+/// version, as discovery matches it, or while the migration's
+/// `validate-command` exits unsuccessfully.
+pub(super) async fn follow_up_reason(
+    context: &StrategyContext<'_>,
+) -> Result<Option<String>, PrError> {
+    let migration = context.migration;
+    for location in context.repository.locations() {
+        // A file the step deleted no longer records the old version.
+        let Ok(contents) = std::fs::read(context.path.join(&location.path)) else {
+            continue;
+        };
+        if still_outdated(&String::from_utf8_lossy(&contents), migration) {
+            return Ok(Some(format!(
+                "{} still records the old template version",
+                location.path
+            )));
+        }
+    }
+
+    let Some((command, args)) = migration
+        .validate_command
+        .as_deref()
+        .and_then(<[String]>::split_first)
+    else {
+        return Ok(None);
+    };
+    debug!(%command, "Running validate-command");
+    let output = run_command(context, command, args, DEFAULT_SCRIPT_TIMEOUT_SECS).await?;
+    Ok((!output.status.success())
+        .then(|| format!("validate-command exited with {}", output.status)))
+}
+
+/// Returns true if `contents` still matches the migration's outdated version
+/// (its `old-version-range`, or `old_string`).
+///
+/// Plain text ignores `old_string` inside `new_string`, since a new version
+/// often extends the old one (`v1` -> `v1.1`).
+fn still_outdated(contents: &str, migration: &Migration) -> bool {
+    if migration.version_source == VersionSource::Text {
+        return version_in(&contents.replace(&migration.new_string, ""), migration).is_some();
+    }
+    version_in(contents, migration).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_old_string_inside_new_string() {
+        let migration = Migration {
+            old_string: "v1".to_string(),
+            new_string: "v1.1".to_string(),
+            ..Migration::default()
+        };
+
+        assert!(still_outdated("version = v1\n", &migration));
+        assert!(!still_outdated("version = v1.1\n", &migration));
+    }
+
+    #[test]
+    fn checks_the_old_version_range() {
+        let migration = Migration {
+            old_string: "v1.0.0".to_string(),
+            new_string: "v2.0.0".to_string(),
+            version_source: VersionSource::Copier,
+            old_version_range: Some(">=1.0.0, <2.0.0".parse().unwrap()),
+            ..Migration::default()
+        };
+
+        assert!(still_outdated("_commit: v1.4.2\n", &migration));
+        assert!(!still_outdated("_commit: v2.0.0\n", &migration));
+        assert!(!still_outdated(
+            "# upgraded from v1.0.0\n_commit: v2.0.0\n",
+            &migration
+        ));
+    }
+}
@@ -20,4 +20,7 @@ pub struct UpgradePR {
 
     /// Creation status.
     pub status: super::PrStatus,
+
+    /// Time spent cloning, applying the migration, pushing and calling the API.
+    pub timings: crate::summary::StageTimings,
 }
@@ -0,0 +1,151 @@
+//! Temporary clone directories with a disk quota and guaranteed cleanup.
+//!
+//! A clone can grow far beyond what its default branch suggests (LFS objects,
+//! submodules, vendored assets), and a campaign clones many repositories in a
+//! row, so each clone is measured against a quota before the agent runs.
+
+use super::PrError;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tracing::{debug, warn};
+
+/// Prefix of every clone directory, so leftovers are easy to recognize.
+const WORKSPACE_PREFIX: &str = "template-upgrade-";
+
+/// Bytes in a mebibyte.
+const MIB: u64 = 1024 * 1024;
+
+/// A temporary directory holding one clone, removed when dropped.
+///
+/// Dropping happens on every exit path, including errors, LLM timeouts,
+/// cancelled futures and panics that unwind; unlike a bare [`TempDir`], a
+/// failed removal is logged instead of being ignored silently.
+pub(super) struct Workspace {
+    dir: Option<TempDir>,
+    path: PathBuf,
+}
+
+impl Workspace {
+    /// Creates an empty workspace in the system's temporary directory.
+    pub(super) fn create() -> Result<Self, PrError> {
+        let dir = tempfile::Builder::new()
+            .prefix(WORKSPACE_PREFIX)
+            .tempdir()
+            .map_err(|e| PrError::CloneFailed {
+                message: format!("Failed to create temp directory: {e}"),
+            })?;
+        let path = dir.path().to_path_buf();
+        Ok(Self {
+            dir: Some(dir),
+            path,
+        })
+    }
+
+    /// Returns the workspace's path.
+    pub(super) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns why the clone exceeds `max_mb` mebibytes, if it does.
+    ///
+    /// A quota of `0` disables the check.
+    pub(super) async fn quota_exceeded(&self, max_mb: u64) -> Option<String> {
+        if max_mb == 0 {
+            return None;
+        }
+        let path = self.path.clone();
+        let size = match tokio::task::spawn_blocking(move || directory_size(&path)).await {
+            Ok(size) => size,
+            Err(e) => {
+                warn!(error = %e, "Failed to measure clone size");
+                return None;
+            }
+        };
+        debug!(size_mb = size / MIB, "Measured clone size");
+        (size > max_mb * MIB).then(|| {
+            format!(
+                "clone exceeds disk quota ({} MiB > {max_mb} MiB)",
+                size.div_ceil(MIB)
+            )
+        })
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        let Some(dir) = self.dir.take() else {
+            return;
+        };
+        if let Err(e) = dir.close() {
+            warn!(path = %self.path.display(), error = %e, "Failed to remove clone directory");
+        }
+    }
+}
+
+/// Returns the total size of the files under `path`, not following symlinks.
+///
+/// Entries that cannot be read are counted as empty.
+fn directory_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending: Vec<PathBuf> = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total += metadata.len();
+            }
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_nested_files() {
+        let workspace = Workspace::create().unwrap();
+        let nested = workspace.path().join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(workspace.path().join("root.txt"), [0u8; 10]).unwrap();
+        std::fs::write(nested.join("deep.bin"), [0u8; 32]).unwrap();
+
+        assert_eq!(directory_size(workspace.path()), 42);
+    }
+
+    #[tokio::test]
+    async fn reports_clones_over_quota() {
+        let workspace = Workspace::create().unwrap();
+        std::fs::write(
+            workspace.path().join("big.bin"),
+            vec![0u8; 2 * MIB as usize],
+        )
+        .unwrap();
+
+        assert_eq!(
+            workspace.quota_exceeded(1).await.as_deref(),
+            Some("clone exceeds disk quota (2 MiB > 1 MiB)")
+        );
+        assert_eq!(workspace.quota_exceeded(3).await, None);
+        assert_eq!(workspace.quota_exceeded(0).await, None);
+    }
+
+    #[test]
+    fn removes_directory_on_drop() {
+        let workspace = Workspace::create().unwrap();
+        let path = workspace.path().to_path_buf();
+        std::fs::write(path.join("file.txt"), "x").unwrap();
+
+        drop(workspace);
+
+        assert!(!path.exists());
+    }
+}
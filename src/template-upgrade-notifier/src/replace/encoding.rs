@@ -0,0 +1,138 @@
+//! Detection of text encodings, binary content and line endings.
+
+/// Number of leading bytes inspected for NUL bytes when detecting binaries.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// How a file's text is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Encoding {
+    /// UTF-8, with or without a byte order mark.
+    Utf8,
+    /// UTF-16 little endian, with a byte order mark.
+    Utf16Le,
+    /// UTF-16 big endian, with a byte order mark.
+    Utf16Be,
+    /// Not UTF-8 and without a BOM; assumed to be an ASCII-compatible legacy
+    /// encoding such as Latin-1, so only ASCII strings can be matched.
+    Legacy,
+    /// Contains NUL bytes and is not UTF-16.
+    Binary,
+}
+
+impl Encoding {
+    /// Detects the encoding of `bytes`.
+    pub(super) fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            Self::Utf8
+        } else if bytes.starts_with(UTF16_LE_BOM) {
+            Self::Utf16Le
+        } else if bytes.starts_with(UTF16_BE_BOM) {
+            Self::Utf16Be
+        } else if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+            Self::Binary
+        } else if core::str::from_utf8(bytes).is_ok() {
+            Self::Utf8
+        } else {
+            Self::Legacy
+        }
+    }
+
+    /// Encodes `text` as it would appear in a file of this encoding.
+    ///
+    /// Returns `None` if `text` cannot be represented losslessly.
+    pub(super) fn encode(self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Utf8 => Some(text.as_bytes().to_vec()),
+            Self::Utf16Le => Some(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Self::Utf16Be => Some(text.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            Self::Legacy => text.is_ascii().then(|| text.as_bytes().to_vec()),
+            Self::Binary => None,
+        }
+    }
+
+    /// Size in bytes of one code unit; matches must start on a unit boundary.
+    pub(super) fn unit_len(self) -> usize {
+        match self {
+            Self::Utf16Le | Self::Utf16Be => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Returns true if most line breaks in `bytes` are `\r\n`.
+pub(super) fn uses_crlf(bytes: &[u8], encoding: Encoding) -> bool {
+    let (Some(crlf), Some(lf)) = (encoding.encode("\r\n"), encoding.encode("\n")) else {
+        return false;
+    };
+    let crlf_count = count(bytes, &crlf, encoding.unit_len());
+    let lf_count = count(bytes, &lf, encoding.unit_len());
+    crlf_count > 0 && crlf_count * 2 > lf_count
+}
+
+/// Rewrites the line breaks of `text` as `\r\n`.
+pub(super) fn to_crlf(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+/// Counts non-overlapping occurrences of `needle` aligned to `unit_len`.
+pub(super) fn count(haystack: &[u8], needle: &[u8], unit_len: usize) -> usize {
+    find_all(haystack, needle, unit_len).len()
+}
+
+/// Returns the offsets of non-overlapping occurrences of `needle` aligned to `unit_len`.
+pub(super) fn find_all(haystack: &[u8], needle: &[u8], unit_len: usize) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    if needle.is_empty() {
+        return offsets;
+    }
+    let mut index = 0;
+    while index + needle.len() <= haystack.len() {
+        if haystack[index..].starts_with(needle) {
+            offsets.push(index);
+            index += needle.len();
+        } else {
+            index += unit_len;
+        }
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_encodings() {
+        assert_eq!(Encoding::detect(b"plain text"), Encoding::Utf8);
+        assert_eq!(Encoding::detect(b"\xEF\xBB\xBFbom"), Encoding::Utf8);
+        assert_eq!(Encoding::detect(b"\xFF\xFEa\0"), Encoding::Utf16Le);
+        assert_eq!(Encoding::detect(b"\xFE\xFF\0a"), Encoding::Utf16Be);
+        assert_eq!(Encoding::detect(b"\x89PNG\r\n\x1a\n\0\0"), Encoding::Binary);
+        assert_eq!(Encoding::detect(b"caf\xE9"), Encoding::Legacy);
+    }
+
+    #[test]
+    fn legacy_encodes_ascii_only() {
+        assert_eq!(Encoding::Legacy.encode("v1"), Some(b"v1".to_vec()));
+        assert_eq!(Encoding::Legacy.encode("café"), None);
+    }
+
+    #[test]
+    fn detects_crlf() {
+        assert!(uses_crlf(b"a\r\nb\r\n", Encoding::Utf8));
+        assert!(!uses_crlf(b"a\nb\n", Encoding::Utf8));
+        assert!(!uses_crlf(b"a\r\nb\nc\nd\n", Encoding::Utf8));
+    }
+
+    #[test]
+    fn matches_only_on_unit_boundaries() {
+        // "ab" in UTF-16LE is 61 00 62 00; offset 1 spells 00 62 which must not match.
+        let haystack = [0x61, 0x00, 0x62, 0x00];
+        assert_eq!(find_all(&haystack, &[0x00, 0x62], 2), Vec::<usize>::new());
+        assert_eq!(find_all(&haystack, &[0x62, 0x00], 2), vec![2]);
+    }
+}
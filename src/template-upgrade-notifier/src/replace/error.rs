@@ -0,0 +1,19 @@
+//! Deterministic replacement error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while replacing strings in files.
+#[derive(Debug, Error)]
+pub enum ReplaceError {
+    /// The string to replace is empty.
+    #[error("Cannot replace an empty string")]
+    EmptyPattern,
+
+    /// Failed to read or write the file.
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
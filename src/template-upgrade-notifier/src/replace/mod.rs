@@ -0,0 +1,185 @@
+//! Deterministic string replacement that is safe for any file.
+//!
+//! Unlike a naive read-to-string/replace/write, replacement works on the raw
+//! bytes, so files keep their encoding (UTF-8 with or without BOM, UTF-16,
+//! ASCII-compatible legacy encodings) and line endings. Binary files and
+//! strings that cannot be represented in a file's encoding are skipped.
+
+mod encoding;
+mod error;
+
+pub use error::ReplaceError;
+
+use encoding::{find_all, to_crlf, uses_crlf, Encoding};
+use std::path::Path;
+use tracing::debug;
+
+/// Result of replacing a string in one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplaceOutcome {
+    /// The file was rewritten with `count` replacements.
+    Replaced { count: usize },
+    /// The string was not found; the file was not touched.
+    NotFound,
+    /// The file was not touched because it cannot be edited losslessly.
+    Skipped { reason: String },
+}
+
+/// Replaces every occurrence of `old` with `new` in the file at `path`.
+///
+/// The file is only written if at least one occurrence was replaced. In files
+/// that mostly use `\r\n`, line breaks in `old` and `new` are matched and
+/// written as `\r\n`.
+///
+/// # Errors
+///
+/// Returns [`ReplaceError::EmptyPattern`] if `old` is empty, or
+/// [`ReplaceError::Io`] if the file cannot be read or written.
+pub fn replace_in_file(path: &Path, old: &str, new: &str) -> Result<ReplaceOutcome, ReplaceError> {
+    let io_error = |source| ReplaceError::Io {
+        path: path.display().to_string(),
+        source,
+    };
+    let contents = std::fs::read(path).map_err(io_error)?;
+    let (replaced, outcome) = replace_in_bytes(&contents, old, new)?;
+    if let Some(replaced) = replaced {
+        std::fs::write(path, replaced).map_err(io_error)?;
+    }
+    debug!(path = %path.display(), ?outcome, "Deterministic replacement");
+    Ok(outcome)
+}
+
+/// Replaces every occurrence of `old` with `new` in raw file contents.
+///
+/// Returns the new contents (if anything changed) and the outcome.
+///
+/// # Errors
+///
+/// Returns [`ReplaceError::EmptyPattern`] if `old` is empty.
+pub fn replace_in_bytes(
+    contents: &[u8],
+    old: &str,
+    new: &str,
+) -> Result<(Option<Vec<u8>>, ReplaceOutcome), ReplaceError> {
+    if old.is_empty() {
+        return Err(ReplaceError::EmptyPattern);
+    }
+    let encoding = Encoding::detect(contents);
+    if encoding == Encoding::Binary {
+        return Ok((None, skipped("binary file")));
+    }
+
+    let (old, new) = if uses_crlf(contents, encoding) {
+        (to_crlf(old), to_crlf(new))
+    } else {
+        (old.to_string(), new.to_string())
+    };
+    let (Some(needle), Some(replacement)) = (encoding.encode(&old), encoding.encode(&new)) else {
+        return Ok((
+            None,
+            skipped("non-ASCII text in a file that is not UTF-8 or UTF-16"),
+        ));
+    };
+
+    let offsets = find_all(contents, &needle, encoding.unit_len());
+    if offsets.is_empty() {
+        return Ok((None, ReplaceOutcome::NotFound));
+    }
+    let mut out = Vec::with_capacity(contents.len() + offsets.len() * replacement.len());
+    let mut copied = 0;
+    for offset in &offsets {
+        out.extend_from_slice(&contents[copied..*offset]);
+        out.extend_from_slice(&replacement);
+        copied = offset + needle.len();
+    }
+    out.extend_from_slice(&contents[copied..]);
+    Ok((
+        Some(out),
+        ReplaceOutcome::Replaced {
+            count: offsets.len(),
+        },
+    ))
+}
+
+fn skipped(reason: &str) -> ReplaceOutcome {
+    ReplaceOutcome::Skipped {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn replace(contents: &[u8], old: &str, new: &str) -> (Option<Vec<u8>>, ReplaceOutcome) {
+        replace_in_bytes(contents, old, new).unwrap()
+    }
+
+    #[test]
+    fn preserves_crlf_line_endings() {
+        let (out, outcome) = replace(b"a: 1\r\nb: 2\r\n", "a: 1\nb: 2", "a: 2\nb: 3");
+
+        assert_eq!(outcome, ReplaceOutcome::Replaced { count: 1 });
+        assert_eq!(out.unwrap(), b"a: 2\r\nb: 3\r\n");
+    }
+
+    #[test]
+    fn preserves_utf8_bom() {
+        let (out, _) = replace(b"\xEF\xBB\xBFversion = v1\n", "v1", "v2");
+        assert_eq!(out.unwrap(), b"\xEF\xBB\xBFversion = v2\n");
+    }
+
+    #[test]
+    fn replaces_in_utf16_files() {
+        let encode = |text: &str| -> Vec<u8> {
+            [0xFF, 0xFE]
+                .into_iter()
+                .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect()
+        };
+
+        let (out, outcome) = replace(&encode("ver=v1\r\n"), "v1", "v2");
+
+        assert_eq!(outcome, ReplaceOutcome::Replaced { count: 1 });
+        assert_eq!(out.unwrap(), encode("ver=v2\r\n"));
+    }
+
+    #[test]
+    fn replaces_ascii_in_legacy_encodings() {
+        let (out, _) = replace(b"caf\xE9 v1", "v1", "v2");
+        assert_eq!(out.unwrap(), b"caf\xE9 v2");
+    }
+
+    #[test]
+    fn skips_unrepresentable_and_binary_files() {
+        assert!(matches!(
+            replace(b"caf\xE9 v1", "café", "cafe").1,
+            ReplaceOutcome::Skipped { .. }
+        ));
+        assert!(matches!(
+            replace(b"v1\0\x01\x02", "v1", "v2").1,
+            ReplaceOutcome::Skipped { .. }
+        ));
+    }
+
+    #[test]
+    fn rejects_empty_pattern() {
+        assert!(matches!(
+            replace_in_bytes(b"text", "", "x"),
+            Err(ReplaceError::EmptyPattern)
+        ));
+    }
+
+    #[test]
+    fn leaves_file_untouched_when_not_found() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("version.txt");
+        std::fs::write(&path, "v3\r\n").unwrap();
+
+        let outcome = replace_in_file(&path, "v1", "v2").unwrap();
+
+        assert_eq!(outcome, ReplaceOutcome::NotFound);
+        assert_eq!(std::fs::read(&path).unwrap(), b"v3\r\n");
+    }
+}
@@ -0,0 +1,23 @@
+//! Per-repository data error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while loading `repo-data.toml`.
+#[derive(Debug, Error)]
+pub enum RepoDataError {
+    /// The file could not be read.
+    #[error("Failed to read repository data '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The file is not valid TOML, or a repository's entry is not a table.
+    #[error("Invalid repository data '{path}': {source}")]
+    Toml {
+        path: String,
+        #[source]
+        source: toml::de::Error,
+    },
+}
@@ -0,0 +1,141 @@
+//! Custom per-repository values exposed to issue templates.
+//!
+//! `repo-data.toml` maps `owner/name` to a table of arbitrary values, such as
+//! the internal service owner or an escalation contact, so rendered issues
+//! can carry organization-specific routing information:
+//!
+//! ```toml
+//! ["my-org/payments-api"]
+//! service_owner = "team-payments"
+//! escalation = "#payments-oncall"
+//! ```
+
+mod error;
+
+pub use error::RepoDataError;
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Values for each repository, keyed by lowercased `owner/name`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoData {
+    repositories: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl RepoData {
+    /// Parses repository data from TOML content.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepoDataError::Toml`] if parsing fails.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, RepoDataError> {
+        let tables: BTreeMap<String, BTreeMap<String, toml::Value>> = toml::from_str(content)
+            .map_err(|source| RepoDataError::Toml {
+                path: path.display().to_string(),
+                source,
+            })?;
+        let repositories = tables
+            .into_iter()
+            .map(|(repository, values)| {
+                let values = values
+                    .into_iter()
+                    .map(|(key, value)| (key, to_json(value)))
+                    .collect();
+                (repository.to_lowercase(), values)
+            })
+            .collect();
+        Ok(Self { repositories })
+    }
+
+    /// Loads repository data from a file, returning empty data if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepoDataError::Io`] if the file exists but cannot be read,
+    /// or [`RepoDataError::Toml`] if parsing fails.
+    pub fn load(path: &Path) -> Result<Self, RepoDataError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(RepoDataError::Io {
+                    path: path.display().to_string(),
+                    source,
+                })
+            }
+        };
+        Self::parse(&content, path)
+    }
+
+    /// Returns the values for `repository` (`owner/name`, case-insensitive), if any.
+    #[must_use]
+    pub fn get(&self, repository: &str) -> Option<&BTreeMap<String, Value>> {
+        self.repositories.get(&repository.to_lowercase())
+    }
+
+    /// Returns true if no repository has data.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty()
+    }
+}
+
+/// Converts a TOML value to JSON for the template context; dates become strings.
+fn to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn parses_values_per_repository() {
+        let data = RepoData::parse(
+            r#"
+["My-Org/payments-api"]
+service_owner = "team-payments"
+tier = 1
+contacts = ["alice", "bob"]
+"#,
+            Path::new("repo-data.toml"),
+        )
+        .unwrap();
+
+        let values = data.get("my-org/Payments-API").unwrap();
+        assert_eq!(values["service_owner"], "team-payments");
+        assert_eq!(values["tier"], 1);
+        assert_eq!(values["contacts"][1], "bob");
+        assert!(data.get("my-org/other").is_none());
+    }
+
+    #[test]
+    fn load_returns_empty_when_missing() {
+        let temp = TempDir::new().unwrap();
+        let data = RepoData::load(&temp.path().join("repo-data.toml")).unwrap();
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn rejects_non_table_entries() {
+        let result = RepoData::parse("\"my-org/app\" = 1\n", Path::new("repo-data.toml"));
+        assert!(matches!(result, Err(RepoDataError::Toml { .. })));
+    }
+}
@@ -0,0 +1,15 @@
+//! Risk assessment error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while assessing a repository's risk.
+#[derive(Debug, Error)]
+pub enum RiskError {
+    /// GitHub API error.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+
+    /// The matched file could not be fetched.
+    #[error(transparent)]
+    Discovery(#[from] crate::discovery::DiscoveryError),
+}
@@ -0,0 +1,128 @@
+//! Risk factors and their scoring.
+
+use serde::Serialize;
+
+/// Points added when the repository has no GitHub Actions workflows, so
+/// nothing would check the auto-fix PR.
+pub const NO_CI_POINTS: u32 = 40;
+
+/// Points added when the default branch is unprotected, so the PR could be
+/// merged without review.
+pub const UNPROTECTED_POINTS: u32 = 20;
+
+/// Points added when nothing was pushed for [`STALE_AFTER_DAYS`] days, so
+/// nobody may be around to review the PR.
+pub const STALE_POINTS: u32 = 20;
+
+/// Days without a push after which a repository counts as inactive.
+pub const STALE_AFTER_DAYS: i64 = 180;
+
+/// Points added per occurrence of `old_string` beyond the first, as an
+/// estimate of how large the diff will be.
+pub const PER_EXTRA_OCCURRENCE_POINTS: u32 = 5;
+
+/// Cap on the points added for the expected diff size.
+pub const MAX_DIFF_POINTS: u32 = 20;
+
+/// What is known about a repository when deciding whether to open an auto-fix PR.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RiskFactors {
+    /// Whether the repository has GitHub Actions workflows.
+    pub has_ci: bool,
+    /// Whether the default branch is protected.
+    pub protected_default_branch: bool,
+    /// Days since the last push, if known.
+    pub days_since_push: Option<i64>,
+    /// Occurrences of `old_string` in the matched file.
+    pub occurrences: usize,
+}
+
+impl RiskFactors {
+    /// Returns the risk score from 0 (safest) to 100.
+    #[must_use]
+    pub fn score(&self) -> u32 {
+        let mut score = 0;
+        if !self.has_ci {
+            score += NO_CI_POINTS;
+        }
+        if !self.protected_default_branch {
+            score += UNPROTECTED_POINTS;
+        }
+        if self
+            .days_since_push
+            .is_none_or(|days| days >= STALE_AFTER_DAYS)
+        {
+            score += STALE_POINTS;
+        }
+        let extra = u32::try_from(self.occurrences.saturating_sub(1)).unwrap_or(u32::MAX);
+        score
+            + extra
+                .saturating_mul(PER_EXTRA_OCCURRENCE_POINTS)
+                .min(MAX_DIFF_POINTS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safest() -> RiskFactors {
+        RiskFactors {
+            has_ci: true,
+            protected_default_branch: true,
+            days_since_push: Some(3),
+            occurrences: 1,
+        }
+    }
+
+    #[test]
+    fn safest_repository_scores_zero() {
+        assert_eq!(safest().score(), 0);
+    }
+
+    #[test]
+    fn adds_points_per_factor() {
+        let no_ci = RiskFactors {
+            has_ci: false,
+            ..safest()
+        };
+        let stale = RiskFactors {
+            days_since_push: Some(STALE_AFTER_DAYS),
+            ..safest()
+        };
+        let unknown_activity = RiskFactors {
+            days_since_push: None,
+            ..safest()
+        };
+
+        assert_eq!(no_ci.score(), NO_CI_POINTS);
+        assert_eq!(stale.score(), STALE_POINTS);
+        assert_eq!(unknown_activity.score(), STALE_POINTS);
+    }
+
+    #[test]
+    fn caps_diff_size_points() {
+        let few = RiskFactors {
+            occurrences: 3,
+            ..safest()
+        };
+        let many = RiskFactors {
+            occurrences: 100,
+            ..safest()
+        };
+
+        assert_eq!(few.score(), 2 * PER_EXTRA_OCCURRENCE_POINTS);
+        assert_eq!(many.score(), MAX_DIFF_POINTS);
+    }
+
+    #[test]
+    fn riskiest_repository_scores_hundred() {
+        let riskiest = RiskFactors {
+            has_ci: false,
+            protected_default_branch: false,
+            days_since_push: None,
+            occurrences: 100,
+        };
+        assert_eq!(riskiest.score(), 100);
+    }
+}
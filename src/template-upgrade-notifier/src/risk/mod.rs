@@ -0,0 +1,86 @@
+//! Per-repository risk scoring before opening auto-fix PRs.
+//!
+//! Auto-fix PRs are only as safe as the repository they land in. Before the
+//! LLM path runs, a few signals are gathered (CI workflows, default branch
+//! protection, recent pushes and how many occurrences need changing) and
+//! summed into a score from 0 to 100. Repositories above the configured
+//! threshold get the issue only, allowing a graduated rollout.
+
+mod error;
+mod factors;
+
+pub use error::RiskError;
+pub use factors::{
+    RiskFactors, MAX_DIFF_POINTS, NO_CI_POINTS, PER_EXTRA_OCCURRENCE_POINTS, STALE_AFTER_DAYS,
+    STALE_POINTS, UNPROTECTED_POINTS,
+};
+
+use crate::config::Migration;
+use crate::discovery::{fetch_default_branch_file, DiscoveredRepository};
+use crate::http_cache::{get_json, route};
+use crate::rate_limit::ensure_core_rate_limit;
+use chrono::{DateTime, Utc};
+use octocrab::models::Repository;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use tracing::debug;
+
+#[derive(Deserialize)]
+struct Branch {
+    protected: bool,
+}
+
+#[derive(Deserialize)]
+struct Workflows {
+    total_count: u64,
+}
+
+/// Gathers the risk factors of opening an auto-fix PR in `repository`.
+///
+/// Requests go through the ETag cache, so re-assessing an unchanged
+/// repository costs no core quota.
+///
+/// # Errors
+///
+/// Returns [`RiskError`] if a GitHub API call fails.
+pub async fn assess_risk(
+    octocrab: &Octocrab,
+    repository: &DiscoveredRepository,
+    migration: &Migration,
+    now: DateTime<Utc>,
+) -> Result<RiskFactors, RiskError> {
+    let (owner, name) = (repository.owner.as_str(), repository.name.as_str());
+
+    ensure_core_rate_limit(octocrab).await?;
+    let info: Repository = get_json(octocrab, &route(["repos", owner, name])).await?;
+    let branch: Branch = get_json(
+        octocrab,
+        &route(["repos", owner, name, "branches", &repository.default_branch]),
+    )
+    .await?;
+    let has_ci = match get_json::<Workflows>(
+        octocrab,
+        &route(["repos", owner, name, "actions", "workflows"]),
+    )
+    .await
+    {
+        Ok(workflows) => workflows.total_count > 0,
+        // Actions disabled for the repository.
+        Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => false,
+        Err(e) => return Err(e.into()),
+    };
+    let occurrences = fetch_default_branch_file(octocrab, repository)
+        .await?
+        .map_or(0, |content| content.matches(&migration.old_string).count());
+
+    let factors = RiskFactors {
+        has_ci,
+        protected_default_branch: branch.protected,
+        days_since_push: info
+            .pushed_at
+            .map(|pushed_at| now.signed_duration_since(pushed_at).num_days()),
+        occurrences,
+    };
+    debug!(repo = %repository.full_name, ?factors, score = factors.score(), "Assessed risk");
+    Ok(factors)
+}
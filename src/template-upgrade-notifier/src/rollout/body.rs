@@ -0,0 +1,196 @@
+//! Rendering of the rollout issue body.
+
+use crate::ledger::LedgerEntry;
+use crate::templates::MAX_BODY_CHARS;
+use core::fmt::Write;
+use std::collections::{BTreeMap, HashSet};
+
+/// Marks the body as generated, so it's recognisable when edited by hand.
+const MARKER: &str = "<!-- template-upgrade-notifier:rollout -->";
+
+/// Ends a body cut to fit GitHub's size limit.
+const TRUNCATED_NOTE: &str = "\n_More repositories were notified than fit in this issue._\n";
+
+/// Renders the checklist of notified repositories, grouped by migration.
+///
+/// Boxes ticked in `previous` (the current issue body) stay ticked, so
+/// maintainers can check off repositories as they upgrade. A checklist too
+/// long for an issue body is cut at a line break to [`MAX_BODY_CHARS`].
+pub(super) fn render_rollout_body(entries: &[LedgerEntry], previous: Option<&str>) -> String {
+    let checked = previous.map(checked_items).unwrap_or_default();
+    let mut by_migration: BTreeMap<&str, Vec<&LedgerEntry>> = BTreeMap::new();
+    for entry in entries {
+        by_migration
+            .entry(&entry.migration_id)
+            .or_default()
+            .push(entry);
+    }
+
+    let mut out = String::new();
+    // Writing into a `String` cannot fail.
+    let _ = write_body(&mut out, &by_migration, &checked);
+    fit_to_limit(out)
+}
+
+/// Cuts `body` at the last line break that leaves room for [`TRUNCATED_NOTE`]
+/// within [`MAX_BODY_CHARS`].
+fn fit_to_limit(mut body: String) -> String {
+    if body.chars().count() <= MAX_BODY_CHARS {
+        return body;
+    }
+    let budget = MAX_BODY_CHARS - TRUNCATED_NOTE.chars().count();
+    let end = body
+        .char_indices()
+        .nth(budget)
+        .map_or(body.len(), |(index, _)| index);
+    let end = body[..end].rfind('\n').map_or(0, |index| index + 1);
+    body.truncate(end);
+    body.push_str(TRUNCATED_NOTE);
+    body
+}
+
+fn write_body(
+    out: &mut String,
+    by_migration: &BTreeMap<&str, Vec<&LedgerEntry>>,
+    checked: &HashSet<(String, String)>,
+) -> core::fmt::Result {
+    writeln!(out, "{MARKER}")?;
+    writeln!(
+        out,
+        "Consumer repositories notified about template upgrades. \
+         Tick a repository once it has upgraded."
+    )?;
+    for (migration_id, entries) in by_migration {
+        writeln!(out)?;
+        writeln!(out, "### {migration_id}")?;
+        writeln!(out)?;
+        let mut entries = entries.clone();
+        entries.sort_by(|a, b| a.repository.cmp(&b.repository));
+        for entry in entries {
+            let key = (migration_id.to_string(), entry.repository.clone());
+            let tick = if checked.contains(&key) { 'x' } else { ' ' };
+            write!(
+                out,
+                "- [{tick}] [{repo}](https://github.com/{repo})",
+                repo = entry.repository
+            )?;
+            if let Some(issue) = entry.issue {
+                write!(
+                    out,
+                    " issue [#{issue}](https://github.com/{}/issues/{issue})",
+                    entry.repository
+                )?;
+            }
+            if let Some(pr) = entry.pr {
+                write!(
+                    out,
+                    " PR [#{pr}](https://github.com/{}/pull/{pr})",
+                    entry.repository
+                )?;
+            }
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the `(migration, repository)` pairs ticked in an existing body.
+fn checked_items(body: &str) -> HashSet<(String, String)> {
+    let mut checked = HashSet::new();
+    let mut migration = None;
+    for line in body.lines() {
+        let line = line.trim();
+        if let Some(heading) = line.strip_prefix("### ") {
+            migration = Some(heading.trim().to_string());
+            continue;
+        }
+        let Some(rest) = line
+            .strip_prefix("- [x] [")
+            .or_else(|| line.strip_prefix("- [X] ["))
+        else {
+            continue;
+        };
+        if let (Some(migration), Some((repository, _))) = (&migration, rest.split_once(']')) {
+            checked.insert((migration.clone(), repository.to_string()));
+        }
+    }
+    checked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        migration_id: &str,
+        repository: &str,
+        issue: Option<u64>,
+        pr: Option<u64>,
+    ) -> LedgerEntry {
+        LedgerEntry {
+            migration_id: migration_id.to_string(),
+            repository: repository.to_string(),
+            issue,
+            pr,
+            dispatched: false,
+            recorded_at: String::new(),
+            declined: false,
+            not_planned_version: None,
+        }
+    }
+
+    #[test]
+    fn lists_repositories_by_migration() {
+        let body = render_rollout_body(
+            &[
+                entry("v2", "user/two", Some(3), Some(4)),
+                entry("v2", "user/one", None, Some(9)),
+                entry("v1", "user/one", Some(1), None),
+            ],
+            None,
+        );
+
+        let v1 = body.find("### v1").unwrap();
+        let v2 = body.find("### v2").unwrap();
+        assert!(v1 < v2);
+        assert!(body.contains(
+            "- [ ] [user/two](https://github.com/user/two) \
+             issue [#3](https://github.com/user/two/issues/3) \
+             PR [#4](https://github.com/user/two/pull/4)\n"
+        ));
+        assert!(
+            body.find("[user/one](https://github.com/user/one) PR")
+                .unwrap()
+                > v2
+        );
+    }
+
+    #[test]
+    fn truncates_to_body_limit() {
+        let entries: Vec<_> = (0..2000)
+            .map(|i| entry("v2", &format!("user/repository-{i:04}"), Some(i), Some(i)))
+            .collect();
+
+        let body = render_rollout_body(&entries, None);
+
+        assert!(body.chars().count() <= MAX_BODY_CHARS);
+        assert!(body.ends_with(TRUNCATED_NOTE));
+        assert!(body.contains("[user/repository-0000]"));
+        assert!(!body.contains("[user/repository-1999]"));
+    }
+
+    #[test]
+    fn keeps_ticked_boxes() {
+        let entries = [
+            entry("v2", "user/one", Some(1), None),
+            entry("v2", "user/two", Some(2), None),
+        ];
+        let previous =
+            render_rollout_body(&entries, None).replace("- [ ] [user/one]", "- [x] [user/one]");
+
+        let body = render_rollout_body(&entries, Some(&previous));
+
+        assert!(body.contains("- [x] [user/one]"));
+        assert!(body.contains("- [ ] [user/two]"));
+    }
+}
@@ -0,0 +1,20 @@
+//! Rollout issue configuration.
+
+use serde::Deserialize;
+
+/// Settings for the rollout issue kept in the template's own repository,
+/// from the `[rollout-issue]` section of `notifier.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RolloutIssueConfig {
+    /// The template's repository, in `owner/name` format.
+    pub repository: String,
+
+    /// Title of the rollout issue (defaults to "Template upgrade rollout").
+    #[serde(default = "default_title")]
+    pub title: String,
+}
+
+fn default_title() -> String {
+    "Template upgrade rollout".to_string()
+}
@@ -0,0 +1,15 @@
+//! Rollout issue error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while updating the rollout issue.
+#[derive(Debug, Error)]
+pub enum RolloutError {
+    /// The template repository is not in `owner/name` format.
+    #[error("invalid rollout repository '{value}', expected owner/name")]
+    InvalidRepository { value: String },
+
+    /// GitHub API error.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+}
@@ -0,0 +1,47 @@
+//! Rollout issue in the template's own repository.
+//!
+//! Template maintainers get one place to follow a rollout: an issue listing
+//! every consumer repository that was notified, grouped by migration, with
+//! links to the per-repository issues and PRs and a checkbox to tick once the
+//! repository has upgraded. The issue is created on the first live run and
+//! its body refreshed after every later one.
+
+mod body;
+mod config;
+mod error;
+
+pub use config::RolloutIssueConfig;
+pub use error::RolloutError;
+
+use crate::issues::{split_repository, upsert_own_issue};
+use crate::ledger::LedgerEntry;
+use body::render_rollout_body;
+use octocrab::Octocrab;
+
+/// Creates or refreshes the rollout issue listing `entries`.
+///
+/// Boxes ticked by maintainers in the existing issue stay ticked.
+///
+/// # Returns
+///
+/// The rollout issue's number and URL.
+///
+/// # Errors
+///
+/// Returns [`RolloutError`] if the repository is malformed or a GitHub API
+/// call fails.
+pub async fn update_rollout_issue(
+    octocrab: &Octocrab,
+    config: &RolloutIssueConfig,
+    entries: &[LedgerEntry],
+) -> Result<(u64, String), RolloutError> {
+    let (owner, name) =
+        split_repository(&config.repository).ok_or_else(|| RolloutError::InvalidRepository {
+            value: config.repository.clone(),
+        })?;
+    let issue = upsert_own_issue(octocrab, owner, name, &config.title, |previous| {
+        render_rollout_body(entries, previous)
+    })
+    .await?;
+    Ok((issue.number, issue.html_url.to_string()))
+}
@@ -0,0 +1,189 @@
+//! Opening the auto-fix PR for a processed repository.
+
+use super::repository::ISSUES_DISABLED_REASON;
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::events::RunEvent;
+use crate::hooks::HookEvent;
+use crate::issues::{update_issue_with_pr, IssueStatus};
+use crate::preferences::RepoPreferences;
+use crate::pull_requests::{create_pr_timed, PrOptions, PrStatus};
+use crate::redact::redact;
+use crate::summary::{ProcessingResult, Stage, StageTimings};
+use tracing::{info, warn};
+
+impl Runner {
+    /// Opens the auto-fix PR directly in a repository with issues disabled.
+    ///
+    /// Without auto-PR, or if the consumer asked for issues only, there is
+    /// nothing left to do and the repository is skipped.
+    pub(super) async fn process_pr_only(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        preferences: &RepoPreferences,
+        timings: &mut StageTimings,
+    ) -> ProcessingResult {
+        if !self.config.auto_pr() || !preferences.wants_auto_pr() {
+            return ProcessingResult::Skipped {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                reason: ISSUES_DISABLED_REASON.to_string(),
+                timings: StageTimings::default(),
+            };
+        }
+
+        info!(
+            repo = %repository.full_name,
+            "Issues disabled, opening auto-fix PR without an issue"
+        );
+        let issue = IssueStatus::Skipped {
+            reason: ISSUES_DISABLED_REASON.to_string(),
+        };
+        let pr = self
+            .open_pr(repository, migration, &issue, preferences, timings)
+            .await;
+        let pr_url = pr.url();
+        timings
+            .measure(
+                Stage::Api,
+                self.publish_check(repository, migration, pr_url),
+            )
+            .await;
+        ProcessingResult::PrOnly {
+            migration_id: migration.id.to_string(),
+            repository: repository.full_name.to_string(),
+            pr,
+            timings: StageTimings::default(),
+        }
+    }
+
+    /// Creates the auto-fix PR with the consumer's preferred labels, then
+    /// reports it to event handlers and hooks.
+    ///
+    /// Repositories above the risk threshold report a skipped PR instead.
+    pub(super) async fn open_pr(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        issue: &IssueStatus,
+        preferences: &RepoPreferences,
+        timings: &mut StageTimings,
+    ) -> PrStatus {
+        let issue_number = match issue {
+            IssueStatus::Created { number, .. } => Some(*number),
+            _ => None,
+        };
+        let risk = timings
+            .measure(Stage::Api, self.risk_skip_reason(repository, migration))
+            .await;
+        let status = match risk {
+            Some(reason) => PrStatus::Skipped { reason },
+            None => {
+                self.create_pr(repository, migration, issue_number, timings)
+                    .await
+            }
+        };
+        if let PrStatus::Created { number, .. } | PrStatus::Updated { number, .. } = &status {
+            self.record_pr(repository, migration, *number);
+        }
+        if let PrStatus::Created { number, url } = &status {
+            timings
+                .measure(
+                    Stage::Api,
+                    self.add_preferred_labels(repository, *number, preferences),
+                )
+                .await;
+            self.events.emit(|| RunEvent::PrCreated {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                number: *number,
+                url: url.to_string(),
+            });
+        }
+        self.hooks
+            .emit(|| HookEvent::AfterPr {
+                migration_id: migration.id.to_string(),
+                repository: repository.clone(),
+                issue: issue.clone(),
+                pr: status.clone(),
+            })
+            .await;
+        status
+    }
+
+    /// Returns the run-wide settings for the migration's auto-fix PRs.
+    pub(super) fn pr_options<'a>(&'a self, migration: &'a Migration) -> PrOptions<'a> {
+        PrOptions {
+            token: self.config.token(),
+            llm_config_path: self.config.llm_config_path(),
+            identity: migration
+                .commit_identity
+                .as_ref()
+                .unwrap_or(&self.notifier.commit_identity),
+            checkout: &self.notifier.checkout,
+            proxy: self.config.proxy(),
+            cancellation: self.config.cancellation_token(),
+        }
+    }
+
+    /// Creates the auto-fix PR and links it from the upgrade issue, if there is one.
+    async fn create_pr(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        issue_number: Option<u64>,
+        timings: &mut StageTimings,
+    ) -> PrStatus {
+        let options = self.pr_options(migration);
+        let pr = match create_pr_timed(
+            &self.octocrab,
+            repository,
+            migration,
+            &self.renderer,
+            &options,
+            timings,
+        )
+        .await
+        {
+            Ok(pr) => pr,
+            Err(e) => {
+                warn!(
+                    repo = %repository.full_name,
+                    error = %e,
+                    "Failed to create PR"
+                );
+                return PrStatus::Failed {
+                    error: redact(&e.to_string()),
+                    retryable: e.is_retryable(),
+                };
+            }
+        };
+
+        if let (Some(url), Some(issue_number)) = (pr.status.url(), issue_number) {
+            let update = timings
+                .measure(
+                    Stage::Api,
+                    update_issue_with_pr(
+                        &self.octocrab,
+                        repository,
+                        issue_number,
+                        migration,
+                        &self.renderer,
+                        &pr.status,
+                        Some(url),
+                    ),
+                )
+                .await;
+            if let Err(e) = update {
+                warn!(
+                    repo = %repository.full_name,
+                    error = %e,
+                    "Failed to update issue with PR info"
+                );
+            }
+        }
+        pr.status
+    }
+}
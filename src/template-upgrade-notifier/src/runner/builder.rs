@@ -0,0 +1,250 @@
+//! Builder for [`Runner`] with injectable dependencies.
+
+use super::forges::OtherForges;
+use super::{Runner, RunnerConfig, RunnerError};
+use crate::campaign::Campaign;
+use crate::clock::{Clock, SystemClock};
+use crate::config::NotifierConfig;
+use crate::discovery::RepoList;
+use crate::events::EventHandlers;
+use crate::hooks::HookSet;
+use crate::ledger::Ledger;
+use crate::proxy::{build_github_client, ClientSettings};
+use crate::redact::{register_env_secrets, register_secret};
+use crate::repo_data::RepoData;
+use crate::selection::{PatternSet, PriorityList};
+use crate::state::{open_state_store, StateStore};
+use crate::summary::SummaryCollector;
+use crate::templates::TemplateRenderer;
+use octocrab::Octocrab;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Builds a [`Runner`], optionally with a pre-built GitHub client, clock or
+/// notifier settings.
+///
+/// Anything not provided falls back to what [`Runner::new`] would use: a
+/// client authenticated with the configured token, the system clock, and
+/// settings loaded from `notifier.toml`.
+pub struct RunnerBuilder {
+    config: RunnerConfig,
+    octocrab: Option<Octocrab>,
+    clock: Option<Box<dyn Clock>>,
+    notifier: Option<NotifierConfig>,
+    state_store: Option<Box<dyn StateStore>>,
+}
+
+impl RunnerBuilder {
+    pub(super) fn new(config: RunnerConfig) -> Self {
+        Self {
+            config,
+            octocrab: None,
+            clock: None,
+            notifier: None,
+            state_store: None,
+        }
+    }
+
+    /// Uses a pre-built GitHub client, e.g. one pointing at a mock server or
+    /// with custom middleware.
+    ///
+    /// The configured token is still used to push auto-fix branches.
+    pub fn octocrab(mut self, octocrab: Octocrab) -> Self {
+        self.octocrab = Some(octocrab);
+        self
+    }
+
+    /// Uses a custom time source for deadlines, delays and timestamps.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Uses the given notifier settings instead of loading `notifier.toml`.
+    pub fn notifier(mut self, notifier: NotifierConfig) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Keeps state in the given store instead of the one at the configured
+    /// state URL.
+    pub fn state_store(mut self, store: impl StateStore + 'static) -> Self {
+        self.state_store = Some(Box::new(store));
+        self
+    }
+
+    /// Builds the runner.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError`] if `notifier.toml` cannot be loaded, the
+    /// campaign is unknown, `repo-data.toml`, the priority list or the
+    /// static repository list cannot be read, a selection or exclusion pattern is invalid, the state store
+    /// cannot be opened, or the GitHub, GitLab or Azure DevOps client cannot
+    /// be built.
+    pub fn build(self) -> Result<Runner, RunnerError> {
+        let mut config = self.config;
+        register_secret(config.token());
+        register_env_secrets();
+        let notifier = match self.notifier {
+            Some(notifier) => notifier,
+            None => NotifierConfig::load(config.notifier_config_path())?,
+        };
+        let campaign = match config.campaign() {
+            Some(name) => Some(Campaign::resolve(&notifier.campaigns, name)?),
+            None => None,
+        };
+        let campaign_filter = match &campaign {
+            Some(campaign) => {
+                config = apply_campaign_settings(config, campaign);
+                campaign.migration_filter()?
+            }
+            None => PatternSet::default(),
+        };
+        let migration_filter = PatternSet::new(config.only_migrations())?;
+        let repo_filter = PatternSet::new(config.only_repos())?;
+        let owner_exclusions = PatternSet::new(&notifier.exclude.owners)?;
+        let repo_data = match config.repo_data_path() {
+            Some(path) => RepoData::load(path)?,
+            None => RepoData::default(),
+        };
+        let priority = match config.priority_path() {
+            Some(path) => PriorityList::load(path)?,
+            None => PriorityList::default(),
+        };
+        let repo_list = config.repo_list_path().map(RepoList::load).transpose()?;
+        let state_store = match self.state_store {
+            Some(store) => Some(store),
+            None => config.state_url().map(open_state_store).transpose()?,
+        };
+        let hooks = HookSet::from_config(&notifier.hooks);
+        let renderer = TemplateRenderer::new().with_markdown_lint(notifier.lint_markdown);
+        let other_forges = OtherForges::from_config(&notifier)?;
+        let octocrab = match self.octocrab {
+            Some(octocrab) => octocrab,
+            None => build_octocrab(&config)?,
+        };
+        Ok(Runner {
+            config,
+            notifier,
+            octocrab,
+            other_forges,
+            renderer,
+            campaign,
+            campaign_filter,
+            migration_filter,
+            repo_filter,
+            owner_exclusions,
+            repo_data,
+            priority,
+            repo_list,
+            hooks,
+            events: EventHandlers::default(),
+            clock: self.clock.unwrap_or_else(|| Box::new(SystemClock)),
+            ledger: Mutex::new(Ledger::default()),
+            state_store,
+            state_versions: Mutex::new(BTreeMap::new()),
+            result_sender: Mutex::new(None),
+            progress: SummaryCollector::default(),
+            large_campaign_prompt: None,
+        })
+    }
+}
+
+/// Overrides run settings with those shared by the campaign's migrations.
+fn apply_campaign_settings(mut config: RunnerConfig, campaign: &Campaign) -> RunnerConfig {
+    let shared = &campaign.config;
+    if let Some(auto_pr) = shared.auto_pr {
+        config = config.with_auto_pr(auto_pr);
+    }
+    if let Some(auto_close) = shared.auto_close {
+        config = config.with_auto_close(auto_close);
+    }
+    if let Some(concurrency) = shared.concurrency {
+        config = config.with_concurrency(concurrency);
+    }
+    config
+}
+
+/// Builds the GitHub client with the configured timeouts, retries and proxy.
+///
+/// Connections are pooled and reused by the client; as many idle ones are
+/// kept as repositories are processed at once.
+fn build_octocrab(config: &RunnerConfig) -> Result<Octocrab, RunnerError> {
+    let settings = ClientSettings {
+        token: config.token(),
+        connect_timeout: config.connect_timeout(),
+        request_timeout: config.request_timeout(),
+        max_retries: config.http_retries(),
+        max_idle_connections: config.concurrency(),
+    };
+    Ok(build_github_client(config.proxy(), settings)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+    use core::time::Duration;
+    use futures::future::BoxFuture;
+    use tempfile::TempDir;
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+
+        fn sleep(&self, _duration: Duration) -> BoxFuture<'static, ()> {
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn builds_with_injected_dependencies() {
+        let temp = TempDir::new().unwrap();
+        let migrations = temp.path().join("migrations");
+        std::fs::create_dir(&migrations).unwrap();
+        let config = RunnerConfig::new(migrations, String::new(), true, 1, false)
+            .with_notifier_config_path(temp.path().join("missing.toml"));
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let octocrab = Octocrab::builder()
+            .base_uri("http://127.0.0.1:1")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let runner = Runner::builder(config)
+            .octocrab(octocrab)
+            .clock(FixedClock(
+                Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ))
+            .notifier(NotifierConfig::default())
+            .build()
+            .unwrap();
+        let summary = runner.run().await.unwrap();
+
+        assert_eq!(summary.migrations_processed, 0);
+    }
+
+    #[cfg(not(feature = "gitlab"))]
+    #[test]
+    fn rejects_gitlab_section_without_feature() {
+        let temp = TempDir::new().unwrap();
+        let config = RunnerConfig::new(temp.path().to_path_buf(), String::new(), true, 1, false);
+        let notifier = NotifierConfig {
+            gitlab: Some(crate::gitlab::GitLabConfig::new(["my-org"])),
+            ..NotifierConfig::default()
+        };
+
+        let result = Runner::builder(config).notifier(notifier).build();
+
+        assert!(matches!(
+            result,
+            Err(RunnerError::Forge(crate::forge::ForgeError::GitLab(
+                crate::gitlab::GitLabError::Disabled
+            )))
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+//! Campaign labelling and tracking issue updates for runs.
+
+use super::Runner;
+use crate::campaign::update_tracking_issue;
+use crate::summary::RunSummary;
+use tracing::{info, warn};
+
+impl Runner {
+    /// Returns an empty summary for the run `run_id`, labelled with the
+    /// campaign if any.
+    pub(super) fn new_summary(&self, run_id: String) -> RunSummary {
+        let mut summary = RunSummary::new(self.config.dry_run());
+        summary.run_id = run_id;
+        summary.campaign = self.campaign.as_ref().map(|c| c.title().to_string());
+        summary
+    }
+
+    /// Refreshes the campaign's tracking issue with the run's report.
+    ///
+    /// Dry runs leave the tracking issue untouched. Failures are logged; they
+    /// never fail an otherwise completed run.
+    pub(super) async fn update_tracking_issue(&self, summary: &RunSummary) {
+        let Some(campaign) = &self.campaign else {
+            return;
+        };
+        if summary.dry_run {
+            return;
+        }
+        match update_tracking_issue(&self.octocrab, campaign, summary).await {
+            Ok(Some((number, url))) => {
+                info!(campaign = %campaign.name, number, url = %url, "Updated tracking issue");
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(campaign = %campaign.name, error = %e, "Failed to update tracking issue")
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Reading remote changelogs at the start of a run.
+
+use super::Runner;
+use crate::changelog::{excerpt_for, fetch_changelog};
+use crate::config::Migration;
+use tracing::{debug, warn};
+
+impl Runner {
+    /// Fills in the changelog excerpt of every migration with a
+    /// `changelog-url`.
+    ///
+    /// A changelog that cannot be read only loses the excerpt: it is logged
+    /// and `{{changelog}}` renders empty. Report-only runs render no
+    /// templates, so nothing is read.
+    pub(super) async fn fetch_changelogs(
+        &self,
+        migrations: impl IntoIterator<Item = &mut Migration>,
+    ) {
+        if self.config.report_only() {
+            return;
+        }
+        for migration in migrations {
+            let Some(url) = migration.changelog_url.as_deref() else {
+                continue;
+            };
+            match fetch_changelog(&self.octocrab, url).await {
+                Ok(markdown) => {
+                    migration.changelog = excerpt_for(
+                        &markdown,
+                        migration.changelog_section.as_deref(),
+                        &migration.new_string,
+                    );
+                    if migration.changelog.is_none() {
+                        warn!(
+                            migration_id = %migration.id,
+                            url,
+                            "No changelog section found for the new version"
+                        );
+                    } else {
+                        debug!(migration_id = %migration.id, url, "Read changelog");
+                    }
+                }
+                Err(e) => {
+                    warn!(migration_id = %migration.id, url, error = %e, "Failed to read changelog");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Checkpoint written when a run stops early at its runtime limit.
+
+use super::retry::ProcessedMigration;
+use super::Runner;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Work left unfinished when a run hit `--max-runtime`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// RFC 3339 timestamp of when the checkpoint was written.
+    pub recorded_at: String,
+    /// Repositories discovered for a started migration but never processed.
+    pub pending_repositories: Vec<PendingRepository>,
+    /// Migrations that were never started.
+    pub pending_migrations: Vec<String>,
+}
+
+/// A repository that was discovered but not processed before the deadline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRepository {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Repository full name.
+    pub repository: String,
+}
+
+impl Checkpoint {
+    /// Returns true if nothing was left unfinished.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending_repositories.is_empty() && self.pending_migrations.is_empty()
+    }
+
+    /// Writes the checkpoint as pretty-printed JSON, replacing existing contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+
+    /// Reads a checkpoint previously written with [`Checkpoint::write`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid checkpoint.
+    pub fn read(path: &Path) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+impl Runner {
+    /// Adds the repositories of `run` that have no result to `checkpoint`.
+    ///
+    /// Repositories abandoned by a cancellation are resumed like unstarted ones.
+    pub(super) fn add_unfinished(&self, run: &ProcessedMigration, checkpoint: &mut Checkpoint) {
+        let cancelled = self.is_cancelled();
+        checkpoint.pending_repositories.extend(
+            run.repositories
+                .iter()
+                .filter(|repo| {
+                    !run.results.iter().any(|r| {
+                        r.repository() == repo.full_name && !(cancelled && r.is_retryable())
+                    })
+                })
+                .map(|repo| PendingRepository {
+                    migration_id: run.migration.id.to_string(),
+                    repository: repo.full_name.to_string(),
+                }),
+        );
+    }
+
+    /// Writes the unfinished work of a run that hit its runtime limit or
+    /// abort threshold.
+    ///
+    /// Failures are logged; the run's results are still reported.
+    pub(super) fn write_checkpoint(&self, mut checkpoint: Checkpoint) {
+        checkpoint.recorded_at = self.clock.now().to_rfc3339();
+        let path = self.config.checkpoint_path();
+        warn!(
+            pending_repositories = checkpoint.pending_repositories.len(),
+            pending_migrations = checkpoint.pending_migrations.len(),
+            "Stopped early"
+        );
+        match checkpoint.write(path) {
+            Ok(()) => info!(path = %path.display(), "Wrote checkpoint"),
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to write checkpoint"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn round_trips_through_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("checkpoint.json");
+        let checkpoint = Checkpoint {
+            recorded_at: "2024-01-01T00:00:00+00:00".to_string(),
+            pending_repositories: vec![PendingRepository {
+                migration_id: "m1".to_string(),
+                repository: "user/repo".to_string(),
+            }],
+            pending_migrations: vec!["m2".to_string()],
+        };
+
+        checkpoint.write(&path).unwrap();
+
+        assert_eq!(Checkpoint::read(&path).unwrap(), checkpoint);
+        assert!(!checkpoint.is_empty());
+    }
+}
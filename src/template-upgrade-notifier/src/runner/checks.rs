@@ -0,0 +1,35 @@
+//! Flagging outdated repositories with a commit status or check run.
+
+use super::Runner;
+use crate::checks::publish_outdated_check;
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use tracing::warn;
+
+impl Runner {
+    /// Publishes the migration's `commit-check` on the repository's default
+    /// branch HEAD, linking `target_url` or else the migration guide.
+    ///
+    /// Does nothing for migrations without a `commit-check`. Failures are
+    /// logged; the repository's issue and PR are unaffected.
+    pub(super) async fn publish_check(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        target_url: Option<&str>,
+    ) {
+        let Some(kind) = migration.commit_check else {
+            return;
+        };
+        let target_url = target_url.or(migration.migration_guide_link.as_deref());
+        if let Err(e) =
+            publish_outdated_check(&self.octocrab, repository, migration, kind, target_url).await
+        {
+            warn!(
+                repo = %repository.full_name,
+                error = %e,
+                "Failed to publish template-version check"
+            );
+        }
+    }
+}
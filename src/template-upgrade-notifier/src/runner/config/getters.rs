@@ -0,0 +1,206 @@
+//! Read access to [`RunnerConfig`] settings.
+
+use super::RunnerConfig;
+use crate::config::DryRunLevel;
+use crate::proxy::ProxyConfig;
+use crate::selection::Sample;
+use core::time::Duration;
+use std::path::Path;
+use tokio_util::sync::CancellationToken;
+
+impl RunnerConfig {
+    /// Returns the migrations directory path.
+    pub fn migrations_path(&self) -> &Path {
+        &self.migrations_path
+    }
+
+    /// Returns the configured GitHub token.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns whether dry-run mode is enabled.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Returns how much of a live run a dry run exercises.
+    pub fn dry_run_level(&self) -> DryRunLevel {
+        self.dry_run_level
+    }
+
+    /// Returns whether the run only collects a compliance report.
+    pub fn report_only(&self) -> bool {
+        self.report_only
+    }
+
+    /// Returns the max concurrent API requests.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Returns whether auto-PR generation is enabled.
+    pub fn auto_pr(&self) -> bool {
+        self.auto_pr
+    }
+
+    /// Returns the LLM config file path.
+    pub fn llm_config_path(&self) -> &Path {
+        &self.llm_config_path
+    }
+
+    /// Returns the notifier settings file path.
+    pub fn notifier_config_path(&self) -> &Path {
+        &self.notifier_config_path
+    }
+
+    /// Returns the migration id selection patterns.
+    pub fn only_migrations(&self) -> &[String] {
+        &self.only_migrations
+    }
+
+    /// Returns whether a migration that fails to load fails the run.
+    pub fn strict_migrations(&self) -> bool {
+        self.strict_migrations
+    }
+
+    /// Returns the repository selection patterns.
+    pub fn only_repos(&self) -> &[String] {
+        &self.only_repos
+    }
+
+    /// Returns the number of retry passes for transient failures.
+    pub fn retry_attempts(&self) -> u32 {
+        self.retry_attempts
+    }
+
+    /// Returns the delay before each retry pass.
+    pub fn retry_delay(&self) -> Duration {
+        self.retry_delay
+    }
+
+    /// Returns the run history database path, if recording is enabled.
+    pub fn history_path(&self) -> Option<&Path> {
+        self.history_path.as_deref()
+    }
+
+    /// Returns the URL of the store state is kept in between runs, if any.
+    pub fn state_url(&self) -> Option<&str> {
+        self.state_url.as_deref()
+    }
+
+    /// Returns the wall-clock limit for a run, if any.
+    pub fn max_runtime(&self) -> Option<Duration> {
+        self.max_runtime
+    }
+
+    /// Returns the checkpoint file path.
+    pub fn checkpoint_path(&self) -> &Path {
+        &self.checkpoint_path
+    }
+
+    /// Returns the run lock file path, if locking is enabled.
+    pub fn lock_path(&self) -> Option<&Path> {
+        self.lock_path.as_deref()
+    }
+
+    /// Returns the age after which an abandoned lock is broken.
+    pub fn lock_stale_after(&self) -> Duration {
+        self.lock_stale_after
+    }
+
+    /// Returns whether issues are filed past duplicate detection.
+    pub fn force_issues(&self) -> bool {
+        self.force_issues
+    }
+
+    /// Returns the ETag cache file path, if caching is enabled.
+    pub fn etag_cache_path(&self) -> Option<&Path> {
+        self.etag_cache_path.as_deref()
+    }
+
+    /// Returns whether notifications in upgraded repositories are closed.
+    pub fn auto_close(&self) -> bool {
+        self.auto_close
+    }
+
+    /// Returns the time allowed to establish a connection to the GitHub API.
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    /// Returns the time allowed for reading or writing a GitHub API request.
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+
+    /// Returns how many times a failed GitHub API request is retried.
+    pub fn http_retries(&self) -> usize {
+        self.http_retries
+    }
+
+    /// Returns the proxy and CA settings.
+    pub fn proxy(&self) -> &ProxyConfig {
+        &self.proxy
+    }
+
+    /// Returns the name of the campaign to run, if any.
+    pub fn campaign(&self) -> Option<&str> {
+        self.campaign.as_deref()
+    }
+
+    /// Returns the highest risk score at which auto-fix PRs are opened, if limited.
+    pub fn max_auto_pr_risk(&self) -> Option<u32> {
+        self.max_auto_pr_risk
+    }
+
+    /// Returns the repository count above which live runs need confirmation, if any.
+    pub fn large_campaign_threshold(&self) -> Option<usize> {
+        self.large_campaign_threshold
+    }
+
+    /// Returns whether large campaigns are confirmed up front.
+    pub fn confirm_large_campaigns(&self) -> bool {
+        self.confirm_large_campaigns
+    }
+
+    /// Returns the staged rollout state path, if enabled.
+    pub fn rollout_state_path(&self) -> Option<&Path> {
+        self.rollout_state_path.as_deref()
+    }
+
+    /// Returns the per-repository template data path, if enabled.
+    pub fn repo_data_path(&self) -> Option<&Path> {
+        self.repo_data_path.as_deref()
+    }
+
+    /// Returns the priority list path, if enabled.
+    pub fn priority_path(&self) -> Option<&Path> {
+        self.priority_path.as_deref()
+    }
+
+    /// Returns the static repository list path, if discovery uses one.
+    pub fn repo_list_path(&self) -> Option<&Path> {
+        self.repo_list_path.as_deref()
+    }
+
+    /// Returns the estimated cost in US dollars of one LLM agent run, if set.
+    pub fn llm_cost_per_run(&self) -> Option<f64> {
+        self.llm_cost_per_run
+    }
+
+    /// Returns the sample of each migration's repositories to process, if sampling.
+    pub fn sample(&self) -> Option<Sample> {
+        self.sample
+    }
+
+    /// Returns the token that cancels the run.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Returns the idempotency ledger path, if enabled.
+    pub fn ledger_path(&self) -> Option<&Path> {
+        self.ledger_path.as_deref()
+    }
+}
@@ -0,0 +1,547 @@
+//! Runner configuration.
+
+mod getters;
+mod search_cache;
+
+use self::search_cache::SearchCacheSettings;
+use crate::config::DryRunLevel;
+use crate::proxy::ProxyConfig;
+use crate::selection::Sample;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+/// Default number of retry passes for transiently failed repositories.
+const DEFAULT_RETRY_ATTEMPTS: u32 = 1;
+
+/// Default delay before each retry pass.
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Default time allowed to establish a connection to the GitHub API.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time allowed for reading or writing a GitHub API request.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Default number of times a GitHub API request is retried after a dropped
+/// connection or server error.
+const DEFAULT_HTTP_RETRIES: usize = 3;
+
+/// Default number of discovered repositories above which a live run needs
+/// confirmation before notifying a migration.
+const DEFAULT_LARGE_CAMPAIGN_THRESHOLD: usize = 500;
+
+/// Default age after which a lock left by a crashed run is broken.
+const DEFAULT_LOCK_STALE_AFTER: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Configuration for running the template upgrade notifier.
+///
+/// Serializes to (and deserializes from) a kebab-case document describing
+/// the whole run. The GitHub token and proxy credentials are never written
+/// out and the cancellation token is not part of it; a deserialized
+/// configuration needs [`with_token`](Self::with_token).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RunnerConfig {
+    /// Path to the migrations directory.
+    migrations_path: PathBuf,
+    /// GitHub token used for API calls and PR pushes.
+    #[serde(skip_serializing, default)]
+    token: String,
+    /// Whether to preview changes without creating issues/PRs.
+    dry_run: bool,
+    /// How much of a live run a dry run exercises.
+    #[serde(default)]
+    dry_run_level: DryRunLevel,
+    /// Whether the run only reads each repository's template version for a
+    /// compliance report, implying a dry run.
+    #[serde(default)]
+    report_only: bool,
+    /// Maximum concurrent API requests.
+    concurrency: usize,
+    /// Whether auto-PR generation is enabled.
+    auto_pr: bool,
+    /// Path to the LLM config file.
+    llm_config_path: PathBuf,
+    /// Path to the run-wide notifier settings file.
+    notifier_config_path: PathBuf,
+    /// Glob patterns selecting migration ids to run; empty selects all.
+    only_migrations: Vec<String>,
+    /// Whether a migration that fails to load fails the run.
+    #[serde(default)]
+    strict_migrations: bool,
+    /// Glob patterns selecting `owner/name` repositories to process; empty selects all.
+    only_repos: Vec<String>,
+    /// Number of end-of-migration retry passes for transient failures.
+    retry_attempts: u32,
+    /// Delay before each retry pass.
+    retry_delay: Duration,
+    /// Path to the run history database; `None` disables recording.
+    history_path: Option<PathBuf>,
+    /// Wall-clock limit after which no new repositories are started.
+    max_runtime: Option<Duration>,
+    /// Where to write the checkpoint when the runtime limit is hit.
+    checkpoint_path: PathBuf,
+    /// Lock file preventing overlapping live runs, if locking is enabled.
+    lock_path: Option<PathBuf>,
+    /// Age after which an abandoned lock is broken.
+    lock_stale_after: Duration,
+    /// Whether to file issues even when an open duplicate exists.
+    force_issues: bool,
+    /// Ledger of already notified repositories, if enabled.
+    ledger_path: Option<PathBuf>,
+    /// Store the ledger, checkpoint and history are kept in between runs, if any.
+    #[serde(default)]
+    state_url: Option<String>,
+    /// Whether to close notifications in repositories that upgraded on their own.
+    auto_close: bool,
+    /// Where responses for conditional requests are cached, if enabled.
+    etag_cache_path: Option<PathBuf>,
+    /// Where and how code search results are cached.
+    #[serde(flatten)]
+    search_cache: SearchCacheSettings,
+    /// Time allowed to establish a connection to the GitHub API.
+    connect_timeout: Duration,
+    /// Time allowed for reading or writing a GitHub API request.
+    request_timeout: Duration,
+    /// Retries of a GitHub API request after a dropped connection or server error.
+    http_retries: usize,
+    /// Campaign from `notifier.toml` to run, if any.
+    campaign: Option<String>,
+    /// Highest risk score at which auto-fix PRs are still opened, if limited.
+    max_auto_pr_risk: Option<u32>,
+    /// Where the stage of each staged rollout is kept, if persisted.
+    rollout_state_path: Option<PathBuf>,
+    /// Proxy and CA settings for GitHub API and git connections.
+    proxy: ProxyConfig,
+    /// Repositories per migration above which live runs need confirmation.
+    large_campaign_threshold: Option<usize>,
+    /// Whether migrations above the threshold are confirmed up front.
+    confirm_large_campaigns: bool,
+    /// Per-repository template data (`repo-data.toml`), if enabled.
+    repo_data_path: Option<PathBuf>,
+    /// Repositories processed first within each migration, if enabled.
+    priority_path: Option<PathBuf>,
+    /// Static list of repositories checked instead of code search, if any.
+    #[serde(default)]
+    repo_list_path: Option<PathBuf>,
+    /// Estimated cost in US dollars of one LLM agent run, for dry-run estimates.
+    #[serde(default)]
+    llm_cost_per_run: Option<f64>,
+    /// Random share of each migration's repositories to process, if sampling.
+    #[serde(default)]
+    sample: Option<Sample>,
+    /// Token that stops the run from starting further work once cancelled.
+    #[serde(skip)]
+    cancellation_token: CancellationToken,
+}
+
+impl RunnerConfig {
+    /// Creates a new configuration for a run.
+    pub fn new(
+        migrations_path: PathBuf,
+        token: String,
+        dry_run: bool,
+        concurrency: usize,
+        auto_pr: bool,
+    ) -> Self {
+        let config_dir = migrations_path.parent().unwrap_or_else(|| Path::new("."));
+        let llm_config_path = config_dir.join("config.toml");
+        let notifier_config_path = config_dir.join("notifier.toml");
+        let history_path = Some(Self::default_history_path(&migrations_path));
+        let checkpoint_path = config_dir.join("checkpoint.json");
+        let lock_path = Some(config_dir.join("run.lock"));
+        let ledger_path = Some(config_dir.join("ledger.json"));
+        let etag_cache_path = Some(config_dir.join("etag-cache.json"));
+        let rollout_state_path = Some(config_dir.join("rollout-state.json"));
+        let repo_data_path = Some(config_dir.join("repo-data.toml"));
+        let priority_path = Some(config_dir.join("priority.txt"));
+        Self {
+            migrations_path,
+            token,
+            dry_run,
+            dry_run_level: DryRunLevel::default(),
+            report_only: false,
+            concurrency,
+            auto_pr,
+            llm_config_path,
+            notifier_config_path,
+            only_migrations: Vec::new(),
+            strict_migrations: false,
+            only_repos: Vec::new(),
+            retry_attempts: DEFAULT_RETRY_ATTEMPTS,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            history_path,
+            max_runtime: None,
+            checkpoint_path,
+            lock_path,
+            lock_stale_after: DEFAULT_LOCK_STALE_AFTER,
+            force_issues: false,
+            ledger_path,
+            state_url: None,
+            auto_close: false,
+            etag_cache_path,
+            search_cache: SearchCacheSettings::default(),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            http_retries: DEFAULT_HTTP_RETRIES,
+            campaign: None,
+            max_auto_pr_risk: None,
+            rollout_state_path,
+            proxy: ProxyConfig::from_env(),
+            large_campaign_threshold: Some(DEFAULT_LARGE_CAMPAIGN_THRESHOLD),
+            confirm_large_campaigns: false,
+            repo_data_path,
+            priority_path,
+            repo_list_path: None,
+            llm_cost_per_run: None,
+            sample: None,
+            cancellation_token: CancellationToken::new(),
+        }
+    }
+
+    /// Returns the default run history database path for a migrations directory.
+    ///
+    /// This is `history.sqlite` next to the migrations folder.
+    pub fn default_history_path(migrations_path: &Path) -> PathBuf {
+        migrations_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("history.sqlite")
+    }
+
+    /// Sets the GitHub token used for API calls and PR pushes.
+    pub fn with_token(mut self, token: String) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Sets how much of a live run a dry run exercises; migrations with a
+    /// `dry-run` level of their own use that instead.
+    pub fn with_dry_run_level(mut self, level: DryRunLevel) -> Self {
+        self.dry_run_level = level;
+        self
+    }
+
+    /// Makes the run report-only: a dry run that reads each discovered
+    /// repository's template version from its default branch for
+    /// [`Runner::report`](crate::runner::Runner::report), without previews.
+    /// Rollout percentages and samples don't narrow the report.
+    pub fn with_report_only(mut self, report_only: bool) -> Self {
+        self.report_only = report_only;
+        self.dry_run |= report_only;
+        self
+    }
+
+    /// Fails the run (and the doctor's migrations check) if any migration
+    /// fails to load, instead of skipping it with a warning.
+    pub fn with_strict_migrations(mut self, strict: bool) -> Self {
+        self.strict_migrations = strict;
+        self
+    }
+
+    /// Sets a custom LLM config path.
+    pub fn with_llm_config_path(mut self, llm_config_path: PathBuf) -> Self {
+        self.llm_config_path = llm_config_path;
+        self
+    }
+
+    /// Sets a custom notifier settings path.
+    pub fn with_notifier_config_path(mut self, notifier_config_path: PathBuf) -> Self {
+        self.notifier_config_path = notifier_config_path;
+        self
+    }
+
+    /// Restricts the run to migrations whose id matches any of the glob patterns.
+    pub fn with_only_migrations(mut self, patterns: Vec<String>) -> Self {
+        self.only_migrations = patterns;
+        self
+    }
+
+    /// Restricts the run to `owner/name` repositories matching any of the glob patterns.
+    pub fn with_only_repos(mut self, patterns: Vec<String>) -> Self {
+        self.only_repos = patterns;
+        self
+    }
+
+    /// Sets how many retry passes run, after every migration had its first
+    /// pass, for repositories that failed transiently.
+    ///
+    /// Defaults to one pass; `0` disables retries.
+    pub fn with_retry_attempts(mut self, retry_attempts: u32) -> Self {
+        self.retry_attempts = retry_attempts;
+        self
+    }
+
+    /// Sets the delay before each retry pass.
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Sets the run history database path; `None` disables recording.
+    ///
+    /// Only used when built with the `history` feature.
+    pub fn with_history_path(mut self, history_path: Option<PathBuf>) -> Self {
+        self.history_path = history_path;
+        self
+    }
+
+    /// Keeps the ledger, checkpoint and history in the store at `state_url`
+    /// between live runs (see [`open_state_store`](crate::state::open_state_store)).
+    ///
+    /// They are pulled into their configured paths before a live run and
+    /// pushed back when it ends.
+    pub fn with_state_url(mut self, state_url: Option<String>) -> Self {
+        self.state_url = state_url;
+        self
+    }
+
+    /// Sets the wall-clock limit for a run.
+    ///
+    /// Once exceeded, no new repositories are started, in-flight work finishes,
+    /// and the remaining work is written to the checkpoint.
+    pub fn with_max_runtime(mut self, max_runtime: Option<Duration>) -> Self {
+        self.max_runtime = max_runtime;
+        self
+    }
+
+    /// Sets where the checkpoint is written when the runtime limit is hit.
+    pub fn with_checkpoint_path(mut self, checkpoint_path: PathBuf) -> Self {
+        self.checkpoint_path = checkpoint_path;
+        self
+    }
+
+    /// Sets the run lock file, or disables locking with `None`.
+    ///
+    /// Defaults to `run.lock` next to the migrations folder.
+    pub fn with_lock_path(mut self, lock_path: Option<PathBuf>) -> Self {
+        self.lock_path = lock_path;
+        self
+    }
+
+    /// Sets the age after which a lock left behind by a crashed run is broken.
+    pub fn with_lock_stale_after(mut self, stale_after: Duration) -> Self {
+        self.lock_stale_after = stale_after;
+        self
+    }
+
+    /// Sets the idempotency ledger file, or disables it with `None`.
+    ///
+    /// Defaults to `ledger.json` next to the migrations folder.
+    pub fn with_ledger_path(mut self, ledger_path: Option<PathBuf>) -> Self {
+        self.ledger_path = ledger_path;
+        self
+    }
+
+    /// Sets the file keeping the stage of staged rollouts, or disables it with `None`.
+    ///
+    /// Without it, staged rollouts stay at their first stage.
+    /// Defaults to `rollout-state.json` next to the migrations folder.
+    pub fn with_rollout_state_path(mut self, rollout_state_path: Option<PathBuf>) -> Self {
+        self.rollout_state_path = rollout_state_path;
+        self
+    }
+
+    /// Sets the per-repository template data file (defaults to
+    /// `repo-data.toml` next to the migrations folder); `None` disables it.
+    pub fn with_repo_data_path(mut self, repo_data_path: Option<PathBuf>) -> Self {
+        self.repo_data_path = repo_data_path;
+        self
+    }
+
+    /// Sets the list of repositories processed first within each migration
+    /// (defaults to `priority.txt` next to the migrations folder); `None`
+    /// keeps discovery order.
+    pub fn with_priority_path(mut self, priority_path: Option<PathBuf>) -> Self {
+        self.priority_path = priority_path;
+        self
+    }
+
+    /// Checks the repositories listed in `repo_list_path` (`repos.txt` or
+    /// `repos.toml`) instead of discovering them with code search; `None`
+    /// (the default) searches.
+    pub fn with_repo_list_path(mut self, repo_list_path: Option<PathBuf>) -> Self {
+        self.repo_list_path = repo_list_path;
+        self
+    }
+
+    /// Sets the estimated cost in US dollars of one LLM agent run, which dry
+    /// runs multiply by the agent runs a live run would start.
+    ///
+    /// Unset by default, in which case only the number of runs is estimated.
+    pub fn with_llm_cost_per_run(mut self, llm_cost_per_run: Option<f64>) -> Self {
+        self.llm_cost_per_run = llm_cost_per_run;
+        self
+    }
+
+    /// Processes only a seeded random sample of each migration's discovered
+    /// repositories, for spot-checking a campaign before the full rollout;
+    /// `None` processes them all.
+    pub fn with_sample(mut self, sample: Option<Sample>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    /// Sets the token that cancels the run; no new migration or repository
+    /// is started once it is cancelled.
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    /// Sets the ETag cache file for conditional requests, or disables caching with `None`.
+    ///
+    /// Defaults to `etag-cache.json` next to the migrations folder.
+    pub fn with_etag_cache_path(mut self, etag_cache_path: Option<PathBuf>) -> Self {
+        self.etag_cache_path = etag_cache_path;
+        self
+    }
+
+    /// Enables or disables auto-PR generation.
+    pub fn with_auto_pr(mut self, auto_pr: bool) -> Self {
+        self.auto_pr = auto_pr;
+        self
+    }
+
+    /// Sets the maximum number of repositories processed concurrently.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Runs the named campaign from `notifier.toml`.
+    ///
+    /// Only the campaign's migrations are run (further narrowed by
+    /// [`with_only_migrations`](Self::with_only_migrations)), and its shared
+    /// settings override the corresponding settings here.
+    pub fn with_campaign(mut self, campaign: Option<String>) -> Self {
+        self.campaign = campaign;
+        self
+    }
+
+    /// Sets the time allowed to establish a connection to the GitHub API.
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Sets the time allowed for reading or writing a GitHub API request.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Sets how many times a GitHub API request is retried after a connect
+    /// error, reset connection, 429 or server error.
+    ///
+    /// Only idempotent requests (`GET`, `PUT`, `DELETE`, ...) are retried,
+    /// waiting 0.5s, 1s, 2s and so on up to 8s in between; a `POST` or
+    /// `PATCH` could otherwise create an issue or PR twice. `0` disables
+    /// retries. Only applies to the client built by the runner,
+    /// not one passed to [`RunnerBuilder::octocrab`](super::RunnerBuilder::octocrab).
+    pub fn with_http_retries(mut self, http_retries: usize) -> Self {
+        self.http_retries = http_retries;
+        self
+    }
+
+    /// Sets the proxy and CA bundle used for GitHub API requests and git.
+    ///
+    /// Defaults to [`ProxyConfig::from_env`]. Only applies to the client built
+    /// by the runner, not one passed to
+    /// [`RunnerBuilder::octocrab`](super::RunnerBuilder::octocrab).
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Closes our open issues and PRs in repositories whose target file
+    /// already contains `new_string`.
+    pub fn with_auto_close(mut self, auto_close: bool) -> Self {
+        self.auto_close = auto_close;
+        self
+    }
+
+    /// Opens auto-fix PRs only in repositories whose risk score is at most
+    /// `max_risk` (0-100); riskier repositories get the issue only.
+    ///
+    /// `None` (the default) opens PRs regardless of risk. See
+    /// [`RiskFactors::score`](crate::risk::RiskFactors::score).
+    pub fn with_max_auto_pr_risk(mut self, max_risk: Option<u32>) -> Self {
+        self.max_auto_pr_risk = max_risk;
+        self
+    }
+
+    /// Sets how many repositories a migration may discover before a live run
+    /// asks for confirmation (defaults to 500); `None` never asks.
+    pub fn with_large_campaign_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.large_campaign_threshold = threshold;
+        self
+    }
+
+    /// Confirms migrations above the large-campaign threshold up front,
+    /// without asking.
+    pub fn with_confirm_large_campaigns(mut self, confirm: bool) -> Self {
+        self.confirm_large_campaigns = confirm;
+        self
+    }
+
+    /// Files issues even when an open issue with the same title exists, or
+    /// the ledger records the repository as already notified. A duplicate
+    /// policy other than `skip` still applies to the open issue.
+    pub fn with_force_issues(mut self, force_issues: bool) -> Self {
+        self.force_issues = force_issues;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_without_the_token() {
+        let config = RunnerConfig::new(
+            PathBuf::from("config/migrations"),
+            "secret".to_string(),
+            true,
+            4,
+            false,
+        )
+        .with_only_repos(vec!["user/*".to_string()])
+        .with_retry_delay(Duration::from_secs(5))
+        .with_ledger_path(None)
+        .with_search_cache_dir(Some(PathBuf::from("cache")));
+
+        let toml = toml::to_string(&config).unwrap();
+        assert!(!toml.contains("secret"));
+        assert!(toml.contains("search-cache-dir = \"cache\""));
+
+        let parsed: RunnerConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.token(), "");
+        assert_eq!(parsed.concurrency(), 4);
+        assert_eq!(parsed.retry_delay(), Duration::from_secs(5));
+        assert_eq!(parsed.ledger_path(), None);
+        assert_eq!(
+            parsed.search_cache_path(),
+            Some(PathBuf::from("cache/search-cache.json"))
+        );
+        assert_eq!(toml::to_string(&parsed).unwrap(), toml);
+    }
+
+    #[test]
+    fn report_only_implies_dry_run() {
+        let config = RunnerConfig::new(
+            PathBuf::from("config/migrations"),
+            String::new(),
+            false,
+            1,
+            false,
+        )
+        .with_report_only(true);
+
+        assert!(config.report_only());
+        assert!(config.dry_run());
+    }
+}
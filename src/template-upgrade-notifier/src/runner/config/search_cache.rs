@@ -0,0 +1,102 @@
+//! Code search cache settings of a [`RunnerConfig`].
+
+use super::RunnerConfig;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Default age after which cached code search results are searched again.
+const DEFAULT_SEARCH_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// File the code search cache is kept in, within its directory.
+const SEARCH_CACHE_FILE: &str = "search-cache.json";
+
+/// Where and how code search results are cached, flattened into the
+/// [`RunnerConfig`] document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) struct SearchCacheSettings {
+    /// Directory code search results are cached in, if enabled.
+    #[serde(default)]
+    search_cache_dir: Option<PathBuf>,
+    /// Age after which cached code search results are searched again.
+    #[serde(default = "default_search_cache_ttl")]
+    search_cache_ttl: Duration,
+    /// Whether every code search runs again, replacing its cached results.
+    #[serde(default)]
+    refresh_search_cache: bool,
+    /// Whether the code search cache is emptied before the run.
+    #[serde(default)]
+    clear_search_cache: bool,
+}
+
+impl Default for SearchCacheSettings {
+    fn default() -> Self {
+        Self {
+            search_cache_dir: None,
+            search_cache_ttl: DEFAULT_SEARCH_CACHE_TTL,
+            refresh_search_cache: false,
+            clear_search_cache: false,
+        }
+    }
+}
+
+impl RunnerConfig {
+    /// Caches code search results in `search_cache_dir`, or disables the
+    /// cache with `None`.
+    ///
+    /// Disabled by default. Lets a dry run and the live run after it share
+    /// one set of searches.
+    pub fn with_search_cache_dir(mut self, search_cache_dir: Option<PathBuf>) -> Self {
+        self.search_cache.search_cache_dir = search_cache_dir;
+        self
+    }
+
+    /// Sets the age after which cached code search results are searched again.
+    ///
+    /// Defaults to one hour.
+    pub fn with_search_cache_ttl(mut self, search_cache_ttl: Duration) -> Self {
+        self.search_cache.search_cache_ttl = search_cache_ttl;
+        self
+    }
+
+    /// Runs every code search again, replacing the cached results, when `true`.
+    pub fn with_refresh_search_cache(mut self, refresh_search_cache: bool) -> Self {
+        self.search_cache.refresh_search_cache = refresh_search_cache;
+        self
+    }
+
+    /// Empties the code search cache before the run when `true`.
+    pub fn with_clear_search_cache(mut self, clear_search_cache: bool) -> Self {
+        self.search_cache.clear_search_cache = clear_search_cache;
+        self
+    }
+
+    /// Returns the code search cache file, if caching is enabled.
+    pub fn search_cache_path(&self) -> Option<PathBuf> {
+        self.search_cache
+            .search_cache_dir
+            .as_ref()
+            .map(|dir| dir.join(SEARCH_CACHE_FILE))
+    }
+
+    /// Returns the age after which cached code search results are searched again.
+    pub fn search_cache_ttl(&self) -> Duration {
+        self.search_cache.search_cache_ttl
+    }
+
+    /// Returns whether every code search runs again, replacing its cached results.
+    pub fn refresh_search_cache(&self) -> bool {
+        self.search_cache.refresh_search_cache
+    }
+
+    /// Returns whether the code search cache is emptied before the run.
+    pub fn clear_search_cache(&self) -> bool {
+        self.search_cache.clear_search_cache
+    }
+}
+
+/// Returns the default age after which cached code search results are searched again.
+fn default_search_cache_ttl() -> Duration {
+    DEFAULT_SEARCH_CACHE_TTL
+}
@@ -0,0 +1,70 @@
+//! Confirmation of migrations that discover unusually many repositories.
+
+use super::Runner;
+use crate::config::Migration;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Asks whether to notify `count` repositories for a migration, given its id
+/// and the threshold that was exceeded.
+pub(super) type LargeCampaignPrompt = Arc<dyn Fn(&str, usize, usize) -> bool + Send + Sync>;
+
+impl Runner {
+    /// Registers the prompt asked before notifying more repositories than the
+    /// large-campaign threshold in a live run.
+    ///
+    /// It receives the migration id, the number of repositories and the
+    /// threshold, and returns whether to go ahead. It runs on a blocking
+    /// thread, so it may wait for input. Without a prompt, such migrations are
+    /// refused unless confirmed up front.
+    pub fn with_large_campaign_prompt(
+        mut self,
+        prompt: impl Fn(&str, usize, usize) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.large_campaign_prompt = Some(Arc::new(prompt));
+        self
+    }
+
+    /// Returns true if `count` discovered repositories may be notified for
+    /// `migration`.
+    ///
+    /// Migrations running live above the large-campaign threshold need
+    /// [`RunnerConfig::with_confirm_large_campaigns`](super::RunnerConfig::with_confirm_large_campaigns)
+    /// or a `yes` from the registered prompt; otherwise the migration is refused
+    /// and recorded in the summary, failing the run.
+    pub(super) async fn confirm_large_campaign(&self, migration: &Migration, count: usize) -> bool {
+        let Some(threshold) = self.config.large_campaign_threshold() else {
+            return true;
+        };
+        if self.dry_run_level(migration).is_some() || count <= threshold {
+            return true;
+        }
+        if self.config.confirm_large_campaigns() {
+            info!(migration_id = %migration.id, count, threshold, "Large campaign confirmed up front");
+            return true;
+        }
+        let confirmed = match self.large_campaign_prompt.clone() {
+            Some(prompt) => {
+                let migration_id = migration.id.to_string();
+                tokio::task::spawn_blocking(move || prompt(&migration_id, count, threshold))
+                    .await
+                    .unwrap_or(false)
+            }
+            None => false,
+        };
+        if !confirmed {
+            error!(
+                migration_id = %migration.id,
+                count,
+                threshold,
+                "Refusing large campaign without confirmation (pass --yes-large)"
+            );
+            self.progress.update(|summary| {
+                summary
+                    .unconfirmed_migrations
+                    .push(migration.id.to_string());
+            });
+        }
+        confirmed
+    }
+}
@@ -0,0 +1,111 @@
+//! Cooldown between notifications of the same repository.
+
+use super::Runner;
+use crate::discovery::DiscoveredRepository;
+use crate::issues::last_notified_at;
+use crate::ledger::LedgerEntry;
+use chrono::{DateTime, TimeDelta, Utc};
+use tracing::warn;
+
+impl Runner {
+    /// Returns why `repository` should not be notified yet, if any migration
+    /// notified it within `min-days-between-notifications`.
+    ///
+    /// With a ledger, its timestamps decide without any API call, and
+    /// notifications made earlier in the same run count too. Without one, our
+    /// own recent issues in the repository are searched instead; a failed
+    /// search is logged and does not hold the repository back. Forced issues,
+    /// and open issues `refreshing` under the duplicate policy, ignore the
+    /// cooldown.
+    pub(super) async fn cooldown_skip_reason(
+        &self,
+        repository: &DiscoveredRepository,
+        refreshing: bool,
+    ) -> Option<String> {
+        let days = self.notifier.min_days_between_notifications?;
+        if days == 0 || self.config.force_issues() || refreshing {
+            return None;
+        }
+        let now = self.clock.now();
+        let cutoff = now - TimeDelta::days(i64::from(days));
+
+        let last = if self.config.ledger_path().is_some() {
+            last_recorded(self.ledger().entries(), &repository.full_name)
+        } else {
+            match last_notified_at(&self.octocrab, repository, cutoff).await {
+                Ok(last) => last,
+                Err(e) => {
+                    warn!(
+                        repo = %repository.full_name,
+                        error = %e,
+                        "Failed to look up recent notifications"
+                    );
+                    None
+                }
+            }
+        };
+        last.filter(|at| *at > cutoff)
+            .map(|at| cooldown_reason(now, at, days))
+    }
+}
+
+/// Returns the newest timestamp recorded for `repository`, by any migration.
+fn last_recorded(entries: &[LedgerEntry], repository: &str) -> Option<DateTime<Utc>> {
+    entries
+        .iter()
+        .filter(|entry| entry.repository == repository)
+        .filter_map(|entry| DateTime::parse_from_rfc3339(&entry.recorded_at).ok())
+        .map(|at| at.with_timezone(&Utc))
+        .max()
+}
+
+/// Formats the skip reason for a repository notified at `last`.
+fn cooldown_reason(now: DateTime<Utc>, last: DateTime<Utc>, days: u32) -> String {
+    let ago = now.signed_duration_since(last).num_days();
+    format!("notified {ago} days ago, within the {days}-day cooldown")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(migration_id: &str, repository: &str, recorded_at: &str) -> LedgerEntry {
+        LedgerEntry {
+            migration_id: migration_id.to_string(),
+            repository: repository.to_string(),
+            issue: Some(1),
+            pr: None,
+            dispatched: false,
+            recorded_at: recorded_at.to_string(),
+            declined: false,
+            not_planned_version: None,
+        }
+    }
+
+    #[test]
+    fn finds_newest_entry_across_migrations() {
+        let entries = [
+            entry("a", "user/repo", "2024-01-01T00:00:00+00:00"),
+            entry("b", "user/repo", "2024-03-01T00:00:00+00:00"),
+            entry("c", "user/other", "2024-05-01T00:00:00+00:00"),
+        ];
+
+        let last = last_recorded(&entries, "user/repo").unwrap();
+
+        assert_eq!(last.to_rfc3339(), "2024-03-01T00:00:00+00:00");
+        assert!(last_recorded(&entries, "user/none").is_none());
+    }
+
+    #[test]
+    fn formats_reason() {
+        let last = DateTime::parse_from_rfc3339("2024-03-01T00:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let now = last + TimeDelta::days(12);
+
+        assert_eq!(
+            cooldown_reason(now, last, 30),
+            "notified 12 days ago, within the 30-day cooldown"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Batched duplicate-issue lookups ahead of processing a migration.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::issues::{find_duplicate_issue, prefetch_duplicate_issues, DuplicatePolicy};
+use crate::templates::generate_issue_title;
+use tracing::{info, warn};
+
+impl Runner {
+    /// Answers the duplicate-issue lookup of every repository with batched
+    /// GraphQL queries, so processing them costs no search request each.
+    ///
+    /// Forced issues under the `skip` policy need no lookup. A failed batch is logged; the
+    /// repositories it left unanswered are searched one by one as before.
+    pub(super) async fn prefetch_duplicates(
+        &self,
+        migration: &Migration,
+        repositories: &[DiscoveredRepository],
+    ) {
+        let forced_skip =
+            self.config.force_issues() && migration.duplicate_policy == DuplicatePolicy::Skip;
+        if forced_skip || repositories.is_empty() {
+            return;
+        }
+        let Ok(title) = generate_issue_title(migration) else {
+            return;
+        };
+        match prefetch_duplicate_issues(&self.octocrab, repositories, &title).await {
+            Ok(answered) => info!(
+                migration_id = %migration.id,
+                answered,
+                "Prefetched duplicate issue lookups"
+            ),
+            Err(e) => warn!(
+                migration_id = %migration.id,
+                error = %e,
+                "Batched duplicate lookup failed, falling back to per-repository searches"
+            ),
+        }
+    }
+
+    /// Returns true if `migration`'s duplicate policy will comment on, update
+    /// or recreate an issue still open in `repository`.
+    ///
+    /// Such repositories bypass the ledger and cooldown skips, so the policy
+    /// takes effect on repositories notified before. A failed lookup is
+    /// logged and counts as no open issue.
+    pub(super) async fn refreshes_duplicate(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+    ) -> bool {
+        if migration.duplicate_policy == DuplicatePolicy::Skip {
+            return false;
+        }
+        match find_duplicate_issue(&self.octocrab, repository, migration).await {
+            Ok(existing) => existing.is_some(),
+            Err(e) => {
+                warn!(repo = %repository.full_name, error = %e, "Failed to look up an open issue");
+                false
+            }
+        }
+    }
+}
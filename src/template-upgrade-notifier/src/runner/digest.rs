@@ -0,0 +1,28 @@
+//! Emailing the run summary.
+
+use super::Runner;
+use crate::email::send_email_digest;
+use crate::summary::RunSummary;
+use tracing::{info, warn};
+
+impl Runner {
+    /// Emails the run summary if an `[email]` section is configured.
+    ///
+    /// Delivery failures are logged; they never fail an otherwise completed run.
+    pub(super) async fn send_digest(&self, summary: &RunSummary) {
+        let Some(email) = &self.notifier.email else {
+            return;
+        };
+        if summary.dry_run && !email.send_on_dry_run {
+            return;
+        }
+        let mut email = email.clone();
+        if let Some(campaign) = &self.campaign {
+            email.subject = format!("{}: {}", email.subject, campaign.title());
+        }
+        match send_email_digest(&email, summary).await {
+            Ok(()) => info!(recipients = email.to.len(), "Sent email digest"),
+            Err(e) => warn!(error = %e, "Failed to send email digest"),
+        }
+    }
+}
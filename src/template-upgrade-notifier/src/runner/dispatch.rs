@@ -0,0 +1,71 @@
+//! Sending `repository_dispatch` events to outdated repositories.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::dispatch::{send_dispatch, DispatchConfig, DispatchMode};
+use crate::summary::{ProcessingResult, StageTimings};
+use tracing::{error, warn};
+
+impl Runner {
+    /// Sends the migration's `repository_dispatch` event in place of the
+    /// issue, recording it in the ledger so the repository is not sent it again.
+    pub(super) async fn dispatch_instead(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        config: &DispatchConfig,
+    ) -> ProcessingResult {
+        if let Err(e) = send_dispatch(&self.octocrab, repository, migration, config, None).await {
+            error!(
+                repo = %repository.full_name,
+                error = %e,
+                "Failed to send repository dispatch event"
+            );
+            return self.failure(repository, migration, &e.to_string(), e.kind());
+        }
+        self.record_dispatch(repository, migration);
+        self.publish_check(repository, migration, None).await;
+        ProcessingResult::Dispatched {
+            migration_id: migration.id.to_string(),
+            repository: repository.full_name.to_string(),
+            event_type: config.event_type.clone(),
+            timings: StageTimings::default(),
+        }
+    }
+
+    /// Sends the migration's `repository_dispatch` event after its issue was
+    /// created, linking `issue_url` in the payload.
+    ///
+    /// Does nothing unless the migration dispatches alongside issues.
+    /// Failures are logged; the repository's issue and PR are unaffected.
+    pub(super) async fn dispatch_alongside(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        issue_url: &str,
+    ) {
+        let Some(config) = migration
+            .dispatch
+            .as_ref()
+            .filter(|config| config.mode == DispatchMode::Alongside)
+        else {
+            return;
+        };
+        if let Err(e) = send_dispatch(
+            &self.octocrab,
+            repository,
+            migration,
+            config,
+            Some(issue_url),
+        )
+        .await
+        {
+            warn!(
+                repo = %repository.full_name,
+                error = %e,
+                "Failed to send repository dispatch event"
+            );
+        }
+    }
+}
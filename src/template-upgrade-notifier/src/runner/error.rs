@@ -7,6 +7,81 @@ pub enum RunnerError {
     #[error(transparent)]
     Config(#[from] crate::config::ConfigError),
 
+    /// Watch-mode schedule errors.
+    #[error(transparent)]
+    Schedule(#[from] crate::schedule::ScheduleError),
+
+    /// Unknown or invalid campaign.
+    #[error(transparent)]
+    Campaign(#[from] crate::campaign::CampaignError),
+
+    /// Invalid migration or repository selection patterns.
+    #[error(transparent)]
+    Selection(#[from] crate::selection::SelectionError),
+
+    /// Fleet status collection errors.
+    #[error(transparent)]
+    Status(#[from] crate::status::StatusError),
+
+    /// A re-run named a migration that is not in the migrations directory.
+    #[error("unknown migration: {id}")]
+    UnknownMigration {
+        /// The requested migration identifier.
+        id: String,
+    },
+
+    /// [`Runner::report`](super::Runner::report) was called without a
+    /// report-only configuration.
+    #[error("compliance reports require a report-only run")]
+    NotReportOnly,
+
+    /// A live run was started without an `allowed-owners` list in `notifier.toml`.
+    #[error("live runs require a non-empty allowed-owners list in notifier.toml")]
+    AllowedOwnersMissing,
+
+    /// Another live run holds the run lock.
+    #[error("another run is in progress (lock file {path}: {holder})")]
+    Locked {
+        /// Path of the lock file.
+        path: String,
+        /// Contents of the lock file, identifying the holder.
+        holder: String,
+    },
+
+    /// The run lock file could not be created or removed.
+    #[error("failed to take run lock {path}: {source}")]
+    Lock {
+        /// Path of the lock file.
+        path: String,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The idempotency ledger could not be read.
+    #[error(transparent)]
+    Ledger(#[from] crate::ledger::LedgerError),
+
+    /// Stored state could not be pulled, or the state store could not be opened.
+    #[error(transparent)]
+    State(#[from] crate::state::StateError),
+
+    /// The per-repository template data could not be loaded.
+    #[error(transparent)]
+    RepoData(#[from] crate::repo_data::RepoDataError),
+
+    /// The static repository list could not be loaded.
+    #[error(transparent)]
+    RepoList(#[from] crate::discovery::RepoListError),
+
+    /// Invalid proxy or CA bundle settings.
+    #[error(transparent)]
+    Proxy(#[from] crate::proxy::ProxyError),
+
+    /// A GitLab or Azure DevOps client could not be built.
+    #[error(transparent)]
+    Forge(#[from] crate::forge::ForgeError),
+
     /// GitHub API client initialization errors.
     #[error(transparent)]
     Octocrab(#[from] octocrab::Error),
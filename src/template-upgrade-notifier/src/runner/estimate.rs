@@ -0,0 +1,46 @@
+//! Dry-run estimates of what a live run would spend.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::rate_limit::check_core_rate_limit;
+use crate::summary::QuotaEstimate;
+use tracing::{debug, info};
+
+impl Runner {
+    /// Records the API quota and LLM runs a live run would use for the
+    /// migration's `repositories` in the run's progress.
+    pub(super) fn record_estimate(&self, migration: &Migration, repositories: usize) {
+        let estimate = QuotaEstimate::for_migration(
+            migration,
+            repositories,
+            self.config.auto_pr(),
+            self.config.llm_cost_per_run(),
+        );
+        info!(
+            repositories,
+            search = estimate.search_requests,
+            graphql = estimate.graphql_requests,
+            core = estimate.core_requests,
+            llm_runs = estimate.llm_runs,
+            "Estimated live run usage"
+        );
+        self.progress
+            .update(|summary| summary.estimates.push(estimate));
+    }
+
+    /// Records how many core requests are left in the current rate limit
+    /// window, so a dry run's estimate can be checked against it.
+    ///
+    /// Does nothing if nothing was estimated; a failed lookup is logged.
+    pub(super) async fn record_core_quota(&self) {
+        if self.progress.read(|summary| summary.estimates.is_empty()) {
+            return;
+        }
+        match check_core_rate_limit(&self.octocrab).await {
+            Ok(info) => self
+                .progress
+                .update(|summary| summary.core_quota_remaining = Some(info.remaining)),
+            Err(e) => debug!(error = %e, "Failed to check the core rate limit"),
+        }
+    }
+}
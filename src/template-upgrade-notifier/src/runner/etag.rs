@@ -0,0 +1,35 @@
+//! Loading and saving the ETag cache around a run.
+
+use super::Runner;
+use crate::http_cache::{disable_etag_cache, load_etag_cache, reset_etag_cache, save_etag_cache};
+use tracing::{debug, warn};
+
+impl Runner {
+    /// Enables conditional requests, loading the responses cached by earlier runs.
+    ///
+    /// An unreadable cache is only a lost optimisation: it is logged and the
+    /// run starts with an empty cache.
+    pub(super) fn load_etag_cache(&self) {
+        let Some(path) = self.config.etag_cache_path() else {
+            disable_etag_cache();
+            return;
+        };
+        match load_etag_cache(path) {
+            Ok(entries) => debug!(entries, path = %path.display(), "Loaded ETag cache"),
+            Err(e) => {
+                warn!(error = %e, "Failed to load ETag cache, starting empty");
+                reset_etag_cache();
+            }
+        }
+    }
+
+    /// Saves the responses requested during the run for the next one.
+    pub(super) fn save_etag_cache(&self) {
+        let Some(path) = self.config.etag_cache_path() else {
+            return;
+        };
+        if let Err(e) = save_etag_cache(path) {
+            warn!(error = %e, "Failed to save ETag cache");
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Leaving bot-owned, mirror, excluded-owner, likely false-positive and
+//! opted-out repositories out of a run.
+
+use super::{Runner, RunnerError};
+use crate::config::Migration;
+use crate::discovery::{fetch_file_sizes, find_opt_outs, DiscoveredRepository, DiscoveryError};
+use crate::ids::RepoSlug;
+use crate::selection::PatternSet;
+use tracing::{debug, info, warn};
+
+impl Runner {
+    /// Removes repositories matched by the `[exclude]` settings.
+    pub(super) fn apply_exclusions(
+        &self,
+        migration: &Migration,
+        repositories: &mut Vec<DiscoveredRepository>,
+    ) {
+        let before = repositories.len();
+        repositories.retain(|repo| match self.exclusion_reason(repo) {
+            Some(reason) => {
+                debug!(repo = %repo.full_name, reason, "Excluding repository");
+                false
+            }
+            None => true,
+        });
+        let excluded = before - repositories.len();
+        if excluded > 0 {
+            info!(migration_id = %migration.id, excluded, "Excluded bot-owned, mirror and excluded-owner repositories");
+        }
+    }
+
+    /// Removes likely false positives per the `[false-positives]` settings,
+    /// after dropping each repository's matches in vendored directories.
+    ///
+    /// With a `max-file-size`, the size of a newly promoted match is looked
+    /// up before it is compared; a failed lookup is logged and lets it pass.
+    pub(super) async fn apply_false_positive_filter(
+        &self,
+        migration: &Migration,
+        repositories: &mut Vec<DiscoveredRepository>,
+    ) {
+        let heuristics = &self.notifier.false_positives;
+        let before = repositories.len();
+        let mut promoted = Vec::new();
+        repositories.retain_mut(|repo| {
+            let path = repo.file_path.clone();
+            let kept = heuristics.strip_vendored(repo);
+            if !kept {
+                debug!(
+                    repo = %repo.full_name,
+                    reason = "only matched in vendored directories",
+                    "Dropping likely false positive"
+                );
+            } else if repo.file_path != path {
+                promoted.push(repo.full_name.clone());
+            }
+            kept
+        });
+        if heuristics.max_file_size.is_some() && !promoted.is_empty() {
+            self.refresh_file_sizes(repositories, &promoted).await;
+        }
+        repositories.retain(|repo| match heuristics.reason(repo) {
+            Some(reason) => {
+                debug!(repo = %repo.full_name, reason, "Dropping likely false positive");
+                false
+            }
+            None => true,
+        });
+        let dropped = before - repositories.len();
+        if dropped > 0 {
+            info!(migration_id = %migration.id, dropped, "Dropped likely false positives");
+        }
+    }
+
+    /// Looks up the matched file size of the `promoted` repositories again.
+    async fn refresh_file_sizes(
+        &self,
+        repositories: &mut [DiscoveredRepository],
+        promoted: &[RepoSlug],
+    ) {
+        let mut stale: Vec<&mut DiscoveredRepository> = repositories
+            .iter_mut()
+            .filter(|repo| promoted.contains(&repo.full_name))
+            .collect();
+        let lookup: Vec<&DiscoveredRepository> = stale.iter().map(|repo| &**repo).collect();
+        match fetch_file_sizes(&self.octocrab, &lookup).await {
+            Ok(sizes) => {
+                for (repo, size) in stale.iter_mut().zip(sizes) {
+                    repo.file_size = size;
+                }
+            }
+            Err(e) => warn!(error = %e, "Failed to look up sizes of promoted matches"),
+        }
+    }
+
+    /// Keeps only repositories matched by the migration's `include-repos`
+    /// and not by its `exclude-repos` globs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Selection`] if a glob is invalid.
+    pub(super) fn apply_repo_lists(
+        &self,
+        migration: &Migration,
+        repositories: &mut Vec<DiscoveredRepository>,
+    ) -> Result<(), RunnerError> {
+        if migration.include_repos.is_empty() && migration.exclude_repos.is_empty() {
+            return Ok(());
+        }
+        let include = PatternSet::new(&migration.include_repos)?;
+        let exclude = PatternSet::new(&migration.exclude_repos)?;
+        let before = repositories.len();
+        repositories.retain(|repo| {
+            let kept = include.matches(&repo.full_name) && !exclude.matches_any(&repo.full_name);
+            if !kept {
+                debug!(repo = %repo.full_name, "Not in the migration's repository lists");
+            }
+            kept
+        });
+        info!(
+            migration_id = %migration.id,
+            kept = repositories.len(),
+            excluded = before - repositories.len(),
+            "Applied include-repos and exclude-repos"
+        );
+        Ok(())
+    }
+
+    /// Marks repositories that opted out through a topic or a label on one
+    /// of our earlier issues (`[opt-out]`); they are skipped when processed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DiscoveryError`] if the lookup fails, in which case none of
+    /// the repositories may be notified.
+    pub(super) async fn apply_opt_outs(
+        &self,
+        migration: &Migration,
+        repositories: &mut [DiscoveredRepository],
+    ) -> Result<(), DiscoveryError> {
+        find_opt_outs(&self.octocrab, repositories, &self.notifier.opt_out).await?;
+        let opted_out = repositories
+            .iter()
+            .filter(|repo| repo.opted_out.is_some())
+            .count();
+        if opted_out > 0 {
+            info!(migration_id = %migration.id, opted_out, "Found opted-out repositories");
+        }
+        Ok(())
+    }
+
+    /// Returns why `repository` cannot be written to, if it is archived or
+    /// disabled and `[exclude] archived` is on.
+    pub(super) fn read_only_skip_reason(
+        &self,
+        repository: &DiscoveredRepository,
+    ) -> Option<&'static str> {
+        if !self.notifier.exclude.archived {
+            None
+        } else if repository.disabled {
+            Some("repository disabled")
+        } else if repository.archived {
+            Some("repository archived")
+        } else {
+            None
+        }
+    }
+
+    /// Returns why `repository` is excluded, if it is.
+    fn exclusion_reason(&self, repository: &DiscoveredRepository) -> Option<&'static str> {
+        let exclude = &self.notifier.exclude;
+        if exclude.bots && repository.owner_is_bot {
+            Some("owned by a bot account")
+        } else if exclude.mirrors && repository.is_mirror {
+            Some("mirror repository")
+        } else if self.owner_exclusions.matches_any(&repository.owner) {
+            Some("owner excluded")
+        } else {
+            None
+        }
+    }
+}
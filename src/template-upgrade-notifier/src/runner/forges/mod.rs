@@ -0,0 +1,73 @@
+//! Notifying repositories on GitLab and Azure DevOps.
+
+#[cfg(any(feature = "gitlab", feature = "azure-devops"))]
+mod notify;
+
+use super::{Deadline, Runner, RunnerError};
+use crate::config::{Migration, NotifierConfig};
+use crate::forge::ForgeError;
+
+/// Clients of the code hosts configured besides GitHub in `notifier.toml`.
+pub(super) struct OtherForges {
+    #[cfg(feature = "gitlab")]
+    gitlab: Option<crate::gitlab::GitLabClient>,
+    #[cfg(feature = "azure-devops")]
+    azure_devops: Option<crate::azure_devops::AzureDevOpsClient>,
+}
+
+impl OtherForges {
+    /// Creates a client for each host with a section in `notifier`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Forge`] if a host's token is missing, or its
+    /// section is present but the crate was built without its feature.
+    pub(super) fn from_config(notifier: &NotifierConfig) -> Result<Self, RunnerError> {
+        #[cfg(not(feature = "gitlab"))]
+        if notifier.gitlab.is_some() {
+            return Err(ForgeError::from(crate::gitlab::GitLabError::Disabled).into());
+        }
+        #[cfg(not(feature = "azure-devops"))]
+        if notifier.azure_devops.is_some() {
+            return Err(ForgeError::from(crate::azure_devops::AzureDevOpsError::Disabled).into());
+        }
+        Ok(Self {
+            #[cfg(feature = "gitlab")]
+            gitlab: notifier
+                .gitlab
+                .clone()
+                .map(crate::gitlab::GitLabClient::new)
+                .transpose()
+                .map_err(ForgeError::from)?,
+            #[cfg(feature = "azure-devops")]
+            azure_devops: notifier
+                .azure_devops
+                .clone()
+                .map(crate::azure_devops::AzureDevOpsClient::new)
+                .transpose()
+                .map_err(ForgeError::from)?,
+        })
+    }
+
+    /// Notifies the migration's outdated repositories on every configured
+    /// host, starting no new repository once the run should stop.
+    #[cfg_attr(
+        not(any(feature = "gitlab", feature = "azure-devops")),
+        allow(unused_variables)
+    )]
+    pub(super) async fn notify(
+        &self,
+        runner: &Runner,
+        migration: &Migration,
+        deadline: Option<Deadline>,
+    ) {
+        #[cfg(feature = "gitlab")]
+        if let Some(client) = &self.gitlab {
+            runner.notify_forge(client, migration, deadline).await;
+        }
+        #[cfg(feature = "azure-devops")]
+        if let Some(client) = &self.azure_devops {
+            runner.notify_forge(client, migration, deadline).await;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Filing notification issues through a [`Forge`].
+
+use crate::config::Migration;
+use crate::forge::Forge;
+use crate::runner::{Deadline, Runner};
+use crate::summary::{ProcessingResult, StageTimings};
+use core::future;
+use futures::stream::{self as fstream, StreamExt};
+use tracing::{error, info};
+
+impl Runner {
+    /// Discovers the migration's outdated repositories on `forge` and files
+    /// the notification issue in each.
+    ///
+    /// The repository selection applies; dry runs and report-only runs only
+    /// count the repositories found. Merge requests need a pushed branch, so
+    /// none are opened. Like GitHub repositories, no new repository is started
+    /// once `deadline` has passed or the run was aborted or cancelled.
+    pub(super) async fn notify_forge<F: Forge>(
+        &self,
+        forge: &F,
+        migration: &Migration,
+        deadline: Option<Deadline>,
+    ) {
+        let mut repositories = match forge.discover(migration).await {
+            Ok(repositories) => repositories,
+            Err(e) => {
+                error!(
+                    forge = forge.name(),
+                    migration_id = %migration.id,
+                    error = %e,
+                    "Failed to discover repositories"
+                );
+                return;
+            }
+        };
+        if !self.repo_filter.is_empty() {
+            repositories.retain(|repo| self.repo_filter.matches(forge.full_name(repo)));
+        }
+        info!(
+            forge = forge.name(),
+            migration_id = %migration.id,
+            count = repositories.len(),
+            "Found repositories"
+        );
+        self.progress
+            .update(|summary| summary.repositories_discovered += repositories.len());
+        if self.config.report_only() || self.dry_run_level(migration).is_some() {
+            return;
+        }
+
+        fstream::iter(&repositories)
+            .take_while(|_| future::ready(!self.should_stop(deadline)))
+            .map(|repo| async move {
+                let result = self.notify_forge_repository(forge, repo, migration).await;
+                self.progress.record(&result);
+                self.check_abort_threshold();
+                self.stream_result(&result).await;
+            })
+            .buffer_unordered(self.config.concurrency())
+            .collect::<()>()
+            .await;
+    }
+
+    /// Files the notification issue in one repository on `forge`.
+    async fn notify_forge_repository<F: Forge>(
+        &self,
+        forge: &F,
+        repository: &F::Repository,
+        migration: &Migration,
+    ) -> ProcessingResult {
+        let full_name = forge.full_name(repository).to_string();
+        match forge
+            .create_issue(repository, migration, &self.renderer)
+            .await
+        {
+            Ok(issue) => ProcessingResult::Success {
+                migration_id: migration.id.to_string(),
+                repository: full_name,
+                issue,
+                pr: None,
+                timings: StageTimings::default(),
+            },
+            Err(e) => {
+                error!(
+                    forge = forge.name(),
+                    repo = %full_name,
+                    error = %e,
+                    "Failed to create issue"
+                );
+                ProcessingResult::Failed {
+                    migration_id: migration.id.to_string(),
+                    repository: full_name,
+                    error: e.to_string(),
+                    kind: e.kind(),
+                    timings: StageTimings::default(),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Consulting and updating the idempotency ledger during a run.
+
+use super::{Runner, RunnerError};
+use crate::config::{is_new_major, Migration, NotPlannedPolicy};
+use crate::discovery::DiscoveredRepository;
+use crate::ids::HtmlUrl;
+use crate::ledger::Ledger;
+use crate::status::declined_issues;
+use std::sync::{MutexGuard, PoisonError};
+use tracing::{debug, info, warn};
+
+impl Runner {
+    /// Loads the ledger for a live run, replacing whatever the previous run left.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Ledger`] if the ledger exists but cannot be read,
+    /// since running without it could notify repositories twice.
+    pub(super) fn load_ledger(&self) -> Result<(), RunnerError> {
+        let ledger = match self.config.ledger_path() {
+            Some(path) if !self.config.dry_run() => Ledger::load(path)?,
+            _ => Ledger::default(),
+        };
+        debug!(entries = ledger.entries().len(), "Loaded ledger");
+        *self.ledger() = ledger;
+        Ok(())
+    }
+
+    /// Writes the ledger back to disk.
+    ///
+    /// Failures are logged; the entries stay in memory and are written by the
+    /// next save.
+    pub(super) fn save_ledger(&self) {
+        let Some(path) = self.config.ledger_path() else {
+            return;
+        };
+        if self.config.dry_run() {
+            return;
+        }
+        if let Err(e) = self.ledger().save(path) {
+            warn!(error = %e, "Failed to write ledger");
+        }
+    }
+
+    /// Returns why `repository` needs no notification for `migration`, if it
+    /// was already notified, issues aren't being forced and no open issue is
+    /// `refreshing` under the duplicate policy.
+    ///
+    /// Repositories whose maintainers declined the upgrade, or closed its
+    /// issue as "not planned", are skipped even when issues are forced. Under
+    /// the default [`NotPlannedPolicy::Template`], an issue closed as not
+    /// planned also skips the template's later migrations until one targets
+    /// a new major version.
+    pub(super) fn ledger_skip_reason(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        refreshing: bool,
+    ) -> Option<String> {
+        let ledger = self.ledger();
+        let honor_not_planned = migration.not_planned != NotPlannedPolicy::Ignore;
+        let own = ledger
+            .get(&migration.id, &repository.full_name)
+            .filter(|entry| {
+                entry.declined
+                    || (honor_not_planned && entry.not_planned_version.is_some())
+                    || !(self.config.force_issues() || refreshing)
+            })
+            .map(|entry| entry.skip_reason());
+        if own.is_some() || migration.not_planned != NotPlannedPolicy::Template {
+            return own;
+        }
+        ledger
+            .entries()
+            .iter()
+            .filter(|entry| entry.repository == repository.full_name)
+            .filter(|entry| entry.migration_id.split('/').next() == Some(migration.template()))
+            .find(|entry| {
+                entry
+                    .not_planned_version
+                    .as_deref()
+                    .is_some_and(|rejected| !is_new_major(rejected, &migration.new_string))
+            })
+            .map(|entry| entry.skip_reason())
+    }
+
+    /// Returns `repository` annotated with the most recent notice the ledger
+    /// records for it, from any migration.
+    pub(super) fn with_previous_notice(
+        &self,
+        repository: &DiscoveredRepository,
+    ) -> DiscoveredRepository {
+        let previous = self
+            .ledger()
+            .latest_for_repository(&repository.full_name)
+            .map(|entry| entry.notice_url().and_then(|url| HtmlUrl::new(url).ok()));
+        DiscoveredRepository {
+            previously_notified: previous.is_some(),
+            previous_issue_url: previous.flatten(),
+            ..repository.clone()
+        }
+    }
+
+    /// Records a created upgrade issue.
+    pub(super) fn record_issue(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        number: u64,
+    ) {
+        if self.dry_run_level(migration).is_some() {
+            return;
+        }
+        let now = self.clock.now().to_rfc3339();
+        self.ledger()
+            .record_issue(&migration.id, &repository.full_name, number, &now);
+    }
+
+    /// Records a created or updated auto-fix PR.
+    pub(super) fn record_pr(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        number: u64,
+    ) {
+        if self.dry_run_level(migration).is_some() {
+            return;
+        }
+        let now = self.clock.now().to_rfc3339();
+        self.ledger()
+            .record_pr(&migration.id, &repository.full_name, number, &now);
+    }
+
+    /// Records a sent `repository_dispatch` event.
+    pub(super) fn record_dispatch(&self, repository: &DiscoveredRepository, migration: &Migration) {
+        if self.dry_run_level(migration).is_some() {
+            return;
+        }
+        let now = self.clock.now().to_rfc3339();
+        self.ledger()
+            .record_dispatch(&migration.id, &repository.full_name, &now);
+    }
+
+    /// Marks every declined issue of `migrations` as declined in the ledger,
+    /// and every issue closed as "not planned" with its migration's version,
+    /// so those repositories are skipped.
+    ///
+    /// Does nothing without a ledger.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Status`] if the issues cannot be listed, since
+    /// running without them could notify repositories that declined.
+    pub(super) async fn record_declines(
+        &self,
+        migrations: &[Migration],
+    ) -> Result<(), RunnerError> {
+        if self.config.ledger_path().is_none() {
+            return Ok(());
+        }
+        let now = self.clock.now().to_rfc3339();
+        let mut newly_declined = 0;
+        let mut newly_not_planned = 0;
+        for migration in migrations {
+            let declines = declined_issues(&self.octocrab, migration).await?;
+            let mut ledger = self.ledger();
+            for issue in &declines.declined {
+                if ledger.record_declined(&migration.id, &issue.repository, issue.number, &now) {
+                    newly_declined += 1;
+                }
+            }
+            for issue in &declines.not_planned {
+                if ledger.record_not_planned(
+                    &migration.id,
+                    &issue.repository,
+                    issue.number,
+                    &migration.new_string,
+                    &now,
+                ) {
+                    newly_not_planned += 1;
+                }
+            }
+        }
+        if newly_declined > 0 {
+            info!(
+                count = newly_declined,
+                "Recorded declined repositories in the ledger"
+            );
+        }
+        if newly_not_planned > 0 {
+            info!(
+                count = newly_not_planned,
+                "Recorded repositories that closed issues as not planned"
+            );
+        }
+        if newly_declined + newly_not_planned > 0 {
+            self.save_ledger();
+        }
+        Ok(())
+    }
+
+    pub(super) fn ledger(&self) -> MutexGuard<'_, Ledger> {
+        self.ledger.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,135 @@
+//! Lock file preventing overlapping live runs.
+
+use super::RunnerError;
+use chrono::{DateTime, Utc};
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Contents of the lock file, identifying the run that holds it.
+#[derive(Debug, Serialize, Deserialize)]
+struct LockInfo {
+    /// Process id of the holder.
+    pid: u32,
+    /// RFC 3339 timestamp of when the lock was taken.
+    acquired_at: String,
+}
+
+/// Holds the run lock; the lock file is removed when dropped.
+#[derive(Debug)]
+pub(crate) struct RunLock {
+    path: PathBuf,
+}
+
+impl RunLock {
+    /// Takes the lock at `path`, breaking it if its holder is older than `stale_after`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Locked`] if another run holds a fresh lock, or
+    /// [`RunnerError::Lock`] if the lock file cannot be created.
+    pub(crate) fn acquire(
+        path: &Path,
+        stale_after: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<Self, RunnerError> {
+        let lock_error = |source| RunnerError::Lock {
+            path: path.display().to_string(),
+            source,
+        };
+        match Self::create(path, now) {
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let holder = std::fs::read_to_string(path).map_err(lock_error)?;
+                if !is_stale(&holder, stale_after, now) {
+                    return Err(RunnerError::Locked {
+                        path: path.display().to_string(),
+                        holder: holder.trim().to_string(),
+                    });
+                }
+                warn!(path = %path.display(), holder = %holder.trim(), "Breaking stale run lock");
+                std::fs::remove_file(path).map_err(lock_error)?;
+                Self::create(path, now).map_err(lock_error)
+            }
+            result => result.map_err(lock_error),
+        }
+    }
+
+    /// Atomically creates the lock file, failing if it already exists.
+    fn create(path: &Path, now: DateTime<Utc>) -> std::io::Result<Self> {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let lock = Self {
+            path: path.to_path_buf(),
+        };
+        let info = LockInfo {
+            pid: std::process::id(),
+            acquired_at: now.to_rfc3339(),
+        };
+        file.write_all(serde_json::to_string(&info)?.as_bytes())?;
+        Ok(lock)
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn!(path = %self.path.display(), error = %e, "Failed to release run lock");
+        }
+    }
+}
+
+/// Returns true if the lock described by `contents` was taken more than `stale_after` ago.
+///
+/// Unreadable lock files are treated as held, so a half-written lock is never
+/// broken by a concurrent run.
+fn is_stale(contents: &str, stale_after: Duration, now: DateTime<Utc>) -> bool {
+    let Ok(info) = serde_json::from_str::<LockInfo>(contents) else {
+        return false;
+    };
+    let Ok(acquired_at) = DateTime::parse_from_rfc3339(&info.acquired_at) else {
+        return false;
+    };
+    now.signed_duration_since(acquired_at)
+        .to_std()
+        .is_ok_and(|age| age > stale_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn second_acquire_fails_until_released() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("run.lock");
+        let now = Utc::now();
+
+        let lock = RunLock::acquire(&path, HOUR, now).unwrap();
+        assert!(matches!(
+            RunLock::acquire(&path, HOUR, now),
+            Err(RunnerError::Locked { .. })
+        ));
+
+        drop(lock);
+        assert!(!path.exists());
+        assert!(RunLock::acquire(&path, HOUR, now).is_ok());
+    }
+
+    #[test]
+    fn breaks_stale_lock() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("run.lock");
+        let then = Utc::now();
+        let abandoned = RunLock::acquire(&path, HOUR, then).unwrap();
+        // Simulate a crashed run that never removed its lock.
+        core::mem::forget(abandoned);
+
+        let later = then + chrono::TimeDelta::hours(2);
+        assert!(RunLock::acquire(&path, HOUR, later).is_ok());
+    }
+}
@@ -1,270 +1,499 @@
 //! Orchestrates template upgrade scans and notifications.
 
+mod auto_pr;
+mod builder;
+mod campaign;
+mod changelog;
+mod checkpoint;
+mod checks;
 mod config;
+mod confirm;
+mod cooldown;
+mod dedupe;
+mod digest;
+mod dispatch;
 mod error;
+mod estimate;
+mod etag;
+mod exclude;
+mod forges;
+mod ledger;
+mod lock;
+mod owners;
+mod preferences;
+mod preview;
+mod project_board;
+mod repo_data;
+mod report;
+mod repository;
+mod rerun;
+mod resolve;
+mod retry;
+mod risk;
+mod rollout;
+mod search_cache;
+mod stages;
+mod state;
+mod stop;
+mod stream;
+mod templates;
+mod timings;
+mod watch;
 
+pub use builder::RunnerBuilder;
+pub use checkpoint::{Checkpoint, PendingRepository};
 pub use config::RunnerConfig;
 pub use error::RunnerError;
+pub use rerun::RerunTarget;
 
-use crate::config::{scan_migrations, Migration};
-use crate::discovery::discover_repositories;
-use crate::issues::{create_issue, update_issue_with_pr, IssueStatus};
-use crate::pull_requests::{create_pr, PrStatus};
-use crate::summary::{ProcessingResult, RunSummary};
+use crate::campaign::Campaign;
+use crate::clock::Clock;
+use crate::config::{scan_migrations, scan_migrations_strict, Migration, NotifierConfig};
+use crate::discovery::{DiscoveredRepository, DiscoveryError, RepoList};
+use crate::doctor::{run_doctor, DoctorReport};
+use crate::events::{EventHandler, EventHandlers, RunEvent};
+use crate::hooks::{HookEvent, HookSet, LifecycleHook};
+use crate::issues::clear_lookup_cache;
+use crate::ledger::Ledger;
+use crate::preferences::clear_preferences_cache;
+use crate::repo_data::RepoData;
+use crate::selection::{PatternSet, PriorityList};
+use crate::state::{StateStore, StateVersion};
+use crate::status::{fleet_status, MigrationStatus};
+use crate::summary::{Occurrence, ProcessingResult, RunSummary, SummaryCollector};
+use crate::teams::clear_team_cache;
 use crate::templates::TemplateRenderer;
-use futures::stream::{self, StreamExt};
+use confirm::LargeCampaignPrompt;
+use core::future;
+use futures::channel::mpsc;
+use futures::stream::{self as fstream, StreamExt};
+use lock::RunLock;
 use octocrab::Octocrab;
-use std::path::Path;
-use tracing::{error, info, warn};
+use retry::ProcessedMigration;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tracing::{error, info, info_span, warn, Instrument};
+use uuid::Uuid;
+
+/// Point in time after which a run starts no new repositories.
+type Deadline = chrono::DateTime<chrono::Utc>;
 
 /// Orchestrates a full template upgrade scan and notification run.
 pub struct Runner {
     config: RunnerConfig,
+    notifier: NotifierConfig,
     octocrab: Octocrab,
+    other_forges: forges::OtherForges,
     renderer: TemplateRenderer,
+    campaign: Option<Campaign>,
+    campaign_filter: PatternSet,
+    migration_filter: PatternSet,
+    repo_filter: PatternSet,
+    owner_exclusions: PatternSet,
+    repo_data: RepoData,
+    priority: PriorityList,
+    repo_list: Option<RepoList>,
+    hooks: HookSet,
+    events: EventHandlers,
+    clock: Box<dyn Clock>,
+    ledger: Mutex<Ledger>,
+    state_store: Option<Box<dyn StateStore>>,
+    /// Versions of the state files pulled, checked when pushing them back.
+    state_versions: Mutex<BTreeMap<&'static str, StateVersion>>,
+    result_sender: Mutex<Option<mpsc::Sender<ProcessingResult>>>,
+    progress: SummaryCollector,
+    large_campaign_prompt: Option<LargeCampaignPrompt>,
 }
 
 impl Runner {
     /// Builds a runner from the provided configuration.
     pub fn new(config: RunnerConfig) -> Result<Self, RunnerError> {
-        let octocrab = Octocrab::builder()
-            .personal_token(config.token().to_string())
-            .build()?;
-        Ok(Self {
-            config,
-            octocrab,
-            renderer: TemplateRenderer::new(),
-        })
+        Self::builder(config).build()
+    }
+
+    /// Starts building a runner with injectable dependencies.
+    pub fn builder(config: RunnerConfig) -> RunnerBuilder {
+        RunnerBuilder::new(config)
+    }
+
+    /// Registers a hook called at every lifecycle point, after any command
+    /// hooks from `notifier.toml`.
+    pub fn with_hook(mut self, hook: impl LifecycleHook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Registers a handler receiving per-repository progress events in real time.
+    pub fn with_event_handler(mut self, handler: impl EventHandler + 'static) -> Self {
+        self.events.push(Box::new(handler));
+        self
     }
 
     /// Executes the full orchestration flow.
+    ///
+    /// Results are recorded as each repository completes; poll
+    /// [`Runner::progress`] from another task to observe them live. Live runs
+    /// refuse to start without an `allowed-owners` list in `notifier.toml`.
+    ///
+    /// Each run gets a random UUID, recorded in the summary and attached as
+    /// `run_id` to every log event, alongside `migration_id` and `repo` while
+    /// a migration or repository is being processed.
     pub async fn run(&self) -> Result<RunSummary, RunnerError> {
-        let mut summary = RunSummary::new(self.config.dry_run());
-        info!(path = %self.config.migrations_path().display(), "Loading migrations");
-        let migrations = scan_migrations(self.config.migrations_path())?;
+        let run_id = Uuid::new_v4().to_string();
+        let span = info_span!("run", run_id = %run_id);
+        self.run_with_id(run_id).instrument(span).await
+    }
+
+    /// Does the work of [`Runner::run`] under the run's identifier.
+    ///
+    /// Live runs pull their state from the state store first and push it
+    /// back afterwards, even if the run failed part way. A push that finds
+    /// the state changed by another run fails the run.
+    async fn run_with_id(&self, run_id: String) -> Result<RunSummary, RunnerError> {
+        self.ensure_allowed_owners()?;
+        let _lock = self.acquire_lock()?;
+        if self.config.dry_run() {
+            return self.run_migrations(run_id).await;
+        }
+        self.pull_state().await?;
+        let result = self.run_migrations(run_id).await;
+        let pushed = self.push_state().await;
+        let summary = result?;
+        pushed?;
+        Ok(summary)
+    }
+
+    /// Processes every selected migration and reports the run.
+    async fn run_migrations(&self, run_id: String) -> Result<RunSummary, RunnerError> {
+        self.progress.reset(self.new_summary(run_id));
+        let mut migrations = self.load_migrations()?;
+        self.load_ledger()?;
+        self.load_etag_cache();
+        self.load_search_cache();
+        clear_lookup_cache();
+        clear_team_cache();
+        clear_preferences_cache();
 
         if migrations.is_empty() {
             warn!("No migrations found");
-            return Ok(summary);
+            return Ok(self.progress.snapshot());
         }
+        self.fetch_changelogs(&mut migrations).await;
+        self.record_declines(&migrations).await?;
 
         info!(count = migrations.len(), "Found migrations");
-        summary.migrations_processed = migrations.len();
-
-        for migration in &migrations {
-            process_migration(
-                &self.octocrab,
-                migration,
-                &self.renderer,
-                &self.config,
-                &mut summary,
-            )
-            .await?;
+        self.progress
+            .update(|summary| summary.migrations_processed = migrations.len());
+
+        let deadline = self.config.max_runtime().and_then(|limit| {
+            let limit = chrono::TimeDelta::from_std(limit).ok()?;
+            self.clock.now().checked_add_signed(limit)
+        });
+        let mut checkpoint = Checkpoint::default();
+        let mut processed = Vec::new();
+        for (index, migration) in migrations.iter().enumerate() {
+            if self.should_stop(deadline) {
+                checkpoint
+                    .pending_migrations
+                    .extend(migrations[index..].iter().map(|m| m.id.to_string()));
+                break;
+            }
+            let span = info_span!("migration", migration_id = %migration.id);
+            self.process_migration(migration, deadline, &mut checkpoint, &mut processed)
+                .instrument(span)
+                .await?;
+        }
+        self.retry_transient_failures(&mut processed, deadline)
+            .await;
+        self.save_ledger();
+        for run in &processed {
+            self.advance_rollout(&run.migration, &run.repositories, &run.results);
+            self.add_unfinished(run, &mut checkpoint);
         }
 
+        if !checkpoint.is_empty() {
+            let cancelled = self.is_cancelled();
+            self.progress
+                .update(|summary| summary.timed_out = !summary.aborted && !cancelled);
+            self.write_checkpoint(checkpoint);
+        }
+
+        self.save_etag_cache();
+        self.save_search_cache();
+        if self.config.dry_run() {
+            self.record_core_quota().await;
+        }
+        let summary = self.progress.snapshot();
+        self.update_tracking_issue(&summary).await;
+        self.update_rollout_issue(&summary).await;
+        self.update_project_board(&summary).await;
+        self.send_digest(&summary).await;
+        #[cfg(feature = "history")]
+        self.record_history(
+            &summary,
+            self.config.only_migrations(),
+            self.config.only_repos(),
+        );
         Ok(summary)
     }
-}
 
-async fn process_migration(
-    octocrab: &Octocrab,
-    migration: &Migration,
-    renderer: &TemplateRenderer,
-    config: &RunnerConfig,
-    summary: &mut RunSummary,
-) -> Result<(), RunnerError> {
-    info!(
-        migration_id = %migration.id,
-        old_string = %migration.old_string,
-        new_string = %migration.new_string,
-        "Processing migration"
-    );
-
-    let repositories = match discover_repositories(octocrab, migration).await {
-        Ok(repos) => repos,
-        Err(e) => {
-            error!(
-                migration_id = %migration.id,
-                error = %e,
-                "Failed to discover repositories"
-            );
-            return Ok(());
-        }
-    };
+    /// Reports how many previously filed issues/PRs are open vs closed per migration.
+    ///
+    /// Respects the migration selection; repository selection does not apply.
+    /// Nothing is written: runs with a ledger record declines themselves.
+    pub async fn status(&self) -> Result<Vec<MigrationStatus>, RunnerError> {
+        let migrations = self.load_migrations()?;
+        Ok(fleet_status(&self.octocrab, &migrations).await?)
+    }
 
-    if repositories.is_empty() {
-        info!(migration_id = %migration.id, "No repositories found");
-        return Ok(());
+    /// Returns the results of the current (or last) run recorded so far.
+    ///
+    /// Safe to call from another task while [`Runner::run`] is in flight.
+    #[must_use]
+    pub fn progress(&self) -> RunSummary {
+        self.progress.snapshot()
     }
 
-    info!(
-        migration_id = %migration.id,
-        count = repositories.len(),
-        "Found repositories"
-    );
-    summary.repositories_discovered += repositories.len();
+    /// Returns the campaign being run, if one was selected.
+    pub fn campaign(&self) -> Option<&Campaign> {
+        self.campaign.as_ref()
+    }
 
-    if config.dry_run() {
-        print_dry_run_preview(migration, &repositories, renderer);
-        return Ok(());
+    /// Returns the run-wide settings loaded from `notifier.toml`.
+    pub fn notifier(&self) -> &NotifierConfig {
+        &self.notifier
     }
 
-    let llm_config_path = config.llm_config_path().to_path_buf();
-    let results: Vec<ProcessingResult> = stream::iter(repositories)
-        .map(|repo| {
-            let octocrab = octocrab.clone();
-            let migration = migration.clone();
-            let renderer_ref = renderer;
-            let token = config.token().to_string();
-            let auto_pr = config.auto_pr();
-            let llm_config_path = llm_config_path.clone();
-
-            async move {
-                process_repository(
-                    &octocrab,
-                    &repo,
-                    &migration,
-                    renderer_ref,
-                    &token,
-                    auto_pr,
-                    &llm_config_path,
-                )
-                .await
-            }
-        })
-        .buffer_unordered(config.concurrency())
-        .collect()
-        .await;
+    /// Runs environment preflight checks without touching any repository.
+    pub async fn doctor(&self) -> DoctorReport {
+        run_doctor(&self.octocrab, &self.config).await
+    }
 
-    for result in &results {
-        summary.record_result(result);
+    /// Takes the run lock so overlapping live runs can't double-file issues.
+    ///
+    /// Dry runs never write to GitHub and skip locking, as do runs with
+    /// locking disabled.
+    fn acquire_lock(&self) -> Result<Option<RunLock>, RunnerError> {
+        match self.config.lock_path() {
+            Some(path) if !self.config.dry_run() => {
+                RunLock::acquire(path, self.config.lock_stale_after(), self.clock.now()).map(Some)
+            }
+            _ => Ok(None),
+        }
     }
 
-    Ok(())
+    /// Stores the run, limited to the `only_migrations` and `only_repos`
+    /// patterns, in the history database, if recording is enabled.
+    ///
+    /// Failures are logged; they never fail an otherwise completed run.
+    #[cfg(feature = "history")]
+    fn record_history(
+        &self,
+        summary: &RunSummary,
+        only_migrations: &[String],
+        only_repos: &[String],
+    ) {
+        use crate::history::HistoryStore;
+
+        let Some(path) = self.config.history_path() else {
+            return;
+        };
+        match HistoryStore::open(path).and_then(|mut store| {
+            store.record_run(summary, only_migrations, only_repos, self.clock.now())
+        }) {
+            Ok(run_id) => info!(run_id, path = %path.display(), "Recorded run history"),
+            Err(e) => warn!(error = %e, "Failed to record run history"),
+        }
+    }
 }
 
-async fn process_repository(
-    octocrab: &Octocrab,
-    repository: &crate::discovery::DiscoveredRepository,
-    migration: &Migration,
-    renderer: &TemplateRenderer,
-    token: &str,
-    auto_pr: bool,
-    llm_config_path: &Path,
-) -> ProcessingResult {
-    info!(repo = %repository.full_name, "Processing repository");
-
-    let issue_result =
-        match create_issue(octocrab, repository, migration, renderer, None, None).await {
-            Ok(issue) => issue,
+impl Runner {
+    /// Scans the migrations directory and applies the migration selection.
+    fn load_migrations(&self) -> Result<Vec<Migration>, RunnerError> {
+        info!(path = %self.config.migrations_path().display(), "Loading migrations");
+        let path = self.config.migrations_path();
+        let mut migrations = if self.config.strict_migrations() {
+            scan_migrations_strict(path)?
+        } else {
+            scan_migrations(path)?
+        };
+
+        if let Some(campaign) = &self.campaign {
+            migrations.retain(|migration| self.campaign_filter.matches(&migration.id));
+            info!(campaign = %campaign.name, count = migrations.len(), "Applied campaign");
+        }
+
+        if !self.migration_filter.is_empty() {
+            migrations.retain(|migration| self.migration_filter.matches(&migration.id));
+            info!(count = migrations.len(), "Applied migration selection");
+        }
+        Ok(migrations)
+    }
+
+    async fn process_migration(
+        &self,
+        migration: &Migration,
+        deadline: Option<Deadline>,
+        checkpoint: &mut Checkpoint,
+        processed: &mut Vec<ProcessedMigration>,
+    ) -> Result<(), RunnerError> {
+        info!(
+            migration_id = %migration.id,
+            old_string = %migration.old_string,
+            new_string = %migration.new_string,
+            "Processing migration"
+        );
+        self.events.emit(|| RunEvent::MigrationStarted {
+            migration_id: migration.id.to_string(),
+        });
+        if !self.templates_valid(migration) {
+            return Ok(());
+        }
+        self.other_forges.notify(self, migration, deadline).await;
+
+        let mut repositories = match self.discover_timed(migration).await {
+            Ok(repos) => repos,
+            Err(DiscoveryError::Cancelled) => {
+                info!(migration_id = %migration.id, "Run cancelled during discovery");
+                checkpoint.pending_migrations.push(migration.id.to_string());
+                return Ok(());
+            }
             Err(e) => {
                 error!(
-                    repo = %repository.full_name,
+                    migration_id = %migration.id,
                     error = %e,
-                    "Failed to create issue"
+                    "Failed to discover repositories"
                 );
-                return ProcessingResult::Failed {
-                    repository: repository.full_name.clone(),
-                    error: e.to_string(),
-                };
+                return Ok(());
             }
         };
 
-    let issue_status = issue_result.status.clone();
-    let mut pr_status: Option<PrStatus> = None;
-
-    if auto_pr {
-        if let IssueStatus::Created { number, .. } = &issue_status {
-            match create_pr(
-                octocrab,
-                repository,
-                migration,
-                renderer,
-                token,
-                llm_config_path,
-            )
-            .await
-            {
-                Ok(pr) => {
-                    pr_status = Some(pr.status.clone());
-                    if let PrStatus::Created { url, .. } = &pr.status {
-                        if let Err(e) = update_issue_with_pr(
-                            octocrab,
-                            repository,
-                            *number,
-                            migration,
-                            renderer,
-                            &pr.status,
-                            Some(url),
-                        )
-                        .await
-                        {
-                            warn!(
-                                repo = %repository.full_name,
-                                error = %e,
-                                "Failed to update issue with PR info"
-                            );
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        repo = %repository.full_name,
-                        error = %e,
-                        "Failed to create PR"
-                    );
-                    pr_status = Some(PrStatus::Failed {
-                        error: e.to_string(),
-                    });
-                }
-            }
+        let dry_run = self.dry_run_level(migration);
+        if self.config.auto_close() && dry_run.is_none() {
+            let closed = self.close_resolved(migration).await;
+            self.progress
+                .update(|summary| summary.notifications_closed += closed);
         }
-    }
 
-    ProcessingResult::Success {
-        repository: repository.full_name.clone(),
-        issue: issue_status,
-        pr: pr_status,
-    }
-}
+        if !self.repo_filter.is_empty() {
+            let before = repositories.len();
+            repositories.retain(|repo| self.repo_filter.matches(&repo.full_name));
+            info!(
+                migration_id = %migration.id,
+                kept = repositories.len(),
+                excluded = before - repositories.len(),
+                "Applied repository selection"
+            );
+        }
+        self.apply_repo_lists(migration, &mut repositories)?;
+        self.apply_exclusions(migration, &mut repositories);
+        self.apply_false_positive_filter(migration, &mut repositories)
+            .await;
+        // Compliance reports cover every repository, not a rollout or sample.
+        if !self.config.report_only() {
+            self.apply_rollout_percent(migration, &mut repositories);
+            self.apply_sample(migration, &mut repositories);
+        }
+        self.attach_repo_data(&mut repositories);
+        self.priority
+            .prioritize(&mut repositories, |repo| repo.full_name.as_str());
 
-fn print_dry_run_preview(
-    migration: &Migration,
-    repositories: &[crate::discovery::DiscoveredRepository],
-    renderer: &TemplateRenderer,
-) {
-    println!("\n[DRY RUN] Migration: {}", migration.id);
-    println!(
-        "  Would upgrade: {} -> {}",
-        migration.old_string, migration.new_string
-    );
-    println!("  Found {} repositories:\n", repositories.len());
-
-    for (i, repo) in repositories.iter().enumerate() {
-        println!("  [{}/{}] {}", i + 1, repositories.len(), repo.full_name);
-        println!(
-            "    Would create issue: \"Template Upgrade Available: {} -> {}\"",
-            migration.old_string, migration.new_string
-        );
-        println!(
-            "    Would create PR on branch: template-upgrade/{}",
-            migration.id
+        if repositories.is_empty() {
+            info!(migration_id = %migration.id, "No repositories found");
+            return Ok(());
+        }
+        if self.config.report_only() {
+            let occurrences = self.report_occurrences(migration, &repositories).await;
+            self.progress.update(|summary| {
+                summary.repositories_discovered += occurrences.len();
+                summary.occurrences.extend(occurrences);
+            });
+            return Ok(());
+        }
+        if let Err(e) = self.apply_opt_outs(migration, &mut repositories).await {
+            error!(
+                migration_id = %migration.id,
+                error = %e,
+                "Failed to check for opt-outs, notifying no repositories"
+            );
+            return Ok(());
+        }
+        if !self
+            .confirm_large_campaign(migration, repositories.len())
+            .await
+        {
+            return Ok(());
+        }
+
+        info!(
+            migration_id = %migration.id,
+            count = repositories.len(),
+            "Found repositories"
         );
-    }
+        self.progress.update(|summary| {
+            summary.repositories_discovered += repositories.len();
+            summary.occurrences.extend(
+                repositories
+                    .iter()
+                    .map(|repo| Occurrence::new(migration, repo)),
+            );
+        });
+        for repo in &repositories {
+            self.events.emit(|| RunEvent::Discovered {
+                migration_id: migration.id.to_string(),
+                repository: repo.full_name.to_string(),
+            });
+        }
+        self.hooks
+            .emit(|| HookEvent::AfterDiscovery {
+                migration_id: migration.id.to_string(),
+                repositories: repositories.clone(),
+            })
+            .await;
 
-    if let Some(_first_repo) = repositories.first() {
-        println!("\n  Sample issue body:");
-        if let Ok(body) =
-            renderer.render_issue_template(&migration.issue_template, migration, None, None)
-        {
-            for line in body.lines().take(10) {
-                println!("    {line}");
-            }
-            if body.lines().count() > 10 {
-                println!("    ...");
-            }
+        if let Some(level) = dry_run {
+            self.record_estimate(migration, repositories.len());
+            self.preview(migration, &repositories, level).await;
+            return Ok(());
         }
+
+        self.prefetch_duplicates(migration, &repositories).await;
+        let results = self
+            .process_repositories(migration, &repositories, deadline)
+            .await;
+        self.save_ledger();
+        processed.push(ProcessedMigration {
+            migration: migration.clone(),
+            repositories,
+            results,
+        });
+        Ok(())
     }
 
-    println!();
+    /// Processes repositories concurrently, bounded by the configured concurrency.
+    ///
+    /// Each result is recorded in the run's progress (and sent to a
+    /// [`Runner::run_stream`] consumer) as soon as it completes,
+    /// replacing the earlier result of a retried repository. No new repository
+    /// is started once `deadline` has passed or the run was aborted;
+    /// repositories already in flight run to completion.
+    async fn process_repositories(
+        &self,
+        migration: &Migration,
+        repositories: &[DiscoveredRepository],
+        deadline: Option<Deadline>,
+    ) -> Vec<ProcessingResult> {
+        fstream::iter(repositories)
+            .take_while(|_| future::ready(!self.should_stop(deadline)))
+            .map(|repo| async move {
+                let result = self.process_repository(repo, migration).await;
+                self.progress.record(&result);
+                self.check_abort_threshold();
+                self.stream_result(&result).await;
+                result
+            })
+            .buffer_unordered(self.config.concurrency())
+            .collect()
+            .await
+    }
 }
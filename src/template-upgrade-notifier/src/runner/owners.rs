@@ -0,0 +1,61 @@
+//! The `allowed-owners` safety boundary for live runs.
+
+use super::{Runner, RunnerError};
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+
+impl Runner {
+    /// Checks that a live run has a non-empty `allowed-owners` list.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::AllowedOwnersMissing`] for live runs without one.
+    pub(super) fn ensure_allowed_owners(&self) -> Result<(), RunnerError> {
+        if self.config.dry_run() {
+            return Ok(());
+        }
+        match &self.notifier.allowed_owners {
+            Some(owners) if !owners.is_empty() => Ok(()),
+            _ => Err(RunnerError::AllowedOwnersMissing),
+        }
+    }
+
+    /// Returns why `repository` must not be touched, if its owner is outside
+    /// `allowed-owners`.
+    ///
+    /// Applies only when `migration` runs live, whatever else it configures.
+    pub(super) fn owner_refusal(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+    ) -> Option<String> {
+        if self.dry_run_level(migration).is_some() {
+            return None;
+        }
+        let owners = self.notifier.allowed_owners.as_deref().unwrap_or_default();
+        (!is_allowed(owners, &repository.owner))
+            .then(|| format!("owner {} is not in allowed-owners", repository.owner))
+    }
+}
+
+/// Returns true if `owner` is in `owners`; GitHub logins are case-insensitive.
+fn is_allowed(owners: &[String], owner: &str) -> bool {
+    owners
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(owner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_owner_case_insensitively() {
+        let owners = vec!["Sewer56".to_string(), "my-org".to_string()];
+
+        assert!(is_allowed(&owners, "sewer56"));
+        assert!(is_allowed(&owners, "my-org"));
+        assert!(!is_allowed(&owners, "my-org-fork"));
+        assert!(!is_allowed(&[], "sewer56"));
+    }
+}
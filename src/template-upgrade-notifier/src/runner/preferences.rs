@@ -0,0 +1,61 @@
+//! Honoring consumer preferences from `.github/template-upgrade.yml`.
+
+use super::Runner;
+use crate::discovery::DiscoveredRepository;
+use crate::preferences::{fetch_preferences, RepoPreferences};
+use crate::rate_limit::ensure_core_rate_limit;
+use tracing::warn;
+
+impl Runner {
+    /// Returns the preferences of `repository`, merged with its organization's
+    /// defaults.
+    ///
+    /// A file that cannot be fetched or parsed is logged and treated as
+    /// setting nothing.
+    pub(super) async fn consumer_preferences(
+        &self,
+        repository: &DiscoveredRepository,
+    ) -> RepoPreferences {
+        match fetch_preferences(&self.octocrab, &repository.owner, &repository.name).await {
+            Ok(preferences) => preferences,
+            Err(e) => {
+                warn!(
+                    repo = %repository.full_name,
+                    error = %e,
+                    "Failed to read consumer preferences"
+                );
+                RepoPreferences::default()
+            }
+        }
+    }
+
+    /// Adds the labels the consumer asked for to our issue or PR `number`.
+    ///
+    /// Failures are logged; the notice itself already exists.
+    pub(super) async fn add_preferred_labels(
+        &self,
+        repository: &DiscoveredRepository,
+        number: u64,
+        preferences: &RepoPreferences,
+    ) {
+        let labels = preferences.labels();
+        if labels.is_empty() {
+            return;
+        }
+        let added = async {
+            ensure_core_rate_limit(&self.octocrab).await?;
+            self.octocrab
+                .issues(&repository.owner, &repository.name)
+                .add_labels(number, labels)
+                .await
+        };
+        if let Err(e) = added.await {
+            warn!(
+                repo = %repository.full_name,
+                number,
+                error = %e,
+                "Failed to add preferred labels"
+            );
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Dry-run preview output.
+
+use super::Runner;
+use crate::config::{DryRunLevel, Migration};
+use crate::discovery::DiscoveredRepository;
+use crate::pull_requests::{preview_pr, PrDiff};
+use crate::redact::redact;
+use crate::templates::{
+    generate_branch_name, generate_issue_title, generate_pr_title, TemplateError, TemplateRenderer,
+};
+use futures::stream::{self, StreamExt};
+
+impl Runner {
+    /// Returns the dry-run level `migration` runs at, or `None` if it runs live.
+    ///
+    /// A migration's own `dry-run` level applies even in live runs.
+    pub(super) fn dry_run_level(&self, migration: &Migration) -> Option<DryRunLevel> {
+        migration
+            .dry_run
+            .or_else(|| self.config.dry_run().then(|| self.config.dry_run_level()))
+    }
+
+    /// Prints what a live run would do for a migration's repositories, going
+    /// as far as `level` allows.
+    pub(super) async fn preview(
+        &self,
+        migration: &Migration,
+        repositories: &[DiscoveredRepository],
+        level: DryRunLevel,
+    ) {
+        print_dry_run_preview(migration, repositories, &self.renderer);
+        if level.renders() {
+            for repo in repositories {
+                print_rendered(migration, repo, &self.renderer);
+            }
+        }
+        if level.clones() {
+            self.print_diffs(migration, repositories).await;
+        }
+    }
+
+    /// Runs the migration's strategy in a clone of each repository, bounded by
+    /// the configured concurrency, and prints the resulting diffs in order.
+    async fn print_diffs(&self, migration: &Migration, repositories: &[DiscoveredRepository]) {
+        let options = self.pr_options(migration);
+        let options = &options;
+        let mut previews = stream::iter(repositories)
+            .map(|repo| async move {
+                let diff = preview_pr(&self.octocrab, repo, migration, options).await;
+                (repo, diff)
+            })
+            .buffered(self.config.concurrency());
+        while let Some((repo, diff)) = previews.next().await {
+            println!("\n[DRY RUN] Diff for {}:", repo.full_name);
+            match diff {
+                Ok(PrDiff::Changes(diff)) => print!("{}", redact(&diff)),
+                Ok(PrDiff::Skipped { reason }) => println!("  Would skip PR: {reason}"),
+                Err(e) => println!("  Would fail: {}", redact(&e.to_string())),
+            }
+        }
+    }
+}
+
+/// Prints what a live run would do for a migration's repositories.
+pub(super) fn print_dry_run_preview(
+    migration: &Migration,
+    repositories: &[DiscoveredRepository],
+    renderer: &TemplateRenderer,
+) {
+    println!("\n[DRY RUN] Migration: {}", migration.id);
+    println!(
+        "  Would upgrade: {} -> {}",
+        migration.old_string, migration.new_string
+    );
+    println!("  Found {} repositories:\n", repositories.len());
+
+    for (i, repo) in repositories.iter().enumerate() {
+        println!("  [{}/{}] {}", i + 1, repositories.len(), repo.full_name);
+        println!(
+            "    Would create issue: \"Template Upgrade Available: {} -> {}\"",
+            migration.old_string, migration.new_string
+        );
+        println!(
+            "    Would create PR on branch: template-upgrade/{}",
+            migration.id
+        );
+    }
+
+    if let Some(first_repo) = repositories.first() {
+        println!("\n  Sample issue body:");
+        if let Ok(body) = renderer.render_issue_template(
+            &migration.issue_template,
+            migration,
+            Some(first_repo),
+            None,
+            None,
+        ) {
+            for line in body.lines().take(10) {
+                println!("    {line}");
+            }
+            if body.lines().count() > 10 {
+                println!("    ...");
+            }
+        }
+    }
+
+    println!();
+}
+
+/// Prints the issue and PR a live run would file in `repo`, in full.
+fn print_rendered(migration: &Migration, repo: &DiscoveredRepository, renderer: &TemplateRenderer) {
+    println!("\n[DRY RUN] Rendered for {}:", repo.full_name);
+    let rendered = |result: Result<String, TemplateError>| match result {
+        Ok(text) => text,
+        Err(e) => format!("<failed to render: {e}>"),
+    };
+    println!(
+        "  Issue title: {}",
+        rendered(generate_issue_title(migration))
+    );
+    println!("  Issue body:");
+    let issue_body = rendered(renderer.render_issue_template(
+        &migration.issue_template,
+        migration,
+        Some(repo),
+        None,
+        None,
+    ));
+    for line in issue_body.lines() {
+        println!("    {line}");
+    }
+    println!("  PR title: {}", rendered(generate_pr_title(migration)));
+    println!("  PR branch: {}", rendered(generate_branch_name(migration)));
+    println!("  PR body:");
+    let pr_body = rendered(renderer.render_pr_template(
+        &migration.pr_template,
+        migration,
+        repo.template_version.as_deref(),
+    ));
+    for line in pr_body.lines() {
+        println!("    {line}");
+    }
+}
@@ -0,0 +1,36 @@
+//! Mirroring run results into a GitHub Projects board after a run.
+
+use super::Runner;
+use crate::project_board::sync_project_board;
+use crate::summary::RunSummary;
+use tracing::{info, warn};
+
+impl Runner {
+    /// Adds every repository notified so far to the `[project-board]` and
+    /// refreshes its status.
+    ///
+    /// Covers the ledger's entries plus this run's results, like the rollout
+    /// issue. Dry runs leave the board untouched. Failures are logged; they
+    /// never fail an otherwise completed run.
+    pub(super) async fn update_project_board(&self, summary: &RunSummary) {
+        let Some(config) = &self.notifier.project_board else {
+            return;
+        };
+        if summary.dry_run {
+            return;
+        }
+        let notified = self.notified_so_far(summary);
+        if notified.entries().is_empty() {
+            return;
+        }
+        match sync_project_board(&self.octocrab, config, notified.entries()).await {
+            Ok(changed) => info!(
+                changed,
+                owner = %config.owner,
+                number = config.number,
+                "Updated project board"
+            ),
+            Err(e) => warn!(error = %e, "Failed to update project board"),
+        }
+    }
+}
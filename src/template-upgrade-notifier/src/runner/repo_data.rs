@@ -0,0 +1,18 @@
+//! Attaching `repo-data.toml` values to discovered repositories.
+
+use super::Runner;
+use crate::discovery::DiscoveredRepository;
+
+impl Runner {
+    /// Copies each repository's `repo-data.toml` values onto it, for templates.
+    pub(super) fn attach_repo_data(&self, repositories: &mut [DiscoveredRepository]) {
+        if self.repo_data.is_empty() {
+            return;
+        }
+        for repository in repositories {
+            if let Some(values) = self.repo_data.get(&repository.full_name) {
+                repository.repo_data.clone_from(values);
+            }
+        }
+    }
+}
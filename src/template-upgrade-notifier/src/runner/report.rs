@@ -0,0 +1,69 @@
+//! Report-only runs producing a compliance matrix.
+
+use super::{Runner, RunnerError};
+use crate::config::Migration;
+use crate::discovery::{verify_on_default_branch, DiscoveredRepository};
+use crate::summary::{ComplianceMatrix, Occurrence};
+use core::future;
+use futures::stream::{self, StreamExt};
+use tracing::{debug, warn};
+
+impl Runner {
+    /// Runs every selected migration report-only and returns the compliance
+    /// matrix of the outdated repositories found.
+    ///
+    /// Nothing is created, commented on or closed: discovery and reading each
+    /// repository's version file from its default branch are the only API
+    /// calls. Requires a configuration
+    /// [`with_report_only`](crate::runner::RunnerConfig::with_report_only).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::NotReportOnly`] if the runner is not configured
+    /// report-only, or any error of [`Runner::run`].
+    pub async fn report(&self) -> Result<ComplianceMatrix, RunnerError> {
+        if !self.config.report_only() {
+            return Err(RunnerError::NotReportOnly);
+        }
+        let summary = self.run().await?;
+        let migrations = self.load_migrations()?;
+        Ok(ComplianceMatrix::new(&summary.occurrences, &migrations))
+    }
+
+    /// Reads the template version of each repository from its default branch.
+    ///
+    /// Repositories no longer containing `old_string` are left out as stale
+    /// search results; repositories that cannot be read are reported without
+    /// a version.
+    pub(super) async fn report_occurrences(
+        &self,
+        migration: &Migration,
+        repositories: &[DiscoveredRepository],
+    ) -> Vec<Occurrence> {
+        stream::iter(repositories)
+            .map(|repo| async move {
+                let verified = verify_on_default_branch(&self.octocrab, repo, migration).await;
+                (repo, verified)
+            })
+            .buffered(self.config.concurrency())
+            .filter_map(|(repo, verified)| {
+                future::ready(match verified {
+                    Ok(Some(verified)) => Some(Occurrence::new(migration, &verified)),
+                    Ok(None) => {
+                        debug!(repo = %repo.full_name, "Old string no longer on default branch");
+                        None
+                    }
+                    Err(e) => {
+                        warn!(
+                            repo = %repo.full_name,
+                            error = %e,
+                            "Failed to read template version, reporting without it"
+                        );
+                        Some(Occurrence::new(migration, repo))
+                    }
+                })
+            })
+            .collect()
+            .await
+    }
+}
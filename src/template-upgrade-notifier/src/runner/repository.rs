@@ -0,0 +1,341 @@
+//! Processing of a single discovered repository.
+
+use super::stop::CANCELLED_REASON;
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::{verify_on_default_branch, DiscoveredRepository, ALREADY_UPGRADED_REASON};
+use crate::dispatch::DispatchMode;
+use crate::error_kind::ErrorKind;
+use crate::events::RunEvent;
+use crate::hooks::HookEvent;
+use crate::issues::{create_issue, force_create_issue, IssueError, IssueStatus};
+use crate::preferences::PREFERENCES_PATH;
+use crate::pull_requests::PrStatus;
+use crate::redact::redact;
+use crate::summary::{ProcessingResult, Stage, StageTimings, TimingRecord};
+use tracing::{error, info, info_span, warn, Instrument};
+
+/// Skip reason for repositories with issues disabled when auto-PR is off.
+pub(super) const ISSUES_DISABLED_REASON: &str = "issues disabled";
+
+impl Runner {
+    /// Creates the upgrade issue (and optionally the auto-fix PR) for one repository.
+    ///
+    /// Repositories whose owner is outside `allowed-owners` are refused
+    /// outright, and archived or disabled repositories are skipped unless
+    /// `[exclude] archived` is off.
+    /// Repositories the ledger records as already notified are skipped, as are
+    /// repositories notified within the cooldown
+    /// (`min-days-between-notifications`), unless the duplicate policy will
+    /// act on an issue still open there; repositories that opted out
+    /// through a topic or issue label (`[opt-out]`) or in their
+    /// `.github/template-upgrade.yml`, whose labels and auto-PR preference
+    /// apply from then on. Repositories whose default branch
+    /// no longer contains `old_string` are skipped as stale search results
+    /// before anything is created;
+    /// the others carry their detected `template_version` from then on.
+    /// Migrations with a `commit-check` also flag the default branch HEAD,
+    /// and migrations with a `[dispatch]` table send a `repository_dispatch`
+    /// event after the issue, or instead of it.
+    ///
+    /// The time spent in each stage is attached to the result and recorded in
+    /// the run's progress, whichever way processing ended.
+    /// Cancelling the run abandons the repository wherever it is, reporting
+    /// it as a retryable failure.
+    pub(super) async fn process_repository(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+    ) -> ProcessingResult {
+        let mut timings = StageTimings::default();
+        let span = info_span!(
+            "repository",
+            migration_id = %migration.id,
+            repo = %repository.full_name
+        );
+        let notify = self.notify_repository(repository, migration, &mut timings);
+        let mut result = async {
+            tokio::select! {
+                result = notify => result,
+                () = self.config.cancellation_token().cancelled() => {
+                    warn!(repo = %repository.full_name, "Run cancelled, abandoning repository");
+                    self.failure(repository, migration, CANCELLED_REASON, ErrorKind::Cancelled)
+                }
+            }
+        }
+        .instrument(span)
+        .await;
+        result.set_timings(timings.clone());
+        self.progress.update(|summary| {
+            summary.record_timings(TimingRecord {
+                migration_id: migration.id.to_string(),
+                repository: Some(repository.full_name.to_string()),
+                timings,
+            });
+        });
+        result
+    }
+
+    /// Does the work of [`Runner::process_repository`], adding the time spent
+    /// in each stage to `timings`.
+    async fn notify_repository(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        timings: &mut StageTimings,
+    ) -> ProcessingResult {
+        info!(repo = %repository.full_name, "Processing repository");
+
+        if let Some(reason) = self.owner_refusal(repository, migration) {
+            warn!(repo = %repository.full_name, %reason, "Refusing repository");
+            return ProcessingResult::Skipped {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                reason,
+                timings: StageTimings::default(),
+            };
+        }
+
+        if let Some(reason) = self.read_only_skip_reason(repository) {
+            info!(repo = %repository.full_name, reason, "Read-only repository, skipping");
+            return ProcessingResult::Skipped {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                reason: reason.to_string(),
+                timings: StageTimings::default(),
+            };
+        }
+
+        let refreshing = timings
+            .measure(
+                Stage::Dedupe,
+                self.refreshes_duplicate(repository, migration),
+            )
+            .await;
+        if let Some(reason) = self.ledger_skip_reason(repository, migration, refreshing) {
+            info!(repo = %repository.full_name, %reason, "In ledger, skipping");
+            return ProcessingResult::Skipped {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                reason,
+                timings: StageTimings::default(),
+            };
+        }
+
+        let repository = &self.with_previous_notice(repository);
+        let cooldown = timings
+            .measure(
+                Stage::Dedupe,
+                self.cooldown_skip_reason(repository, refreshing),
+            )
+            .await;
+        if let Some(reason) = cooldown {
+            info!(repo = %repository.full_name, %reason, "Notified recently, skipping");
+            return ProcessingResult::Skipped {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                reason,
+                timings: StageTimings::default(),
+            };
+        }
+
+        if let Some(reason) = &repository.opted_out {
+            info!(repo = %repository.full_name, %reason, "Opted out, skipping");
+            return ProcessingResult::Skipped {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                reason: reason.clone(),
+                timings: StageTimings::default(),
+            };
+        }
+
+        let preferences = timings
+            .measure(Stage::Dedupe, self.consumer_preferences(repository))
+            .await;
+        if preferences.opted_out() {
+            info!(repo = %repository.full_name, "Opted out in preferences, skipping");
+            return ProcessingResult::Skipped {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                reason: format!("opted out ({PREFERENCES_PATH})"),
+                timings: StageTimings::default(),
+            };
+        }
+
+        let verified = timings
+            .measure(
+                Stage::Dedupe,
+                verify_on_default_branch(&self.octocrab, repository, migration),
+            )
+            .await;
+        let repository = &match verified {
+            Ok(Some(repository)) => repository,
+            Ok(None) => {
+                info!(
+                    repo = %repository.full_name,
+                    "Old string no longer on default branch, skipping"
+                );
+                return ProcessingResult::Skipped {
+                    migration_id: migration.id.to_string(),
+                    repository: repository.full_name.to_string(),
+                    reason: ALREADY_UPGRADED_REASON.to_string(),
+                    timings: StageTimings::default(),
+                };
+            }
+            Err(e) => {
+                error!(
+                    repo = %repository.full_name,
+                    error = %e,
+                    "Failed to verify old string on default branch"
+                );
+                return self.failure(repository, migration, &e.to_string(), e.kind());
+            }
+        };
+
+        if let Some(dispatch) = migration
+            .dispatch
+            .as_ref()
+            .filter(|dispatch| dispatch.mode == DispatchMode::Instead)
+        {
+            return timings
+                .measure(
+                    Stage::Api,
+                    self.dispatch_instead(repository, migration, dispatch),
+                )
+                .await;
+        }
+
+        self.hooks
+            .emit(|| HookEvent::BeforeIssue {
+                migration_id: migration.id.to_string(),
+                repository: repository.clone(),
+            })
+            .await;
+
+        let create = timings
+            .measure(Stage::Api, async {
+                if self.config.force_issues() {
+                    force_create_issue(
+                        &self.octocrab,
+                        repository,
+                        migration,
+                        &self.renderer,
+                        None,
+                        None,
+                    )
+                    .await
+                } else {
+                    create_issue(
+                        &self.octocrab,
+                        repository,
+                        migration,
+                        &self.renderer,
+                        None,
+                        None,
+                    )
+                    .await
+                }
+            })
+            .await;
+        let issue_result = match create {
+            Ok(issue) => issue,
+            Err(IssueError::IssuesDisabled { .. }) => {
+                return self
+                    .process_pr_only(repository, migration, &preferences, timings)
+                    .await;
+            }
+            Err(e) => {
+                error!(
+                    repo = %repository.full_name,
+                    error = %e,
+                    "Failed to create issue"
+                );
+                return self.failure(repository, migration, &e.to_string(), e.kind());
+            }
+        };
+
+        let issue_status = issue_result.status.clone();
+        let issue_url = match &issue_status {
+            IssueStatus::Created { url, .. } => Some(url.as_str()),
+            _ => None,
+        };
+        timings
+            .measure(
+                Stage::Api,
+                self.publish_check(repository, migration, issue_url),
+            )
+            .await;
+        if let IssueStatus::Created { number, url } = &issue_status {
+            self.record_issue(repository, migration, *number);
+            timings
+                .measure(
+                    Stage::Api,
+                    self.add_preferred_labels(repository, *number, &preferences),
+                )
+                .await;
+            timings
+                .measure(
+                    Stage::Api,
+                    self.dispatch_alongside(repository, migration, url.as_str()),
+                )
+                .await;
+            self.events.emit(|| RunEvent::IssueCreated {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+                number: *number,
+                url: url.to_string(),
+            });
+        }
+        self.hooks
+            .emit(|| HookEvent::AfterIssue {
+                migration_id: migration.id.to_string(),
+                repository: repository.clone(),
+                issue: issue_status.clone(),
+            })
+            .await;
+
+        let mut pr_status: Option<PrStatus> = None;
+        if self.config.auto_pr()
+            && preferences.wants_auto_pr()
+            && matches!(issue_status, IssueStatus::Created { .. })
+        {
+            pr_status = Some(
+                self.open_pr(repository, migration, &issue_status, &preferences, timings)
+                    .await,
+            );
+        }
+
+        ProcessingResult::Success {
+            migration_id: migration.id.to_string(),
+            repository: repository.full_name.to_string(),
+            issue: issue_status,
+            pr: pr_status,
+            timings: StageTimings::default(),
+        }
+    }
+
+    /// Reports a repository that could not be processed.
+    pub(super) fn failure(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        error: &str,
+        kind: ErrorKind,
+    ) -> ProcessingResult {
+        let error = redact(error);
+        self.events.emit(|| RunEvent::Failed {
+            migration_id: migration.id.to_string(),
+            repository: repository.full_name.to_string(),
+            error: error.clone(),
+            kind,
+            retryable: kind.is_retryable(),
+        });
+        ProcessingResult::Failed {
+            migration_id: migration.id.to_string(),
+            repository: repository.full_name.to_string(),
+            error,
+            kind,
+            timings: StageTimings::default(),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Targeted re-runs of specific repository/migration pairs.
+
+use super::preview::print_dry_run_preview;
+use super::{Runner, RunnerError};
+use crate::discovery::discover_in_repository;
+use crate::events::RunEvent;
+use crate::issues::clear_lookup_cache;
+use crate::redact::redact;
+use crate::summary::{Occurrence, ProcessingResult, RunSummary, StageTimings};
+use crate::teams::clear_team_cache;
+use std::collections::HashSet;
+use tracing::{info, info_span, warn, Instrument};
+use uuid::Uuid;
+
+/// Skip reason for a target the scoped code search no longer finds.
+const NOT_FOUND_REASON: &str = "old string not found by code search";
+
+/// A repository/migration pair to re-run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RerunTarget {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Repository full name in `owner/name` format.
+    pub repository: String,
+}
+
+impl Runner {
+    /// Re-executes the full pipeline for specific repository/migration pairs.
+    ///
+    /// Each repository is rediscovered with a code search scoped to it and then
+    /// processed exactly like in a full run (issue, optional PR, hooks, events).
+    /// Combine with [`RunnerConfig::with_force_issues`](super::RunnerConfig::with_force_issues)
+    /// to re-file issues that already exist. Targets are processed one at a time.
+    ///
+    /// Like [`Runner::run`], each re-run gets a random UUID, recorded in the
+    /// summary and attached as `run_id` to every log event.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::UnknownMigration`] if a target names a migration
+    /// that is not in the migrations directory, or
+    /// [`RunnerError::AllowedOwnersMissing`] for a live re-run without
+    /// `allowed-owners`; nothing is processed in either case.
+    pub async fn rerun(&self, targets: &[RerunTarget]) -> Result<RunSummary, RunnerError> {
+        let run_id = Uuid::new_v4().to_string();
+        let span = info_span!("run", run_id = %run_id);
+        self.rerun_with_id(targets, run_id).instrument(span).await
+    }
+
+    /// Does the work of [`Runner::rerun`] under the run's identifier.
+    async fn rerun_with_id(
+        &self,
+        targets: &[RerunTarget],
+        run_id: String,
+    ) -> Result<RunSummary, RunnerError> {
+        self.ensure_allowed_owners()?;
+        let _lock = self.acquire_lock()?;
+        self.progress.reset(self.new_summary(run_id));
+        let mut migrations = self.load_migrations()?;
+        self.load_ledger()?;
+        self.load_etag_cache();
+        self.load_search_cache();
+        clear_lookup_cache();
+        clear_team_cache();
+        self.fetch_changelogs(
+            migrations
+                .iter_mut()
+                .filter(|m| targets.iter().any(|t| m.id == t.migration_id)),
+        )
+        .await;
+
+        // Resolve every target first so a typo fails before anything is filed.
+        let resolved = targets
+            .iter()
+            .map(|target| {
+                migrations
+                    .iter()
+                    .find(|m| m.id == target.migration_id)
+                    .map(|migration| (target, migration))
+                    .ok_or_else(|| RunnerError::UnknownMigration {
+                        id: target.migration_id.clone(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let migrations_processed = resolved
+            .iter()
+            .map(|(_, m)| &m.id)
+            .collect::<HashSet<_>>()
+            .len();
+        self.progress
+            .update(|summary| summary.migrations_processed = migrations_processed);
+
+        for (target, migration) in resolved {
+            info!(
+                migration_id = %migration.id,
+                repo = %target.repository,
+                "Re-running repository"
+            );
+            let mut repository = match discover_in_repository(
+                &self.octocrab,
+                migration,
+                &target.repository,
+            )
+            .await
+            {
+                Ok(Some(repository)) => repository,
+                Ok(None) => {
+                    warn!(repo = %target.repository, "Repository no longer matches");
+                    self.progress.record(&ProcessingResult::Skipped {
+                        migration_id: migration.id.to_string(),
+                        repository: target.repository.clone(),
+                        reason: NOT_FOUND_REASON.to_string(),
+                        timings: StageTimings::default(),
+                    });
+                    continue;
+                }
+                Err(e) => {
+                    warn!(repo = %target.repository, error = %e, "Failed to rediscover repository");
+                    self.progress.record(&ProcessingResult::Failed {
+                        migration_id: migration.id.to_string(),
+                        repository: target.repository.clone(),
+                        error: redact(&e.to_string()),
+                        kind: e.kind(),
+                        timings: StageTimings::default(),
+                    });
+                    continue;
+                }
+            };
+
+            self.attach_repo_data(core::slice::from_mut(&mut repository));
+            if let Err(e) = self
+                .apply_opt_outs(migration, core::slice::from_mut(&mut repository))
+                .await
+            {
+                warn!(repo = %target.repository, error = %e, "Failed to check for an opt-out");
+                self.progress.record(&ProcessingResult::Failed {
+                    migration_id: migration.id.to_string(),
+                    repository: target.repository.clone(),
+                    error: redact(&e.to_string()),
+                    kind: e.kind(),
+                    timings: StageTimings::default(),
+                });
+                continue;
+            }
+            self.progress.update(|summary| {
+                summary.repositories_discovered += 1;
+                summary
+                    .occurrences
+                    .push(Occurrence::new(migration, &repository));
+            });
+            self.events.emit(|| RunEvent::Discovered {
+                migration_id: migration.id.to_string(),
+                repository: repository.full_name.to_string(),
+            });
+
+            if self.dry_run_level(migration).is_some() {
+                print_dry_run_preview(
+                    migration,
+                    core::slice::from_ref(&repository),
+                    &self.renderer,
+                );
+                continue;
+            }
+            let result = self.process_repository(&repository, migration).await;
+            self.progress.record(&result);
+            self.save_ledger();
+        }
+
+        self.save_etag_cache();
+        self.save_search_cache();
+        let summary = self.progress.snapshot();
+        #[cfg(feature = "history")]
+        {
+            let only_migrations: Vec<String> =
+                targets.iter().map(|t| t.migration_id.clone()).collect();
+            let only_repos: Vec<String> = targets.iter().map(|t| t.repository.clone()).collect();
+            self.record_history(&summary, &only_migrations, &only_repos);
+        }
+        Ok(summary)
+    }
+}
@@ -0,0 +1,85 @@
+//! Closing notifications for repositories that upgraded on their own.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::{discover_upgraded_repositories, verify_upgraded};
+use crate::events::RunEvent;
+use crate::issues::{close_notification, find_open_notifications};
+use tracing::{info, warn};
+
+impl Runner {
+    /// Closes our open issues and PRs in repositories that now contain the
+    /// migration's `new_string`, with a comment explaining why.
+    ///
+    /// Failures are logged and never fail the run. Returns how many
+    /// notifications were closed.
+    pub(super) async fn close_resolved(&self, migration: &Migration) -> usize {
+        let open = match find_open_notifications(&self.octocrab, migration).await {
+            Ok(open) => open,
+            Err(e) => {
+                warn!(migration_id = %migration.id, error = %e, "Failed to list open notifications");
+                return 0;
+            }
+        };
+        if open.is_empty() {
+            return 0;
+        }
+        let upgraded = match discover_upgraded_repositories(&self.octocrab, migration).await {
+            Ok(upgraded) => upgraded,
+            Err(e) => {
+                warn!(migration_id = %migration.id, error = %e, "Failed to discover upgraded repositories");
+                return 0;
+            }
+        };
+
+        let comment = format!(
+            "Resolved — this repository has been upgraded to `{}`. Closing automatically.",
+            migration.new_string
+        );
+        let mut closed = 0;
+        for repository in &upgraded {
+            if !self.repo_filter.is_empty() && !self.repo_filter.matches(&repository.full_name) {
+                continue;
+            }
+            let numbers: Vec<u64> = open
+                .iter()
+                .filter(|n| n.repository.eq_ignore_ascii_case(&repository.full_name))
+                .map(|n| n.number)
+                .collect();
+            if numbers.is_empty() {
+                continue;
+            }
+            // The search index may lag; only close once the default branch agrees.
+            match verify_upgraded(&self.octocrab, repository, migration).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!(repo = %repository.full_name, error = %e, "Failed to verify upgrade");
+                    continue;
+                }
+            }
+            for number in numbers {
+                match close_notification(&self.octocrab, repository, number, &comment).await {
+                    Ok(()) => {
+                        closed += 1;
+                        self.events.emit(|| RunEvent::Resolved {
+                            migration_id: migration.id.to_string(),
+                            repository: repository.full_name.to_string(),
+                            number,
+                        });
+                    }
+                    Err(e) => warn!(
+                        repo = %repository.full_name,
+                        number,
+                        error = %e,
+                        "Failed to close resolved notification"
+                    ),
+                }
+            }
+        }
+        if closed > 0 {
+            info!(migration_id = %migration.id, closed, "Closed notifications in upgraded repositories");
+        }
+        closed
+    }
+}
@@ -0,0 +1,162 @@
+//! Retrying repositories that failed with transient errors, once every
+//! migration has had its first pass.
+
+use super::repository::ISSUES_DISABLED_REASON;
+use super::{Deadline, Runner};
+use crate::config::Migration;
+use crate::discovery::{verify_on_default_branch, DiscoveredRepository, ALREADY_UPGRADED_REASON};
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::redact::redact;
+use crate::summary::{ProcessingResult, Stage, StageTimings, TimingRecord};
+use tracing::{info_span, warn, Instrument};
+
+/// A migration's first pass over its repositories, kept until the end of
+/// the run so its failures can be retried.
+pub(super) struct ProcessedMigration {
+    /// The migration that was processed.
+    pub migration: Migration,
+    /// Repositories it was processed for.
+    pub repositories: Vec<DiscoveredRepository>,
+    /// Latest result of each repository, replaced as retries finish.
+    pub results: Vec<ProcessingResult>,
+}
+
+impl Runner {
+    /// Re-processes repositories that failed with transient errors, after
+    /// every migration had its first pass.
+    ///
+    /// Each pass waits for the configured delay first, then replaces the failed
+    /// results with the outcome of the retry. A repository is only reported as
+    /// failed if every attempt failed. Repositories whose issue was filed but
+    /// whose auto-fix PR failed transiently only have the PR retried.
+    pub(super) async fn retry_transient_failures(
+        &self,
+        processed: &mut [ProcessedMigration],
+        deadline: Option<Deadline>,
+    ) {
+        for attempt in 1..=self.config.retry_attempts() {
+            let pending: usize = processed
+                .iter()
+                .flat_map(|run| &run.results)
+                .filter(|r| r.is_retryable() || r.has_retryable_pr())
+                .count();
+            if pending == 0 || self.should_stop(deadline) {
+                return;
+            }
+            warn!(
+                attempt,
+                count = pending,
+                delay_secs = self.config.retry_delay().as_secs(),
+                "Retrying repositories that failed with transient errors"
+            );
+            self.clock.sleep(self.config.retry_delay()).await;
+
+            for run in processed.iter_mut() {
+                let span = info_span!("migration", migration_id = %run.migration.id);
+                self.retry_migration(run, deadline).instrument(span).await;
+            }
+        }
+    }
+
+    /// Runs one retry pass over a migration's failed repositories.
+    async fn retry_migration(&self, run: &mut ProcessedMigration, deadline: Option<Deadline>) {
+        let failed_with = |predicate: fn(&ProcessingResult) -> bool| {
+            run.repositories
+                .iter()
+                .filter(|repo| {
+                    run.results
+                        .iter()
+                        .any(|r| predicate(r) && r.repository() == repo.full_name)
+                })
+                .cloned()
+                .collect::<Vec<DiscoveredRepository>>()
+        };
+        let retry = failed_with(ProcessingResult::is_retryable);
+        let retry_prs = failed_with(ProcessingResult::has_retryable_pr);
+
+        let migration = &run.migration;
+        for retried in self.process_repositories(migration, &retry, deadline).await {
+            if let Some(slot) = run
+                .results
+                .iter_mut()
+                .find(|r| r.repository() == retried.repository())
+            {
+                *slot = retried;
+            }
+        }
+        for repository in &retry_prs {
+            if self.should_stop(deadline) {
+                return;
+            }
+            if let Some(slot) = run
+                .results
+                .iter_mut()
+                .find(|r| r.repository() == repository.full_name)
+            {
+                self.retry_pr(repository, migration, slot).await;
+                self.progress.record(slot);
+                self.stream_result(slot).await;
+            }
+        }
+    }
+
+    /// Opens the auto-fix PR again for a repository whose PR failed, keeping
+    /// the issue already filed for it.
+    async fn retry_pr(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+        result: &mut ProcessingResult,
+    ) {
+        let (issue, pr) = match result {
+            ProcessingResult::Success {
+                issue,
+                pr: Some(pr),
+                ..
+            } => (issue.clone(), pr),
+            ProcessingResult::PrOnly { pr, .. } => (
+                IssueStatus::Skipped {
+                    reason: ISSUES_DISABLED_REASON.to_string(),
+                },
+                pr,
+            ),
+            _ => return,
+        };
+
+        let mut timings = StageTimings::default();
+        let verified = timings
+            .measure(
+                Stage::Dedupe,
+                verify_on_default_branch(&self.octocrab, repository, migration),
+            )
+            .await;
+        *pr = match verified {
+            Ok(Some(repository)) => {
+                let preferences = timings
+                    .measure(Stage::Dedupe, self.consumer_preferences(&repository))
+                    .await;
+                self.open_pr(&repository, migration, &issue, &preferences, &mut timings)
+                    .await
+            }
+            Ok(None) => PrStatus::Skipped {
+                reason: ALREADY_UPGRADED_REASON.to_string(),
+            },
+            Err(e) => PrStatus::Failed {
+                error: redact(&e.to_string()),
+                retryable: e.is_retryable(),
+            },
+        };
+        // The first attempt's stages stay counted alongside the retry's.
+        let mut stages = result.timings().clone();
+        stages.merge(&timings);
+        result.set_timings(stages.clone());
+        self.progress.update(|summary| {
+            summary.record_timings(TimingRecord {
+                migration_id: migration.id.to_string(),
+                repository: Some(repository.full_name.to_string()),
+                timings: stages,
+            });
+        });
+    }
+}
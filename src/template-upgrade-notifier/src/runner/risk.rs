@@ -0,0 +1,35 @@
+//! Holding back auto-fix PRs in risky repositories.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::risk::assess_risk;
+use tracing::{info, warn};
+
+impl Runner {
+    /// Returns why no auto-fix PR should be opened in `repository`, if its
+    /// risk score is above [`RunnerConfig::with_max_auto_pr_risk`](super::RunnerConfig::with_max_auto_pr_risk).
+    ///
+    /// A repository whose risk cannot be assessed is held back too, since
+    /// the threshold exists to keep PRs out of repositories not known to be safe.
+    pub(super) async fn risk_skip_reason(
+        &self,
+        repository: &DiscoveredRepository,
+        migration: &Migration,
+    ) -> Option<String> {
+        let max_risk = self.config.max_auto_pr_risk()?;
+        match assess_risk(&self.octocrab, repository, migration, self.clock.now()).await {
+            Ok(factors) => {
+                let score = factors.score();
+                (score > max_risk).then(|| {
+                    info!(repo = %repository.full_name, score, max_risk, "Risk too high for auto-PR");
+                    format!("risk score {score} above threshold {max_risk}")
+                })
+            }
+            Err(e) => {
+                warn!(repo = %repository.full_name, error = %e, "Failed to assess risk");
+                Some("risk score unavailable".to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Refreshing the rollout issue in the template's repository after a run.
+
+use super::Runner;
+use crate::issues::IssueStatus;
+use crate::ledger::Ledger;
+use crate::pull_requests::PrStatus;
+use crate::rollout::update_rollout_issue;
+use crate::summary::{ProcessingResult, RunSummary};
+use tracing::{info, warn};
+
+impl Runner {
+    /// Refreshes the `[rollout-issue]` with every repository notified so far.
+    ///
+    /// Lists the ledger's entries plus this run's results, so repositories
+    /// notified by earlier runs stay listed. Dry runs leave the issue
+    /// untouched. Failures are logged; they never fail an otherwise completed run.
+    pub(super) async fn update_rollout_issue(&self, summary: &RunSummary) {
+        let Some(config) = &self.notifier.rollout_issue else {
+            return;
+        };
+        if summary.dry_run {
+            return;
+        }
+        let notified = self.notified_so_far(summary);
+        if notified.entries().is_empty() {
+            return;
+        }
+        match update_rollout_issue(&self.octocrab, config, notified.entries()).await {
+            Ok((number, url)) => info!(number, url = %url, "Updated rollout issue"),
+            Err(e) => warn!(error = %e, "Failed to update rollout issue"),
+        }
+    }
+
+    /// Returns the ledger plus the issues, PRs and dispatches of this run.
+    pub(super) fn notified_so_far(&self, summary: &RunSummary) -> Ledger {
+        let mut notified = self.ledger().clone();
+        let now = self.clock.now().to_rfc3339();
+        for result in &summary.results {
+            record_result(&mut notified, result, &now);
+        }
+        notified
+    }
+}
+
+/// Adds the issue and PR created (or PR updated) for a result, if any.
+fn record_result(ledger: &mut Ledger, result: &ProcessingResult, now: &str) {
+    let (issue, pr) = match result {
+        ProcessingResult::Success { issue, pr, .. } => (Some(issue), pr.as_ref()),
+        ProcessingResult::PrOnly { pr, .. } => (None, Some(pr)),
+        ProcessingResult::Dispatched { .. } => {
+            ledger.record_dispatch(result.migration_id(), result.repository(), now);
+            return;
+        }
+        ProcessingResult::Skipped { .. } | ProcessingResult::Failed { .. } => return,
+    };
+    let (migration_id, repository) = (result.migration_id(), result.repository());
+    if let Some(IssueStatus::Created { number, .. }) = issue {
+        ledger.record_issue(migration_id, repository, *number, now);
+    }
+    if let Some(PrStatus::Created { number, .. } | PrStatus::Updated { number, .. }) = pr {
+        ledger.record_pr(migration_id, repository, *number, now);
+    }
+}
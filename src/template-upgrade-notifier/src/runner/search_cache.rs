@@ -0,0 +1,44 @@
+//! Loading and saving the code search cache around a run.
+
+use super::Runner;
+use crate::discovery::{
+    disable_search_cache, load_search_cache, reset_search_cache, save_search_cache,
+};
+use tracing::{debug, info, warn};
+
+impl Runner {
+    /// Enables the code search cache, loading the searches cached by earlier
+    /// runs unless the cache is to be cleared.
+    ///
+    /// Like the ETag cache, an unreadable cache is logged and the run starts
+    /// with an empty one.
+    pub(super) fn load_search_cache(&self) {
+        let Some(path) = self.config.search_cache_path() else {
+            disable_search_cache();
+            return;
+        };
+        let ttl = self.config.search_cache_ttl();
+        if self.config.clear_search_cache() {
+            info!(path = %path.display(), "Cleared code search cache");
+            reset_search_cache(ttl);
+            return;
+        }
+        match load_search_cache(&path, ttl, self.config.refresh_search_cache()) {
+            Ok(entries) => debug!(entries, path = %path.display(), "Loaded code search cache"),
+            Err(e) => {
+                warn!(error = %e, "Failed to load code search cache, starting empty");
+                reset_search_cache(ttl);
+            }
+        }
+    }
+
+    /// Saves the code searches of this and earlier runs still within their TTL.
+    pub(super) fn save_search_cache(&self) {
+        let Some(path) = self.config.search_cache_path() else {
+            return;
+        };
+        if let Err(e) = save_search_cache(&path) {
+            warn!(error = %e, "Failed to save code search cache");
+        }
+    }
+}
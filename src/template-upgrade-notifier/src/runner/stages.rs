@@ -0,0 +1,108 @@
+//! Narrowing a migration to its current staged rollout percentage or a
+//! spot-check sample.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use crate::stages::{in_rollout, RolloutPercent, StageState};
+use crate::summary::ProcessingResult;
+use tracing::{info, warn};
+
+impl Runner {
+    /// Keeps only the configured sample of the migration's repositories.
+    pub(super) fn apply_sample(
+        &self,
+        migration: &Migration,
+        repositories: &mut Vec<DiscoveredRepository>,
+    ) {
+        let Some(sample) = self.config.sample() else {
+            return;
+        };
+        let before = repositories.len();
+        sample.apply(repositories, &migration.id, |repo| repo.full_name.as_str());
+        info!(
+            migration_id = %migration.id,
+            size = sample.size,
+            seed = sample.seed,
+            kept = repositories.len(),
+            excluded = before - repositories.len(),
+            "Applied repository sample"
+        );
+    }
+
+    /// Keeps only the repositories within the migration's `rollout-percent`.
+    ///
+    /// Staged rollouts use the percentage of their current stage. An
+    /// unreadable state file is logged and the first stage is used, so a
+    /// broken file never widens a rollout.
+    pub(super) fn apply_rollout_percent(
+        &self,
+        migration: &Migration,
+        repositories: &mut Vec<DiscoveredRepository>,
+    ) {
+        let Some(rollout) = &migration.rollout_percent else {
+            return;
+        };
+        let state = match self.config.rollout_state_path().map(StageState::load) {
+            Some(Ok(state)) => state,
+            Some(Err(e)) => {
+                warn!(error = %e, "Failed to read rollout state, using the first stage");
+                StageState::default()
+            }
+            None => StageState::default(),
+        };
+        let stage = state.stage(&migration.id);
+        let percent = rollout.at_stage(stage);
+
+        let before = repositories.len();
+        repositories.retain(|repo| in_rollout(&repo.full_name, percent));
+        info!(
+            migration_id = %migration.id,
+            percent,
+            stage,
+            kept = repositories.len(),
+            excluded = before - repositories.len(),
+            "Applied rollout percentage"
+        );
+    }
+
+    /// Moves a staged rollout on to its next stage for the following run.
+    ///
+    /// Called after a live run processed `migration`. The stage only advances
+    /// if every one of its `repositories` has a result and none failed, and
+    /// the run was neither stopped early nor narrowed by a repository
+    /// selection or sample. An unreadable state file is never overwritten.
+    pub(super) fn advance_rollout(
+        &self,
+        migration: &Migration,
+        repositories: &[DiscoveredRepository],
+        results: &[ProcessingResult],
+    ) {
+        if !matches!(migration.rollout_percent, Some(RolloutPercent::Stages(_))) {
+            return;
+        }
+        let Some(path) = self.config.rollout_state_path() else {
+            return;
+        };
+        let narrowed = !self.repo_filter.is_empty() || self.config.sample().is_some();
+        let stopped = self.is_cancelled() || self.progress.read(|summary| summary.aborted);
+        let failed = results
+            .iter()
+            .any(|result| matches!(result, ProcessingResult::Failed { .. }));
+        if narrowed || stopped || failed || results.len() < repositories.len() {
+            info!(migration_id = %migration.id, "Keeping the rollout at its current stage");
+            return;
+        }
+        let mut state = match StageState::load(path) {
+            Ok(state) => state,
+            Err(e) => {
+                warn!(error = %e, "Failed to read rollout state, not advancing");
+                return;
+            }
+        };
+        state.advance(&migration.id);
+        if let Err(e) = state.save(path) {
+            warn!(error = %e, "Failed to write rollout state");
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Pulling the run's state files from the state store and pushing them back.
+
+use super::{Runner, RunnerError};
+use crate::state::{StateError, StateVersion, CHECKPOINT_KEY, LEDGER_KEY};
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::{MutexGuard, PoisonError};
+use tracing::{debug, error, info, warn};
+
+impl Runner {
+    /// Replaces the local state files with those in the state store, if one
+    /// is configured. Files the store doesn't have are left as they are.
+    ///
+    /// The version of each file is remembered, so [`push_state`](Self::push_state)
+    /// only replaces files no other run has written since.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::State`] if the store cannot be read or a file
+    /// cannot be written, since running on stale state could notify
+    /// repositories twice.
+    pub(super) async fn pull_state(&self) -> Result<(), RunnerError> {
+        let Some(store) = &self.state_store else {
+            return Ok(());
+        };
+        let mut versions = BTreeMap::new();
+        for (key, path) in self.state_files() {
+            let Some(stored) = store.get(key).await? else {
+                debug!(key, "No stored state");
+                continue;
+            };
+            write_state_file(path, &stored.bytes).map_err(|source| StateError::Io {
+                path: path.display().to_string(),
+                source,
+            })?;
+            versions.insert(key, stored.version);
+            info!(key, path = %path.display(), "Pulled state");
+        }
+        *self.versions() = versions;
+        Ok(())
+    }
+
+    /// Uploads the local state files to the state store, if one is configured.
+    ///
+    /// Other failures are logged, and the files stay on disk for a later
+    /// attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::State`] with [`StateError::Conflict`] if another
+    /// run wrote a file after it was pulled; that file is not overwritten.
+    pub(super) async fn push_state(&self) -> Result<(), RunnerError> {
+        let Some(store) = &self.state_store else {
+            return Ok(());
+        };
+        let mut conflict = None;
+        for (key, path) in self.state_files() {
+            let bytes = match tokio::fs::read(path).await {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => {
+                    warn!(key, path = %path.display(), error = %e, "Failed to read state");
+                    continue;
+                }
+            };
+            let expected = self.versions().get(key).cloned();
+            match store.put(key, bytes, expected.as_ref()).await {
+                Ok(version) => {
+                    self.versions().insert(key, version);
+                    info!(key, "Pushed state");
+                }
+                Err(e @ StateError::Conflict { .. }) => {
+                    error!(
+                        key,
+                        path = %path.display(),
+                        "State changed by another run; kept local copy"
+                    );
+                    conflict.get_or_insert(e);
+                }
+                Err(e) => warn!(key, error = %e, "Failed to push state"),
+            }
+        }
+        conflict.map_or(Ok(()), |e| Err(e.into()))
+    }
+
+    /// Returns the versions of the state files last pulled or pushed.
+    fn versions(&self) -> MutexGuard<'_, BTreeMap<&'static str, StateVersion>> {
+        self.state_versions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the state files in use, with the keys they are stored under.
+    fn state_files(&self) -> Vec<(&'static str, &Path)> {
+        let mut files = Vec::with_capacity(3);
+        if let Some(path) = self.config.ledger_path() {
+            files.push((LEDGER_KEY, path));
+        }
+        files.push((CHECKPOINT_KEY, self.config.checkpoint_path()));
+        #[cfg(feature = "history")]
+        if let Some(path) = self.config.history_path() {
+            files.push((crate::state::HISTORY_KEY, path));
+        }
+        files
+    }
+}
+
+/// Writes a pulled state file, creating its directory if needed.
+fn write_state_file(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NotifierConfig;
+    use crate::runner::RunnerConfig;
+    use crate::state::{LocalStateStore, StateStore};
+    use octocrab::Octocrab;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn pulls_and_pushes_state_files() {
+        let temp = TempDir::new().unwrap();
+        let store = LocalStateStore::new(temp.path().join("store"));
+        store
+            .put(LEDGER_KEY, b"{\"entries\":[]}".to_vec(), None)
+            .await
+            .unwrap();
+        let ledger = temp.path().join("work/ledger.json");
+        let config = RunnerConfig::new(
+            temp.path().join("migrations"),
+            String::new(),
+            false,
+            1,
+            false,
+        )
+        .with_ledger_path(Some(ledger.clone()))
+        .with_checkpoint_path(temp.path().join("work/checkpoint.json"));
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let runner = Runner::builder(config)
+            .octocrab(Octocrab::builder().build().unwrap())
+            .notifier(NotifierConfig::default())
+            .state_store(store.clone())
+            .build()
+            .unwrap();
+
+        runner.pull_state().await.unwrap();
+        assert_eq!(std::fs::read(&ledger).unwrap(), b"{\"entries\":[]}");
+
+        std::fs::write(&ledger, b"{}").unwrap();
+        runner.push_state().await.unwrap();
+        let stored = store.get(LEDGER_KEY).await.unwrap().unwrap();
+        assert_eq!(stored.bytes, b"{}");
+        assert_eq!(store.get(CHECKPOINT_KEY).await.unwrap(), None);
+
+        // Another run pushes in between; ours must not overwrite it.
+        store
+            .put(
+                LEDGER_KEY,
+                b"{\"theirs\":1}".to_vec(),
+                Some(&stored.version),
+            )
+            .await
+            .unwrap();
+        std::fs::write(&ledger, b"{\"ours\":1}").unwrap();
+        assert!(matches!(
+            runner.push_state().await,
+            Err(RunnerError::State(StateError::Conflict { .. }))
+        ));
+        let stored = store.get(LEDGER_KEY).await.unwrap().unwrap();
+        assert_eq!(stored.bytes, b"{\"theirs\":1}");
+    }
+}
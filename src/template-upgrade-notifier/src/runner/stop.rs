@@ -0,0 +1,49 @@
+//! Deciding when a run stops starting new repositories.
+
+use super::{Deadline, Runner};
+use tracing::error;
+
+/// Error reported for repositories abandoned when the run was cancelled.
+pub(super) const CANCELLED_REASON: &str = "run cancelled";
+
+impl Runner {
+    /// Marks the run as aborted if the results so far exceed the
+    /// `[abort-threshold]` from `notifier.toml`.
+    ///
+    /// Called after every recorded result; repositories already in flight
+    /// still complete, but no new one is started.
+    pub(super) fn check_abort_threshold(&self) {
+        let Some(threshold) = &self.notifier.abort_threshold else {
+            return;
+        };
+        self.progress.update(|summary| {
+            if summary.aborted || !threshold.is_exceeded(summary) {
+                return;
+            }
+            summary.aborted = true;
+            error!(
+                failure_percent = threshold.failure_percent,
+                processed = summary.results.len(),
+                "Abort threshold exceeded, stopping the run"
+            );
+        });
+    }
+
+    /// Returns true once the run should start no further repository, because
+    /// its deadline passed, it was aborted or it was cancelled.
+    pub(super) fn should_stop(&self, deadline: Option<Deadline>) -> bool {
+        self.is_expired(deadline)
+            || self.is_cancelled()
+            || self.progress.read(|summary| summary.aborted)
+    }
+
+    /// Returns true once the run's cancellation token was cancelled.
+    pub(super) fn is_cancelled(&self) -> bool {
+        self.config.cancellation_token().is_cancelled()
+    }
+
+    /// Returns true once the run's deadline, if any, has passed.
+    pub(super) fn is_expired(&self, deadline: Option<Deadline>) -> bool {
+        deadline.is_some_and(|deadline| self.clock.now() >= deadline)
+    }
+}
@@ -0,0 +1,52 @@
+//! Consuming a run's results as a stream.
+
+use super::Runner;
+use crate::summary::ProcessingResult;
+use core::future;
+use futures::channel::mpsc;
+use futures::stream::{self, Stream, StreamExt};
+use futures::SinkExt;
+use std::sync::{MutexGuard, PoisonError};
+use tracing::error;
+
+impl Runner {
+    /// Executes the full orchestration flow like [`Runner::run`], yielding each
+    /// repository's result as it completes.
+    ///
+    /// The run only advances while the stream is polled, and at most
+    /// [`concurrency`](crate::RunnerConfig::concurrency) results wait to be
+    /// read, so a slow consumer holds the run back. Dropping the stream stops
+    /// the run; to stop it gracefully instead, cancel the
+    /// [`cancellation_token`](crate::RunnerConfig::with_cancellation_token)
+    /// and read the stream to its end. A run that fails is logged and ends the
+    /// stream; [`Runner::progress`] still holds the results recorded so far.
+    pub fn run_stream(&self) -> impl Stream<Item = ProcessingResult> + '_ {
+        let (sender, receiver) = mpsc::channel(self.config.concurrency().max(1));
+        *self.result_sender() = Some(sender);
+        let run = async move {
+            if let Err(e) = self.run().await {
+                error!(error = %e, "Streamed run failed");
+            }
+            // Dropping the sender ends the stream once its results are read.
+            self.result_sender().take();
+            None
+        };
+        stream::select(stream::once(run).filter_map(future::ready), receiver)
+    }
+
+    /// Sends `result` to the [`Runner::run_stream`] consumer, if there is one,
+    /// waiting while its buffer is full.
+    pub(super) async fn stream_result(&self, result: &ProcessingResult) {
+        let sender = self.result_sender().clone();
+        if let Some(mut sender) = sender {
+            // A closed channel only means the consumer stopped reading.
+            let _ = sender.send(result.clone()).await;
+        }
+    }
+
+    fn result_sender(&self) -> MutexGuard<'_, Option<mpsc::Sender<ProcessingResult>>> {
+        self.result_sender
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,59 @@
+//! Validating a migration's templates before any repository is touched.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::ids::HtmlUrl;
+use crate::pull_requests::PrStatus;
+use crate::templates::TemplateError;
+use tracing::error;
+
+/// PR link used to render the "PR created" variant of issue templates.
+const SAMPLE_PR_LINK: &str = "https://github.com/owner/repo/pull/1";
+
+impl Runner {
+    /// Renders the migration's issue (with and without a created PR) and PR
+    /// templates once, so a template that fails to render, exceeds GitHub's
+    /// size limit or fails the markdown lint stops the migration before
+    /// anything is posted.
+    ///
+    /// Returns false, after logging the error, if the migration must be skipped.
+    pub(super) fn templates_valid(&self, migration: &Migration) -> bool {
+        match self.render_samples(migration) {
+            Ok(()) => true,
+            Err(e) => {
+                error!(
+                    migration_id = %migration.id,
+                    error = %e,
+                    "Invalid template, skipping migration"
+                );
+                false
+            }
+        }
+    }
+
+    fn render_samples(&self, migration: &Migration) -> Result<(), TemplateError> {
+        let created = PrStatus::Created {
+            number: 1,
+            url: HtmlUrl::new(SAMPLE_PR_LINK).expect("sample link is valid"),
+        };
+        self.renderer.render_issue_template(
+            &migration.issue_template,
+            migration,
+            None,
+            None,
+            None,
+        )?;
+        self.renderer.render_issue_template(
+            &migration.issue_template,
+            migration,
+            None,
+            Some(&created),
+            Some(SAMPLE_PR_LINK),
+        )?;
+        if self.config.auto_pr() {
+            self.renderer
+                .render_pr_template(&migration.pr_template, migration, None)?;
+        }
+        Ok(())
+    }
+}
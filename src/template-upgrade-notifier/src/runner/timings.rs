@@ -0,0 +1,43 @@
+//! Timing of the run's per-migration stages.
+
+use super::Runner;
+use crate::config::Migration;
+use crate::discovery::{
+    discover_from_list, discover_repositories_graphql, DiscoveredRepository, DiscoveryError,
+};
+use crate::summary::{Stage, StageTimings, TimingRecord};
+
+impl Runner {
+    /// Discovers the migration's repositories with their default branch,
+    /// archived flag and visibility, recording how long the code search and
+    /// metadata lookups took in the run's progress.
+    ///
+    /// With a static repository list, the listed repositories are checked
+    /// instead of searching.
+    ///
+    /// Cancelling the run abandons the search with [`DiscoveryError::Cancelled`].
+    pub(super) async fn discover_timed(
+        &self,
+        migration: &Migration,
+    ) -> Result<Vec<DiscoveredRepository>, DiscoveryError> {
+        let mut timings = StageTimings::default();
+        let discover = timings.measure(Stage::Discovery, async {
+            match &self.repo_list {
+                Some(list) => discover_from_list(&self.octocrab, list, migration).await,
+                None => discover_repositories_graphql(&self.octocrab, migration).await,
+            }
+        });
+        let discovered = tokio::select! {
+            discovered = discover => discovered,
+            () = self.config.cancellation_token().cancelled() => Err(DiscoveryError::Cancelled),
+        };
+        self.progress.update(|summary| {
+            summary.record_timings(TimingRecord {
+                migration_id: migration.id.to_string(),
+                repository: None,
+                timings,
+            });
+        });
+        discovered
+    }
+}
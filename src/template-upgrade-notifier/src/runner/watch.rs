@@ -0,0 +1,67 @@
+//! Running on a schedule until shut down.
+
+use super::{Runner, RunnerError};
+use crate::schedule::{Schedule, ScheduleError};
+use crate::summary::RunSummary;
+use core::future::Future;
+use tracing::{error, info};
+
+impl Runner {
+    /// Runs repeatedly on the `[schedule]` from `notifier.toml` until `shutdown` resolves.
+    ///
+    /// Migrations are rescanned at the start of every iteration, so edits to the
+    /// migrations directory take effect without restarting. A failed iteration is
+    /// logged and the loop continues with the next slot. Cancelling the
+    /// run's cancellation token stops the iteration in progress, which then
+    /// completes as a cancelled run, and ends the loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `shutdown` - Future that stops the loop when it completes (e.g., Ctrl-C)
+    /// * `on_complete` - Called with the summary of every successful iteration
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunnerError::Schedule`] if no valid schedule is configured.
+    pub async fn watch<F, C>(&self, shutdown: F, mut on_complete: C) -> Result<(), RunnerError>
+    where
+        F: Future<Output = ()>,
+        C: FnMut(&RunSummary),
+    {
+        let schedule_config = self
+            .notifier
+            .schedule
+            .as_ref()
+            .ok_or(ScheduleError::NotConfigured)?;
+        let schedule = Schedule::from_config(schedule_config)?;
+        tokio::pin!(shutdown);
+
+        let mut wait = !schedule_config.run_on_start;
+        loop {
+            if wait {
+                let delay = schedule.delay_after(self.clock.now())?;
+                info!(
+                    delay_secs = delay.as_secs(),
+                    "Waiting for next scheduled run"
+                );
+                tokio::select! {
+                    () = &mut shutdown => return Ok(()),
+                    () = self.config.cancellation_token().cancelled() => return Ok(()),
+                    () = self.clock.sleep(delay) => {}
+                }
+            }
+            wait = true;
+
+            tokio::select! {
+                () = &mut shutdown => return Ok(()),
+                result = self.run() => match result {
+                    Ok(summary) => on_complete(&summary),
+                    Err(e) => error!(error = %e, "Scheduled run failed"),
+                },
+            }
+            if self.is_cancelled() {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Schedule error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while building a run schedule.
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    /// No `[schedule]` section was configured.
+    #[error("No [schedule] section configured in notifier.toml")]
+    NotConfigured,
+
+    /// Neither or both of `cron` and `interval-secs` were set.
+    #[error("[schedule] must set exactly one of `cron` or `interval-secs`")]
+    Ambiguous,
+
+    /// The interval was zero.
+    #[error("[schedule] interval-secs must be greater than zero")]
+    ZeroInterval,
+
+    /// The cron expression could not be parsed.
+    #[error("Invalid cron expression '{expression}': {message}")]
+    InvalidCron { expression: String, message: String },
+
+    /// The cron expression has no future occurrences.
+    #[error("Cron expression '{0}' has no upcoming occurrences")]
+    Exhausted(String),
+}
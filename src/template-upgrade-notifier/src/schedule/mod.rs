@@ -0,0 +1,175 @@
+//! Scheduling for the long-running watch mode.
+//!
+//! A [`Schedule`] is built from the `[schedule]` section of `notifier.toml`
+//! and tells the runner how long to sleep between iterations.
+
+mod error;
+
+pub use error::ScheduleError;
+
+use core::str::FromStr;
+use core::time::Duration;
+use serde::Deserialize;
+
+/// The `[schedule]` section of `notifier.toml`.
+///
+/// Exactly one of `cron` or `interval-secs` must be set.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScheduleConfig {
+    /// Cron expression with seconds, in UTC (e.g., `"0 0 6 * * Mon"`).
+    pub cron: Option<String>,
+
+    /// Fixed delay between the end of one run and the start of the next.
+    pub interval_secs: Option<u64>,
+
+    /// Whether to run immediately on startup instead of waiting for the first slot.
+    #[serde(default = "default_run_on_start")]
+    pub run_on_start: bool,
+}
+
+fn default_run_on_start() -> bool {
+    true
+}
+
+/// When the runner should wake up next.
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// Cron-driven schedule evaluated in UTC.
+    Cron {
+        /// Original expression, kept for logging.
+        expression: String,
+        /// Parsed schedule.
+        schedule: Box<cron::Schedule>,
+    },
+
+    /// Fixed delay between runs.
+    Interval(Duration),
+}
+
+impl Schedule {
+    /// Builds a schedule from its configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScheduleError`] if the configuration is ambiguous or the cron
+    /// expression is invalid.
+    pub fn from_config(config: &ScheduleConfig) -> Result<Self, ScheduleError> {
+        match (&config.cron, config.interval_secs) {
+            (Some(expression), None) => {
+                let schedule = cron::Schedule::from_str(expression).map_err(|e| {
+                    ScheduleError::InvalidCron {
+                        expression: expression.clone(),
+                        message: e.to_string(),
+                    }
+                })?;
+                Ok(Self::Cron {
+                    expression: expression.clone(),
+                    schedule: Box::new(schedule),
+                })
+            }
+            (None, Some(0)) => Err(ScheduleError::ZeroInterval),
+            (None, Some(secs)) => Ok(Self::Interval(Duration::from_secs(secs))),
+            _ => Err(ScheduleError::Ambiguous),
+        }
+    }
+
+    /// Returns how long to wait before the next run.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScheduleError::Exhausted`] if a cron schedule has no future slot.
+    pub fn delay_until_next(&self) -> Result<Duration, ScheduleError> {
+        self.delay_after(chrono::Utc::now())
+    }
+
+    /// Returns how long to wait, starting at `now`, before the next run.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScheduleError::Exhausted`] if a cron schedule has no slot after `now`.
+    pub fn delay_after(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Duration, ScheduleError> {
+        match self {
+            Self::Interval(delay) => Ok(*delay),
+            Self::Cron {
+                expression,
+                schedule,
+            } => {
+                let next = schedule
+                    .after(&now)
+                    .next()
+                    .ok_or_else(|| ScheduleError::Exhausted(expression.clone()))?;
+                Ok((next - now).to_std().unwrap_or_default())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config(cron: Option<&str>, interval_secs: Option<u64>) -> ScheduleConfig {
+        ScheduleConfig {
+            cron: cron.map(str::to_string),
+            interval_secs,
+            run_on_start: true,
+        }
+    }
+
+    #[test]
+    fn builds_interval_schedule() {
+        let schedule = Schedule::from_config(&config(None, Some(60))).unwrap();
+        assert_eq!(
+            schedule.delay_until_next().unwrap(),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn builds_cron_schedule() {
+        let schedule = Schedule::from_config(&config(Some("0 0 * * * *"), None)).unwrap();
+        let delay = schedule.delay_until_next().unwrap();
+        assert!(delay <= Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn cron_delay_is_relative_to_given_time() {
+        let schedule = Schedule::from_config(&config(Some("0 0 * * * *"), None)).unwrap();
+        let now = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 10, 30, 0).unwrap();
+        assert_eq!(
+            schedule.delay_after(now).unwrap(),
+            Duration::from_secs(30 * 60)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_cron() {
+        let result = Schedule::from_config(&config(Some("not a cron"), None));
+        assert!(matches!(result, Err(ScheduleError::InvalidCron { .. })));
+    }
+
+    #[test]
+    fn rejects_ambiguous_config() {
+        assert!(matches!(
+            Schedule::from_config(&config(Some("0 0 * * * *"), Some(60))),
+            Err(ScheduleError::Ambiguous)
+        ));
+        assert!(matches!(
+            Schedule::from_config(&config(None, None)),
+            Err(ScheduleError::Ambiguous)
+        ));
+    }
+
+    #[test]
+    fn rejects_zero_interval() {
+        assert!(matches!(
+            Schedule::from_config(&config(None, Some(0))),
+            Err(ScheduleError::ZeroInterval)
+        ));
+    }
+}
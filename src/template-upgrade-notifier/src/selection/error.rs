@@ -0,0 +1,20 @@
+//! Selection error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while compiling selection patterns or loading a
+/// priority list.
+#[derive(Debug, Error)]
+pub enum SelectionError {
+    /// A glob pattern could not be parsed.
+    #[error("Invalid glob pattern '{pattern}': {message}")]
+    InvalidPattern { pattern: String, message: String },
+
+    /// The priority list could not be read.
+    #[error("Failed to read priority list '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
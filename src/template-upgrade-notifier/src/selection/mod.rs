@@ -0,0 +1,117 @@
+//! Glob-based selection of migrations and repositories, seeded samples of
+//! them, and the order in which repositories are processed.
+
+mod error;
+mod priority;
+mod sample;
+
+pub use error::SelectionError;
+pub use priority::PriorityList;
+pub use sample::Sample;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// A compiled set of glob patterns.
+///
+/// An empty set matches everything, so an unset filter selects all items.
+#[derive(Debug, Clone, Default)]
+pub struct PatternSet {
+    set: Option<GlobSet>,
+}
+
+impl PatternSet {
+    /// Compiles a list of glob patterns.
+    ///
+    /// Matching is case-insensitive, since GitHub owner and repository names are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SelectionError::InvalidPattern`] if any pattern is invalid.
+    pub fn new<I, S>(patterns: I) -> Result<Self, SelectionError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut builder = GlobSetBuilder::new();
+        let mut any = false;
+        for pattern in patterns {
+            let pattern = pattern.as_ref();
+            let glob = GlobBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| SelectionError::InvalidPattern {
+                    pattern: pattern.to_string(),
+                    message: e.to_string(),
+                })?;
+            builder.add(glob);
+            any = true;
+        }
+        if !any {
+            return Ok(Self::default());
+        }
+        let set = builder
+            .build()
+            .map_err(|e| SelectionError::InvalidPattern {
+                pattern: String::new(),
+                message: e.to_string(),
+            })?;
+        Ok(Self { set: Some(set) })
+    }
+
+    /// Returns true if no patterns were configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.set.is_none()
+    }
+
+    /// Returns true if `value` matches any pattern, or the set is empty.
+    #[must_use]
+    pub fn matches(&self, value: &str) -> bool {
+        self.set.as_ref().is_none_or(|set| set.is_match(value))
+    }
+
+    /// Returns true if the set is non-empty and `value` matches a pattern.
+    ///
+    /// Use this for exclusion lists, where an empty set must exclude nothing.
+    #[must_use]
+    pub fn matches_any(&self, value: &str) -> bool {
+        self.set.as_ref().is_some_and(|set| set.is_match(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_set_matches_everything() {
+        let set = PatternSet::new(Vec::<String>::new()).unwrap();
+        assert!(set.is_empty());
+        assert!(set.matches("anything"));
+        assert!(!set.matches_any("anything"));
+    }
+
+    #[test]
+    fn matches_repository_globs() {
+        let set = PatternSet::new(["my-org/*", "friend/repo"]).unwrap();
+        assert!(set.matches("my-org/service"));
+        assert!(set.matches("My-Org/Service"));
+        assert!(set.matches("friend/repo"));
+        assert!(!set.matches("friend/other"));
+    }
+
+    #[test]
+    fn matches_migration_id_globs() {
+        let set = PatternSet::new(["acme-template/*"]).unwrap();
+        assert!(set.matches("acme-template/v1.0.0-to-v1.0.1"));
+        assert!(!set.matches("other-template/v1.0.0-to-v1.0.1"));
+    }
+
+    #[test]
+    fn rejects_invalid_pattern() {
+        assert!(matches!(
+            PatternSet::new(["[unclosed"]),
+            Err(SelectionError::InvalidPattern { .. })
+        ));
+    }
+}
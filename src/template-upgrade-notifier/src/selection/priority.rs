@@ -0,0 +1,88 @@
+//! Repositories processed ahead of the rest of a migration.
+
+use super::SelectionError;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// An ordered list of `owner/name` repositories to process first.
+///
+/// Loaded from a text file with one repository per line; blank lines and
+/// lines starting with `#` are ignored. Names are compared case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PriorityList {
+    repositories: Vec<String>,
+}
+
+impl PriorityList {
+    /// Parses a priority list from the file's text.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let repositories = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_lowercase)
+            .collect();
+        Self { repositories }
+    }
+
+    /// Loads a priority list, returning an empty one if the file doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SelectionError::Io`] if the file exists but cannot be read.
+    pub fn load(path: &Path) -> Result<Self, SelectionError> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(SelectionError::Io {
+                path: path.display().to_string(),
+                source,
+            }),
+        }
+    }
+
+    /// Returns true if no repositories are listed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty()
+    }
+
+    /// Returns the position of `repository` in the list, if it is listed.
+    #[must_use]
+    pub fn rank(&self, repository: &str) -> Option<usize> {
+        self.repositories
+            .iter()
+            .position(|listed| listed.eq_ignore_ascii_case(repository))
+    }
+
+    /// Moves listed items to the front, in list order; the rest keep their order.
+    pub fn prioritize<T>(&self, items: &mut [T], name: impl Fn(&T) -> &str) {
+        if self.is_empty() {
+            return;
+        }
+        items.sort_by_key(|item| self.rank(name(item)).unwrap_or(usize::MAX));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_listed_repositories_first() {
+        let list = PriorityList::parse("# flagship consumers\nOrg/Main\n\norg/docs\n");
+        let mut repos = vec!["user/a", "org/docs", "user/b", "org/main"];
+
+        list.prioritize(&mut repos, |repo| repo);
+
+        assert_eq!(repos, ["org/main", "org/docs", "user/a", "user/b"]);
+    }
+
+    #[test]
+    fn load_returns_empty_when_missing() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let list = PriorityList::load(&temp.path().join("priority.txt")).unwrap();
+        assert!(list.is_empty());
+    }
+}
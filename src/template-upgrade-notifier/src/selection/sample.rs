@@ -0,0 +1,121 @@
+//! Seeded random samples of a migration's repositories, for spot checks.
+
+use serde::{Deserialize, Serialize};
+
+/// Picks a reproducible random sample of `size` repositories.
+///
+/// Each repository is ranked by a (finalized) FNV-1a hash of the seed, the `scope`
+/// (typically the migration id) and its lowercased `owner/name`; the `size`
+/// lowest ranks are kept. The same seed and repositories always give the same
+/// sample, while a different seed or scope gives an independent one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sample {
+    /// Repositories to keep.
+    pub size: usize,
+    /// Seed the sample is drawn with.
+    pub seed: u64,
+}
+
+impl Sample {
+    /// Keeps `size` items drawn from `items`, in their original order.
+    ///
+    /// Does nothing if there are no more than `size` items.
+    pub fn apply<T>(&self, items: &mut Vec<T>, scope: &str, name: impl Fn(&T) -> &str) {
+        if items.len() <= self.size {
+            return;
+        }
+        let mut ranked: Vec<(u64, usize)> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (self.rank(scope, name(item)), index))
+            .collect();
+        ranked.sort_unstable();
+        let mut keep = vec![false; items.len()];
+        for &(_, index) in ranked.iter().take(self.size) {
+            keep[index] = true;
+        }
+        let mut keep = keep.into_iter();
+        items.retain(|_| keep.next().unwrap_or(false));
+    }
+
+    /// Returns the repository's rank within the sample's ordering.
+    fn rank(&self, scope: &str, repository: &str) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let fnv = |hash: u64, bytes: &[u8]| {
+            bytes.iter().fold(hash, |hash, &byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+            })
+        };
+        let hash = fnv(OFFSET, &self.seed.to_le_bytes());
+        let hash = fnv(hash, scope.as_bytes());
+        // Separates the scope from the name, so "a" + "b/c" and "ab" + "/c" differ.
+        let hash = fnv(hash, &[0]);
+        finalize(fnv(hash, repository.to_lowercase().as_bytes()))
+    }
+}
+
+/// Mixes every input bit into every output bit (the `fmix64` step of
+/// MurmurHash3); FNV-1a alone leaves hashes that differ only in the seed
+/// ordered almost alike.
+fn finalize(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repositories() -> Vec<String> {
+        (0..50).map(|i| format!("org/repo-{i}")).collect()
+    }
+
+    #[test]
+    fn keeps_size_items_in_order() {
+        let mut repos = repositories();
+        Sample { size: 5, seed: 7 }.apply(&mut repos, "m", |repo| repo);
+
+        assert_eq!(repos.len(), 5);
+        let positions: Vec<usize> = repos
+            .iter()
+            .map(|repo| repositories().iter().position(|r| r == repo).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn same_seed_gives_same_sample() {
+        let sample = Sample { size: 5, seed: 42 };
+        let mut first = repositories();
+        let mut second = repositories();
+        second.reverse();
+        sample.apply(&mut first, "m", |repo| repo);
+        sample.apply(&mut second, "m", |repo| repo);
+        second.reverse();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn seed_and_scope_change_the_sample() {
+        let draw = |seed, scope| {
+            let mut repos = repositories();
+            Sample { size: 10, seed }.apply(&mut repos, scope, |repo| repo);
+            repos
+        };
+
+        assert_ne!(draw(1, "m"), draw(2, "m"));
+        assert_ne!(draw(1, "m"), draw(1, "n"));
+    }
+
+    #[test]
+    fn small_lists_are_kept_whole() {
+        let mut repos = vec!["a/b".to_string(), "c/d".to_string()];
+        Sample { size: 5, seed: 0 }.apply(&mut repos, "m", |repo| repo);
+        assert_eq!(repos.len(), 2);
+    }
+}
@@ -0,0 +1,23 @@
+//! Staged rollout error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while reading or writing the rollout stage state.
+#[derive(Debug, Error)]
+pub enum StageError {
+    /// The state file could not be read or written.
+    #[error("Failed to access rollout state '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The state file is not valid JSON.
+    #[error("Invalid rollout state '{path}': {source}")]
+    Json {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
@@ -0,0 +1,16 @@
+//! Percentage-based staged rollouts.
+//!
+//! Like a feature flag rollout, a migration can notify only a share of the
+//! repositories it discovers (`rollout-percent`). Repositories are picked by a
+//! stable hash of their name, so the same ones are picked on every run and a
+//! larger percentage always includes the smaller one's repositories. With
+//! stages, the percentage grows after every live run, and the current stage is
+//! persisted in a small JSON file next to the ledger.
+
+mod error;
+mod percent;
+mod state;
+
+pub use error::StageError;
+pub use percent::{in_rollout, rollout_bucket, RolloutPercent};
+pub use state::StageState;
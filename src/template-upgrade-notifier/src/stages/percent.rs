@@ -0,0 +1,120 @@
+//! The `rollout-percent` setting and stable repository bucketing.
+
+use serde::{Deserialize, Serialize};
+
+/// Share of discovered repositories a migration notifies.
+///
+/// Written in `metadata.toml` either as one percentage (`rollout-percent = 25`)
+/// or as stages (`rollout-percent = [10, 50, 100]`) that advance by one after
+/// every live run that processed the whole stage without failures.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RolloutPercent {
+    /// The same percentage on every run.
+    Fixed(u8),
+    /// Percentages for successive live runs; the last one applies from then on.
+    Stages(Vec<u8>),
+}
+
+impl RolloutPercent {
+    /// Returns the percentage for the given stage (0 for the first run).
+    #[must_use]
+    pub fn at_stage(&self, stage: usize) -> u8 {
+        match self {
+            Self::Fixed(percent) => *percent,
+            Self::Stages(stages) => stages
+                .get(stage)
+                .or_else(|| stages.last())
+                .copied()
+                .unwrap_or(100),
+        }
+    }
+
+    /// Returns a description of what is wrong with the setting, if anything.
+    #[must_use]
+    pub fn problem(&self) -> Option<&'static str> {
+        let values = match self {
+            Self::Fixed(percent) => core::slice::from_ref(percent),
+            Self::Stages(stages) if stages.is_empty() => return Some("must not be empty"),
+            Self::Stages(stages) => stages.as_slice(),
+        };
+        if values.iter().any(|percent| *percent > 100) {
+            return Some("must be between 0 and 100");
+        }
+        if values.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Some("stages must not decrease");
+        }
+        None
+    }
+}
+
+/// Returns the repository's stable bucket in `0..100`.
+///
+/// Based on an FNV-1a hash of the lowercased `owner/name`, so a repository
+/// keeps its bucket across runs, machines and migrations, and raising the
+/// percentage only ever adds repositories.
+#[must_use]
+pub fn rollout_bucket(repository: &str) -> u8 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let hash = repository
+        .to_lowercase()
+        .bytes()
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        });
+    // The remainder is always below 100.
+    (hash % 100) as u8
+}
+
+/// Returns true if `repository` falls within the first `percent` buckets.
+#[must_use]
+pub fn in_rollout(repository: &str, percent: u8) -> bool {
+    rollout_bucket(repository) < percent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_are_stable_and_case_insensitive() {
+        assert_eq!(rollout_bucket("Org/Repo"), rollout_bucket("org/repo"));
+        assert!(rollout_bucket("org/repo") < 100);
+    }
+
+    #[test]
+    fn raising_percent_only_adds_repositories() {
+        let repos: Vec<String> = (0..500).map(|i| format!("org/repo-{i}")).collect();
+        let at = |percent| {
+            repos
+                .iter()
+                .filter(|repo| in_rollout(repo, percent))
+                .collect::<Vec<_>>()
+        };
+
+        let (ten, fifty, all) = (at(10), at(50), at(100));
+
+        assert!(ten.iter().all(|repo| fifty.contains(repo)));
+        assert!(ten.len() < fifty.len());
+        assert_eq!(all.len(), repos.len());
+        assert!(at(0).is_empty());
+    }
+
+    #[test]
+    fn stages_advance_and_hold_at_last() {
+        let stages = RolloutPercent::Stages(vec![10, 50, 100]);
+        assert_eq!(stages.at_stage(0), 10);
+        assert_eq!(stages.at_stage(1), 50);
+        assert_eq!(stages.at_stage(7), 100);
+        assert_eq!(RolloutPercent::Fixed(25).at_stage(3), 25);
+    }
+
+    #[test]
+    fn reports_invalid_settings() {
+        assert_eq!(RolloutPercent::Fixed(50).problem(), None);
+        assert!(RolloutPercent::Fixed(101).problem().is_some());
+        assert!(RolloutPercent::Stages(vec![]).problem().is_some());
+        assert!(RolloutPercent::Stages(vec![50, 10]).problem().is_some());
+    }
+}
@@ -0,0 +1,86 @@
+//! Persisted stage of each staged rollout.
+
+use super::StageError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// How many live runs each staged migration has completed, by migration id.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageState {
+    stages: BTreeMap<String, usize>,
+}
+
+impl StageState {
+    /// Loads the state at `path`; a missing file starts every rollout at stage 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StageError`] if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, StageError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(StageError::Io {
+                    path: path.display().to_string(),
+                    source,
+                })
+            }
+        };
+        serde_json::from_str(&json).map_err(|source| StageError::Json {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    /// Writes the state to `path` as pretty-printed JSON, replacing it atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StageError::Io`] if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), StageError> {
+        let io_error = |source| StageError::Io {
+            path: path.display().to_string(),
+            source,
+        };
+        let json = serde_json::to_string_pretty(self).map_err(|e| io_error(e.into()))?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, json).map_err(io_error)?;
+        std::fs::rename(&temp, path).map_err(io_error)
+    }
+
+    /// Returns the current stage of `migration_id`.
+    #[must_use]
+    pub fn stage(&self, migration_id: &str) -> usize {
+        self.stages.get(migration_id).copied().unwrap_or_default()
+    }
+
+    /// Moves `migration_id` on to its next stage.
+    pub fn advance(&mut self, migration_id: &str) {
+        *self.stages.entry(migration_id.to_string()).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn round_trips_stages() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("rollout-state.json");
+        let mut state = StageState::load(&path).unwrap();
+        assert_eq!(state.stage("m1"), 0);
+
+        state.advance("m1");
+        state.advance("m1");
+        state.save(&path).unwrap();
+
+        let loaded = StageState::load(&path).unwrap();
+        assert_eq!(loaded.stage("m1"), 2);
+        assert_eq!(loaded.stage("m2"), 0);
+    }
+}
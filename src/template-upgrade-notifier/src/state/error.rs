@@ -0,0 +1,33 @@
+//! State store error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while reading or writing stored state.
+#[derive(Debug, Error)]
+pub enum StateError {
+    /// The state URL names a store that is unknown or not compiled in.
+    #[error("Unsupported state store '{url}': {reason}")]
+    Unsupported { url: String, reason: String },
+
+    /// Another run wrote the key after this run read it.
+    #[error("State '{key}' was changed by another run; not overwriting it")]
+    Conflict { key: String },
+
+    /// A state file could not be read or written.
+    #[error("Failed to access state '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A query against the state database failed.
+    #[cfg(feature = "state-sqlite")]
+    #[error("State database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    /// A request to the object store failed.
+    #[cfg(feature = "state-object")]
+    #[error("Object store error: {0}")]
+    Object(#[from] object_store::Error),
+}
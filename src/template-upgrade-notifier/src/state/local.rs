@@ -0,0 +1,128 @@
+//! State kept as files in a local directory.
+
+use super::{StateError, StateStore, StateVersion, StoredState};
+use futures::future::BoxFuture;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Keeps each key as a file in a directory, e.g. one restored from and saved
+/// to a CI cache.
+#[derive(Debug, Clone)]
+pub struct LocalStateStore {
+    dir: PathBuf,
+}
+
+impl LocalStateStore {
+    /// Uses `dir`, which is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl StateStore for LocalStateStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<StoredState>, StateError>> {
+        Box::pin(async move {
+            let path = self.dir.join(key);
+            match tokio::fs::read(&path).await {
+                Ok(bytes) => Ok(Some(StoredState {
+                    version: StateVersion::of_contents(&bytes),
+                    bytes,
+                })),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(source) => Err(StateError::Io {
+                    path: path.display().to_string(),
+                    source,
+                }),
+            }
+        })
+    }
+
+    /// Replaces the file atomically, so a crash never leaves it half-written.
+    ///
+    /// The current contents are compared with `expected` just before the
+    /// file is replaced; the directory offers no lock to hold across both.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        expected: Option<&'a StateVersion>,
+    ) -> BoxFuture<'a, Result<StateVersion, StateError>> {
+        Box::pin(async move {
+            let current = self.get(key).await?.map(|stored| stored.version);
+            if current.as_ref() != expected {
+                return Err(StateError::Conflict {
+                    key: key.to_string(),
+                });
+            }
+            let version = StateVersion::of_contents(&bytes);
+            let path = self.dir.join(key);
+            let io_error = |source| StateError::Io {
+                path: path.display().to_string(),
+                source,
+            };
+            tokio::fs::create_dir_all(&self.dir)
+                .await
+                .map_err(io_error)?;
+            let temp = self.dir.join(format!("{key}.tmp"));
+            tokio::fs::write(&temp, bytes).await.map_err(io_error)?;
+            tokio::fs::rename(&temp, &path).await.map_err(io_error)?;
+            Ok(version)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn missing_keys_are_none() {
+        let temp = TempDir::new().unwrap();
+        let store = LocalStateStore::new(temp.path().join("state"));
+
+        assert_eq!(store.get("ledger.json").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn put_replaces_existing_contents() {
+        let temp = TempDir::new().unwrap();
+        let store = LocalStateStore::new(temp.path().join("state"));
+
+        let old = store
+            .put("ledger.json", b"old".to_vec(), None)
+            .await
+            .unwrap();
+        store
+            .put("ledger.json", b"new".to_vec(), Some(&old))
+            .await
+            .unwrap();
+
+        let stored = store.get("ledger.json").await.unwrap().unwrap();
+        assert_eq!(stored.bytes, b"new");
+    }
+
+    #[tokio::test]
+    async fn put_rejects_concurrent_writes() {
+        let temp = TempDir::new().unwrap();
+        let store = LocalStateStore::new(temp.path().join("state"));
+        let read = store
+            .put("ledger.json", b"old".to_vec(), None)
+            .await
+            .unwrap();
+        store
+            .put("ledger.json", b"theirs".to_vec(), Some(&read))
+            .await
+            .unwrap();
+
+        let ours = store
+            .put("ledger.json", b"ours".to_vec(), Some(&read))
+            .await;
+        let created = store.put("ledger.json", b"ours".to_vec(), None).await;
+
+        assert!(matches!(ours, Err(StateError::Conflict { .. })));
+        assert!(matches!(created, Err(StateError::Conflict { .. })));
+        let stored = store.get("ledger.json").await.unwrap().unwrap();
+        assert_eq!(stored.bytes, b"theirs");
+    }
+}
@@ -0,0 +1,159 @@
+//! Pluggable storage for the state a live run keeps between runs.
+//!
+//! The idempotency ledger, the checkpoint and the run history are files on
+//! disk while a run is in flight. With a [`StateStore`] configured, a live
+//! run pulls them from the store before it starts and pushes them back when
+//! it ends, so runs on ephemeral CI runners pick up where the previous one
+//! left off. Each file is stored under a fixed key ([`LEDGER_KEY`],
+//! [`CHECKPOINT_KEY`], [`HISTORY_KEY`]).
+//!
+//! Writes are conditional on the [`StateVersion`] read at the start of the
+//! run, so two runs sharing a store cannot silently overwrite each other's
+//! ledger; the second to finish gets [`StateError::Conflict`].
+
+mod error;
+mod local;
+#[cfg(feature = "state-object")]
+mod object;
+#[cfg(feature = "state-sqlite")]
+mod sqlite;
+
+pub use error::StateError;
+pub use local::LocalStateStore;
+#[cfg(feature = "state-object")]
+pub use object::ObjectStateStore;
+#[cfg(feature = "state-sqlite")]
+pub use sqlite::SqliteStateStore;
+
+use futures::future::BoxFuture;
+use sha2::{Digest, Sha256};
+
+/// Key the idempotency ledger is stored under.
+pub const LEDGER_KEY: &str = "ledger.json";
+
+/// Key the checkpoint of an interrupted run is stored under.
+pub const CHECKPOINT_KEY: &str = "checkpoint.json";
+
+/// Key the run history database is stored under.
+pub const HISTORY_KEY: &str = "history.sqlite";
+
+/// Identifies the value stored under a key when it was read, so writing it
+/// back can check that nobody else wrote in between.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateVersion {
+    /// ETag of the stored value, or a digest of its contents for stores
+    /// without ETags.
+    pub e_tag: Option<String>,
+    /// Store-specific version, such as a GCS object generation.
+    pub version: Option<String>,
+}
+
+impl StateVersion {
+    /// Returns the version of `bytes` in a store that tells values apart by
+    /// their contents.
+    #[must_use]
+    pub fn of_contents(bytes: &[u8]) -> Self {
+        Self {
+            e_tag: Some(hex::encode(Sha256::digest(bytes))),
+            version: None,
+        }
+    }
+}
+
+/// A value read from a [`StateStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredState {
+    /// The stored bytes.
+    pub bytes: Vec<u8>,
+    /// Version to pass to [`StateStore::put`] when replacing them.
+    pub version: StateVersion,
+}
+
+/// Somewhere state files are kept between runs.
+pub trait StateStore: Send + Sync {
+    /// Returns the value stored under `key`, or `None` if nothing is.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<StoredState>, StateError>>;
+
+    /// Stores `bytes` under `key` if it still holds the value read at
+    /// `expected`, or, when `expected` is `None`, if it holds nothing yet.
+    ///
+    /// Returns the version of the new value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StateError::Conflict`] if the key was written since.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        expected: Option<&'a StateVersion>,
+    ) -> BoxFuture<'a, Result<StateVersion, StateError>>;
+}
+
+/// Opens the store a state URL points at.
+///
+/// - `file:///path/to/dir`, or a plain directory path: one file per key in
+///   that directory ([`LocalStateStore`]).
+/// - `sqlite:///path/to/state.sqlite`: one row per key (`state-sqlite` feature).
+/// - `s3://bucket/prefix` or `gs://bucket/prefix`: one object per key below
+///   the prefix, with credentials from the usual environment variables
+///   (`state-object` feature).
+///
+/// # Errors
+///
+/// Returns [`StateError::Unsupported`] for an unknown scheme or one whose
+/// feature is not compiled in, or an error if the store cannot be opened.
+pub fn open_state_store(url: &str) -> Result<Box<dyn StateStore>, StateError> {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return Ok(Box::new(LocalStateStore::new(url)));
+    };
+    match scheme {
+        "file" => Ok(Box::new(LocalStateStore::new(rest))),
+        #[cfg(feature = "state-sqlite")]
+        "sqlite" => Ok(Box::new(SqliteStateStore::open(std::path::Path::new(
+            rest,
+        ))?)),
+        #[cfg(feature = "state-object")]
+        "s3" | "gs" => Ok(Box::new(ObjectStateStore::open(url)?)),
+        #[cfg(not(feature = "state-sqlite"))]
+        "sqlite" => Err(not_compiled(url, "state-sqlite")),
+        #[cfg(not(feature = "state-object"))]
+        "s3" | "gs" => Err(not_compiled(url, "state-object")),
+        _ => Err(StateError::Unsupported {
+            url: url.to_string(),
+            reason: "expected a path or a file://, sqlite://, s3:// or gs:// URL".to_string(),
+        }),
+    }
+}
+
+/// Returns the error for a store whose `feature` is not compiled in.
+#[cfg(not(all(feature = "state-sqlite", feature = "state-object")))]
+fn not_compiled(url: &str, feature: &str) -> StateError {
+    StateError::Unsupported {
+        url: url.to_string(),
+        reason: format!("built without the '{feature}' feature"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_unknown_schemes() {
+        let result = open_state_store("ftp://example.com/state");
+        assert!(matches!(result, Err(StateError::Unsupported { .. })));
+    }
+
+    #[tokio::test]
+    async fn plain_paths_open_a_local_store() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let store = open_state_store(temp.path().to_str().unwrap()).unwrap();
+
+        store.put(LEDGER_KEY, b"{}".to_vec(), None).await.unwrap();
+
+        let stored = store.get(LEDGER_KEY).await.unwrap().unwrap();
+        assert_eq!(stored.bytes, b"{}");
+        assert_eq!(stored.version, StateVersion::of_contents(b"{}"));
+    }
+}
@@ -0,0 +1,118 @@
+//! State kept as objects in an S3 or GCS bucket.
+
+use super::{StateError, StateStore, StateVersion, StoredState};
+use futures::future::BoxFuture;
+use object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey, S3ConditionalPut};
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutMode, PutPayload, UpdateVersion};
+use url::Url;
+
+/// Keeps each key as an object below a prefix in a bucket, for runners that
+/// keep nothing between runs.
+///
+/// Credentials come from the usual environment variables (`AWS_*` for S3,
+/// `GOOGLE_*` for GCS), as do S3-compatible endpoints (`AWS_ENDPOINT`).
+/// Writes are conditional: `If-Match` on the ETag for S3 (or whatever
+/// `AWS_CONDITIONAL_PUT` selects), and the object generation for GCS.
+pub struct ObjectStateStore {
+    store: Box<dyn ObjectStore>,
+    prefix: ObjectPath,
+}
+
+impl ObjectStateStore {
+    /// Opens the bucket and prefix of an `s3://bucket/prefix` or
+    /// `gs://bucket/prefix` URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StateError::Unsupported`] if the URL is not an S3 or GCS
+    /// URL, or [`StateError::Object`] if the client cannot be configured.
+    pub fn open(url: &str) -> Result<Self, StateError> {
+        let unsupported = |reason: &str| StateError::Unsupported {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        };
+        let parsed = Url::parse(url).map_err(|e| unsupported(&e.to_string()))?;
+        let store: Box<dyn ObjectStore> = match parsed.scheme() {
+            "s3" => {
+                let mut builder = AmazonS3Builder::from_env().with_url(url);
+                if builder
+                    .get_config_value(&AmazonS3ConfigKey::ConditionalPut)
+                    .is_none()
+                {
+                    builder = builder.with_conditional_put(S3ConditionalPut::ETagMatch);
+                }
+                Box::new(builder.build()?)
+            }
+            "gs" => Box::new(
+                GoogleCloudStorageBuilder::from_env()
+                    .with_url(url)
+                    .build()?,
+            ),
+            _ => return Err(unsupported("expected an s3:// or gs:// URL")),
+        };
+        Ok(Self {
+            store,
+            prefix: ObjectPath::from(parsed.path().trim_matches('/')),
+        })
+    }
+
+    fn path(&self, key: &str) -> ObjectPath {
+        self.prefix.child(key)
+    }
+}
+
+impl StateStore for ObjectStateStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<StoredState>, StateError>> {
+        Box::pin(async move {
+            match self.store.get(&self.path(key)).await {
+                Ok(result) => {
+                    let version = StateVersion {
+                        e_tag: result.meta.e_tag.clone(),
+                        version: result.meta.version.clone(),
+                    };
+                    let bytes = result.bytes().await?.to_vec();
+                    Ok(Some(StoredState { bytes, version }))
+                }
+                Err(object_store::Error::NotFound { .. }) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        expected: Option<&'a StateVersion>,
+    ) -> BoxFuture<'a, Result<StateVersion, StateError>> {
+        Box::pin(async move {
+            let mode = match expected {
+                Some(version) => PutMode::Update(UpdateVersion {
+                    e_tag: version.e_tag.clone(),
+                    version: version.version.clone(),
+                }),
+                None => PutMode::Create,
+            };
+            let payload = PutPayload::from(bytes);
+            match self
+                .store
+                .put_opts(&self.path(key), payload, mode.into())
+                .await
+            {
+                Ok(result) => Ok(StateVersion {
+                    e_tag: result.e_tag,
+                    version: result.version,
+                }),
+                Err(
+                    object_store::Error::Precondition { .. }
+                    | object_store::Error::AlreadyExists { .. },
+                ) => Err(StateError::Conflict {
+                    key: key.to_string(),
+                }),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+}
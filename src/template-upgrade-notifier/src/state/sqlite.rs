@@ -0,0 +1,129 @@
+//! State kept as rows of a SQLite database.
+
+use super::{StateError, StateStore, StateVersion, StoredState};
+use futures::future::BoxFuture;
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS state (
+    key TEXT PRIMARY KEY,
+    value BLOB NOT NULL
+);
+";
+
+/// Keeps each key as a row of a single SQLite file, which is easier to
+/// carry between runners than a directory.
+pub struct SqliteStateStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStateStore {
+    /// Opens (creating if needed) the state database at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StateError::Sqlite`] if the file cannot be opened or the
+    /// schema cannot be created.
+    pub fn open(path: &Path) -> Result<Self, StateError> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Opens a throwaway in-memory database.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StateError::Sqlite`] if the schema cannot be created.
+    pub fn open_in_memory() -> Result<Self, StateError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl StateStore for SqliteStateStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<StoredState>, StateError>> {
+        Box::pin(async move {
+            let value: Option<Vec<u8>> = self
+                .conn()
+                .query_row("SELECT value FROM state WHERE key = ?1", [key], |row| {
+                    row.get(0)
+                })
+                .optional()?;
+            Ok(value.map(|bytes| StoredState {
+                version: StateVersion::of_contents(&bytes),
+                bytes,
+            }))
+        })
+    }
+
+    /// Compares and replaces the row in one write transaction, so other
+    /// processes sharing the file cannot write in between.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        expected: Option<&'a StateVersion>,
+    ) -> BoxFuture<'a, Result<StateVersion, StateError>> {
+        Box::pin(async move {
+            let mut conn = self.conn();
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let current: Option<Vec<u8>> = tx
+                .query_row("SELECT value FROM state WHERE key = ?1", [key], |row| {
+                    row.get(0)
+                })
+                .optional()?;
+            if current.as_deref().map(StateVersion::of_contents).as_ref() != expected {
+                return Err(StateError::Conflict {
+                    key: key.to_string(),
+                });
+            }
+            let version = StateVersion::of_contents(&bytes);
+            tx.execute(
+                "INSERT INTO state (key, value) VALUES (?1, ?2) \
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, bytes],
+            )?;
+            tx.commit()?;
+            Ok(version)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trips_and_replaces_values() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+
+        assert_eq!(store.get("ledger.json").await.unwrap(), None);
+        let old = store
+            .put("ledger.json", b"old".to_vec(), None)
+            .await
+            .unwrap();
+        store
+            .put("ledger.json", b"new".to_vec(), Some(&old))
+            .await
+            .unwrap();
+
+        let stored = store.get("ledger.json").await.unwrap().unwrap();
+        assert_eq!(stored.bytes, b"new");
+        assert!(matches!(
+            store.put("ledger.json", b"late".to_vec(), Some(&old)).await,
+            Err(StateError::Conflict { .. })
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+//! How consumers responded to upgrade issues: reactions and labels.
+
+use super::maintainers::maintainer_reactions;
+use super::StatusError;
+use crate::issues::repository_from_api_url;
+use crate::rate_limit::ensure_search_rate_limit;
+use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+
+/// Results per page when listing our issues.
+const RESULTS_PER_PAGE: usize = 100;
+
+/// Pages the search API returns at most (1000 results).
+const MAX_PAGES: u32 = 10;
+
+/// Labels (compared case-insensitively, ignoring `-` and `_`) meaning the
+/// maintainers won't upgrade.
+const DECLINE_LABELS: &[&str] = &[
+    "wontfix",
+    "wont fix",
+    "won't fix",
+    "will not fix",
+    "not planned",
+    "declined",
+];
+
+/// A consumer's response to an upgrade issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Acknowledgement {
+    /// More 👍 than 👎 reactions from maintainers.
+    Acknowledged,
+    /// A "wontfix"-style label, or more 👎 than 👍 reactions from maintainers.
+    Declined,
+    /// No reactions or labels either way.
+    NoResponse,
+}
+
+impl Acknowledgement {
+    /// Classifies an issue from its reaction counts and label names.
+    ///
+    /// A decline label wins over any reactions.
+    #[must_use]
+    pub fn classify(thumbs_up: u64, thumbs_down: u64, labels: &[String]) -> Self {
+        if labels.iter().any(|label| is_decline_label(label)) {
+            return Self::Declined;
+        }
+        match thumbs_up.cmp(&thumbs_down) {
+            core::cmp::Ordering::Greater => Self::Acknowledged,
+            core::cmp::Ordering::Less => Self::Declined,
+            core::cmp::Ordering::Equal => Self::NoResponse,
+        }
+    }
+}
+
+/// An upgrade issue whose maintainers declined the upgrade.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeclinedIssue {
+    /// Repository full name in `owner/name` format.
+    pub repository: String,
+    /// Issue number.
+    pub number: u64,
+}
+
+/// One of our issues with its classified response.
+pub(super) struct IssueResponse {
+    pub(super) repository: String,
+    pub(super) number: u64,
+    pub(super) acknowledgement: Acknowledgement,
+    /// Whether the issue is closed.
+    pub(super) closed: bool,
+    /// Whether the issue was closed with the "not planned" reason.
+    pub(super) not_planned: bool,
+}
+
+impl IssueResponse {
+    /// Whether the issue was closed without the upgrade being adopted.
+    pub(super) fn closed_unadopted(&self) -> bool {
+        self.closed && (self.not_planned || self.acknowledgement == Acknowledgement::Declined)
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchPage {
+    items: Vec<SearchItem>,
+}
+
+#[derive(Deserialize)]
+struct SearchItem {
+    number: u64,
+    repository_url: String,
+    #[serde(default)]
+    labels: Vec<LabelRef>,
+    #[serde(default)]
+    reactions: Reactions,
+    state: String,
+    #[serde(default)]
+    state_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LabelRef {
+    name: String,
+}
+
+#[derive(Default, Deserialize)]
+struct Reactions {
+    #[serde(rename = "+1", default)]
+    thumbs_up: u64,
+    #[serde(rename = "-1", default)]
+    thumbs_down: u64,
+}
+
+/// Lists every issue (open or closed) matched by `query` with its response.
+///
+/// Uses the raw search endpoint, whose items carry the reaction rollup.
+/// Issues with 👍 or 👎 reactions and no decline label have their reactions
+/// listed, so only those from maintainers are counted.
+pub(super) async fn list_responses(
+    octocrab: &Octocrab,
+    query: &str,
+) -> Result<Vec<IssueResponse>, StatusError> {
+    let mut responses = Vec::new();
+    for page in 1..=MAX_PAGES {
+        ensure_search_rate_limit(octocrab).await?;
+        let params = [
+            ("q", query.to_string()),
+            ("per_page", RESULTS_PER_PAGE.to_string()),
+            ("page", page.to_string()),
+        ];
+        let result: SearchPage = octocrab.get("/search/issues", Some(&params)).await?;
+        let fetched = result.items.len();
+        for item in result.items {
+            let Some(repository) = repository_from_api_url(&item.repository_url) else {
+                continue;
+            };
+            let labels: Vec<String> = item.labels.into_iter().map(|l| l.name).collect();
+            let reacted = item.reactions.thumbs_up + item.reactions.thumbs_down > 0;
+            let (thumbs_up, thumbs_down) = if reacted && !labels.iter().any(|l| is_decline_label(l))
+            {
+                maintainer_reactions(octocrab, &repository, item.number).await?
+            } else {
+                (0, 0)
+            };
+            responses.push(IssueResponse {
+                repository,
+                number: item.number,
+                acknowledgement: Acknowledgement::classify(thumbs_up, thumbs_down, &labels),
+                closed: item.state == "closed",
+                not_planned: item.state_reason.as_deref() == Some("not_planned"),
+            });
+        }
+        if fetched < RESULTS_PER_PAGE {
+            break;
+        }
+    }
+    Ok(responses)
+}
+
+fn is_decline_label(label: &str) -> bool {
+    let normalized = label.to_lowercase().replace(['-', '_'], " ");
+    DECLINE_LABELS.contains(&normalized.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_by_reactions() {
+        assert_eq!(
+            Acknowledgement::classify(2, 1, &[]),
+            Acknowledgement::Acknowledged
+        );
+        assert_eq!(
+            Acknowledgement::classify(0, 1, &[]),
+            Acknowledgement::Declined
+        );
+        assert_eq!(
+            Acknowledgement::classify(1, 1, &[]),
+            Acknowledgement::NoResponse
+        );
+    }
+
+    #[test]
+    fn decline_label_wins() {
+        for label in ["wontfix", "Won't Fix", "not-planned", "declined"] {
+            assert_eq!(
+                Acknowledgement::classify(5, 0, &[label.to_string()]),
+                Acknowledgement::Declined
+            );
+        }
+        assert_eq!(
+            Acknowledgement::classify(0, 0, &["enhancement".to_string()]),
+            Acknowledgement::NoResponse
+        );
+    }
+}
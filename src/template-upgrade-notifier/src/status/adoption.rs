@@ -0,0 +1,90 @@
+//! Adoption counts for a single migration.
+
+use super::DeclinedIssue;
+use serde::Serialize;
+
+/// Open vs closed counts of the issues and PRs filed for one migration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationStatus {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Issues still open.
+    pub issues_open: u64,
+    /// Issues closed, for any reason.
+    pub issues_closed: u64,
+    /// Closed issues that were declined or closed as "not planned".
+    pub issues_closed_unadopted: u64,
+    /// PRs still open.
+    pub prs_open: u64,
+    /// PRs merged.
+    pub prs_merged: u64,
+    /// PRs closed without merging.
+    pub prs_closed: u64,
+    /// Issues with more 👍 than 👎 reactions and no decline label.
+    pub acknowledged: u64,
+    /// Issues whose maintainers declined the upgrade.
+    pub declined_issues: Vec<DeclinedIssue>,
+    /// Issues the maintainers closed as "not planned".
+    pub not_planned_issues: Vec<DeclinedIssue>,
+}
+
+impl MigrationStatus {
+    /// Returns the total number of issues filed.
+    #[must_use]
+    pub fn issues_filed(&self) -> u64 {
+        self.issues_open + self.issues_closed
+    }
+
+    /// Returns the total number of PRs filed.
+    #[must_use]
+    pub fn prs_filed(&self) -> u64 {
+        self.prs_open + self.prs_merged + self.prs_closed
+    }
+
+    /// Returns the number of issues whose maintainers declined the upgrade.
+    #[must_use]
+    pub fn declined(&self) -> u64 {
+        self.declined_issues.len() as u64
+    }
+
+    /// Returns the share of filed issues that have been closed without being
+    /// declined, or `None` if none were filed.
+    #[must_use]
+    pub fn adoption_rate(&self) -> Option<f64> {
+        let filed = self.issues_filed();
+        let adopted = self
+            .issues_closed
+            .saturating_sub(self.issues_closed_unadopted);
+        (filed > 0).then(|| adopted as f64 / filed as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adoption_rate_is_share_of_closed_issues() {
+        let status = MigrationStatus {
+            migration_id: "test".to_string(),
+            issues_open: 1,
+            issues_closed: 3,
+            ..MigrationStatus::default()
+        };
+        assert_eq!(status.issues_filed(), 4);
+        assert_eq!(status.adoption_rate(), Some(0.75));
+        assert_eq!(MigrationStatus::default().adoption_rate(), None);
+    }
+
+    #[test]
+    fn adoption_rate_excludes_declines() {
+        let status = MigrationStatus {
+            migration_id: "test".to_string(),
+            issues_open: 1,
+            issues_closed: 3,
+            issues_closed_unadopted: 2,
+            ..MigrationStatus::default()
+        };
+        assert_eq!(status.adoption_rate(), Some(0.25));
+    }
+}
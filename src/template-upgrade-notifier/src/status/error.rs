@@ -0,0 +1,15 @@
+//! Fleet status error types.
+
+use thiserror::Error;
+
+/// Errors that can occur while collecting adoption status.
+#[derive(Debug, Error)]
+pub enum StatusError {
+    /// GitHub API error.
+    #[error("GitHub API error: {0}")]
+    GitHubError(#[from] octocrab::Error),
+
+    /// Issue or PR title rendering error.
+    #[error("Template rendering error: {0}")]
+    TemplateError(String),
+}
@@ -0,0 +1,188 @@
+//! Reactions left on an issue by the repository's maintainers.
+//!
+//! Anyone can react to a public issue, so only reactions from users with
+//! write, maintain or admin permission count as the maintainers' response.
+
+use super::StatusError;
+use crate::http_cache::route;
+use crate::rate_limit::ensure_core_rate_limit;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Reactions fetched per page.
+const RESULTS_PER_PAGE: usize = 100;
+
+/// Pages of reactions read per issue at most.
+const MAX_PAGES: u32 = 3;
+
+/// Permissions (or role names) that make a user a maintainer.
+const MAINTAINER_PERMISSIONS: &[&str] = &["admin", "maintain", "write"];
+
+#[derive(Deserialize)]
+struct Reaction {
+    content: String,
+    user: Option<User>,
+}
+
+#[derive(Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct Permission {
+    permission: String,
+    #[serde(default)]
+    role_name: Option<String>,
+}
+
+/// Counts the 👍 and 👎 reactions maintainers of `repository` left on issue
+/// `number`.
+///
+/// The repository owner always counts as a maintainer. Users whose
+/// permission can't be read (GitHub only shows it to collaborators) don't.
+///
+/// # Errors
+///
+/// Returns [`StatusError`] if listing the reactions or a permission lookup
+/// fails for reasons other than missing access.
+pub(super) async fn maintainer_reactions(
+    octocrab: &Octocrab,
+    repository: &str,
+    number: u64,
+) -> Result<(u64, u64), StatusError> {
+    let reactions = list_reactions(octocrab, repository, number).await?;
+    let mut maintainers = HashMap::new();
+    for login in reactions.iter().filter_map(|r| r.user.as_ref()) {
+        if let Entry::Vacant(entry) = maintainers.entry(login.login.to_lowercase()) {
+            let maintainer = is_maintainer(octocrab, repository, entry.key()).await?;
+            entry.insert(maintainer);
+        }
+    }
+    Ok(tally(&reactions, &maintainers))
+}
+
+async fn list_reactions(
+    octocrab: &Octocrab,
+    repository: &str,
+    number: u64,
+) -> Result<Vec<Reaction>, StatusError> {
+    let (owner, name) = repository.split_once('/').unwrap_or((repository, ""));
+    let path = route([
+        "repos",
+        owner,
+        name,
+        "issues",
+        &number.to_string(),
+        "reactions",
+    ]);
+    let mut reactions = Vec::new();
+    for page in 1..=MAX_PAGES {
+        ensure_core_rate_limit(octocrab).await?;
+        let params = [
+            ("per_page", RESULTS_PER_PAGE.to_string()),
+            ("page", page.to_string()),
+        ];
+        let batch: Vec<Reaction> = octocrab.get(&path, Some(&params)).await?;
+        let fetched = batch.len();
+        reactions.extend(batch);
+        if fetched < RESULTS_PER_PAGE {
+            break;
+        }
+    }
+    Ok(reactions)
+}
+
+async fn is_maintainer(
+    octocrab: &Octocrab,
+    repository: &str,
+    login: &str,
+) -> Result<bool, StatusError> {
+    let (owner, name) = repository.split_once('/').unwrap_or((repository, ""));
+    if owner.eq_ignore_ascii_case(login) {
+        return Ok(true);
+    }
+    ensure_core_rate_limit(octocrab).await?;
+    let path = route(["repos", owner, name, "collaborators", login, "permission"]);
+    match octocrab.get::<Permission, _, ()>(&path, None).await {
+        Ok(permission) => Ok(is_maintainer_permission(&permission)),
+        Err(octocrab::Error::GitHub { source, .. })
+            if matches!(source.status_code.as_u16(), 403 | 404) =>
+        {
+            debug!(repository, login = %login, "Cannot read permission, skipping reaction");
+            Ok(false)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn is_maintainer_permission(permission: &Permission) -> bool {
+    MAINTAINER_PERMISSIONS.contains(&permission.permission.as_str())
+        || permission
+            .role_name
+            .as_deref()
+            .is_some_and(|role| MAINTAINER_PERMISSIONS.contains(&role))
+}
+
+/// Counts the 👍 and 👎 among `reactions` from users marked as maintainers.
+fn tally(reactions: &[Reaction], maintainers: &HashMap<String, bool>) -> (u64, u64) {
+    let by_maintainer = |content: &str| {
+        reactions
+            .iter()
+            .filter(|r| r.content == content)
+            .filter_map(|r| r.user.as_ref())
+            .filter(|user| {
+                maintainers
+                    .get(&user.login.to_lowercase())
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .count() as u64
+    };
+    (by_maintainer("+1"), by_maintainer("-1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reaction(content: &str, login: &str) -> Reaction {
+        Reaction {
+            content: content.to_string(),
+            user: Some(User {
+                login: login.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn counts_only_maintainer_reactions() {
+        let reactions = [
+            reaction("-1", "Passerby"),
+            reaction("-1", "Owner"),
+            reaction("+1", "collaborator"),
+            reaction("heart", "owner"),
+        ];
+        let maintainers = HashMap::from([
+            ("passerby".to_string(), false),
+            ("owner".to_string(), true),
+            ("collaborator".to_string(), true),
+        ]);
+        assert_eq!(tally(&reactions, &maintainers), (1, 1));
+    }
+
+    #[test]
+    fn recognizes_maintainer_permissions() {
+        let permission = |permission: &str, role: &str| Permission {
+            permission: permission.to_string(),
+            role_name: Some(role.to_string()),
+        };
+        assert!(is_maintainer_permission(&permission("write", "maintain")));
+        assert!(is_maintainer_permission(&permission("admin", "admin")));
+        assert!(!is_maintainer_permission(&permission("read", "triage")));
+        assert!(!is_maintainer_permission(&permission("none", "none")));
+    }
+}
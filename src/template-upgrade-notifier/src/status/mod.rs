@@ -0,0 +1,183 @@
+//! Fleet adoption status.
+//!
+//! Counts how many of the issues and PRs previously filed for each migration
+//! are still open versus closed or merged, so template authors can track
+//! upgrade adoption without visiting every repository. Reactions and labels
+//! on the issues classify each consumer as acknowledged or declined.
+
+mod acknowledgement;
+mod adoption;
+mod error;
+mod maintainers;
+
+pub use acknowledgement::{Acknowledgement, DeclinedIssue};
+pub use adoption::MigrationStatus;
+pub use error::StatusError;
+
+use crate::config::Migration;
+use crate::issues::author_qualifier;
+use crate::rate_limit::ensure_search_rate_limit;
+use crate::templates::{generate_issue_title, generate_pr_title};
+use octocrab::Octocrab;
+use tracing::{info, info_span, Instrument};
+
+/// Collects adoption status for each migration.
+///
+/// Only issues and PRs authored by the authenticated user or app and titled
+/// as this tool titles them are counted. Nothing is written: declines are
+/// recorded in the ledger by live runs, through [`declined_issues`].
+///
+/// # Errors
+///
+/// Returns [`StatusError`] if a title cannot be rendered or a search fails.
+pub async fn fleet_status(
+    octocrab: &Octocrab,
+    migrations: &[Migration],
+) -> Result<Vec<MigrationStatus>, StatusError> {
+    let mut statuses = Vec::with_capacity(migrations.len());
+    for migration in migrations {
+        let span = info_span!("fleet_status", migration_id = %migration.id);
+        statuses.push(
+            migration_status(octocrab, migration)
+                .instrument(span)
+                .await?,
+        );
+    }
+    Ok(statuses)
+}
+
+async fn migration_status(
+    octocrab: &Octocrab,
+    migration: &Migration,
+) -> Result<MigrationStatus, StatusError> {
+    info!("Collecting adoption status");
+    let issue_title =
+        generate_issue_title(migration).map_err(|e| StatusError::TemplateError(e.to_string()))?;
+    let pr_title =
+        generate_pr_title(migration).map_err(|e| StatusError::TemplateError(e.to_string()))?;
+
+    let author = author_qualifier(octocrab).await?;
+    let issues = |state| title_query(&author, "issue", &issue_title, state);
+    let prs = |state| title_query(&author, "pr", &pr_title, state);
+
+    let responses = acknowledgement::list_responses(octocrab, &issues("")).await?;
+    let acknowledged = responses
+        .iter()
+        .filter(|r| r.acknowledgement == Acknowledgement::Acknowledged)
+        .count() as u64;
+    let declined_issues = responses
+        .iter()
+        .filter(|r| r.acknowledgement == Acknowledgement::Declined)
+        .map(|r| DeclinedIssue {
+            repository: r.repository.clone(),
+            number: r.number,
+        })
+        .collect();
+    let not_planned_issues = responses
+        .iter()
+        .filter(|r| r.not_planned)
+        .map(|r| DeclinedIssue {
+            repository: r.repository.clone(),
+            number: r.number,
+        })
+        .collect();
+    let issues_closed_unadopted = responses.iter().filter(|r| r.closed_unadopted()).count() as u64;
+
+    Ok(MigrationStatus {
+        migration_id: migration.id.to_string(),
+        issues_open: count(octocrab, &issues("is:open")).await?,
+        issues_closed: count(octocrab, &issues("is:closed")).await?,
+        issues_closed_unadopted,
+        prs_open: count(octocrab, &prs("is:open")).await?,
+        prs_merged: count(octocrab, &prs("is:merged")).await?,
+        prs_closed: count(octocrab, &prs("is:closed is:unmerged")).await?,
+        acknowledged,
+        declined_issues,
+        not_planned_issues,
+    })
+}
+
+/// Issues filed for a migration that its consumers turned down.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Declines {
+    /// Issues whose maintainers declined the upgrade.
+    pub declined: Vec<DeclinedIssue>,
+    /// Issues the maintainers closed as "not planned".
+    pub not_planned: Vec<DeclinedIssue>,
+}
+
+/// Lists the issues filed for `migration` that were declined or closed as
+/// "not planned", without counting anything else.
+///
+/// # Errors
+///
+/// Returns [`StatusError`] if the title cannot be rendered or a search fails.
+pub async fn declined_issues(
+    octocrab: &Octocrab,
+    migration: &Migration,
+) -> Result<Declines, StatusError> {
+    let issue_title =
+        generate_issue_title(migration).map_err(|e| StatusError::TemplateError(e.to_string()))?;
+    let author = author_qualifier(octocrab).await?;
+    let query = title_query(&author, "issue", &issue_title, "");
+    let responses = acknowledgement::list_responses(octocrab, &query).await?;
+    let issue = |r: &acknowledgement::IssueResponse| DeclinedIssue {
+        repository: r.repository.clone(),
+        number: r.number,
+    };
+    Ok(Declines {
+        declined: responses
+            .iter()
+            .filter(|r| r.acknowledgement == Acknowledgement::Declined)
+            .map(issue)
+            .collect(),
+        not_planned: responses
+            .iter()
+            .filter(|r| r.not_planned)
+            .map(issue)
+            .collect(),
+    })
+}
+
+/// Builds a search query for items of `kind` matching `author` with the
+/// given title, in any state if `state` is empty.
+fn title_query(author: &str, kind: &str, title: &str, state: &str) -> String {
+    let state = if state.is_empty() {
+        String::new()
+    } else {
+        format!("{state} ")
+    };
+    format!(
+        "{author} is:{kind} {state}in:title \"{}\"",
+        title.replace('"', "")
+    )
+}
+
+/// Returns the total number of search results without fetching them.
+async fn count(octocrab: &Octocrab, query: &str) -> Result<u64, StatusError> {
+    ensure_search_rate_limit(octocrab).await?;
+    let page = octocrab
+        .search()
+        .issues_and_pull_requests(query)
+        .per_page(1)
+        .send()
+        .await?;
+    Ok(page.total_count.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_query_strips_quotes() {
+        assert_eq!(
+            title_query("author:@me", "issue", "Upgrade \"v1\" -> v2", "is:open"),
+            "author:@me is:issue is:open in:title \"Upgrade v1 -> v2\""
+        );
+        assert_eq!(
+            title_query("author:app/upgrader", "issue", "Upgrade", ""),
+            "author:app/upgrader is:issue in:title \"Upgrade\""
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Circuit breaker stopping a run whose repositories keep failing.
+
+use super::result::ProcessingResult;
+use super::run_summary::RunSummary;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use serde::Deserialize;
+
+/// Failure rate at which a run stops, read from the `[abort-threshold]`
+/// section of `notifier.toml`.
+///
+/// Guards against a bad template or prompt being applied to the whole fleet
+/// before anyone notices: once at least `min-processed` repositories were
+/// processed and more than `failure-percent` of them failed, no further
+/// repository is started.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AbortThreshold {
+    /// Highest tolerated share of failed repositories, in percent.
+    pub failure_percent: u8,
+    /// Repositories processed before the threshold applies.
+    #[serde(default = "default_min_processed")]
+    pub min_processed: usize,
+}
+
+fn default_min_processed() -> usize {
+    10
+}
+
+impl AbortThreshold {
+    /// Returns true if the results recorded so far exceed the threshold.
+    ///
+    /// Repositories skipped without being processed (duplicates, ledger hits,
+    /// cooldowns, ...) count neither way. A repository failed if it could not
+    /// be processed, or its issue or PR failed (including PR validation).
+    #[must_use]
+    pub fn is_exceeded(&self, summary: &RunSummary) -> bool {
+        let processed: Vec<&ProcessingResult> = summary
+            .results
+            .iter()
+            .filter(|result| !matches!(result, ProcessingResult::Skipped { .. }))
+            .collect();
+        if processed.is_empty() || processed.len() < self.min_processed {
+            return false;
+        }
+        let failed = processed.iter().filter(|result| is_failure(result)).count();
+        failed * 100 > processed.len() * usize::from(self.failure_percent)
+    }
+}
+
+/// Returns true if the repository, its issue or its PR failed.
+fn is_failure(result: &ProcessingResult) -> bool {
+    match result {
+        ProcessingResult::Failed { .. } => true,
+        ProcessingResult::Success { issue, pr, .. } => {
+            matches!(issue, IssueStatus::Failed { .. })
+                || matches!(pr, Some(PrStatus::Failed { .. }))
+        }
+        ProcessingResult::PrOnly { pr, .. } => matches!(pr, PrStatus::Failed { .. }),
+        ProcessingResult::Dispatched { .. } | ProcessingResult::Skipped { .. } => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_kind::ErrorKind;
+    use crate::summary::StageTimings;
+
+    fn summary(failed: usize, succeeded: usize, skipped: usize) -> RunSummary {
+        let mut summary = RunSummary::new(false);
+        for i in 0..failed {
+            summary.record_result(&ProcessingResult::Failed {
+                migration_id: "m".to_string(),
+                repository: format!("user/failed{i}"),
+                error: "boom".to_string(),
+                kind: ErrorKind::Other,
+                timings: StageTimings::default(),
+            });
+        }
+        for i in 0..succeeded {
+            summary.record_result(&ProcessingResult::Success {
+                migration_id: "m".to_string(),
+                repository: format!("user/ok{i}"),
+                issue: IssueStatus::Created {
+                    number: 1,
+                    url: "https://github.com/user/repo/issues/1".parse().unwrap(),
+                },
+                pr: Some(PrStatus::Failed {
+                    error: "validation failed".to_string(),
+                    retryable: false,
+                })
+                .filter(|_| i == 0),
+                timings: StageTimings::default(),
+            });
+        }
+        for i in 0..skipped {
+            summary.record_result(&ProcessingResult::Skipped {
+                migration_id: "m".to_string(),
+                repository: format!("user/skipped{i}"),
+                reason: "duplicate".to_string(),
+                timings: StageTimings::default(),
+            });
+        }
+        summary
+    }
+
+    fn threshold(failure_percent: u8, min_processed: usize) -> AbortThreshold {
+        AbortThreshold {
+            failure_percent,
+            min_processed,
+        }
+    }
+
+    #[test]
+    fn waits_for_minimum_sample() {
+        assert!(!threshold(50, 10).is_exceeded(&summary(5, 0, 20)));
+        assert!(threshold(50, 5).is_exceeded(&summary(5, 0, 20)));
+    }
+
+    #[test]
+    fn counts_pr_failures_and_ignores_skips() {
+        // 2 of 4 processed repositories failed (one of them only its PR).
+        assert!(threshold(40, 4).is_exceeded(&summary(1, 3, 100)));
+        assert!(!threshold(50, 4).is_exceeded(&summary(1, 3, 100)));
+    }
+
+    #[test]
+    fn parses_with_default_minimum() {
+        let threshold: AbortThreshold = toml::from_str("failure-percent = 30").unwrap();
+        assert_eq!(threshold.failure_percent, 30);
+        assert_eq!(threshold.min_processed, 10);
+    }
+}
@@ -0,0 +1,208 @@
+//! GitHub Actions workflow commands: annotations and step outputs.
+
+use super::result::ProcessingResult;
+use super::run_summary::RunSummary;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::redact::redact;
+use std::io::Write as _;
+use std::path::Path;
+
+/// Environment variable GitHub Actions sets to `true` inside a workflow.
+pub const GITHUB_ACTIONS_ENV: &str = "GITHUB_ACTIONS";
+
+/// Environment variable GitHub Actions sets to the step output file path.
+pub const GITHUB_OUTPUT_ENV: &str = "GITHUB_OUTPUT";
+
+impl RunSummary {
+    /// Renders `::error` / `::warning` workflow commands for problem repositories.
+    ///
+    /// Repositories that failed outright become errors; issue or PR failures
+    /// within an otherwise processed repository become warnings.
+    #[must_use]
+    pub fn to_annotations(&self) -> Vec<String> {
+        let mut annotations = Vec::new();
+        for result in &self.results {
+            let location = format!("{} ({})", result.repository(), result.migration_id());
+            match result {
+                ProcessingResult::Failed { error, kind, .. } => {
+                    let title = format!("Repository failed ({kind})");
+                    annotations.push(annotation("error", &title, &location, error));
+                }
+                ProcessingResult::Success { issue, pr, .. } => {
+                    if let IssueStatus::Failed { error } = issue {
+                        annotations.push(annotation("warning", "Issue failed", &location, error));
+                    }
+                    annotations.extend(pr.as_ref().and_then(|pr| pr_annotation(pr, &location)));
+                }
+                ProcessingResult::PrOnly { pr, .. } => {
+                    annotations.extend(pr_annotation(pr, &location));
+                }
+                ProcessingResult::Dispatched { .. } | ProcessingResult::Skipped { .. } => {}
+            }
+        }
+        annotations
+    }
+}
+
+/// Returns the warning for a failed or timed out PR, if any.
+fn pr_annotation(pr: &PrStatus, location: &str) -> Option<String> {
+    match pr {
+        PrStatus::Failed { error, .. } => Some(annotation("warning", "PR failed", location, error)),
+        PrStatus::TimedOut => Some(annotation(
+            "warning",
+            "PR timed out",
+            location,
+            "PR generation timed out",
+        )),
+        _ => None,
+    }
+}
+
+/// Formats a single workflow command with secrets redacted from the message.
+fn annotation(level: &str, title: &str, location: &str, message: &str) -> String {
+    format!(
+        "::{level} title={}::{}",
+        escape_property(title),
+        escape_data(&redact(&format!("{location}: {message}")))
+    )
+}
+
+/// Escapes a workflow command message.
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a workflow command property value.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Prints annotations for the run when running inside GitHub Actions.
+///
+/// Does nothing unless [`GITHUB_ACTIONS_ENV`] is `true`.
+///
+/// # Returns
+///
+/// `true` if running inside GitHub Actions.
+pub fn emit_annotations(summary: &RunSummary) -> bool {
+    if std::env::var(GITHUB_ACTIONS_ENV).as_deref() != Ok("true") {
+        return false;
+    }
+    for line in summary.to_annotations() {
+        println!("{line}");
+    }
+    true
+}
+
+/// Appends key run counters to the GitHub Actions step outputs.
+///
+/// Writes `run_id`, `issues_created`, `issues_skipped`, `issues_failed`,
+/// `prs_created`, `prs_updated`, `prs_failed`, `repositories_discovered` and,
+/// when given, `report_path`.
+/// Does nothing when [`GITHUB_OUTPUT_ENV`] is unset.
+///
+/// # Returns
+///
+/// `true` if outputs were written, `false` if no output file is configured.
+///
+/// # Errors
+///
+/// Returns an error if the output file cannot be opened or written.
+pub fn write_github_outputs(
+    summary: &RunSummary,
+    report_path: Option<&Path>,
+) -> std::io::Result<bool> {
+    let Some(path) = std::env::var_os(GITHUB_OUTPUT_ENV) else {
+        return Ok(false);
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+
+    writeln!(file, "run_id={}", summary.run_id)?;
+    writeln!(file, "issues_created={}", summary.issues_created)?;
+    writeln!(file, "issues_skipped={}", summary.issues_skipped)?;
+    writeln!(file, "issues_failed={}", summary.issues_failed)?;
+    writeln!(file, "prs_created={}", summary.prs_created)?;
+    writeln!(file, "prs_updated={}", summary.prs_updated)?;
+    writeln!(file, "prs_failed={}", summary.prs_failed)?;
+    writeln!(
+        file,
+        "repositories_discovered={}",
+        summary.repositories_discovered
+    )?;
+    if let Some(report_path) = report_path {
+        writeln!(file, "report_path={}", report_path.display())?;
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_kind::ErrorKind;
+    use crate::summary::StageTimings;
+    use tempfile::TempDir;
+
+    fn sample_summary() -> RunSummary {
+        let mut summary = RunSummary::new(false);
+        summary.record_result(&ProcessingResult::Success {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            issue: IssueStatus::Created {
+                number: 1,
+                url: "https://github.com/user/repo/issues/1".parse().unwrap(),
+            },
+            pr: Some(PrStatus::TimedOut),
+            timings: StageTimings::default(),
+        });
+        summary.record_result(&ProcessingResult::Failed {
+            migration_id: "test-migration".to_string(),
+            repository: "other/project".to_string(),
+            error: "50% broken\nbadly".to_string(),
+            kind: ErrorKind::Transient,
+            timings: StageTimings::default(),
+        });
+        summary
+    }
+
+    #[test]
+    fn renders_escaped_annotations() {
+        let annotations = sample_summary().to_annotations();
+
+        assert_eq!(
+            annotations,
+            [
+                "::warning title=PR timed out::user/repo (test-migration): PR generation timed out",
+                "::error title=Repository failed (transient)::other/project (test-migration): 50%25 broken%0Abadly",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_outputs() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("output");
+
+        temp_env::with_var(GITHUB_OUTPUT_ENV, Some(&path), || {
+            assert!(write_github_outputs(&sample_summary(), Some(Path::new("report.md"))).unwrap());
+        });
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("issues_created=1\n"));
+        assert!(contents.contains("issues_failed=1\n"));
+        assert!(contents.contains("report_path=report.md\n"));
+    }
+
+    #[test]
+    fn skips_outputs_when_unset() {
+        temp_env::with_var_unset(GITHUB_OUTPUT_ENV, || {
+            assert!(!write_github_outputs(&sample_summary(), None).unwrap());
+        });
+    }
+}
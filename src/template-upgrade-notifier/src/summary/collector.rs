@@ -0,0 +1,88 @@
+//! Thread-safe, incremental recording of a run's results.
+
+use super::result::ProcessingResult;
+use super::run_summary::RunSummary;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A cloneable handle to a [`RunSummary`] that concurrent tasks record into
+/// as they complete.
+///
+/// Every clone shares the same summary, so progress can be read with
+/// [`SummaryCollector::snapshot`] while a run is still in flight.
+#[derive(Debug, Clone, Default)]
+pub struct SummaryCollector {
+    summary: Arc<Mutex<RunSummary>>,
+}
+
+impl SummaryCollector {
+    /// Creates a collector starting from `summary`.
+    #[must_use]
+    pub fn new(summary: RunSummary) -> Self {
+        Self {
+            summary: Arc::new(Mutex::new(summary)),
+        }
+    }
+
+    /// Records a result, replacing an earlier result for the same repository
+    /// and migration (see [`RunSummary::replace_result`]).
+    pub fn record(&self, result: &ProcessingResult) {
+        self.lock().replace_result(result);
+    }
+
+    /// Applies an arbitrary update, e.g. to a counter.
+    pub fn update(&self, update: impl FnOnce(&mut RunSummary)) {
+        update(&mut self.lock());
+    }
+
+    /// Reads the summary without copying it.
+    pub fn read<T>(&self, read: impl FnOnce(&RunSummary) -> T) -> T {
+        read(&self.lock())
+    }
+
+    /// Starts over from `summary`, e.g. at the beginning of a new run.
+    pub fn reset(&self, summary: RunSummary) {
+        *self.lock() = summary;
+    }
+
+    /// Returns a copy of the summary as recorded so far.
+    #[must_use]
+    pub fn snapshot(&self) -> RunSummary {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RunSummary> {
+        self.summary.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summary::StageTimings;
+
+    #[tokio::test]
+    async fn records_from_concurrent_tasks() {
+        let collector = SummaryCollector::new(RunSummary::new(false));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|i| {
+                let collector = collector.clone();
+                tokio::spawn(async move {
+                    collector.record(&ProcessingResult::Skipped {
+                        migration_id: "m".to_string(),
+                        repository: format!("user/repo{i}"),
+                        reason: "duplicate".to_string(),
+                        timings: StageTimings::default(),
+                    });
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let summary = collector.snapshot();
+        assert_eq!(summary.issues_skipped, 8);
+        assert_eq!(summary.results.len(), 8);
+    }
+}
@@ -0,0 +1,307 @@
+//! Compliance matrix of outdated repositories for report-only runs.
+//!
+//! Platform teams that only need visibility get one row per outdated
+//! repository and template, with the version it is on and the newest version
+//! it could upgrade to, as CSV, JSON or markdown.
+
+use super::markdown::escape_cell;
+use super::occurrence::Occurrence;
+use crate::config::Migration;
+use core::fmt;
+use core::str::FromStr;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One outdated repository in the compliance matrix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComplianceRow {
+    /// Repository full name.
+    pub repository: String,
+    /// Template the repository was generated from: the first segment of the
+    /// migration identifier.
+    pub template: String,
+    /// Migration that found the repository.
+    pub migration_id: String,
+    /// Path to the matching file within the repository.
+    pub file_path: String,
+    /// Template version the repository is on.
+    pub current_version: String,
+    /// Newest version the template can be upgraded to.
+    pub latest_version: String,
+    /// Migrations between the repository and the latest version.
+    pub versions_behind: usize,
+}
+
+/// Repository × template × current version × latest version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ComplianceMatrix {
+    /// One row per outdated file, sorted by repository and template.
+    pub rows: Vec<ComplianceRow>,
+}
+
+impl ComplianceMatrix {
+    /// Builds the matrix from a run's occurrences.
+    ///
+    /// The current version is the one read from the default branch, or else
+    /// the migration's `old_string`. The latest version is the `new_string`
+    /// at the end of the template's chain of migrations (`v1 -> v2`,
+    /// `v2 -> v3` gives `v3`), so a repository several upgrades behind shows
+    /// the version it should end up on, and how many migrations away it is.
+    #[must_use]
+    pub fn new(occurrences: &[Occurrence], migrations: &[Migration]) -> Self {
+        let latest = latest_versions(migrations);
+        let behind: BTreeMap<String, usize> = migrations
+            .iter()
+            .map(|m| (m.id.to_string(), m.versions_behind))
+            .collect();
+        let mut rows: Vec<ComplianceRow> = occurrences
+            .iter()
+            .map(|occurrence| {
+                let template = occurrence
+                    .migration_id
+                    .split('/')
+                    .next()
+                    .unwrap_or_default();
+                ComplianceRow {
+                    repository: occurrence.repository.clone(),
+                    template: template.to_string(),
+                    migration_id: occurrence.migration_id.clone(),
+                    file_path: occurrence.file_path.clone(),
+                    current_version: occurrence
+                        .template_version
+                        .clone()
+                        .unwrap_or_else(|| occurrence.old_string.clone()),
+                    latest_version: latest
+                        .get(template)
+                        .map_or_else(|| occurrence.new_string.clone(), |v| (*v).to_string()),
+                    versions_behind: behind
+                        .get(&occurrence.migration_id)
+                        .copied()
+                        .unwrap_or(1)
+                        .max(1),
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            (&a.repository, &a.template, &a.file_path).cmp(&(
+                &b.repository,
+                &b.template,
+                &b.file_path,
+            ))
+        });
+        Self { rows }
+    }
+
+    /// Renders the matrix in `format`.
+    #[must_use]
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Csv => self.to_csv(),
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Markdown => self.to_markdown(),
+        }
+    }
+
+    /// Renders the matrix as CSV with a header row.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "repository,template,migration_id,file_path,current_version,latest_version,versions_behind\n",
+        );
+        for row in &self.rows {
+            let fields = [
+                &row.repository,
+                &row.template,
+                &row.migration_id,
+                &row.file_path,
+                &row.current_version,
+                &row.latest_version,
+            ];
+            let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            out.push_str(&fields.join(","));
+            out.push_str(&format!(",{}\n", row.versions_behind));
+        }
+        out
+    }
+
+    /// Renders the rows as a pretty-printed JSON array.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        // Rows are plain strings, which always serialize.
+        serde_json::to_string_pretty(&self.rows).unwrap_or_default()
+    }
+
+    /// Renders the matrix as a markdown table.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from(
+            "## Template Compliance\n\n\
+             | Repository | Template | Current | Latest | Behind | File |\n\
+             | --- | --- | --- | --- | --- | --- |\n",
+        );
+        for row in &self.rows {
+            out.push_str(&format!(
+                "| {} | {} | `{}` | `{}` | {} | {} |\n",
+                escape_cell(&row.repository),
+                escape_cell(&row.template),
+                escape_cell(&row.current_version),
+                escape_cell(&row.latest_version),
+                row.versions_behind,
+                escape_cell(&row.file_path),
+            ));
+        }
+        if self.rows.is_empty() {
+            out.push_str("\nNo outdated repositories found.\n");
+        }
+        out
+    }
+}
+
+/// Output format of a compliance report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// A JSON array of rows.
+    Json,
+    /// A markdown table.
+    #[default]
+    Markdown,
+}
+
+impl ReportFormat {
+    /// Every format.
+    pub const ALL: [Self; 3] = [Self::Csv, Self::Json, Self::Markdown];
+
+    /// Returns the format as it is written on the command line.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Markdown => "markdown",
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| format!("unknown report format '{s}'; expected csv, json or markdown"))
+    }
+}
+
+/// Returns the newest version of each template: the `new_string` of a
+/// migration no other migration of the template upgrades from.
+fn latest_versions(migrations: &[Migration]) -> BTreeMap<&str, &str> {
+    let upgraded_from: BTreeSet<(&str, &str)> = migrations
+        .iter()
+        .map(|m| (m.template(), m.old_string.as_str()))
+        .collect();
+    let mut latest = BTreeMap::new();
+    for migration in migrations {
+        let template = migration.template();
+        if !upgraded_from.contains(&(template, migration.new_string.as_str())) {
+            latest.insert(template, migration.new_string.as_str());
+        }
+    }
+    latest
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(id: &str, old_string: &str, new_string: &str) -> Migration {
+        Migration {
+            id: id.parse().unwrap(),
+            old_string: old_string.to_string(),
+            new_string: new_string.to_string(),
+            versions_behind: 1,
+            ..Migration::default()
+        }
+    }
+
+    fn occurrence(migration_id: &str, repository: &str, version: Option<&str>) -> Occurrence {
+        Occurrence {
+            migration_id: migration_id.to_string(),
+            repository: repository.to_string(),
+            file_path: "template-version.txt".to_string(),
+            file_url: String::new(),
+            old_string: "tpl:1".to_string(),
+            new_string: "tpl:2".to_string(),
+            template_version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn reports_end_of_migration_chain_as_latest() {
+        let mut migrations = [
+            migration("tpl/v1-to-v2", "tpl:1", "tpl:2"),
+            migration("tpl/v2-to-v3", "tpl:2", "tpl:3"),
+        ];
+        migrations[0].versions_behind = 2;
+        let occurrences = [
+            occurrence("tpl/v1-to-v2", "user/b", None),
+            occurrence("tpl/v1-to-v2", "user/a", Some("tpl:1.0.0-3-gabc")),
+        ];
+
+        let matrix = ComplianceMatrix::new(&occurrences, &migrations);
+
+        assert_eq!(matrix.rows.len(), 2);
+        assert_eq!(matrix.rows[0].repository, "user/a");
+        assert_eq!(matrix.rows[0].template, "tpl");
+        assert_eq!(matrix.rows[0].current_version, "tpl:1.0.0-3-gabc");
+        assert_eq!(matrix.rows[1].current_version, "tpl:1");
+        assert_eq!(matrix.rows[1].latest_version, "tpl:3");
+        assert_eq!(matrix.rows[1].versions_behind, 2);
+    }
+
+    #[test]
+    fn renders_every_format() {
+        let migrations = [migration("tpl/v1-to-v2", "tpl:1", "tpl:2")];
+        let mut occurrence = occurrence("tpl/v1-to-v2", "user/a", None);
+        occurrence.file_path = "a,b.txt".to_string();
+        let matrix = ComplianceMatrix::new(&[occurrence], &migrations);
+
+        assert_eq!(
+            matrix.render(ReportFormat::Csv),
+            "repository,template,migration_id,file_path,current_version,latest_version,versions_behind\n\
+             user/a,tpl,tpl/v1-to-v2,\"a,b.txt\",tpl:1,tpl:2,1\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&matrix.render(ReportFormat::Json)).unwrap();
+        assert_eq!(json[0]["latest_version"], "tpl:2");
+        assert!(matrix
+            .render(ReportFormat::Markdown)
+            .contains("| user/a | tpl | `tpl:1` | `tpl:2` | 1 | a,b.txt |"));
+    }
+
+    #[test]
+    fn parses_formats() {
+        for format in ReportFormat::ALL {
+            assert_eq!(format.as_str().parse::<ReportFormat>(), Ok(format));
+        }
+        assert!("xml".parse::<ReportFormat>().is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Dry-run estimates of the API quota and LLM runs a live run would use.
+
+use crate::config::Migration;
+use serde::{Deserialize, Serialize};
+
+/// Code search results per page.
+const SEARCH_PAGE_SIZE: u64 = 100;
+
+/// Repositories per GraphQL metadata or duplicate lookup.
+const GRAPHQL_BATCH_SIZE: u64 = 50;
+
+/// Core requests to notify one repository: reading the template file on the
+/// default branch, reading its preferences file, creating the issue and
+/// labelling it.
+const CORE_PER_ISSUE: u64 = 4;
+
+/// Core requests to open one auto-fix PR: looking for an earlier PR,
+/// creating the PR, labelling it, requesting reviews and linking it from the
+/// issue. Cloning and pushing go over git and cost no API quota.
+const CORE_PER_PR: u64 = 5;
+
+/// The API quota, and LLM runs, a live run of one migration would use.
+///
+/// Counts are upper bounds: every discovered repository is assumed to be
+/// notified, although the ledger, cooldowns, opt-outs and duplicates skip
+/// some in practice, and an escalating strategy counts as an LLM run
+/// although earlier steps may finish the migration first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaEstimate {
+    /// Migration identifier; empty for a run total.
+    pub migration_id: String,
+    /// Repositories the live run would process.
+    pub repositories: usize,
+    /// Code search requests, limited per minute.
+    pub search_requests: u64,
+    /// GraphQL queries, counted against the GraphQL hourly limit.
+    pub graphql_requests: u64,
+    /// Core REST requests, counted against the core hourly limit.
+    pub core_requests: u64,
+    /// LLM agent runs.
+    pub llm_runs: u64,
+    /// Estimated LLM cost in US dollars, if a cost per run is configured.
+    pub llm_cost: Option<f64>,
+}
+
+impl QuotaEstimate {
+    /// Estimates a live run of `migration` over `repositories` discovered
+    /// repositories.
+    ///
+    /// With `auto_pr`, every notified repository also gets a PR, which starts
+    /// an LLM run if the migration's strategy uses one; `llm_cost_per_run`
+    /// prices those runs.
+    #[must_use]
+    pub fn for_migration(
+        migration: &Migration,
+        repositories: usize,
+        auto_pr: bool,
+        llm_cost_per_run: Option<f64>,
+    ) -> Self {
+        let count = repositories as u64;
+        // Queries over the 1,000-result cap are split by file size, so every
+        // result page is read; finding the split costs a few requests more.
+        let pages = count.div_ceil(SEARCH_PAGE_SIZE).max(1);
+        let search_requests = migration.target_files().len() as u64 * pages;
+        // One batch for discovery metadata, one for duplicate issues.
+        let graphql_requests = 2 * count.div_ceil(GRAPHQL_BATCH_SIZE);
+
+        let mut per_repository = CORE_PER_ISSUE;
+        if migration.commit_check.is_some() {
+            per_repository += 1;
+        }
+        if migration.dispatch.is_some() {
+            per_repository += 1;
+        }
+        if auto_pr {
+            per_repository += CORE_PER_PR;
+        }
+        let llm_runs = if auto_pr && migration.strategy.uses_llm() {
+            count
+        } else {
+            0
+        };
+
+        Self {
+            migration_id: migration.id.to_string(),
+            repositories,
+            search_requests,
+            graphql_requests,
+            core_requests: count * per_repository,
+            llm_runs,
+            llm_cost: llm_cost_per_run.map(|cost| cost * llm_runs as f64),
+        }
+    }
+
+    /// Returns the sum of `estimates`, with an empty migration identifier.
+    #[must_use]
+    pub fn total<'a>(estimates: impl IntoIterator<Item = &'a Self>) -> Self {
+        estimates
+            .into_iter()
+            .fold(Self::default(), |mut total, estimate| {
+                total.repositories += estimate.repositories;
+                total.search_requests += estimate.search_requests;
+                total.graphql_requests += estimate.graphql_requests;
+                total.core_requests += estimate.core_requests;
+                total.llm_runs += estimate.llm_runs;
+                total.llm_cost = match (total.llm_cost, estimate.llm_cost) {
+                    (None, None) => None,
+                    (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+                };
+                total
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pull_requests::MigrationStrategy;
+
+    fn migration(strategy: MigrationStrategy) -> Migration {
+        Migration {
+            id: "tpl/v1-to-v2".parse().unwrap(),
+            old_string: "tpl:1".to_string(),
+            new_string: "tpl:2".to_string(),
+            target_file: "template-version.txt".to_string(),
+            strategy,
+            ..Migration::default()
+        }
+    }
+
+    #[test]
+    fn estimates_issues_only() {
+        let estimate =
+            QuotaEstimate::for_migration(&migration(MigrationStrategy::Llm), 120, false, None);
+
+        assert_eq!(estimate.search_requests, 2);
+        assert_eq!(estimate.graphql_requests, 6);
+        assert_eq!(estimate.core_requests, 120 * CORE_PER_ISSUE);
+        assert_eq!(estimate.llm_runs, 0);
+        assert_eq!(estimate.llm_cost, None);
+    }
+
+    #[test]
+    fn counts_llm_runs_of_auto_prs() {
+        let llm =
+            QuotaEstimate::for_migration(&migration(MigrationStrategy::Llm), 10, true, Some(0.5));
+        let escalate =
+            MigrationStrategy::Escalate(vec![MigrationStrategy::Replace, MigrationStrategy::Llm]);
+        let escalating = QuotaEstimate::for_migration(&migration(escalate), 10, true, None);
+        let replace = QuotaEstimate::for_migration(
+            &migration(MigrationStrategy::Replace),
+            10,
+            true,
+            Some(0.5),
+        );
+
+        assert_eq!(llm.core_requests, 10 * (CORE_PER_ISSUE + CORE_PER_PR));
+        assert_eq!(llm.llm_runs, 10);
+        assert_eq!(llm.llm_cost, Some(5.0));
+        assert_eq!(escalating.llm_runs, 10);
+        assert_eq!(replace.llm_runs, 0);
+        assert_eq!(replace.llm_cost, Some(0.0));
+    }
+
+    #[test]
+    fn counts_every_search_page() {
+        let estimate = QuotaEstimate::for_migration(
+            &migration(MigrationStrategy::Replace),
+            5_000,
+            false,
+            None,
+        );
+
+        assert_eq!(estimate.search_requests, 50);
+    }
+
+    #[test]
+    fn sums_estimates() {
+        let a =
+            QuotaEstimate::for_migration(&migration(MigrationStrategy::Llm), 10, true, Some(1.0));
+        let b =
+            QuotaEstimate::for_migration(&migration(MigrationStrategy::Replace), 5, false, None);
+
+        let total = QuotaEstimate::total([&a, &b]);
+
+        assert_eq!(total.migration_id, "");
+        assert_eq!(total.repositories, 15);
+        assert_eq!(total.core_requests, a.core_requests + b.core_requests);
+        assert_eq!(total.llm_cost, Some(10.0));
+    }
+}
@@ -0,0 +1,294 @@
+//! Mapping of run outcomes to a pass/warn/fail verdict.
+
+use super::result::ProcessingResult;
+use super::run_summary::RunSummary;
+use crate::error_kind::ErrorKind;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use serde::Deserialize;
+
+/// Skip reason recorded when the token cannot write to a repository.
+pub(crate) const PERMISSION_DENIED_REASON: &str = "no write access";
+
+/// How much an outcome category matters for the overall verdict.
+///
+/// Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    /// Doesn't affect the verdict.
+    Ignore,
+    /// Reported as a warning; the run still succeeds.
+    Warn,
+    /// Fails the run.
+    Fail,
+}
+
+/// Which outcomes count as failures, read from the `[exit-policy]` section
+/// of `notifier.toml`.
+///
+/// The defaults fail the run on any issue or PR failure (including timeouts)
+/// and ignore skips.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ExitPolicy {
+    /// A repository could not be processed at all.
+    pub repository_failed: Severity,
+    /// Issue creation failed.
+    pub issue_failed: Severity,
+    /// PR creation failed.
+    pub pr_failed: Severity,
+    /// The LLM timed out while generating a PR.
+    pub pr_timed_out: Severity,
+    /// The token had no write access to a repository.
+    pub permission_denied: Severity,
+    /// Any other skip (duplicates, no changes, ...).
+    pub skipped: Severity,
+}
+
+impl Default for ExitPolicy {
+    fn default() -> Self {
+        Self {
+            repository_failed: Severity::Fail,
+            issue_failed: Severity::Fail,
+            pr_failed: Severity::Fail,
+            pr_timed_out: Severity::Fail,
+            permission_denied: Severity::Ignore,
+            skipped: Severity::Ignore,
+        }
+    }
+}
+
+/// Overall verdict of a run under an [`ExitPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// Nothing noteworthy happened.
+    Success,
+    /// Only outcomes configured as warnings occurred.
+    Warnings,
+    /// At least one outcome configured as a failure occurred.
+    Failure,
+    /// Like [`RunOutcome::Failure`], but a repository failed because the
+    /// token was rejected; rerunning needs new credentials, not just time.
+    Unauthorized,
+    /// The run hit its runtime limit and left work unfinished.
+    TimedOut,
+    /// The run exceeded its abort threshold and left work unfinished.
+    Aborted,
+}
+
+impl RunOutcome {
+    /// Returns the process exit code for this outcome.
+    #[must_use]
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Success | Self::Warnings => 0,
+            Self::Failure => 1,
+            Self::TimedOut => 3,
+            Self::Aborted => 4,
+            Self::Unauthorized => 5,
+        }
+    }
+}
+
+impl ExitPolicy {
+    /// Evaluates the per-repository results of a run against this policy.
+    ///
+    /// A run that stopped at its abort threshold is always
+    /// [`RunOutcome::Aborted`], and one that stopped at its runtime limit
+    /// [`RunOutcome::TimedOut`]. A run that refused an unconfirmed large
+    /// campaign is a [`RunOutcome::Failure`], and a failing run where a
+    /// repository failed with an [`ErrorKind::Auth`] error is
+    /// [`RunOutcome::Unauthorized`].
+    #[must_use]
+    pub fn evaluate(&self, summary: &RunSummary) -> RunOutcome {
+        if summary.aborted {
+            return RunOutcome::Aborted;
+        }
+        if summary.timed_out {
+            return RunOutcome::TimedOut;
+        }
+        if !summary.unconfirmed_migrations.is_empty() {
+            return RunOutcome::Failure;
+        }
+
+        let worst = summary
+            .results
+            .iter()
+            .flat_map(|result| self.severities(result))
+            .max()
+            .unwrap_or(Severity::Ignore);
+
+        let unauthorized = summary
+            .results
+            .iter()
+            .any(|result| result.error_kind() == Some(ErrorKind::Auth));
+        match worst {
+            Severity::Ignore => RunOutcome::Success,
+            Severity::Warn => RunOutcome::Warnings,
+            Severity::Fail if unauthorized => RunOutcome::Unauthorized,
+            Severity::Fail => RunOutcome::Failure,
+        }
+    }
+
+    /// Returns the severities of every outcome within a single result.
+    fn severities(&self, result: &ProcessingResult) -> [Severity; 2] {
+        match result {
+            ProcessingResult::Success { issue, pr, .. } => {
+                let issue = match issue {
+                    IssueStatus::Failed { .. } => self.issue_failed,
+                    IssueStatus::Skipped { reason } => self.skip_severity(reason),
+                    IssueStatus::Created { .. } | IssueStatus::Pending => Severity::Ignore,
+                };
+                let pr = pr
+                    .as_ref()
+                    .map_or(Severity::Ignore, |pr| self.pr_severity(pr));
+                [issue, pr]
+            }
+            ProcessingResult::PrOnly { pr, .. } => [Severity::Ignore, self.pr_severity(pr)],
+            ProcessingResult::Dispatched { .. } => [Severity::Ignore, Severity::Ignore],
+            ProcessingResult::Skipped { reason, .. } => {
+                [self.skip_severity(reason), Severity::Ignore]
+            }
+            ProcessingResult::Failed { .. } => [self.repository_failed, Severity::Ignore],
+        }
+    }
+
+    fn pr_severity(&self, pr: &PrStatus) -> Severity {
+        match pr {
+            PrStatus::Failed { .. } => self.pr_failed,
+            PrStatus::TimedOut => self.pr_timed_out,
+            PrStatus::Skipped { reason } => self.skip_severity(reason),
+            PrStatus::Created { .. } | PrStatus::Updated { .. } | PrStatus::Pending => {
+                Severity::Ignore
+            }
+        }
+    }
+
+    fn skip_severity(&self, reason: &str) -> Severity {
+        if reason == PERMISSION_DENIED_REASON {
+            self.permission_denied
+        } else {
+            self.skipped
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summary::StageTimings;
+
+    fn summary_with(result: ProcessingResult) -> RunSummary {
+        let mut summary = RunSummary::new(false);
+        summary.record_result(&result);
+        summary
+    }
+
+    fn timed_out() -> ProcessingResult {
+        ProcessingResult::Success {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            issue: IssueStatus::Created {
+                number: 1,
+                url: "https://github.com/user/repo/issues/1".parse().unwrap(),
+            },
+            pr: Some(PrStatus::TimedOut),
+            timings: StageTimings::default(),
+        }
+    }
+
+    fn permission_denied() -> ProcessingResult {
+        ProcessingResult::Success {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            issue: IssueStatus::Skipped {
+                reason: PERMISSION_DENIED_REASON.to_string(),
+            },
+            pr: None,
+            timings: StageTimings::default(),
+        }
+    }
+
+    #[test]
+    fn default_policy_matches_legacy_mapping() {
+        let policy = ExitPolicy::default();
+        assert_eq!(
+            policy.evaluate(&RunSummary::new(false)),
+            RunOutcome::Success
+        );
+        assert_eq!(
+            policy.evaluate(&summary_with(timed_out())),
+            RunOutcome::Failure
+        );
+        assert_eq!(
+            policy.evaluate(&summary_with(permission_denied())),
+            RunOutcome::Success
+        );
+    }
+
+    #[test]
+    fn timeouts_can_be_warnings() {
+        let policy: ExitPolicy = toml::from_str(r#"pr-timed-out = "warn""#).unwrap();
+        let outcome = policy.evaluate(&summary_with(timed_out()));
+        assert_eq!(outcome, RunOutcome::Warnings);
+        assert_eq!(outcome.exit_code(), 0);
+    }
+
+    #[test]
+    fn permission_denied_can_fail() {
+        let policy: ExitPolicy = toml::from_str(r#"permission-denied = "fail""#).unwrap();
+        assert_eq!(
+            policy.evaluate(&summary_with(permission_denied())),
+            RunOutcome::Failure
+        );
+    }
+
+    #[test]
+    fn timed_out_run_has_distinct_outcome() {
+        let mut summary = RunSummary::new(false);
+        summary.timed_out = true;
+        let outcome = ExitPolicy::default().evaluate(&summary);
+        assert_eq!(outcome, RunOutcome::TimedOut);
+        assert_eq!(outcome.exit_code(), 3);
+    }
+
+    #[test]
+    fn unconfirmed_large_campaign_fails() {
+        let mut summary = RunSummary::new(false);
+        summary
+            .unconfirmed_migrations
+            .push("test-migration".to_string());
+        let outcome = ExitPolicy::default().evaluate(&summary);
+        assert_eq!(outcome, RunOutcome::Failure);
+        assert_eq!(outcome.exit_code(), 1);
+    }
+
+    #[test]
+    fn rejected_token_has_distinct_outcome() {
+        let failed = |kind| ProcessingResult::Failed {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            error: "Bad credentials".to_string(),
+            kind,
+            timings: StageTimings::default(),
+        };
+
+        let outcome = ExitPolicy::default().evaluate(&summary_with(failed(ErrorKind::Auth)));
+        assert_eq!(outcome, RunOutcome::Unauthorized);
+        assert_eq!(outcome.exit_code(), 5);
+        assert_eq!(
+            ExitPolicy::default().evaluate(&summary_with(failed(ErrorKind::Transient))),
+            RunOutcome::Failure
+        );
+    }
+
+    #[test]
+    fn aborted_run_has_distinct_outcome() {
+        let mut summary = RunSummary::new(false);
+        summary.aborted = true;
+        let outcome = ExitPolicy::default().evaluate(&summary);
+        assert_eq!(outcome, RunOutcome::Aborted);
+        assert_eq!(outcome.exit_code(), 4);
+    }
+}
@@ -0,0 +1,335 @@
+//! Markdown report rendering and GitHub Actions step summary output.
+
+use super::result::ProcessingResult;
+use super::run_summary::RunSummary;
+use super::timings::format_duration;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::redact::redact;
+use core::fmt::Write;
+use std::io::Write as _;
+use std::path::Path;
+
+/// Environment variable GitHub Actions sets to the step summary file path.
+pub const GITHUB_STEP_SUMMARY_ENV: &str = "GITHUB_STEP_SUMMARY";
+
+impl RunSummary {
+    /// Renders the run as a human-readable markdown report.
+    ///
+    /// The report contains the aggregate counters followed by tables of
+    /// created issues, created PRs, skipped repositories, failures and
+    /// per-stage durations.
+    /// Secrets are redacted from the rendered text.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        // Writing into a `String` cannot fail.
+        let _ = self.write_markdown(&mut out);
+        redact(&out)
+    }
+
+    fn write_markdown(&self, out: &mut String) -> core::fmt::Result {
+        let mode = if self.dry_run { "Dry Run" } else { "Live" };
+        writeln!(out, "## Template Upgrade Notifier Report")?;
+        writeln!(out)?;
+        writeln!(out, "| Metric | Value |")?;
+        writeln!(out, "| ------ | ----- |")?;
+        writeln!(out, "| Mode | {mode} |")?;
+        if !self.run_id.is_empty() {
+            writeln!(out, "| Run ID | `{}` |", self.run_id)?;
+        }
+        if let Some(campaign) = &self.campaign {
+            writeln!(out, "| Campaign | {} |", escape_cell(campaign))?;
+        }
+        writeln!(
+            out,
+            "| Migrations processed | {} |",
+            self.migrations_processed
+        )?;
+        writeln!(
+            out,
+            "| Repositories discovered | {} |",
+            self.repositories_discovered
+        )?;
+        if !self.dry_run {
+            writeln!(out, "| Issues created | {} |", self.issues_created)?;
+            writeln!(out, "| Issues skipped | {} |", self.issues_skipped)?;
+            writeln!(out, "| Issues failed | {} |", self.issues_failed)?;
+            writeln!(out, "| PRs created | {} |", self.prs_created)?;
+            if self.prs_updated > 0 {
+                writeln!(out, "| PRs updated | {} |", self.prs_updated)?;
+            }
+            writeln!(out, "| PRs failed | {} |", self.prs_failed)?;
+            if self.pr_only > 0 {
+                writeln!(out, "| PR-only (issues disabled) | {} |", self.pr_only)?;
+            }
+            if self.dispatched > 0 {
+                writeln!(out, "| Repository dispatches | {} |", self.dispatched)?;
+            }
+            if self.notifications_closed > 0 {
+                writeln!(
+                    out,
+                    "| Closed (upgraded independently) | {} |",
+                    self.notifications_closed
+                )?;
+            }
+        }
+
+        let mut issues = Vec::new();
+        let mut prs = Vec::new();
+        let mut skipped = Vec::new();
+        let mut failures = Vec::new();
+        for result in &self.results {
+            match result {
+                ProcessingResult::Success {
+                    repository,
+                    issue,
+                    pr,
+                    ..
+                } => {
+                    match issue {
+                        IssueStatus::Created { number, url } => {
+                            issues.push((repository, format!("[#{number}]({url})")));
+                        }
+                        IssueStatus::Skipped { reason } => skipped.push((repository, reason)),
+                        IssueStatus::Failed { error } => failures.push((repository, error.clone())),
+                        IssueStatus::Pending => {}
+                    }
+                    match pr {
+                        Some(
+                            PrStatus::Created { number, url } | PrStatus::Updated { number, url },
+                        ) => {
+                            prs.push((repository, format!("[#{number}]({url})")));
+                        }
+                        Some(PrStatus::Failed { error, .. }) => {
+                            failures.push((repository, error.clone()));
+                        }
+                        _ => {}
+                    }
+                }
+                ProcessingResult::PrOnly { repository, pr, .. } => match pr {
+                    PrStatus::Created { number, url } | PrStatus::Updated { number, url } => {
+                        prs.push((repository, format!("[#{number}]({url})")));
+                    }
+                    PrStatus::Failed { error, .. } => failures.push((repository, error.clone())),
+                    _ => {}
+                },
+                ProcessingResult::Dispatched { .. } => {}
+                ProcessingResult::Skipped {
+                    repository, reason, ..
+                } => {
+                    skipped.push((repository, reason));
+                }
+                ProcessingResult::Failed {
+                    repository,
+                    error,
+                    kind,
+                    ..
+                } => {
+                    failures.push((repository, format!("{error} ({kind})")));
+                }
+            }
+        }
+
+        write_table(out, "Created Issues", ("Repository", "Issue"), &issues)?;
+        write_table(out, "Created PRs", ("Repository", "Pull Request"), &prs)?;
+        write_table(out, "Skipped", ("Repository", "Reason"), &skipped)?;
+        write_table(out, "Failures", ("Repository", "Reason"), &failures)?;
+        self.write_stage_durations(out)
+    }
+
+    /// Writes the per-stage duration percentiles, omitted when nothing was timed.
+    fn write_stage_durations(&self, out: &mut String) -> core::fmt::Result {
+        let percentiles = self.stage_percentiles();
+        if percentiles.is_empty() {
+            return Ok(());
+        }
+        writeln!(out)?;
+        writeln!(out, "### Stage Durations")?;
+        writeln!(out)?;
+        writeln!(out, "| Stage | Samples | Total | p50 | p90 | p99 | Max |")?;
+        writeln!(out, "| --- | --- | --- | --- | --- | --- | --- |")?;
+        for p in percentiles {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                p.stage.as_str(),
+                p.samples,
+                format_duration(p.total),
+                format_duration(p.p50),
+                format_duration(p.p90),
+                format_duration(p.p99),
+                format_duration(p.max)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a two-column table section, omitting it entirely when empty.
+fn write_table<T: AsRef<str>>(
+    out: &mut String,
+    heading: &str,
+    columns: (&str, &str),
+    rows: &[(&String, T)],
+) -> core::fmt::Result {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(out)?;
+    writeln!(out, "### {heading}")?;
+    writeln!(out)?;
+    writeln!(out, "| {} | {} |", columns.0, columns.1)?;
+    writeln!(out, "| --- | --- |")?;
+    for (repository, value) in rows {
+        writeln!(
+            out,
+            "| {} | {} |",
+            escape_cell(repository),
+            escape_cell(value.as_ref())
+        )?;
+    }
+    Ok(())
+}
+
+/// Escapes characters that would break a markdown table cell.
+pub(super) fn escape_cell(value: &str) -> String {
+    value
+        .replace('|', "\\|")
+        .replace("\r\n", " ")
+        .replace('\n', " ")
+}
+
+/// Writes the markdown report for a run to a file, replacing existing contents.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn write_markdown_report(summary: &RunSummary, path: &Path) -> std::io::Result<()> {
+    std::fs::write(path, summary.to_markdown())
+}
+
+/// Appends the markdown report to the GitHub Actions step summary.
+///
+/// Does nothing when [`GITHUB_STEP_SUMMARY_ENV`] is unset, which is the case
+/// outside of GitHub Actions.
+///
+/// # Returns
+///
+/// `true` if the report was appended, `false` if no step summary is configured.
+///
+/// # Errors
+///
+/// Returns an error if the step summary file cannot be opened or written.
+pub fn append_step_summary(summary: &RunSummary) -> std::io::Result<bool> {
+    let Some(path) = std::env::var_os(GITHUB_STEP_SUMMARY_ENV) else {
+        return Ok(false);
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(summary.to_markdown().as_bytes())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_kind::ErrorKind;
+    use crate::summary::{Stage, StageTimings, TimingRecord};
+    use core::time::Duration;
+    use tempfile::TempDir;
+
+    fn sample_summary() -> RunSummary {
+        let mut summary = RunSummary::new(false);
+        summary.record_result(&ProcessingResult::Success {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            issue: IssueStatus::Created {
+                number: 3,
+                url: "https://github.com/user/repo/issues/3".parse().unwrap(),
+            },
+            pr: Some(PrStatus::Created {
+                number: 4,
+                url: "https://github.com/user/repo/pull/4".parse().unwrap(),
+            }),
+            timings: StageTimings::default(),
+        });
+        summary.record_result(&ProcessingResult::Failed {
+            migration_id: "test-migration".to_string(),
+            repository: "other/project".to_string(),
+            error: "bad | thing\nhappened".to_string(),
+            kind: ErrorKind::Other,
+            timings: StageTimings::default(),
+        });
+        summary
+    }
+
+    #[test]
+    fn renders_tables_for_results() {
+        let markdown = sample_summary().to_markdown();
+
+        assert!(markdown.contains("| Issues created | 1 |"));
+        assert!(markdown.contains("### Created Issues"));
+        assert!(markdown.contains("| user/repo | [#3](https://github.com/user/repo/issues/3) |"));
+        assert!(markdown.contains("| user/repo | [#4](https://github.com/user/repo/pull/4) |"));
+        assert!(markdown.contains("| other/project | bad \\| thing happened (other) |"));
+        assert!(!markdown.contains("### Skipped"));
+    }
+
+    #[test]
+    fn dry_run_omits_live_counters() {
+        let markdown = RunSummary::new(true).to_markdown();
+
+        assert!(markdown.contains("| Mode | Dry Run |"));
+        assert!(!markdown.contains("Issues created"));
+    }
+
+    #[test]
+    fn renders_stage_durations() {
+        let mut summary = sample_summary();
+        assert!(!summary.to_markdown().contains("### Stage Durations"));
+
+        let mut timings = StageTimings::default();
+        timings.add(Stage::Llm, Duration::from_secs(90));
+        summary.record_timings(TimingRecord {
+            migration_id: "test-migration".to_string(),
+            repository: Some("user/repo".to_string()),
+            timings,
+        });
+
+        let markdown = summary.to_markdown();
+        assert!(markdown.contains("### Stage Durations"));
+        assert!(markdown.contains("| llm | 1 | 90.0s | 90.0s | 90.0s | 90.0s | 90.0s |"));
+    }
+
+    #[test]
+    fn labels_campaign() {
+        let mut summary = RunSummary::new(true);
+        summary.campaign = Some("Template v2".to_string());
+
+        assert!(summary.to_markdown().contains("| Campaign | Template v2 |"));
+    }
+
+    #[test]
+    fn appends_to_step_summary() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("summary.md");
+        std::fs::write(&path, "existing\n").unwrap();
+
+        temp_env::with_var(GITHUB_STEP_SUMMARY_ENV, Some(&path), || {
+            assert!(append_step_summary(&sample_summary()).unwrap());
+        });
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("existing\n## Template Upgrade Notifier Report"));
+    }
+
+    #[test]
+    fn skips_step_summary_when_unset() {
+        temp_env::with_var_unset(GITHUB_STEP_SUMMARY_ENV, || {
+            assert!(!append_step_summary(&sample_summary()).unwrap());
+        });
+    }
+}
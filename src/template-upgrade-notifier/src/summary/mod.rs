@@ -1,7 +1,30 @@
 //! Run summary types and helpers.
 
+mod abort_threshold;
+mod actions;
+mod collector;
+mod compliance;
+mod estimate;
+mod exit_policy;
+mod markdown;
+mod occurrence;
 mod result;
 mod run_summary;
+mod sarif;
+mod table;
+mod timings;
 
+pub use abort_threshold::AbortThreshold;
+pub use actions::{emit_annotations, write_github_outputs, GITHUB_ACTIONS_ENV, GITHUB_OUTPUT_ENV};
+pub use collector::SummaryCollector;
+pub use compliance::{ComplianceMatrix, ComplianceRow, ReportFormat};
+pub use estimate::QuotaEstimate;
+pub(crate) use exit_policy::PERMISSION_DENIED_REASON;
+pub use exit_policy::{ExitPolicy, RunOutcome, Severity};
+pub use markdown::{append_step_summary, write_markdown_report, GITHUB_STEP_SUMMARY_ENV};
+pub use occurrence::Occurrence;
 pub use result::ProcessingResult;
 pub use run_summary::RunSummary;
+pub use sarif::write_sarif_report;
+pub use table::MigrationTotals;
+pub use timings::{Stage, StagePercentiles, StageTimings, TimingRecord};
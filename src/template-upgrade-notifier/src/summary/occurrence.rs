@@ -0,0 +1,41 @@
+//! Outdated template occurrences found during discovery.
+
+use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
+use serde::{Deserialize, Serialize};
+
+/// A file in a repository that still contains a migration's old string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Occurrence {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Repository full name.
+    pub repository: String,
+    /// Path to the matching file within the repository.
+    pub file_path: String,
+    /// GitHub URL to the matching file.
+    pub file_url: String,
+    /// The outdated version string that was found.
+    pub old_string: String,
+    /// The version string to upgrade to.
+    pub new_string: String,
+    /// Template version read from the default branch, in report-only runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_version: Option<String>,
+}
+
+impl Occurrence {
+    /// Creates an occurrence for a repository discovered by a migration.
+    #[must_use]
+    pub fn new(migration: &Migration, repository: &DiscoveredRepository) -> Self {
+        Self {
+            migration_id: migration.id.to_string(),
+            repository: repository.full_name.to_string(),
+            file_path: repository.file_path.clone(),
+            file_url: repository.file_url.to_string(),
+            old_string: migration.old_string.clone(),
+            new_string: migration.new_string.clone(),
+            template_version: repository.template_version.clone(),
+        }
+    }
+}
@@ -1,34 +1,226 @@
 //! Processing result types.
 
+use super::timings::StageTimings;
+use crate::error_kind::ErrorKind;
 use crate::issues::IssueStatus;
 use crate::pull_requests::PrStatus;
+use serde::{Deserialize, Serialize};
 
 /// Result of processing a single repository.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
 pub enum ProcessingResult {
     /// Processing succeeded.
     Success {
+        /// Migration identifier the repository was processed for.
+        migration_id: String,
         /// Repository full name.
         repository: String,
         /// Issue creation status.
         issue: IssueStatus,
         /// Optional PR creation status.
         pr: Option<PrStatus>,
+        /// Time spent in each stage while processing the repository.
+        #[serde(default, skip_serializing_if = "StageTimings::is_empty")]
+        timings: StageTimings,
+    },
+
+    /// Issues are disabled, so the auto-fix PR was opened without an issue.
+    PrOnly {
+        /// Migration identifier the repository was processed for.
+        migration_id: String,
+        /// Repository full name.
+        repository: String,
+        /// PR creation status.
+        pr: PrStatus,
+        /// Time spent in each stage while processing the repository.
+        #[serde(default, skip_serializing_if = "StageTimings::is_empty")]
+        timings: StageTimings,
+    },
+
+    /// A `repository_dispatch` event was sent instead of an issue.
+    Dispatched {
+        /// Migration identifier the repository was processed for.
+        migration_id: String,
+        /// Repository full name.
+        repository: String,
+        /// Event type of the dispatch.
+        event_type: String,
+        /// Time spent in each stage while processing the repository.
+        #[serde(default, skip_serializing_if = "StageTimings::is_empty")]
+        timings: StageTimings,
     },
 
     /// Processing was skipped.
     Skipped {
+        /// Migration identifier the repository was processed for.
+        migration_id: String,
         /// Repository full name.
         repository: String,
         /// Reason for skipping.
         reason: String,
+        /// Time spent in each stage while processing the repository.
+        #[serde(default, skip_serializing_if = "StageTimings::is_empty")]
+        timings: StageTimings,
     },
 
     /// Processing failed.
     Failed {
+        /// Migration identifier the repository was processed for.
+        migration_id: String,
         /// Repository full name.
         repository: String,
         /// Error message.
         error: String,
+        /// What kind of failure it was.
+        #[serde(default)]
+        kind: ErrorKind,
+        /// Time spent in each stage while processing the repository.
+        #[serde(default, skip_serializing_if = "StageTimings::is_empty")]
+        timings: StageTimings,
     },
 }
+
+impl ProcessingResult {
+    /// Returns the repository full name this result belongs to.
+    #[must_use]
+    pub fn repository(&self) -> &str {
+        match self {
+            Self::Success { repository, .. }
+            | Self::PrOnly { repository, .. }
+            | Self::Dispatched { repository, .. }
+            | Self::Skipped { repository, .. }
+            | Self::Failed { repository, .. } => repository,
+        }
+    }
+
+    /// Returns the migration identifier this result belongs to.
+    #[must_use]
+    pub fn migration_id(&self) -> &str {
+        match self {
+            Self::Success { migration_id, .. }
+            | Self::PrOnly { migration_id, .. }
+            | Self::Dispatched { migration_id, .. }
+            | Self::Skipped { migration_id, .. }
+            | Self::Failed { migration_id, .. } => migration_id,
+        }
+    }
+
+    /// Returns the time spent in each stage while processing the repository.
+    #[must_use]
+    pub fn timings(&self) -> &StageTimings {
+        match self {
+            Self::Success { timings, .. }
+            | Self::PrOnly { timings, .. }
+            | Self::Dispatched { timings, .. }
+            | Self::Skipped { timings, .. }
+            | Self::Failed { timings, .. } => timings,
+        }
+    }
+
+    /// Replaces the time spent in each stage.
+    pub fn set_timings(&mut self, stages: StageTimings) {
+        match self {
+            Self::Success { timings, .. }
+            | Self::PrOnly { timings, .. }
+            | Self::Dispatched { timings, .. }
+            | Self::Skipped { timings, .. }
+            | Self::Failed { timings, .. } => *timings = stages,
+        }
+    }
+
+    /// Returns the kind of failure, if processing failed.
+    #[must_use]
+    pub fn error_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::Failed { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Returns true if processing failed with a transient error, or was
+    /// abandoned by a cancellation.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.error_kind()
+            .is_some_and(|kind| kind.is_retryable() || kind == ErrorKind::Cancelled)
+    }
+
+    /// Returns true if the repository was processed but its auto-fix PR
+    /// failed with a transient error.
+    #[must_use]
+    pub fn has_retryable_pr(&self) -> bool {
+        match self {
+            Self::Success { pr: Some(pr), .. } | Self::PrOnly { pr, .. } => pr.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summary::Stage;
+    use core::time::Duration;
+
+    #[test]
+    fn only_transient_failures_are_retryable() {
+        let failed = |kind| ProcessingResult::Failed {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            error: "error".to_string(),
+            kind,
+            timings: StageTimings::default(),
+        };
+        assert!(failed(ErrorKind::Transient).is_retryable());
+        assert!(failed(ErrorKind::Cancelled).is_retryable());
+        assert!(!failed(ErrorKind::Auth).is_retryable());
+        assert_eq!(failed(ErrorKind::Auth).error_kind(), Some(ErrorKind::Auth));
+        assert_eq!(failed(ErrorKind::Other).repository(), "user/repo");
+        assert_eq!(failed(ErrorKind::Other).migration_id(), "test-migration");
+    }
+
+    #[test]
+    fn carries_stage_timings() {
+        let mut result = ProcessingResult::Skipped {
+            migration_id: "m".to_string(),
+            repository: "user/repo".to_string(),
+            reason: "duplicate".to_string(),
+            timings: StageTimings::default(),
+        };
+        assert!(!serde_json::to_string(&result).unwrap().contains("timings"));
+
+        let mut timings = StageTimings::default();
+        timings.add(Stage::Dedupe, Duration::from_secs(2));
+        result.set_timings(timings.clone());
+
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: ProcessingResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.timings(), &timings);
+    }
+
+    #[test]
+    fn reads_failures_recorded_without_a_kind() {
+        let json = r#"{"outcome":"failed","migration_id":"m","repository":"user/repo","error":"boom","retryable":true}"#;
+
+        let result: ProcessingResult = serde_json::from_str(json).unwrap();
+
+        assert_eq!(result.error_kind(), Some(ErrorKind::Other));
+    }
+
+    #[test]
+    fn only_transient_pr_failures_are_retryable() {
+        let pr_only = |retryable| ProcessingResult::PrOnly {
+            migration_id: "test-migration".to_string(),
+            repository: "user/repo".to_string(),
+            pr: PrStatus::Failed {
+                error: "error".to_string(),
+                retryable,
+            },
+            timings: StageTimings::default(),
+        };
+        assert!(pr_only(true).has_retryable_pr());
+        assert!(!pr_only(false).has_retryable_pr());
+        assert!(!pr_only(true).is_retryable());
+    }
+}
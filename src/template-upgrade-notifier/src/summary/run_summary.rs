@@ -1,11 +1,19 @@
 //! Run summary types.
 
+use super::estimate::QuotaEstimate;
+use super::occurrence::Occurrence;
 use super::result::ProcessingResult;
+use super::timings::TimingRecord;
 use crate::issues::IssueStatus;
 use crate::pull_requests::PrStatus;
+use serde::{Deserialize, Serialize};
 
 /// Summary of a complete run.
-#[derive(Debug, Clone, Default)]
+///
+/// Serializes every count and result, so a finished (or checkpointed) run can
+/// be reported on or resumed from its JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RunSummary {
     /// Number of migrations processed.
     pub migrations_processed: usize,
@@ -25,11 +33,56 @@ pub struct RunSummary {
     /// Number of PRs successfully created.
     pub prs_created: usize,
 
+    /// Number of auto-fix PRs left open by earlier runs that were updated.
+    pub prs_updated: usize,
+
     /// Number of PRs that failed to create.
     pub prs_failed: usize,
 
+    /// Number of repositories with issues disabled that were processed PR-only.
+    pub pr_only: usize,
+
+    /// Number of repositories sent a `repository_dispatch` event instead of an issue.
+    pub dispatched: usize,
+
+    /// Number of open issues/PRs closed because the repository upgraded on its own.
+    pub notifications_closed: usize,
+
     /// Whether this was a dry run.
     pub dry_run: bool,
+
+    /// Random identifier of the run, attached as `run_id` to every log line
+    /// it emitted.
+    pub run_id: String,
+
+    /// Title of the campaign the run belonged to, if any.
+    pub campaign: Option<String>,
+
+    /// Whether the run stopped early because it hit its runtime limit.
+    pub timed_out: bool,
+
+    /// Whether the run stopped early because too many repositories failed.
+    pub aborted: bool,
+
+    /// Migrations refused because they matched more repositories than the
+    /// large-campaign threshold without confirmation.
+    pub unconfirmed_migrations: Vec<String>,
+
+    /// Per-repository results, in the order they were recorded.
+    pub results: Vec<ProcessingResult>,
+
+    /// Outdated template occurrences found during discovery, including in dry runs.
+    pub occurrences: Vec<Occurrence>,
+
+    /// Per-stage durations of each processed repository and each discovery.
+    pub timings: Vec<TimingRecord>,
+
+    /// Dry runs only: the quota and LLM runs a live run would use, per migration.
+    pub estimates: Vec<QuotaEstimate>,
+
+    /// Dry runs only: core requests left in the current rate limit window
+    /// when the run finished, to compare the estimate against.
+    pub core_quota_remaining: Option<u32>,
 }
 
 impl RunSummary {
@@ -44,24 +97,57 @@ impl RunSummary {
 
     /// Updates the summary with a processing result.
     pub fn record_result(&mut self, result: &ProcessingResult) {
-        match result {
+        self.tally(result, true);
+        self.results.push(result.clone());
+    }
+
+    /// Records a result, replacing an earlier result for the same repository
+    /// and migration (e.g. a retried failure) instead of counting it twice.
+    pub fn replace_result(&mut self, result: &ProcessingResult) {
+        let earlier = self.results.iter().position(|r| {
+            r.repository() == result.repository() && r.migration_id() == result.migration_id()
+        });
+        match earlier {
+            Some(index) => {
+                let earlier = core::mem::replace(&mut self.results[index], result.clone());
+                self.tally(&earlier, false);
+                self.tally(result, true);
+            }
+            None => self.record_result(result),
+        }
+    }
+
+    /// Adds a result to the counters, or removes it again when `add` is false.
+    fn tally(&mut self, result: &ProcessingResult, add: bool) {
+        let counter = match result {
             ProcessingResult::Success { issue, pr, .. } => {
-                match issue {
-                    IssueStatus::Created { .. } => self.issues_created += 1,
-                    IssueStatus::Skipped { .. } => self.issues_skipped += 1,
-                    IssueStatus::Failed { .. } => self.issues_failed += 1,
-                    IssueStatus::Pending => {}
-                }
                 if let Some(pr_status) = pr {
-                    match pr_status {
-                        PrStatus::Created { .. } => self.prs_created += 1,
-                        PrStatus::Failed { .. } | PrStatus::TimedOut => self.prs_failed += 1,
-                        _ => {}
-                    }
+                    self.tally_pr(pr_status, add);
+                }
+                match issue {
+                    IssueStatus::Created { .. } => &mut self.issues_created,
+                    IssueStatus::Skipped { .. } => &mut self.issues_skipped,
+                    IssueStatus::Failed { .. } => &mut self.issues_failed,
+                    IssueStatus::Pending => return,
                 }
             }
-            ProcessingResult::Skipped { .. } => self.issues_skipped += 1,
-            ProcessingResult::Failed { .. } => self.issues_failed += 1,
+            ProcessingResult::PrOnly { pr, .. } => {
+                self.tally_pr(pr, add);
+                &mut self.pr_only
+            }
+            ProcessingResult::Dispatched { .. } => &mut self.dispatched,
+            ProcessingResult::Skipped { .. } => &mut self.issues_skipped,
+            ProcessingResult::Failed { .. } => &mut self.issues_failed,
+        };
+        bump(counter, add);
+    }
+
+    fn tally_pr(&mut self, pr: &PrStatus, add: bool) {
+        match pr {
+            PrStatus::Created { .. } => bump(&mut self.prs_created, add),
+            PrStatus::Updated { .. } => bump(&mut self.prs_updated, add),
+            PrStatus::Failed { .. } | PrStatus::TimedOut => bump(&mut self.prs_failed, add),
+            _ => {}
         }
     }
 
@@ -78,28 +164,155 @@ impl RunSummary {
     }
 }
 
+/// Increments `counter`, or decrements it when `add` is false.
+fn bump(counter: &mut usize, add: bool) {
+    if add {
+        *counter += 1;
+    } else {
+        *counter = counter.saturating_sub(1);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error_kind::ErrorKind;
+    use crate::summary::StageTimings;
 
     #[test]
     fn can_record_result() {
         let mut summary = RunSummary::new(false);
 
         summary.record_result(&ProcessingResult::Success {
+            migration_id: "test-migration".to_string(),
             repository: "test/repo".to_string(),
             issue: IssueStatus::Created {
                 number: 1,
-                url: "https://example.com".to_string(),
+                url: "https://example.com".parse().unwrap(),
             },
             pr: Some(PrStatus::Created {
                 number: 2,
-                url: "https://example.com/pr".to_string(),
+                url: "https://example.com/pr".parse().unwrap(),
             }),
+            timings: StageTimings::default(),
         });
 
         assert_eq!(summary.issues_created, 1);
         assert_eq!(summary.prs_created, 1);
+        assert_eq!(summary.results.len(), 1);
         assert!(summary.all_success());
     }
+
+    #[test]
+    fn counts_pr_only_results() {
+        let mut summary = RunSummary::new(false);
+
+        summary.record_result(&ProcessingResult::PrOnly {
+            migration_id: "test-migration".to_string(),
+            repository: "test/repo".to_string(),
+            pr: PrStatus::Created {
+                number: 2,
+                url: "https://example.com/pr".parse().unwrap(),
+            },
+            timings: StageTimings::default(),
+        });
+
+        assert_eq!(summary.pr_only, 1);
+        assert_eq!(summary.prs_created, 1);
+        assert_eq!(summary.issues_created, 0);
+        assert_eq!(summary.issues_failed, 0);
+    }
+
+    #[test]
+    fn counts_updated_prs() {
+        let mut summary = RunSummary::new(false);
+
+        summary.record_result(&ProcessingResult::PrOnly {
+            migration_id: "test-migration".to_string(),
+            repository: "test/repo".to_string(),
+            pr: PrStatus::Updated {
+                number: 2,
+                url: "https://example.com/pr".parse().unwrap(),
+            },
+            timings: StageTimings::default(),
+        });
+
+        assert_eq!(summary.prs_updated, 1);
+        assert_eq!(summary.prs_created, 0);
+        assert_eq!(summary.per_migration()[0].prs_updated, 1);
+    }
+
+    #[test]
+    fn counts_dispatched_results() {
+        let mut summary = RunSummary::new(false);
+
+        summary.record_result(&ProcessingResult::Dispatched {
+            migration_id: "test-migration".to_string(),
+            repository: "test/repo".to_string(),
+            event_type: "template-upgrade".to_string(),
+            timings: StageTimings::default(),
+        });
+
+        assert_eq!(summary.dispatched, 1);
+        assert_eq!(summary.issues_skipped, 0);
+        assert!(summary.all_success());
+    }
+
+    #[test]
+    fn replacing_a_retried_failure_updates_counts() {
+        let mut summary = RunSummary::new(false);
+        summary.replace_result(&ProcessingResult::Failed {
+            migration_id: "m".to_string(),
+            repository: "test/repo".to_string(),
+            error: "timeout".to_string(),
+            kind: ErrorKind::Transient,
+            timings: StageTimings::default(),
+        });
+
+        summary.replace_result(&ProcessingResult::Success {
+            migration_id: "m".to_string(),
+            repository: "test/repo".to_string(),
+            issue: IssueStatus::Created {
+                number: 1,
+                url: "https://example.com".parse().unwrap(),
+            },
+            pr: None,
+            timings: StageTimings::default(),
+        });
+
+        assert_eq!(summary.issues_failed, 0);
+        assert_eq!(summary.issues_created, 1);
+        assert_eq!(summary.results.len(), 1);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut summary = RunSummary::new(true);
+        summary.record_result(&ProcessingResult::Success {
+            migration_id: "m".to_string(),
+            repository: "test/repo".to_string(),
+            issue: IssueStatus::Created {
+                number: 1,
+                url: "https://github.com/test/repo/issues/1".parse().unwrap(),
+            },
+            pr: Some(PrStatus::TimedOut),
+            timings: StageTimings::default(),
+        });
+        summary.record_result(&ProcessingResult::Failed {
+            migration_id: "m".to_string(),
+            repository: "test/other".to_string(),
+            error: "boom".to_string(),
+            kind: ErrorKind::Transient,
+            timings: StageTimings::default(),
+        });
+
+        let json = serde_json::to_string(&summary).unwrap();
+        let parsed: RunSummary = serde_json::from_str(&json).unwrap();
+
+        assert!(parsed.dry_run);
+        assert_eq!(parsed.issues_created, 1);
+        assert_eq!(parsed.results.len(), 2);
+        assert!(parsed.results[1].is_retryable());
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
 }
@@ -0,0 +1,149 @@
+//! SARIF report output for GitHub code scanning.
+
+use super::occurrence::Occurrence;
+use super::run_summary::RunSummary;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// SARIF specification version emitted.
+const SARIF_VERSION: &str = "2.1.0";
+
+/// SARIF JSON schema location.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+impl RunSummary {
+    /// Renders discovered occurrences as a SARIF 2.1.0 log.
+    ///
+    /// Each migration becomes a rule and each outdated file a `warning`
+    /// result. Locations are `owner/name/path` so results from many
+    /// repositories can be uploaded to a single tracking repository.
+    /// The run's identifier is recorded as the run's `automationDetails.guid`.
+    #[must_use]
+    pub fn to_sarif(&self) -> Value {
+        let mut rules = BTreeMap::new();
+        for occurrence in &self.occurrences {
+            rules
+                .entry(occurrence.migration_id.as_str())
+                .or_insert_with(|| rule(occurrence));
+        }
+
+        let mut run = json!({
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules.into_values().collect::<Vec<_>>(),
+                    }
+                },
+                "results": self.occurrences.iter().map(result).collect::<Vec<_>>(),
+        });
+        if !self.run_id.is_empty() {
+            run["automationDetails"] = json!({ "guid": self.run_id });
+        }
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [run],
+        })
+    }
+}
+
+fn rule(occurrence: &Occurrence) -> Value {
+    json!({
+        "id": occurrence.migration_id,
+        "shortDescription": {
+            "text": format!(
+                "Outdated template: {} -> {}",
+                occurrence.old_string, occurrence.new_string
+            )
+        },
+        "defaultConfiguration": { "level": "warning" },
+    })
+}
+
+fn result(occurrence: &Occurrence) -> Value {
+    json!({
+        "ruleId": occurrence.migration_id,
+        "level": "warning",
+        "message": {
+            "text": format!(
+                "{} uses outdated template version '{}'; upgrade to '{}'.",
+                occurrence.repository, occurrence.old_string, occurrence.new_string
+            )
+        },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": format!("{}/{}", occurrence.repository, occurrence.file_path)
+                }
+            }
+        }],
+        "partialFingerprints": {
+            "templateUpgrade/v1": format!(
+                "{}:{}:{}",
+                occurrence.migration_id, occurrence.repository, occurrence.file_path
+            )
+        },
+        "properties": {
+            "repository": occurrence.repository,
+            "filePath": occurrence.file_path,
+            "fileUrl": occurrence.file_url,
+            "oldVersion": occurrence.old_string,
+            "newVersion": occurrence.new_string,
+        },
+    })
+}
+
+/// Writes the SARIF report for a run to a file, replacing existing contents.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn write_sarif_report(summary: &RunSummary, path: &Path) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(&summary.to_sarif())?;
+    std::fs::write(path, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn occurrence(migration_id: &str, repository: &str) -> Occurrence {
+        Occurrence {
+            migration_id: migration_id.to_string(),
+            repository: repository.to_string(),
+            file_path: "template.toml".to_string(),
+            file_url: format!("https://github.com/{repository}/blob/main/template.toml"),
+            old_string: "v1".to_string(),
+            new_string: "v2".to_string(),
+            template_version: None,
+        }
+    }
+
+    #[test]
+    fn renders_rule_per_migration_and_result_per_occurrence() {
+        let mut summary = RunSummary::new(true);
+        summary.run_id = "0b1e6f5c-6a4f-4c57-9d0e-2f3a8c1d7e42".to_string();
+        summary.occurrences = vec![
+            occurrence("m1", "user/a"),
+            occurrence("m1", "user/b"),
+            occurrence("m2", "user/a"),
+        ];
+
+        let sarif = summary.to_sarif();
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["automationDetails"]["guid"], summary.run_id);
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+        assert_eq!(run["results"].as_array().unwrap().len(), 3);
+        assert_eq!(run["results"][1]["ruleId"], "m1");
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "user/b/template.toml"
+        );
+        assert_eq!(run["results"][1]["properties"]["newVersion"], "v2");
+    }
+}
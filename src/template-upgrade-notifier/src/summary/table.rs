@@ -0,0 +1,499 @@
+//! Plain-text summary tables for terminal output.
+
+use super::estimate::QuotaEstimate;
+use super::result::ProcessingResult;
+use super::run_summary::RunSummary;
+use super::timings::format_duration;
+use crate::issues::IssueStatus;
+use crate::pull_requests::PrStatus;
+use crate::redact::redact;
+use core::fmt::Write;
+
+/// Per-migration counters of a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationTotals {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Repositories discovered for the migration.
+    pub discovered: usize,
+    /// Issues created.
+    pub issues_created: usize,
+    /// Issues skipped (duplicates, no access, ...).
+    pub issues_skipped: usize,
+    /// Issues that failed to create.
+    pub issues_failed: usize,
+    /// PRs created.
+    pub prs_created: usize,
+    /// Auto-fix PRs left open by earlier runs that were updated.
+    pub prs_updated: usize,
+    /// PRs that failed or timed out.
+    pub prs_failed: usize,
+    /// Repositories with issues disabled that were processed PR-only.
+    pub pr_only: usize,
+    /// Repositories sent a `repository_dispatch` event instead of an issue.
+    pub dispatched: usize,
+}
+
+impl MigrationTotals {
+    fn new(migration_id: &str) -> Self {
+        Self {
+            migration_id: migration_id.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Counts a result the same way [`RunSummary::record_result`] does.
+    fn record(&mut self, result: &ProcessingResult) {
+        match result {
+            ProcessingResult::Success { issue, pr, .. } => {
+                match issue {
+                    IssueStatus::Created { .. } => self.issues_created += 1,
+                    IssueStatus::Skipped { .. } => self.issues_skipped += 1,
+                    IssueStatus::Failed { .. } => self.issues_failed += 1,
+                    IssueStatus::Pending => {}
+                }
+                if let Some(pr) = pr {
+                    self.record_pr(pr);
+                }
+            }
+            ProcessingResult::PrOnly { pr, .. } => {
+                self.pr_only += 1;
+                self.record_pr(pr);
+            }
+            ProcessingResult::Dispatched { .. } => self.dispatched += 1,
+            ProcessingResult::Skipped { .. } => self.issues_skipped += 1,
+            ProcessingResult::Failed { .. } => self.issues_failed += 1,
+        }
+    }
+
+    fn record_pr(&mut self, pr: &PrStatus) {
+        match pr {
+            PrStatus::Created { .. } => self.prs_created += 1,
+            PrStatus::Updated { .. } => self.prs_updated += 1,
+            PrStatus::Failed { .. } | PrStatus::TimedOut => self.prs_failed += 1,
+            _ => {}
+        }
+    }
+}
+
+impl RunSummary {
+    /// Breaks the run's counters down by migration, in the order migrations
+    /// were first seen.
+    ///
+    /// Migrations that discovered no repositories are not listed.
+    #[must_use]
+    pub fn per_migration(&self) -> Vec<MigrationTotals> {
+        let mut totals = Vec::new();
+        for occurrence in &self.occurrences {
+            totals_for(&mut totals, &occurrence.migration_id).discovered += 1;
+        }
+        for result in &self.results {
+            totals_for(&mut totals, result.migration_id()).record(result);
+        }
+        totals
+    }
+
+    /// Renders the run as aligned plain-text tables for the terminal.
+    ///
+    /// Always includes one row per migration plus a total row, and per-stage
+    /// duration percentiles when anything was timed; with `verbose`, also one
+    /// row per processed repository. Secrets are redacted.
+    #[must_use]
+    pub fn to_table(&self, verbose: bool) -> String {
+        let mut out = String::new();
+        // Writing into a `String` cannot fail.
+        let _ = self.write_table(&mut out, verbose);
+        redact(&out)
+    }
+
+    fn write_table(&self, out: &mut String, verbose: bool) -> core::fmt::Result {
+        let mode = if self.dry_run { "Dry Run" } else { "Live" };
+        writeln!(out, "\nSummary ({mode}):")?;
+        if !self.run_id.is_empty() {
+            writeln!(out, "  Run ID: {}", self.run_id)?;
+        }
+        if let Some(campaign) = &self.campaign {
+            writeln!(out, "  Campaign: {campaign}")?;
+        }
+        writeln!(out, "  Migrations processed: {}", self.migrations_processed)?;
+        if self.notifications_closed > 0 {
+            writeln!(
+                out,
+                "  Closed in upgraded repositories: {}",
+                self.notifications_closed
+            )?;
+        }
+        if self.dispatched > 0 {
+            writeln!(out, "  Repository dispatches: {}", self.dispatched)?;
+        }
+        if self.prs_updated > 0 {
+            writeln!(out, "  Open PRs updated: {}", self.prs_updated)?;
+        }
+        if self.timed_out {
+            writeln!(out, "  Stopped early at the runtime limit")?;
+        }
+        if self.aborted {
+            writeln!(out, "  Stopped early at the abort threshold")?;
+        }
+        if !self.unconfirmed_migrations.is_empty() {
+            writeln!(
+                out,
+                "  Refused as unconfirmed large campaigns: {}",
+                self.unconfirmed_migrations.join(", ")
+            )?;
+        }
+        writeln!(out)?;
+
+        let mut rows: Vec<Vec<String>> = self
+            .per_migration()
+            .into_iter()
+            .map(|t| {
+                vec![
+                    t.migration_id,
+                    t.discovered.to_string(),
+                    t.issues_created.to_string(),
+                    t.issues_skipped.to_string(),
+                    t.issues_failed.to_string(),
+                    t.prs_created.to_string(),
+                    t.prs_failed.to_string(),
+                    t.pr_only.to_string(),
+                ]
+            })
+            .collect();
+        rows.push(vec![
+            "TOTAL".to_string(),
+            self.repositories_discovered.to_string(),
+            self.issues_created.to_string(),
+            self.issues_skipped.to_string(),
+            self.issues_failed.to_string(),
+            self.prs_created.to_string(),
+            self.prs_failed.to_string(),
+            self.pr_only.to_string(),
+        ]);
+        let mut headers = vec![
+            "MIGRATION",
+            "DISCOVERED",
+            "ISSUES",
+            "SKIPPED",
+            "FAILED",
+            "PRS",
+            "PRS FAILED",
+            "PR ONLY",
+        ];
+        // Dry runs never create anything; only discovery is meaningful.
+        if self.dry_run {
+            headers.truncate(2);
+            rows.iter_mut().for_each(|row| row.truncate(2));
+        }
+        write_rows(out, &headers, &rows, 1)?;
+
+        let percentiles = self.stage_percentiles();
+        if !percentiles.is_empty() {
+            writeln!(out)?;
+            let rows: Vec<Vec<String>> = percentiles
+                .into_iter()
+                .map(|p| {
+                    vec![
+                        p.stage.as_str().to_string(),
+                        p.samples.to_string(),
+                        format_duration(p.total),
+                        format_duration(p.p50),
+                        format_duration(p.p90),
+                        format_duration(p.p99),
+                        format_duration(p.max),
+                    ]
+                })
+                .collect();
+            write_rows(
+                out,
+                &["STAGE", "SAMPLES", "TOTAL", "P50", "P90", "P99", "MAX"],
+                &rows,
+                1,
+            )?;
+        }
+
+        if self.dry_run && !self.estimates.is_empty() {
+            self.write_estimates(out)?;
+        }
+
+        if verbose && !self.results.is_empty() {
+            writeln!(out)?;
+            let rows: Vec<Vec<String>> = self.results.iter().map(repository_row).collect();
+            write_rows(
+                out,
+                &["REPOSITORY", "MIGRATION", "ISSUE", "PR", "DETAIL"],
+                &rows,
+                usize::MAX,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the quota a live run would use, per migration and in total,
+    /// and warns if the core requests exceed what is left of the quota.
+    fn write_estimates(&self, out: &mut String) -> core::fmt::Result {
+        writeln!(out, "\nEstimated live run usage:")?;
+        let total = QuotaEstimate::total(&self.estimates);
+        let row = |migration_id: &str, estimate: &QuotaEstimate| {
+            vec![
+                migration_id.to_string(),
+                estimate.search_requests.to_string(),
+                estimate.graphql_requests.to_string(),
+                estimate.core_requests.to_string(),
+                estimate.llm_runs.to_string(),
+                estimate
+                    .llm_cost
+                    .map_or_else(|| "-".to_string(), |cost| format!("${cost:.2}")),
+            ]
+        };
+        let mut rows: Vec<Vec<String>> = self
+            .estimates
+            .iter()
+            .map(|estimate| row(&estimate.migration_id, estimate))
+            .collect();
+        rows.push(row("TOTAL", &total));
+        write_rows(
+            out,
+            &[
+                "MIGRATION",
+                "SEARCH",
+                "GRAPHQL",
+                "CORE",
+                "LLM RUNS",
+                "LLM COST",
+            ],
+            &rows,
+            1,
+        )?;
+        if let Some(remaining) = self.core_quota_remaining {
+            let fits = if total.core_requests <= u64::from(remaining) {
+                "fit"
+            } else {
+                "exceed"
+            };
+            writeln!(
+                out,
+                "  Core requests {fits} the {remaining} left in the current rate limit window"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the totals for `migration_id`, appending them if not yet present.
+fn totals_for<'a>(
+    totals: &'a mut Vec<MigrationTotals>,
+    migration_id: &str,
+) -> &'a mut MigrationTotals {
+    let index = match totals.iter().position(|t| t.migration_id == migration_id) {
+        Some(index) => index,
+        None => {
+            totals.push(MigrationTotals::new(migration_id));
+            totals.len() - 1
+        }
+    };
+    &mut totals[index]
+}
+
+/// Returns the verbose table row for one repository.
+fn repository_row(result: &ProcessingResult) -> Vec<String> {
+    let (issue, pr, detail) = match result {
+        ProcessingResult::Success { issue, pr, .. } => {
+            let detail = match issue {
+                IssueStatus::Created { url, .. } => url.as_str(),
+                IssueStatus::Skipped { reason } => reason.as_str(),
+                IssueStatus::Failed { error } => error.as_str(),
+                IssueStatus::Pending => "",
+            };
+            let detail = match pr {
+                Some(PrStatus::Failed { error, .. }) if detail.is_empty() => error.as_str(),
+                _ => detail,
+            };
+            (
+                issue.as_str(),
+                pr.as_ref().map_or("-", PrStatus::as_str),
+                detail,
+            )
+        }
+        ProcessingResult::PrOnly { pr, .. } => {
+            let detail = match pr {
+                PrStatus::Created { url, .. } | PrStatus::Updated { url, .. } => url.as_str(),
+                PrStatus::Failed { error, .. } => error.as_str(),
+                _ => "",
+            };
+            ("disabled", pr.as_str(), detail)
+        }
+        ProcessingResult::Dispatched { event_type, .. } => ("dispatched", "-", event_type.as_str()),
+        ProcessingResult::Skipped { reason, .. } => ("skipped", "-", reason.as_str()),
+        ProcessingResult::Failed { error, .. } => ("failed", "-", error.as_str()),
+    };
+    vec![
+        result.repository().to_string(),
+        result.migration_id().to_string(),
+        issue.to_string(),
+        pr.to_string(),
+        // Keep each repository on one line.
+        detail.lines().next().unwrap_or_default().to_string(),
+    ]
+}
+
+/// Writes an aligned table; columns from `numeric_from` on are right-aligned.
+fn write_rows(
+    out: &mut String,
+    headers: &[&str],
+    rows: &[Vec<String>],
+    numeric_from: usize,
+) -> core::fmt::Result {
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(column, header)| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain([header.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let header_row: Vec<String> = headers.iter().map(|h| (*h).to_string()).collect();
+    for row in core::iter::once(&header_row).chain(rows) {
+        let mut line = String::new();
+        for (column, cell) in row.iter().enumerate() {
+            let width = widths[column];
+            if column >= numeric_from {
+                write!(line, "  {cell:>width$}")?;
+            } else {
+                write!(line, "  {cell:<width$}")?;
+            }
+        }
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_kind::ErrorKind;
+    use crate::summary::Occurrence;
+    use crate::summary::StageTimings;
+
+    fn occurrence(migration_id: &str, repository: &str) -> Occurrence {
+        Occurrence {
+            migration_id: migration_id.to_string(),
+            repository: repository.to_string(),
+            file_path: "version.txt".to_string(),
+            file_url: String::new(),
+            old_string: "v1".to_string(),
+            new_string: "v2".to_string(),
+            template_version: None,
+        }
+    }
+
+    fn sample_summary() -> RunSummary {
+        let mut summary = RunSummary::new(false);
+        summary.migrations_processed = 2;
+        summary.repositories_discovered = 3;
+        summary.occurrences = vec![
+            occurrence("alpha", "user/one"),
+            occurrence("alpha", "user/two"),
+            occurrence("beta", "user/one"),
+        ];
+        summary.record_result(&ProcessingResult::Success {
+            migration_id: "alpha".to_string(),
+            repository: "user/one".to_string(),
+            issue: IssueStatus::Created {
+                number: 1,
+                url: "https://github.com/user/one/issues/1".parse().unwrap(),
+            },
+            pr: Some(PrStatus::TimedOut),
+            timings: StageTimings::default(),
+        });
+        summary.record_result(&ProcessingResult::Skipped {
+            migration_id: "alpha".to_string(),
+            repository: "user/two".to_string(),
+            reason: "duplicate".to_string(),
+            timings: StageTimings::default(),
+        });
+        summary.record_result(&ProcessingResult::Failed {
+            migration_id: "beta".to_string(),
+            repository: "user/one".to_string(),
+            error: "boom\ndetails".to_string(),
+            kind: ErrorKind::Other,
+            timings: StageTimings::default(),
+        });
+        summary
+    }
+
+    #[test]
+    fn totals_per_migration() {
+        let totals = sample_summary().per_migration();
+
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].migration_id, "alpha");
+        assert_eq!(totals[0].discovered, 2);
+        assert_eq!(totals[0].issues_created, 1);
+        assert_eq!(totals[0].issues_skipped, 1);
+        assert_eq!(totals[0].prs_failed, 1);
+        assert_eq!(totals[1].migration_id, "beta");
+        assert_eq!(totals[1].issues_failed, 1);
+    }
+
+    #[test]
+    fn renders_aligned_table() {
+        let table = sample_summary().to_table(false);
+
+        assert!(table.contains(
+            "  MIGRATION  DISCOVERED  ISSUES  SKIPPED  FAILED  PRS  PRS FAILED  PR ONLY\n\
+             \x20 alpha               2       1        1       0    0           1        0\n\
+             \x20 beta                1       0        0       1    0           0        0\n\
+             \x20 TOTAL               3       1        1       1    0           1        0\n"
+        ));
+        assert!(!table.contains("REPOSITORY"));
+    }
+
+    #[test]
+    fn verbose_lists_repositories() {
+        let table = sample_summary().to_table(true);
+
+        assert!(table.contains("REPOSITORY"));
+        assert!(table.contains("  user/one    beta       failed   -       boom\n"));
+    }
+
+    #[test]
+    fn dry_run_shows_discovery_only() {
+        let mut summary = RunSummary::new(true);
+        summary.repositories_discovered = 1;
+        summary.occurrences = vec![occurrence("alpha", "user/one")];
+
+        let table = summary.to_table(true);
+
+        assert!(table.contains("  MIGRATION  DISCOVERED\n  alpha               1\n"));
+        assert!(!table.contains("ISSUES"));
+    }
+
+    #[test]
+    fn dry_run_shows_estimates() {
+        let mut summary = RunSummary::new(true);
+        summary.estimates = vec![QuotaEstimate {
+            migration_id: "alpha".to_string(),
+            repositories: 3,
+            search_requests: 1,
+            graphql_requests: 2,
+            core_requests: 12,
+            llm_runs: 3,
+            llm_cost: Some(1.5),
+        }];
+        summary.core_quota_remaining = Some(10);
+
+        let table = summary.to_table(false);
+
+        assert!(table.contains(
+            "  MIGRATION  SEARCH  GRAPHQL  CORE  LLM RUNS  LLM COST\n\
+             \x20 alpha           1        2    12         3     $1.50\n\
+             \x20 TOTAL           1        2    12         3     $1.50\n"
+        ));
+        assert!(table.contains("Core requests exceed the 10 left"));
+    }
+}
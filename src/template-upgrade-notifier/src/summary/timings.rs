@@ -0,0 +1,239 @@
+//! Per-stage durations of a run and their percentiles.
+
+use super::run_summary::RunSummary;
+use core::future::Future;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// A phase of processing whose duration is recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Code search for a migration; recorded once per migration.
+    Discovery,
+    /// Ledger, cooldown and stale-result checks before notifying.
+    Dedupe,
+    /// Cloning the repository and creating the PR branch.
+    Clone,
+    /// Applying the migration with its strategy (the LLM by default).
+    Llm,
+    /// Committing and pushing the changes, including any fork fallback.
+    Push,
+    /// GitHub API calls creating and updating issues, PRs and checks.
+    Api,
+}
+
+impl Stage {
+    /// Every stage, in processing order.
+    pub const ALL: [Self; 6] = [
+        Self::Discovery,
+        Self::Dedupe,
+        Self::Clone,
+        Self::Llm,
+        Self::Push,
+        Self::Api,
+    ];
+
+    /// Returns the stage name used in reports.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Discovery => "discovery",
+            Self::Dedupe => "dedupe",
+            Self::Clone => "clone",
+            Self::Llm => "llm",
+            Self::Push => "push",
+            Self::Api => "api",
+        }
+    }
+}
+
+/// Time spent in each stage while processing one repository or migration.
+///
+/// Stages that never ran are absent rather than zero, so they don't drag
+/// percentiles down.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StageTimings {
+    durations: BTreeMap<Stage, Duration>,
+}
+
+impl StageTimings {
+    /// Adds `duration` to the time spent in `stage`.
+    pub fn add(&mut self, stage: Stage, duration: Duration) {
+        *self.durations.entry(stage).or_default() += duration;
+    }
+
+    /// Awaits `future`, adding the time it took to `stage`.
+    pub async fn measure<F: Future>(&mut self, stage: Stage, future: F) -> F::Output {
+        let start = Instant::now();
+        let output = future.await;
+        self.add(stage, start.elapsed());
+        output
+    }
+
+    /// Adds every duration of `other`.
+    pub fn merge(&mut self, other: &Self) {
+        for (stage, duration) in &other.durations {
+            self.add(*stage, *duration);
+        }
+    }
+
+    /// Returns the time spent in `stage`, if it ran.
+    #[must_use]
+    pub fn get(&self, stage: Stage) -> Option<Duration> {
+        self.durations.get(&stage).copied()
+    }
+
+    /// Returns true if no stage ran.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.durations.is_empty()
+    }
+}
+
+/// Stage timings of one repository, or of a migration's discovery.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingRecord {
+    /// Migration identifier.
+    pub migration_id: String,
+    /// Repository full name; `None` for the migration's discovery.
+    pub repository: Option<String>,
+    /// Time spent in each stage.
+    pub timings: StageTimings,
+}
+
+/// Distribution of one stage's durations across a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagePercentiles {
+    /// The stage.
+    pub stage: Stage,
+    /// Number of repositories (or migrations, for discovery) that ran it.
+    pub samples: usize,
+    /// Sum of all durations.
+    pub total: Duration,
+    /// Median duration.
+    pub p50: Duration,
+    /// 90th percentile duration.
+    pub p90: Duration,
+    /// 99th percentile duration.
+    pub p99: Duration,
+    /// Longest duration.
+    pub max: Duration,
+}
+
+impl RunSummary {
+    /// Records the stage timings of a repository or discovery, replacing the
+    /// timings of an earlier attempt (e.g. a retried failure).
+    pub fn record_timings(&mut self, record: TimingRecord) {
+        if record.timings.is_empty() {
+            return;
+        }
+        let earlier = self
+            .timings
+            .iter_mut()
+            .find(|r| r.migration_id == record.migration_id && r.repository == record.repository);
+        match earlier {
+            Some(earlier) => *earlier = record,
+            None => self.timings.push(record),
+        }
+    }
+
+    /// Aggregates the recorded timings into per-stage percentiles, in stage
+    /// order; stages that never ran are omitted.
+    #[must_use]
+    pub fn stage_percentiles(&self) -> Vec<StagePercentiles> {
+        Stage::ALL
+            .into_iter()
+            .filter_map(|stage| {
+                let mut samples: Vec<Duration> = self
+                    .timings
+                    .iter()
+                    .filter_map(|record| record.timings.get(stage))
+                    .collect();
+                samples.sort_unstable();
+                let max = *samples.last()?;
+                Some(StagePercentiles {
+                    stage,
+                    samples: samples.len(),
+                    total: samples.iter().sum(),
+                    p50: percentile(&samples, 50),
+                    p90: percentile(&samples, 90),
+                    p99: percentile(&samples, 99),
+                    max,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the nearest-rank `percent`th percentile of non-empty, sorted samples.
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Formats a duration for reports, e.g. `12.3s`.
+pub(super) fn format_duration(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(repository: &str, stage: Stage, secs: u64) -> TimingRecord {
+        let mut timings = StageTimings::default();
+        timings.add(stage, Duration::from_secs(secs));
+        TimingRecord {
+            migration_id: "m".to_string(),
+            repository: Some(repository.to_string()),
+            timings,
+        }
+    }
+
+    #[test]
+    fn computes_percentiles_per_stage() {
+        let mut summary = RunSummary::new(false);
+        for i in 1..=10 {
+            summary.record_timings(record(&format!("user/repo{i}"), Stage::Llm, i));
+        }
+        summary.record_timings(record("user/other", Stage::Api, 2));
+
+        let percentiles = summary.stage_percentiles();
+
+        assert_eq!(percentiles.len(), 2);
+        let llm = &percentiles[0];
+        assert_eq!(llm.stage, Stage::Llm);
+        assert_eq!(llm.samples, 10);
+        assert_eq!(llm.total, Duration::from_secs(55));
+        assert_eq!(llm.p50, Duration::from_secs(5));
+        assert_eq!(llm.p90, Duration::from_secs(9));
+        assert_eq!(llm.max, Duration::from_secs(10));
+        assert_eq!(percentiles[1].stage, Stage::Api);
+    }
+
+    #[test]
+    fn retries_replace_earlier_timings() {
+        let mut summary = RunSummary::new(false);
+        summary.record_timings(record("user/repo", Stage::Clone, 5));
+        summary.record_timings(record("user/repo", Stage::Clone, 1));
+
+        let clone = &summary.stage_percentiles()[0];
+        assert_eq!(clone.samples, 1);
+        assert_eq!(clone.total, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn accumulates_repeated_stages() {
+        let mut timings = StageTimings::default();
+        timings.add(Stage::Api, Duration::from_millis(300));
+        timings.add(Stage::Api, Duration::from_millis(200));
+
+        assert_eq!(timings.get(Stage::Api), Some(Duration::from_millis(500)));
+        assert_eq!(timings.get(Stage::Llm), None);
+        assert_eq!(format_duration(Duration::from_millis(1300)), "1.3s");
+    }
+}
@@ -0,0 +1,110 @@
+//! GitHub team mentions and team review requests.
+//!
+//! Migrations can name team slugs to @-mention in issue bodies and to request
+//! as PR reviewers. Teams only exist in organizations, so each slug is checked
+//! against the repository owner's teams first; slugs that don't resolve (user
+//! repositories, other organizations, or a token without `read:org`) are
+//! left out rather than rendered as dead mentions.
+
+use octocrab::Octocrab;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tracing::{debug, warn};
+
+/// Whether each `(organization, slug)` resolved to a team.
+static TEAMS: Mutex<BTreeMap<(String, String), bool>> = Mutex::new(BTreeMap::new());
+
+/// Forgets which teams were found, so the next lookup asks GitHub again.
+pub fn clear_team_cache() {
+    teams().clear();
+}
+
+/// Returns the slugs that name a team in `organization`, in the given order.
+///
+/// Results are cached for the rest of the run. Lookups that fail for reasons
+/// other than a missing team are logged and treated as missing, uncached.
+pub async fn existing_teams(
+    octocrab: &Octocrab,
+    organization: &str,
+    slugs: &[String],
+) -> Vec<String> {
+    let mut found = Vec::new();
+    for slug in slugs {
+        let key = (organization.to_lowercase(), slug.to_lowercase());
+        let cached = teams().get(&key).copied();
+        let exists = match cached {
+            Some(exists) => exists,
+            None => match octocrab.teams(organization).get(slug).await {
+                Ok(_) => {
+                    teams().insert(key, true);
+                    true
+                }
+                Err(octocrab::Error::GitHub { source, .. })
+                    if source.status_code.as_u16() == 404 =>
+                {
+                    debug!(organization, slug = %slug, "Team not found");
+                    teams().insert(key, false);
+                    false
+                }
+                Err(e) => {
+                    warn!(organization, slug = %slug, error = %e, "Failed to look up team");
+                    false
+                }
+            },
+        };
+        if exists {
+            found.push(slug.clone());
+        }
+    }
+    found
+}
+
+/// Appends a line mentioning the migration's teams that exist in `organization`.
+///
+/// Returns `body` unchanged when no team resolves.
+pub(crate) async fn append_team_mentions(
+    octocrab: &Octocrab,
+    organization: &str,
+    slugs: &[String],
+    body: String,
+) -> String {
+    if slugs.is_empty() {
+        return body;
+    }
+    let teams = existing_teams(octocrab, organization, slugs).await;
+    match mention_line(organization, &teams) {
+        Some(line) => format!("{}\n\n{line}\n", body.trim_end()),
+        None => body,
+    }
+}
+
+/// Formats `cc @org/team ...`, or `None` if there are no teams.
+fn mention_line(organization: &str, teams: &[String]) -> Option<String> {
+    if teams.is_empty() {
+        return None;
+    }
+    let mentions: Vec<String> = teams
+        .iter()
+        .map(|slug| format!("@{organization}/{slug}"))
+        .collect();
+    Some(format!("cc {}", mentions.join(" ")))
+}
+
+fn teams() -> MutexGuard<'static, BTreeMap<(String, String), bool>> {
+    TEAMS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_mention_line() {
+        let teams = ["platform".to_string(), "sre".to_string()];
+        assert_eq!(
+            mention_line("my-org", &teams).as_deref(),
+            Some("cc @my-org/platform @my-org/sre")
+        );
+        assert_eq!(mention_line("my-org", &[]), None);
+    }
+}
@@ -19,4 +19,20 @@ pub enum TemplateError {
         /// Reason for invalidity.
         reason: String,
     },
+
+    /// Rendered body exceeds GitHub's size limit.
+    #[error("Rendered body has {length} characters, more than GitHub's limit of {max}")]
+    BodyTooLarge {
+        /// Length of the rendered body, in characters.
+        length: usize,
+        /// Maximum accepted length.
+        max: usize,
+    },
+
+    /// Rendered body failed the markdown lint.
+    #[error("Rendered body failed markdown lint: {}", problems.join("; "))]
+    MarkdownLint {
+        /// One message per problem found.
+        problems: Vec<String>,
+    },
 }
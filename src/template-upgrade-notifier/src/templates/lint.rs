@@ -0,0 +1,147 @@
+//! Checks of rendered issue and PR bodies before they are posted.
+
+use super::TemplateError;
+
+/// Longest body GitHub accepts for an issue, PR or comment, in characters.
+pub const MAX_BODY_CHARS: usize = 65_536;
+
+/// Fence markers opening and closing fenced code blocks.
+const FENCE_MARKERS: [&str; 2] = ["```", "~~~"];
+
+/// Validates a rendered body against GitHub's size limit and, with `lint`,
+/// against [`lint_markdown`].
+///
+/// # Errors
+///
+/// Returns [`TemplateError::BodyTooLarge`] if the body exceeds
+/// [`MAX_BODY_CHARS`], or [`TemplateError::MarkdownLint`] if linting found
+/// problems.
+pub fn validate_body(body: &str, lint: bool) -> Result<(), TemplateError> {
+    let length = body.chars().count();
+    if length > MAX_BODY_CHARS {
+        return Err(TemplateError::BodyTooLarge {
+            length,
+            max: MAX_BODY_CHARS,
+        });
+    }
+    if lint {
+        let problems = lint_markdown(body);
+        if !problems.is_empty() {
+            return Err(TemplateError::MarkdownLint { problems });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the problems found in rendered markdown, one message per problem.
+///
+/// Detects code fences that are never closed, which swallow the rest of the
+/// body, and empty or relative link targets, which GitHub resolves against
+/// the issue's own URL and which therefore never work. Links inside code
+/// blocks are ignored.
+#[must_use]
+pub fn lint_markdown(body: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut open_fence: Option<(&str, usize)> = None;
+
+    for (index, line) in body.lines().enumerate() {
+        let number = index + 1;
+        let trimmed = line.trim_start();
+        let marker = FENCE_MARKERS
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker));
+        match (open_fence, marker) {
+            (None, Some(marker)) => {
+                open_fence = Some((marker, number));
+                continue;
+            }
+            (Some((open, _)), Some(marker)) if open == marker => {
+                open_fence = None;
+                continue;
+            }
+            (Some(_), _) => continue,
+            (None, None) => {}
+        }
+
+        for target in link_targets(line) {
+            if target.is_empty() {
+                problems.push(format!("line {number}: link has an empty target"));
+            } else if is_relative(target) {
+                problems.push(format!(
+                    "line {number}: relative link '{target}' won't resolve in an issue or PR"
+                ));
+            }
+        }
+    }
+
+    if let Some((marker, number)) = open_fence {
+        problems.push(format!(
+            "line {number}: code fence '{marker}' is never closed"
+        ));
+    }
+    problems
+}
+
+/// Returns the targets of inline links and images (`[text](target "title")`).
+fn link_targets(line: &str) -> Vec<&str> {
+    let mut targets = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("](") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let target = rest[..end].trim();
+        // Drop an optional title and angle brackets around the destination.
+        let target = target.split_whitespace().next().unwrap_or_default();
+        targets.push(target.trim_start_matches('<').trim_end_matches('>'));
+        rest = &rest[end + 1..];
+    }
+    targets
+}
+
+/// Returns true for link targets that are neither absolute URLs, root-relative
+/// paths nor anchors.
+fn is_relative(target: &str) -> bool {
+    !(target.starts_with('#')
+        || target.starts_with('/')
+        || target.contains("://")
+        || target.starts_with("mailto:"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_well_formed_markdown() {
+        let body = "See the [guide](https://example.com/guide \"Guide\") and [below](#steps).\n\
+                    ```toml\n[link](./not-a-link)\n```\n![logo](/org/repo/raw/main/logo.png)\n";
+        assert!(lint_markdown(body).is_empty());
+        assert!(validate_body(body, true).is_ok());
+    }
+
+    #[test]
+    fn reports_unclosed_fence() {
+        let problems = lint_markdown("Intro\n~~~\ncode\n```\n");
+        assert_eq!(problems, ["line 2: code fence '~~~' is never closed"]);
+    }
+
+    #[test]
+    fn reports_empty_and_relative_links() {
+        let problems = lint_markdown("[guide]() and [docs](docs/upgrade.md)");
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("empty target"));
+        assert!(problems[1].contains("'docs/upgrade.md'"));
+    }
+
+    #[test]
+    fn rejects_oversized_body_even_without_lint() {
+        let body = "a".repeat(MAX_BODY_CHARS + 1);
+        assert!(matches!(
+            validate_body(&body, false),
+            Err(TemplateError::BodyTooLarge { .. })
+        ));
+        assert!(validate_body("[x](y)", false).is_ok());
+    }
+}
@@ -4,27 +4,37 @@
 //! variable substitution and conditional logic.
 
 mod error;
+mod lint;
 mod renderer;
+mod slug;
 
 pub use error::TemplateError;
+pub use lint::{lint_markdown, validate_body, MAX_BODY_CHARS};
 pub use renderer::{create_handlebars_registry, TemplateRenderer};
+pub use slug::slugify;
 
 use crate::config::Migration;
+use crate::ids::MigrationId;
 use bstr::ByteSlice;
 use handlebars::Handlebars;
 use serde_json::json;
 
 /// Renders a format template with migration data.
 ///
-/// Available variables: `old_string`, `new_string`, `id`, `target_file`, `migration_guide_link`
+/// Available variables: `old_string`, `new_string`, `id`, `target_file`, `target_files`,
+/// `migration_guide_link`, `versions_behind`.
+/// The `slug` helper is available for branch-safe values.
 fn render_format(template: &str, migration: &Migration) -> Result<String, TemplateError> {
-    let hbs = Handlebars::new();
+    let mut hbs = Handlebars::new();
+    hbs.register_helper("slug", Box::new(slug::slug_helper));
     let data = json!({
         "old_string": migration.old_string,
         "new_string": migration.new_string,
         "id": migration.id,
         "target_file": migration.target_file,
-        "migration_guide_link": migration.migration_guide_link.as_deref().unwrap_or("")
+        "target_files": migration.target_files(),
+        "migration_guide_link": migration.migration_guide_link.as_deref().unwrap_or(""),
+        "versions_behind": migration.versions_behind
     });
     Ok(hbs.render_template(template, &data)?)
 }
@@ -53,18 +63,36 @@ pub fn generate_pr_title(migration: &Migration) -> Result<String, TemplateError>
 
 /// Generates the branch name for an upgrade PR.
 ///
-/// Uses the `branch_name_format` from the migration config.
+/// Uses the `branch_name_format` from the migration config, prefixed with
+/// the `[pr-metadata]` branch prefix if any.
 ///
 /// # Errors
 ///
 /// Returns [`TemplateError::RenderError`] if template rendering fails,
 /// or [`TemplateError::InvalidBranchName`] if the rendered name is invalid.
 pub fn generate_branch_name(migration: &Migration) -> Result<String, TemplateError> {
-    let branch = render_format(&migration.branch_name_format, migration)?;
+    let mut branch = render_format(&migration.branch_name_format, migration)?;
+    if let Some(metadata) = &migration.pr_metadata {
+        branch.insert_str(0, &metadata.branch_prefix);
+    }
     validate_branch_name(&branch)?;
     Ok(branch)
 }
 
+/// Returns the start every migration of `migration`'s template shares in its
+/// branch name, up to where the rest of the migration id is rendered, or
+/// `None` if the branch name doesn't contain the id.
+pub(crate) fn template_branch_prefix(migration: &Migration) -> Option<String> {
+    const MARKER: &str = "template-upgrade-notifier-marker";
+    let id = MigrationId::new(format!("{}/{MARKER}", migration.template())).ok()?;
+    let branch = generate_branch_name(&Migration {
+        id,
+        ..migration.clone()
+    })
+    .ok()?;
+    branch.find(MARKER).map(|end| branch[..end].to_string())
+}
+
 /// Generates the commit title for an upgrade.
 ///
 /// Uses the `commit_title_format` from the migration config.
@@ -97,7 +125,7 @@ mod tests {
 
     fn sample_migration() -> Migration {
         Migration {
-            id: "my-template/v1.0.0-to-v1.0.1".to_string(),
+            id: "my-template/v1.0.0-to-v1.0.1".parse().unwrap(),
             old_string: "my-template:1.0.0".to_string(),
             new_string: "my-template:1.0.1".to_string(),
             migration_guide_link: Some("https://example.com/docs".to_string()),
@@ -108,6 +136,7 @@ mod tests {
             pr_title_format: default_pr_title_format(),
             branch_name_format: default_branch_name_format(),
             commit_title_format: default_commit_title_format(),
+            ..Migration::default()
         }
     }
 
@@ -164,6 +193,28 @@ mod tests {
         assert_eq!(branch, "upgrade/my-template/v1.0.0-to-v1.0.1");
     }
 
+    #[test]
+    fn pr_metadata_prefixes_branch_name() {
+        let mut migration = sample_migration();
+        migration.pr_metadata = Some(crate::pull_requests::PrMetadata {
+            branch_prefix: "renovate/".to_string(),
+            ..Default::default()
+        });
+        let branch = generate_branch_name(&migration).unwrap();
+        assert_eq!(
+            branch,
+            "renovate/template-upgrade/my-template/v1.0.0-to-v1.0.1"
+        );
+    }
+
+    #[test]
+    fn slug_helper_makes_branch_names_valid() {
+        let mut migration = sample_migration();
+        migration.branch_name_format = "upgrade/{{slug old_string}}".to_string();
+        let branch = generate_branch_name(&migration).unwrap();
+        assert_eq!(branch, "upgrade/my-template-1.0.0");
+    }
+
     #[test]
     fn branch_name_rejects_invalid() {
         // Just verify our error wrapping works; gix-validate handles the actual validation
@@ -177,4 +228,19 @@ mod tests {
     fn branch_name_accepts_valid() {
         assert!(validate_branch_name("template-upgrade/my-template/v1.0.0-to-v1.0.1").is_ok());
     }
+
+    #[test]
+    fn branch_prefix_is_shared_by_the_template() {
+        let migration = sample_migration();
+        assert_eq!(
+            template_branch_prefix(&migration).as_deref(),
+            Some("template-upgrade/my-template/")
+        );
+
+        let versioned = Migration {
+            branch_name_format: "upgrade/{{slug new_string}}".to_string(),
+            ..migration
+        };
+        assert_eq!(template_branch_prefix(&versioned), None);
+    }
 }
@@ -1,6 +1,7 @@
 //! Template renderer.
 
 use crate::config::Migration;
+use crate::discovery::DiscoveredRepository;
 use crate::pull_requests::PrStatus;
 use handlebars::{
     handlebars_helper, no_escape, Context, Handlebars, Helper, HelperResult, Output, RenderContext,
@@ -13,6 +14,7 @@ use serde_json::{json, Value};
 /// - No HTML escaping (for markdown output)
 /// - Strict mode (catches missing variables)
 /// - `eq` helper for equality comparisons
+/// - `slug` helper for git refname-safe values
 #[must_use]
 pub fn create_handlebars_registry() -> Handlebars<'static> {
     let mut hbs = Handlebars::new();
@@ -26,6 +28,9 @@ pub fn create_handlebars_registry() -> Handlebars<'static> {
     // Register the eq helper for conditionals
     hbs.register_helper("eq", Box::new(eq_helper));
 
+    // Register the slug helper for branch-safe values
+    hbs.register_helper("slug", Box::new(super::slug::slug_helper));
+
     hbs
 }
 
@@ -57,6 +62,7 @@ handlebars_helper!(str_eq: |a: str, b: str| a == b);
 /// Template renderer for issue and PR templates.
 pub struct TemplateRenderer {
     handlebars: Handlebars<'static>,
+    lint_markdown: bool,
 }
 
 impl Default for TemplateRenderer {
@@ -71,33 +77,68 @@ impl TemplateRenderer {
     pub fn new() -> Self {
         Self {
             handlebars: create_handlebars_registry(),
+            lint_markdown: false,
         }
     }
 
+    /// Enables linting rendered bodies for unclosed code fences and broken
+    /// links (see [`lint_markdown`](super::lint_markdown)).
+    ///
+    /// Bodies are always checked against GitHub's size limit.
+    #[must_use]
+    pub fn with_markdown_lint(mut self, enabled: bool) -> Self {
+        self.lint_markdown = enabled;
+        self
+    }
+
     /// Renders an issue template with the given migration data.
     ///
     /// # Arguments
     ///
     /// * `template` - The issue template content
     /// * `migration` - The migration data
+    /// * `repository` - Repository the issue is for, providing the detected
+    ///   template version (defaults to `old_string`), every matched location,
+    ///   the before/after snippet, any earlier notice and its `repo-data.toml`
+    ///   values; `None` leaves those empty
     /// * `pr_status` - Optional PR status for conditional rendering
     /// * `pr_link` - Optional PR URL
     ///
     /// # Errors
     ///
-    /// Returns an error if template rendering fails.
+    /// Returns an error if template rendering fails or the body fails
+    /// [`validate_body`](super::validate_body).
     pub fn render_issue_template(
         &self,
         template: &str,
         migration: &Migration,
+        repository: Option<&DiscoveredRepository>,
         pr_status: Option<&PrStatus>,
         pr_link: Option<&str>,
     ) -> Result<String, super::TemplateError> {
+        let template_version = repository.and_then(|r| r.template_version.as_deref());
+        let locations = repository
+            .map(DiscoveredRepository::locations)
+            .unwrap_or_default();
+        let snippet = repository.and_then(|r| r.snippet.as_ref());
+        let previous_issue_url = repository.and_then(|r| r.previous_issue_url.as_deref());
         let data = json!({
             "old_string": migration.old_string,
             "new_string": migration.new_string,
             "migration_guide_link": migration.migration_guide_link.as_deref().unwrap_or(""),
+            "changelog": migration.changelog.as_deref().unwrap_or(""),
             "target_file": migration.target_file,
+            "target_files": migration.target_files(),
+            "template_version": template_version.unwrap_or(&migration.old_string),
+            "current_version": template_version.unwrap_or(&migration.old_string),
+            "versions_behind": migration.versions_behind,
+            "multiple_locations": locations.len() > 1,
+            "locations": locations,
+            "before_snippet": snippet.map_or("", |s| s.before.as_str()),
+            "after_snippet": snippet.map_or("", |s| s.after.as_str()),
+            "previously_notified": repository.is_some_and(|r| r.previously_notified),
+            "previous_issue_url": previous_issue_url.unwrap_or(""),
+            "repo_data": repository.map_or_else(|| json!({}), |r| json!(r.repo_data)),
             "pr_status": pr_status.map_or("", |s| s.as_str()),
             "pr_link": pr_link.unwrap_or("")
         });
@@ -111,32 +152,42 @@ impl TemplateRenderer {
     ///
     /// * `template` - The PR template content
     /// * `migration` - The migration data
+    /// * `template_version` - Version the repository is on (defaults to `old_string`)
     ///
     /// # Errors
     ///
-    /// Returns an error if template rendering fails.
+    /// Returns an error if template rendering fails or the body fails
+    /// [`validate_body`](super::validate_body).
     pub fn render_pr_template(
         &self,
         template: &str,
         migration: &Migration,
+        template_version: Option<&str>,
     ) -> Result<String, super::TemplateError> {
         let data = json!({
             "old_string": migration.old_string,
             "new_string": migration.new_string,
             "migration_guide_link": migration.migration_guide_link.as_deref().unwrap_or(""),
-            "target_file": migration.target_file
+            "changelog": migration.changelog.as_deref().unwrap_or(""),
+            "target_file": migration.target_file,
+            "target_files": migration.target_files(),
+            "template_version": template_version.unwrap_or(&migration.old_string),
+            "current_version": template_version.unwrap_or(&migration.old_string),
+            "versions_behind": migration.versions_behind
         });
 
         self.render_template(template, &data)
     }
 
-    /// Renders a template with the given data.
+    /// Renders a template with the given data and validates the result.
     fn render_template(
         &self,
         template: &str,
         data: &Value,
     ) -> Result<String, super::TemplateError> {
-        Ok(self.handlebars.render_template(template, data)?)
+        let body = self.handlebars.render_template(template, data)?;
+        super::validate_body(&body, self.lint_markdown)?;
+        Ok(body)
     }
 }
 
@@ -147,10 +198,12 @@ mod tests {
         default_branch_name_format, default_commit_title_format, default_issue_title_format,
         default_pr_title_format,
     };
+    use crate::discovery::{FileSnippet, TemplateLocation};
+    use crate::ids::HtmlUrl;
 
     fn sample_migration() -> Migration {
         Migration {
-            id: "my-template/v1.0.0-to-v1.0.1".to_string(),
+            id: "my-template/v1.0.0-to-v1.0.1".parse().unwrap(),
             old_string: "my-template:1.0.0".to_string(),
             new_string: "my-template:1.0.1".to_string(),
             migration_guide_link: Some("https://example.com/docs".to_string()),
@@ -161,6 +214,7 @@ mod tests {
             pr_title_format: default_pr_title_format(),
             branch_name_format: default_branch_name_format(),
             commit_title_format: default_commit_title_format(),
+            ..Migration::default()
         }
     }
 
@@ -170,7 +224,9 @@ mod tests {
         let migration = sample_migration();
 
         let template = "Upgrade from {{old_string}} to {{new_string}}";
-        let result = renderer.render_pr_template(template, &migration).unwrap();
+        let result = renderer
+            .render_pr_template(template, &migration, None)
+            .unwrap();
 
         assert_eq!(
             result,
@@ -190,9 +246,10 @@ mod tests {
             .render_issue_template(
                 template,
                 &migration,
+                None,
                 Some(&PrStatus::Created {
                     number: 42,
-                    url: "https://github.com/test/repo/pull/42".to_string(),
+                    url: "https://github.com/test/repo/pull/42".parse().unwrap(),
                 }),
                 Some("https://github.com/test/repo/pull/42"),
             )
@@ -213,9 +270,10 @@ mod tests {
             .render_issue_template(
                 template,
                 &migration,
+                None,
                 Some(&PrStatus::Created {
                     number: 1,
-                    url: String::new(),
+                    url: "https://github.com/test/repo/pull/1".parse().unwrap(),
                 }),
                 None,
             )
@@ -224,6 +282,184 @@ mod tests {
         assert_eq!(result, "PR was created");
     }
 
+    #[test]
+    fn renders_detected_template_version() {
+        let renderer = TemplateRenderer::new();
+        let migration = sample_migration();
+        let template = "On {{template_version}}";
+
+        let detected = renderer
+            .render_pr_template(template, &migration, Some("1.0.0-3-gabc1234"))
+            .unwrap();
+        let fallback = renderer
+            .render_pr_template(template, &migration, None)
+            .unwrap();
+
+        assert_eq!(detected, "On 1.0.0-3-gabc1234");
+        assert_eq!(fallback, "On my-template:1.0.0");
+    }
+
+    #[test]
+    fn renders_changelog_excerpt() {
+        let renderer = TemplateRenderer::new();
+        let mut migration = sample_migration();
+        let template = "{{#if changelog}}Changes:\n{{changelog}}{{/if}}";
+
+        let without = renderer
+            .render_pr_template(template, &migration, None)
+            .unwrap();
+        migration.changelog = Some("- Fixed CI".to_string());
+        let with = renderer
+            .render_pr_template(template, &migration, None)
+            .unwrap();
+
+        assert_eq!(without, "");
+        assert_eq!(with, "Changes:\n- Fixed CI");
+    }
+
+    #[test]
+    fn renders_current_version_and_versions_behind() {
+        let renderer = TemplateRenderer::new();
+        let mut migration = sample_migration();
+        migration.versions_behind = 3;
+
+        let result = renderer
+            .render_pr_template(
+                "{{current_version}}, {{versions_behind}} behind",
+                &migration,
+                Some("1.0.0-3-gabc1234"),
+            )
+            .unwrap();
+
+        assert_eq!(result, "1.0.0-3-gabc1234, 3 behind");
+    }
+
+    fn sample_repository(paths: &[&str]) -> DiscoveredRepository {
+        let url = |path: &str| -> HtmlUrl {
+            format!("https://github.com/test/repo/blob/main/{path}")
+                .parse()
+                .unwrap()
+        };
+        DiscoveredRepository {
+            owner: "test".to_string(),
+            name: "repo".to_string(),
+            full_name: "test/repo".parse().unwrap(),
+            file_path: paths[0].to_string(),
+            file_url: url(paths[0]),
+            default_branch: "main".to_string(),
+            template_version: None,
+            additional_locations: paths[1..]
+                .iter()
+                .map(|path| TemplateLocation {
+                    path: (*path).to_string(),
+                    url: url(path),
+                })
+                .collect(),
+            snippet: None,
+            previously_notified: false,
+            previous_issue_url: None,
+            owner_is_bot: false,
+            is_mirror: false,
+            archived: false,
+            disabled: false,
+            visibility: None,
+            fork_ancestors: Vec::new(),
+            file_size: None,
+            topics: None,
+            opted_out: None,
+            repo_data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn renders_template_locations() {
+        let renderer = TemplateRenderer::new();
+        let migration = sample_migration();
+        let template =
+            "{{#if multiple_locations}}{{#each locations}}- {{path}} ({{url}})\n{{/each}}{{/if}}";
+        let multiple_repo = sample_repository(&["a/version.txt", "b/version.txt"]);
+        let single_repo = sample_repository(&["a/version.txt"]);
+
+        let multiple = renderer
+            .render_issue_template(template, &migration, Some(&multiple_repo), None, None)
+            .unwrap();
+        let single = renderer
+            .render_issue_template(template, &migration, Some(&single_repo), None, None)
+            .unwrap();
+
+        assert_eq!(
+            multiple,
+            "- a/version.txt (https://github.com/test/repo/blob/main/a/version.txt)\n\
+             - b/version.txt (https://github.com/test/repo/blob/main/b/version.txt)\n"
+        );
+        assert_eq!(single, "");
+    }
+
+    #[test]
+    fn renders_before_and_after_snippets() {
+        let renderer = TemplateRenderer::new();
+        let migration = sample_migration();
+        let template = "{{#if before_snippet}}-{{before_snippet}}\n+{{after_snippet}}{{/if}}";
+        let mut repository = sample_repository(&["version.txt"]);
+
+        let without = renderer
+            .render_issue_template(template, &migration, Some(&repository), None, None)
+            .unwrap();
+        repository.snippet = FileSnippet::extract(
+            "template = my-template:1.0.0\n",
+            &migration.old_string,
+            &migration.new_string,
+        );
+        let with = renderer
+            .render_issue_template(template, &migration, Some(&repository), None, None)
+            .unwrap();
+
+        assert_eq!(without, "");
+        assert_eq!(
+            with,
+            "-template = my-template:1.0.0\n+template = my-template:1.0.1"
+        );
+    }
+
+    #[test]
+    fn renders_previous_notice() {
+        let renderer = TemplateRenderer::new();
+        let migration = sample_migration();
+        let template = "{{#if previously_notified}}See {{previous_issue_url}}{{/if}}";
+        let mut repository = sample_repository(&["version.txt"]);
+        repository.previously_notified = true;
+        repository.previous_issue_url =
+            Some("https://github.com/test/repo/issues/12".parse().unwrap());
+
+        let result = renderer
+            .render_issue_template(template, &migration, Some(&repository), None, None)
+            .unwrap();
+
+        assert_eq!(result, "See https://github.com/test/repo/issues/12");
+    }
+
+    #[test]
+    fn renders_repo_data() {
+        let renderer = TemplateRenderer::new();
+        let migration = sample_migration();
+        let mut repository = sample_repository(&["version.txt"]);
+        repository
+            .repo_data
+            .insert("service_owner".to_string(), json!("team-payments"));
+
+        let result = renderer
+            .render_issue_template(
+                "Owner: {{repo_data.service_owner}}",
+                &migration,
+                Some(&repository),
+                None,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(result, "Owner: team-payments");
+    }
+
     #[test]
     fn does_not_escape_html() {
         let renderer = TemplateRenderer::new();
@@ -231,7 +467,9 @@ mod tests {
         migration.old_string = "<script>alert('xss')</script>".to_string();
 
         let template = "{{old_string}}";
-        let result = renderer.render_pr_template(template, &migration).unwrap();
+        let result = renderer
+            .render_pr_template(template, &migration, None)
+            .unwrap();
 
         // Should NOT escape HTML entities
         assert_eq!(result, "<script>alert('xss')</script>");
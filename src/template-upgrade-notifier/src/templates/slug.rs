@@ -0,0 +1,56 @@
+//! The `slug` helper, turning arbitrary strings into git refname components.
+
+use handlebars::handlebars_helper;
+
+handlebars_helper!(slug_helper: |value: str| slugify(value));
+
+/// Converts `value` into a lowercase string that is valid as one component
+/// of a git refname.
+///
+/// Runs of anything other than ASCII letters, digits, `.`, `_` and `-`
+/// (spaces, colons, slashes, ...) become a single `-`, `..` becomes `.`, and
+/// leading/trailing `-` and `.` as well as a trailing `.lock` are removed.
+///
+/// Usage: `branch-name-format = "upgrade/{{slug old_string}}"`
+#[must_use]
+pub fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    for c in value.chars() {
+        let c = if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+            c.to_ascii_lowercase()
+        } else {
+            '-'
+        };
+        let collapses = matches!(
+            (slug.chars().next_back(), c),
+            (Some('-'), '-') | (Some('.'), '.')
+        );
+        if !collapses {
+            slug.push(c);
+        }
+    }
+    let mut slug = slug.trim_matches(['-', '.']);
+    while let Some(stripped) = slug.strip_suffix(".lock") {
+        slug = stripped.trim_end_matches(['-', '.']);
+    }
+    slug.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_invalid_characters() {
+        assert_eq!(slugify("my-template:1.0.0"), "my-template-1.0.0");
+        assert_eq!(slugify("My Template / v2"), "my-template-v2");
+        assert_eq!(slugify("a~b^c?d*e[f]g\\h@{i}"), "a-b-c-d-e-f-g-h-i");
+    }
+
+    #[test]
+    fn removes_invalid_dots_and_affixes() {
+        assert_eq!(slugify("..hidden..name.."), "hidden.name");
+        assert_eq!(slugify("-x-"), "x");
+        assert_eq!(slugify("config.lock"), "config");
+    }
+}
@@ -0,0 +1,52 @@
+//! Webhook verification error types.
+
+use crate::error_kind::ErrorKind;
+use thiserror::Error;
+
+/// Reasons a webhook delivery is rejected.
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    /// The verifier was given an empty secret.
+    #[error("Webhook secret must not be empty")]
+    EmptySecret,
+
+    /// A header the delivery needs is absent or not valid UTF-8.
+    #[error("Missing or invalid {0} header")]
+    MissingHeader(&'static str),
+
+    /// The signature header is not a hex-encoded SHA-256 HMAC.
+    #[error("Malformed webhook signature")]
+    MalformedSignature,
+
+    /// The signature does not match the body and secret.
+    #[error("Webhook signature does not match")]
+    SignatureMismatch,
+
+    /// A delivery with this ID, or with the same signed body, was already
+    /// accepted within the replay window.
+    #[error("Webhook delivery {0} was already received")]
+    Replayed(String),
+
+    /// The event type is not in the allowlist.
+    #[error("Webhook event '{0}' is not allowed")]
+    EventNotAllowed(String),
+
+    /// The body is not JSON.
+    #[error("Invalid webhook payload: {0}")]
+    InvalidPayload(#[from] serde_json::Error),
+}
+
+impl WebhookError {
+    /// Returns what kind of failure this is.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::MalformedSignature | Self::SignatureMismatch => ErrorKind::Auth,
+            Self::EmptySecret
+            | Self::MissingHeader(_)
+            | Self::Replayed(_)
+            | Self::EventNotAllowed(_)
+            | Self::InvalidPayload(_) => ErrorKind::Validation,
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Verification of incoming GitHub and Gitea webhook deliveries.
+//!
+//! A server that reacts to pushes or issue comments has to accept requests
+//! from anyone, so every delivery is checked before it is acted on: its
+//! HMAC-SHA256 signature must match the shared secret, neither its delivery ID
+//! nor its signature may have been seen recently, and its event type must be
+//! one the server handles.
+//! [`WebhookVerifier`] does all three, independent of any HTTP framework.
+
+mod error;
+
+pub use error::WebhookError;
+
+use crate::clock::{Clock, SystemClock};
+use chrono::{DateTime, Utc};
+use core::fmt;
+use core::time::Duration;
+use hmac::{Hmac, Mac};
+use http::HeaderMap;
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, PoisonError};
+
+/// How long a delivery ID and signature are remembered by default.
+pub const DEFAULT_REPLAY_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// The forge a delivery came from, which decides the headers it is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookSource {
+    /// GitHub: `X-GitHub-Event`, `X-GitHub-Delivery` and
+    /// `X-Hub-Signature-256: sha256=<hex>`.
+    GitHub,
+    /// Gitea (and Forgejo): `X-Gitea-Event`, `X-Gitea-Delivery` and
+    /// `X-Gitea-Signature: <hex>`.
+    Gitea,
+}
+
+impl WebhookSource {
+    /// Returns the source of a delivery from its headers.
+    ///
+    /// Gitea also sends `X-GitHub-Event` for compatibility, so its own header
+    /// is checked first.
+    fn detect(headers: &HeaderMap) -> Option<Self> {
+        if headers.contains_key("x-gitea-event") {
+            Some(Self::Gitea)
+        } else if headers.contains_key("x-github-event") {
+            Some(Self::GitHub)
+        } else {
+            None
+        }
+    }
+
+    fn event_header(self) -> &'static str {
+        match self {
+            Self::GitHub => "X-GitHub-Event",
+            Self::Gitea => "X-Gitea-Event",
+        }
+    }
+
+    fn delivery_header(self) -> &'static str {
+        match self {
+            Self::GitHub => "X-GitHub-Delivery",
+            Self::Gitea => "X-Gitea-Delivery",
+        }
+    }
+
+    fn signature_header(self) -> &'static str {
+        match self {
+            Self::GitHub => "X-Hub-Signature-256",
+            Self::Gitea => "X-Gitea-Signature",
+        }
+    }
+}
+
+/// A delivery that passed verification.
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    /// Forge the delivery came from.
+    pub source: WebhookSource,
+    /// Event type, e.g. `push` or `issue_comment`.
+    pub event: String,
+    /// Unique ID the forge assigned to the delivery.
+    pub delivery_id: String,
+    /// The parsed JSON body.
+    pub payload: Value,
+}
+
+/// What an accepted delivery is remembered by.
+///
+/// The delivery ID header is not covered by the signature, so a captured
+/// body and signature could be replayed under a fresh ID; the signature,
+/// which changes with every body, is remembered too.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum ReplayKey {
+    DeliveryId(String),
+    Signature(Vec<u8>),
+}
+
+/// Checks signatures, replays and event types of webhook deliveries.
+///
+/// Shared between request handlers; the delivery IDs and signatures seen are
+/// kept behind a lock.
+pub struct WebhookVerifier {
+    secret: Vec<u8>,
+    allowed_events: BTreeSet<String>,
+    replay_window: Duration,
+    clock: Box<dyn Clock>,
+    /// Delivery IDs and signatures accepted within the replay window, with when.
+    seen: Mutex<BTreeMap<ReplayKey, DateTime<Utc>>>,
+}
+
+impl fmt::Debug for WebhookVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookVerifier")
+            .field("allowed_events", &self.allowed_events)
+            .field("replay_window", &self.replay_window)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebhookVerifier {
+    /// Creates a verifier for deliveries signed with `secret` that accepts
+    /// only the `allowed_events` types.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebhookError::EmptySecret`] if `secret` is empty, since every
+    /// forge skips signing without one.
+    pub fn new<I, S>(secret: impl Into<Vec<u8>>, allowed_events: I) -> Result<Self, WebhookError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let secret = secret.into();
+        if secret.is_empty() {
+            return Err(WebhookError::EmptySecret);
+        }
+        Ok(Self {
+            secret,
+            allowed_events: allowed_events.into_iter().map(Into::into).collect(),
+            replay_window: DEFAULT_REPLAY_WINDOW,
+            clock: Box::new(SystemClock),
+            seen: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Sets how long a delivery ID and signature are remembered; a second
+    /// delivery with the same ID or the same signed body within this window
+    /// is rejected.
+    ///
+    /// Deliveries redelivered from the forge's UI keep their ID, so they are
+    /// rejected too until the window has passed.
+    #[must_use]
+    pub fn with_replay_window(mut self, window: Duration) -> Self {
+        self.replay_window = window;
+        self
+    }
+
+    /// Sets the clock the replay window is measured with.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Verifies a delivery from its request headers and raw body.
+    ///
+    /// The signature is checked first, in constant time, so unauthenticated
+    /// requests learn nothing about the allowlist or earlier deliveries. A
+    /// delivery ID and signature are only remembered once the delivery is
+    /// accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebhookError`] if a required header is missing, the
+    /// signature does not match, the event type is not allowed, the delivery
+    /// was already received, or the body is not JSON.
+    pub fn verify(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<WebhookDelivery, WebhookError> {
+        let source =
+            WebhookSource::detect(headers).ok_or(WebhookError::MissingHeader("X-GitHub-Event"))?;
+        let signature = header(headers, source.signature_header())?;
+        let mac = self.check_signature(source, signature, body)?;
+
+        let event = header(headers, source.event_header())?.to_string();
+        if !self.allowed_events.contains(&event) {
+            return Err(WebhookError::EventNotAllowed(event));
+        }
+        let delivery_id = header(headers, source.delivery_header())?.to_string();
+        let payload = serde_json::from_slice(body)?;
+        self.record_delivery(&delivery_id, mac)?;
+        Ok(WebhookDelivery {
+            source,
+            event,
+            delivery_id,
+            payload,
+        })
+    }
+
+    /// Checks `signature` against the HMAC-SHA256 of `body` under the secret,
+    /// returning the decoded MAC.
+    fn check_signature(
+        &self,
+        source: WebhookSource,
+        signature: &str,
+        body: &[u8],
+    ) -> Result<Vec<u8>, WebhookError> {
+        let hex_digest = match source {
+            WebhookSource::GitHub => signature
+                .strip_prefix("sha256=")
+                .ok_or(WebhookError::MalformedSignature)?,
+            WebhookSource::Gitea => signature,
+        };
+        let expected = hex::decode(hex_digest).map_err(|_| WebhookError::MalformedSignature)?;
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(body);
+        mac.verify_slice(&expected)
+            .map_err(|_| WebhookError::SignatureMismatch)?;
+        Ok(expected)
+    }
+
+    /// Remembers `delivery_id` and the delivery's `mac`, forgetting entries
+    /// older than the replay window.
+    fn record_delivery(&self, delivery_id: &str, mac: Vec<u8>) -> Result<(), WebhookError> {
+        let now = self.clock.now();
+        let window =
+            chrono::Duration::from_std(self.replay_window).unwrap_or(chrono::Duration::MAX);
+        let keys = [
+            ReplayKey::DeliveryId(delivery_id.to_string()),
+            ReplayKey::Signature(mac),
+        ];
+        let mut seen = self.seen.lock().unwrap_or_else(PoisonError::into_inner);
+        seen.retain(|_, received| now.signed_duration_since(*received) < window);
+        if keys.iter().any(|key| seen.contains_key(key)) {
+            return Err(WebhookError::Replayed(delivery_id.to_string()));
+        }
+        seen.extend(keys.map(|key| (key, now)));
+        Ok(())
+    }
+}
+
+/// Returns the value of the header `name`.
+fn header<'a>(headers: &'a HeaderMap, name: &'static str) -> Result<&'a str, WebhookError> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(WebhookError::MissingHeader(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::BoxFuture;
+    use std::sync::Arc;
+
+    const SECRET: &str = "It's a Secret to Everybody";
+    const BODY: &[u8] = b"{\"action\":\"opened\"}";
+
+    #[derive(Clone)]
+    struct TestClock(Arc<Mutex<DateTime<Utc>>>);
+
+    impl Clock for TestClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+
+        fn sleep(&self, _duration: Duration) -> BoxFuture<'static, ()> {
+            Box::pin(async {})
+        }
+    }
+
+    fn sign(body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn github_headers(event: &str, delivery: &str, signature: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-github-event", event.parse().unwrap());
+        headers.insert("x-github-delivery", delivery.parse().unwrap());
+        headers.insert("x-hub-signature-256", signature.parse().unwrap());
+        headers
+    }
+
+    fn verifier() -> WebhookVerifier {
+        WebhookVerifier::new(SECRET, ["push", "issue_comment"]).unwrap()
+    }
+
+    #[test]
+    fn accepts_signed_github_delivery() {
+        let signature = format!("sha256={}", sign(BODY));
+        let headers = github_headers("issue_comment", "guid-1", &signature);
+
+        let delivery = verifier().verify(&headers, BODY).unwrap();
+
+        assert_eq!(delivery.source, WebhookSource::GitHub);
+        assert_eq!(delivery.event, "issue_comment");
+        assert_eq!(delivery.delivery_id, "guid-1");
+        assert_eq!(delivery.payload["action"], "opened");
+    }
+
+    #[test]
+    fn accepts_signed_gitea_delivery() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-gitea-event", "push".parse().unwrap());
+        headers.insert("x-github-event", "push".parse().unwrap());
+        headers.insert("x-gitea-delivery", "gitea-1".parse().unwrap());
+        headers.insert("x-gitea-signature", sign(BODY).parse().unwrap());
+
+        let delivery = verifier().verify(&headers, BODY).unwrap();
+
+        assert_eq!(delivery.source, WebhookSource::Gitea);
+    }
+
+    #[test]
+    fn rejects_bad_signatures() {
+        let verifier = verifier();
+        let tampered = format!("sha256={}", sign(b"{}"));
+        let unprefixed = sign(BODY);
+
+        for (signature, expected) in [
+            (tampered.as_str(), "does not match"),
+            (unprefixed.as_str(), "Malformed"),
+            ("sha256=zz", "Malformed"),
+        ] {
+            let headers = github_headers("push", "guid-1", signature);
+            let error = verifier.verify(&headers, BODY).unwrap_err();
+            assert!(error.to_string().contains(expected), "{error}");
+            assert_eq!(error.kind(), crate::error_kind::ErrorKind::Auth);
+        }
+        assert!(matches!(
+            verifier.verify(&HeaderMap::new(), BODY),
+            Err(WebhookError::MissingHeader(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_events_outside_allowlist() {
+        let signature = format!("sha256={}", sign(BODY));
+        let headers = github_headers("workflow_run", "guid-1", &signature);
+
+        assert!(matches!(
+            verifier().verify(&headers, BODY),
+            Err(WebhookError::EventNotAllowed(event)) if event == "workflow_run"
+        ));
+    }
+
+    #[test]
+    fn rejects_replays_within_window() {
+        let now = Arc::new(Mutex::new(Utc::now()));
+        let verifier = verifier()
+            .with_replay_window(Duration::from_secs(60))
+            .with_clock(TestClock(Arc::clone(&now)));
+        let signature = format!("sha256={}", sign(BODY));
+        let headers = github_headers("push", "guid-1", &signature);
+
+        verifier.verify(&headers, BODY).unwrap();
+        assert!(matches!(
+            verifier.verify(&headers, BODY),
+            Err(WebhookError::Replayed(id)) if id == "guid-1"
+        ));
+
+        *now.lock().unwrap() += chrono::Duration::seconds(60);
+        assert!(verifier.verify(&headers, BODY).is_ok());
+    }
+
+    #[test]
+    fn rejects_replayed_body_under_fresh_delivery_id() {
+        let verifier = verifier();
+        let signature = format!("sha256={}", sign(BODY));
+
+        verifier
+            .verify(&github_headers("push", "guid-1", &signature), BODY)
+            .unwrap();
+        assert!(matches!(
+            verifier.verify(&github_headers("push", "guid-2", &signature), BODY),
+            Err(WebhookError::Replayed(id)) if id == "guid-2"
+        ));
+
+        let other = b"{\"action\":\"closed\"}";
+        let other_signature = format!("sha256={}", sign(other));
+        let headers = github_headers("push", "guid-3", &other_signature);
+        assert!(verifier.verify(&headers, other).is_ok());
+    }
+
+    #[test]
+    fn rejects_empty_secret() {
+        assert!(matches!(
+            WebhookVerifier::new("", ["push"]),
+            Err(WebhookError::EmptySecret)
+        ));
+    }
+}
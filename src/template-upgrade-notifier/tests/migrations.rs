@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use template_upgrade_notifier::{scan_migrations, ConfigError, Migration};
+use template_upgrade_notifier::{scan_migrations, scan_migrations_strict, ConfigError, Migration};
 
 fn fixtures_root() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/migrations")
@@ -46,3 +46,15 @@ fn scan_migrations_skips_invalid_fixture() {
         .iter()
         .any(|migration| migration.id == "acme-template/v1.0.0-to-v1.0.1"));
 }
+
+#[test]
+fn strict_scan_rejects_invalid_fixture() {
+    let result = scan_migrations_strict(&fixtures_root());
+
+    assert!(matches!(
+        result,
+        Err(ConfigError::InvalidMigrations { ref failures })
+            if failures.len() == 1
+                && matches!(failures[0], ConfigError::ValidationError { .. })
+    ));
+}